@@ -18,8 +18,16 @@ pub enum Commands {
     /// Kompiliert eine Velisch Datei zu Rust
     Compile {
         /// Eingabe-Datei (.velin)
-        #[arg(short, long)]
-        input: PathBuf,
+        #[arg(short, long, required_unless_present_any = ["package", "workspace"])]
+        input: Option<PathBuf>,
+
+        /// Workspace-Member kompilieren (statt --input)
+        #[arg(short, long, conflicts_with = "input")]
+        package: Option<String>,
+
+        /// Alle Workspace-Members kompilieren
+        #[arg(long, conflicts_with_all = ["input", "package"])]
+        workspace: bool,
 
         /// Ausgabe-Datei (.rs)
         #[arg(short, long)]
@@ -61,20 +69,32 @@ pub enum Commands {
         #[arg(long)]
         ai_api_key: Option<String>,
 
-        /// Ziel-Sprache (rust, php, python, etc.)
+        /// Ziel-Sprache (rust, php, python, ..., wasm)
         #[arg(long, default_value = "rust")]
         target: String,
 
         /// Web Framework (laravel, symfony, fastapi, flask, axum, actix)
         #[arg(long)]
         framework: Option<String>,
+
+        /// Inspect-Endpunkt für velin-inspect einbauen (nur Rust)
+        #[arg(long)]
+        inspect: bool,
     },
 
     /// Prüft eine Velisch Datei (nur Parsing & Type Checking)
     Check {
         /// Eingabe-Datei (.velin)
-        #[arg(short, long)]
-        input: PathBuf,
+        #[arg(short, long, required_unless_present_any = ["package", "workspace"])]
+        input: Option<PathBuf>,
+
+        /// Workspace-Member prüfen (statt --input)
+        #[arg(short, long, conflicts_with = "input")]
+        package: Option<String>,
+
+        /// Alle Workspace-Members prüfen
+        #[arg(long, conflicts_with_all = ["input", "package"])]
+        workspace: bool,
 
         /// Automatische Fehlerkorrektur aktivieren
         #[arg(long)]
@@ -166,11 +186,15 @@ pub enum Commands {
         /// Ausgabe-Datei (.json oder .yaml)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Ausgabeformat: json oder yaml (Standard: anhand der Dateiendung, sonst json)
+        #[arg(long)]
+        format: Option<String>,
     },
 
     /// Generiert Code (Boilerplate, CRUD, etc.)
     Generate {
-        /// Art der Generierung (api, crud, test, client)
+        /// Art der Generierung (api, crud, test, client, from-openapi, deployment)
         #[arg(value_name = "TYPE")]
         gen_type: String,
 
@@ -186,7 +210,7 @@ pub enum Commands {
         #[arg(short, long)]
         path: Option<String>,
 
-        /// OpenAPI Datei (für Client)
+        /// OpenAPI Datei (für Client und from-openapi)
         #[arg(long)]
         openapi: Option<PathBuf>,
 
@@ -197,6 +221,18 @@ pub enum Commands {
         /// Ausgabe-Datei
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Prüft, ob der generierte Code wieder die Eingabe-Spezifikation ergibt (für from-openapi)
+        #[arg(long)]
+        check: bool,
+
+        /// Velin-Datei, aus der der Deployment-Plan abgeleitet wird (für deployment)
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+
+        /// Zielplattform (local, compose, kubernetes, serverless) statt der Empfehlung (für deployment)
+        #[arg(long)]
+        platform: Option<String>,
     },
 
     /// Führt Tests aus (Unit + Integration)
@@ -205,6 +241,14 @@ pub enum Commands {
         #[arg(short, long)]
         directory: Option<PathBuf>,
 
+        /// Tests eines Workspace-Members ausführen (`<member>/tests`)
+        #[arg(short, long, conflicts_with = "directory")]
+        package: Option<String>,
+
+        /// Tests aller Workspace-Members ausführen
+        #[arg(long, conflicts_with_all = ["directory", "package"])]
+        workspace: bool,
+
         /// Nur Unit Tests
         #[arg(long)]
         unit: bool,
@@ -216,6 +260,22 @@ pub enum Commands {
         /// Verbose Output
         #[arg(short, long)]
         verbose: bool,
+
+        /// Source-Level Coverage erheben (LCOV + HTML)
+        #[arg(long)]
+        coverage: bool,
+
+        /// Minimale Zeilen-Coverage in Prozent (überschreibt velin.config.json)
+        #[arg(long)]
+        coverage_threshold: Option<f64>,
+
+        /// Minimale Branch-Coverage in Prozent (überschreibt velin.config.json)
+        #[arg(long)]
+        branch_threshold: Option<f64>,
+
+        /// Ausgabe-Verzeichnis für lcov.info und index.html
+        #[arg(long, default_value = "coverage")]
+        coverage_dir: PathBuf,
     },
 
     /// Verwaltet velin.config.json
@@ -263,6 +323,20 @@ pub enum Commands {
         #[command(subcommand)]
         subcommand: SerializeCommands,
     },
+
+    /// Datenbank-Werkzeuge
+    Db {
+        /// Subcommand
+        #[command(subcommand)]
+        subcommand: DbCommands,
+    },
+
+    /// Audit-Log-Werkzeuge
+    Audit {
+        /// Subcommand
+        #[command(subcommand)]
+        subcommand: AuditCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -445,3 +519,94 @@ pub enum SerializeCommands {
         file: PathBuf,
     },
 }
+
+#[derive(Subcommand)]
+pub enum DbCommands {
+    /// Schema-Migrationen aus `@Entity`-Structs
+    Migrate {
+        /// Subcommand
+        #[command(subcommand)]
+        subcommand: MigrateCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MigrateCommands {
+    /// Erzeugt eine Migration aus den Änderungen seit dem letzten Snapshot
+    Generate {
+        /// Eingabe-Datei mit den Entities (inklusive `use`-Modulen)
+        #[arg(short, long, default_value = "main.velin")]
+        input: PathBuf,
+
+        /// Migrations-Verzeichnis
+        #[arg(short, long, default_value = "migrations")]
+        dir: PathBuf,
+
+        /// Name der Migration (Standard: aus den Änderungen abgeleitet)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+
+    /// Wendet ausstehende Migrationen an
+    Up {
+        /// Migrations-Verzeichnis
+        #[arg(short, long, default_value = "migrations")]
+        dir: PathBuf,
+
+        /// Datenbank-URL (sqlite:, postgres://, mysql://; Standard: DATABASE_URL)
+        #[arg(long)]
+        database_url: Option<String>,
+
+        /// Nur bis einschließlich dieser Version
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Zeigt das SQL, ohne es auszuführen
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Nimmt die zuletzt angewendeten Migrationen zurück
+    Down {
+        /// Migrations-Verzeichnis
+        #[arg(short, long, default_value = "migrations")]
+        dir: PathBuf,
+
+        /// Datenbank-URL (sqlite:, postgres://, mysql://; Standard: DATABASE_URL)
+        #[arg(long)]
+        database_url: Option<String>,
+
+        /// Anzahl der Migrationen
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+
+        /// Zeigt das SQL, ohne es auszuführen
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Zeigt angewendete und ausstehende Migrationen
+    Status {
+        /// Migrations-Verzeichnis
+        #[arg(short, long, default_value = "migrations")]
+        dir: PathBuf,
+
+        /// Datenbank-URL (sqlite:, postgres://, mysql://; Standard: DATABASE_URL)
+        #[arg(long)]
+        database_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuditCommands {
+    /// Prüft Hash-Kette, Signaturen und Head eines Audit-Logs inklusive rotierter Segmente
+    Verify {
+        /// Aktive Log-Datei (Standard: AUDIT_LOG_FILE oder audit.log)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// HMAC-Schlüssel (Standard: AUDIT_HMAC_KEY)
+        #[arg(long)]
+        key: Option<String>,
+    },
+}
@@ -0,0 +1,501 @@
+// AsyncAPI 3.0 Generator
+// Beschreibt `@WebSocket`- und `@SSE`-Endpunkte als Kanäle mit getypten Nachrichten;
+// Schemas kommen aus demselben Builder wie beim OpenAPI-Dokument
+
+use crate::codegen::openapi::{
+    collect_refs, openapi_path, OpenAPIFormat, OpenAPIInfo, Schema, SchemaBuilder, SecurityScheme,
+    SCHEMA_PREFIX,
+};
+use crate::parser::ast::*;
+use crate::stdlib::realtime::{path_params, EndpointKind, RealtimeCatalog};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+pub const ASYNCAPI_VERSION: &str = "3.0.0";
+
+const CHANNEL_PREFIX: &str = "#/channels/";
+const MESSAGE_PREFIX: &str = "#/components/messages/";
+const SECURITY_PREFIX: &str = "#/components/securitySchemes/";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncAPISpec {
+    pub asyncapi: String,
+    pub info: OpenAPIInfo,
+    pub default_content_type: String,
+    #[serde(default)]
+    pub channels: BTreeMap<String, Channel>,
+    #[serde(default)]
+    pub operations: BTreeMap<String, AsyncOperation>,
+    #[serde(default)]
+    pub components: AsyncComponents,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncComponents {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub schemas: BTreeMap<String, Schema>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub messages: BTreeMap<String, Message>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub security_schemes: BTreeMap<String, AsyncSecurityScheme>,
+}
+
+/// `$ref` auf ein anderes Objekt des Dokuments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reference {
+    #[serde(rename = "$ref")]
+    pub reference: String,
+}
+
+impl Reference {
+    fn to(prefix: &str, name: &str) -> Self {
+        Reference {
+            reference: format!("{}{}", prefix, name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Channel {
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub messages: BTreeMap<String, Reference>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, ChannelParameter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bindings: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelParameter {
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsyncOperation {
+    /// `receive`: Nachrichten vom Client, `send`: Nachrichten an den Client
+    pub action: String,
+    pub channel: Reference,
+    #[serde(default)]
+    pub messages: Vec<Reference>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply: Option<OperationReply>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub security: Vec<Reference>,
+    /// Rollen aus `@Role`; AsyncAPI kennt keine Scopes für HTTP-Schemes
+    #[serde(rename = "x-roles", default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationReply {
+    pub channel: Reference,
+    #[serde(default)]
+    pub messages: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub name: String,
+    pub content_type: String,
+    pub payload: Schema,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsyncSecurityScheme {
+    #[serde(flatten)]
+    pub scheme: SecurityScheme,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "in", default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+pub struct AsyncAPIGenerator {
+    pub title: String,
+    pub version: String,
+    pub description: Option<String>,
+}
+
+impl Default for AsyncAPIGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncAPIGenerator {
+    pub fn new() -> Self {
+        AsyncAPIGenerator {
+            title: "VelinScript API".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("Realtime API generated from VelinScript".to_string()),
+        }
+    }
+
+    pub fn build(&self, program: &Program) -> AsyncAPISpec {
+        let mut schemas = SchemaBuilder::new(&program.items);
+        let mut spec = AsyncAPISpec {
+            asyncapi: ASYNCAPI_VERSION.to_string(),
+            info: OpenAPIInfo {
+                title: self.title.clone(),
+                version: self.version.clone(),
+                description: self.description.clone(),
+            },
+            default_content_type: "application/json".to_string(),
+            channels: BTreeMap::new(),
+            operations: BTreeMap::new(),
+            components: AsyncComponents::default(),
+        };
+
+        // Ungültige Endpunkte meldet der Type-Checker, sie fehlen hier
+        let (catalog, _) = RealtimeCatalog::build(&program.items);
+        for endpoint in &catalog.endpoints {
+            let name = endpoint.function.clone();
+            let mut messages = BTreeMap::new();
+            for message in endpoint.incoming.iter().chain([&endpoint.outgoing]) {
+                let payload = schemas.type_schema(&Type::Named(message.clone()), &HashMap::new());
+                spec.components
+                    .messages
+                    .entry(message.clone())
+                    .or_insert_with(|| Message {
+                        name: message.clone(),
+                        content_type: "application/json".to_string(),
+                        payload,
+                    });
+                messages.insert(message.clone(), Reference::to(MESSAGE_PREFIX, message));
+            }
+
+            let parameters = endpoint
+                .path_params
+                .iter()
+                .map(|(param, ty)| {
+                    let description = ChannelParameter {
+                        description: format!("Pfad-Parameter ({})", ty.to_string()),
+                    };
+                    (param.clone(), description)
+                })
+                .collect();
+            let (description, bindings) = match endpoint.kind {
+                EndpointKind::WebSocket => (
+                    endpoint.documentation.clone(),
+                    Some(serde_json::json!({
+                        "ws": { "method": "GET", "bindingVersion": "0.1.0" }
+                    })),
+                ),
+                EndpointKind::Sse => {
+                    let sse =
+                        "Server-Sent Events (text/event-stream), eine Nachricht pro `data:`-Zeile";
+                    let description = match &endpoint.documentation {
+                        Some(doc) => format!("{}\n\n{}", doc, sse),
+                        None => sse.to_string(),
+                    };
+                    (Some(description), None)
+                }
+            };
+            spec.channels.insert(
+                name.clone(),
+                Channel {
+                    address: openapi_path(&endpoint.path),
+                    description,
+                    messages,
+                    parameters,
+                    bindings,
+                },
+            );
+
+            let security = if endpoint.auth {
+                security_schemes(&mut spec.components.security_schemes)
+            } else {
+                Vec::new()
+            };
+            let channel = Reference::to(CHANNEL_PREFIX, &name);
+            let channel_message = |message: &str| {
+                Reference::to(&format!("{}{}/messages/", CHANNEL_PREFIX, name), message)
+            };
+            if let Some(incoming) = &endpoint.incoming {
+                let reply = endpoint.replies.then(|| OperationReply {
+                    channel: channel.clone(),
+                    messages: vec![channel_message(&endpoint.outgoing)],
+                });
+                spec.operations.insert(
+                    format!("{}.receive", name),
+                    AsyncOperation {
+                        action: "receive".to_string(),
+                        channel: channel.clone(),
+                        messages: vec![channel_message(incoming)],
+                        reply,
+                        security: security.clone(),
+                        roles: endpoint.roles.clone(),
+                    },
+                );
+            }
+            spec.operations.insert(
+                format!("{}.send", name),
+                AsyncOperation {
+                    action: "send".to_string(),
+                    channel,
+                    messages: vec![channel_message(&endpoint.outgoing)],
+                    reply: None,
+                    security,
+                    roles: endpoint.roles.clone(),
+                },
+            );
+        }
+
+        spec.components.schemas = schemas.finish();
+        spec
+    }
+}
+
+/// JWT im `Authorization`-Header oder, für Browser, im Query-Parameter `token`
+fn security_schemes(schemes: &mut BTreeMap<String, AsyncSecurityScheme>) -> Vec<Reference> {
+    let scheme = |scheme_type: &str| SecurityScheme {
+        scheme_type: scheme_type.to_string(),
+        scheme: None,
+        bearer_format: None,
+        description: None,
+        flows: None,
+        open_id_connect_url: None,
+    };
+    schemes
+        .entry("bearerAuth".to_string())
+        .or_insert_with(|| AsyncSecurityScheme {
+            scheme: SecurityScheme {
+                scheme: Some("bearer".to_string()),
+                bearer_format: Some("JWT".to_string()),
+                ..scheme("http")
+            },
+            name: None,
+            location: None,
+        });
+    schemes
+        .entry("tokenQuery".to_string())
+        .or_insert_with(|| AsyncSecurityScheme {
+            scheme: SecurityScheme {
+                description: Some("JWT als Query-Parameter `token`".to_string()),
+                ..scheme("httpApiKey")
+            },
+            name: Some("token".to_string()),
+            location: Some("query".to_string()),
+        });
+    vec![
+        Reference::to(SECURITY_PREFIX, "bearerAuth"),
+        Reference::to(SECURITY_PREFIX, "tokenQuery"),
+    ]
+}
+
+/// Serialisiert das Dokument
+pub fn render(spec: &AsyncAPISpec, format: OpenAPIFormat) -> anyhow::Result<String> {
+    Ok(match format {
+        OpenAPIFormat::Json => serde_json::to_string_pretty(spec)? + "\n",
+        OpenAPIFormat::Yaml => serde_yaml::to_string(spec)?,
+    })
+}
+
+/// Strukturelle Prüfung: Adressen, Kanal-Parameter und alle `$ref`s
+pub fn validate(spec: &AsyncAPISpec) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !spec.asyncapi.starts_with("3.") {
+        errors.push(format!(
+            "asyncapi version must be 3.x, found {}",
+            spec.asyncapi
+        ));
+    }
+    if spec.info.title.is_empty() || spec.info.version.is_empty() {
+        errors.push("info.title and info.version are required".to_string());
+    }
+
+    let mut refs = Vec::new();
+    for (name, schema) in &spec.components.schemas {
+        collect_refs(schema, &format!("components.schemas.{}", name), &mut refs);
+    }
+    for (name, message) in &spec.components.messages {
+        collect_refs(
+            &message.payload,
+            &format!("components.messages.{}", name),
+            &mut refs,
+        );
+    }
+
+    for (name, channel) in &spec.channels {
+        let at = format!("channels.{}", name);
+        if !channel.address.starts_with('/') {
+            errors.push(format!(
+                "{}: address '{}' must start with '/'",
+                at, channel.address
+            ));
+        }
+        let template: HashSet<String> = path_params(&channel.address).into_iter().collect();
+        let declared: HashSet<String> = channel.parameters.keys().cloned().collect();
+        for missing in template.difference(&declared) {
+            errors.push(format!("{}: parameter '{}' is not declared", at, missing));
+        }
+        for unused in declared.difference(&template) {
+            errors.push(format!(
+                "{}: parameter '{}' does not appear in the address",
+                at, unused
+            ));
+        }
+        for message in channel.messages.values() {
+            match message.reference.strip_prefix(MESSAGE_PREFIX) {
+                Some(component) if spec.components.messages.contains_key(component) => {}
+                _ => errors.push(format!("{}: unresolved $ref '{}'", at, message.reference)),
+            }
+        }
+    }
+
+    for (name, operation) in &spec.operations {
+        let at = format!("operations.{}", name);
+        if !matches!(operation.action.as_str(), "send" | "receive") {
+            errors.push(format!(
+                "{}: action must be send or receive, found '{}'",
+                at, operation.action
+            ));
+        }
+        let mut channels = vec![(&operation.channel, &operation.messages)];
+        if let Some(reply) = &operation.reply {
+            channels.push((&reply.channel, &reply.messages));
+        }
+        for (channel, messages) in channels {
+            let channel_name = channel.reference.strip_prefix(CHANNEL_PREFIX);
+            if !channel_name.is_some_and(|c| spec.channels.contains_key(c)) {
+                errors.push(format!("{}: unresolved $ref '{}'", at, channel.reference));
+                continue;
+            }
+            for message in messages {
+                if message_channel(spec, message) != channel_name {
+                    errors.push(format!(
+                        "{}: message '{}' is not part of '{}'",
+                        at, message.reference, channel.reference
+                    ));
+                }
+            }
+        }
+        for scheme in &operation.security {
+            match scheme.reference.strip_prefix(SECURITY_PREFIX) {
+                Some(name) if spec.components.security_schemes.contains_key(name) => {}
+                _ => errors.push(format!(
+                    "{}: unknown security scheme '{}'",
+                    at, scheme.reference
+                )),
+            }
+        }
+    }
+
+    for (at, component) in refs {
+        if !spec.components.schemas.contains_key(&component) {
+            errors.push(format!(
+                "{}: unresolved $ref '{}{}'",
+                at, SCHEMA_PREFIX, component
+            ));
+        }
+    }
+    errors
+}
+
+/// Kanal, dessen `messages` die Referenz auflöst
+fn message_channel<'a>(spec: &AsyncAPISpec, reference: &'a Reference) -> Option<&'a str> {
+    let (channel, message) = reference
+        .reference
+        .strip_prefix(CHANNEL_PREFIX)?
+        .split_once("/messages/")?;
+    spec.channels
+        .get(channel)?
+        .messages
+        .contains_key(message)
+        .then_some(channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_realtime_endpoints_become_channels() {
+        let program = Parser::parse(
+            r#"
+            struct ChatMessage {
+                text: string,
+                author: Author,
+            }
+
+            struct Author {
+                name: string,
+            }
+
+            struct NewsItem {
+                title: string,
+            }
+
+            /// Chat pro Raum
+            @Auth
+            @Role("member")
+            @WebSocket("/chat/:room")
+            fn chat(conn: Connection<ChatMessage>, message: ChatMessage, room: string): ChatMessage {
+                return message;
+            }
+
+            @SSE("/news")
+            fn news(conn: Connection<NewsItem>) {
+                conn.join("news");
+            }
+            "#,
+        )
+        .expect("parse");
+        let spec = AsyncAPIGenerator::new().build(&program);
+        assert!(validate(&spec).is_empty(), "{:?}", validate(&spec));
+
+        let chat = &spec.channels["chat"];
+        assert_eq!(chat.address, "/chat/{room}");
+        assert_eq!(chat.description.as_deref(), Some("Chat pro Raum"));
+        assert!(chat.parameters.contains_key("room"));
+        assert!(chat.bindings.is_some());
+        assert!(spec.channels["news"].bindings.is_none());
+
+        let receive = &spec.operations["chat.receive"];
+        assert_eq!(receive.action, "receive");
+        assert_eq!(
+            receive.reply.as_ref().unwrap().messages[0].reference,
+            "#/channels/chat/messages/ChatMessage"
+        );
+        assert_eq!(receive.security.len(), 2);
+        assert_eq!(receive.roles, vec!["member".to_string()]);
+        assert!(!spec.operations.contains_key("news.receive"));
+        assert!(spec.operations["news.send"].security.is_empty());
+
+        // Verschachtelte Structs landen mit in components/schemas
+        assert!(spec.components.schemas.contains_key("Author"));
+        assert_eq!(
+            spec.components.messages["NewsItem"]
+                .payload
+                .ref_path
+                .as_deref(),
+            Some("#/components/schemas/NewsItem")
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render(&spec, OpenAPIFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["asyncapi"], "3.0.0");
+        assert_eq!(
+            json["components"]["securitySchemes"]["tokenQuery"]["in"],
+            "query"
+        );
+        assert_eq!(
+            json["operations"]["chat.send"]["channel"]["$ref"],
+            "#/channels/chat"
+        );
+
+        let mut broken = spec.clone();
+        broken.channels.get_mut("news").unwrap().address = "/news/{id}".to_string();
+        broken.operations.get_mut("news.send").unwrap().messages[0].reference =
+            "#/channels/chat/messages/ChatMessage".to_string();
+        let errors = validate(&broken);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+    }
+}
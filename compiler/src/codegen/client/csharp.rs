@@ -0,0 +1,435 @@
+// C# Client
+// Klassen aus `components/schemas`, asynchroner Client über `HttpClient` und System.Text.Json
+
+use super::{camel_case, doc_line, pascal_case, ClientModel, ModelKind, TypeRef};
+
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+fn identifier(name: &str) -> String {
+    let name = camel_case(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("@{}", name)
+    } else {
+        name
+    }
+}
+
+fn cs_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String | TypeRef::Literals(_) => "string".to_string(),
+        TypeRef::Number => "double".to_string(),
+        TypeRef::Integer => "long".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Array(item) => format!("List<{}>", cs_type(item)),
+        TypeRef::Map(value) => format!("Dictionary<string, {}>", cs_type(value)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => nullable(cs_type(inner)),
+        TypeRef::Object(_) | TypeRef::Union(_) | TypeRef::Any => "JsonElement".to_string(),
+    }
+}
+
+fn nullable(ty: String) -> String {
+    if ty.ends_with('?') {
+        ty
+    } else {
+        format!("{}?", ty)
+    }
+}
+
+fn optional_type(ty: &TypeRef, required: bool) -> String {
+    if required {
+        cs_type(ty)
+    } else {
+        nullable(cs_type(ty))
+    }
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n#nullable enable\n\n",
+        model.title, model.version
+    ));
+    code.push_str(
+        r#"using System;
+using System.Collections.Generic;
+using System.Linq;
+using System.Net.Http;
+using System.Net.Http.Headers;
+using System.Text;
+using System.Text.Json;
+using System.Text.Json.Serialization;
+using System.Threading;
+using System.Threading.Tasks;
+
+namespace Api
+{
+"#,
+    );
+
+    for m in &model.models {
+        if let Some(doc) = doc_line(&m.description) {
+            code.push_str(&format!("    /// <summary>{}</summary>\n", doc));
+        }
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                code.push_str(&format!("    public class {}\n    {{\n", m.name));
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        code.push('\n');
+                    }
+                    if let Some(doc) = doc_line(&field.description) {
+                        code.push_str(&format!("        /// <summary>{}</summary>\n", doc));
+                    }
+                    let ty = optional_type(&field.ty, field.required);
+                    let init = if ty.ends_with('?') {
+                        ""
+                    } else if ty == "string" {
+                        " = \"\";"
+                    } else if ty.starts_with("List<") || ty.starts_with("Dictionary<") {
+                        " = new();"
+                    } else if matches!(field.ty, TypeRef::Named(_)) {
+                        " = default!;"
+                    } else {
+                        ""
+                    };
+                    code.push_str(&format!("        [JsonPropertyName({:?})]\n", field.name));
+                    if ty.ends_with('?') {
+                        code.push_str(
+                            "        [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]\n",
+                        );
+                    }
+                    code.push_str(&format!(
+                        "        public {} {} {{ get; set; }}{}\n",
+                        ty,
+                        pascal_case(&field.name),
+                        init
+                    ));
+                }
+                code.push_str("    }\n\n");
+            }
+            ModelKind::Enum(values) => {
+                // String-Enums als Konstanten, damit unbekannte Werte nicht beim Deserialisieren scheitern
+                code.push_str(&format!("    public static class {}\n    {{\n", m.name));
+                for value in values {
+                    code.push_str(&format!(
+                        "        public const string {} = {:?};\n",
+                        pascal_case(value),
+                        value
+                    ));
+                }
+                code.push_str("    }\n\n");
+            }
+            ModelKind::Alias(ty) => {
+                // C# kennt keine exportierbaren Typ-Aliase: Wrapper mit Konverter
+                let inner = cs_type(ty);
+                code.push_str(&format!(
+                    "    [JsonConverter(typeof(ValueConverter<{name}, {inner}>))]\n    public class {name} : IValue<{inner}>\n    {{\n        public {inner} Value {{ get; set; }} = default!;\n    }}\n\n",
+                    name = m.name,
+                    inner = inner
+                ));
+            }
+        }
+    }
+
+    let has_alias = model
+        .models
+        .iter()
+        .any(|m| matches!(m.kind, ModelKind::Alias(_)));
+    if has_alias {
+        code.push_str(
+            r#"    public interface IValue<T>
+    {
+        T Value { get; set; }
+    }
+
+    public class ValueConverter<TWrapper, T> : JsonConverter<TWrapper> where TWrapper : IValue<T>, new()
+    {
+        public override TWrapper Read(ref Utf8JsonReader reader, Type typeToConvert, JsonSerializerOptions options)
+            => new TWrapper { Value = JsonSerializer.Deserialize<T>(ref reader, options)! };
+
+        public override void Write(Utf8JsonWriter writer, TWrapper value, JsonSerializerOptions options)
+            => JsonSerializer.Serialize(writer, value.Value, options);
+    }
+
+"#,
+        );
+    }
+
+    code.push_str(
+        r#"    public class ValidationIssue
+    {
+        [JsonPropertyName("field")]
+        public string Field { get; set; } = "";
+
+        [JsonPropertyName("message")]
+        public string Message { get; set; } = "";
+    }
+
+    /// <summary>Fehlerantwort der API (Status &gt;= 400)</summary>
+    public class ApiException : Exception
+    {
+        public int Status { get; }
+        public IReadOnlyList<ValidationIssue> Errors { get; }
+
+        public ApiException(int status, string message, IReadOnlyList<ValidationIssue>? errors = null)
+            : base($"{status}: {message}")
+        {
+            Status = status;
+            Errors = errors ?? Array.Empty<ValidationIssue>();
+        }
+    }
+
+    internal class ErrorBody
+    {
+        [JsonPropertyName("error")]
+        public string? Error { get; set; }
+
+        [JsonPropertyName("errors")]
+        public List<ValidationIssue>? Errors { get; set; }
+    }
+
+    public class ApiClient
+    {
+        private readonly HttpClient _http;
+        private readonly string _baseUrl;
+        private static readonly JsonSerializerOptions JsonOptions = new JsonSerializerOptions();
+
+        /// <summary>Bearer Token für geschützte Endpunkte</summary>
+        public string? Token { get; set; }
+
+        public ApiClient(string baseUrl, HttpClient? http = null)
+        {
+            _baseUrl = baseUrl.TrimEnd('/');
+            _http = http ?? new HttpClient();
+        }
+"#,
+    );
+
+    for op in &model.operations {
+        let mut params = Vec::new();
+        for (param, optional) in op.signature() {
+            let ty = optional_type(&param.ty, param.required);
+            params.push(if optional {
+                format!("{} {} = null", nullable(ty), identifier(&param.name))
+            } else {
+                format!("{} {}", ty, identifier(&param.name))
+            });
+        }
+        params.push("CancellationToken cancellationToken = default".to_string());
+        let response = op.response.as_ref().map(cs_type);
+
+        code.push('\n');
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("        /// <summary>{}</summary>\n", doc));
+        }
+        code.push_str(&format!(
+            "        public async {} {}Async({})\n        {{\n",
+            match &response {
+                Some(ty) => format!("Task<{}>", ty),
+                None => "Task".to_string(),
+            },
+            pascal_case(&op.name),
+            params.join(", ")
+        ));
+
+        let path = op
+            .path_segments()
+            .into_iter()
+            .map(|segment| match segment {
+                Ok(text) => text.replace('{', "{{").replace('}', "}}").replace('"', "\\\""),
+                Err(param) => format!(
+                    "{{Uri.EscapeDataString(Convert.ToString({}, System.Globalization.CultureInfo.InvariantCulture)!)}}",
+                    identifier(&param)
+                ),
+            })
+            .collect::<String>();
+        code.push_str(&format!("            var path = $\"{}\";\n", path));
+        code.push_str("            var query = new List<KeyValuePair<string, object?>>();\n");
+        for param in &op.query_params {
+            code.push_str(&format!(
+                "            query.Add(new KeyValuePair<string, object?>({:?}, {}));\n",
+                param.name,
+                identifier(&param.name)
+            ));
+        }
+        let body = op
+            .body
+            .as_ref()
+            .map(|b| identifier(&b.name))
+            .unwrap_or_else(|| "null".to_string());
+        let secured = if op.secured { "true" } else { "false" };
+        match &response {
+            Some(ty) => code.push_str(&format!(
+                "            using var response = await SendAsync(HttpMethod.{}, path, query, {}, {}, cancellationToken);\n            var stream = await response.Content.ReadAsStreamAsync();\n            return (await JsonSerializer.DeserializeAsync<{}>(stream, JsonOptions, cancellationToken))!;\n",
+                method(&op.method),
+                body,
+                secured,
+                ty
+            )),
+            None => code.push_str(&format!(
+                "            using var response = await SendAsync(HttpMethod.{}, path, query, {}, {}, cancellationToken);\n",
+                method(&op.method),
+                body,
+                secured
+            )),
+        }
+        code.push_str("        }\n");
+    }
+
+    code.push_str(
+        r#"
+        private async Task<HttpResponseMessage> SendAsync(HttpMethod method, string path, List<KeyValuePair<string, object?>> query, object? body, bool auth, CancellationToken cancellationToken)
+        {
+            var parts = query
+                .Where(p => p.Value != null)
+                .Select(p => Uri.EscapeDataString(p.Key) + "=" + Uri.EscapeDataString(QueryValue(p.Value!)));
+            var queryString = string.Join("&", parts);
+            var request = new HttpRequestMessage(method, _baseUrl + path + (queryString.Length > 0 ? "?" + queryString : ""));
+            request.Headers.Accept.Add(new MediaTypeWithQualityHeaderValue("application/json"));
+            if (body != null)
+            {
+                request.Content = new StringContent(JsonSerializer.Serialize(body, body.GetType(), JsonOptions), Encoding.UTF8, "application/json");
+            }
+            if (auth && Token != null)
+            {
+                request.Headers.Authorization = new AuthenticationHeaderValue("Bearer", Token);
+            }
+
+            var response = await _http.SendAsync(request, cancellationToken);
+            if ((int)response.StatusCode >= 400)
+            {
+                var text = await response.Content.ReadAsStringAsync();
+                ErrorBody? error = null;
+                try
+                {
+                    error = JsonSerializer.Deserialize<ErrorBody>(text, JsonOptions);
+                }
+                catch (JsonException)
+                {
+                    // Kein JSON-Fehlerobjekt
+                }
+                var status = (int)response.StatusCode;
+                response.Dispose();
+                throw new ApiException(status, error?.Error ?? response.ReasonPhrase ?? "HTTP " + status, error?.Errors);
+            }
+            return response;
+        }
+
+        private static string QueryValue(object value) => value switch
+        {
+            bool b => b ? "true" : "false",
+            string s => s,
+            IFormattable f => f.ToString(null, System.Globalization.CultureInfo.InvariantCulture),
+            _ => JsonSerializer.Serialize(value, value.GetType(), JsonOptions),
+        };
+    }
+}
+"#,
+    );
+    code
+}
+
+/// `GET` → `Get` für `HttpMethod.Get`
+fn method(method: &str) -> String {
+    pascal_case(&method.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::client;
+
+    #[test]
+    fn test_csharp_client_output() {
+        let code = client("csharp");
+        for expected in [
+            r#"public const string Admin = "Admin";"#,
+            "public string? Nickname { get; set; }",
+            "public List<string> Tags { get; set; } = new();",
+            "public async Task<User> GetUserAsync(string id, bool verbose, CancellationToken cancellationToken = default)",
+            "using var response = await SendAsync(HttpMethod.Get, path, query, null, true, cancellationToken);",
+            "public async Task DeleteUserAsync(string id, CancellationToken cancellationToken = default)",
+            r#"request.Headers.Authorization = new AuthenticationHeaderValue("Bearer", Token);"#,
+        ] {
+            assert!(code.contains(expected), "fehlt: {}\n{}", expected, code);
+        }
+    }
+}
@@ -0,0 +1,403 @@
+// Go Client
+// Structs mit JSON-Tags aus `components/schemas`, Client über `net/http`
+
+use super::{camel_case, doc_line, pascal_case, ClientModel, ModelKind, TypeRef};
+
+const KEYWORDS: &[&str] = &[
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+    "ctx",
+    "url",
+    "query",
+    "req",
+    "resp",
+];
+
+fn identifier(name: &str) -> String {
+    let name = camel_case(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}Param", name)
+    } else {
+        name
+    }
+}
+
+fn go_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String | TypeRef::Literals(_) => "string".to_string(),
+        TypeRef::Number => "float64".to_string(),
+        TypeRef::Integer => "int64".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Array(item) => format!("[]{}", go_type(item)),
+        TypeRef::Map(value) => format!("map[string]{}", go_type(value)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => pointer(go_type(inner)),
+        TypeRef::Object(_) | TypeRef::Union(_) | TypeRef::Any => "json.RawMessage".to_string(),
+    }
+}
+
+/// Slices, Maps und RawMessage sind schon nil-fähig
+fn pointer(ty: String) -> String {
+    if ty.starts_with('*')
+        || ty.starts_with("[]")
+        || ty.starts_with("map[")
+        || ty == "json.RawMessage"
+    {
+        ty
+    } else {
+        format!("*{}", ty)
+    }
+}
+
+fn field_type(ty: &TypeRef, required: bool) -> String {
+    if required {
+        go_type(ty)
+    } else {
+        pointer(go_type(ty))
+    }
+}
+
+/// Ausdruck, der einen Query-Wert als String formatiert
+fn format_value(ty: &TypeRef, value: &str) -> String {
+    match ty.inner() {
+        TypeRef::String | TypeRef::Literals(_) => value.to_string(),
+        TypeRef::Named(_) => format!("fmt.Sprint({})", value),
+        TypeRef::Array(_)
+        | TypeRef::Map(_)
+        | TypeRef::Object(_)
+        | TypeRef::Union(_)
+        | TypeRef::Any => {
+            format!("jsonString({})", value)
+        }
+        _ => format!("fmt.Sprint({})", value),
+    }
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n\n",
+        model.title, model.version
+    ));
+    code.push_str(
+        r#"package client
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"strings"
+)
+"#,
+    );
+
+    for m in &model.models {
+        code.push('\n');
+        if let Some(doc) = doc_line(&m.description) {
+            code.push_str(&format!("// {} {}\n", m.name, doc));
+        }
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                code.push_str(&format!("type {} struct {{\n", m.name));
+                let columns: Vec<(String, String)> = fields
+                    .iter()
+                    .map(|f| (pascal_case(&f.name), field_type(&f.ty, f.required)))
+                    .collect();
+                // Spalten wie gofmt ausrichten
+                let name_width = columns.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+                let type_width = columns.iter().map(|(_, t)| t.len()).max().unwrap_or(0);
+                for (field, (name, ty)) in fields.iter().zip(&columns) {
+                    let omit = if field.required { "" } else { ",omitempty" };
+                    code.push_str(&format!(
+                        "\t{:nw$} {:tw$} `json:\"{}{}\"`\n",
+                        name,
+                        ty,
+                        field.name,
+                        omit,
+                        nw = name_width,
+                        tw = type_width
+                    ));
+                }
+                code.push_str("}\n");
+            }
+            ModelKind::Enum(values) => {
+                code.push_str(&format!("type {} string\n\nconst (\n", m.name));
+                for value in values {
+                    code.push_str(&format!(
+                        "\t{}{} {} = {:?}\n",
+                        m.name,
+                        pascal_case(value),
+                        m.name,
+                        value
+                    ));
+                }
+                code.push_str(")\n");
+            }
+            ModelKind::Alias(ty) => {
+                code.push_str(&format!("type {} = {}\n", m.name, go_type(ty)));
+            }
+        }
+    }
+
+    code.push_str(
+        r#"
+type ValidationIssue struct {
+	Field   string `json:"field"`
+	Message string `json:"message"`
+}
+
+// APIError ist die Fehlerantwort der API (Status >= 400)
+type APIError struct {
+	Status  int               `json:"-"`
+	Message string            `json:"error"`
+	Errors  []ValidationIssue `json:"errors"`
+}
+
+func (e *APIError) Error() string {
+	return fmt.Sprintf("%d: %s", e.Status, e.Message)
+}
+
+type Client struct {
+	BaseURL    string
+	Token      string
+	HTTPClient *http.Client
+}
+
+func NewClient(baseURL string) *Client {
+	return &Client{
+		BaseURL:    strings.TrimRight(baseURL, "/"),
+		HTTPClient: http.DefaultClient,
+	}
+}
+
+// WithToken setzt den Bearer Token für geschützte Endpunkte
+func (c *Client) WithToken(token string) *Client {
+	c.Token = token
+	return c
+}
+"#,
+    );
+
+    for op in &model.operations {
+        let mut params = vec!["ctx context.Context".to_string()];
+        for param in op.params() {
+            params.push(format!(
+                "{} {}",
+                identifier(&param.name),
+                field_type(&param.ty, param.required)
+            ));
+        }
+        let response = op.response.as_ref().map(go_type);
+        let returns = match &response {
+            Some(ty) => format!("({}, error)", pointer(ty.clone())),
+            None => "error".to_string(),
+        };
+
+        code.push('\n');
+        let method_name = pascal_case(&op.name);
+        match doc_line(&op.summary) {
+            Some(doc) => code.push_str(&format!("// {} {}\n", method_name, doc)),
+            None => code.push_str(&format!(
+                "// {} ruft {} {} auf\n",
+                method_name, op.method, op.path
+            )),
+        }
+        code.push_str(&format!(
+            "func (c *Client) {}({}) {} {{\n",
+            method_name,
+            params.join(", "),
+            returns
+        ));
+
+        let mut format_string = String::new();
+        let mut args = Vec::new();
+        for segment in op.path_segments() {
+            match segment {
+                Ok(text) => format_string.push_str(&text.replace('%', "%%")),
+                Err(param) => {
+                    format_string.push_str("%s");
+                    args.push(format!(
+                        "url.PathEscape(fmt.Sprint({}))",
+                        identifier(&param)
+                    ));
+                }
+            }
+        }
+        if args.is_empty() {
+            code.push_str(&format!("\tpath := {:?}\n", op.path));
+        } else {
+            code.push_str(&format!(
+                "\tpath := fmt.Sprintf({:?}, {})\n",
+                format_string,
+                args.join(", ")
+            ));
+        }
+
+        code.push_str("\tquery := url.Values{}\n");
+        for param in &op.query_params {
+            let name = identifier(&param.name);
+            let ty = field_type(&param.ty, param.required);
+            if ty.starts_with('*') {
+                code.push_str(&format!(
+                    "\tif {} != nil {{\n\t\tquery.Set({:?}, {})\n\t}}\n",
+                    name,
+                    param.name,
+                    format_value(&param.ty, &format!("*{}", name))
+                ));
+            } else if ty.starts_with("[]") || ty.starts_with("map[") || ty == "json.RawMessage" {
+                code.push_str(&format!(
+                    "\tif {} != nil {{\n\t\tquery.Set({:?}, {})\n\t}}\n",
+                    name,
+                    param.name,
+                    format_value(&param.ty, &name)
+                ));
+            } else {
+                code.push_str(&format!(
+                    "\tquery.Set({:?}, {})\n",
+                    param.name,
+                    format_value(&param.ty, &name)
+                ));
+            }
+        }
+
+        let body = op
+            .body
+            .as_ref()
+            .map(|b| identifier(&b.name))
+            .unwrap_or_else(|| "nil".to_string());
+        match &response {
+            Some(ty) => {
+                // Slices und Maps werden direkt zurückgegeben, alles andere als Pointer
+                let result = if pointer(ty.clone()) == *ty {
+                    "result"
+                } else {
+                    "&result"
+                };
+                code.push_str(&format!("\tvar result {}\n", ty));
+                code.push_str(&format!(
+                    "\tif err := c.do(ctx, {:?}, path, query, {}, {}, &result); err != nil {{\n\t\treturn nil, err\n\t}}\n\treturn {}, nil\n}}\n",
+                    op.method, body, op.secured, result
+                ));
+            }
+            None => {
+                code.push_str(&format!(
+                    "\treturn c.do(ctx, {:?}, path, query, {}, {}, nil)\n}}\n",
+                    op.method, body, op.secured
+                ));
+            }
+        }
+    }
+
+    code.push_str(
+        r#"
+func (c *Client) do(ctx context.Context, method, path string, query url.Values, body interface{}, auth bool, result interface{}) error {
+	target := c.BaseURL + path
+	if encoded := query.Encode(); encoded != "" {
+		target += "?" + encoded
+	}
+
+	var reader io.Reader
+	if body != nil {
+		payload, err := json.Marshal(body)
+		if err != nil {
+			return err
+		}
+		reader = bytes.NewReader(payload)
+	}
+
+	req, err := http.NewRequestWithContext(ctx, method, target, reader)
+	if err != nil {
+		return err
+	}
+	req.Header.Set("Accept", "application/json")
+	if body != nil {
+		req.Header.Set("Content-Type", "application/json")
+	}
+	if auth && c.Token != "" {
+		req.Header.Set("Authorization", "Bearer "+c.Token)
+	}
+
+	httpClient := c.HTTPClient
+	if httpClient == nil {
+		httpClient = http.DefaultClient
+	}
+	resp, err := httpClient.Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+
+	data, err := io.ReadAll(resp.Body)
+	if err != nil {
+		return err
+	}
+	if resp.StatusCode >= 400 {
+		apiErr := &APIError{Status: resp.StatusCode}
+		if json.Unmarshal(data, apiErr) != nil || apiErr.Message == "" {
+			apiErr.Message = http.StatusText(resp.StatusCode)
+		}
+		return apiErr
+	}
+	if result != nil && len(data) > 0 {
+		return json.Unmarshal(data, result)
+	}
+	return nil
+}
+
+func jsonString(value interface{}) string {
+	data, _ := json.Marshal(value)
+	return string(data)
+}
+"#,
+    );
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::client;
+
+    #[test]
+    fn test_go_client_output() {
+        let code = client("go");
+        for expected in [
+            "type Membership string",
+            r#"MembershipAdmin Membership = "Admin""#,
+            r#"Nickname *string  `json:"nickname,omitempty"`"#,
+            "func (c *Client) GetUser(ctx context.Context, id string, verbose bool) (*User, error) {",
+            r#"path := fmt.Sprintf("/users/%s", url.PathEscape(fmt.Sprint(id)))"#,
+            r#"query.Set("verbose", fmt.Sprint(verbose))"#,
+            r#"c.do(ctx, "GET", path, query, nil, true, &result)"#,
+            "func (c *Client) DeleteUser(ctx context.Context, id string) error {",
+        ] {
+            assert!(code.contains(expected), "fehlt: {}\n{}", expected, code);
+        }
+    }
+}
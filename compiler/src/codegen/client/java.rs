@@ -0,0 +1,382 @@
+// Java Client
+// Records aus `components/schemas`, Client über `java.net.http` und Jackson
+
+use super::{camel_case, doc_line, snake_case, ClientModel, ModelKind, TypeRef};
+
+const KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "try",
+    "void",
+    "volatile",
+    "while",
+    "record",
+    "var",
+    "yield",
+    "path",
+    "query",
+];
+
+fn identifier(name: &str) -> String {
+    let name = camel_case(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}Value", name)
+    } else {
+        name
+    }
+}
+
+fn java_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String | TypeRef::Literals(_) => "String".to_string(),
+        TypeRef::Number => "Double".to_string(),
+        TypeRef::Integer => "Long".to_string(),
+        TypeRef::Boolean => "Boolean".to_string(),
+        TypeRef::Array(item) => format!("List<{}>", java_type(item)),
+        TypeRef::Map(value) => format!("Map<String, {}>", java_type(value)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => java_type(inner),
+        TypeRef::Object(_) | TypeRef::Union(_) | TypeRef::Any => "JsonNode".to_string(),
+    }
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n\n",
+        model.title, model.version
+    ));
+    code.push_str(
+        r#"import com.fasterxml.jackson.annotation.JsonCreator;
+import com.fasterxml.jackson.annotation.JsonIgnoreProperties;
+import com.fasterxml.jackson.annotation.JsonInclude;
+import com.fasterxml.jackson.annotation.JsonProperty;
+import com.fasterxml.jackson.annotation.JsonValue;
+import com.fasterxml.jackson.core.type.TypeReference;
+import com.fasterxml.jackson.databind.JsonNode;
+import com.fasterxml.jackson.databind.ObjectMapper;
+
+import java.io.IOException;
+import java.io.UncheckedIOException;
+import java.net.URI;
+import java.net.URLEncoder;
+import java.net.http.HttpClient;
+import java.net.http.HttpRequest;
+import java.net.http.HttpResponse;
+import java.nio.charset.StandardCharsets;
+import java.util.Collections;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+
+public class ApiClient {
+"#,
+    );
+
+    for m in &model.models {
+        code.push('\n');
+        if let Some(doc) = doc_line(&m.description) {
+            code.push_str(&format!("    /** {} */\n", doc));
+        }
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                let components = fields
+                    .iter()
+                    .map(|field| {
+                        let name = identifier(&field.name);
+                        let annotation = if name != field.name {
+                            format!("@JsonProperty({:?}) ", field.name)
+                        } else {
+                            String::new()
+                        };
+                        format!("{}{} {}", annotation, java_type(&field.ty), name)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",\n        ");
+                code.push_str(
+                    "    @JsonIgnoreProperties(ignoreUnknown = true)\n    @JsonInclude(JsonInclude.Include.NON_NULL)\n",
+                );
+                code.push_str(&format!(
+                    "    public record {}(\n        {}\n    ) {{}}\n",
+                    m.name, components
+                ));
+            }
+            ModelKind::Enum(values) => {
+                let constants = values
+                    .iter()
+                    .map(|value| {
+                        format!(
+                            "@JsonProperty({:?}) {}",
+                            value,
+                            snake_case(value).to_uppercase()
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",\n        ");
+                code.push_str(&format!(
+                    "    public enum {} {{\n        {}\n    }}\n",
+                    m.name, constants
+                ));
+            }
+            ModelKind::Alias(ty) => {
+                // Java kennt keine Typ-Aliase: Record mit einem Wert
+                code.push_str(&format!(
+                    "    public record {}(@JsonValue {} value) {{\n        @JsonCreator\n        public {} {{}}\n    }}\n",
+                    m.name,
+                    java_type(ty),
+                    m.name
+                ));
+            }
+        }
+    }
+
+    code.push_str(
+        r#"
+    @JsonIgnoreProperties(ignoreUnknown = true)
+    public record ValidationIssue(String field, String message) {}
+
+    @JsonIgnoreProperties(ignoreUnknown = true)
+    private record ErrorBody(String error, List<ValidationIssue> errors) {}
+
+    /** Fehlerantwort der API (Status >= 400) */
+    public static class ApiException extends RuntimeException {
+        private final int status;
+        private final List<ValidationIssue> errors;
+
+        public ApiException(int status, String message, List<ValidationIssue> errors) {
+            super(status + ": " + message);
+            this.status = status;
+            this.errors = errors == null ? Collections.emptyList() : errors;
+        }
+
+        public int getStatus() {
+            return status;
+        }
+
+        public List<ValidationIssue> getErrors() {
+            return errors;
+        }
+    }
+
+    private final String baseUrl;
+    private final HttpClient http;
+    private final ObjectMapper mapper;
+    private volatile String token;
+
+    public ApiClient(String baseUrl) {
+        this(baseUrl, HttpClient.newHttpClient(), new ObjectMapper());
+    }
+
+    public ApiClient(String baseUrl, HttpClient http, ObjectMapper mapper) {
+        this.baseUrl = baseUrl.replaceAll("/+$", "");
+        this.http = http;
+        this.mapper = mapper;
+    }
+
+    /** Bearer Token für geschützte Endpunkte */
+    public ApiClient withToken(String token) {
+        this.token = token;
+        return this;
+    }
+"#,
+    );
+
+    for op in &model.operations {
+        let params = op
+            .params()
+            .iter()
+            .map(|p| format!("{} {}", java_type(&p.ty), identifier(&p.name)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let response = op.response.as_ref().map(java_type);
+
+        code.push('\n');
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("    /** {} */\n", doc));
+        }
+        code.push_str(&format!(
+            "    public {} {}({}) {{\n",
+            response.clone().unwrap_or_else(|| "void".to_string()),
+            op.name,
+            params
+        ));
+
+        let path = op
+            .path_segments()
+            .into_iter()
+            .map(|segment| match segment {
+                Ok(text) => format!("{:?}", text),
+                Err(param) => format!("encode(String.valueOf({}))", identifier(&param)),
+            })
+            .collect::<Vec<_>>()
+            .join(" + ");
+        code.push_str(&format!(
+            "        String path = {};\n",
+            if path.is_empty() {
+                "\"\"".to_string()
+            } else {
+                path
+            }
+        ));
+        code.push_str("        Map<String, Object> query = new LinkedHashMap<>();\n");
+        for param in &op.query_params {
+            code.push_str(&format!(
+                "        query.put({:?}, {});\n",
+                param.name,
+                identifier(&param.name)
+            ));
+        }
+        let body = op
+            .body
+            .as_ref()
+            .map(|b| identifier(&b.name))
+            .unwrap_or_else(|| "null".to_string());
+        match &response {
+            Some(ty) => code.push_str(&format!(
+                "        return send({:?}, path, query, {}, {}, new TypeReference<{}>() {{}});\n",
+                op.method, body, op.secured, ty
+            )),
+            None => code.push_str(&format!(
+                "        send({:?}, path, query, {}, {}, null);\n",
+                op.method, body, op.secured
+            )),
+        }
+        code.push_str("    }\n");
+    }
+
+    code.push_str(
+        r#"
+    private <T> T send(String method, String path, Map<String, Object> query, Object body, boolean auth, TypeReference<T> type) {
+        try {
+            StringBuilder target = new StringBuilder(baseUrl).append(path);
+            String separator = "?";
+            for (Map.Entry<String, Object> entry : query.entrySet()) {
+                if (entry.getValue() == null) {
+                    continue;
+                }
+                target.append(separator)
+                    .append(URLEncoder.encode(entry.getKey(), StandardCharsets.UTF_8))
+                    .append('=')
+                    .append(URLEncoder.encode(queryValue(entry.getValue()), StandardCharsets.UTF_8));
+                separator = "&";
+            }
+
+            HttpRequest.Builder request = HttpRequest.newBuilder(URI.create(target.toString()))
+                .header("Accept", "application/json");
+            if (body != null) {
+                request.header("Content-Type", "application/json");
+                request.method(method, HttpRequest.BodyPublishers.ofString(mapper.writeValueAsString(body)));
+            } else {
+                request.method(method, HttpRequest.BodyPublishers.noBody());
+            }
+            String currentToken = token;
+            if (auth && currentToken != null) {
+                request.header("Authorization", "Bearer " + currentToken);
+            }
+
+            HttpResponse<String> response = http.send(request.build(), HttpResponse.BodyHandlers.ofString());
+            if (response.statusCode() >= 400) {
+                throw error(response);
+            }
+            if (type == null || response.body() == null || response.body().isEmpty()) {
+                return null;
+            }
+            return mapper.readValue(response.body(), type);
+        } catch (IOException e) {
+            throw new UncheckedIOException(e);
+        } catch (InterruptedException e) {
+            Thread.currentThread().interrupt();
+            throw new IllegalStateException(e);
+        }
+    }
+
+    private ApiException error(HttpResponse<String> response) {
+        try {
+            ErrorBody body = mapper.readValue(response.body(), ErrorBody.class);
+            if (body.error() != null) {
+                return new ApiException(response.statusCode(), body.error(), body.errors());
+            }
+        } catch (IOException | RuntimeException ignored) {
+            // Kein JSON-Fehlerobjekt
+        }
+        return new ApiException(response.statusCode(), "HTTP " + response.statusCode(), null);
+    }
+
+    private String queryValue(Object value) {
+        JsonNode node = mapper.valueToTree(value);
+        return node.isValueNode() ? node.asText() : node.toString();
+    }
+
+    private static String encode(String value) {
+        return URLEncoder.encode(value, StandardCharsets.UTF_8).replace("+", "%20");
+    }
+}
+"#,
+    );
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::client;
+
+    #[test]
+    fn test_java_client_output() {
+        let code = client("java");
+        for expected in [
+            "public record User(\n        String id,\n        String nickname,\n        List<String> tags\n    ) {}",
+            r#"@JsonProperty("Admin") ADMIN,"#,
+            "public User getUser(String id, Boolean verbose) {",
+            r#"query.put("verbose", verbose);"#,
+            r#"return send("GET", path, query, null, true, new TypeReference<User>() {});"#,
+            "public User createUser(User body) {",
+            "public void deleteUser(String id) {",
+        ] {
+            assert!(code.contains(expected), "fehlt: {}\n{}", expected, code);
+        }
+    }
+}
@@ -0,0 +1,188 @@
+// JavaScript Client
+// JSDoc-Typedefs aus `components/schemas`, CommonJS-Modul über `fetch`
+
+use super::typescript::{path_template, property_name, request_init, ts_type};
+use super::{camel_case, doc_line, ClientModel, ModelKind, TypeRef};
+
+/// JSDoc versteht die TypeScript-Typsyntax, nur `unknown` heißt dort `*`
+fn jsdoc_type(ty: &TypeRef) -> String {
+    ts_type(ty).replace("unknown", "*")
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n'use strict';\n\n",
+        model.title, model.version
+    ));
+
+    for m in &model.models {
+        code.push_str("/**\n");
+        if let Some(doc) = doc_line(&m.description) {
+            code.push_str(&format!(" * {}\n", doc));
+        }
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                code.push_str(&format!(" * @typedef {{Object}} {}\n", m.name));
+                for field in fields {
+                    let name = if field.required {
+                        property_name(&field.name)
+                    } else {
+                        format!("[{}]", property_name(&field.name))
+                    };
+                    let doc = doc_line(&field.description)
+                        .map(|d| format!(" - {}", d))
+                        .unwrap_or_default();
+                    code.push_str(&format!(
+                        " * @property {{{}}} {}{}\n",
+                        jsdoc_type(&field.ty),
+                        name,
+                        doc
+                    ));
+                }
+            }
+            ModelKind::Enum(values) => {
+                code.push_str(&format!(
+                    " * @typedef {{{}}} {}\n",
+                    jsdoc_type(&TypeRef::Literals(values.clone())),
+                    m.name
+                ));
+            }
+            ModelKind::Alias(ty) => {
+                code.push_str(&format!(" * @typedef {{{}}} {}\n", jsdoc_type(ty), m.name));
+            }
+        }
+        code.push_str(" */\n\n");
+    }
+
+    code.push_str(
+        r#"/**
+ * @typedef {Object} ValidationIssue
+ * @property {string} field
+ * @property {string} message
+ */
+
+/** Fehlerantwort der API (Status >= 400) */
+class ApiError extends Error {
+  /**
+   * @param {number} status
+   * @param {string} message
+   * @param {ValidationIssue[]} [errors]
+   */
+  constructor(status, message, errors = []) {
+    super(message);
+    this.name = 'ApiError';
+    this.status = status;
+    this.errors = errors;
+  }
+}
+
+/**
+ * @typedef {string | (() => (string | undefined | Promise<string | undefined>))} TokenProvider
+ */
+
+class ApiClient {
+  /**
+   * @param {{ baseUrl: string, token?: TokenProvider, headers?: Record<string, string>, fetch?: typeof fetch }} options
+   */
+  constructor(options) {
+    this.options = options;
+    this.baseUrl = options.baseUrl.replace(/\/+$/, '');
+  }
+
+  /** @param {TokenProvider | undefined} token */
+  setToken(token) {
+    this.options.token = token;
+  }
+"#,
+    );
+
+    for op in &model.operations {
+        let signature = op.signature();
+        code.push_str("\n  /**\n");
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("   * {}\n", doc));
+        }
+        for (param, optional) in &signature {
+            let name = camel_case(&param.name);
+            code.push_str(&format!(
+                "   * @param {{{}}} {}\n",
+                jsdoc_type(&param.ty),
+                if *optional {
+                    format!("[{}]", name)
+                } else {
+                    name
+                }
+            ));
+        }
+        code.push_str(&format!(
+            "   * @returns {{Promise<{}>}}\n   */\n",
+            op.response
+                .as_ref()
+                .map(jsdoc_type)
+                .unwrap_or_else(|| "void".to_string())
+        ));
+        let params = signature
+            .iter()
+            .map(|(param, _)| camel_case(&param.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        code.push_str(&format!(
+            "  async {}({}) {{\n    return this.request('{}', {}{});\n  }}\n",
+            op.name,
+            params,
+            op.method,
+            path_template(op),
+            request_init(op)
+        ));
+    }
+
+    code.push_str(
+        r#"
+  async request(method, path, init = {}) {
+    const search = new URLSearchParams();
+    for (const [key, value] of Object.entries(init.query || {})) {
+      if (value === undefined || value === null) continue;
+      for (const item of Array.isArray(value) ? value : [value]) {
+        search.append(key, String(item));
+      }
+    }
+    const query = search.toString();
+
+    const headers = Object.assign({ Accept: 'application/json' }, this.options.headers);
+    if (init.body !== undefined) {
+      headers['Content-Type'] = 'application/json';
+    }
+    if (init.auth) {
+      const token = typeof this.options.token === 'function' ? await this.options.token() : this.options.token;
+      if (token) {
+        headers['Authorization'] = `Bearer ${token}`;
+      }
+    }
+
+    const fetchImpl = this.options.fetch || fetch;
+    const response = await fetchImpl(this.baseUrl + path + (query ? `?${query}` : ''), {
+      method,
+      headers,
+      body: init.body === undefined ? undefined : JSON.stringify(init.body),
+    });
+    const text = await response.text();
+    let data;
+    try {
+      data = text ? JSON.parse(text) : undefined;
+    } catch (e) {
+      data = text;
+    }
+    if (!response.ok) {
+      const message = data && typeof data.error === 'string' ? data.error : response.statusText;
+      throw new ApiError(response.status, message, data && Array.isArray(data.errors) ? data.errors : []);
+    }
+    return data;
+  }
+}
+
+module.exports = { ApiClient, ApiError };
+"#,
+    );
+    code
+}
@@ -0,0 +1,658 @@
+// API Client Generator aus OpenAPI
+// Liest ein OpenAPI-Dokument (JSON oder YAML) in ein sprachunabhängiges Modell
+// und erzeugt daraus typisierte Clients für alle Ziel-Sprachen
+
+mod csharp;
+mod go;
+mod java;
+mod javascript;
+mod php;
+mod python;
+mod rust;
+mod typescript;
+
+use crate::codegen::openapi::{OpenAPISpec, Schema, SchemaType};
+use crate::codegen::traits::TargetLanguage;
+use std::fs;
+use std::path::Path;
+
+pub struct ClientGenerator;
+
+/// Typ eines Feldes, Parameters oder Bodys
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array(Box<TypeRef>),
+    Map(Box<TypeRef>),
+    /// Komponente aus `components/schemas`
+    Named(String),
+    Nullable(Box<TypeRef>),
+    /// Inline-Objekt (z.B. Daten-Variante eines Enums)
+    Object(Vec<(String, TypeRef)>),
+    /// Inline-String-Enum
+    Literals(Vec<String>),
+    /// `oneOf` ohne `null`
+    Union(Vec<TypeRef>),
+    Any,
+}
+
+impl TypeRef {
+    pub fn from_schema(schema: &Schema) -> Self {
+        if let Some(component) = schema.component() {
+            return TypeRef::Named(component.to_string());
+        }
+        if schema.schema_type.is_none() && !schema.one_of.is_empty() {
+            let variants: Vec<&Schema> = schema
+                .one_of
+                .iter()
+                .filter(|s| s.schema_type != Some(SchemaType::Single("null".to_string())))
+                .collect();
+            return match variants.as_slice() {
+                [single] if variants.len() < schema.one_of.len() => {
+                    TypeRef::Nullable(Box::new(TypeRef::from_schema(single)))
+                }
+                _ => {
+                    let union =
+                        TypeRef::Union(variants.iter().map(|s| TypeRef::from_schema(s)).collect());
+                    if variants.len() < schema.one_of.len() {
+                        TypeRef::Nullable(Box::new(union))
+                    } else {
+                        union
+                    }
+                }
+            };
+        }
+        let base = match schema.primary_type() {
+            Some("string") if !schema.enum_values.is_empty() => TypeRef::Literals(
+                schema
+                    .enum_values
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect(),
+            ),
+            Some("string") => TypeRef::String,
+            Some("number") => TypeRef::Number,
+            Some("integer") => TypeRef::Integer,
+            Some("boolean") => TypeRef::Boolean,
+            Some("array") => TypeRef::Array(Box::new(
+                schema
+                    .items
+                    .as_deref()
+                    .map(TypeRef::from_schema)
+                    .unwrap_or(TypeRef::Any),
+            )),
+            Some("object") if !schema.properties.is_empty() => TypeRef::Object(
+                schema
+                    .properties
+                    .iter()
+                    .map(|(name, s)| (name.clone(), TypeRef::from_schema(s)))
+                    .collect(),
+            ),
+            Some("object") => match &schema.additional_properties {
+                Some(value) => TypeRef::Map(Box::new(TypeRef::from_schema(value))),
+                None => TypeRef::Map(Box::new(TypeRef::Any)),
+            },
+            _ => TypeRef::Any,
+        };
+        if schema.is_nullable() && base != TypeRef::Any {
+            TypeRef::Nullable(Box::new(base))
+        } else {
+            base
+        }
+    }
+
+    /// Typ ohne `Nullable`-Hülle
+    pub fn inner(&self) -> &TypeRef {
+        match self {
+            TypeRef::Nullable(inner) => inner,
+            other => other,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelField {
+    pub name: String,
+    pub ty: TypeRef,
+    pub required: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ModelKind {
+    Object(Vec<ModelField>),
+    /// String-Enum (`type: string` + `enum`)
+    Enum(Vec<String>),
+    /// Alles andere (`oneOf`, Alias auf einen anderen Typ)
+    Alias(TypeRef),
+}
+
+#[derive(Debug, Clone)]
+pub struct Model {
+    pub name: String,
+    pub description: Option<String>,
+    pub kind: ModelKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientParam {
+    /// Name wie im Dokument; die Emitter passen ihn an die Namenskonvention an
+    pub name: String,
+    pub ty: TypeRef,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientOperation {
+    pub name: String,
+    /// HTTP-Methode in Großbuchstaben
+    pub method: String,
+    /// Pfad mit `{param}`-Platzhaltern
+    pub path: String,
+    pub summary: Option<String>,
+    pub path_params: Vec<ClientParam>,
+    /// Pflicht-Parameter zuerst
+    pub query_params: Vec<ClientParam>,
+    pub body: Option<ClientParam>,
+    /// `None` bei Responses ohne Body (204)
+    pub response: Option<TypeRef>,
+    /// Operation verlangt einen Token (`security`)
+    pub secured: bool,
+}
+
+impl ClientOperation {
+    /// Alle Parameter in Aufruf-Reihenfolge: Pfad, Body, Query
+    pub fn params(&self) -> Vec<&ClientParam> {
+        self.path_params
+            .iter()
+            .chain(self.body.iter())
+            .chain(self.query_params.iter())
+            .collect()
+    }
+
+    /// Parameter mit Angabe, ob sie in der Signatur weggelassen werden dürfen
+    /// (nur optionale Parameter, auf die kein Pflicht-Parameter mehr folgt)
+    pub fn signature(&self) -> Vec<(&ClientParam, bool)> {
+        let params = self.params();
+        let mut optional_from = params.len();
+        while optional_from > 0 && !params[optional_from - 1].required {
+            optional_from -= 1;
+        }
+        params
+            .into_iter()
+            .enumerate()
+            .map(|(index, param)| (param, index >= optional_from))
+            .collect()
+    }
+
+    /// Pfad-Segmente: `Ok(text)` oder `Err(param)`
+    pub fn path_segments(&self) -> Vec<Result<String, String>> {
+        let mut segments = Vec::new();
+        let mut rest = self.path.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                break;
+            };
+            if start > 0 {
+                segments.push(Ok(rest[..start].to_string()));
+            }
+            segments.push(Err(rest[start + 1..start + end].to_string()));
+            rest = &rest[start + end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Ok(rest.to_string()));
+        }
+        segments
+    }
+}
+
+/// Sprachunabhängiges Client-Modell eines OpenAPI-Dokuments
+#[derive(Debug, Clone)]
+pub struct ClientModel {
+    pub title: String,
+    pub version: String,
+    pub models: Vec<Model>,
+    pub operations: Vec<ClientOperation>,
+}
+
+impl ClientModel {
+    pub fn from_spec(spec: &OpenAPISpec) -> Self {
+        let models = spec
+            .components
+            .schemas
+            .iter()
+            .map(|(name, schema)| model(name, schema))
+            .collect();
+
+        let mut operations: Vec<ClientOperation> = Vec::new();
+        for (path, item) in &spec.paths {
+            for (method, op) in item.operations() {
+                let mut name = if op.operation_id.is_empty() {
+                    fallback_name(method, path)
+                } else {
+                    camel_case(&op.operation_id)
+                };
+                if operations.iter().any(|o| o.name == name) {
+                    name = format!("{}{}", name, pascal_case(method));
+                }
+
+                let param = |p: &crate::codegen::openapi::Parameter| ClientParam {
+                    name: p.name.clone(),
+                    ty: TypeRef::from_schema(&p.schema),
+                    required: p.required,
+                };
+                let path_params = op
+                    .parameters
+                    .iter()
+                    .filter(|p| p.location == "path")
+                    .map(param)
+                    .collect();
+                let mut query_params: Vec<ClientParam> = op
+                    .parameters
+                    .iter()
+                    .filter(|p| p.location == "query")
+                    .map(param)
+                    .collect();
+                query_params.sort_by_key(|p| !p.required);
+
+                let body = op.request_body.as_ref().and_then(|body| {
+                    body.content
+                        .get("application/json")
+                        .map(|media| ClientParam {
+                            name: "body".to_string(),
+                            ty: TypeRef::from_schema(&media.schema),
+                            required: body.required,
+                        })
+                });
+                let response = op
+                    .responses
+                    .iter()
+                    .filter(|(status, _)| status.starts_with('2'))
+                    .find_map(|(_, response)| {
+                        response
+                            .content
+                            .as_ref()
+                            .and_then(|c| c.get("application/json"))
+                            .map(|media| TypeRef::from_schema(&media.schema))
+                    });
+
+                operations.push(ClientOperation {
+                    name,
+                    method: method.to_uppercase(),
+                    path: path.clone(),
+                    summary: op.summary.clone(),
+                    path_params,
+                    query_params,
+                    body,
+                    response,
+                    secured: !op.security.is_empty(),
+                });
+            }
+        }
+
+        ClientModel {
+            title: spec.info.title.clone(),
+            version: spec.info.version.clone(),
+            models,
+            operations,
+        }
+    }
+}
+
+impl ClientModel {
+    /// Ersetzt Referenzen auf String-Enums durch ihre Werte und optional
+    /// Referenzen auf Aliase durch den Ziel-Typ (für Sprachen ohne passende Typen)
+    pub fn inlined(&self, aliases: bool) -> ClientModel {
+        let mut model = self.clone();
+        let resolve = |ty: &TypeRef| self.resolve(ty, aliases, 0);
+        for m in &mut model.models {
+            match &mut m.kind {
+                ModelKind::Object(fields) => {
+                    for field in fields {
+                        field.ty = resolve(&field.ty);
+                    }
+                }
+                ModelKind::Alias(ty) => *ty = resolve(ty),
+                ModelKind::Enum(_) => {}
+            }
+        }
+        for op in &mut model.operations {
+            for param in op
+                .path_params
+                .iter_mut()
+                .chain(op.query_params.iter_mut())
+                .chain(op.body.iter_mut())
+            {
+                param.ty = resolve(&param.ty);
+            }
+            op.response = op.response.as_ref().map(resolve);
+        }
+        model
+    }
+
+    fn resolve(&self, ty: &TypeRef, aliases: bool, depth: usize) -> TypeRef {
+        let resolve = |ty: &TypeRef| self.resolve(ty, aliases, depth + 1);
+        match ty {
+            TypeRef::Named(name) if depth < 16 => {
+                match self
+                    .models
+                    .iter()
+                    .find(|m| &m.name == name)
+                    .map(|m| &m.kind)
+                {
+                    Some(ModelKind::Enum(values)) => TypeRef::Literals(values.clone()),
+                    Some(ModelKind::Alias(target)) if aliases => resolve(target),
+                    _ => ty.clone(),
+                }
+            }
+            TypeRef::Array(item) => TypeRef::Array(Box::new(resolve(item))),
+            TypeRef::Map(value) => TypeRef::Map(Box::new(resolve(value))),
+            TypeRef::Nullable(inner) => TypeRef::Nullable(Box::new(resolve(inner))),
+            TypeRef::Object(fields) => TypeRef::Object(
+                fields
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), resolve(ty)))
+                    .collect(),
+            ),
+            TypeRef::Union(variants) => TypeRef::Union(variants.iter().map(resolve).collect()),
+            other => other.clone(),
+        }
+    }
+}
+
+fn model(name: &str, schema: &Schema) -> Model {
+    let description = schema.description.clone();
+    let kind = match schema.primary_type() {
+        Some("object") if schema.component().is_none() && !schema.properties.is_empty() => {
+            ModelKind::Object(
+                schema
+                    .properties
+                    .iter()
+                    .map(|(field, s)| ModelField {
+                        name: field.clone(),
+                        ty: TypeRef::from_schema(s),
+                        required: schema.required.contains(field),
+                        description: s.description.clone(),
+                    })
+                    .collect(),
+            )
+        }
+        Some("string") if !schema.enum_values.is_empty() => ModelKind::Enum(
+            schema
+                .enum_values
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+        ),
+        _ => ModelKind::Alias(TypeRef::from_schema(schema)),
+    };
+    Model {
+        name: name.to_string(),
+        description,
+        kind,
+    }
+}
+
+/// Name einer Operation ohne `operationId` (`GET /users/{id}` → `getUsersId`)
+pub fn fallback_name(method: &str, path: &str) -> String {
+    let words: Vec<&str> = path
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect();
+    camel_case(&format!("{} {}", method, words.join(" ")))
+}
+
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+pub fn pascal_case(name: &str) -> String {
+    let name: String = words(name).iter().map(|w| capitalize(w)).collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+pub fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let mut chars = pascal.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().collect::<String>() + chars.as_str(),
+        None => pascal,
+    }
+}
+
+pub fn snake_case(name: &str) -> String {
+    let name = words(name)
+        .iter()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// Erste Zeile einer Beschreibung für Doc-Kommentare
+pub fn doc_line(text: &Option<String>) -> Option<String> {
+    text.as_deref()
+        .and_then(|t| t.lines().next())
+        .map(|line| line.trim().replace("*/", "* /"))
+        .filter(|line| !line.is_empty())
+}
+
+impl ClientGenerator {
+    pub fn new() -> Self {
+        ClientGenerator
+    }
+
+    pub fn generate_from_openapi(
+        &self,
+        openapi_path: &Path,
+        language: &str,
+    ) -> Result<String, String> {
+        // Lese OpenAPI Datei
+        let content = fs::read_to_string(openapi_path)
+            .map_err(|e| format!("Fehler beim Lesen der OpenAPI Datei: {}", e))?;
+
+        let yaml = matches!(
+            openapi_path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        );
+        let spec: OpenAPISpec = if yaml {
+            serde_yaml::from_str(&content)
+                .map_err(|e| format!("Fehler beim Parsen der OpenAPI YAML: {}", e))?
+        } else {
+            serde_json::from_str(&content)
+                .map_err(|e| format!("Fehler beim Parsen der OpenAPI JSON: {}", e))?
+        };
+
+        self.generate_from_spec(&spec, language)
+    }
+
+    pub fn generate_from_spec(&self, spec: &OpenAPISpec, language: &str) -> Result<String, String> {
+        let target: TargetLanguage = language.parse().map_err(|_| unsupported(language))?;
+        let model = ClientModel::from_spec(spec);
+        match target {
+            TargetLanguage::TypeScript => Ok(typescript::generate(&model)),
+            TargetLanguage::JavaScript => Ok(javascript::generate(&model)),
+            TargetLanguage::Rust => Ok(rust::generate(&model)),
+            TargetLanguage::Python => Ok(python::generate(&model)),
+            TargetLanguage::Go => Ok(go::generate(&model)),
+            TargetLanguage::Java => Ok(java::generate(&model)),
+            TargetLanguage::CSharp => Ok(csharp::generate(&model.inlined(false))),
+            TargetLanguage::Php => Ok(php::generate(&model, &model.inlined(true))),
+            TargetLanguage::Wasm => Err(unsupported(language)),
+        }
+    }
+}
+
+fn unsupported(language: &str) -> String {
+    format!(
+        "Unbekannte Sprache: {}. Unterstützt: typescript, javascript, rust, python, go, java, csharp, php",
+        language
+    )
+}
+
+/// Standard-Dateiname des generierten Clients je Sprache
+pub fn default_file_name(language: &str) -> Option<&'static str> {
+    match language.parse::<TargetLanguage>().ok()? {
+        TargetLanguage::TypeScript => Some("api-client.ts"),
+        TargetLanguage::JavaScript => Some("api-client.js"),
+        TargetLanguage::Rust => Some("api_client.rs"),
+        TargetLanguage::Python => Some("api_client.py"),
+        TargetLanguage::Go => Some("api_client.go"),
+        TargetLanguage::Java => Some("ApiClient.java"),
+        TargetLanguage::CSharp => Some("ApiClient.cs"),
+        TargetLanguage::Php => Some("ApiClient.php"),
+        TargetLanguage::Wasm => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::openapi::OpenAPIGenerator;
+    use crate::parser::parser::Parser;
+
+    fn model(source: &str) -> ClientModel {
+        let program = Parser::parse(source).expect("parse");
+        ClientModel::from_spec(&OpenAPIGenerator::new().build(&program))
+    }
+
+    /// Client für `API` in der angegebenen Sprache
+    pub(super) fn client(language: &str) -> String {
+        let program = Parser::parse(API).expect("parse");
+        let spec = OpenAPIGenerator::new().build(&program);
+        ClientGenerator::new()
+            .generate_from_spec(&spec, language)
+            .unwrap()
+    }
+
+    const API: &str = r#"
+        struct User {
+            id: string,
+            nickname: Option<string>,
+            tags: List<string>,
+        }
+
+        enum Membership {
+            Admin,
+            Member,
+        }
+
+        @Auth
+        @GET("/users/:id")
+        fn getUser(id: string, verbose: boolean): User {
+            return User { id: id, nickname: null, tags: [] };
+        }
+
+        @POST("/users")
+        fn createUser(user: User): User {
+            return user;
+        }
+
+        @DELETE("/users/:id")
+        fn deleteUser(id: string): void {
+        }
+    "#;
+
+    #[test]
+    fn test_operations_are_typed_from_the_spec() {
+        let model = model(API);
+        let get = model
+            .operations
+            .iter()
+            .find(|o| o.name == "getUser")
+            .unwrap();
+        assert_eq!(get.method, "GET");
+        assert!(get.secured);
+        assert_eq!(get.path_params[0].ty, TypeRef::String);
+        assert_eq!(get.query_params[0].ty, TypeRef::Boolean);
+        assert_eq!(get.response, Some(TypeRef::Named("User".to_string())));
+        assert_eq!(
+            get.path_segments(),
+            vec![Ok("/users/".to_string()), Err("id".to_string())]
+        );
+
+        let create = model
+            .operations
+            .iter()
+            .find(|o| o.name == "createUser")
+            .unwrap();
+        assert_eq!(
+            create.body.as_ref().unwrap().ty,
+            TypeRef::Named("User".to_string())
+        );
+
+        let delete = model
+            .operations
+            .iter()
+            .find(|o| o.name == "deleteUser")
+            .unwrap();
+        assert_eq!(delete.response, None);
+
+        let user = model.models.iter().find(|m| m.name == "User").unwrap();
+        let ModelKind::Object(fields) = &user.kind else {
+            panic!("User should be an object model");
+        };
+        assert_eq!(fields[1].ty, TypeRef::Nullable(Box::new(TypeRef::String)));
+        assert!(!fields[1].required);
+    }
+
+    #[test]
+    fn test_every_target_language_gets_a_client() {
+        let program = Parser::parse(API).expect("parse");
+        let spec = OpenAPIGenerator::new().build(&program);
+        let generator = ClientGenerator::new();
+        for language in ["ts", "js", "rust", "python", "go", "java", "csharp", "php"] {
+            let code = generator.generate_from_spec(&spec, language).unwrap();
+            assert!(
+                code.contains("getUser") || code.contains("get_user") || code.contains("GetUser"),
+                "{}",
+                language
+            );
+            assert!(
+                code.contains("Bearer"),
+                "{} client must inject the token",
+                language
+            );
+        }
+        assert!(generator.generate_from_spec(&spec, "wasm").is_err());
+    }
+}
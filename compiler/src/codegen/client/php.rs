@@ -0,0 +1,280 @@
+// PHP Client
+// Psalm-Array-Shapes aus `components/schemas`, Client über cURL
+
+use super::{camel_case, doc_line, ClientModel, ModelKind, TypeRef};
+
+fn php_doc_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String => "string".to_string(),
+        TypeRef::Number => "float".to_string(),
+        TypeRef::Integer => "int".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Array(item) => format!("list<{}>", php_doc_type(item)),
+        TypeRef::Map(value) => format!("array<string, {}>", php_doc_type(value)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => format!("{}|null", php_doc_type(inner)),
+        TypeRef::Object(_) | TypeRef::Any => "mixed".to_string(),
+        TypeRef::Literals(values) => values
+            .iter()
+            .map(|v| format!("'{}'", v.replace('\'', "\\'")))
+            .collect::<Vec<_>>()
+            .join("|"),
+        TypeRef::Union(variants) => variants
+            .iter()
+            .map(php_doc_type)
+            .collect::<Vec<_>>()
+            .join("|"),
+    }
+}
+
+/// Nativer Parametertyp (Modelle sind assoziative Arrays)
+fn php_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String | TypeRef::Literals(_) => "string".to_string(),
+        TypeRef::Number => "float".to_string(),
+        TypeRef::Integer => "int".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Nullable(inner) => match php_type(inner).as_str() {
+            "mixed" => "mixed".to_string(),
+            other => format!("?{}", other),
+        },
+        TypeRef::Array(_) | TypeRef::Map(_) | TypeRef::Named(_) => "array".to_string(),
+        TypeRef::Object(_) | TypeRef::Union(_) | TypeRef::Any => "mixed".to_string(),
+    }
+}
+
+/// Psalm-Array-Shape eines Modells
+fn shape(fields: &[super::ModelField]) -> String {
+    format!(
+        "array{{{}}}",
+        fields
+            .iter()
+            .map(|f| format!(
+                "{}{}: {}",
+                f.name,
+                if f.required { "" } else { "?" },
+                php_doc_type(&f.ty)
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// `resolved` ist das Modell mit aufgelösten Enums und Aliasen für native Typen
+pub fn generate(model: &ClientModel, resolved: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "<?php\n// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n\ndeclare(strict_types=1);\n\n",
+        model.title, model.version
+    ));
+
+    code.push_str(
+        r#"/**
+ * Fehlerantwort der API (Status >= 400)
+ */
+class ApiException extends RuntimeException
+{
+    /** @var list<array{field: string, message: string}> */
+    public array $errors;
+    public int $status;
+
+    /** @param list<array{field: string, message: string}> $errors */
+    public function __construct(int $status, string $message, array $errors = [])
+    {
+        parent::__construct("{$status}: {$message}", $status);
+        $this->status = $status;
+        $this->errors = $errors;
+    }
+}
+
+/**
+ * @psalm-type ValidationIssue = array{field: string, message: string}
+"#,
+    );
+    for m in &model.models {
+        let ty = match &m.kind {
+            ModelKind::Object(fields) => shape(fields),
+            ModelKind::Enum(values) => php_doc_type(&TypeRef::Literals(values.clone())),
+            ModelKind::Alias(ty) => php_doc_type(ty),
+        };
+        code.push_str(&format!(" * @psalm-type {} = {}\n", m.name, ty));
+    }
+    code.push_str(
+        r#" */
+class ApiClient
+{
+    private string $baseUrl;
+    private ?string $token = null;
+
+    public function __construct(string $baseUrl)
+    {
+        $this->baseUrl = rtrim($baseUrl, '/');
+    }
+
+    /** Bearer Token für geschützte Endpunkte */
+    public function withToken(?string $token): self
+    {
+        $this->token = $token;
+        return $this;
+    }
+"#,
+    );
+
+    for (op, native) in model.operations.iter().zip(&resolved.operations) {
+        let signature = op.signature();
+        code.push_str("\n    /**\n");
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("     * {}\n     *\n", doc));
+        }
+        for (param, optional) in &signature {
+            let mut ty = php_doc_type(&param.ty);
+            if *optional && !ty.ends_with("|null") {
+                ty.push_str("|null");
+            }
+            code.push_str(&format!(
+                "     * @param {} ${}\n",
+                ty,
+                camel_case(&param.name)
+            ));
+        }
+        code.push_str(&format!(
+            "     * @return {}\n     * @throws ApiException\n     */\n",
+            op.response
+                .as_ref()
+                .map(php_doc_type)
+                .unwrap_or_else(|| "void".to_string())
+        ));
+
+        let params = native
+            .signature()
+            .iter()
+            .map(|(param, optional)| {
+                let ty = php_type(&param.ty);
+                let name = camel_case(&param.name);
+                if *optional {
+                    let ty = if ty.starts_with('?') || ty == "mixed" {
+                        ty
+                    } else {
+                        format!("?{}", ty)
+                    };
+                    format!("{} ${} = null", ty, name)
+                } else {
+                    format!("{} ${}", ty, name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let returns = native
+            .response
+            .as_ref()
+            .map(php_type)
+            .unwrap_or_else(|| "void".to_string());
+        code.push_str(&format!(
+            "    public function {}({}): {}\n    {{\n",
+            op.name, params, returns
+        ));
+
+        let path = op
+            .path_segments()
+            .into_iter()
+            .map(|segment| match segment {
+                Ok(text) => format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'")),
+                Err(param) => format!("rawurlencode((string) ${})", camel_case(&param)),
+            })
+            .collect::<Vec<_>>()
+            .join(" . ");
+        let query = op
+            .query_params
+            .iter()
+            .map(|p| format!("'{}' => ${}", p.name, camel_case(&p.name)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let body = op
+            .body
+            .as_ref()
+            .map(|b| format!("${}", camel_case(&b.name)))
+            .unwrap_or_else(|| "null".to_string());
+        code.push_str(&format!(
+            "        {}$this->request('{}', {}, [{}], {}, {});\n    }}\n",
+            if op.response.is_some() { "return " } else { "" },
+            op.method,
+            if path.is_empty() {
+                "''".to_string()
+            } else {
+                path
+            },
+            query,
+            body,
+            op.secured
+        ));
+    }
+
+    code.push_str(
+        r#"
+    /**
+     * @param array<string, mixed> $query
+     * @return mixed
+     */
+    private function request(string $method, string $path, array $query, mixed $body, bool $auth): mixed
+    {
+        $query = array_map(
+            fn ($value) => is_bool($value) ? ($value ? 'true' : 'false') : (is_array($value) ? json_encode($value) : $value),
+            array_filter($query, fn ($value) => $value !== null)
+        );
+        $url = $this->baseUrl . $path . ($query ? '?' . http_build_query($query) : '');
+
+        $headers = ['Accept: application/json'];
+        $curl = curl_init($url);
+        curl_setopt($curl, CURLOPT_CUSTOMREQUEST, $method);
+        curl_setopt($curl, CURLOPT_RETURNTRANSFER, true);
+        if ($body !== null) {
+            $headers[] = 'Content-Type: application/json';
+            curl_setopt($curl, CURLOPT_POSTFIELDS, json_encode($body));
+        }
+        if ($auth && $this->token !== null) {
+            $headers[] = 'Authorization: Bearer ' . $this->token;
+        }
+        curl_setopt($curl, CURLOPT_HTTPHEADER, $headers);
+
+        $response = curl_exec($curl);
+        if ($response === false) {
+            $message = curl_error($curl);
+            curl_close($curl);
+            throw new ApiException(0, $message);
+        }
+        $status = (int) curl_getinfo($curl, CURLINFO_RESPONSE_CODE);
+        curl_close($curl);
+
+        $data = $response === '' ? null : json_decode($response, true);
+        if ($status >= 400) {
+            $message = is_array($data) && isset($data['error']) ? (string) $data['error'] : "HTTP {$status}";
+            $errors = is_array($data) && isset($data['errors']) && is_array($data['errors']) ? $data['errors'] : [];
+            throw new ApiException($status, $message, $errors);
+        }
+        return $data;
+    }
+}
+"#,
+    );
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::client;
+
+    #[test]
+    fn test_php_client_output() {
+        let code = client("php");
+        for expected in [
+            "@psalm-type Membership = 'Admin'|'Member'",
+            "@psalm-type User = array{id: string, nickname?: string|null, tags: list<string>}",
+            "public function getUser(string $id, bool $verbose): array",
+            "return $this->request('GET', '/users/' . rawurlencode((string) $id), ['verbose' => $verbose], null, true);",
+            "public function createUser(array $body): array",
+            "public function deleteUser(string $id): void",
+        ] {
+            assert!(code.contains(expected), "fehlt: {}\n{}", expected, code);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+// Python Client
+// TypedDicts aus `components/schemas`, Client über `requests`
+
+use super::{doc_line, snake_case, ClientModel, ModelKind, TypeRef};
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+fn identifier(name: &str) -> String {
+    let name = snake_case(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn py_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String => "str".to_string(),
+        TypeRef::Number => "float".to_string(),
+        TypeRef::Integer => "int".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Array(item) => format!("List[{}]", py_type(item)),
+        TypeRef::Map(value) => format!("Dict[str, {}]", py_type(value)),
+        // Vorwärtsreferenzen als String, Modelle dürfen sich gegenseitig referenzieren
+        TypeRef::Named(name) => format!("\"{}\"", name),
+        TypeRef::Nullable(inner) => format!("Optional[{}]", py_type(inner)),
+        TypeRef::Object(_) | TypeRef::Any => "Any".to_string(),
+        TypeRef::Literals(values) => format!(
+            "Literal[{}]",
+            values
+                .iter()
+                .map(|v| format!("{:?}", v))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        TypeRef::Union(variants) => format!(
+            "Union[{}]",
+            variants.iter().map(py_type).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "# Generierter API Client für {} {}\n# Nicht manuell bearbeiten\n\n",
+        model.title, model.version
+    ));
+    code.push_str(
+        r#"from typing import Any, Callable, Dict, List, Literal, Optional, TypedDict, Union
+from urllib.parse import quote
+
+import requests
+"#,
+    );
+
+    for m in &model.models {
+        code.push_str("\n\n");
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                let valid_names = fields.iter().all(|f| {
+                    f.name
+                        .chars()
+                        .all(|c| c == '_' || c.is_ascii_alphanumeric())
+                        && !KEYWORDS.contains(&f.name.as_str())
+                });
+                if valid_names {
+                    code.push_str(&format!("class {}(TypedDict, total=False):\n", m.name));
+                    if let Some(doc) = doc_line(&m.description) {
+                        code.push_str(&format!("    \"\"\"{}\"\"\"\n\n", doc.replace('"', "'")));
+                    }
+                    for field in fields {
+                        code.push_str(&format!("    {}: {}\n", field.name, py_type(&field.ty)));
+                    }
+                } else {
+                    // Funktionale Syntax für Feldnamen, die keine Python-Bezeichner sind
+                    let entries = fields
+                        .iter()
+                        .map(|f| format!("{:?}: {}", f.name, py_type(&f.ty)))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    code.push_str(&format!(
+                        "{} = TypedDict({:?}, {{{}}}, total=False)\n",
+                        m.name, m.name, entries
+                    ));
+                }
+            }
+            ModelKind::Enum(values) => {
+                code.push_str(&format!(
+                    "{} = {}\n",
+                    m.name,
+                    py_type(&TypeRef::Literals(values.clone()))
+                ));
+            }
+            ModelKind::Alias(ty) => {
+                // String-Vorwärtsreferenzen sind nur innerhalb von Generics erlaubt
+                let alias = match ty {
+                    TypeRef::Named(name) => name.clone(),
+                    other => py_type(other),
+                };
+                code.push_str(&format!("{} = {}\n", m.name, alias));
+            }
+        }
+    }
+
+    code.push_str(
+        r#"
+
+class ValidationIssue(TypedDict):
+    field: str
+    message: str
+
+
+class ApiError(Exception):
+    """Fehlerantwort der API (Status >= 400)"""
+
+    def __init__(self, status: int, message: str, errors: Optional[List[ValidationIssue]] = None):
+        super().__init__(f"{status}: {message}")
+        self.status = status
+        self.message = message
+        self.errors = errors or []
+
+
+TokenProvider = Union[str, Callable[[], Optional[str]]]
+
+
+class ApiClient:
+    def __init__(
+        self,
+        base_url: str,
+        token: Optional[TokenProvider] = None,
+        session: Optional[requests.Session] = None,
+        timeout: float = 30.0,
+    ):
+        self.base_url = base_url.rstrip("/")
+        self.token = token
+        self.session = session or requests.Session()
+        self.timeout = timeout
+"#,
+    );
+
+    for op in &model.operations {
+        let signature = op.signature();
+        let mut params = vec!["self".to_string()];
+        for (param, optional) in &signature {
+            let ty = py_type(&param.ty);
+            params.push(if *optional {
+                let ty = if matches!(param.ty, TypeRef::Nullable(_)) {
+                    ty
+                } else {
+                    format!("Optional[{}]", ty)
+                };
+                format!("{}: {} = None", identifier(&param.name), ty)
+            } else {
+                format!("{}: {}", identifier(&param.name), ty)
+            });
+        }
+        let response = op
+            .response
+            .as_ref()
+            .map(py_type)
+            .unwrap_or_else(|| "None".to_string());
+
+        code.push_str(&format!(
+            "\n    def {}({}) -> {}:\n",
+            snake_case(&op.name),
+            params.join(", "),
+            response
+        ));
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("        \"\"\"{}\"\"\"\n", doc.replace('"', "'")));
+        }
+
+        let templated = !op.path_params.is_empty();
+        let mut path = String::from(if templated { "f\"" } else { "\"" });
+        for segment in op.path_segments() {
+            match segment {
+                Ok(text) if templated => path.push_str(&text.replace('{', "{{").replace('}', "}}")),
+                Ok(text) => path.push_str(&text),
+                Err(param) => {
+                    path.push_str(&format!("{{quote(str({}), safe='')}}", identifier(&param)))
+                }
+            }
+        }
+        path.push('"');
+
+        let mut args = vec![format!("\"{}\"", op.method), path];
+        if !op.query_params.is_empty() {
+            let query = op
+                .query_params
+                .iter()
+                .map(|p| format!("{:?}: {}", p.name, identifier(&p.name)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            args.push(format!("query={{{}}}", query));
+        }
+        if let Some(body) = &op.body {
+            args.push(format!("body={}", identifier(&body.name)));
+        }
+        if op.secured {
+            args.push("auth=True".to_string());
+        }
+        code.push_str(&format!(
+            "        {}self._request({})\n",
+            if op.response.is_some() { "return " } else { "" },
+            args.join(", ")
+        ));
+    }
+
+    code.push_str(
+        r#"
+    def _request(self, method: str, path: str, query: Optional[Dict[str, Any]] = None, body: Any = None, auth: bool = False) -> Any:
+        params = {}
+        for key, value in (query or {}).items():
+            if value is None:
+                continue
+            if isinstance(value, bool):
+                value = "true" if value else "false"
+            params[key] = value
+
+        headers = {"Accept": "application/json"}
+        if auth:
+            token = self.token() if callable(self.token) else self.token
+            if token:
+                headers["Authorization"] = f"Bearer {token}"
+
+        response = self.session.request(
+            method,
+            self.base_url + path,
+            params=params,
+            json=body,
+            headers=headers,
+            timeout=self.timeout,
+        )
+        try:
+            data = response.json() if response.content else None
+        except ValueError:
+            data = response.text
+        if response.status_code >= 400:
+            message = data.get("error") if isinstance(data, dict) else None
+            errors = data.get("errors") if isinstance(data, dict) else None
+            raise ApiError(response.status_code, message or response.reason, errors)
+        return data
+"#,
+    );
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::client;
+
+    #[test]
+    fn test_python_client_output() {
+        let code = client("python");
+        for expected in [
+            r#"Membership = Literal["Admin", "Member"]"#,
+            "    nickname: Optional[str]\n    tags: List[str]",
+            r#"def get_user(self, id: str, verbose: bool) -> "User":"#,
+            r#"return self._request("GET", f"/users/{quote(str(id), safe='')}", query={"verbose": verbose}, auth=True)"#,
+            r#"def create_user(self, body: "User") -> "User":"#,
+            "def delete_user(self, id: str) -> None:",
+            "raise ApiError(response.status_code, message or response.reason, errors)",
+        ] {
+            assert!(code.contains(expected), "fehlt: {}\n{}", expected, code);
+        }
+    }
+}
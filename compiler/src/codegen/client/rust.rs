@@ -0,0 +1,357 @@
+// Rust Client
+// Serde-Structs aus `components/schemas`, asynchroner Client über `reqwest`
+
+use super::{doc_line, pascal_case, snake_case, ClientModel, ModelKind, TypeRef};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "yield",
+];
+
+fn identifier(name: &str) -> String {
+    let name = snake_case(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+fn rust_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String | TypeRef::Literals(_) => "String".to_string(),
+        TypeRef::Number => "f64".to_string(),
+        TypeRef::Integer => "i64".to_string(),
+        TypeRef::Boolean => "bool".to_string(),
+        TypeRef::Array(item) => format!("Vec<{}>", rust_type(item)),
+        TypeRef::Map(value) => format!("HashMap<String, {}>", rust_type(value)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => format!("Option<{}>", rust_type(inner)),
+        TypeRef::Object(_) | TypeRef::Union(_) | TypeRef::Any => "serde_json::Value".to_string(),
+    }
+}
+
+/// Typ eines Parameters, optionale Parameter werden zu `Option<T>`
+fn param_type(ty: &TypeRef, required: bool) -> String {
+    if required || matches!(ty, TypeRef::Nullable(_)) {
+        rust_type(ty)
+    } else {
+        format!("Option<{}>", rust_type(ty))
+    }
+}
+
+/// Parameter als `&T` für Strings, Vecs und Modelle
+fn borrowed(ty: &str) -> String {
+    match ty {
+        "String" => "&str".to_string(),
+        "bool" | "i64" | "f64" => ty.to_string(),
+        _ if ty.starts_with("Vec<") => format!("&[{}]", &ty[4..ty.len() - 1]),
+        _ => format!("&{}", ty),
+    }
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n\n",
+        model.title, model.version
+    ));
+    code.push_str(
+        r#"#![allow(dead_code, unused_imports)]
+
+use reqwest::{Client, Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+"#,
+    );
+
+    for m in &model.models {
+        code.push('\n');
+        if let Some(doc) = doc_line(&m.description) {
+            code.push_str(&format!("/// {}\n", doc));
+        }
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                code.push_str(&format!(
+                    "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {} {{\n",
+                    m.name
+                ));
+                for field in fields {
+                    if let Some(doc) = doc_line(&field.description) {
+                        code.push_str(&format!("    /// {}\n", doc));
+                    }
+                    let name = identifier(&field.name);
+                    if name.trim_start_matches("r#") != field.name {
+                        code.push_str(&format!("    #[serde(rename = {:?})]\n", field.name));
+                    }
+                    let ty = param_type(&field.ty, field.required);
+                    if ty.starts_with("Option<") {
+                        code.push_str(
+                            "    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n",
+                        );
+                    }
+                    code.push_str(&format!("    pub {}: {},\n", name, ty));
+                }
+                code.push_str("}\n");
+            }
+            ModelKind::Enum(values) => {
+                code.push_str(&format!(
+                    "#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\npub enum {} {{\n",
+                    m.name
+                ));
+                for value in values {
+                    let variant = pascal_case(value);
+                    if &variant != value {
+                        code.push_str(&format!("    #[serde(rename = {:?})]\n", value));
+                    }
+                    code.push_str(&format!("    {},\n", variant));
+                }
+                code.push_str("}\n");
+            }
+            ModelKind::Alias(ty) => {
+                code.push_str(&format!("pub type {} = {};\n", m.name, rust_type(ty)));
+            }
+        }
+    }
+
+    code.push_str(
+        r#"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: Option<String>,
+    #[serde(default)]
+    errors: Vec<ValidationIssue>,
+}
+
+/// Fehler eines API-Aufrufs
+#[derive(Debug)]
+pub enum ApiError {
+    /// Fehlerantwort der API (Status >= 400)
+    Status {
+        status: u16,
+        message: String,
+        errors: Vec<ValidationIssue>,
+    },
+    /// Verbindungs- oder Dekodierungsfehler
+    Transport(reqwest::Error),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Status { status, message, .. } => write!(f, "{}: {}", status, message),
+            ApiError::Transport(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        ApiError::Transport(e)
+    }
+}
+
+pub struct ApiClient {
+    base_url: String,
+    token: Option<String>,
+    http: Client,
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(base_url, Client::new())
+    }
+
+    pub fn with_client(base_url: impl Into<String>, http: Client) -> Self {
+        let base_url: String = base_url.into();
+        ApiClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: None,
+            http,
+        }
+    }
+
+    /// Bearer Token für geschützte Endpunkte
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token;
+    }
+"#,
+    );
+
+    for op in &model.operations {
+        let mut params = vec!["&self".to_string()];
+        for param in op.params() {
+            params.push(format!(
+                "{}: {}",
+                identifier(&param.name),
+                borrowed(&param_type(&param.ty, param.required))
+            ));
+        }
+        let response = op.response.as_ref().map(rust_type);
+
+        code.push('\n');
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("    /// {}\n", doc));
+        }
+        code.push_str(&format!(
+            "    pub async fn {}({}) -> Result<{}, ApiError> {{\n",
+            snake_case(&op.name),
+            params.join(", "),
+            response.clone().unwrap_or_else(|| "()".to_string())
+        ));
+
+        let mut path = String::new();
+        let mut args = Vec::new();
+        for segment in op.path_segments() {
+            match segment {
+                Ok(text) => path.push_str(&text.replace('{', "{{").replace('}', "}}")),
+                Err(param) => {
+                    path.push_str("{}");
+                    args.push(format!("encode({}.to_string())", identifier(&param)));
+                }
+            }
+        }
+        let url = if args.is_empty() {
+            format!("{:?}", path)
+        } else {
+            format!("&format!({:?}, {})", path, args.join(", "))
+        };
+        code.push_str(&format!(
+            "        let {}request = self.request(Method::{}, {}, {});\n",
+            if op.query_params.is_empty() && op.body.is_none() {
+                ""
+            } else {
+                "mut "
+            },
+            op.method,
+            url,
+            op.secured
+        ));
+
+        if !op.query_params.is_empty() {
+            code.push_str("        let mut query: Vec<(&str, String)> = Vec::new();\n");
+            for param in &op.query_params {
+                let name = identifier(&param.name);
+                let is_option = param_type(&param.ty, param.required).starts_with("Option<");
+                let value_of = |v: &str| {
+                    match param.ty.inner() {
+                    TypeRef::Array(_) | TypeRef::Object(_) | TypeRef::Map(_) => {
+                        format!("serde_json::to_string(&{}).unwrap_or_default()", v)
+                    }
+                    TypeRef::Named(_) => format!(
+                        "serde_json::to_value(&{}).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()",
+                        v
+                    ),
+                    _ => format!("{}.to_string()", v),
+                }
+                };
+                if is_option {
+                    code.push_str(&format!(
+                        "        if let Some(value) = &{} {{\n            query.push(({:?}, {}));\n        }}\n",
+                        name,
+                        param.name,
+                        value_of("value")
+                    ));
+                } else {
+                    code.push_str(&format!(
+                        "        query.push(({:?}, {}));\n",
+                        param.name,
+                        value_of(&name)
+                    ));
+                }
+            }
+            code.push_str("        request = request.query(&query);\n");
+        }
+        if let Some(body) = &op.body {
+            code.push_str(&format!(
+                "        request = request.json(&{});\n",
+                identifier(&body.name)
+            ));
+        }
+        code.push_str(&format!(
+            "        {}(request).await\n    }}\n",
+            if response.is_some() {
+                "Self::send_json"
+            } else {
+                "Self::send_empty"
+            }
+        ));
+    }
+
+    code.push_str(
+        r#"
+    fn request(&self, method: Method, path: &str, auth: bool) -> RequestBuilder {
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("Accept", "application/json");
+        if auth {
+            if let Some(token) = &self.token {
+                request = request.header("Authorization", format!("Bearer {}", token));
+            }
+        }
+        request
+    }
+
+    async fn send_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, ApiError> {
+        let response = Self::check(request.send().await?).await?;
+        Ok(response.json::<T>().await?)
+    }
+
+    async fn send_empty(request: RequestBuilder) -> Result<(), ApiError> {
+        Self::check(request.send().await?).await?;
+        Ok(())
+    }
+
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let text = response.text().await.unwrap_or_default();
+        let body: Option<ErrorBody> = serde_json::from_str(&text).ok();
+        let message = body
+            .as_ref()
+            .and_then(|b| b.error.clone())
+            .unwrap_or_else(|| status.canonical_reason().unwrap_or("Unbekannter Fehler").to_string());
+        Err(ApiError::Status {
+            status: status.as_u16(),
+            message,
+            errors: body.map(|b| b.errors).unwrap_or_default(),
+        })
+    }
+}
+
+/// Prozent-Kodierung für Pfad-Parameter
+fn encode(value: String) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+"#,
+    );
+    code
+}
@@ -0,0 +1,267 @@
+// TypeScript Client
+// Interfaces aus `components/schemas`, typisierte Methoden über `fetch`
+
+use super::{camel_case, doc_line, ClientModel, ClientOperation, ModelKind, TypeRef};
+
+pub fn ts_type(ty: &TypeRef) -> String {
+    match ty {
+        TypeRef::String => "string".to_string(),
+        TypeRef::Number | TypeRef::Integer => "number".to_string(),
+        TypeRef::Boolean => "boolean".to_string(),
+        TypeRef::Array(item) => match item.as_ref() {
+            TypeRef::Nullable(_) | TypeRef::Union(_) | TypeRef::Literals(_) => {
+                format!("Array<{}>", ts_type(item))
+            }
+            _ => format!("{}[]", ts_type(item)),
+        },
+        TypeRef::Map(value) => format!("Record<string, {}>", ts_type(value)),
+        TypeRef::Named(name) => name.clone(),
+        TypeRef::Nullable(inner) => format!("{} | null", ts_type(inner)),
+        TypeRef::Object(fields) => format!(
+            "{{ {} }}",
+            fields
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", property_name(name), ts_type(ty)))
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+        TypeRef::Literals(values) => values
+            .iter()
+            .map(|v| format!("'{}'", v.replace('\'', "\\'")))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        TypeRef::Union(variants) => variants.iter().map(ts_type).collect::<Vec<_>>().join(" | "),
+        TypeRef::Any => "unknown".to_string(),
+    }
+}
+
+/// Property-Name, bei Bedarf in Anführungszeichen
+pub fn property_name(name: &str) -> String {
+    let identifier = name.chars().enumerate().all(|(i, c)| {
+        c == '_' || c == '$' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit())
+    });
+    if identifier && !name.is_empty() {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "\\'"))
+    }
+}
+
+/// Template-Literal für den Pfad mit kodierten Pfad-Parametern
+pub fn path_template(op: &ClientOperation) -> String {
+    let mut template = String::from("`");
+    for segment in op.path_segments() {
+        match segment {
+            Ok(text) => template.push_str(&text.replace('`', "\\`").replace("${", "\\${")),
+            Err(param) => template.push_str(&format!(
+                "${{encodeURIComponent(String({}))}}",
+                camel_case(&param)
+            )),
+        }
+    }
+    template.push('`');
+    template
+}
+
+/// Optionen-Objekt für `request()`: Query, Body und Auth
+pub fn request_init(op: &ClientOperation) -> String {
+    let mut init = Vec::new();
+    if !op.query_params.is_empty() {
+        let query = op
+            .query_params
+            .iter()
+            .map(|p| {
+                let variable = camel_case(&p.name);
+                if variable == p.name {
+                    variable
+                } else {
+                    format!("{}: {}", property_name(&p.name), variable)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        init.push(format!("query: {{ {} }}", query));
+    }
+    if op.body.is_some() {
+        init.push("body".to_string());
+    }
+    if op.secured {
+        init.push("auth: true".to_string());
+    }
+    if init.is_empty() {
+        String::new()
+    } else {
+        format!(", {{ {} }}", init.join(", "))
+    }
+}
+
+pub fn generate(model: &ClientModel) -> String {
+    let mut code = String::new();
+    code.push_str(&format!(
+        "// Generierter API Client für {} {}\n// Nicht manuell bearbeiten\n\n",
+        model.title, model.version
+    ));
+
+    for m in &model.models {
+        if let Some(doc) = doc_line(&m.description) {
+            code.push_str(&format!("/** {} */\n", doc));
+        }
+        match &m.kind {
+            ModelKind::Object(fields) => {
+                code.push_str(&format!("export interface {} {{\n", m.name));
+                for field in fields {
+                    if let Some(doc) = doc_line(&field.description) {
+                        code.push_str(&format!("  /** {} */\n", doc));
+                    }
+                    code.push_str(&format!(
+                        "  {}{}: {};\n",
+                        property_name(&field.name),
+                        if field.required { "" } else { "?" },
+                        ts_type(&field.ty)
+                    ));
+                }
+                code.push_str("}\n\n");
+            }
+            ModelKind::Enum(values) => {
+                code.push_str(&format!(
+                    "export type {} = {};\n\n",
+                    m.name,
+                    ts_type(&TypeRef::Literals(values.clone()))
+                ));
+            }
+            ModelKind::Alias(ty) => {
+                code.push_str(&format!("export type {} = {};\n\n", m.name, ts_type(ty)));
+            }
+        }
+    }
+
+    code.push_str(
+        r#"export interface ValidationIssue {
+  field: string;
+  message: string;
+}
+
+/** Fehlerantwort der API (Status >= 400) */
+export class ApiError extends Error {
+  constructor(
+    public readonly status: number,
+    message: string,
+    public readonly errors: ValidationIssue[] = [],
+  ) {
+    super(message);
+    this.name = 'ApiError';
+  }
+}
+
+export type TokenProvider = string | (() => string | undefined | Promise<string | undefined>);
+
+export interface ApiClientOptions {
+  baseUrl: string;
+  /** Bearer Token für geschützte Endpunkte */
+  token?: TokenProvider;
+  headers?: Record<string, string>;
+  fetch?: typeof fetch;
+}
+
+interface RequestOptions {
+  query?: Record<string, unknown>;
+  body?: unknown;
+  auth?: boolean;
+}
+
+export class ApiClient {
+  private readonly baseUrl: string;
+
+  constructor(private readonly options: ApiClientOptions) {
+    this.baseUrl = options.baseUrl.replace(/\/+$/, '');
+  }
+
+  setToken(token: TokenProvider | undefined): void {
+    this.options.token = token;
+  }
+"#,
+    );
+
+    for op in &model.operations {
+        let params = op
+            .signature()
+            .iter()
+            .map(|(param, optional)| {
+                format!(
+                    "{}{}: {}",
+                    camel_case(&param.name),
+                    if *optional { "?" } else { "" },
+                    ts_type(&param.ty)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let response = op
+            .response
+            .as_ref()
+            .map(ts_type)
+            .unwrap_or_else(|| "void".to_string());
+
+        code.push('\n');
+        if let Some(doc) = doc_line(&op.summary) {
+            code.push_str(&format!("  /** {} */\n", doc));
+        }
+        code.push_str(&format!(
+            "  async {}({}): Promise<{}> {{\n",
+            op.name, params, response
+        ));
+        code.push_str(&format!(
+            "    return this.request<{}>('{}', {}{});\n  }}\n",
+            response,
+            op.method,
+            path_template(op),
+            request_init(op)
+        ));
+    }
+
+    code.push_str(
+        r#"
+  private async request<T>(method: string, path: string, init: RequestOptions = {}): Promise<T> {
+    const search = new URLSearchParams();
+    for (const [key, value] of Object.entries(init.query ?? {})) {
+      if (value === undefined || value === null) continue;
+      for (const item of Array.isArray(value) ? value : [value]) {
+        search.append(key, String(item));
+      }
+    }
+    const query = search.toString();
+
+    const headers: Record<string, string> = { Accept: 'application/json', ...this.options.headers };
+    if (init.body !== undefined) {
+      headers['Content-Type'] = 'application/json';
+    }
+    if (init.auth) {
+      const token = typeof this.options.token === 'function' ? await this.options.token() : this.options.token;
+      if (token) {
+        headers['Authorization'] = `Bearer ${token}`;
+      }
+    }
+
+    const response = await (this.options.fetch ?? fetch)(this.baseUrl + path + (query ? `?${query}` : ''), {
+      method,
+      headers,
+      body: init.body === undefined ? undefined : JSON.stringify(init.body),
+    });
+    const text = await response.text();
+    let data: any = undefined;
+    try {
+      data = text ? JSON.parse(text) : undefined;
+    } catch {
+      data = text;
+    }
+    if (!response.ok) {
+      const message = typeof data?.error === 'string' ? data.error : response.statusText;
+      throw new ApiError(response.status, message, Array.isArray(data?.errors) ? data.errors : []);
+    }
+    return data as T;
+  }
+}
+"#,
+    );
+    code
+}
@@ -1,13 +1,16 @@
 use super::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::codegen::framework::{Framework, FrameworkSelector};
 use crate::parser::ast::*;
+use crate::validation::{self, Check, FieldValidation, RuleValue, ValueKind};
 use anyhow::Result;
+use std::collections::HashSet;
 
 pub struct CSharpCodeGenerator {
     buffer: String,
     indent_level: usize,
     framework: Option<Framework>,
     namespace: String,
+    validated_structs: HashSet<String>,
 }
 
 impl CSharpCodeGenerator {
@@ -17,6 +20,7 @@ impl CSharpCodeGenerator {
             indent_level: 0,
             framework: None,
             namespace: "VelinApp".to_string(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -76,11 +80,66 @@ impl CSharpCodeGenerator {
                 field.name
             ));
         }
+        if self.validated_structs.contains(&s.name) {
+            self.writeln("");
+            self.generate_validation_method(s);
+        }
         self.dedent();
         self.writeln("}");
         self.writeln("");
     }
 
+    /// `ValidationErrors()` mit einem Dictionary `{field, message}` pro verletzter `@Validate`-Regel
+    fn generate_validation_method(&mut self, s: &Struct) {
+        self.writeln("public List<Dictionary<string, string>> ValidationErrors()");
+        self.writeln("{");
+        self.indent();
+        self.writeln("var errors = new List<Dictionary<string, string>>();");
+        for field in validation::struct_validations(s) {
+            for check in field.checks() {
+                let Some(condition) = csharp_failure(&check.check, &field) else {
+                    continue;
+                };
+                self.writeln(&format!("if ({})", condition));
+                self.writeln("{");
+                self.indent();
+                self.writeln(&format!(
+                    "errors.Add(new Dictionary<string, string> {{ [\"field\"] = {:?}, [\"message\"] = {:?} }});",
+                    field.name, check.message
+                ));
+                self.dedent();
+                self.writeln("}");
+            }
+        }
+        self.writeln("return errors;");
+        self.dedent();
+        self.writeln("}");
+    }
+
+    /// Prüft `[FromBody]`-Parameter und antwortet mit `BadRequest`
+    fn generate_request_validation(&mut self, f: &Function) {
+        let params: Vec<String> = validation::validated_params(f, &self.validated_structs)
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        for param in params {
+            self.writeln(&format!(
+                "var {}Errors = {}.ValidationErrors();",
+                param, param
+            ));
+            self.writeln(&format!("if ({}Errors.Count > 0)", param));
+            self.writeln("{");
+            self.indent();
+            self.writeln(&format!(
+                "return BadRequest(new {{ error = {:?}, errors = {}Errors }});",
+                validation::VALIDATION_FAILED,
+                param
+            ));
+            self.dedent();
+            self.writeln("}");
+        }
+    }
+
     fn generate_function(&mut self, f: &Function) {
         // Attributes
         if self.framework == Some(Framework::AspNet) {
@@ -161,6 +220,9 @@ impl CSharpCodeGenerator {
         ));
         self.writeln("{");
         self.indent();
+        if self.framework == Some(Framework::AspNet) {
+            self.generate_request_validation(f);
+        }
 
         // Body
         for stmt in &f.body.statements {
@@ -346,6 +408,7 @@ impl CodeGenerator for CSharpCodeGenerator {
         // Structs need to be outside Controller in C# usually, or nested.
         // Let's put them outside. But for this simple generator, nested is easier to manage context.
         // Actually, C# nested classes are fine.
+        self.validated_structs = validation::validated_structs(&program.items);
 
         for item in &program.items {
             if let Item::Struct(s) = item {
@@ -369,3 +432,51 @@ impl CodeGenerator for CSharpCodeGenerator {
         TargetLanguage::CSharp
     }
 }
+
+/// C#-Bedingung für eine verletzte Regel. `None` für `required` auf nicht-nullbaren
+/// Werttypen (`double`, `bool`), die immer einen Wert haben.
+fn csharp_failure(check: &Check, field: &FieldValidation) -> Option<String> {
+    let access = field.name.clone();
+    let nullable = field.optional || field.kind != ValueKind::Number;
+    let failed = match check {
+        Check::Required if field.kind == ValueKind::Text => {
+            return Some(format!("string.IsNullOrEmpty({})", access))
+        }
+        Check::Required if nullable => return Some(format!("{} == null", access)),
+        Check::Required => return None,
+        Check::MinLength(n) => format!("{}.Length < {}", access, n),
+        Check::MaxLength(n) => format!("{}.Length > {}", access, n),
+        Check::MinItems(n) => format!("{}.Count < {}", access, n),
+        Check::MaxItems(n) => format!("{}.Count > {}", access, n),
+        Check::Pattern(pattern) => format!(
+            "!System.Text.RegularExpressions.Regex.IsMatch({}, {:?})",
+            access, pattern
+        ),
+        Check::Min(n) => format!("{} < {:?}", access, n),
+        Check::Max(n) => format!("{} > {:?}", access, n),
+        Check::OneOf(values) => {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| match v {
+                    RuleValue::String(s) => format!("{:?}", s),
+                    RuleValue::Number(n) => format!("{:?}", n),
+                })
+                .collect();
+            let value = if field.optional && field.kind == ValueKind::Number {
+                format!("{}.Value", access)
+            } else {
+                access.clone()
+            };
+            format!(
+                "System.Array.IndexOf(new[] {{ {} }}, {}) < 0",
+                values.join(", "),
+                value
+            )
+        }
+    };
+    if nullable {
+        Some(format!("{} != null && {}", access, failed))
+    } else {
+        Some(failed)
+    }
+}
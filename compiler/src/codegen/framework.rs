@@ -99,7 +99,7 @@ impl FrameworkSelector {
                 "import express, { Request, Response } from 'express';\n".to_string()
             }
             Framework::NestJS => {
-                "import { Controller, Get, Post, Put, Delete, Body, Param, Query } from '@nestjs/common';\n".to_string()
+                "import { Controller, Get, Post, Put, Delete, Body, Param, Query, BadRequestException } from '@nestjs/common';\n".to_string()
             }
             Framework::Spring => {
                 "import org.springframework.boot.SpringApplication;\nimport org.springframework.boot.autoconfigure.SpringBootApplication;\nimport org.springframework.web.bind.annotation.*;\nimport org.springframework.http.ResponseEntity;\n".to_string()
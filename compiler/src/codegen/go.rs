@@ -2,12 +2,15 @@ use crate::codegen::framework::{Framework, FrameworkSelector};
 use crate::codegen::traits::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::compiler::language::VELISCH_FINGERPRINT;
 use crate::parser::ast::*;
+use crate::validation::{self, Check, FieldValidation, RuleValue, ValueKind};
+use std::collections::HashSet;
 
 pub struct GoCodeGenerator {
     output: String,
     indent_level: usize,
     framework: Framework,
     routes: Vec<(String, String, String)>, // Method, Path, HandlerName
+    validated_structs: HashSet<String>,
 }
 
 impl GoCodeGenerator {
@@ -17,6 +20,7 @@ impl GoCodeGenerator {
             indent_level: 0,
             framework: Framework::Axum,
             routes: Vec::new(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -51,6 +55,38 @@ impl GoCodeGenerator {
         self.dedent();
         self.writeln("}");
         self.writeln("");
+        if self.validated_structs.contains(&s.name) {
+            self.generate_validation_method(s);
+        }
+    }
+
+    /// `ValidationErrors()` mit einem `ValidationIssue` pro verletzter `@Validate`-Regel
+    fn generate_validation_method(&mut self, s: &Struct) {
+        self.writeln(&format!(
+            "func (v {}) ValidationErrors() []ValidationIssue {{",
+            s.name
+        ));
+        self.indent();
+        self.writeln("errs := []ValidationIssue{}");
+        for field in validation::struct_validations(s) {
+            for check in field.checks() {
+                let Some(condition) = go_failure(&check.check, &field) else {
+                    continue;
+                };
+                self.writeln(&format!("if {} {{", condition));
+                self.indent();
+                self.writeln(&format!(
+                    "errs = append(errs, ValidationIssue{{Field: {:?}, Message: {:?}}})",
+                    field.name, check.message
+                ));
+                self.dedent();
+                self.writeln("}");
+            }
+        }
+        self.writeln("return errs");
+        self.dedent();
+        self.writeln("}");
+        self.writeln("");
     }
 
     fn generate_enum(&mut self, e: &Enum) {
@@ -186,6 +222,21 @@ impl GoCodeGenerator {
                         self.writeln("return");
                         self.dedent();
                         self.writeln("}");
+                        if matches!(&param.param_type, Type::Named(name) if self.validated_structs.contains(name))
+                        {
+                            self.writeln(&format!(
+                                "if errs := {}.ValidationErrors(); len(errs) > 0 {{",
+                                param.name
+                            ));
+                            self.indent();
+                            self.writeln(&format!(
+                                "c.JSON(http.StatusBadRequest, gin.H{{\"error\": \"{}\", \"errors\": errs}})",
+                                validation::VALIDATION_FAILED
+                            ));
+                            self.writeln("return");
+                            self.dedent();
+                            self.writeln("}");
+                        }
                     } else {
                         // Bind Query or Path
                         if param_in_path {
@@ -290,6 +341,47 @@ impl GoCodeGenerator {
         }
     }
 
+    /// Zusätzliche Imports und der gemeinsame `ValidationIssue`-Typ
+    fn generate_validation_prelude(&mut self, program: &Program) {
+        let checks: Vec<Check> = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(s) => Some(validation::struct_validations(s)),
+                _ => None,
+            })
+            .flatten()
+            .flat_map(|field| field.checks().into_iter().map(|c| c.check))
+            .collect();
+        let mut imports = Vec::new();
+        if checks.iter().any(|c| matches!(c, Check::Pattern(_))) {
+            imports.push("regexp");
+        }
+        if checks
+            .iter()
+            .any(|c| matches!(c, Check::MinLength(_) | Check::MaxLength(_)))
+        {
+            imports.push("unicode/utf8");
+        }
+        if !imports.is_empty() {
+            self.writeln("import (");
+            self.indent();
+            for import in imports {
+                self.writeln(&format!("{:?}", import));
+            }
+            self.dedent();
+            self.writeln(")");
+            self.writeln("");
+        }
+        self.writeln("type ValidationIssue struct {");
+        self.indent();
+        self.writeln("Field   string `json:\"field\"`");
+        self.writeln("Message string `json:\"message\"`");
+        self.dedent();
+        self.writeln("}");
+        self.writeln("");
+    }
+
     fn map_type(&self, t: &Type) -> String {
         match t {
             Type::String => "string".to_string(),
@@ -298,6 +390,9 @@ impl GoCodeGenerator {
             Type::Void => "".to_string(),
             Type::Any => "interface{}".to_string(),
             Type::List(inner) => format!("[]{}", self.map_type(inner)),
+            Type::Generic { name, params } if name == "List" && params.len() == 1 => {
+                format!("[]{}", self.map_type(&params[0]))
+            }
             Type::Map { key, value } => {
                 format!("map[{}]{}", self.map_type(key), self.map_type(value))
             }
@@ -326,6 +421,11 @@ impl CodeGenerator for GoCodeGenerator {
         // Imports
         self.writeln(&FrameworkSelector::generate_imports(framework));
 
+        self.validated_structs = validation::validated_structs(&program.items);
+        if !self.validated_structs.is_empty() {
+            self.generate_validation_prelude(program);
+        }
+
         for item in &program.items {
             match item {
                 Item::Struct(s) => self.generate_struct(s),
@@ -351,3 +451,49 @@ impl CodeGenerator for GoCodeGenerator {
         TargetLanguage::Go
     }
 }
+
+/// Go-Bedingung für eine verletzte Regel. `None` für `required` auf Werttypen,
+/// deren Nullwert sich nicht von einem fehlenden Wert unterscheiden lässt.
+fn go_failure(check: &Check, field: &FieldValidation) -> Option<String> {
+    let access = format!("v.{}", field.name);
+    let nullable = field.optional || field.kind == ValueKind::List;
+    if *check == Check::Required {
+        return match (field.optional, field.kind) {
+            (false, ValueKind::Text) => Some(format!("{} == \"\"", access)),
+            _ if nullable => Some(format!("{} == nil", access)),
+            _ => None,
+        };
+    }
+    let value = if field.optional {
+        format!("*{}", access)
+    } else {
+        access.clone()
+    };
+    let failed = match check {
+        Check::Required => unreachable!(),
+        Check::MinLength(n) => format!("utf8.RuneCountInString({}) < {}", value, n),
+        Check::MaxLength(n) => format!("utf8.RuneCountInString({}) > {}", value, n),
+        Check::MinItems(n) => format!("len({}) < {}", value, n),
+        Check::MaxItems(n) => format!("len({}) > {}", value, n),
+        Check::Pattern(pattern) => {
+            format!("!regexp.MustCompile({:?}).MatchString({})", pattern, value)
+        }
+        Check::Min(n) => format!("{} < {:?}", value, n),
+        Check::Max(n) => format!("{} > {:?}", value, n),
+        Check::OneOf(values) => {
+            let conditions: Vec<String> = values
+                .iter()
+                .map(|v| match v {
+                    RuleValue::String(s) => format!("{} != {:?}", value, s),
+                    RuleValue::Number(n) => format!("{} != {:?}", value, n),
+                })
+                .collect();
+            format!("({})", conditions.join(" && "))
+        }
+    };
+    if field.optional {
+        Some(format!("{} != nil && {}", access, failed))
+    } else {
+        Some(failed)
+    }
+}
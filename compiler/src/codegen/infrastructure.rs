@@ -1,12 +1,13 @@
 pub use crate::codegen::system_generator::{DeploymentConfig, DeploymentType, Requirements};
 use anyhow::Result;
+use serde::Deserialize;
 
 /// Infrastructure-as-Code Generator
 ///
 /// Generiert Infrastructure-Code für Deployment:
 /// - Dockerfile
-/// - docker-compose.yml
-/// - Kubernetes Manifests
+/// - docker-compose.yml (Service + Backing Services)
+/// - Kubernetes Manifests (Service + Backing Services)
 /// - Helm Charts
 /// - Serverless Configs
 pub struct InfrastructureGenerator;
@@ -22,7 +23,13 @@ impl InfrastructureGenerator {
 
         match plan.deployment_type {
             DeploymentType::Local => {
-                // Keine Infrastructure nötig
+                // Service läuft auf dem Host, nur die Backing Services kommen aus Compose
+                if !plan.services.is_empty() {
+                    code.add(self.generate_docker_compose(plan)?);
+                }
+                if !plan.env_vars.is_empty() || !plan.services.is_empty() {
+                    code.add(self.generate_env_example(plan));
+                }
             }
             DeploymentType::CloudSingle => {
                 code.add(self.generate_dockerfile(plan)?);
@@ -38,16 +45,6 @@ impl InfrastructureGenerator {
             }
         }
 
-        // Caching-Infrastructure
-        if plan.needs_caching {
-            code.add(self.generate_redis(plan)?);
-        }
-
-        // Database-Infrastructure
-        if plan.needs_database {
-            code.add(self.generate_database(plan)?);
-        }
-
         // Validiere alle generierten Configs
         self.validate_infrastructure_code(&code)?;
 
@@ -59,9 +56,11 @@ impl InfrastructureGenerator {
         for file in &code.files {
             match file.name.as_str() {
                 name if name.ends_with(".yaml") || name.ends_with(".yml") => {
-                    // YAML-Validierung
-                    let _: serde_yaml::Value = serde_yaml::from_str(&file.content)
-                        .map_err(|e| anyhow::anyhow!("Invalid YAML in {}: {}", file.name, e))?;
+                    // YAML-Validierung (Manifeste enthalten mehrere Dokumente)
+                    for document in serde_yaml::Deserializer::from_str(&file.content) {
+                        serde_yaml::Value::deserialize(document)
+                            .map_err(|e| anyhow::anyhow!("Invalid YAML in {}: {}", file.name, e))?;
+                    }
                 }
                 name if name.ends_with(".json") => {
                     // JSON-Validierung
@@ -81,55 +80,125 @@ impl InfrastructureGenerator {
     }
 
     /// Generiert Dockerfile
-    fn generate_dockerfile(&self, _plan: &DeploymentPlan) -> Result<InfrastructureFile> {
+    fn generate_dockerfile(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
+        // curl wird nur für den Compose-Healthcheck gebraucht
+        let packages = if plan.health.liveness.is_some() {
+            "ca-certificates curl"
+        } else {
+            "ca-certificates"
+        };
+        let expose = plan
+            .port
+            .map(|port| format!("EXPOSE {}\n", port))
+            .unwrap_or_default();
+
         Ok(InfrastructureFile {
             name: "Dockerfile".to_string(),
-            content: r#"FROM rust:1.70 as builder
+            content: format!(
+                r#"FROM rust:1.75 as builder
 WORKDIR /app
 COPY Cargo.toml Cargo.lock ./
 COPY src ./src
 RUN cargo build --release
 
 FROM debian:bookworm-slim
-RUN apt-get update && apt-get install -y ca-certificates && rm -rf /var/lib/apt/lists/*
-COPY --from=builder /app/target/release/velin-compiler /usr/local/bin/velin-compiler
-EXPOSE 3000
-ENV RUST_LOG=info
-CMD ["velin-compiler"]
-"#
-            .to_string(),
+RUN apt-get update && apt-get install -y {packages} && rm -rf /var/lib/apt/lists/*
+COPY --from=builder /app/target/release/{name} /usr/local/bin/{name}
+{expose}ENV RUST_LOG=info
+CMD ["{name}"]
+"#,
+                packages = packages,
+                name = plan.service_name,
+                expose = expose,
+            ),
         })
     }
 
-    /// Generiert docker-compose.yml
+    /// Generiert docker-compose.yml mit Service und Backing Services
+    ///
+    /// Im lokalen Deployment läuft der Service auf dem Host; dann enthält die Datei
+    /// nur die Backing Services, mit veröffentlichten Ports.
     fn generate_docker_compose(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
-        let mut compose = r#"version: '3.8'
-services:
-  api:
-    build: .
-    ports:
-      - "3000:3000"
-    environment:
-      - RUST_LOG=info
-"#
-        .to_string();
+        let local = matches!(plan.deployment_type, DeploymentType::Local);
+        let mut compose = String::from("services:\n");
 
-        if plan.needs_caching {
-            compose.push_str("      - REDIS_URL=redis://redis:6379\n");
-            compose.push_str("    depends_on:\n      - redis\n");
-        }
-        if plan.needs_database {
-            compose.push_str("      - DATABASE_URL=${DATABASE_URL}\n");
-            compose.push_str("    depends_on:\n      - postgres\n");
+        if !local {
+            compose.push_str(&format!("  {}:\n    build: .\n", plan.service_name));
+            if let Some(port) = plan.port {
+                compose.push_str(&format!("    ports:\n      - \"{}:{}\"\n", port, port));
+            }
+            compose.push_str("    environment:\n");
+            for (name, value) in plan.app_env(&|service| service.name().to_string()) {
+                compose.push_str(&format!("      {}: {}\n", name, quote(&value.compose())));
+            }
+            if !plan.services.is_empty() {
+                compose.push_str("    depends_on:\n");
+                for service in &plan.services {
+                    let condition = if service.healthcheck().is_some() {
+                        "service_healthy"
+                    } else {
+                        "service_started"
+                    };
+                    compose.push_str(&format!(
+                        "      {}:\n        condition: {}\n",
+                        service.name(),
+                        condition
+                    ));
+                }
+            }
+            if let (Some(port), Some(path)) = (plan.port, &plan.health.liveness) {
+                compose.push_str(&format!(
+                    "    healthcheck:\n      test: [\"CMD\", \"curl\", \"-fsS\", \"http://localhost:{}{}\"]\n      interval: 10s\n      timeout: 3s\n      retries: 5\n",
+                    port, path
+                ));
+            }
         }
 
-        if plan.needs_caching {
-            compose.push_str(
-                "\n  redis:\n    image: redis:alpine\n    ports:\n      - \"6379:6379\"\n",
-            );
+        for service in &plan.services {
+            compose.push_str(&format!(
+                "  {}:\n    image: {}\n",
+                service.name(),
+                service.image()
+            ));
+            let env = service.container_env();
+            if !env.is_empty() {
+                compose.push_str("    environment:\n");
+                for (name, value) in env {
+                    compose.push_str(&format!("      {}: {}\n", name, quote(&value.compose())));
+                }
+            }
+            if local {
+                compose.push_str(&format!(
+                    "    ports:\n      - \"{}:{}\"\n",
+                    service.port(),
+                    service.port()
+                ));
+            }
+            if let Some(dir) = service.data_dir() {
+                compose.push_str(&format!(
+                    "    volumes:\n      - {}-data:{}\n",
+                    service.name(),
+                    dir
+                ));
+            }
+            if let Some(test) = service.healthcheck() {
+                compose.push_str(&format!(
+                    "    healthcheck:\n      test: {}\n      interval: 5s\n      timeout: 3s\n      retries: 10\n",
+                    test
+                ));
+            }
         }
-        if plan.needs_database {
-            compose.push_str("\n  postgres:\n    image: postgres:15\n    environment:\n      - POSTGRES_PASSWORD=${POSTGRES_PASSWORD}\n    ports:\n      - \"5432:5432\"\n");
+
+        let volumes: Vec<_> = plan
+            .services
+            .iter()
+            .filter(|service| service.data_dir().is_some())
+            .collect();
+        if !volumes.is_empty() {
+            compose.push_str("\nvolumes:\n");
+            for service in volumes {
+                compose.push_str(&format!("  {}-data:\n", service.name()));
+            }
         }
 
         Ok(InfrastructureFile {
@@ -138,121 +207,177 @@ services:
         })
     }
 
-    /// Generiert Kubernetes Manifests
+    /// Generiert .env.example für den lokal laufenden Service
+    fn generate_env_example(&self, plan: &DeploymentPlan) -> InfrastructureFile {
+        let mut content = format!(
+            "# Umgebung für {} (Backing Services aus docker-compose.yml auf localhost)\n",
+            plan.service_name
+        );
+        for (name, value) in plan.app_env(&|_| "localhost".to_string()) {
+            match value {
+                EnvValue::Plain(value) => content.push_str(&format!("{}={}\n", name, value)),
+                EnvValue::Secret(_) | EnvValue::Required(_) => {
+                    content.push_str(&format!("{}=\n", name))
+                }
+            }
+        }
+        InfrastructureFile {
+            name: ".env.example".to_string(),
+            content,
+        }
+    }
+
+    /// Generiert Kubernetes Manifests für Service und Backing Services
     fn generate_kubernetes(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
+        let name = &plan.service_name;
         let replicas = plan.replicas.unwrap_or(3);
+        let env = plan.app_env(&|service| service.name().to_string());
 
-        let port = std::env::var("PORT")
-            .ok()
-            .and_then(|p| p.parse::<u16>().ok())
-            .unwrap_or(3000);
-
-        let content = format!(
-            r#"# Kubernetes Deployment Configuration
-# Port: {}
-# Replicas: {}
+        let mut content = format!(
+            "# Kubernetes Deployment Configuration\n# Replicas: {}\n",
+            replicas
+        );
+        if let Some(port) = plan.port {
+            content.push_str(&format!("# Port: {}\n", port));
+        }
+        let secrets = plan.secret_keys();
+        if !secrets.is_empty() {
+            let literals: Vec<_> = secrets
+                .iter()
+                .map(|key| format!("--from-literal={}=...", key))
+                .collect();
+            content.push_str(&format!(
+                "#\n# Secrets vor dem Deployment anlegen:\n#   kubectl create secret generic {} {}\n",
+                plan.secret_name(),
+                literals.join(" ")
+            ));
+        }
+        let required: Vec<_> = env
+            .iter()
+            .filter_map(|(_, value)| match value {
+                EnvValue::Required(key) => Some(format!("--from-literal={}=...", key)),
+                _ => None,
+            })
+            .collect();
+        if !required.is_empty() {
+            content.push_str(&format!(
+                "#\n# Variablen ohne Default (optional):\n#   kubectl create configmap {} {}\n",
+                plan.config_name(),
+                required.join(" ")
+            ));
+        }
 
+        content.push_str(&format!(
+            r#"
 apiVersion: apps/v1
 kind: Deployment
 metadata:
-  name: velin-api
+  name: {name}
   labels:
-    app: velin-api
-    version: "3.1.0"
+    app: {name}
 spec:
-  replicas: {}
+  replicas: {replicas}
   selector:
     matchLabels:
-      app: velin-api
+      app: {name}
   template:
     metadata:
       labels:
-        app: velin-api
-        version: "3.1.0"
+        app: {name}
     spec:
       containers:
-      - name: api
-        image: velin-api:latest
+      - name: {name}
+        image: {name}:latest
         imagePullPolicy: Always
-        ports:
-        - name: http
-          containerPort: {}
-          protocol: TCP
-        - name: metrics
-          containerPort: 9090
-          protocol: TCP
-        env:
-        - name: PORT
-          value: "{}"
-        - name: RUST_LOG
-          value: "info"
-        # Health Checks
-        livenessProbe:
-          httpGet:
-            path: /health
-            port: {}
-          initialDelaySeconds: 30
-          periodSeconds: 10
-          timeoutSeconds: 5
-          failureThreshold: 3
-        readinessProbe:
-          httpGet:
-            path: /health
-            port: {}
-          initialDelaySeconds: 5
-          periodSeconds: 5
-          timeoutSeconds: 3
-          failureThreshold: 3
-        # Resource Limits
-        resources:
+"#,
+            name = name,
+            replicas = replicas,
+        ));
+        if let Some(port) = plan.port {
+            content.push_str(&format!(
+                "        ports:\n        - name: http\n          containerPort: {}\n          protocol: TCP\n",
+                port
+            ));
+        }
+        content.push_str("        env:\n");
+        for (var, value) in &env {
+            content.push_str(&format!("        - name: {}\n", var));
+            content.push_str(&value.kubernetes(plan, "          "));
+        }
+        if let Some(port) = plan.port {
+            if let Some(path) = &plan.health.liveness {
+                content.push_str(&format!(
+                    "        livenessProbe:\n          httpGet:\n            path: {}\n            port: {}\n          initialDelaySeconds: 30\n          periodSeconds: 10\n          timeoutSeconds: 5\n          failureThreshold: 3\n",
+                    path, port
+                ));
+            }
+            if let Some(path) = plan
+                .health
+                .readiness
+                .as_ref()
+                .or(plan.health.liveness.as_ref())
+            {
+                content.push_str(&format!(
+                    "        readinessProbe:\n          httpGet:\n            path: {}\n            port: {}\n          initialDelaySeconds: 5\n          periodSeconds: 5\n          timeoutSeconds: 3\n          failureThreshold: 3\n",
+                    path, port
+                ));
+            }
+        }
+        content.push_str(&format!(
+            r#"        resources:
           requests:
             memory: "{}"
             cpu: "{}"
           limits:
             memory: "{}"
             cpu: "{}"
-        # Security Context
         securityContext:
           runAsNonRoot: true
           runAsUser: 1000
           allowPrivilegeEscalation: false
----
-# Service für Load Balancing
+"#,
+            plan.memory_request.as_deref().unwrap_or("256Mi"),
+            plan.cpu_request.as_deref().unwrap_or("100m"),
+            plan.memory_limit.as_deref().unwrap_or("512Mi"),
+            plan.cpu_limit.as_deref().unwrap_or("500m"),
+        ));
+
+        if let Some(port) = plan.port {
+            content.push_str(&format!(
+                r#"---
 apiVersion: v1
 kind: Service
 metadata:
-  name: velin-api-service
+  name: {name}
   labels:
-    app: velin-api
+    app: {name}
 spec:
   type: LoadBalancer
   selector:
-    app: velin-api
+    app: {name}
   ports:
   - name: http
     port: 80
-    targetPort: {}
-    protocol: TCP
-  - name: metrics
-    port: 9090
-    targetPort: 9090
+    targetPort: {port}
     protocol: TCP
-  sessionAffinity: ClientIP
-  sessionAffinityConfig:
-    clientIP:
-      timeoutSeconds: 10800
----
-# HorizontalPodAutoscaler
+"#,
+                name = name,
+                port = port,
+            ));
+        }
+
+        content.push_str(&format!(
+            r#"---
 apiVersion: autoscaling/v2
 kind: HorizontalPodAutoscaler
 metadata:
-  name: velin-api-hpa
+  name: {name}-hpa
 spec:
   scaleTargetRef:
     apiVersion: apps/v1
     kind: Deployment
-    name: velin-api
-  minReplicas: 2
+    name: {name}
+  minReplicas: {min}
   maxReplicas: 10
   metrics:
   - type: Resource
@@ -261,43 +386,38 @@ spec:
       target:
         type: Utilization
         averageUtilization: 70
-  - type: Resource
-    resource:
-      name: memory
-      target:
-        type: Utilization
-        averageUtilization: 80
----
-# ServiceMonitor für Prometheus (optional, benötigt Prometheus Operator)
+"#,
+            name = name,
+            min = replicas.clamp(1, 10),
+        ));
+
+        if let (Some(_), Some(path)) = (plan.port, &plan.metrics_path) {
+            content.push_str(&format!(
+                r#"---
+# ServiceMonitor für Prometheus (benötigt Prometheus Operator)
 apiVersion: monitoring.coreos.com/v1
 kind: ServiceMonitor
 metadata:
-  name: velin-api-monitor
+  name: {name}-monitor
   labels:
-    app: velin-api
+    app: {name}
 spec:
   selector:
     matchLabels:
-      app: velin-api
+      app: {name}
   endpoints:
-  - port: metrics
-    path: /metrics
+  - port: http
+    path: {path}
     interval: 30s
-    scrapeTimeout: 10s
 "#,
-            port,
-            replicas,
-            replicas,
-            port,
-            port,
-            port,
-            port,
-            plan.memory_request.as_deref().unwrap_or("256Mi"),
-            plan.cpu_request.as_deref().unwrap_or("100m"),
-            plan.memory_limit.as_deref().unwrap_or("512Mi"),
-            plan.cpu_limit.as_deref().unwrap_or("500m"),
-            port,
-        );
+                name = name,
+                path = path,
+            ));
+        }
+
+        for service in &plan.services {
+            content.push_str(&self.kubernetes_backing_service(plan, *service));
+        }
 
         Ok(InfrastructureFile {
             name: "kubernetes.yaml".to_string(),
@@ -305,39 +425,134 @@ spec:
         })
     }
 
-    /// Generiert Helm Chart
-    fn generate_helm_chart(&self, _plan: &DeploymentPlan) -> Result<InfrastructureFile> {
-        let content = r#"apiVersion: v1
-kind: ConfigMap
-metadata:
-  name: velin-api-config
-data:
-  RUST_LOG: "info"
----
+    /// Deployment und Service für einen Backing Service
+    ///
+    /// Daten liegen im Container; für Produktion ein StatefulSet mit
+    /// PersistentVolumeClaim oder einen verwalteten Dienst verwenden.
+    fn kubernetes_backing_service(&self, plan: &DeploymentPlan, service: BackingService) -> String {
+        let mut content = format!(
+            r#"---
 apiVersion: apps/v1
 kind: Deployment
 metadata:
-  name: velin-api
+  name: {name}
+  labels:
+    app: {name}
+    part-of: {owner}
 spec:
-  replicas: 3
+  replicas: 1
   selector:
     matchLabels:
-      app: velin-api
+      app: {name}
   template:
     metadata:
       labels:
-        app: velin-api
+        app: {name}
     spec:
       containers:
-      - name: api
-        image: velin-api:latest
+      - name: {name}
+        image: {image}
         ports:
-        - containerPort: 3000
-        envFrom:
-        - configMapRef:
-            name: velin-api-config
-"#
-        .to_string();
+        - containerPort: {port}
+"#,
+            name = service.name(),
+            owner = plan.service_name,
+            image = service.image(),
+            port = service.port(),
+        );
+        let env = service.container_env();
+        if !env.is_empty() {
+            content.push_str("        env:\n");
+            for (var, value) in env {
+                content.push_str(&format!("        - name: {}\n", var));
+                content.push_str(&value.kubernetes(plan, "          "));
+            }
+        }
+        content.push_str(&format!(
+            r#"---
+apiVersion: v1
+kind: Service
+metadata:
+  name: {name}
+spec:
+  selector:
+    app: {name}
+  ports:
+  - port: {port}
+    targetPort: {port}
+"#,
+            name = service.name(),
+            port = service.port(),
+        ));
+        content
+    }
+
+    /// Generiert Helm Chart
+    fn generate_helm_chart(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
+        let name = &plan.service_name;
+        let mut content = format!(
+            "apiVersion: v1\nkind: ConfigMap\nmetadata:\n  name: {}\ndata:\n",
+            plan.config_name()
+        );
+        // Werte mit Secret-Verweis (z.B. DATABASE_URL) gehen als `env` an den Container
+        let mut interpolated = Vec::new();
+        for (var, value) in plan.app_env(&|service| service.name().to_string()) {
+            match value {
+                EnvValue::Plain(text) if text.contains("${") => {
+                    interpolated.push((var, EnvValue::Plain(text)))
+                }
+                EnvValue::Plain(text) => {
+                    content.push_str(&format!("  {}: {}\n", var, quote(&text)))
+                }
+                _ => {}
+            }
+        }
+        content.push_str(&format!(
+            r#"---
+apiVersion: apps/v1
+kind: Deployment
+metadata:
+  name: {name}
+spec:
+  replicas: {replicas}
+  selector:
+    matchLabels:
+      app: {name}
+  template:
+    metadata:
+      labels:
+        app: {name}
+    spec:
+      containers:
+      - name: {name}
+        image: {name}:latest
+"#,
+            name = name,
+            replicas = plan.replicas.unwrap_or(3),
+        ));
+        if let Some(port) = plan.port {
+            content.push_str(&format!(
+                "        ports:\n        - containerPort: {}\n",
+                port
+            ));
+        }
+        content.push_str(&format!(
+            "        envFrom:\n        - configMapRef:\n            name: {}\n",
+            plan.config_name()
+        ));
+        if !plan.secret_keys().is_empty() {
+            content.push_str(&format!(
+                "        - secretRef:\n            name: {}\n",
+                plan.secret_name()
+            ));
+        }
+        if !interpolated.is_empty() {
+            content.push_str("        env:\n");
+            for (var, value) in interpolated {
+                content.push_str(&format!("        - name: {}\n", var));
+                content.push_str(&value.kubernetes(plan, "          "));
+            }
+        }
 
         Ok(InfrastructureFile {
             name: "helm-chart.yaml".to_string(),
@@ -346,35 +561,37 @@ spec:
     }
 
     /// Generiert AWS Lambda Config
-    fn generate_lambda(&self, _plan: &DeploymentPlan) -> Result<InfrastructureFile> {
-        let content = r#"{
-  "Runtime": "provided.al2",
-  "Handler": "bootstrap",
-  "MemorySize": 512,
-  "Timeout": 30,
-  "Environment": {
-    "Variables": {
-      "RUST_LOG": "info"
-    }
-  }
-}
-"#
-        .to_string();
+    fn generate_lambda(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
+        let mut variables = serde_json::Map::new();
+        variables.insert("RUST_LOG".to_string(), "info".into());
+        for var in &plan.env_vars {
+            if let (false, Some(default)) = (var.secret, &var.default) {
+                variables.insert(var.name.clone(), default.clone().into());
+            }
+        }
+        let config = serde_json::json!({
+            "FunctionName": plan.service_name,
+            "Runtime": "provided.al2",
+            "Handler": "bootstrap",
+            "MemorySize": 512,
+            "Timeout": 30,
+            "Environment": { "Variables": variables }
+        });
 
         Ok(InfrastructureFile {
             name: "lambda-config.json".to_string(),
-            content,
+            content: format!("{}\n", serde_json::to_string_pretty(&config)?),
         })
     }
 
     /// Generiert API Gateway Config
-    fn generate_api_gateway(&self, _plan: &DeploymentPlan) -> Result<InfrastructureFile> {
+    fn generate_api_gateway(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
         // Ersetze Platzhalter durch Umgebungsvariablen oder Config-Werte
         let account_id =
             std::env::var("AWS_ACCOUNT_ID").unwrap_or_else(|_| "${AWS_ACCOUNT_ID}".to_string());
         let region = std::env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string());
         let function_name =
-            std::env::var("LAMBDA_FUNCTION_NAME").unwrap_or_else(|_| "velin-api".to_string());
+            std::env::var("LAMBDA_FUNCTION_NAME").unwrap_or_else(|_| plan.service_name.clone());
 
         let content = format!(
             r#"{{
@@ -404,103 +621,23 @@ spec:
             content,
         })
     }
-
-    /// Generiert Redis Config
-    fn generate_redis(&self, plan: &DeploymentPlan) -> Result<InfrastructureFile> {
-        let _ = plan; // Für zukünftige Verwendung
-        let content = r#"apiVersion: v1
-kind: Service
-metadata:
-  name: redis
-spec:
-  ports:
-  - port: 6379
-    targetPort: 6379
-  selector:
-    app: redis
----
-apiVersion: apps/v1
-kind: Deployment
-metadata:
-  name: redis
-spec:
-  replicas: 1
-  selector:
-    matchLabels:
-      app: redis
-  template:
-    metadata:
-      labels:
-        app: redis
-    spec:
-      containers:
-      - name: redis
-        image: redis:alpine
-        ports:
-        - containerPort: 6379
-"#
-        .to_string();
-
-        Ok(InfrastructureFile {
-            name: "redis.yaml".to_string(),
-            content,
-        })
-    }
-
-    /// Generiert Database Config
-    fn generate_database(&self, _plan: &DeploymentPlan) -> Result<InfrastructureFile> {
-        let content = r#"apiVersion: v1
-kind: Service
-metadata:
-  name: postgres
-spec:
-  ports:
-  - port: 5432
-    targetPort: 5432
-  selector:
-    app: postgres
----
-apiVersion: apps/v1
-kind: Deployment
-metadata:
-  name: postgres
-spec:
-  replicas: 1
-  selector:
-    matchLabels:
-      app: postgres
-  template:
-    metadata:
-      labels:
-        app: postgres
-    spec:
-      containers:
-      - name: postgres
-        image: postgres:15
-        ports:
-        - containerPort: 5432
-        env:
-        - name: POSTGRES_PASSWORD
-          valueFrom:
-            secretKeyRef:
-              name: postgres-secret
-              key: password
-"#
-        .to_string();
-
-        Ok(InfrastructureFile {
-            name: "postgres.yaml".to_string(),
-            content,
-        })
-    }
 }
 
 /// Deployment-Plan für Infrastructure-Generierung
 #[derive(Debug, Clone)]
 pub struct DeploymentPlan {
     pub deployment_type: DeploymentType,
-    pub needs_caching: bool,
-    pub needs_database: bool,
+    /// Name des Services (DNS-tauglich, z.B. für Kubernetes-Ressourcen)
+    pub service_name: String,
+    /// HTTP-Port, falls der Service Routen anbietet
+    pub port: Option<u16>,
+    /// Backing Services, die der Code über die Standardbibliothek nutzt
+    pub services: Vec<BackingService>,
+    /// Umgebungsvariablen, die der Code über `env.*`/`config.*` liest
+    pub env_vars: Vec<EnvVar>,
+    pub health: HealthEndpoints,
+    /// Pfad einer `@GET("/metrics")`-Route
+    pub metrics_path: Option<String>,
     pub replicas: Option<u32>,
     pub memory_request: Option<String>,
     pub cpu_request: Option<String>,
@@ -508,6 +645,267 @@ pub struct DeploymentPlan {
     pub cpu_limit: Option<String>,
 }
 
+impl DeploymentPlan {
+    /// Setzt den Service-Namen; Zeichen außerhalb von `[a-z0-9-]` werden zu `-`
+    pub fn set_service_name(&mut self, name: &str) {
+        let name: String = name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        let name = name.trim_matches('-');
+        if !name.is_empty() {
+            self.service_name = name.to_string();
+        }
+    }
+
+    fn secret_name(&self) -> String {
+        format!("{}-secrets", self.service_name)
+    }
+
+    fn config_name(&self) -> String {
+        format!("{}-config", self.service_name)
+    }
+
+    /// Umgebung des Services: Secrets der Backing Services zuerst, damit
+    /// Verbindungs-URLs in Kubernetes per `$(VAR)` darauf verweisen können
+    fn app_env(&self, host: &dyn Fn(BackingService) -> String) -> Vec<(String, EnvValue)> {
+        let mut env: Vec<(String, EnvValue)> = Vec::new();
+        let mut push = |name: &str, value: EnvValue| {
+            if !env.iter().any(|(existing, _)| existing == name) {
+                env.push((name.to_string(), value));
+            }
+        };
+
+        for service in &self.services {
+            for key in service.secrets() {
+                push(key, EnvValue::Secret(key.to_string()));
+            }
+        }
+        for service in &self.services {
+            for (name, value) in service.connection_env(&host(*service)) {
+                push(name, EnvValue::Plain(value));
+            }
+        }
+        if let Some(port) = self.port {
+            push("PORT", EnvValue::Plain(port.to_string()));
+        }
+        for var in &self.env_vars {
+            let value = if var.secret {
+                EnvValue::Secret(var.name.clone())
+            } else if let Some(default) = &var.default {
+                EnvValue::Plain(default.clone())
+            } else {
+                EnvValue::Required(var.name.clone())
+            };
+            push(&var.name, value);
+        }
+        push("RUST_LOG", EnvValue::Plain("info".to_string()));
+        env
+    }
+
+    /// Secrets, die vor dem Deployment angelegt werden müssen
+    fn secret_keys(&self) -> Vec<String> {
+        self.app_env(&|service| service.name().to_string())
+            .into_iter()
+            .filter_map(|(_, value)| match value {
+                EnvValue::Secret(key) => Some(key),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Infrastruktur, die der Service zur Laufzeit braucht
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BackingService {
+    Postgres,
+    Redis,
+    MongoDB,
+    Smtp,
+    Qdrant,
+    Vault,
+}
+
+impl BackingService {
+    /// Hostname im Compose-Netz bzw. Name des Kubernetes-Services
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackingService::Postgres => "postgres",
+            BackingService::Redis => "redis",
+            BackingService::MongoDB => "mongodb",
+            BackingService::Smtp => "smtp",
+            BackingService::Qdrant => "qdrant",
+            BackingService::Vault => "vault",
+        }
+    }
+
+    pub fn image(&self) -> &'static str {
+        match self {
+            BackingService::Postgres => "postgres:16-alpine",
+            BackingService::Redis => "redis:7-alpine",
+            BackingService::MongoDB => "mongo:7",
+            BackingService::Smtp => "axllent/mailpit:latest",
+            BackingService::Qdrant => "qdrant/qdrant:latest",
+            BackingService::Vault => "hashicorp/vault:1.15",
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        match self {
+            BackingService::Postgres => 5432,
+            BackingService::Redis => 6379,
+            BackingService::MongoDB => 27017,
+            BackingService::Smtp => 1025,
+            BackingService::Qdrant => 6333,
+            BackingService::Vault => 8200,
+        }
+    }
+
+    /// Secrets, die Service und Backing Service teilen
+    fn secrets(&self) -> &'static [&'static str] {
+        match self {
+            BackingService::Postgres => &["POSTGRES_PASSWORD"],
+            BackingService::Vault => &["VAULT_TOKEN"],
+            _ => &[],
+        }
+    }
+
+    /// Variablen, über die der Service den Backing Service findet
+    fn connection_env(&self, host: &str) -> Vec<(&'static str, String)> {
+        let port = self.port();
+        match self {
+            BackingService::Postgres => vec![(
+                "DATABASE_URL",
+                format!(
+                    "postgres://velin:${{POSTGRES_PASSWORD}}@{}:{}/velin",
+                    host, port
+                ),
+            )],
+            BackingService::Redis => vec![("REDIS_URL", format!("redis://{}:{}", host, port))],
+            BackingService::MongoDB => {
+                vec![("MONGODB_URI", format!("mongodb://{}:{}", host, port))]
+            }
+            BackingService::Smtp => vec![
+                ("SMTP_HOST", host.to_string()),
+                ("SMTP_PORT", port.to_string()),
+            ],
+            BackingService::Qdrant => vec![("QDRANT_URL", format!("http://{}:{}", host, port))],
+            BackingService::Vault => vec![("VAULT_ADDR", format!("http://{}:{}", host, port))],
+        }
+    }
+
+    /// Umgebung des Backing-Service-Containers
+    fn container_env(&self) -> Vec<(&'static str, EnvValue)> {
+        match self {
+            BackingService::Postgres => vec![
+                ("POSTGRES_USER", EnvValue::Plain("velin".to_string())),
+                ("POSTGRES_DB", EnvValue::Plain("velin".to_string())),
+                (
+                    "POSTGRES_PASSWORD",
+                    EnvValue::Secret("POSTGRES_PASSWORD".to_string()),
+                ),
+            ],
+            BackingService::Vault => vec![
+                (
+                    "VAULT_DEV_ROOT_TOKEN_ID",
+                    EnvValue::Secret("VAULT_TOKEN".to_string()),
+                ),
+                (
+                    "VAULT_DEV_LISTEN_ADDRESS",
+                    EnvValue::Plain("0.0.0.0:8200".to_string()),
+                ),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Compose-Healthcheck als YAML-Liste
+    fn healthcheck(&self) -> Option<&'static str> {
+        match self {
+            BackingService::Postgres => Some(r#"["CMD-SHELL", "pg_isready -U velin"]"#),
+            BackingService::Redis => Some(r#"["CMD", "redis-cli", "ping"]"#),
+            BackingService::MongoDB => {
+                Some(r#"["CMD", "mongosh", "--quiet", "--eval", "db.adminCommand('ping')"]"#)
+            }
+            _ => None,
+        }
+    }
+
+    /// Volume für persistente Daten
+    fn data_dir(&self) -> Option<&'static str> {
+        match self {
+            BackingService::Postgres => Some("/var/lib/postgresql/data"),
+            BackingService::MongoDB => Some("/data/db"),
+            BackingService::Qdrant => Some("/qdrant/storage"),
+            _ => None,
+        }
+    }
+}
+
+/// Umgebungsvariable, die der Code liest
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvVar {
+    pub name: String,
+    /// Default aus dem Aufruf, z.B. `env.get("LOG_LEVEL", "info")`
+    pub default: Option<String>,
+    /// Über `env.get_secret` gelesen oder am Namen als Secret erkennbar
+    pub secret: bool,
+}
+
+/// Health-Endpunkte aus `@GET`-Routen
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthEndpoints {
+    pub liveness: Option<String>,
+    pub readiness: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum EnvValue {
+    Plain(String),
+    /// Aus einem Secret; in Compose Pflicht, in Kubernetes aus `<name>-secrets`
+    Secret(String),
+    /// Ohne Default gelesen; muss von außen gesetzt werden
+    Required(String),
+}
+
+impl EnvValue {
+    fn compose(&self) -> String {
+        match self {
+            EnvValue::Plain(value) => value.clone(),
+            EnvValue::Secret(key) => format!("${{{}:?{} muss gesetzt sein}}", key, key),
+            EnvValue::Required(key) => format!("${{{}}}", key),
+        }
+    }
+
+    fn kubernetes(&self, plan: &DeploymentPlan, indent: &str) -> String {
+        match self {
+            EnvValue::Plain(value) => format!(
+                "{}value: {}\n",
+                indent,
+                quote(&value.replace("${", "$(").replace('}', ")"))
+            ),
+            EnvValue::Secret(key) => format!(
+                "{i}valueFrom:\n{i}  secretKeyRef:\n{i}    name: {}\n{i}    key: {}\n",
+                plan.secret_name(),
+                key,
+                i = indent
+            ),
+            EnvValue::Required(key) => format!(
+                "{i}valueFrom:\n{i}  configMapKeyRef:\n{i}    name: {}\n{i}    key: {}\n{i}    optional: true\n",
+                plan.config_name(),
+                key,
+                i = indent
+            ),
+        }
+    }
+}
+
+/// YAML-String in doppelten Anführungszeichen (JSON-Strings sind gültiges YAML)
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
 /// Infrastructure-Code Container
 #[derive(Debug, Clone)]
 pub struct InfrastructureCode {
@@ -38,6 +38,9 @@ impl IRCodeGenerator {
             TargetLanguage::Go => Ok(self.generate_go(module)),
             TargetLanguage::Java => Ok(self.generate_java(module)),
             TargetLanguage::CSharp => Ok(self.generate_csharp(module)),
+            TargetLanguage::Wasm => Err(anyhow::anyhow!(
+                "WebAssembly wird über den AST-Codegen des Rust-Backends erzeugt"
+            )),
         }
     }
 
@@ -1,13 +1,16 @@
 use super::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::codegen::framework::{Framework, FrameworkSelector};
 use crate::parser::ast::*;
+use crate::validation::{self, Check, FieldValidation, RuleValue, ValueKind};
 use anyhow::Result;
+use std::collections::HashSet;
 
 pub struct JavaCodeGenerator {
     buffer: String,
     indent_level: usize,
     framework: Option<Framework>,
     package_name: String,
+    validated_structs: HashSet<String>,
 }
 
 impl JavaCodeGenerator {
@@ -17,6 +20,7 @@ impl JavaCodeGenerator {
             indent_level: 0,
             framework: None,
             package_name: "com.example.app".to_string(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -150,6 +154,9 @@ impl JavaCodeGenerator {
             params.join(", ")
         ));
         self.indent();
+        if self.framework == Some(Framework::Spring) {
+            self.generate_request_validation(f);
+        }
 
         // Body
         for stmt in &f.body.statements {
@@ -161,6 +168,76 @@ impl JavaCodeGenerator {
         self.writeln("");
     }
 
+    /// `validationErrors()` mit einer Map `{field, message}` pro verletzter `@Validate`-Regel
+    fn generate_validation_method(&mut self, s: &Struct) {
+        self.writeln("public List<Map<String, String>> validationErrors() {");
+        self.indent();
+        self.writeln("List<Map<String, String>> errors = new ArrayList<>();");
+        for field in validation::struct_validations(s) {
+            for check in field.checks() {
+                self.writeln(&format!("if ({}) {{", java_failure(&check.check, &field)));
+                self.indent();
+                self.writeln(&format!(
+                    "errors.add(Map.of(\"field\", {:?}, \"message\", {:?}));",
+                    field.name, check.message
+                ));
+                self.dedent();
+                self.writeln("}");
+            }
+        }
+        self.writeln("return errors;");
+        self.dedent();
+        self.writeln("}");
+    }
+
+    /// Exception für ungültige Request-Bodies und ihr 400-Handler
+    fn generate_validation_exception(&mut self) {
+        self.writeln("public static class ValidationException extends RuntimeException {");
+        self.indent();
+        self.writeln("public final List<Map<String, String>> errors;");
+        self.writeln("");
+        self.writeln("public ValidationException(List<Map<String, String>> errors) {");
+        self.indent();
+        self.writeln(&format!("super({:?});", validation::VALIDATION_FAILED));
+        self.writeln("this.errors = errors;");
+        self.dedent();
+        self.writeln("}");
+        self.dedent();
+        self.writeln("}");
+        self.writeln("");
+        self.writeln("@ExceptionHandler(ValidationException.class)");
+        self.writeln(
+            "public ResponseEntity<Map<String, Object>> handleValidation(ValidationException e) {",
+        );
+        self.indent();
+        self.writeln(&format!(
+            "return ResponseEntity.badRequest().body(Map.of(\"error\", {:?}, \"errors\", e.errors));",
+            validation::VALIDATION_FAILED
+        ));
+        self.dedent();
+        self.writeln("}");
+        self.writeln("");
+    }
+
+    /// Prüft `@RequestBody`-Parameter und wirft `ValidationException`
+    fn generate_request_validation(&mut self, f: &Function) {
+        let params: Vec<String> = validation::validated_params(f, &self.validated_structs)
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        for param in params {
+            self.writeln(&format!(
+                "List<Map<String, String>> {}Errors = {}.validationErrors();",
+                param, param
+            ));
+            self.writeln(&format!("if (!{}Errors.isEmpty()) {{", param));
+            self.indent();
+            self.writeln(&format!("throw new ValidationException({}Errors);", param));
+            self.dedent();
+            self.writeln("}");
+        }
+    }
+
     fn generate_statement(&mut self, stmt: &Statement) {
         match stmt {
             Statement::Return(ret) => {
@@ -327,6 +404,11 @@ impl CodeGenerator for JavaCodeGenerator {
             self.indent();
         }
 
+        self.validated_structs = validation::validated_structs(&program.items);
+        if self.framework == Some(Framework::Spring) && !self.validated_structs.is_empty() {
+            self.generate_validation_exception();
+        }
+
         // Structs need to be static nested classes if inside Main/App, or separate files.
         // For simplicity, static nested.
         for item in &program.items {
@@ -341,6 +423,10 @@ impl CodeGenerator for JavaCodeGenerator {
                         field.name
                     ));
                 }
+                if self.validated_structs.contains(&s.name) {
+                    self.writeln("");
+                    self.generate_validation_method(s);
+                }
                 self.dedent();
                 self.writeln("}");
                 self.writeln("");
@@ -363,3 +449,35 @@ impl CodeGenerator for JavaCodeGenerator {
         TargetLanguage::Java
     }
 }
+
+/// Java-Bedingung für eine verletzte Regel (Felder sind geboxt und damit nullable)
+fn java_failure(check: &Check, field: &FieldValidation) -> String {
+    let access = format!("this.{}", field.name);
+    let failed = match check {
+        Check::Required if field.kind == ValueKind::Text => {
+            return format!("{} == null || {}.isEmpty()", access, access)
+        }
+        Check::Required => return format!("{} == null", access),
+        Check::MinLength(n) => format!("{}.codePointCount(0, {}.length()) < {}", access, access, n),
+        Check::MaxLength(n) => format!("{}.codePointCount(0, {}.length()) > {}", access, access, n),
+        Check::MinItems(n) => format!("{}.size() < {}", access, n),
+        Check::MaxItems(n) => format!("{}.size() > {}", access, n),
+        Check::Pattern(pattern) => format!(
+            "!java.util.regex.Pattern.compile({:?}).matcher({}).find()",
+            pattern, access
+        ),
+        Check::Min(n) => format!("{} < {:?}", access, n),
+        Check::Max(n) => format!("{} > {:?}", access, n),
+        Check::OneOf(values) => {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| match v {
+                    RuleValue::String(s) => format!("{:?}", s),
+                    RuleValue::Number(n) => format!("{:?}", n),
+                })
+                .collect();
+            format!("!List.of({}).contains({})", values.join(", "), access)
+        }
+    };
+    format!("{} != null && {}", access, failed)
+}
@@ -1,7 +1,10 @@
 use super::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::codegen::framework::{Framework, FrameworkSelector};
+use crate::codegen::typescript::script_failure;
 use crate::parser::ast::*;
+use crate::validation;
 use anyhow::Result;
+use std::collections::HashSet;
 
 /// JavaScript Code Generator
 ///
@@ -12,6 +15,7 @@ pub struct JavaScriptCodeGenerator {
     indent_level: usize,
     framework: Option<Framework>,
     routes: Vec<(String, String, String)>, // (method, path, handler_name)
+    validated_structs: HashSet<String>,
 }
 
 impl JavaScriptCodeGenerator {
@@ -21,6 +25,7 @@ impl JavaScriptCodeGenerator {
             indent_level: 0,
             framework: None,
             routes: Vec::new(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -185,6 +190,61 @@ impl JavaScriptCodeGenerator {
         self.writeln("}");
     }
 
+    /// `validate<Struct>()` mit einem `{ field, message }` pro verletzter `@Validate`-Regel
+    fn generate_validator(&mut self, s: &Struct) {
+        self.writeln(&format!("function validate{}(value) {{", s.name));
+        self.indent();
+        self.writeln("const errors = [];");
+        for field in validation::struct_validations(s) {
+            let access = format!("value.{}", field.name);
+            for check in field.checks() {
+                let condition = script_failure(&check.check, &access, field.kind);
+                self.writeln(&format!("if ({}) {{", condition));
+                self.indent();
+                self.writeln(&format!(
+                    "errors.push({{ field: {:?}, message: {:?} }});",
+                    field.name, check.message
+                ));
+                self.dedent();
+                self.writeln("}");
+            }
+        }
+        self.writeln("return errors;");
+        self.dedent();
+        self.writeln("}");
+    }
+
+    /// Prüft den Request-Body von Express-Handlern und antwortet mit 400
+    fn generate_request_validation(&mut self, f: &Function) {
+        if self.framework != Some(Framework::Express) {
+            return;
+        }
+        let params: Vec<(String, String)> =
+            validation::validated_params(f, &self.validated_structs)
+                .iter()
+                .filter_map(|p| match &p.param_type {
+                    Type::Named(name) => Some((p.name.clone(), name.clone())),
+                    _ => None,
+                })
+                .collect();
+        for (param, struct_name) in params {
+            self.writeln(&format!(
+                "const {}Errors = validate{}(req.body);",
+                param, struct_name
+            ));
+            self.writeln(&format!("if ({}Errors.length > 0) {{", param));
+            self.indent();
+            self.writeln(&format!(
+                "res.status(400).json({{ error: '{}', errors: {}Errors }});",
+                validation::VALIDATION_FAILED,
+                param
+            ));
+            self.writeln("return;");
+            self.dedent();
+            self.writeln("}");
+        }
+    }
+
     fn add_route(&mut self, method: &str, decorator: &Decorator, function_name: &str) {
         if let Some(arg) = decorator.args.first() {
             if let DecoratorArg::String(path) = arg {
@@ -241,6 +301,9 @@ impl JavaScriptCodeGenerator {
             handler_params.join(", ")
         ));
         self.indent();
+        if is_handler {
+            self.generate_request_validation(f);
+        }
 
         // Generate body
         if f.body.statements.is_empty() {
@@ -390,10 +453,15 @@ impl CodeGenerator for JavaScriptCodeGenerator {
         }
 
         // Structs/Classes
+        self.validated_structs = validation::validated_structs(&program.items);
         for item in &program.items {
             if let Item::Struct(s) = item {
                 self.generate_struct(s);
                 self.writeln("");
+                if self.validated_structs.contains(&s.name) {
+                    self.generate_validator(s);
+                    self.writeln("");
+                }
             }
         }
 
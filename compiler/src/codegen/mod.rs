@@ -1,3 +1,4 @@
+pub mod asyncapi;
 pub mod autodoc;
 pub mod autotest;
 pub mod boilerplate;
@@ -11,6 +12,7 @@ pub mod ir_codegen;
 pub mod java;
 pub mod javascript;
 pub mod openapi;
+pub mod openapi_import;
 pub mod php;
 pub mod python;
 pub mod rust;
@@ -18,6 +20,7 @@ pub mod system_generator;
 pub mod templates;
 pub mod traits;
 pub mod typescript;
+pub mod wasm;
 
 pub use autodoc::AutoDocGenerator;
 pub use autotest::AutoTestGenerator;
@@ -27,14 +30,339 @@ pub use csharp::CSharpCodeGenerator;
 pub use distributed::DeploymentAnalyzer;
 pub use framework::{Framework, FrameworkSelector};
 pub use go::GoCodeGenerator;
-pub use infrastructure::{DeploymentPlan, InfrastructureCode, InfrastructureGenerator};
+pub use infrastructure::{
+    BackingService, DeploymentPlan, DeploymentType, InfrastructureCode, InfrastructureGenerator,
+};
 pub use ir_codegen::IRCodeGenerator;
 pub use java::JavaCodeGenerator;
 pub use javascript::JavaScriptCodeGenerator;
 pub use openapi::OpenAPIGenerator;
+pub use openapi_import::OpenAPIImporter;
 pub use php::PhpCodeGenerator;
 pub use python::PythonCodeGenerator;
 pub use rust::RustCodeGenerator;
 pub use system_generator::{APICall, GeneratedSystem, SystemGenerator};
 pub use traits::{CodeGenerator, CodegenConfig, TargetLanguage};
 pub use typescript::TypeScriptCodeGenerator;
+pub use wasm::{WasmCodeGenerator, WasmPackage};
+
+/// Generiert Rust-Code für `program` und prüft, dass `syn` das Ergebnis parsen kann.
+///
+/// Prüft nur die Syntax: Typfehler oder fehlende Imports im generierten Code fallen nicht auf.
+#[cfg(test)]
+pub(crate) fn generate_parsed_rust(
+    program: &crate::parser::ast::Program,
+    framework: Option<&str>,
+) -> String {
+    let config = CodegenConfig {
+        target: TargetLanguage::Rust,
+        framework: framework.map(str::to_string),
+        orm: None,
+        output_path: None,
+    };
+    let code = CodeGenerator::generate(&mut RustCodeGenerator::new(), program, &config)
+        .expect("codegen should succeed");
+    assert_parses_as_rust(&code);
+    code
+}
+
+/// Bricht mit Zeile und Umgebung ab, wenn sich `code` nicht als Rust parsen lässt
+#[cfg(test)]
+pub(crate) fn assert_parses_as_rust(code: &str) {
+    if let Err(error) = syn::parse_file(code) {
+        let line = error.span().start().line;
+        let context: Vec<String> = code
+            .lines()
+            .enumerate()
+            .skip(line.saturating_sub(4))
+            .take(7)
+            .map(|(index, text)| format!("{:>5} | {}", index + 1, text))
+            .collect();
+        panic!(
+            "generated code does not parse as Rust: {} at line {}\n{}",
+            error,
+            line,
+            context.join("\n")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    /// Service, der eine Stdlib-Runtime braucht, und Stellen, die im generierten
+    /// Code vorkommen müssen
+    struct RuntimeService {
+        feature: &'static str,
+        source: &'static str,
+        expected: &'static [&'static str],
+    }
+
+    const RUNTIME_SERVICES: &[RuntimeService] = &[
+        RuntimeService {
+            feature: "workflow",
+            source: r#"@Step(retries: 3, compensate: "refund")
+fn charge(orderId: string): string {
+    return orderId;
+}
+
+fn refund(payment: string): boolean {
+    return true;
+}
+
+@POST("/orders/:id/checkout")
+fn checkout(id: string): any {
+    let definition = workflow.create({
+        name: "checkout",
+        store: "redis://localhost:6379",
+        steps: ["charge"],
+    });
+    return workflow.run(definition, id);
+}
+"#,
+            expected: &[
+                "pub mod workflow {",
+                "pub struct RedisStore",
+                "super::super::charge(arg0)",
+            ],
+        },
+        RuntimeService {
+            feature: "scheduler",
+            source: r#"@Scheduled("0 */5 * * * *", overlap: "skip")
+fn cleanupSessions() {
+    let x = 1;
+}
+
+fn report(day: string) {
+    let x = day;
+}
+
+@POST("/reports")
+fn scheduleReport(): string {
+    scheduler.schedule({ name: "daily report", run: "report", input: "today" }, "0 0 6 * * *");
+    return "ok";
+}
+
+fn main() {
+    let x = 1;
+}
+"#,
+            expected: &[
+                "pub mod scheduler {",
+                "super::super::cleanup_sessions()",
+                "super::super::report(",
+                "crate::stdlib::scheduler::start_scheduled();",
+            ],
+        },
+        RuntimeService {
+            feature: "event_bus",
+            source: r#"@Event("orders.created")
+struct OrderCreated {
+    id: string,
+    total: number,
+}
+
+@Subscribe(retries: 2, backoff: "500ms")
+fn onOrderCreated(event: OrderCreated): Result<boolean, string> {
+    return Ok(true);
+}
+
+@POST("/orders")
+fn createOrder(order: OrderCreated): string {
+    let bus = event_bus.connect("nats://localhost:4222");
+    event_bus.publish(bus, "orders.created", order);
+    return order.id;
+}
+
+fn main() {
+    let x = 1;
+}
+"#,
+            expected: &[
+                "pub mod event_bus {",
+                "pub mod transport {",
+                "NatsTransport::open(url)",
+                "super::super::on_order_created(",
+                "crate::stdlib::event_bus::start_subscribers();",
+            ],
+        },
+        RuntimeService {
+            feature: "tracing",
+            source: r#"@GET("/orders/:id/price")
+fn price(id: string): number {
+    let span = tracing.start_span("pricing");
+    tracing.set_attribute(span, "order.id", id);
+    tracing.end_span(span);
+    return 42;
+}
+
+@GET("/health")
+async fn health(): string {
+    let client = HttpClient.new();
+    let stock = client.get("http://inventory:8080/health");
+    return "ok";
+}
+
+fn main() {
+    tracing.export("http://localhost:4318");
+}
+"#,
+            expected: &[
+                "pub mod tracing {",
+                "pub mod context {",
+                "__span.traceparent()",
+            ],
+        },
+        RuntimeService {
+            feature: "audit",
+            source: r#"@POST("/login")
+fn login(userId: string): string {
+    audit.log({ action: "user.login", user_id: userId, ip_address: "192.168.1.1" });
+    return userId;
+}
+
+@GET("/audit")
+fn history(userId: string): any {
+    return audit.query({ user_id: userId, from: "2026-01-01T00:00:00Z", limit: 50 });
+}
+
+@GET("/audit/verify")
+fn verify(): any {
+    return audit.verify();
+}
+
+@DELETE("/orders/:id")
+@Audited("orders.delete", user: "userId")
+async fn deleteOrder(id: string, userId: string): boolean {
+    return true;
+}
+"#,
+            expected: &[
+                "pub mod audit {",
+                "pub mod store {",
+                "Invocation::start(\"orders.delete\", \"deleteOrder\"",
+            ],
+        },
+        RuntimeService {
+            feature: "realtime",
+            source: r#"struct ChatMessage {
+    text: string,
+}
+
+struct NewsItem {
+    title: string,
+}
+
+@Auth
+@WebSocket("/chat/:room")
+fn chat(conn: Connection<ChatMessage>, message: ChatMessage, room: string): ChatMessage {
+    conn.broadcast(room, message);
+    return message;
+}
+
+@OnConnect("/chat/:room")
+fn joined(conn: Connection<ChatMessage>, room: string) {
+    conn.join(room);
+}
+
+@OnDisconnect("/chat/:room")
+async fn left(room: string) {
+    let x = room;
+}
+
+@SSE("/news")
+fn news(conn: Connection<NewsItem>) {
+    conn.join("news");
+}
+
+@GET("/health")
+fn health(): string {
+    return "ok";
+}
+"#,
+            expected: &[
+                "pub mod realtime {",
+                "pub mod hub {",
+                "conn.broadcast(&room, &message)",
+            ],
+        },
+        RuntimeService {
+            feature: "template",
+            source: r#"struct User {
+    name: string,
+    admin: boolean,
+    tags: List<string>,
+}
+
+@GET("/users/:name")
+fn profile(name: string): string {
+    let user = User { name: name, admin: false, tags: ["new"] };
+    return template.render("<h1>\{\{ name \}\}</h1>\{\{#if admin\}\}<b>admin</b>\{\{/if\}\}<ul>\{\{#each tags as tag\}\}<li>\{\{ tag \}\}</li>\{\{else\}\}<li>-</li>\{\{/each\}\}</ul>", user);
+}
+
+@POST("/preview")
+fn preview(body: string): string {
+    return template.render(body, { name: "John" });
+}
+"#,
+            expected: &["pub mod template {", "for __item_1 in __items_1 {"],
+        },
+        RuntimeService {
+            feature: "stream",
+            source: r#"@Event("orders.placed")
+struct OrderPlaced {
+    id: string,
+    total: number,
+}
+
+struct Totals {
+    count: number,
+}
+
+fn summarize(orders: List<OrderPlaced>): Totals {
+    return Totals { count: 0 };
+}
+
+@GET("/api/orders/live")
+fn liveOrders(): Stream<OrderPlaced> {
+    let events = stream.from_topic(event_bus.create(), "orders.placed", 32);
+    return stream.filter(events, (e: OrderPlaced) => e.total > 100);
+}
+
+@SSE("/orders/totals")
+fn totals(conn: Connection<Totals>) {
+    let events = stream.from_topic(event_bus.create(), "orders.placed");
+    let windows = stream.window(events, 50, 1000);
+    stream.send_to(stream.map(windows, summarize), conn);
+}
+"#,
+            expected: &[
+                "pub mod stream {",
+                "pub fn ndjson_line<",
+                "pub mod realtime {",
+                "pub mod event_bus {",
+            ],
+        },
+    ];
+
+    #[test]
+    fn test_stdlib_runtimes_parse_as_rust_for_every_framework() {
+        for service in RUNTIME_SERVICES {
+            let program = Parser::parse(service.source).unwrap();
+            for framework in ["axum", "actix"] {
+                let code = generate_parsed_rust(&program, Some(framework));
+                for expected in service.expected {
+                    assert!(
+                        code.contains(expected),
+                        "{} ({}): `{}` fehlt",
+                        service.feature,
+                        framework,
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,695 +1,1471 @@
+// OpenAPI 3.1 Generator
+// Erzeugt aus Route-Decorators, Structs und Enums ein OpenAPI-Dokument (JSON oder YAML)
+
 use crate::parser::ast::*;
+use crate::validation::{self, FieldValidation, RuleValue, ValueKind};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
-pub struct OpenAPIGenerator {
-    #[allow(dead_code)]
-    output: String,
+pub const OPENAPI_VERSION: &str = "3.1.0";
+
+/// Gemeinsames Fehler-Schema aller Error-Responses (`{"error", "errors"}`)
+pub const ERROR_SCHEMA: &str = "ErrorResponse";
+
+pub(crate) const SCHEMA_PREFIX: &str = "#/components/schemas/";
+const ROUTE_METHODS: &[&str] = &["get", "post", "put", "patch", "delete"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenAPIFormat {
+    Json,
+    Yaml,
+}
+
+impl OpenAPIFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "json" => Some(OpenAPIFormat::Json),
+            "yaml" | "yml" => Some(OpenAPIFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Format anhand der Dateiendung (`.yaml`/`.yml`), sonst JSON
+    pub fn from_path(path: &std::path::Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::parse)
+            .unwrap_or(OpenAPIFormat::Json)
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OpenAPIFormat::Json => "openapi.json",
+            OpenAPIFormat::Yaml => "openapi.yaml",
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenAPISpec {
     pub openapi: String,
     pub info: OpenAPIInfo,
-    pub paths: std::collections::HashMap<String, PathItem>,
-    pub components: Option<Components>,
+    #[serde(default)]
+    pub paths: BTreeMap<String, PathItem>,
+    #[serde(default)]
+    pub components: Components,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Components {
-    pub schemas: std::collections::HashMap<String, Schema>,
-    pub security_schemes: std::collections::HashMap<String, SecurityScheme>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub schemas: BTreeMap<String, Schema>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub security_schemes: BTreeMap<String, SecurityScheme>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenAPIInfo {
     pub title: String,
     pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PathItem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub get: Option<Operation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub post: Option<Operation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub put: Option<Operation>,
-    pub delete: Option<Operation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub patch: Option<Operation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delete: Option<Operation>,
+}
+
+impl PathItem {
+    /// Alle Operationen mit ihrer HTTP-Methode (klein geschrieben)
+    pub fn operations(&self) -> Vec<(&'static str, &Operation)> {
+        [
+            ("get", &self.get),
+            ("post", &self.post),
+            ("put", &self.put),
+            ("patch", &self.patch),
+            ("delete", &self.delete),
+        ]
+        .into_iter()
+        .filter_map(|(method, op)| op.as_ref().map(|op| (method, op)))
+        .collect()
+    }
+
+    fn slot(&mut self, method: &str) -> &mut Option<Operation> {
+        match method {
+            "get" => &mut self.get,
+            "post" => &mut self.post,
+            "put" => &mut self.put,
+            "patch" => &mut self.patch,
+            _ => &mut self.delete,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Operation {
+    #[serde(default)]
+    pub operation_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    pub operation_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub parameters: Vec<Parameter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub request_body: Option<RequestBody>,
-    pub responses: std::collections::HashMap<String, Response>,
+    pub responses: BTreeMap<String, Response>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub security: Vec<SecurityRequirement>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deprecated: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Parameter {
+    pub name: String,
+    #[serde(rename = "in")]
+    pub location: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub schema: Schema,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestBody {
-    pub content: std::collections::HashMap<String, MediaType>,
+    #[serde(default)]
+    pub required: bool,
+    pub content: BTreeMap<String, MediaType>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MediaType {
     pub schema: Schema,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Response {
     pub description: String,
-    pub content: Option<std::collections::HashMap<String, MediaType>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<BTreeMap<String, MediaType>>,
 }
 
-#[derive(Debug, Clone)]
-pub struct SecurityRequirement {
-    pub name: String,
-    pub scopes: Vec<String>,
-}
+/// Scheme-Name → Scopes (bei `bearerAuth` die geforderten Rollen)
+pub type SecurityRequirement = BTreeMap<String, Vec<String>>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SecurityScheme {
+    #[serde(rename = "type")]
     pub scheme_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scheme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bearer_format: Option<String>,
-    pub name: Option<String>,
-    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flows: Option<OAuthFlows>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_id_connect_url: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlows {
+    pub authorization_code: OAuthFlow,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthFlow {
+    pub authorization_url: String,
+    pub token_url: String,
+    #[serde(default)]
+    pub scopes: BTreeMap<String, String>,
+}
+
+/// `type` ist in OpenAPI 3.1 ein String oder – für nullable Werte – eine Liste
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaType {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+/// JSON-Schema (Draft 2020-12) eines Wertes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Schema {
-    pub schema_type: String,
+    #[serde(rename = "$ref", default, skip_serializing_if = "Option::is_none")]
+    pub ref_path: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub schema_type: Option<SchemaType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub items: Option<Box<Schema>>,
-    pub properties: Option<std::collections::HashMap<String, Schema>>,
-    pub ref_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefix_items: Vec<Schema>,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        with = "ordered_properties"
+    )]
+    pub properties: Vec<(String, Schema)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_properties: Option<Box<Schema>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required: Vec<String>,
+    #[serde(rename = "enum", default, skip_serializing_if = "Vec::is_empty")]
+    pub enum_values: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub one_of: Vec<Schema>,
+    // Constraints aus @Validate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_items: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<Value>,
+}
+
+impl Schema {
+    pub fn of(schema_type: &str) -> Self {
+        Schema {
+            schema_type: Some(SchemaType::Single(schema_type.to_string())),
+            ..Default::default()
+        }
+    }
+
+    pub fn reference(component: &str) -> Self {
+        Schema {
+            ref_path: Some(format!("{}{}", SCHEMA_PREFIX, component)),
+            ..Default::default()
+        }
+    }
+
+    /// Name der referenzierten Komponente (`#/components/schemas/X` → `X`)
+    pub fn component(&self) -> Option<&str> {
+        self.ref_path
+            .as_deref()
+            .and_then(|r| r.strip_prefix(SCHEMA_PREFIX))
+    }
+
+    /// Der primäre Typ ohne `"null"`
+    pub fn primary_type(&self) -> Option<&str> {
+        match &self.schema_type {
+            Some(SchemaType::Single(t)) => Some(t),
+            Some(SchemaType::Multiple(types)) => {
+                types.iter().map(String::as_str).find(|t| *t != "null")
+            }
+            None => None,
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        let null = Some(SchemaType::Single("null".to_string()));
+        matches!(&self.schema_type, Some(SchemaType::Multiple(types)) if types.iter().any(|t| t == "null"))
+            || self.one_of.iter().any(|s| s.schema_type == null)
+    }
+
+    /// `T | null`: erweitert `type` oder umschließt Referenzen mit `oneOf`
+    pub fn nullable(self) -> Self {
+        match self.schema_type.clone() {
+            Some(SchemaType::Single(t)) if self.ref_path.is_none() && self.one_of.is_empty() => {
+                let mut schema = self;
+                schema.schema_type = Some(SchemaType::Multiple(vec![t, "null".to_string()]));
+                if !schema.enum_values.is_empty() {
+                    schema.enum_values.push(Value::Null);
+                }
+                schema
+            }
+            Some(SchemaType::Multiple(_)) => self,
+            _ if self == Schema::default() => self,
+            _ => Schema {
+                one_of: vec![self, Schema::of("null")],
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Properties in Deklarationsreihenfolge statt alphabetisch
+mod ordered_properties {
+    use super::Schema;
+    use serde::de::{MapAccess, Visitor};
+    use serde::ser::SerializeMap;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        properties: &[(String, Schema)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(properties.len()))?;
+        for (name, schema) in properties {
+            map.serialize_entry(name, schema)?;
+        }
+        map.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(String, Schema)>, D::Error> {
+        struct PropertiesVisitor;
+
+        impl<'de> Visitor<'de> for PropertiesVisitor {
+            type Value = Vec<(String, Schema)>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a map of property schemas")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut properties = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    properties.push(entry);
+                }
+                Ok(properties)
+            }
+        }
+
+        deserializer.deserialize_map(PropertiesVisitor)
+    }
+}
+
+pub struct OpenAPIGenerator {
+    pub title: String,
+    pub version: String,
+    pub description: Option<String>,
 }
 
 impl OpenAPIGenerator {
     pub fn new() -> Self {
         OpenAPIGenerator {
-            output: String::new(),
+            title: "VelinScript API".to_string(),
+            version: "1.0.0".to_string(),
+            description: Some("API generated from VelinScript".to_string()),
         }
     }
 
+    /// Dokument als JSON
     pub fn generate(&mut self, program: &Program) -> String {
+        render(&self.build(program), OpenAPIFormat::Json).unwrap_or_default()
+    }
+
+    pub fn build(&self, program: &Program) -> OpenAPISpec {
+        let mut schemas = SchemaBuilder::new(&program.items);
         let mut spec = OpenAPISpec {
-            openapi: "3.0.0".to_string(),
+            openapi: OPENAPI_VERSION.to_string(),
             info: OpenAPIInfo {
-                title: "VelinScript API".to_string(),
-                version: "1.0.0".to_string(),
-                description: Some("API generated from VelinScript".to_string()),
+                title: self.title.clone(),
+                version: self.version.clone(),
+                description: self.description.clone(),
             },
-            paths: std::collections::HashMap::new(),
-            components: Some(Components {
-                schemas: std::collections::HashMap::new(),
-                security_schemes: std::collections::HashMap::new(),
-            }),
+            paths: BTreeMap::new(),
+            components: Components::default(),
         };
 
-        // Extract structs for components/schemas
-        for item in &program.items {
-            if let Item::Struct(s) = item {
-                self.extract_struct_schema(&mut spec, s);
+        // Alle Structs und Enums ohne Typparameter landen in components/schemas,
+        // generische nur in den verwendeten Instanziierungen
+        for def in schemas.structs.clone().values() {
+            if def.type_params.is_empty() {
+                schemas.struct_component(def, &[]);
             }
         }
+        for def in schemas.enums.clone().values() {
+            schemas.enum_component(def);
+        }
 
-        // Extract security schemes
-        self.extract_security_schemes(&mut spec, program);
-
-        // Extract API endpoints from functions
-        for item in &program.items {
-            if let Item::Function(f) = item {
-                self.extract_endpoint(&mut spec, f);
+        let mut operation_ids = HashSet::new();
+        for (function, module) in functions(&program.items, None) {
+            for (method, path) in routes(function) {
+                let operation = self.build_operation(
+                    &mut schemas,
+                    &mut spec.components.security_schemes,
+                    function,
+                    module,
+                    method,
+                    &path,
+                    &mut operation_ids,
+                );
+                *spec
+                    .paths
+                    .entry(openapi_path(&path))
+                    .or_default()
+                    .slot(method) = Some(operation);
             }
         }
 
-        // Generate JSON
-        self.generate_json(&spec)
+        spec.components.schemas = schemas.finish();
+        spec
     }
 
-    fn extract_struct_schema(&self, spec: &mut OpenAPISpec, struct_def: &Struct) {
-        if let Some(ref mut components) = spec.components {
-            let mut properties = std::collections::HashMap::new();
-
-            for field in &struct_def.fields {
-                let field_schema = self.type_to_schema(&field.field_type);
-                properties.insert(field.name.clone(), field_schema);
-            }
-
-            let schema = Schema {
-                schema_type: "object".to_string(),
-                format: None,
-                items: None,
-                properties: Some(properties),
-                ref_path: None,
+    #[allow(clippy::too_many_arguments)]
+    fn build_operation(
+        &self,
+        schemas: &mut SchemaBuilder,
+        security_schemes: &mut BTreeMap<String, SecurityScheme>,
+        function: &Function,
+        module: Option<&str>,
+        method: &str,
+        path: &str,
+        operation_ids: &mut HashSet<String>,
+    ) -> Operation {
+        let mut summary = None;
+        let mut description = None;
+        let mut tags = Vec::new();
+        let mut deprecated = false;
+        for decorator in &function.decorators {
+            let first = || match decorator.args.first() {
+                Some(DecoratorArg::String(s)) => Some(s.clone()),
+                _ => None,
             };
-
-            components.schemas.insert(struct_def.name.clone(), schema);
+            match decorator.name.trim_start_matches('@') {
+                "Summary" => summary = first(),
+                "Description" => description = first(),
+                "Tag" | "Tags" => tags.extend(decorator.args.iter().filter_map(|a| match a {
+                    DecoratorArg::String(s) => Some(s.clone()),
+                    _ => None,
+                })),
+                "Deprecated" => deprecated = true,
+                _ => {}
+            }
+        }
+        if let Some(doc) = function.documentation.as_deref().map(str::trim) {
+            if summary.is_none() {
+                summary = doc.lines().next().map(|line| line.trim().to_string());
+            }
+            if description.is_none() && doc.lines().count() > 1 {
+                description = Some(doc.to_string());
+            }
+        }
+        if tags.is_empty() {
+            if let Some(module) = module {
+                tags.push(module.to_string());
+            }
         }
-    }
 
-    fn extract_security_schemes(&self, spec: &mut OpenAPISpec, program: &Program) {
-        let mut has_auth = false;
-        let mut has_oauth2 = false;
+        let mut operation_id = function.name.clone();
+        if !operation_ids.insert(operation_id.clone()) {
+            operation_id = format!("{}_{}", function.name, method);
+            operation_ids.insert(operation_id.clone());
+        }
 
-        for item in &program.items {
-            if let Item::Function(f) = item {
-                for decorator in &f.decorators {
-                    match decorator.name.as_str() {
-                        "Auth" => has_auth = true,
-                        "OAuth2" | "OIDC" => has_oauth2 = true,
-                        _ => {}
-                    }
-                }
+        // Parameter: Pfad, Query, Body (erster nicht-primitiver Parameter)
+        let path_params = path_params(path);
+        let allows_body = matches!(method, "post" | "put" | "patch");
+        let mut parameters = Vec::new();
+        let mut request_body = None;
+        for param in &function.params {
+            let schema = schemas.type_schema(&param.param_type, &HashMap::new());
+            let optional =
+                validation::unwrap_optional(&param.param_type).1 || param.default.is_some();
+            if path_params.contains(&param.name) {
+                parameters.push(Parameter {
+                    name: param.name.clone(),
+                    location: "path".to_string(),
+                    required: true,
+                    description: None,
+                    schema,
+                });
+            } else if allows_body && request_body.is_none() && !is_scalar(&param.param_type) {
+                let mut content = BTreeMap::new();
+                content.insert("application/json".to_string(), MediaType { schema });
+                request_body = Some(RequestBody {
+                    required: !optional,
+                    content,
+                });
+            } else {
+                parameters.push(Parameter {
+                    name: param.name.clone(),
+                    location: "query".to_string(),
+                    required: !optional,
+                    description: None,
+                    schema,
+                });
+            }
+        }
+        // Pfad-Parameter ohne gleichnamigen Funktionsparameter
+        for name in &path_params {
+            if !parameters
+                .iter()
+                .any(|p| &p.name == name && p.location == "path")
+            {
+                parameters.push(Parameter {
+                    name: name.clone(),
+                    location: "path".to_string(),
+                    required: true,
+                    description: None,
+                    schema: Schema::of("string"),
+                });
             }
         }
 
-        if let Some(ref mut components) = spec.components {
-            if has_auth {
-                components.security_schemes.insert(
-                    "bearerAuth".to_string(),
-                    SecurityScheme {
-                        scheme_type: "http".to_string(),
-                        scheme: Some("bearer".to_string()),
-                        bearer_format: Some("JWT".to_string()),
-                        name: None,
-                        location: None,
+        let security = security(function, security_schemes);
+        let secured = !security.is_empty();
+        let has_roles = security
+            .iter()
+            .any(|req| req.values().any(|s| !s.is_empty()));
+
+        let mut responses = BTreeMap::new();
+        match function.return_type.as_ref().map(success_type) {
+            None | Some(Type::Void) => {
+                responses.insert(
+                    "204".to_string(),
+                    Response {
+                        description: "No Content".to_string(),
+                        content: None,
                     },
                 );
             }
-
-            if has_oauth2 {
-                components.security_schemes.insert(
-                    "oauth2".to_string(),
-                    SecurityScheme {
-                        scheme_type: "oauth2".to_string(),
-                        scheme: None,
-                        bearer_format: None,
-                        name: None,
-                        location: None,
+            // `Stream<T>`: eine JSON-Zeile je Element
+            Some(Type::Stream(item)) => {
+                let schema = schemas.type_schema(item, &HashMap::new());
+                let mut content = BTreeMap::new();
+                content.insert("application/x-ndjson".to_string(), MediaType { schema });
+                responses.insert(
+                    "200".to_string(),
+                    Response {
+                        description: "Success".to_string(),
+                        content: Some(content),
+                    },
+                );
+            }
+            Some(ty) => {
+                let schema = schemas.type_schema(ty, &HashMap::new());
+                responses.insert(
+                    "200".to_string(),
+                    Response {
+                        description: "Success".to_string(),
+                        content: Some(json_content(schema)),
                     },
                 );
             }
         }
+        let mut error = |status: &str, description: &str| {
+            responses.insert(
+                status.to_string(),
+                Response {
+                    description: description.to_string(),
+                    content: Some(json_content(Schema::reference(ERROR_SCHEMA))),
+                },
+            );
+        };
+        if !parameters.is_empty() || request_body.is_some() {
+            error("400", "Bad Request - Validation Error");
+        }
+        if secured {
+            error("401", "Unauthorized");
+        }
+        if has_roles {
+            error("403", "Forbidden");
+        }
+        if !path_params.is_empty() {
+            error("404", "Not Found");
+        }
+        error("500", "Internal Server Error");
+        schemas.use_error_schema();
+
+        Operation {
+            operation_id,
+            summary,
+            description,
+            tags,
+            parameters,
+            request_body,
+            responses,
+            security,
+            deprecated,
+        }
     }
+}
 
-    fn extract_endpoint(&self, spec: &mut OpenAPISpec, function: &Function) {
-        let mut path: Option<String> = None;
-        let mut method: Option<String> = None;
-        let mut has_auth = false;
-        let mut roles: Vec<String> = Vec::new();
-        let mut summary: Option<String> = None;
-        let mut description: Option<String> = None;
-        let mut tags: Vec<String> = Vec::new();
+/// Serialisiert das Dokument
+pub fn render(spec: &OpenAPISpec, format: OpenAPIFormat) -> anyhow::Result<String> {
+    Ok(match format {
+        OpenAPIFormat::Json => serde_json::to_string_pretty(spec)? + "\n",
+        OpenAPIFormat::Yaml => serde_yaml::to_string(spec)?,
+    })
+}
 
-        // Extract decorators
-        for decorator in &function.decorators {
-            match decorator.name.as_str() {
-                "GET" | "POST" | "PUT" | "DELETE" | "PATCH" => {
-                    method = Some(decorator.name.to_lowercase());
-                    if let Some(DecoratorArg::String(p)) = decorator.args.first() {
-                        path = Some(p.clone());
+/// Strukturelle Prüfung gegen die OpenAPI-3.1-Regeln, die der Generator
+/// verletzen könnte: Referenzen, Pfad-Parameter, eindeutige operationIds, Security.
+pub fn validate(spec: &OpenAPISpec) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !spec.openapi.starts_with("3.1") {
+        errors.push(format!(
+            "openapi version must be 3.1.x, found {}",
+            spec.openapi
+        ));
+    }
+    if spec.info.title.is_empty() || spec.info.version.is_empty() {
+        errors.push("info.title and info.version are required".to_string());
+    }
+
+    let mut refs = Vec::new();
+    for (name, schema) in &spec.components.schemas {
+        collect_refs(schema, &format!("components.schemas.{}", name), &mut refs);
+    }
+
+    let mut operation_ids = HashSet::new();
+    for (path, item) in &spec.paths {
+        if !path.starts_with('/') {
+            errors.push(format!("path '{}' must start with '/'", path));
+        }
+        let template = path_params(path);
+        for (method, op) in item.operations() {
+            let at = format!("{} {}", method.to_uppercase(), path);
+            if !operation_ids.insert(op.operation_id.clone()) {
+                errors.push(format!(
+                    "{}: duplicate operationId '{}'",
+                    at, op.operation_id
+                ));
+            }
+            if op.responses.is_empty() {
+                errors.push(format!("{}: at least one response is required", at));
+            }
+
+            let mut seen = HashSet::new();
+            for param in &op.parameters {
+                if !seen.insert((param.name.clone(), param.location.clone())) {
+                    errors.push(format!("{}: duplicate parameter '{}'", at, param.name));
+                }
+                match param.location.as_str() {
+                    "path" => {
+                        if !param.required {
+                            errors.push(format!(
+                                "{}: path parameter '{}' must be required",
+                                at, param.name
+                            ));
+                        }
+                        if !template.contains(&param.name) {
+                            errors.push(format!(
+                                "{}: path parameter '{}' does not appear in the path",
+                                at, param.name
+                            ));
+                        }
                     }
+                    "query" | "header" | "cookie" => {}
+                    other => errors.push(format!(
+                        "{}: parameter '{}' has invalid location '{}'",
+                        at, param.name, other
+                    )),
                 }
-                "Auth" => {
-                    has_auth = true;
+                collect_refs(&param.schema, &at, &mut refs);
+            }
+            for name in &template {
+                if !op
+                    .parameters
+                    .iter()
+                    .any(|p| &p.name == name && p.location == "path")
+                {
+                    errors.push(format!("{}: path parameter '{}' is not declared", at, name));
                 }
-                "Role" => {
-                    if let Some(DecoratorArg::String(role)) = decorator.args.first() {
-                        roles.push(role.clone());
-                    }
+            }
+
+            if let Some(body) = &op.request_body {
+                for media in body.content.values() {
+                    collect_refs(&media.schema, &at, &mut refs);
                 }
-                "Summary" | "@Summary" => {
-                    if let Some(DecoratorArg::String(s)) = decorator.args.first() {
-                        summary = Some(s.clone());
-                    }
+            }
+            for (status, response) in &op.responses {
+                if status != "default"
+                    && !(status.len() == 3
+                        && status.chars().all(|c| c.is_ascii_digit() || c == 'X'))
+                {
+                    errors.push(format!("{}: invalid response status '{}'", at, status));
                 }
-                "Description" | "@Description" => {
-                    if let Some(DecoratorArg::String(d)) = decorator.args.first() {
-                        description = Some(d.clone());
-                    }
+                for media in response.content.iter().flat_map(|c| c.values()) {
+                    collect_refs(&media.schema, &at, &mut refs);
                 }
-                "Tag" | "@Tag" => {
-                    if let Some(DecoratorArg::String(tag)) = decorator.args.first() {
-                        tags.push(tag.clone());
+            }
+
+            for requirement in &op.security {
+                for (scheme_name, scopes) in requirement {
+                    match spec.components.security_schemes.get(scheme_name) {
+                        None => errors
+                            .push(format!("{}: unknown security scheme '{}'", at, scheme_name)),
+                        Some(scheme) => {
+                            if let Some(flows) = &scheme.flows {
+                                for scope in scopes {
+                                    if !flows.authorization_code.scopes.contains_key(scope) {
+                                        errors.push(format!(
+                                            "{}: scope '{}' is not defined by '{}'",
+                                            at, scope, scheme_name
+                                        ));
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
-                _ => {}
             }
         }
+    }
 
-        if let (Some(path_str), Some(method_str)) = (path, method) {
-            let path_item = spec
-                .paths
-                .entry(path_str.clone())
-                .or_insert_with(|| PathItem {
-                    get: None,
-                    post: None,
-                    put: None,
-                    delete: None,
-                    patch: None,
-                });
+    for (at, component) in refs {
+        if !spec.components.schemas.contains_key(&component) {
+            errors.push(format!(
+                "{}: unresolved $ref '{}{}'",
+                at, SCHEMA_PREFIX, component
+            ));
+        }
+    }
+    errors
+}
 
-            let operation = Operation {
-                summary: summary.or(Some(function.name.clone())),
-                description,
-                operation_id: self.to_snake_case(&function.name),
-                parameters: self.extract_parameters(function),
-                request_body: self.extract_request_body(function, &method_str),
-                responses: self.extract_responses(function),
-                security: if has_auth {
-                    vec![SecurityRequirement {
-                        name: "bearerAuth".to_string(),
-                        scopes: roles,
-                    }]
-                } else {
-                    vec![]
-                },
-            };
+pub(crate) fn collect_refs(schema: &Schema, at: &str, refs: &mut Vec<(String, String)>) {
+    if let Some(ref_path) = &schema.ref_path {
+        match ref_path.strip_prefix(SCHEMA_PREFIX) {
+            Some(component) => refs.push((at.to_string(), component.to_string())),
+            None => refs.push((at.to_string(), ref_path.clone())),
+        }
+    }
+    let nested = schema
+        .items
+        .iter()
+        .map(|s| s.as_ref())
+        .chain(schema.additional_properties.iter().map(|s| s.as_ref()))
+        .chain(schema.prefix_items.iter())
+        .chain(schema.properties.iter().map(|(_, s)| s))
+        .chain(schema.one_of.iter());
+    for child in nested {
+        collect_refs(child, at, refs);
+    }
+}
 
-            match method_str.as_str() {
-                "get" => path_item.get = Some(operation),
-                "post" => path_item.post = Some(operation),
-                "put" => path_item.put = Some(operation),
-                "delete" => path_item.delete = Some(operation),
-                "patch" => path_item.patch = Some(operation),
+/// Baut `components/schemas` aus den Structs, Enums und Typ-Aliasen des Programms
+pub(crate) struct SchemaBuilder<'a> {
+    structs: BTreeMap<String, &'a Struct>,
+    enums: BTreeMap<String, &'a Enum>,
+    aliases: BTreeMap<String, &'a TypeAlias>,
+    schemas: BTreeMap<String, Schema>,
+    in_progress: HashSet<String>,
+    error_schema: bool,
+}
+
+impl<'a> SchemaBuilder<'a> {
+    pub(crate) fn new(items: &'a [Item]) -> Self {
+        let mut builder = SchemaBuilder {
+            structs: BTreeMap::new(),
+            enums: BTreeMap::new(),
+            aliases: BTreeMap::new(),
+            schemas: BTreeMap::new(),
+            in_progress: HashSet::new(),
+            error_schema: false,
+        };
+        builder.collect(items);
+        builder
+    }
+
+    fn collect(&mut self, items: &'a [Item]) {
+        for item in items {
+            match item {
+                Item::Struct(s) => {
+                    self.structs.insert(s.name.clone(), s);
+                }
+                Item::Enum(e) => {
+                    self.enums.insert(e.name.clone(), e);
+                }
+                Item::TypeAlias(a) => {
+                    self.aliases.insert(a.name.clone(), a);
+                }
+                Item::Module(m) => self.collect(&m.items),
                 _ => {}
             }
         }
     }
 
-    fn extract_parameters(&self, function: &Function) -> Vec<Parameter> {
-        let mut params = Vec::new();
+    fn use_error_schema(&mut self) {
+        self.error_schema = true;
+    }
 
-        // Extract path from decorators to identify path parameters
-        let mut path_str: Option<String> = None;
-        for decorator in &function.decorators {
-            if matches!(
-                decorator.name.as_str(),
-                "GET" | "POST" | "PUT" | "DELETE" | "PATCH"
-            ) {
-                if let Some(DecoratorArg::String(p)) = decorator.args.first() {
-                    path_str = Some(p.clone());
-                    break;
+    pub(crate) fn finish(mut self) -> BTreeMap<String, Schema> {
+        if self.error_schema && !self.schemas.contains_key(ERROR_SCHEMA) {
+            let issue = Schema {
+                properties: vec![
+                    ("field".to_string(), Schema::of("string")),
+                    ("message".to_string(), Schema::of("string")),
+                ],
+                required: vec!["field".to_string(), "message".to_string()],
+                ..Schema::of("object")
+            };
+            let schema = Schema {
+                properties: vec![
+                    ("error".to_string(), Schema::of("string")),
+                    (
+                        "errors".to_string(),
+                        Schema {
+                            items: Some(Box::new(issue)),
+                            ..Schema::of("array")
+                        },
+                    ),
+                ],
+                required: vec!["error".to_string()],
+                ..Schema::of("object")
+            };
+            self.schemas.insert(ERROR_SCHEMA.to_string(), schema);
+        }
+        self.schemas
+    }
+
+    pub(crate) fn type_schema(&mut self, ty: &Type, bindings: &HashMap<String, Type>) -> Schema {
+        match ty {
+            Type::String => Schema::of("string"),
+            Type::Number => Schema {
+                format: Some("double".to_string()),
+                ..Schema::of("number")
+            },
+            Type::Boolean => Schema::of("boolean"),
+            Type::Null => Schema::of("null"),
+            Type::List(inner) => Schema {
+                items: Some(Box::new(self.type_schema(inner, bindings))),
+                ..Schema::of("array")
+            },
+            Type::Map { value, .. } => Schema {
+                additional_properties: Some(Box::new(self.type_schema(value, bindings))),
+                ..Schema::of("object")
+            },
+            Type::Tuple(types) => Schema {
+                prefix_items: types
+                    .iter()
+                    .map(|t| self.type_schema(t, bindings))
+                    .collect(),
+                min_items: Some(types.len() as u64),
+                max_items: Some(types.len() as u64),
+                ..Schema::of("array")
+            },
+            Type::Optional(inner) => self.type_schema(inner, bindings).nullable(),
+            Type::Result { ok, .. } => self.type_schema(ok, bindings),
+            Type::Named(name) => {
+                if let Some(bound) = bindings.get(name) {
+                    return self.type_schema(&bound.clone(), &HashMap::new());
+                }
+                if let Some(def) = self.structs.get(name).copied() {
+                    return Schema::reference(&self.struct_component(def, &[]));
+                }
+                if let Some(def) = self.enums.get(name).copied() {
+                    return Schema::reference(&self.enum_component(def));
+                }
+                if let Some(def) = self.aliases.get(name).copied() {
+                    return Schema::reference(&self.alias_component(def));
+                }
+                // Unbekannte Typen (z.B. aus der Standardbibliothek) bleiben unbeschränkt
+                Schema::default()
+            }
+            Type::Generic { name, params } => {
+                let params: Vec<Type> = params.iter().map(|p| substitute(p, bindings)).collect();
+                match (name.as_str(), params.as_slice()) {
+                    ("List" | "Vec", [inner]) => {
+                        self.type_schema(&Type::List(Box::new(inner.clone())), bindings)
+                    }
+                    ("Map" | "HashMap", [key, value]) => self.type_schema(
+                        &Type::Map {
+                            key: Box::new(key.clone()),
+                            value: Box::new(value.clone()),
+                        },
+                        bindings,
+                    ),
+                    ("Option", [inner]) => self.type_schema(inner, bindings).nullable(),
+                    ("Result", [ok, ..]) => self.type_schema(ok, bindings),
+                    _ => match self.structs.get(name).copied() {
+                        Some(def) => Schema::reference(&self.struct_component(def, &params)),
+                        None => Schema::default(),
+                    },
                 }
             }
+            _ => Schema::default(),
         }
+    }
 
-        let path_params: Vec<String> = if let Some(ref path) = path_str {
-            path.split('/')
-                .filter(|s| s.starts_with(':'))
-                .map(|s| s[1..].to_string())
-                .collect()
+    /// Komponente eines Structs; generische Structs werden pro Typargument-Kombination
+    /// als `Name_Arg` instanziiert
+    fn struct_component(&mut self, def: &'a Struct, args: &[Type]) -> String {
+        let name = if args.is_empty() {
+            def.name.clone()
         } else {
-            Vec::new()
+            let args: Vec<String> = args.iter().map(component_name).collect();
+            format!("{}_{}", def.name, args.join("_"))
         };
+        if self.schemas.contains_key(&name) || !self.in_progress.insert(name.clone()) {
+            return name;
+        }
 
-        for param in &function.params {
-            let param_schema = self.type_to_schema(&param.param_type);
-            let location = if path_params.contains(&param.name) {
-                "path".to_string()
-            } else {
-                "query".to_string()
-            };
-
-            params.push(Parameter {
-                name: param.name.clone(),
-                location,
-                required: param.default.is_none(),
-                schema: param_schema,
-            });
+        let bindings: HashMap<String, Type> = def
+            .type_params
+            .iter()
+            .cloned()
+            .zip(args.iter().cloned())
+            .collect();
+        let validations: HashMap<String, FieldValidation> = validation::struct_validations(def)
+            .into_iter()
+            .map(|v| (v.name.clone(), v))
+            .collect();
+
+        let mut properties = Vec::new();
+        let mut required = Vec::new();
+        for field in &def.fields {
+            let mut schema = self.type_schema(&field.field_type, &bindings);
+            let optional = validation::unwrap_optional(&field.field_type).1;
+            if let Some(field_validation) = validations.get(&field.name) {
+                apply_constraints(&mut schema, field_validation);
+                if field_validation.rules.required && optional {
+                    required.push(field.name.clone());
+                }
+            }
+            if !optional {
+                required.push(field.name.clone());
+            }
+            schema.examples = field
+                .decorators
+                .iter()
+                .filter(|d| d.name.trim_start_matches('@') == "Example")
+                .flat_map(|d| d.args.iter().map(decorator_value))
+                .collect();
+            properties.push((field.name.clone(), schema));
         }
 
-        params
+        let schema = Schema {
+            description: def.documentation.as_deref().map(|d| d.trim().to_string()),
+            properties,
+            required,
+            ..Schema::of("object")
+        };
+        self.in_progress.remove(&name);
+        self.schemas.insert(name.clone(), schema);
+        name
     }
 
-    fn extract_request_body(&self, function: &Function, method: &str) -> Option<RequestBody> {
-        if method == "get" || method == "delete" {
-            return None;
-        }
-
-        // For POST/PUT/PATCH, use first parameter as body
-        if let Some(first_param) = function.params.first() {
-            let schema = self.type_to_schema(&first_param.param_type);
-            let mut content = std::collections::HashMap::new();
-            content.insert("application/json".to_string(), MediaType { schema });
-            Some(RequestBody { content })
-        } else {
-            None
+    /// Typ-Aliase werden eigene Komponenten mit dem Schema des Zieltyps
+    fn alias_component(&mut self, def: &'a TypeAlias) -> String {
+        let name = def.name.clone();
+        if self.schemas.contains_key(&name) || !self.in_progress.insert(name.clone()) {
+            return name;
         }
+        let schema = self.type_schema(&def.aliased_type, &HashMap::new());
+        self.in_progress.remove(&name);
+        self.schemas.insert(name.clone(), schema);
+        name
     }
 
-    fn extract_responses(
-        &self,
-        function: &Function,
-    ) -> std::collections::HashMap<String, Response> {
-        let mut responses = std::collections::HashMap::new();
-
-        // Success response (200)
-        if let Some(ref return_type) = function.return_type {
-            let schema = self.type_to_schema(return_type);
-            let mut content = std::collections::HashMap::new();
-            content.insert("application/json".to_string(), MediaType { schema });
-            responses.insert(
-                "200".to_string(),
-                Response {
-                    description: "Success".to_string(),
-                    content: Some(content),
-                },
-            );
-        } else {
-            responses.insert(
-                "200".to_string(),
-                Response {
-                    description: "Success".to_string(),
-                    content: None,
-                },
-            );
+    /// Enums wie serde sie serialisiert: Unit-Varianten als String,
+    /// Varianten mit Daten als `{"Variante": wert}` (`oneOf`)
+    fn enum_component(&mut self, def: &'a Enum) -> String {
+        let name = def.name.clone();
+        if self.schemas.contains_key(&name) || !self.in_progress.insert(name.clone()) {
+            return name;
         }
 
-        // Error responses (400, 401, 403, 404, 500)
-        let error_schema = Schema {
-            schema_type: "object".to_string(),
-            format: None,
-            items: None,
-            properties: Some({
-                let mut props = std::collections::HashMap::new();
-                props.insert(
-                    "error".to_string(),
-                    Schema {
-                        schema_type: "string".to_string(),
-                        format: None,
-                        items: None,
-                        properties: None,
-                        ref_path: None,
-                    },
-                );
-                props.insert(
-                    "errors".to_string(),
-                    Schema {
-                        schema_type: "array".to_string(),
-                        format: None,
-                        items: Some(Box::new(Schema {
-                            schema_type: "object".to_string(),
-                            format: None,
-                            items: None,
-                            properties: None,
-                            ref_path: None,
-                        })),
-                        properties: None,
-                        ref_path: None,
-                    },
-                );
-                props
-            }),
-            ref_path: None,
+        let units: Vec<Value> = def
+            .variants
+            .iter()
+            .filter(|v| v.data.is_none())
+            .map(|v| Value::String(v.name.clone()))
+            .collect();
+        let unit_schema = Schema {
+            enum_values: units.clone(),
+            ..Schema::of("string")
         };
 
-        let error_content = {
-            let mut content = std::collections::HashMap::new();
-            content.insert(
-                "application/json".to_string(),
-                MediaType {
-                    schema: error_schema,
-                },
-            );
-            content
+        let mut variants = Vec::new();
+        for variant in &def.variants {
+            let Some(types) = &variant.data else { continue };
+            let data = match types.as_slice() {
+                [single] => self.type_schema(single, &HashMap::new()),
+                many => self.type_schema(&Type::Tuple(many.to_vec()), &HashMap::new()),
+            };
+            variants.push(Schema {
+                properties: vec![(variant.name.clone(), data)],
+                required: vec![variant.name.clone()],
+                ..Schema::of("object")
+            });
+        }
+
+        let description = def.documentation.as_deref().map(|d| d.trim().to_string());
+        let schema = if variants.is_empty() {
+            Schema {
+                description,
+                ..unit_schema
+            }
+        } else {
+            if !units.is_empty() {
+                variants.insert(0, unit_schema);
+            }
+            Schema {
+                description,
+                one_of: variants,
+                ..Default::default()
+            }
         };
+        self.in_progress.remove(&name);
+        self.schemas.insert(name.clone(), schema);
+        name
+    }
+}
 
-        responses.insert(
-            "400".to_string(),
-            Response {
-                description: "Bad Request - Validation Error".to_string(),
-                content: Some(error_content.clone()),
-            },
-        );
+fn substitute(ty: &Type, bindings: &HashMap<String, Type>) -> Type {
+    match ty {
+        Type::Named(name) => bindings.get(name).cloned().unwrap_or_else(|| ty.clone()),
+        Type::Generic { name, params } => Type::Generic {
+            name: name.clone(),
+            params: params.iter().map(|p| substitute(p, bindings)).collect(),
+        },
+        Type::List(inner) => Type::List(Box::new(substitute(inner, bindings))),
+        Type::Optional(inner) => Type::Optional(Box::new(substitute(inner, bindings))),
+        other => other.clone(),
+    }
+}
 
-        responses.insert(
-            "401".to_string(),
-            Response {
-                description: "Unauthorized".to_string(),
-                content: Some(error_content.clone()),
-            },
-        );
+/// Name einer generischen Instanziierung (`Page<List<User>>` → `Page_ListUser`)
+fn component_name(ty: &Type) -> String {
+    match ty {
+        Type::String => "String".to_string(),
+        Type::Number => "Number".to_string(),
+        Type::Boolean => "Boolean".to_string(),
+        Type::Named(name) => name.clone(),
+        Type::List(inner) => format!("List{}", component_name(inner)),
+        Type::Optional(inner) => format!("Optional{}", component_name(inner)),
+        Type::Generic { name, params } => {
+            let params: Vec<String> = params.iter().map(component_name).collect();
+            format!("{}{}", name, params.join(""))
+        }
+        _ => "Any".to_string(),
+    }
+}
 
-        responses.insert(
-            "403".to_string(),
-            Response {
-                description: "Forbidden".to_string(),
-                content: Some(error_content.clone()),
-            },
-        );
+fn decorator_value(arg: &DecoratorArg) -> Value {
+    match arg {
+        DecoratorArg::String(s) | DecoratorArg::Identifier(s) => Value::String(s.clone()),
+        DecoratorArg::Number(n) => serde_json::Number::from_f64(*n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        DecoratorArg::Boolean(b) => Value::Bool(*b),
+        DecoratorArg::List(values) => Value::Array(values.iter().map(decorator_value).collect()),
+        DecoratorArg::Named { value, .. } => decorator_value(value),
+    }
+}
 
-        responses.insert(
-            "404".to_string(),
-            Response {
-                description: "Not Found".to_string(),
-                content: Some(error_content.clone()),
-            },
-        );
+/// Alle Funktionen samt Modul (rekursiv durch `mod`-Blöcke)
+fn functions<'a>(
+    items: &'a [Item],
+    module: Option<&'a str>,
+) -> Vec<(&'a Function, Option<&'a str>)> {
+    let mut functions = Vec::new();
+    for item in items {
+        match item {
+            Item::Function(f) => functions.push((f, module)),
+            Item::Module(m) => functions.extend(self::functions(&m.items, Some(&m.name))),
+            _ => {}
+        }
+    }
+    functions
+}
 
-        responses.insert(
-            "500".to_string(),
-            Response {
-                description: "Internal Server Error".to_string(),
-                content: Some(error_content),
-            },
-        );
+/// `(methode, pfad)` aller Route-Decorators (`@GET`, `@Post`, ...) einer Funktion
+fn routes(function: &Function) -> Vec<(&'static str, String)> {
+    function
+        .decorators
+        .iter()
+        .filter_map(|d| {
+            let name = d.name.trim_start_matches('@').to_lowercase();
+            let method = ROUTE_METHODS.iter().find(|m| **m == name)?;
+            let path = match d.args.first() {
+                Some(DecoratorArg::String(path)) => path.clone(),
+                _ => "/".to_string(),
+            };
+            Some((*method, path))
+        })
+        .collect()
+}
 
-        responses
-    }
+/// `/users/:id` → `/users/{id}`
+pub(crate) fn openapi_path(path: &str) -> String {
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{}", path)
+    };
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
-    fn type_to_schema(&self, ty: &Type) -> Schema {
-        match ty {
-            Type::String => Schema {
-                schema_type: "string".to_string(),
-                format: None,
-                items: None,
-                properties: None,
-                ref_path: None,
-            },
-            Type::Number => Schema {
-                schema_type: "number".to_string(),
-                format: Some("double".to_string()),
-                items: None,
-                properties: None,
-                ref_path: None,
-            },
-            Type::Boolean => Schema {
-                schema_type: "boolean".to_string(),
-                format: None,
-                items: None,
-                properties: None,
-                ref_path: None,
-            },
-            Type::List(item_type) => {
-                let item_schema = self.type_to_schema(item_type);
-                Schema {
-                    schema_type: "array".to_string(),
-                    format: None,
-                    items: Some(Box::new(item_schema)),
-                    properties: None,
-                    ref_path: None,
-                }
-            }
-            Type::Named(name) => Schema {
-                schema_type: "object".to_string(),
-                format: None,
-                items: None,
-                properties: None,
-                ref_path: Some(format!("#/components/schemas/{}", name)),
-            },
-            Type::Generic { name, params } => {
-                if name == "List" && !params.is_empty() {
-                    let item_schema = self.type_to_schema(&params[0]);
-                    Schema {
-                        schema_type: "array".to_string(),
-                        format: None,
-                        items: Some(Box::new(item_schema)),
-                        properties: None,
-                        ref_path: None,
-                    }
-                } else {
-                    Schema {
-                        schema_type: "object".to_string(),
-                        format: None,
-                        items: None,
-                        properties: None,
-                        ref_path: None,
-                    }
-                }
-            }
-            _ => Schema {
-                schema_type: "object".to_string(),
-                format: None,
-                items: None,
-                properties: None,
-                ref_path: None,
-            },
+/// Parameternamen aus `:name`- oder `{name}`-Segmenten
+fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| {
+            segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+fn is_scalar(ty: &Type) -> bool {
+    match ty {
+        Type::String | Type::Number | Type::Boolean => true,
+        Type::Optional(inner) => is_scalar(inner),
+        Type::Generic { name, params } if name == "Option" && params.len() == 1 => {
+            is_scalar(&params[0])
         }
+        _ => false,
     }
+}
 
-    fn generate_json(&mut self, spec: &OpenAPISpec) -> String {
-        // Simple JSON generation (in production, use serde_json)
-        let mut json = String::new();
-        json.push_str("{\n");
-        json.push_str(&format!("  \"openapi\": \"{}\",\n", spec.openapi));
-        json.push_str("  \"info\": {\n");
-        json.push_str(&format!("    \"title\": \"{}\",\n", spec.info.title));
-        json.push_str(&format!("    \"version\": \"{}\",\n", spec.info.version));
-        if let Some(ref desc) = spec.info.description {
-            json.push_str(&format!("    \"description\": \"{}\",\n", desc));
+/// Erfolgstyp einer Route: `Result<T, E>` → `T`
+fn success_type(ty: &Type) -> &Type {
+    match ty {
+        Type::Result { ok, .. } => success_type(ok),
+        Type::Generic { name, params } if name == "Result" && !params.is_empty() => {
+            success_type(&params[0])
         }
-        json.push_str("  },\n");
-        json.push_str("  \"paths\": {\n");
+        other => other,
+    }
+}
 
-        for (path, path_item) in &spec.paths {
-            json.push_str(&format!("    \"{}\": {{\n", path));
+fn json_content(schema: Schema) -> BTreeMap<String, MediaType> {
+    let mut content = BTreeMap::new();
+    content.insert("application/json".to_string(), MediaType { schema });
+    content
+}
 
-            if let Some(ref op) = path_item.get {
-                json.push_str("      \"get\": ");
-                json.push_str(&self.operation_to_json(op));
-                json.push_str(",\n");
-            }
-            if let Some(ref op) = path_item.post {
-                json.push_str("      \"post\": ");
-                json.push_str(&self.operation_to_json(op));
-                json.push_str(",\n");
-            }
-            if let Some(ref op) = path_item.put {
-                json.push_str("      \"put\": ");
-                json.push_str(&self.operation_to_json(op));
-                json.push_str(",\n");
+/// Security-Anforderungen aus `@Auth`, `@Role`, `@OAuth2`/`@OAuth` und `@OIDC`;
+/// benötigte Schemes werden in `components.securitySchemes` eingetragen
+fn security(
+    function: &Function,
+    schemes: &mut BTreeMap<String, SecurityScheme>,
+) -> Vec<SecurityRequirement> {
+    let mut auth = false;
+    let mut oauth2 = false;
+    let mut oidc = false;
+    let mut roles = Vec::new();
+    for decorator in &function.decorators {
+        match decorator.name.trim_start_matches('@') {
+            "Auth" => auth = true,
+            "OAuth2" | "OAuth" => oauth2 = true,
+            "OIDC" => oidc = true,
+            "Role" | "Roles" => {
+                auth = true;
+                roles.extend(decorator.args.iter().filter_map(|a| match a {
+                    DecoratorArg::String(role) => Some(role.clone()),
+                    _ => None,
+                }));
             }
-            if let Some(ref op) = path_item.delete {
-                json.push_str("      \"delete\": ");
-                json.push_str(&self.operation_to_json(op));
-                json.push_str(",\n");
-            }
-            if let Some(ref op) = path_item.patch {
-                json.push_str("      \"patch\": ");
-                json.push_str(&self.operation_to_json(op));
-                json.push_str(",\n");
+            _ => {}
+        }
+    }
+
+    let mut requirements = Vec::new();
+    let mut require = |name: &str, scopes: Vec<String>| {
+        let mut requirement = SecurityRequirement::new();
+        requirement.insert(name.to_string(), scopes);
+        requirements.push(requirement);
+    };
+    if oauth2 {
+        let scheme = schemes
+            .entry("oauth2".to_string())
+            .or_insert_with(|| SecurityScheme {
+                scheme_type: "oauth2".to_string(),
+                scheme: None,
+                bearer_format: None,
+                description: None,
+                flows: Some(OAuthFlows {
+                    authorization_code: OAuthFlow {
+                        authorization_url: "/oauth/authorize".to_string(),
+                        token_url: "/oauth/token".to_string(),
+                        scopes: BTreeMap::new(),
+                    },
+                }),
+                open_id_connect_url: None,
+            });
+        if let Some(flows) = &mut scheme.flows {
+            for role in &roles {
+                flows
+                    .authorization_code
+                    .scopes
+                    .entry(role.clone())
+                    .or_insert_with(|| format!("Rolle {}", role));
             }
+        }
+        require("oauth2", roles.clone());
+    }
+    if oidc {
+        schemes
+            .entry("openIdConnect".to_string())
+            .or_insert_with(|| SecurityScheme {
+                scheme_type: "openIdConnect".to_string(),
+                scheme: None,
+                bearer_format: None,
+                description: None,
+                flows: None,
+                open_id_connect_url: Some("/.well-known/openid-configuration".to_string()),
+            });
+        require("openIdConnect", roles.clone());
+    }
+    if auth && !oauth2 && !oidc {
+        schemes
+            .entry("bearerAuth".to_string())
+            .or_insert_with(|| SecurityScheme {
+                scheme_type: "http".to_string(),
+                scheme: Some("bearer".to_string()),
+                bearer_format: Some("JWT".to_string()),
+                description: None,
+                flows: None,
+                open_id_connect_url: None,
+            });
+        require("bearerAuth", roles);
+    }
+    requirements
+}
 
-            json.push_str("    },\n");
+/// Überträgt die `@Validate`-Regeln eines Feldes auf sein Schema
+fn apply_constraints(schema: &mut Schema, field: &FieldValidation) {
+    let rules = &field.rules;
+    match field.kind {
+        ValueKind::Text => {
+            schema.min_length = rules.min.map(|n| n as u64);
+            schema.max_length = rules.max.map(|n| n as u64);
+            if rules.email {
+                schema.format = Some("email".to_string());
+            }
+            schema.pattern = rules.pattern.clone();
+        }
+        ValueKind::Number => {
+            schema.minimum = rules.min;
+            schema.maximum = rules.max;
+        }
+        ValueKind::List => {
+            schema.min_items = rules.min.map(|n| n as u64);
+            schema.max_items = rules.max.map(|n| n as u64);
         }
+        ValueKind::Other => {}
+    }
+    schema.enum_values = rules
+        .one_of
+        .iter()
+        .map(|value| match value {
+            RuleValue::String(s) => Value::String(s.clone()),
+            RuleValue::Number(n) => serde_json::Number::from_f64(*n)
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+        })
+        .collect();
+    if field.optional && !schema.enum_values.is_empty() {
+        schema.enum_values.push(Value::Null);
+    }
+}
 
-        json.push_str("  },\n");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
 
-        // Add components section
-        if let Some(ref components) = spec.components {
-            json.push_str("  \"components\": {\n");
+    fn spec(source: &str) -> OpenAPISpec {
+        let program = Parser::parse(source).expect("parse");
+        OpenAPIGenerator::new().build(&program)
+    }
 
-            // Security Schemes
-            if !components.security_schemes.is_empty() {
-                json.push_str("    \"securitySchemes\": {\n");
-                for (name, scheme) in &components.security_schemes {
-                    json.push_str(&format!("      \"{}\": {{\n", name));
-                    json.push_str(&format!("        \"type\": \"{}\",\n", scheme.scheme_type));
-                    if let Some(ref s) = scheme.scheme {
-                        json.push_str(&format!("        \"scheme\": \"{}\",\n", s));
-                    }
-                    if let Some(ref bf) = scheme.bearer_format {
-                        json.push_str(&format!("        \"bearerFormat\": \"{}\",\n", bf));
-                    }
-                    json.push_str("      },\n");
-                }
-                json.push_str("    },\n");
-            }
-
-            // Schemas
-            if !components.schemas.is_empty() {
-                json.push_str("    \"schemas\": {\n");
-                for (name, schema) in &components.schemas {
-                    json.push_str(&format!(
-                        "      \"{}\": {},\n",
-                        name,
-                        self.schema_to_json(schema)
-                    ));
-                }
-                json.push_str("    }\n");
+    #[test]
+    fn test_routes_become_valid_operations() {
+        let spec = spec(
+            r#"
+            struct User {
+                @Validate(min: 2)
+                name: string,
+                tags: List<string>,
             }
 
-            json.push_str("  }\n");
-        }
+            /// Lädt einen User
+            @Auth
+            @Role("admin")
+            @GET("/users/:id")
+            fn getUser(id: string, verbose: boolean): User {
+                return User { name: id, tags: [] };
+            }
 
-        json.push_str("}\n");
-        json
+            @POST("/users")
+            fn createUser(user: User): User {
+                return user;
+            }
+            "#,
+        );
+        assert_eq!(spec.openapi, OPENAPI_VERSION);
+        assert!(validate(&spec).is_empty(), "{:?}", validate(&spec));
+
+        let get = spec.paths["/users/{id}"].get.as_ref().unwrap();
+        assert_eq!(get.summary.as_deref(), Some("Lädt einen User"));
+        assert_eq!(get.parameters[0].location, "path");
+        assert_eq!(get.parameters[1].location, "query");
+        assert_eq!(get.security[0]["bearerAuth"], vec!["admin".to_string()]);
+        assert!(get.responses.contains_key("403"));
+
+        let post = spec.paths["/users"].post.as_ref().unwrap();
+        let body = &post.request_body.as_ref().unwrap().content["application/json"];
+        assert_eq!(body.schema.component(), Some("User"));
+
+        let user = &spec.components.schemas["User"];
+        assert_eq!(user.required, vec!["name", "tags"]);
+        assert_eq!(user.properties[0].1.min_length, Some(2));
     }
 
-    fn schema_to_json(&self, schema: &Schema) -> String {
-        if let Some(ref ref_path) = schema.ref_path {
-            return format!("{{\"$ref\": \"{}\"}}", ref_path);
-        }
+    #[test]
+    fn test_enums_and_generics_follow_serde_layout() {
+        let spec = spec(
+            r#"
+            enum Shape {
+                Empty,
+                Circle(number),
+                Rect(number, number),
+            }
 
-        let mut json = String::new();
-        json.push_str("{\n");
-        json.push_str(&format!("        \"type\": \"{}\",\n", schema.schema_type));
+            struct Page<T> {
+                items: List<T>,
+                total: number,
+            }
 
-        if let Some(ref format) = schema.format {
-            json.push_str(&format!("        \"format\": \"{}\",\n", format));
-        }
+            @GET("/shapes")
+            fn shapes(): Page<Shape> {
+                return Page { items: [], total: 0 };
+            }
+            "#,
+        );
+        assert!(validate(&spec).is_empty(), "{:?}", validate(&spec));
 
-        if let Some(ref items) = schema.items {
-            json.push_str("        \"items\": ");
-            json.push_str(&self.schema_to_json(items));
-            json.push_str(",\n");
-        }
+        let shape = &spec.components.schemas["Shape"];
+        assert_eq!(shape.one_of.len(), 3);
+        assert_eq!(shape.one_of[0].enum_values, vec![Value::from("Empty")]);
+        assert_eq!(shape.one_of[2].properties[0].1.prefix_items.len(), 2);
 
-        if let Some(ref properties) = schema.properties {
-            json.push_str("        \"properties\": {\n");
-            for (name, prop_schema) in properties {
-                json.push_str(&format!(
-                    "          \"{}\": {},\n",
-                    name,
-                    self.schema_to_json(prop_schema)
-                ));
-            }
-            json.push_str("        },\n");
-        }
+        let page = &spec.components.schemas["Page_Shape"];
+        let items = page.properties[0].1.items.as_ref().unwrap();
+        assert_eq!(items.component(), Some("Shape"));
+        assert!(!spec.components.schemas.contains_key("Page"));
 
-        json.push_str("      }");
-        json
+        let yaml = render(&spec, OpenAPIFormat::Yaml).unwrap();
+        assert!(yaml.starts_with("openapi: 3.1.0"));
     }
 
-    fn operation_to_json(&self, op: &Operation) -> String {
-        let mut json = String::new();
-        json.push_str("{\n");
-        if let Some(ref summary) = op.summary {
-            json.push_str(&format!("        \"summary\": \"{}\",\n", summary));
-        }
-        json.push_str(&format!(
-            "        \"operationId\": \"{}\",\n",
-            op.operation_id
-        ));
-        json.push_str("        \"responses\": {\n");
-        json.push_str("          \"200\": {\n");
-        json.push_str("            \"description\": \"Success\"\n");
-        json.push_str("          }\n");
-        json.push_str("        }\n");
-        json.push_str("      }");
-        json
+    #[test]
+    fn test_validate_reports_broken_references() {
+        let mut spec = spec("@GET(\"/ping\") fn ping(): string { return \"pong\"; }");
+        spec.components.schemas.remove(ERROR_SCHEMA);
+        let errors = validate(&spec);
+        assert!(
+            errors.iter().any(|e| e.contains("unresolved $ref")),
+            "{:?}",
+            errors
+        );
     }
 
-    fn to_snake_case(&self, s: &str) -> String {
-        let mut result = String::new();
-        for (i, c) in s.chars().enumerate() {
-            if c.is_uppercase() && i > 0 {
-                result.push('_');
+    #[test]
+    fn test_maps_optionals_and_results() {
+        let spec = spec(
+            r#"
+            struct Metrics {
+                counts: Map<string, number>,
+                note: Option<string>,
             }
-            result.push(c.to_lowercase().next().unwrap_or(c));
-        }
-        result
+
+            @GET("/metrics")
+            fn metrics(): Result<Metrics, string> {
+                return Ok(Metrics { counts: {}, note: null });
+            }
+            "#,
+        );
+        assert!(validate(&spec).is_empty(), "{:?}", validate(&spec));
+
+        let metrics = &spec.components.schemas["Metrics"];
+        assert_eq!(metrics.required, vec!["counts"]);
+        let counts = &metrics.properties[0].1;
+        assert_eq!(counts.primary_type(), Some("object"));
+        let values = counts.additional_properties.as_ref().unwrap();
+        assert_eq!(values.primary_type(), Some("number"));
+        assert_eq!(values.format.as_deref(), Some("double"));
+        assert!(metrics.properties[1].1.is_nullable());
+
+        // `Result<T, E>` liefert im Erfolgsfall T
+        let get = spec.paths["/metrics"].get.as_ref().unwrap();
+        let ok = &get.responses["200"].content.as_ref().unwrap()["application/json"];
+        assert_eq!(ok.schema.component(), Some("Metrics"));
     }
-}
 
-#[derive(Debug, Clone)]
-pub struct Parameter {
-    pub name: String,
-    pub location: String,
-    pub required: bool,
-    pub schema: Schema,
+    #[test]
+    fn test_json_and_yaml_render_the_same_document() {
+        let spec = spec(
+            r#"
+            struct User {
+                name: Option<string>,
+            }
+
+            @GET("/users/:id")
+            fn getUser(id: string): User {
+                return User { name: null };
+            }
+            "#,
+        );
+        let json = render(&spec, OpenAPIFormat::Json).unwrap();
+        let yaml = render(&spec, OpenAPIFormat::Yaml).unwrap();
+        let from_json: Value = serde_json::from_str(&json).unwrap();
+        let from_yaml: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(from_json, from_yaml);
+
+        // Nullable Werte nutzen die 3.1-Schreibweise statt `nullable: true`
+        let name = &from_json["components"]["schemas"]["User"]["properties"]["name"];
+        assert_eq!(name["type"], serde_json::json!(["string", "null"]));
+        assert!(!json.contains("\"nullable\""));
+
+        // Das gerenderte Dokument liest sich wieder ein
+        let parsed: OpenAPISpec = serde_json::from_str(&json).unwrap();
+        assert!(validate(&parsed).is_empty(), "{:?}", validate(&parsed));
+        assert_eq!(OpenAPIFormat::parse("yml"), Some(OpenAPIFormat::Yaml));
+    }
+
+    #[test]
+    fn test_validate_reports_undeclared_path_parameters() {
+        let mut spec = spec("@GET(\"/users/:id\") fn getUser(id: string): string { return id; }");
+        let get = spec.paths.remove("/users/{id}").unwrap();
+        spec.paths.insert("/users/{userId}".to_string(), get);
+        let errors = validate(&spec);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("path parameter 'userId' is not declared")),
+            "{:?}",
+            errors
+        );
+    }
 }
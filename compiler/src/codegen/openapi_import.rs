@@ -0,0 +1,1961 @@
+// OpenAPI Import
+// Erzeugt aus einem OpenAPI-3-Dokument oder JSON Schema Velin-Structs, Enums und Route-Stubs.
+// `round_trip` baut aus dem erzeugten Code wieder ein Dokument und vergleicht es mit dem Original.
+
+use super::client::{camel_case, fallback_name, pascal_case};
+use super::openapi::{OpenAPIGenerator, OpenAPISpec, Operation, Schema, SchemaType};
+use crate::parser::ast::*;
+use crate::parser::parser::Parser;
+use crate::validation::{self, ValueKind};
+use serde_json::Value;
+use serde_yaml::{Mapping, Value as Node};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const SCHEMA_PREFIX: &str = "#/components/schemas/";
+
+/// Verweise auf Schemas in JSON-Schema-Dokumenten und OpenAPI 3
+const SCHEMA_REF_PREFIXES: &[&str] = &[SCHEMA_PREFIX, "#/$defs/", "#/definitions/"];
+
+/// Schlüsselwörter des Lexers, die nicht als Name taugen
+const RESERVED: &[&str] = &[
+    "fn",
+    "let",
+    "return",
+    "if",
+    "else",
+    "for",
+    "while",
+    "match",
+    "throw",
+    "break",
+    "try",
+    "catch",
+    "finally",
+    "type",
+    "struct",
+    "enum",
+    "impl",
+    "trait",
+    "interface",
+    "pub",
+    "use",
+    "mod",
+    "const",
+    "static",
+    "async",
+    "await",
+    "in",
+    "true",
+    "false",
+    "null",
+    "GET",
+    "POST",
+    "PUT",
+    "DELETE",
+    "PATCH",
+    "Auth",
+    "Role",
+    "Cache",
+    "SEO",
+    "AI",
+];
+
+/// Eingebaute Typen, die keine Komponente verdecken darf
+const BUILTIN_TYPES: &[&str] = &[
+    "string", "number", "boolean", "void", "any", "List", "Vec", "Map", "HashMap", "Option",
+    "Result",
+];
+
+const ROUTE_METHODS: &[&str] = &["get", "post", "put", "patch", "delete"];
+
+pub struct OpenAPIImporter {
+    warnings: Vec<String>,
+    /// Roh-Schemas der Komponenten, um `allOf`-Referenzen aufzulösen
+    raw_schemas: Mapping,
+    /// Komponentenname → Velin-Typname
+    type_names: BTreeMap<String, String>,
+    used_names: HashSet<String>,
+    types: Vec<Item>,
+}
+
+impl Default for OpenAPIImporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OpenAPIImporter {
+    pub fn new() -> Self {
+        OpenAPIImporter {
+            warnings: Vec::new(),
+            raw_schemas: Mapping::new(),
+            type_names: BTreeMap::new(),
+            used_names: HashSet::new(),
+            types: Vec::new(),
+        }
+    }
+
+    /// Alles, was beim Import nicht oder nur verlustbehaftet übernommen wurde
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    fn warn(&mut self, message: String) {
+        if !self.warnings.contains(&message) {
+            self.warnings.push(message);
+        }
+    }
+
+    /// Liest ein OpenAPI-3.x-Dokument oder ein JSON Schema (JSON oder YAML).
+    /// OpenAPI-3.0-Eigenheiten (`nullable`, `example`) und `allOf`/`anyOf`
+    /// werden dabei auf das 3.1-Modell des Generators abgebildet.
+    pub fn parse(&mut self, content: &str, yaml: bool) -> anyhow::Result<OpenAPISpec> {
+        // YAML-Mappings behalten die Reihenfolge der Properties, auch für JSON-Eingaben
+        let mut document: Node = if yaml {
+            serde_yaml::from_str(content)?
+        } else {
+            serde_json::from_str(content)?
+        };
+        stringify_keys(&mut document);
+        if !document.is_mapping() {
+            anyhow::bail!("Das Dokument muss ein JSON-Objekt sein");
+        }
+        if document.get("swagger").is_some() {
+            anyhow::bail!(
+                "Swagger 2.0 wird nicht unterstützt, bitte zuerst nach OpenAPI 3 konvertieren"
+            );
+        }
+        let mut document = if document.get("openapi").is_some() {
+            document
+        } else {
+            wrap_json_schema(document)
+        };
+        self.normalize_document(&mut document);
+        Ok(serde_yaml::from_value(document)?)
+    }
+
+    /// Erzeugt Structs, Enums und Aliase aus `components/schemas`
+    /// sowie einen Route-Stub pro Operation
+    pub fn import(&mut self, spec: &OpenAPISpec) -> Program {
+        for component in spec.components.schemas.keys() {
+            let name = self.claim_type_name(&type_identifier(component));
+            if &name != component {
+                self.warn(format!(
+                    "components.schemas.{}: heißt in Velin '{}'",
+                    component, name
+                ));
+            }
+            self.type_names.insert(component.clone(), name);
+        }
+        for (component, schema) in &spec.components.schemas {
+            let item = self.component_item(component, schema);
+            self.types.push(item);
+        }
+
+        let mut functions = Vec::new();
+        let mut function_names = HashSet::new();
+        for (path, item) in &spec.paths {
+            for (method, operation) in item.operations() {
+                let function =
+                    self.function_item(spec, path, method, operation, &mut function_names);
+                functions.push(Item::Function(function));
+            }
+        }
+
+        let mut items = std::mem::take(&mut self.types);
+        items.extend(functions);
+        Program { items }
+    }
+
+    fn claim_type_name(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut counter = 2;
+        while !self.used_names.insert(name.clone()) {
+            name = format!("{}{}", base, counter);
+            counter += 1;
+        }
+        name
+    }
+
+    fn component_item(&mut self, component: &str, schema: &Schema) -> Item {
+        let name = self.type_names[component].clone();
+        let at = format!("components.schemas.{}", component);
+        let (inner, nullable) = non_null(schema);
+        let documentation = description(&inner);
+
+        if !nullable {
+            if let Some(variants) = self.enum_variants(&inner, &name, &at) {
+                return Item::Enum(Enum {
+                    name,
+                    variants,
+                    visibility: Visibility::Private,
+                    documentation,
+                });
+            }
+            if is_struct(&inner) {
+                return Item::Struct(self.struct_def(name, &inner, &at));
+            }
+        }
+
+        let mut aliased_type = self.map_type(&inner, &name, &at);
+        if nullable {
+            aliased_type = option(aliased_type);
+        }
+        Item::TypeAlias(TypeAlias {
+            name,
+            aliased_type,
+            visibility: Visibility::Private,
+        })
+    }
+
+    fn struct_def(&mut self, name: String, schema: &Schema, at: &str) -> Struct {
+        let mut fields = Vec::new();
+        let mut field_names = HashSet::new();
+        for (property, property_schema) in &schema.properties {
+            let field_at = format!("{}.{}", at, property);
+            let field_name = self.value_name(property, &field_at, &mut field_names);
+            let required = schema.required.contains(property);
+            let (mut inner, nullable) = non_null(property_schema);
+
+            // Aufzählungen direkt am Feld bleiben Strings/Zahlen mit `one_of`
+            let one_of = if inner.ref_path.is_none()
+                && matches!(inner.primary_type(), Some("string" | "number" | "integer"))
+            {
+                std::mem::take(&mut inner.enum_values)
+            } else {
+                Vec::new()
+            };
+
+            let hint = format!("{}{}", name, pascal_case(property));
+            let field_type = self.map_type(&inner, &hint, &field_at);
+            let mut rules = self.constraints(&inner, &field_type, &one_of, &field_at);
+            if nullable && required {
+                rules.push(named("required", DecoratorArg::Boolean(true)));
+            }
+
+            let mut decorators = Vec::new();
+            if !rules.is_empty() {
+                decorators.push(decorator("Validate", rules));
+            }
+            for example in &inner.examples {
+                match decorator_arg(example) {
+                    Some(arg) => decorators.push(decorator("Example", vec![arg])),
+                    None => self.warn(format!(
+                        "{}: Beispiel {} kann nicht als @Example übernommen werden",
+                        field_at, example
+                    )),
+                }
+            }
+
+            fields.push(StructField {
+                name: field_name,
+                field_type: if required && !nullable {
+                    field_type
+                } else {
+                    option(field_type)
+                },
+                visibility: Visibility::Private,
+                decorators,
+            });
+        }
+
+        Struct {
+            name,
+            type_params: Vec::new(),
+            fields,
+            visibility: Visibility::Private,
+            decorators: Vec::new(),
+            documentation: description(schema),
+        }
+    }
+
+    /// `@Validate`-Regeln aus den JSON-Schema-Constraints eines Feldes
+    fn constraints(
+        &mut self,
+        schema: &Schema,
+        field_type: &Type,
+        one_of: &[Value],
+        at: &str,
+    ) -> Vec<DecoratorArg> {
+        let kind = validation::value_kind(field_type);
+        let mut rules = Vec::new();
+        let bound = |rule: &str, value: Option<f64>, rules: &mut Vec<DecoratorArg>| {
+            if let Some(value) = value {
+                rules.push(named(rule, DecoratorArg::Number(value)));
+            }
+        };
+        let mut unsupported = Vec::new();
+        match kind {
+            ValueKind::Text => {
+                bound("min", schema.min_length.map(|n| n as f64), &mut rules);
+                bound("max", schema.max_length.map(|n| n as f64), &mut rules);
+                if schema.format.as_deref() == Some("email") {
+                    rules.push(named("email", DecoratorArg::Boolean(true)));
+                }
+                if let Some(pattern) = &schema.pattern {
+                    if regex::Regex::new(pattern).is_ok() {
+                        rules.push(named("pattern", DecoratorArg::String(pattern.clone())));
+                    } else {
+                        unsupported.push("pattern");
+                    }
+                }
+            }
+            ValueKind::Number => {
+                bound("min", schema.minimum, &mut rules);
+                bound("max", schema.maximum, &mut rules);
+            }
+            ValueKind::List => {
+                bound("min", schema.min_items.map(|n| n as f64), &mut rules);
+                bound("max", schema.max_items.map(|n| n as f64), &mut rules);
+            }
+            ValueKind::Other => {}
+        }
+
+        if kind != ValueKind::Text {
+            for (present, keyword) in [
+                (schema.min_length.is_some(), "minLength"),
+                (schema.max_length.is_some(), "maxLength"),
+                (schema.pattern.is_some(), "pattern"),
+            ] {
+                if present {
+                    unsupported.push(keyword);
+                }
+            }
+        }
+        if kind != ValueKind::Number {
+            for (present, keyword) in [
+                (schema.minimum.is_some(), "minimum"),
+                (schema.maximum.is_some(), "maximum"),
+            ] {
+                if present {
+                    unsupported.push(keyword);
+                }
+            }
+        }
+        if kind != ValueKind::List {
+            for (present, keyword) in [
+                (schema.min_items.is_some(), "minItems"),
+                (schema.max_items.is_some(), "maxItems"),
+            ] {
+                if present {
+                    unsupported.push(keyword);
+                }
+            }
+        }
+
+        let values: Vec<DecoratorArg> = one_of
+            .iter()
+            .filter(|v| !v.is_null())
+            .filter_map(|v| match (kind, v) {
+                (ValueKind::Text, Value::String(s)) => Some(DecoratorArg::String(s.clone())),
+                (ValueKind::Number, Value::Number(n)) => n.as_f64().map(DecoratorArg::Number),
+                _ => None,
+            })
+            .collect();
+        if !values.is_empty() {
+            rules.push(named("one_of", DecoratorArg::List(values)));
+        } else if one_of.iter().any(|v| !v.is_null()) {
+            unsupported.push("enum");
+        }
+
+        for keyword in unsupported {
+            self.warn(format!(
+                "{}: {} wird nicht als @Validate-Regel übernommen",
+                at, keyword
+            ));
+        }
+        rules
+    }
+
+    /// Varianten eines Enums: String-Aufzählung oder `oneOf` aus
+    /// `{"Variante": wert}`-Objekten, wie der Generator Enums serialisiert
+    fn enum_variants(&mut self, schema: &Schema, name: &str, at: &str) -> Option<Vec<EnumVariant>> {
+        if schema.one_of.is_empty() {
+            let units = unit_values(schema)?;
+            return Some(
+                units
+                    .into_iter()
+                    .map(|name| EnumVariant { name, data: None })
+                    .collect(),
+            );
+        }
+
+        // Erst prüfen, dann abbilden: sonst blieben verworfene Hilfstypen übrig
+        let is_variant = |part: &Schema| {
+            unit_values(part).is_some()
+                || (part.properties.len() == 1
+                    && part.required.contains(&part.properties[0].0)
+                    && is_identifier(&part.properties[0].0))
+        };
+        if !schema.one_of.iter().all(is_variant) {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+        for part in &schema.one_of {
+            if let Some(units) = unit_values(part) {
+                variants.extend(
+                    units
+                        .into_iter()
+                        .map(|name| EnumVariant { name, data: None }),
+                );
+                continue;
+            }
+            let (variant, data) = &part.properties[0];
+            let hint = format!("{}{}", name, variant);
+            let variant_at = format!("{}.{}", at, variant);
+            let types = if data.prefix_items.is_empty() {
+                vec![self.map_type(data, &hint, &variant_at)]
+            } else {
+                data.prefix_items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        self.map_type(item, &format!("{}{}", hint, i + 1), &variant_at)
+                    })
+                    .collect()
+            };
+            variants.push(EnumVariant {
+                name: variant.clone(),
+                data: Some(types),
+            });
+        }
+        Some(variants)
+    }
+
+    /// Velin-Typ eines Schemas ohne `null`; Aufrufer wickeln nullable Werte in `Option`
+    fn map_type(&mut self, schema: &Schema, hint: &str, at: &str) -> Type {
+        let (schema, _) = non_null(schema);
+        if let Some(component) = schema.component() {
+            return match self.type_names.get(component) {
+                Some(name) => Type::Named(name.clone()),
+                None => {
+                    self.warn(format!(
+                        "{}: unbekannte Referenz '{}' wird als any übernommen",
+                        at, component
+                    ));
+                    Type::Any
+                }
+            };
+        }
+
+        if !schema.one_of.is_empty() {
+            if let Some(variants) = self.enum_variants(&schema, hint, at) {
+                return self.hoist_enum(hint, variants);
+            }
+            self.warn(format!("{}: oneOf wird als any übernommen", at));
+            return Type::Any;
+        }
+
+        if let Some(SchemaType::Multiple(types)) = &schema.schema_type {
+            if types.len() > 1 {
+                self.warn(format!(
+                    "{}: Typ-Union {} wird als any übernommen",
+                    at,
+                    types.join(" | ")
+                ));
+                return Type::Any;
+            }
+        }
+
+        match schema.primary_type() {
+            Some("string") => {
+                if schema.enum_values.is_empty() {
+                    return Type::String;
+                }
+                match unit_values(&schema) {
+                    Some(units) => self.hoist_enum(
+                        hint,
+                        units
+                            .into_iter()
+                            .map(|name| EnumVariant { name, data: None })
+                            .collect(),
+                    ),
+                    None => {
+                        self.warn(format!(
+                            "{}: Aufzählung ohne gültige Variantennamen wird als string übernommen",
+                            at
+                        ));
+                        Type::String
+                    }
+                }
+            }
+            Some("number" | "integer") => {
+                if !schema.enum_values.is_empty() {
+                    self.warn(format!(
+                        "{}: Zahlen-Aufzählung wird als number übernommen",
+                        at
+                    ));
+                }
+                Type::Number
+            }
+            Some("boolean") => Type::Boolean,
+            Some("array") => {
+                if !schema.prefix_items.is_empty() {
+                    self.warn(format!("{}: prefixItems wird als List<any> übernommen", at));
+                    return Type::List(Box::new(Type::Any));
+                }
+                let item = match &schema.items {
+                    Some(items) => self.map_type(items, &format!("{}Item", hint), at),
+                    None => Type::Any,
+                };
+                Type::List(Box::new(item))
+            }
+            Some("object") | None if !schema.properties.is_empty() => {
+                let name = self.claim_type_name(&type_identifier(hint));
+                let def = self.struct_def(name.clone(), &schema, at);
+                self.types.push(Item::Struct(def));
+                Type::Named(name)
+            }
+            Some("object") => {
+                let value = match &schema.additional_properties {
+                    Some(value) => self.map_type(value, &format!("{}Value", hint), at),
+                    None => Type::Any,
+                };
+                Type::Map {
+                    key: Box::new(Type::String),
+                    value: Box::new(value),
+                }
+            }
+            None => match unit_values(&schema) {
+                Some(units) => self.hoist_enum(
+                    hint,
+                    units
+                        .into_iter()
+                        .map(|name| EnumVariant { name, data: None })
+                        .collect(),
+                ),
+                None => Type::Any,
+            },
+            Some(other) => {
+                self.warn(format!("{}: Typ '{}' wird als any übernommen", at, other));
+                Type::Any
+            }
+        }
+    }
+
+    fn hoist_enum(&mut self, hint: &str, variants: Vec<EnumVariant>) -> Type {
+        let name = self.claim_type_name(&type_identifier(hint));
+        self.types.push(Item::Enum(Enum {
+            name: name.clone(),
+            variants,
+            visibility: Visibility::Private,
+            documentation: None,
+        }));
+        Type::Named(name)
+    }
+
+    /// Gültiger, eindeutiger Name für Felder und Parameter
+    fn value_name(&mut self, name: &str, at: &str, taken: &mut HashSet<String>) -> String {
+        let base = value_identifier(name);
+        let mut unique = base.clone();
+        let mut counter = 2;
+        while !taken.insert(unique.clone()) {
+            unique = format!("{}{}", base, counter);
+            counter += 1;
+        }
+        if unique != name {
+            self.warn(format!("{}: heißt in Velin '{}'", at, unique));
+        }
+        unique
+    }
+
+    fn function_item(
+        &mut self,
+        spec: &OpenAPISpec,
+        path: &str,
+        method: &str,
+        operation: &Operation,
+        function_names: &mut HashSet<String>,
+    ) -> Function {
+        let at = format!("{} {}", method.to_uppercase(), path);
+        let name = if operation.operation_id.is_empty() {
+            fallback_name(method, path)
+        } else {
+            operation.operation_id.clone()
+        };
+        let name = self.value_name(&name, &at, function_names);
+        let hint = pascal_case(&name);
+
+        let mut params = Vec::new();
+        let mut param_names = HashSet::new();
+
+        // Pfad-Parameter in der Reihenfolge des Pfads, `{id}` → `:id`
+        let mut route = Vec::new();
+        for segment in path.split('/') {
+            let Some(param) = segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) else {
+                if segment.contains('{') {
+                    self.warn(format!(
+                        "{}: Pfadsegment '{}' wird nicht unterstützt",
+                        at, segment
+                    ));
+                }
+                route.push(segment.to_string());
+                continue;
+            };
+            let param_at = format!("{}: Pfad-Parameter '{}'", at, param);
+            let param_name = self.value_name(param, &param_at, &mut param_names);
+            let param_type = match operation
+                .parameters
+                .iter()
+                .find(|p| p.location == "path" && p.name == param)
+            {
+                Some(p) => self.map_type(
+                    &p.schema,
+                    &format!("{}{}", hint, pascal_case(param)),
+                    &param_at,
+                ),
+                None => Type::String,
+            };
+            route.push(format!(":{}", param_name));
+            params.push(Parameter {
+                name: param_name,
+                param_type,
+                default: None,
+            });
+        }
+
+        // Request-Body direkt nach den Pfad-Parametern: der Generator nimmt den
+        // ersten nicht-primitiven Parameter als Body
+        let allows_body = matches!(method, "post" | "put" | "patch");
+        let mut has_body = false;
+        if let Some(body) = &operation.request_body {
+            let body_at = format!("{}: requestBody", at);
+            match json_schema(&body.content) {
+                Some((media, schema)) => {
+                    if !media.contains("json") {
+                        self.warn(format!("{}: {} wird als JSON übernommen", body_at, media));
+                    }
+                    let base = match schema.component() {
+                        Some(component) => camel_case(
+                            self.type_names
+                                .get(component)
+                                .map(String::as_str)
+                                .unwrap_or("body"),
+                        ),
+                        None => "body".to_string(),
+                    };
+                    let body_name = self.value_name(&base, &body_at, &mut param_names);
+                    let (inner, nullable) = non_null(schema);
+                    let mut param_type = self.map_type(&inner, &format!("{}Body", hint), &body_at);
+                    if !allows_body {
+                        self.warn(format!(
+                            "{}: Bodies sind nur bei POST, PUT und PATCH möglich",
+                            body_at
+                        ));
+                    } else if is_scalar(&param_type) {
+                        self.warn(format!(
+                            "{}: primitiver Body wird als Query-Parameter generiert",
+                            body_at
+                        ));
+                    }
+                    if !body.required || nullable {
+                        param_type = option(param_type);
+                    }
+                    has_body = true;
+                    params.push(Parameter {
+                        name: body_name,
+                        param_type,
+                        default: None,
+                    });
+                }
+                None => self.warn(format!("{}: Body ohne Schema wird übersprungen", body_at)),
+            }
+        }
+
+        for param in &operation.parameters {
+            match param.location.as_str() {
+                "path" => continue,
+                "query" => {}
+                other => {
+                    self.warn(format!(
+                        "{}: {}-Parameter '{}' wird übersprungen",
+                        at, other, param.name
+                    ));
+                    continue;
+                }
+            }
+            let param_at = format!("{}: Query-Parameter '{}'", at, param.name);
+            let param_name = self.value_name(&param.name, &param_at, &mut param_names);
+            let (inner, nullable) = non_null(&param.schema);
+            let mut param_type = self.map_type(
+                &inner,
+                &format!("{}{}", hint, pascal_case(&param.name)),
+                &param_at,
+            );
+            if allows_body && !has_body && !is_scalar(&param_type) {
+                self.warn(format!(
+                    "{}: wird vom Generator als Body behandelt",
+                    param_at
+                ));
+            }
+            if !param.required || nullable {
+                param_type = option(param_type);
+            }
+            params.push(Parameter {
+                name: param_name,
+                param_type,
+                default: None,
+            });
+        }
+
+        let return_type = self.success_type(operation, &hint, &at);
+
+        let mut decorators = self.security_decorators(spec, operation, &at);
+        if !operation.tags.is_empty() {
+            decorators.push(decorator(
+                "Tag",
+                operation
+                    .tags
+                    .iter()
+                    .map(|t| DecoratorArg::String(t.clone()))
+                    .collect(),
+            ));
+        }
+        if operation.deprecated {
+            decorators.push(decorator("Deprecated", Vec::new()));
+        }
+        decorators.push(decorator(
+            &method.to_uppercase(),
+            vec![DecoratorArg::String(route.join("/"))],
+        ));
+
+        let documentation = match (&operation.summary, &operation.description) {
+            (Some(summary), Some(description)) if summary.trim() != description.trim() => {
+                Some(format!("{}\n{}", summary.trim(), description.trim()))
+            }
+            (Some(doc), _) | (None, Some(doc)) => Some(doc.trim().to_string()),
+            (None, None) => None,
+        };
+
+        Function {
+            decorators,
+            visibility: Visibility::Private,
+            name: name.clone(),
+            type_params: Vec::new(),
+            params,
+            return_type,
+            body: Block {
+                statements: vec![Statement::Throw(ThrowStatement {
+                    expression: Expression::Literal(Literal::String(format!(
+                        "Nicht implementiert: {}",
+                        name
+                    ))),
+                })],
+            },
+            is_async: false,
+            is_const: false,
+            documentation,
+        }
+    }
+
+    /// Typ der ersten 2xx-Antwort mit Inhalt
+    fn success_type(&mut self, operation: &Operation, hint: &str, at: &str) -> Option<Type> {
+        let (status, response) = operation
+            .responses
+            .iter()
+            .find(|(status, _)| status.starts_with('2'))?;
+        let content = response.content.as_ref()?;
+        let response_at = format!("{}: Antwort {}", at, status);
+        let (media, schema) = json_schema(content)?;
+        if !media.contains("json") {
+            self.warn(format!(
+                "{}: {} wird als JSON übernommen",
+                response_at, media
+            ));
+        }
+        let (inner, nullable) = non_null(schema);
+        let ty = self.map_type(&inner, &format!("{}Response", hint), &response_at);
+        Some(if nullable { option(ty) } else { ty })
+    }
+
+    /// `@Auth`, `@Role`, `@OAuth2` und `@OIDC` aus den Security-Anforderungen
+    fn security_decorators(
+        &mut self,
+        spec: &OpenAPISpec,
+        operation: &Operation,
+        at: &str,
+    ) -> Vec<Decorator> {
+        let mut bearer = false;
+        let mut oauth2 = false;
+        let mut oidc = false;
+        let mut scopes: Vec<String> = Vec::new();
+        for requirement in &operation.security {
+            for (scheme_name, required_scopes) in requirement {
+                let scheme = spec.components.security_schemes.get(scheme_name);
+                match scheme.map(|s| (s.scheme_type.as_str(), s.scheme.as_deref())) {
+                    Some(("oauth2", _)) => oauth2 = true,
+                    Some(("openIdConnect", _)) => oidc = true,
+                    Some(("http", Some(kind))) if kind.eq_ignore_ascii_case("bearer") => {
+                        bearer = true
+                    }
+                    Some((kind, _)) => {
+                        self.warn(format!(
+                            "{}: Security-Scheme '{}' ({}) wird als Bearer-Auth übernommen",
+                            at, scheme_name, kind
+                        ));
+                        bearer = true;
+                    }
+                    None => {
+                        self.warn(format!(
+                            "{}: unbekanntes Security-Scheme '{}' wird als Bearer-Auth übernommen",
+                            at, scheme_name
+                        ));
+                        bearer = true;
+                    }
+                }
+                for scope in required_scopes {
+                    if !scopes.contains(scope) {
+                        scopes.push(scope.clone());
+                    }
+                }
+            }
+        }
+        if bearer && (oauth2 || oidc) {
+            self.warn(format!(
+                "{}: Bearer-Auth neben OAuth2/OIDC wird nicht übernommen",
+                at
+            ));
+        }
+
+        let mut decorators = Vec::new();
+        if oauth2 {
+            decorators.push(decorator("OAuth2", Vec::new()));
+        }
+        if oidc {
+            decorators.push(decorator("OIDC", Vec::new()));
+        }
+        if !scopes.is_empty() {
+            decorators.push(decorator(
+                "Role",
+                scopes.into_iter().map(DecoratorArg::String).collect(),
+            ));
+        } else if bearer && !oauth2 && !oidc {
+            decorators.push(decorator("Auth", Vec::new()));
+        }
+        decorators
+    }
+
+    fn normalize_document(&mut self, document: &mut Node) {
+        if let Some(Node::Mapping(schemas)) = pointer(document, &["components", "schemas"]) {
+            self.raw_schemas = schemas.clone();
+        }
+        let snapshot = document.clone();
+
+        let Node::Mapping(root) = document else {
+            return;
+        };
+        let info = root
+            .entry(key("info"))
+            .or_insert_with(|| Node::Mapping(Mapping::new()));
+        if let Node::Mapping(info) = info {
+            info.entry(key("title")).or_insert_with(|| key("API"));
+            info.entry(key("version")).or_insert_with(|| key("1.0.0"));
+        }
+        let default_security = root.get("security").cloned();
+
+        if let Some(Node::Mapping(components)) = root.get_mut("components") {
+            if let Some(Node::Mapping(schemas)) = components.get_mut("schemas") {
+                for (name, schema) in schemas.iter_mut() {
+                    let at = format!("components.schemas.{}", name.as_str().unwrap_or_default());
+                    self.normalize_schema(schema, &at, 0);
+                }
+            }
+            // Flows braucht der Import nicht, nur die Art des Schemes
+            if let Some(Node::Mapping(schemes)) = components.get_mut("securitySchemes") {
+                for scheme in schemes.values_mut() {
+                    if let Node::Mapping(scheme) = scheme {
+                        scheme.shift_remove("flows");
+                    }
+                }
+            }
+        }
+
+        let Some(Node::Mapping(paths)) = root.get_mut("paths") else {
+            return;
+        };
+        for (path, item) in paths.iter_mut() {
+            let path = path.as_str().unwrap_or_default();
+            let Node::Mapping(item) = item else { continue };
+            let shared = match item.shift_remove("parameters") {
+                Some(Node::Sequence(params)) => params,
+                _ => Vec::new(),
+            };
+            for method in ["head", "options", "trace"] {
+                if item.shift_remove(method).is_some() {
+                    self.warn(format!(
+                        "{} {}: Methode wird nicht unterstützt",
+                        method.to_uppercase(),
+                        path
+                    ));
+                }
+            }
+            for method in ROUTE_METHODS {
+                let Some(Node::Mapping(operation)) = item.get_mut(*method) else {
+                    continue;
+                };
+                let at = format!("{} {}", method.to_uppercase(), path);
+                self.normalize_operation(operation, &shared, &snapshot, &at);
+                if !operation.contains_key("security") {
+                    if let Some(security) = &default_security {
+                        operation.insert(key("security"), security.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn normalize_operation(
+        &mut self,
+        operation: &mut Mapping,
+        shared: &[Node],
+        snapshot: &Node,
+        at: &str,
+    ) {
+        let mut params: Vec<Node> = match operation.shift_remove("parameters") {
+            Some(Node::Sequence(params)) => params,
+            _ => Vec::new(),
+        }
+        .into_iter()
+        .filter_map(|p| self.resolve(p, snapshot, at))
+        .collect();
+        let identity = |p: &Node| (p.get("name").cloned(), p.get("in").cloned());
+        for param in shared {
+            if let Some(param) = self.resolve(param.clone(), snapshot, at) {
+                if !params.iter().any(|p| identity(p) == identity(&param)) {
+                    params.push(param);
+                }
+            }
+        }
+        for param in params.iter_mut() {
+            let Node::Mapping(param) = param else {
+                continue;
+            };
+            // Parameter mit `content` statt `schema`
+            if !param.contains_key("schema") {
+                let schema = param
+                    .get("content")
+                    .and_then(Node::as_mapping)
+                    .and_then(|content| content.values().next())
+                    .and_then(|media| media.get("schema"))
+                    .cloned()
+                    .unwrap_or_else(|| Node::Mapping(Mapping::new()));
+                param.insert(key("schema"), schema);
+            }
+            let name = param
+                .get("name")
+                .and_then(Node::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if let Some(schema) = param.get_mut("schema") {
+                self.normalize_schema(schema, &format!("{}: Parameter '{}'", at, name), 0);
+            }
+        }
+        operation.insert(key("parameters"), Node::Sequence(params));
+
+        if let Some(body) = operation.shift_remove("requestBody") {
+            if let Some(mut body) = self.resolve(body, snapshot, at) {
+                self.normalize_content(&mut body, &format!("{}: requestBody", at));
+                operation.insert(key("requestBody"), body);
+            }
+        }
+
+        let mut responses = Mapping::new();
+        if let Some(Node::Mapping(original)) = operation.shift_remove("responses") {
+            for (status, response) in original {
+                let Some(mut response) = self.resolve(response, snapshot, at) else {
+                    continue;
+                };
+                if let Node::Mapping(fields) = &mut response {
+                    fields.entry(key("description")).or_insert_with(|| key(""));
+                }
+                let status = status.as_str().unwrap_or_default().to_string();
+                self.normalize_content(&mut response, &format!("{}: Antwort {}", at, status));
+                responses.insert(key(&status), response);
+            }
+        }
+        operation.insert(key("responses"), Node::Mapping(responses));
+    }
+
+    fn normalize_content(&mut self, value: &mut Node, at: &str) {
+        let Node::Mapping(fields) = value else { return };
+        let Node::Mapping(content) = fields
+            .entry(key("content"))
+            .or_insert_with(|| Node::Mapping(Mapping::new()))
+        else {
+            return;
+        };
+        for media in content.values_mut() {
+            if let Node::Mapping(media) = media {
+                let schema = media
+                    .entry(key("schema"))
+                    .or_insert_with(|| Node::Mapping(Mapping::new()));
+                self.normalize_schema(schema, at, 0);
+            }
+        }
+    }
+
+    /// Löst `$ref` auf `#/components/parameters|requestBodies|responses/...` auf
+    fn resolve(&mut self, mut value: Node, snapshot: &Node, at: &str) -> Option<Node> {
+        for _ in 0..16 {
+            let Some(reference) = value.get("$ref").and_then(Node::as_str) else {
+                return Some(value);
+            };
+            let segments: Vec<String> = reference
+                .strip_prefix("#/")
+                .map(|pointer| {
+                    pointer
+                        .split('/')
+                        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+            match pointer(snapshot, &segments).filter(|_| !segments.is_empty()) {
+                Some(target) => value = target.clone(),
+                None => {
+                    self.warn(format!("{}: $ref '{}' wird nicht aufgelöst", at, reference));
+                    return None;
+                }
+            }
+        }
+        self.warn(format!("{}: zyklische $ref-Kette", at));
+        None
+    }
+
+    fn normalize_schema(&mut self, value: &mut Node, at: &str, depth: usize) {
+        if let Node::Bool(allowed) = value {
+            if !*allowed {
+                self.warn(format!("{}: Schema `false` wird als any übernommen", at));
+            }
+            *value = Node::Mapping(Mapping::new());
+        }
+        let Node::Mapping(schema) = value else { return };
+
+        if let Some(Node::Sequence(parts)) = schema.shift_remove("allOf") {
+            self.merge_all_of(schema, parts, at, depth);
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(Node::as_str) {
+            match schema_ref_name(reference) {
+                Some(name) => {
+                    let target = format!("{}{}", SCHEMA_PREFIX, name);
+                    schema.insert(key("$ref"), key(&target));
+                }
+                None => {
+                    self.warn(format!(
+                        "{}: $ref '{}' wird als any übernommen",
+                        at, reference
+                    ));
+                    schema.shift_remove("$ref");
+                }
+            }
+        }
+        if let Some(any_of) = schema.shift_remove("anyOf") {
+            schema.entry(key("oneOf")).or_insert(any_of);
+        }
+        if let Some(constant) = schema.shift_remove("const") {
+            schema
+                .entry(key("enum"))
+                .or_insert_with(|| Node::Sequence(vec![constant]));
+        }
+        if let Some(example) = schema.shift_remove("example") {
+            schema
+                .entry(key("examples"))
+                .or_insert_with(|| Node::Sequence(vec![example]));
+        }
+        if !matches!(schema.get("examples"), None | Some(Node::Sequence(_))) {
+            schema.shift_remove("examples");
+        }
+        for keyword in ["exclusiveMinimum", "exclusiveMaximum"] {
+            match schema.shift_remove(keyword) {
+                None | Some(Node::Bool(false)) => {}
+                Some(_) => self.warn(format!("{}: {} wird nicht unterstützt", at, keyword)),
+            }
+        }
+        // Tupel alter Drafts: `items: [..]`
+        if let Some(Node::Sequence(items)) = schema.get("items").cloned() {
+            schema.shift_remove("items");
+            schema
+                .entry(key("prefixItems"))
+                .or_insert(Node::Sequence(items));
+        }
+        match schema.get("additionalProperties") {
+            Some(Node::Bool(true)) => {
+                schema.insert(key("additionalProperties"), Node::Mapping(Mapping::new()));
+            }
+            Some(Node::Bool(false)) => {
+                schema.shift_remove("additionalProperties");
+            }
+            _ => {}
+        }
+
+        if depth < 64 {
+            if let Some(Node::Mapping(properties)) = schema.get_mut("properties") {
+                for (name, property) in properties.iter_mut() {
+                    let at = format!("{}.{}", at, name.as_str().unwrap_or_default());
+                    self.normalize_schema(property, &at, depth + 1);
+                }
+            }
+            for child in ["items", "additionalProperties"] {
+                if let Some(child) = schema.get_mut(child) {
+                    self.normalize_schema(child, at, depth + 1);
+                }
+            }
+            for children in ["prefixItems", "oneOf"] {
+                if let Some(Node::Sequence(children)) = schema.get_mut(children) {
+                    for child in children {
+                        self.normalize_schema(child, at, depth + 1);
+                    }
+                }
+            }
+        }
+
+        // OpenAPI 3.0: `nullable: true` → Typ-Liste bzw. `oneOf` mit null
+        if schema.shift_remove("nullable") == Some(Node::Bool(true)) {
+            match schema.get("type").cloned() {
+                Some(Node::String(ty))
+                    if !schema.contains_key("$ref") && !schema.contains_key("oneOf") =>
+                {
+                    schema.insert(
+                        key("type"),
+                        Node::Sequence(vec![Node::String(ty), key("null")]),
+                    );
+                    if let Some(Node::Sequence(values)) = schema.get_mut("enum") {
+                        values.push(Node::Null);
+                    }
+                }
+                _ => {
+                    let inner = std::mem::take(schema);
+                    let mut null = Mapping::new();
+                    null.insert(key("type"), key("null"));
+                    schema.insert(
+                        key("oneOf"),
+                        Node::Sequence(vec![Node::Mapping(inner), Node::Mapping(null)]),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Führt `allOf`-Teile zu einem Schema zusammen; Referenzen werden dabei
+    /// eingebettet, das Ergebnis ist ein strukturell gleichwertiges Objekt
+    fn merge_all_of(&mut self, schema: &mut Mapping, parts: Vec<Node>, at: &str, depth: usize) {
+        for mut part in parts {
+            for _ in 0..16 {
+                let Some(reference) = part.get("$ref").and_then(Node::as_str) else {
+                    break;
+                };
+                match schema_ref_name(reference).and_then(|name| self.raw_schemas.get(name)) {
+                    Some(target) => part = target.clone(),
+                    None => {
+                        self.warn(format!("{}: allOf-Referenz '{}' fehlt", at, reference));
+                        part = Node::Mapping(Mapping::new());
+                    }
+                }
+            }
+            let Node::Mapping(mut part) = part else {
+                continue;
+            };
+            if let Some(Node::Sequence(nested)) = part.shift_remove("allOf") {
+                if depth < 16 {
+                    self.merge_all_of(&mut part, nested, at, depth + 1);
+                }
+            }
+            for (name, value) in part {
+                match (name.as_str(), schema.get_mut(&name), value) {
+                    (Some("properties"), Some(Node::Mapping(existing)), Node::Mapping(added)) => {
+                        for (property, definition) in added {
+                            existing.entry(property).or_insert(definition);
+                        }
+                    }
+                    (Some("required"), Some(Node::Sequence(existing)), Node::Sequence(added)) => {
+                        for required in added {
+                            if !existing.contains(&required) {
+                                existing.push(required);
+                            }
+                        }
+                    }
+                    (_, Some(_), _) => {}
+                    (_, None, value) => {
+                        schema.insert(name, value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn key(name: &str) -> Node {
+    Node::String(name.to_string())
+}
+
+/// `a.b.c`-Zugriff auf verschachtelte Mappings
+fn pointer<'a>(node: &'a Node, segments: &[&str]) -> Option<&'a Node> {
+    segments.iter().try_fold(node, |node, segment| match node {
+        Node::Mapping(mapping) => mapping.get(*segment),
+        Node::Sequence(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => None,
+    })
+}
+
+/// YAML erlaubt Zahlen und Booleans als Schlüssel (`200:`), OpenAPI erwartet Strings
+fn stringify_keys(node: &mut Node) {
+    match node {
+        Node::Mapping(mapping) => {
+            let entries = std::mem::take(mapping);
+            for (name, mut value) in entries {
+                stringify_keys(&mut value);
+                let name = match name {
+                    Node::String(name) => name,
+                    Node::Number(n) => n.to_string(),
+                    Node::Bool(b) => b.to_string(),
+                    Node::Null => "null".to_string(),
+                    other => serde_yaml::to_string(&other)
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string(),
+                };
+                mapping.insert(Node::String(name), value);
+            }
+        }
+        Node::Sequence(items) => items.iter_mut().for_each(stringify_keys),
+        Node::Tagged(tagged) => stringify_keys(&mut tagged.value),
+        _ => {}
+    }
+}
+
+/// JSON Schema ohne OpenAPI-Hülle: `$defs`/`definitions` und das Wurzel-Schema
+/// (benannt nach `title`) werden zu `components/schemas`
+fn wrap_json_schema(document: Node) -> Node {
+    let Node::Mapping(mut root) = document else {
+        return document;
+    };
+    let mut schemas = Mapping::new();
+    for definitions in ["$defs", "definitions"] {
+        if let Some(Node::Mapping(definitions)) = root.shift_remove(definitions) {
+            schemas.extend(definitions);
+        }
+    }
+    root.shift_remove("$schema");
+    root.shift_remove("$id");
+
+    let title = root
+        .get("title")
+        .and_then(Node::as_str)
+        .unwrap_or("Schema")
+        .to_string();
+    let is_schema = [
+        "type",
+        "properties",
+        "enum",
+        "oneOf",
+        "anyOf",
+        "allOf",
+        "$ref",
+    ]
+    .iter()
+    .any(|keyword| root.contains_key(*keyword));
+    if is_schema {
+        let name = match pascal_case(&title) {
+            name if name.is_empty() => "Schema".to_string(),
+            name => name,
+        };
+        // `"$ref": "#"` verweist auf das Wurzel-Schema
+        let target = format!("{}{}", SCHEMA_PREFIX, name);
+        let mut root = Node::Mapping(root);
+        replace_root_refs(&mut root, &target);
+        for schema in schemas.values_mut() {
+            replace_root_refs(schema, &target);
+        }
+        schemas.insert(key(&name), root);
+    }
+
+    let mut info = Mapping::new();
+    info.insert(key("title"), key(&title));
+    info.insert(key("version"), key("1.0.0"));
+    let mut components = Mapping::new();
+    components.insert(key("schemas"), Node::Mapping(schemas));
+    let mut document = Mapping::new();
+    document.insert(key("openapi"), key(super::openapi::OPENAPI_VERSION));
+    document.insert(key("info"), Node::Mapping(info));
+    document.insert(key("paths"), Node::Mapping(Mapping::new()));
+    document.insert(key("components"), Node::Mapping(components));
+    Node::Mapping(document)
+}
+
+fn replace_root_refs(node: &mut Node, target: &str) {
+    match node {
+        Node::Mapping(fields) => {
+            if fields.get("$ref").and_then(Node::as_str) == Some("#") {
+                fields.insert(key("$ref"), key(target));
+            }
+            for child in fields.values_mut() {
+                replace_root_refs(child, target);
+            }
+        }
+        Node::Sequence(items) => {
+            for item in items {
+                replace_root_refs(item, target);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn schema_ref_name(reference: &str) -> Option<&str> {
+    SCHEMA_REF_PREFIXES
+        .iter()
+        .find_map(|prefix| reference.strip_prefix(prefix))
+        .filter(|name| !name.is_empty() && !name.contains('/'))
+}
+
+/// Schema ohne `null`-Anteil, plus ob `null` erlaubt war
+fn non_null(schema: &Schema) -> (Schema, bool) {
+    let null = Some(SchemaType::Single("null".to_string()));
+    if let Some(SchemaType::Multiple(types)) = &schema.schema_type {
+        let rest: Vec<String> = types.iter().filter(|t| *t != "null").cloned().collect();
+        if rest.len() < types.len() {
+            let mut inner = schema.clone();
+            inner.schema_type = match rest.len() {
+                0 => None,
+                1 => Some(SchemaType::Single(rest[0].clone())),
+                _ => Some(SchemaType::Multiple(rest)),
+            };
+            inner.enum_values.retain(|v| !v.is_null());
+            return (inner, true);
+        }
+    }
+    if schema.one_of.iter().any(|s| s.schema_type == null) {
+        let rest: Vec<Schema> = schema
+            .one_of
+            .iter()
+            .filter(|s| s.schema_type != null)
+            .cloned()
+            .collect();
+        let mut inner = if rest.len() == 1 {
+            let mut single = rest[0].clone();
+            if single.description.is_none() {
+                single.description = schema.description.clone();
+            }
+            single
+        } else {
+            Schema {
+                one_of: rest,
+                ..schema.clone()
+            }
+        };
+        inner.enum_values.retain(|v| !v.is_null());
+        return (inner, true);
+    }
+    (schema.clone(), false)
+}
+
+fn is_struct(schema: &Schema) -> bool {
+    schema.ref_path.is_none()
+        && schema.one_of.is_empty()
+        && (!schema.properties.is_empty()
+            || (schema.primary_type() == Some("object") && schema.additional_properties.is_none()))
+}
+
+/// Werte einer String-Aufzählung, wenn alle als Variantennamen taugen
+fn unit_values(schema: &Schema) -> Option<Vec<String>> {
+    if schema.ref_path.is_some()
+        || !schema.properties.is_empty()
+        || !matches!(schema.primary_type(), None | Some("string"))
+    {
+        return None;
+    }
+    let values: Vec<&Value> = schema.enum_values.iter().filter(|v| !v.is_null()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    values
+        .into_iter()
+        .map(|v| v.as_str().filter(|s| is_identifier(s)).map(str::to_string))
+        .collect()
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !RESERVED.contains(&name)
+}
+
+fn type_identifier(name: &str) -> String {
+    if is_identifier(name) && !BUILTIN_TYPES.contains(&name) {
+        return name.to_string();
+    }
+    let name = pascal_case(name);
+    if name.is_empty() {
+        "Schema".to_string()
+    } else if is_identifier(&name) && !BUILTIN_TYPES.contains(&name.as_str()) {
+        name
+    } else {
+        format!("{}Type", name)
+    }
+}
+
+fn value_identifier(name: &str) -> String {
+    if is_identifier(name) {
+        return name.to_string();
+    }
+    let name = camel_case(name);
+    if name.is_empty() {
+        "value".to_string()
+    } else if is_identifier(&name) {
+        name
+    } else {
+        format!("{}_", name)
+    }
+}
+
+fn is_scalar(ty: &Type) -> bool {
+    matches!(ty, Type::String | Type::Number | Type::Boolean)
+}
+
+fn option(ty: Type) -> Type {
+    Type::Generic {
+        name: "Option".to_string(),
+        params: vec![ty],
+    }
+}
+
+fn description(schema: &Schema) -> Option<String> {
+    schema
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+}
+
+fn decorator(name: &str, args: Vec<DecoratorArg>) -> Decorator {
+    Decorator {
+        name: name.to_string(),
+        args,
+    }
+}
+
+fn named(name: &str, value: DecoratorArg) -> DecoratorArg {
+    DecoratorArg::Named {
+        name: name.to_string(),
+        value: Box::new(value),
+    }
+}
+
+fn decorator_arg(value: &Value) -> Option<DecoratorArg> {
+    match value {
+        Value::String(s) => Some(DecoratorArg::String(s.clone())),
+        Value::Number(n) => n.as_f64().map(DecoratorArg::Number),
+        Value::Bool(b) => Some(DecoratorArg::Boolean(*b)),
+        Value::Array(items) => items
+            .iter()
+            .map(decorator_arg)
+            .collect::<Option<Vec<_>>>()
+            .map(DecoratorArg::List),
+        Value::Null | Value::Object(_) => None,
+    }
+}
+
+/// JSON-Inhalt bevorzugt, sonst der erste Medientyp
+fn json_schema(content: &BTreeMap<String, super::openapi::MediaType>) -> Option<(&str, &Schema)> {
+    content
+        .iter()
+        .find(|(media, _)| media.as_str() == "application/json" || media.ends_with("+json"))
+        .or_else(|| content.iter().next())
+        .map(|(media, m)| (media.as_str(), &m.schema))
+}
+
+/// Generiert aus `source` erneut ein Dokument und listet alle Abweichungen
+/// vom Original (Parameter, Bodies, Antworten, Security, Komponenten).
+/// Leer bedeutet verlustfrei; `Err` enthält den Parser-Fehler.
+pub fn round_trip(original: &OpenAPISpec, source: &str) -> Result<Vec<String>, String> {
+    let program = Parser::parse(source).map_err(|e| e.to_string())?;
+    let generator = OpenAPIGenerator {
+        title: original.info.title.clone(),
+        version: original.info.version.clone(),
+        description: original.info.description.clone(),
+    };
+    let regenerated = generator.build(&program);
+
+    // Nur Structs und Enums sind nominal, Aliase werden strukturell verglichen
+    let nominal: HashSet<String> = original
+        .components
+        .schemas
+        .iter()
+        .filter(|(_, schema)| {
+            let (inner, _) = non_null(schema);
+            is_struct(&inner) || unit_values(&inner).is_some() || !inner.one_of.is_empty()
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+    let before = Signatures {
+        spec: original,
+        nominal: &nominal,
+    };
+    let after = Signatures {
+        spec: &regenerated,
+        nominal: &nominal,
+    };
+
+    let mut differences = Vec::new();
+    for (path, item) in &original.paths {
+        for (method, operation) in item.operations() {
+            let at = format!("{} {}", method.to_uppercase(), path);
+            let Some(other) = find_operation(&regenerated, path, method) else {
+                differences.push(format!("{}: Operation fehlt", at));
+                continue;
+            };
+
+            for param in &operation.parameters {
+                let Some(generated) = other
+                    .parameters
+                    .iter()
+                    .find(|p| p.name == param.name && p.location == param.location)
+                else {
+                    differences.push(format!(
+                        "{}: {}-Parameter '{}' fehlt",
+                        at, param.location, param.name
+                    ));
+                    continue;
+                };
+                if param.required != generated.required {
+                    differences.push(format!(
+                        "{}: Parameter '{}' ist {}",
+                        at,
+                        param.name,
+                        if generated.required {
+                            "Pflicht statt optional"
+                        } else {
+                            "optional statt Pflicht"
+                        }
+                    ));
+                }
+                let (expected, actual) = (before.of(&param.schema), after.of(&generated.schema));
+                if expected != actual {
+                    differences.push(format!(
+                        "{}: Parameter '{}' hat Typ {} statt {}",
+                        at, param.name, actual, expected
+                    ));
+                }
+            }
+            for generated in &other.parameters {
+                if !operation
+                    .parameters
+                    .iter()
+                    .any(|p| p.name == generated.name && p.location == generated.location)
+                {
+                    differences.push(format!(
+                        "{}: zusätzlicher {}-Parameter '{}'",
+                        at, generated.location, generated.name
+                    ));
+                }
+            }
+
+            let body = |op: &Operation, signatures: &Signatures| {
+                op.request_body.as_ref().map(|body| {
+                    let schema = json_schema(&body.content)
+                        .map(|(_, schema)| signatures.of(schema))
+                        .unwrap_or_else(|| "any".to_string());
+                    format!(
+                        "{}{}",
+                        schema,
+                        if body.required { "" } else { " (optional)" }
+                    )
+                })
+            };
+            let (expected, actual) = (body(operation, &before), body(other, &after));
+            if expected != actual {
+                differences.push(format!(
+                    "{}: Body ist {} statt {}",
+                    at,
+                    actual.as_deref().unwrap_or("leer"),
+                    expected.as_deref().unwrap_or("leer")
+                ));
+            }
+
+            let (expected, actual) = (
+                success_signature(operation, &before),
+                success_signature(other, &after),
+            );
+            if expected != actual {
+                differences.push(format!(
+                    "{}: Antwort ist {} statt {}",
+                    at,
+                    actual.as_deref().unwrap_or("leer"),
+                    expected.as_deref().unwrap_or("leer")
+                ));
+            }
+
+            let (expected, actual) = (
+                security_signature(original, operation),
+                security_signature(&regenerated, other),
+            );
+            if expected != actual {
+                differences.push(format!(
+                    "{}: Security ist {:?} statt {:?}",
+                    at, actual, expected
+                ));
+            }
+        }
+    }
+    for (path, item) in &regenerated.paths {
+        for (method, _) in item.operations() {
+            if find_operation(original, path, method).is_none() {
+                differences.push(format!(
+                    "{} {}: zusätzliche Operation",
+                    method.to_uppercase(),
+                    path
+                ));
+            }
+        }
+    }
+
+    for (name, schema) in &original.components.schemas {
+        match regenerated.components.schemas.get(name) {
+            None => differences.push(format!("components.schemas.{}: fehlt", name)),
+            Some(generated) => {
+                let (expected, actual) = (before.expand(schema), after.expand(generated));
+                if expected != actual {
+                    differences.push(format!(
+                        "components.schemas.{}: {} statt {}",
+                        name, actual, expected
+                    ));
+                }
+            }
+        }
+    }
+    Ok(differences)
+}
+
+/// Operation zum gleichen Pfad-Template; umbenannte Pfad-Parameter
+/// zeigen sich dann als Parameter-Abweichung statt als fehlende Operation
+fn find_operation<'a>(spec: &'a OpenAPISpec, path: &str, method: &str) -> Option<&'a Operation> {
+    let template = |path: &str| {
+        path.split('/')
+            .map(|segment| {
+                if segment.starts_with('{') && segment.ends_with('}') {
+                    "{}"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    };
+    let wanted = template(path);
+    spec.paths
+        .iter()
+        .filter(|(candidate, _)| template(candidate) == wanted)
+        .flat_map(|(_, item)| item.operations())
+        .find(|(m, _)| *m == method)
+        .map(|(_, op)| op)
+}
+
+fn success_signature(operation: &Operation, signatures: &Signatures) -> Option<String> {
+    let (_, response) = operation
+        .responses
+        .iter()
+        .find(|(status, _)| status.starts_with('2'))?;
+    let (_, schema) = json_schema(response.content.as_ref()?)?;
+    Some(signatures.of(schema))
+}
+
+/// Art des Schemes und Scopes je Anforderung; API-Keys zählen wie Bearer-Tokens
+fn security_signature(spec: &OpenAPISpec, operation: &Operation) -> BTreeSet<String> {
+    let mut signature = BTreeSet::new();
+    for requirement in &operation.security {
+        for (scheme_name, scopes) in requirement {
+            let kind = match spec
+                .components
+                .security_schemes
+                .get(scheme_name)
+                .map(|s| s.scheme_type.as_str())
+            {
+                Some("oauth2") => "oauth2",
+                Some("openIdConnect") => "openIdConnect",
+                _ => "bearer",
+            };
+            let mut scopes = scopes.clone();
+            scopes.sort();
+            signature.insert(format!("{}{:?}", kind, scopes));
+        }
+    }
+    signature
+}
+
+/// Strukturelle Typ-Signatur eines Schemas: `null`, Formate außer `email`
+/// und der Unterschied integer/number spielen keine Rolle
+struct Signatures<'a> {
+    spec: &'a OpenAPISpec,
+    nominal: &'a HashSet<String>,
+}
+
+impl Signatures<'_> {
+    fn of(&self, schema: &Schema) -> String {
+        self.render(schema, &mut Vec::new())
+    }
+
+    /// Signatur einer Komponente selbst statt ihres Namens
+    fn expand(&self, schema: &Schema) -> String {
+        let (inner, _) = non_null(schema);
+        match inner.component() {
+            Some(_) => self.of(&inner),
+            None => self.structure(&inner, &mut Vec::new()),
+        }
+    }
+
+    fn render(&self, schema: &Schema, stack: &mut Vec<String>) -> String {
+        let (schema, _) = non_null(schema);
+        if let Some(component) = schema.component() {
+            if self.nominal.contains(component) || stack.iter().any(|c| c == component) {
+                return component.to_string();
+            }
+            return match self.spec.components.schemas.get(component) {
+                Some(target) => {
+                    stack.push(component.to_string());
+                    let signature = self.render(target, stack);
+                    stack.pop();
+                    signature
+                }
+                None => component.to_string(),
+            };
+        }
+        self.structure(&schema, stack)
+    }
+
+    fn structure(&self, schema: &Schema, stack: &mut Vec<String>) -> String {
+        if !schema.one_of.is_empty() {
+            let mut variants: Vec<String> = schema
+                .one_of
+                .iter()
+                .map(|s| self.render(s, stack))
+                .collect();
+            variants.sort();
+            return format!("oneOf({})", variants.join(" | "));
+        }
+
+        let mut signature = match schema.primary_type() {
+            Some("string") => "string".to_string(),
+            Some("number" | "integer") => "number".to_string(),
+            Some("boolean") => "boolean".to_string(),
+            Some("array") if !schema.prefix_items.is_empty() => format!(
+                "Tuple<{}>",
+                schema
+                    .prefix_items
+                    .iter()
+                    .map(|s| self.render(s, stack))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Some("array") => format!(
+                "List<{}>",
+                schema
+                    .items
+                    .as_ref()
+                    .map(|s| self.render(s, stack))
+                    .unwrap_or_else(|| "any".to_string())
+            ),
+            Some("object") | None if !schema.properties.is_empty() => {
+                let mut properties: Vec<String> = schema
+                    .properties
+                    .iter()
+                    .map(|(name, s)| {
+                        format!(
+                            "{}{}: {}",
+                            name,
+                            if schema.required.contains(name) {
+                                ""
+                            } else {
+                                "?"
+                            },
+                            self.render(s, stack)
+                        )
+                    })
+                    .collect();
+                properties.sort();
+                format!("{{{}}}", properties.join(", "))
+            }
+            Some("object") => format!(
+                "Map<{}>",
+                schema
+                    .additional_properties
+                    .as_ref()
+                    .map(|s| self.render(s, stack))
+                    .unwrap_or_else(|| "any".to_string())
+            ),
+            None if !schema.enum_values.is_empty() => "string".to_string(),
+            None => "any".to_string(),
+            Some(other) => other.to_string(),
+        };
+
+        let mut constraints = Vec::new();
+        let mut push = |name: &str, value: Option<String>| {
+            if let Some(value) = value {
+                constraints.push(format!("{}={}", name, value));
+            }
+        };
+        push("minLength", schema.min_length.map(|n| n.to_string()));
+        push("maxLength", schema.max_length.map(|n| n.to_string()));
+        push("minimum", schema.minimum.map(|n| n.to_string()));
+        push("maximum", schema.maximum.map(|n| n.to_string()));
+        // Tupel haben ihre Länge schon in der Signatur
+        let tuple = |n: &u64| schema.prefix_items.len() as u64 != *n;
+        push(
+            "minItems",
+            schema.min_items.filter(tuple).map(|n| n.to_string()),
+        );
+        push(
+            "maxItems",
+            schema.max_items.filter(tuple).map(|n| n.to_string()),
+        );
+        push("pattern", schema.pattern.clone());
+        push(
+            "format",
+            schema.format.clone().filter(|format| format == "email"),
+        );
+        let mut values: Vec<String> = schema
+            .enum_values
+            .iter()
+            .filter(|v| !v.is_null())
+            .map(|v| v.to_string())
+            .collect();
+        values.sort();
+        if !values.is_empty() {
+            push("enum", Some(values.join("|")));
+        }
+        if !constraints.is_empty() {
+            signature.push_str(&format!(" [{}]", constraints.join(", ")));
+        }
+        signature
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::{config::FormatConfig, formatter::Formatter};
+
+    const SPEC: &str = r##"{
+      "openapi": "3.0.3",
+      "info": { "title": "Shop", "version": "2.0.0" },
+      "security": [{ "bearer": [] }],
+      "paths": {
+        "/orders/{order-id}": {
+          "parameters": [
+            { "name": "order-id", "in": "path", "required": true, "schema": { "type": "string" } }
+          ],
+          "get": {
+            "operationId": "getOrder",
+            "summary": "Lädt eine Bestellung",
+            "parameters": [
+              { "name": "expand", "in": "query", "schema": { "type": "boolean" } },
+              { "name": "X-Trace", "in": "header", "schema": { "type": "string" } }
+            ],
+            "responses": {
+              "200": {
+                "description": "ok",
+                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Order" } } }
+              }
+            }
+          }
+        },
+        "/orders": {
+          "post": {
+            "operationId": "createOrder",
+            "security": [{ "bearer": ["admin"] }],
+            "requestBody": {
+              "required": true,
+              "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Order" } } }
+            },
+            "responses": { "204": { "description": "created" } }
+          }
+        }
+      },
+      "components": {
+        "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+        "schemas": {
+          "Status": { "type": "string", "enum": ["open", "paid"] },
+          "Order": {
+            "type": "object",
+            "description": "Eine Bestellung",
+            "required": ["id", "status", "items"],
+            "properties": {
+              "id": { "type": "string", "minLength": 3, "pattern": "^o-[0-9]{2}$", "example": "o-12" },
+              "status": { "$ref": "#/components/schemas/Status" },
+              "note": { "type": "string", "nullable": true, "maxLength": 200 },
+              "items": { "type": "array", "minItems": 1, "items": { "$ref": "#/components/schemas/Line" } }
+            }
+          },
+          "Line": {
+            "type": "object",
+            "required": ["sku", "quantity"],
+            "properties": {
+              "sku": { "type": "string" },
+              "quantity": { "type": "integer", "minimum": 1 }
+            }
+          }
+        }
+      }
+    }"##;
+
+    fn import(content: &str) -> (OpenAPISpec, String, Vec<String>) {
+        let mut importer = OpenAPIImporter::new();
+        let spec = importer.parse(content, false).expect("parse");
+        let program = importer.import(&spec);
+        let source = Formatter::new(FormatConfig::default()).format(&program);
+        (spec, source, importer.warnings().to_vec())
+    }
+
+    #[test]
+    fn test_import_generates_parseable_stubs() {
+        let (_, source, warnings) = import(SPEC);
+        assert!(source.contains("@Validate(min: 3, pattern: \"^o-[0-9]\\{2}$\")"));
+        assert!(source.contains("note: Option<string>"));
+        assert!(source.contains("@GET(\"/orders/:orderId\")"));
+        assert!(source.contains("fn getOrder(orderId: string, expand: Option<boolean>): Order"));
+        assert!(source.contains("@Role(\"admin\")"));
+        assert!(warnings.iter().any(|w| w.contains("X-Trace")));
+
+        let program = Parser::parse(&source).expect("generierter Code parst");
+        assert!(program
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Enum(e) if e.name == "Status")));
+    }
+
+    #[test]
+    fn test_round_trip_reports_only_lossy_parts() {
+        let (spec, source, _) = import(SPEC);
+        let differences = round_trip(&spec, &source).expect("round trip");
+        // Umbenannter Pfad-Parameter und übersprungener Header sind die einzigen Verluste
+        assert_eq!(differences.len(), 3, "{:#?}", differences);
+        assert!(differences.iter().any(|d| d.contains("'order-id' fehlt")));
+        assert!(differences
+            .iter()
+            .any(|d| d.contains("zusätzlicher path-Parameter 'orderId'")));
+        assert!(differences.iter().any(|d| d.contains("'X-Trace' fehlt")));
+
+        let json_schema = r##"{
+          "title": "Config",
+          "type": "object",
+          "required": ["name"],
+          "properties": {
+            "name": { "type": "string", "maxLength": 20 },
+            "mode": { "enum": ["fast", "safe"] },
+            "limits": { "$ref": "#/$defs/Limits" }
+          },
+          "$defs": {
+            "Limits": { "type": "object", "properties": { "cpu": { "type": "number" } } }
+          }
+        }"##;
+        let (spec, source, _) = import(json_schema);
+        assert!(source.contains("struct Config"));
+        assert_eq!(
+            round_trip(&spec, &source).expect("round trip"),
+            Vec::<String>::new()
+        );
+    }
+}
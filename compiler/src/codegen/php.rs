@@ -2,12 +2,15 @@ use crate::codegen::framework::{Framework, FrameworkSelector};
 use crate::codegen::traits::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::compiler::language::VELISCH_FINGERPRINT;
 use crate::parser::ast::*;
+use crate::validation::{self, Check, RuleValue, ValueKind};
+use std::collections::HashSet;
 
 pub struct PhpCodeGenerator {
     output: String,
     indent_level: usize,
     framework: Framework,
     routes: Vec<(String, String, String)>, // Method, Path, FunctionName
+    validated_structs: HashSet<String>,
 }
 
 impl CodeGenerator for PhpCodeGenerator {
@@ -19,6 +22,7 @@ impl CodeGenerator for PhpCodeGenerator {
         // Detect framework
         let framework = FrameworkSelector::detect_framework(program, config.framework.as_deref());
         self.framework = framework;
+        self.validated_structs = validation::validated_structs(&program.items);
 
         self.writeln("<?php");
         self.writeln(&format!("// {}", VELISCH_FINGERPRINT));
@@ -117,6 +121,7 @@ impl PhpCodeGenerator {
             indent_level: 0,
             framework: Framework::Axum,
             routes: Vec::new(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -177,11 +182,112 @@ impl PhpCodeGenerator {
             self.writeln("}");
         }
 
+        if self.validated_structs.contains(&s.name) {
+            self.generate_validation_method(s);
+        }
+
         self.dedent();
         self.writeln("}");
         self.writeln("");
     }
 
+    /// `validationErrors()` mit einem `['field', 'message']`-Array pro verletzter `@Validate`-Regel
+    fn generate_validation_method(&mut self, s: &Struct) {
+        self.writeln("");
+        self.writeln("public function validationErrors(): array {");
+        self.indent();
+        self.writeln("$errors = [];");
+        for field in validation::struct_validations(s) {
+            let access = format!("$this->{}", field.name);
+            for check in field.checks() {
+                let failed = match &check.check {
+                    Check::Required if field.kind == ValueKind::Text => {
+                        format!("{} === null || {} === ''", access, access)
+                    }
+                    Check::Required => format!("{} === null", access),
+                    Check::MinLength(n) => format!("mb_strlen({}) < {}", access, n),
+                    Check::MaxLength(n) => format!("mb_strlen({}) > {}", access, n),
+                    Check::Pattern(pattern) => format!(
+                        "!preg_match({}, {})",
+                        php_string(&format!("~{}~u", pattern.replace('~', "\\~"))),
+                        access
+                    ),
+                    Check::Min(n) => format!("{} < {:?}", access, n),
+                    Check::Max(n) => format!("{} > {:?}", access, n),
+                    Check::MinItems(n) => format!("count({}) < {}", access, n),
+                    Check::MaxItems(n) => format!("count({}) > {}", access, n),
+                    Check::OneOf(values) => {
+                        let values: Vec<String> = values
+                            .iter()
+                            .map(|v| match v {
+                                RuleValue::String(s) => php_string(s),
+                                RuleValue::Number(n) => format!("{:?}", n),
+                            })
+                            .collect();
+                        format!("!in_array({}, [{}])", access, values.join(", "))
+                    }
+                };
+                // Alle Regeln außer `required` gelten nur für vorhandene Werte
+                let condition = if check.check == Check::Required {
+                    failed
+                } else {
+                    format!("{} !== null && {}", access, failed)
+                };
+                self.writeln(&format!("if ({}) {{", condition));
+                self.indent();
+                self.writeln(&format!(
+                    "$errors[] = ['field' => {}, 'message' => {}];",
+                    php_string(&field.name),
+                    php_string(&check.message)
+                ));
+                self.dedent();
+                self.writeln("}");
+            }
+        }
+        self.writeln("return $errors;");
+        self.dedent();
+        self.writeln("}");
+    }
+
+    /// Prüft Request-Bodys mit `@Validate`-Feldern und antwortet mit 400
+    fn generate_request_validation(&mut self, f: &Function) {
+        let params: Vec<String> = validation::validated_params(f, &self.validated_structs)
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        for param in params {
+            let body = format!(
+                "['error' => '{}', 'errors' => ${}Errors]",
+                validation::VALIDATION_FAILED,
+                param
+            );
+            self.writeln(&format!(
+                "${}Errors = ${}->validationErrors();",
+                param, param
+            ));
+            self.writeln(&format!("if (!empty(${}Errors)) {{", param));
+            self.indent();
+            match self.framework {
+                Framework::Laravel => self.writeln(&format!(
+                    "throw new \\Illuminate\\Http\\Exceptions\\HttpResponseException(response()->json({}, 400));",
+                    body
+                )),
+                Framework::Symfony => self.writeln(&format!(
+                    "return new \\Symfony\\Component\\HttpFoundation\\JsonResponse({}, 400);",
+                    body
+                )),
+                _ => {
+                    self.writeln("http_response_code(400);");
+                    self.writeln("header('Content-Type: application/json');");
+                    self.writeln(&format!("echo json_encode({});", body));
+                    self.writeln("exit;");
+                }
+            }
+            self.dedent();
+            self.writeln("}");
+        }
+    }
+
     fn generate_enum(&mut self, e: &Enum) {
         self.writeln(&format!("enum {} {{", e.name));
         self.indent();
@@ -239,6 +345,7 @@ impl PhpCodeGenerator {
                         params.join(", ")
                     ));
                     self.indent();
+                    self.generate_request_validation(f);
                     for stmt in &f.body.statements {
                         self.generate_statement(stmt);
                     }
@@ -278,6 +385,7 @@ impl PhpCodeGenerator {
             return_type
         ));
         self.indent();
+        self.generate_request_validation(f);
 
         // Body
         for stmt in &f.body.statements {
@@ -740,3 +848,8 @@ impl PhpCodeGenerator {
         }
     }
 }
+
+/// PHP-String in einfachen Anführungszeichen
+fn php_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -2,12 +2,15 @@ use crate::codegen::framework::{Framework, FrameworkSelector};
 use crate::codegen::traits::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::compiler::language::VELISCH_FINGERPRINT;
 use crate::parser::ast::*;
+use crate::validation::{self, Check, RuleValue, ValueKind};
+use std::collections::HashSet;
 
 pub struct PythonCodeGenerator {
     output: String,
     indent_level: usize,
     framework: Framework,
     routes: Vec<(String, String, String)>,
+    validated_structs: HashSet<String>,
 }
 
 impl CodeGenerator for PythonCodeGenerator {
@@ -19,6 +22,7 @@ impl CodeGenerator for PythonCodeGenerator {
         // Detect framework
         let framework = FrameworkSelector::detect_framework(program, config.framework.as_deref());
         self.framework = framework;
+        self.validated_structs = validation::validated_structs(&program.items);
 
         self.writeln("#!/usr/bin/env python3");
         self.writeln(&format!("# {}", VELISCH_FINGERPRINT));
@@ -32,6 +36,12 @@ impl CodeGenerator for PythonCodeGenerator {
         self.writeln("from typing import List, Optional, Any, Dict");
         self.writeln("from enum import Enum");
         self.writeln("import sys");
+        if !self.validated_structs.is_empty() {
+            self.writeln("import re");
+            if matches!(self.framework, Framework::FastAPI) {
+                self.writeln("from fastapi.responses import JSONResponse");
+            }
+        }
         self.writeln("");
 
         // Generate Items
@@ -86,6 +96,7 @@ impl PythonCodeGenerator {
             indent_level: 0,
             framework: Framework::Axum,
             routes: Vec::new(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -132,10 +143,106 @@ impl PythonCodeGenerator {
             }
         }
 
+        if self.validated_structs.contains(&s.name) {
+            self.generate_validation_method(s);
+        }
+
         self.dedent();
         self.writeln("");
     }
 
+    /// `validation_errors()` mit einem `{field, message}`-Dict pro verletzter `@Validate`-Regel
+    fn generate_validation_method(&mut self, s: &Struct) {
+        self.write("\n");
+        self.writeln("def validation_errors(self) -> List[Dict[str, str]]:");
+        self.indent();
+        self.writeln("errors: List[Dict[str, str]] = []");
+        for field in validation::struct_validations(s) {
+            let access = format!("self.{}", field.name);
+            let checks = field.checks();
+            let (required, rest): (Vec<_>, Vec<_>) =
+                checks.iter().partition(|c| c.check == Check::Required);
+            for check in required {
+                let missing = if field.kind == ValueKind::Text {
+                    format!("not {}", access)
+                } else {
+                    format!("{} is None", access)
+                };
+                self.writeln(&format!("if {}:", missing));
+                self.indent();
+                self.writeln(&Self::validation_error(&field.name, &check.message));
+                self.dedent();
+            }
+            if rest.is_empty() {
+                continue;
+            }
+            self.writeln(&format!("if {} is not None:", access));
+            self.indent();
+            for check in rest {
+                let failed = match &check.check {
+                    Check::MinLength(n) | Check::MinItems(n) => format!("len({}) < {}", access, n),
+                    Check::MaxLength(n) | Check::MaxItems(n) => format!("len({}) > {}", access, n),
+                    Check::Pattern(pattern) => {
+                        format!("not re.search({:?}, {})", pattern, access)
+                    }
+                    Check::Min(n) => format!("{} < {:?}", access, n),
+                    Check::Max(n) => format!("{} > {:?}", access, n),
+                    Check::OneOf(values) => {
+                        let values: Vec<String> = values
+                            .iter()
+                            .map(|v| match v {
+                                RuleValue::String(s) => format!("{:?}", s),
+                                RuleValue::Number(n) => format!("{:?}", n),
+                            })
+                            .collect();
+                        format!("{} not in [{}]", access, values.join(", "))
+                    }
+                    Check::Required => continue,
+                };
+                self.writeln(&format!("if {}:", failed));
+                self.indent();
+                self.writeln(&Self::validation_error(&field.name, &check.message));
+                self.dedent();
+            }
+            self.dedent();
+        }
+        self.writeln("return errors");
+        self.dedent();
+    }
+
+    fn validation_error(field: &str, message: &str) -> String {
+        format!(
+            "errors.append({{\"field\": {:?}, \"message\": {:?}}})",
+            field, message
+        )
+    }
+
+    /// Prüft Request-Bodys mit `@Validate`-Feldern und antwortet mit 400
+    fn generate_request_validation(&mut self, f: &Function) {
+        let params: Vec<String> = validation::validated_params(f, &self.validated_structs)
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
+        for param in params {
+            let response = match self.framework {
+                Framework::FastAPI => format!(
+                    "return JSONResponse(status_code=400, content={{\"error\": \"{}\", \"errors\": {}_errors}})",
+                    validation::VALIDATION_FAILED, param
+                ),
+                Framework::Flask => format!(
+                    "return jsonify({{\"error\": \"{}\", \"errors\": {}_errors}}), 400",
+                    validation::VALIDATION_FAILED, param
+                ),
+                _ => continue,
+            };
+            self.writeln(&format!("{}_errors = {}.validation_errors()", param, param));
+            self.writeln(&format!("if {}_errors:", param));
+            self.indent();
+            self.writeln(&response);
+            self.dedent();
+        }
+    }
+
     fn generate_enum(&mut self, e: &Enum) {
         self.writeln(&format!("class {}(Enum):", e.name));
         self.indent();
@@ -194,6 +301,7 @@ impl PythonCodeGenerator {
             return_type
         ));
         self.indent();
+        self.generate_request_validation(f);
 
         if f.body.statements.is_empty() {
             self.writeln("pass");
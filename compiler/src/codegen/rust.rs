@@ -15,6 +15,28 @@ pub struct RustCodeGenerator {
     use_seaorm: bool,
     framework: Framework,
     has_validation: bool,
+    /// Structs mit `@Validate`-Feldern; ihre Body-Parameter werden automatisch geprüft
+    validated_structs: std::collections::HashSet<String>,
+    /// Schlanker Modus für `--target wasm`: kein Web-Framework, keine Server-Laufzeit
+    wasm: bool,
+    /// `workflow.*`/`@Step`: Runtime-Modul und Fortsetzen beim Start
+    has_workflows: bool,
+    has_scheduled: bool,
+    /// `@Subscribe`-Handler werden beim Start an den Event-Bus gehängt
+    has_event_handlers: bool,
+    /// `tracing.*`: Routen und `db.*`/`http.*`/`llm.*`-Aufrufe bekommen Spans
+    has_tracing: bool,
+    /// `@WebSocket`/`@SSE`: Endpunkte und Hooks werden vom Runtime-Modul aufgerufen
+    has_realtime: bool,
+    realtime_functions: std::collections::HashSet<String>,
+    /// `Connection<T>`-Parameter der aktuellen Funktion
+    connections: std::collections::HashSet<String>,
+    /// Verzeichnis der Quelldatei: Basis für `template.render_file` und Partials
+    template_dir: std::path::PathBuf,
+    /// `Stream<T>`/`stream.*`: Runtime-Modul und `CancelToken`-Typ
+    has_streams: bool,
+    /// `@Event`-Struct je Topic für `stream.from_topic`
+    stream_topics: std::collections::BTreeMap<String, String>,
 }
 
 impl CodeGenerator for RustCodeGenerator {
@@ -37,6 +59,32 @@ impl RustCodeGenerator {
             use_seaorm: false,
             framework: Framework::Axum,
             has_validation: false,
+            validated_structs: std::collections::HashSet::new(),
+            wasm: false,
+            has_workflows: false,
+            has_scheduled: false,
+            has_event_handlers: false,
+            has_tracing: false,
+            has_realtime: false,
+            realtime_functions: std::collections::HashSet::new(),
+            connections: std::collections::HashSet::new(),
+            template_dir: std::path::PathBuf::new(),
+            has_streams: false,
+            stream_topics: std::collections::BTreeMap::new(),
+        }
+    }
+
+    /// Template-Dateien relativ zu dieser Quelldatei einbetten
+    pub fn with_source_file(mut self, source_file: &str) -> Self {
+        self.template_dir = crate::stdlib::template::source_dir(source_file);
+        self
+    }
+
+    /// Generator für die Rust-Quelle eines `wasm32`-Moduls
+    pub fn for_wasm() -> Self {
+        RustCodeGenerator {
+            wasm: true,
+            ..Self::new()
         }
     }
 
@@ -63,6 +111,18 @@ impl RustCodeGenerator {
         self.writeln("#![allow(unused_imports, unused_variables, dead_code)]");
         self.writeln("");
 
+        if self.wasm {
+            self.writeln("#![allow(unused_mut)]");
+            self.writeln("use serde::{Deserialize, Serialize};");
+            self.writeln("use serde_json::Value as Any;");
+            self.writeln("");
+            for item in &program.items {
+                self.generate_item(item, &Framework::Axum, false);
+                self.writeln("");
+            }
+            return self.output.clone();
+        }
+
         // Detect framework
         let framework = FrameworkSelector::detect_framework(program, config_framework);
         self.framework = framework;
@@ -70,7 +130,9 @@ impl RustCodeGenerator {
         self.writeln(&framework_imports);
 
         // Check for validation usage
-        self.has_validation = self.has_validation_decorators(program);
+        self.validated_structs = crate::validation::validated_structs(&program.items);
+        self.has_validation =
+            !self.validated_structs.is_empty() || self.has_validation_decorators(program);
         if self.has_validation {
             use crate::stdlib::validation::ValidationStdlib;
             self.writeln("use regex::Regex;");
@@ -165,6 +227,118 @@ where
             self.writeln("    }");
         }
 
+        // Workflow Runtime
+        let workflow_usage = crate::stdlib::workflow::WorkflowUsage::collect(program);
+        self.has_workflows = workflow_usage.is_some();
+        if let Some(usage) = workflow_usage {
+            use crate::stdlib::workflow::WorkflowStdlib;
+            self.writeln("    pub mod workflow {");
+            self.writeln("        use super::super::*;");
+            let runtime = WorkflowStdlib::generate_workflow_runtime_code(&usage, &|name| {
+                self.to_snake_case(name)
+            });
+            self.writeln(&runtime);
+            self.writeln("    }");
+        }
+
+        // Scheduler Runtime
+        let scheduler_usage = crate::stdlib::scheduler::SchedulerUsage::collect(program);
+        self.has_scheduled = scheduler_usage
+            .as_ref()
+            .is_some_and(|usage| !usage.scheduled.is_empty());
+        if let Some(usage) = scheduler_usage {
+            use crate::stdlib::scheduler::SchedulerStdlib;
+            self.writeln("    pub mod scheduler {");
+            self.writeln("        use super::super::*;");
+            let runtime = SchedulerStdlib::generate_scheduler_runtime_code(&usage, &|name| {
+                self.to_snake_case(name)
+            });
+            self.writeln(&runtime);
+            self.writeln("    }");
+        }
+
+        // Event Bus Runtime
+        let event_bus_usage = crate::stdlib::event_bus::EventBusUsage::collect(program);
+        self.has_event_handlers = event_bus_usage
+            .as_ref()
+            .is_some_and(|usage| !usage.catalog.subscribers.is_empty());
+        let has_event_bus = event_bus_usage.is_some();
+        if let Some(usage) = event_bus_usage {
+            use crate::stdlib::event_bus::EventBusStdlib;
+            self.writeln("    pub mod event_bus {");
+            self.writeln("        use super::super::*;");
+            let runtime = EventBusStdlib::generate_event_bus_runtime_code(&usage, &|name| {
+                self.to_snake_case(name)
+            });
+            self.writeln(&runtime);
+            self.writeln("    }");
+        }
+
+        // Tracing Runtime
+        self.has_tracing = crate::stdlib::tracing::uses_tracing(program);
+        if self.has_tracing {
+            use crate::stdlib::tracing::TracingStdlib;
+            self.writeln("    pub mod tracing {");
+            self.writeln("        use super::super::*;");
+            self.writeln(&TracingStdlib::generate_tracing_runtime_code());
+            self.writeln("    }");
+        }
+
+        // Audit Runtime
+        if crate::stdlib::audit::uses_audit(program) {
+            use crate::stdlib::audit::AuditStdlib;
+            self.writeln("    pub mod audit {");
+            self.writeln(&AuditStdlib::generate_audit_runtime_code());
+            self.writeln("    }");
+        }
+
+        // Privacy Runtime (`privacy.mask` / `privacy.declassify`)
+        if crate::privacy::uses_privacy_calls(program) {
+            self.writeln("    pub mod privacy {");
+            self.writeln(crate::privacy::RUNTIME_CODE);
+            self.writeln("    }");
+        }
+
+        // Realtime Runtime (`@WebSocket` / `@SSE`)
+        self.has_realtime = crate::stdlib::realtime::uses_realtime(program);
+        if self.has_realtime {
+            use crate::stdlib::realtime::{RealtimeCatalog, RealtimeStdlib};
+            let (catalog, _) = RealtimeCatalog::build(&program.items);
+            self.realtime_functions = catalog.functions();
+            self.writeln("    pub mod realtime {");
+            self.writeln("        use super::super::*;");
+            let runtime = RealtimeStdlib::generate_realtime_runtime_code(
+                &catalog,
+                framework,
+                &|name| self.to_snake_case(name),
+                &|name| format!("super::super::{}", self.to_pascal_case(name)),
+            );
+            self.writeln(&runtime);
+            self.writeln("    }");
+        }
+
+        // Template Runtime: Helfer für beim Kompilieren übersetzte Templates
+        if crate::stdlib::template::uses_templates(program) {
+            self.writeln("    pub mod template {");
+            self.writeln(crate::stdlib::template::RUNTIME_CODE);
+            self.writeln("    }");
+        }
+
+        // Stream Runtime (`Stream<T>` / `stream.*`)
+        self.has_streams = crate::stdlib::stream::uses_streams(program);
+        if self.has_streams {
+            use crate::stdlib::stream::StreamStdlib;
+            let (catalog, _) = crate::stdlib::event_bus::EventCatalog::build(&program.items);
+            self.stream_topics = catalog.topics;
+            self.writeln("    pub mod stream {");
+            self.writeln(&StreamStdlib::generate_stream_runtime_code(
+                framework,
+                self.has_realtime,
+                has_event_bus,
+            ));
+            self.writeln("    }");
+        }
+
         self.writeln("}");
         self.writeln("");
 
@@ -231,6 +405,10 @@ where
     fn has_security_decorators(&self, program: &Program) -> bool {
         for item in &program.items {
             if let Item::Function(f) = item {
+                // Realtime-Endpunkte prüfen das JWT selbst vor dem Upgrade
+                if self.realtime_functions.contains(&f.name) {
+                    continue;
+                }
                 for decorator in &f.decorators {
                     if matches!(decorator.name.as_str(), "Auth" | "Role") {
                         return true;
@@ -560,7 +738,12 @@ where
     fn generate_item(&mut self, item: &Item, framework: &Framework, use_seaorm: bool) {
         match item {
             Item::Function(f) => self.generate_function(f, framework, use_seaorm),
-            Item::Struct(s) => self.generate_struct(s, use_seaorm),
+            Item::Struct(s) => {
+                self.generate_struct(s, use_seaorm);
+                if self.has_validation && self.validated_structs.contains(&s.name) {
+                    self.generate_struct_validation(s);
+                }
+            }
             Item::Enum(e) => self.generate_enum(e),
             Item::TypeAlias(ta) => self.generate_type_alias(ta),
             Item::Module(m) => self.generate_module(m),
@@ -580,9 +763,19 @@ where
 
     fn generate_function(&mut self, function: &Function, framework: &Framework, _use_seaorm: bool) {
         // Generate decorators as Rust attributes
+        let is_realtime = self.realtime_functions.contains(&function.name);
         for decorator in &function.decorators {
+            if is_realtime && matches!(decorator.name.as_str(), "Auth" | "Role" | "Roles") {
+                continue;
+            }
             self.generate_decorator(decorator);
         }
+        self.connections = function
+            .params
+            .iter()
+            .filter(|p| crate::stdlib::realtime::connection_message(&p.param_type).is_some())
+            .map(|p| p.name.clone())
+            .collect();
 
         // Generate function signature
         if function.visibility == Visibility::Public {
@@ -597,7 +790,7 @@ where
         let is_flow = function.decorators.iter().any(|d| d.name == "Flow");
         let is_main = function.name == "main";
 
-        if is_main {
+        if is_main && !self.wasm {
             self.writeln("#[tokio::main]");
             self.write("async ");
         } else if function.is_async || is_flow {
@@ -648,6 +841,18 @@ where
         // Generate parameters
         let route_info = self.is_route_handler(&function.decorators);
 
+        // Eingehender `traceparent`-Header für den Server-Span
+        let traced_route = route_info.clone().filter(|_| self.has_tracing);
+        if traced_route.is_some() {
+            match framework {
+                Framework::Actix => self.write("__request: actix_web::HttpRequest"),
+                _ => self.write("__headers: axum::http::HeaderMap"),
+            }
+            if !function.params.is_empty() {
+                self.write(", ");
+            }
+        }
+
         for (i, param) in function.params.iter().enumerate() {
             if i > 0 {
                 self.write(", ");
@@ -692,6 +897,7 @@ where
         self.write(")");
 
         // Generate return type
+        let return_start = self.output.len();
         if let Some(ref return_type) = function.return_type {
             self.write(" -> ");
             // Prüfe ob es ein Result-Type ist
@@ -712,10 +918,94 @@ where
                 self.generate_type(return_type);
             }
         }
+        let rust_return_type = match self.output[return_start..].trim_start_matches(" -> ") {
+            "" => "()".to_string(),
+            written => written.to_string(),
+        };
+
+        // Geprüfte Handler antworten bei Verstößen mit 400, sonst mit dem JSON-Ergebnis
+        let validated = route_info.is_some() && !is_realtime && self.validates_request(function);
+        if validated {
+            self.output.truncate(return_start);
+            self.write(" -> ");
+            self.write(&Self::validated_return_type(framework, &rust_return_type));
+        }
 
         self.writeln(" {");
         self.indent();
 
+        let is_async_route = function.is_async || is_flow;
+        if validated {
+            self.generate_validation_code(function, framework);
+            if is_async_route {
+                self.writeln(&format!(
+                    "let __validated: {} = async move {{",
+                    rust_return_type
+                ));
+            } else {
+                self.writeln(&format!(
+                    "let __validated: {} = (move || {{",
+                    rust_return_type
+                ));
+            }
+            self.indent();
+        }
+
+        // Unterbrochene Workflow-Läufe im Hintergrund fortsetzen
+        if function.name == "main" && self.has_workflows {
+            self.writeln("std::thread::spawn(crate::stdlib::workflow::resume_pending);");
+        }
+        // `@Scheduled`-Funktionen registrieren
+        if function.name == "main" && self.has_scheduled {
+            use crate::stdlib::scheduler::SchedulerStdlib;
+            self.writeln(&SchedulerStdlib::generate_start_scheduled_code());
+        }
+        // `@Subscribe`-Handler starten
+        if function.name == "main" && self.has_event_handlers {
+            use crate::stdlib::event_bus::EventBusStdlib;
+            self.writeln(&EventBusStdlib::generate_start_subscribers_code());
+        }
+
+        // Server-Span um den gesamten Rumpf der Route
+        if let Some((method, path)) = &traced_route {
+            use crate::stdlib::tracing::TracingStdlib;
+            let traceparent = match framework {
+                Framework::Actix => {
+                    "__request.headers().get(\"traceparent\").and_then(|value| value.to_str().ok())"
+                }
+                _ => "__headers.get(\"traceparent\").and_then(|value| value.to_str().ok())",
+            };
+            self.writeln(&TracingStdlib::generate_server_span_code(
+                method,
+                path,
+                &function.name,
+                traceparent,
+            ));
+            self.writeln(&TracingStdlib::generate_scope_start_code(
+                &rust_return_type,
+                is_async_route,
+            ));
+            self.indent();
+        }
+
+        // `@Audited`: jeder Aufruf landet mit Ergebnis und Dauer im Audit-Log
+        let audited = crate::stdlib::audit::audited_spec(function).and_then(Result::ok);
+        if let Some(spec) = &audited {
+            use crate::stdlib::audit::AuditStdlib;
+            let user = spec
+                .user_param
+                .as_ref()
+                .map(|param| self.to_snake_case(param));
+            self.writeln(&AuditStdlib::generate_invocation_start_code(
+                spec,
+                &function.name,
+                user.as_deref(),
+                &rust_return_type,
+                is_async_route,
+            ));
+            self.indent();
+        }
+
         // Check for @Flow decorator
         let is_flow = function.decorators.iter().any(|d| d.name == "Flow");
 
@@ -733,11 +1023,6 @@ where
             self.indent();
         }
 
-        // Generate validation code if needed
-        if self.has_validation {
-            self.generate_validation_code(function, framework);
-        }
-
         // Generate function body
         self.generate_block(&function.body);
 
@@ -763,6 +1048,31 @@ where
             self.writeln("result");
         }
 
+        if audited.is_some() {
+            use crate::stdlib::audit::AuditStdlib;
+            self.unindent();
+            self.writeln(&AuditStdlib::generate_invocation_end_code(is_async_route));
+        }
+
+        if traced_route.is_some() {
+            use crate::stdlib::tracing::TracingStdlib;
+            self.unindent();
+            self.writeln(&TracingStdlib::generate_scope_end_code(is_async_route));
+            self.writeln(&TracingStdlib::generate_finish_code("__trace", "__result"));
+            self.writeln("__result");
+        }
+
+        if validated {
+            self.unindent();
+            self.writeln(if is_async_route { "}.await;" } else { "})();" });
+            match framework {
+                Framework::Actix => {
+                    self.writeln("actix_web::Either::Left(actix_web::web::Json(__validated))")
+                }
+                _ => self.writeln("Ok(axum::Json(__validated))"),
+            }
+        }
+
         self.unindent();
         self.writeln("}");
     }
@@ -910,7 +1220,9 @@ where
                     .unwrap_or("Mock");
                 self.writeln(&format!("// Mock: {} for {}", struct_name, trait_name));
             }
-            "Optimize" | "Flow" | "Generate" => {
+            "Optimize" | "Flow" | "Step" | "Scheduled" | "Subscribe" | "Generate"
+            | "WASMExport" | "WASMInit" | "WASMOptimize" | "Expose" | "Audited" | "WebSocket"
+            | "SSE" | "OnConnect" | "OnDisconnect" => {
                 // Compiler directives or handled elsewhere - do not generate Rust attributes
             }
             _ => {
@@ -941,6 +1253,13 @@ where
             DecoratorArg::Named { name, value } => {
                 format!("{} = {}", name, self.decorator_arg_to_string(value))
             }
+            DecoratorArg::List(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| self.decorator_arg_to_string(item))
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
         }
     }
 
@@ -963,7 +1282,10 @@ where
         }
 
         // Generate struct with derives
-        if has_auto_derive {
+        if self.wasm {
+            // Nur Serde: die Structs werden als JSON über die Modulgrenze gereicht
+            self.writeln("#[derive(Debug, Clone, Serialize, Deserialize)]");
+        } else if has_auto_derive {
             // Alle sinnvollen Derives
             self.writeln("#[derive(Debug, Clone, Serialize, Deserialize, validator::Validate, derive_more::Add, derive_more::Display, derive_more::From, derive_more::Into, derive_more::Deref)]");
         } else if !custom_derives.is_empty() {
@@ -999,6 +1321,11 @@ where
         self.indent();
 
         for field in &struct_def.fields {
+            let field_name = self.to_snake_case(&field.name);
+            if self.wasm && field_name != field.name {
+                // JS-Bindings verwenden die Feldnamen aus dem Velin-Code
+                self.writeln(&format!("    #[serde(rename = \"{}\")]", field.name));
+            }
             if field.visibility == Visibility::Public {
                 self.write("    pub ");
             } else {
@@ -1014,7 +1341,7 @@ where
                 }
             }
 
-            self.write(&self.to_snake_case(&field.name));
+            self.write(&field_name);
             self.write(": ");
             self.generate_type(&field.field_type);
 
@@ -1032,6 +1359,145 @@ where
         self.writeln("}");
     }
 
+    /// `validate()` für Structs mit `@Validate`-Feldern; Fehler landen im übergebenen Validator
+    fn generate_struct_validation(&mut self, struct_def: &Struct) {
+        use crate::validation::{messages, struct_validations, RuleValue, ValueKind};
+
+        self.writeln("");
+        self.writeln(&format!(
+            "impl {} {{",
+            self.to_pascal_case(&struct_def.name)
+        ));
+        self.writeln("    pub fn validate(&self, validator: &mut Validator) {");
+
+        for field in struct_validations(struct_def) {
+            let access = format!("self.{}", self.to_snake_case(&field.name));
+            let name = &field.name;
+            let rules = &field.rules;
+            let mut calls = Vec::new();
+
+            // Optionale Felder werden nur geprüft, wenn ein Wert vorhanden ist
+            let (text, number, list) = if field.optional {
+                (
+                    "value".to_string(),
+                    "*value".to_string(),
+                    "value".to_string(),
+                )
+            } else {
+                (format!("&{}", access), access.clone(), access.clone())
+            };
+
+            match field.kind {
+                ValueKind::Text => {
+                    if let Some(min) = rules.min {
+                        calls.push(format!(
+                            "validator.min_length(\"{}\", {}, {});",
+                            name, text, min as usize
+                        ));
+                    }
+                    if let Some(max) = rules.max {
+                        calls.push(format!(
+                            "validator.max_length(\"{}\", {}, {});",
+                            name, text, max as usize
+                        ));
+                    }
+                    if rules.email {
+                        calls.push(format!("validator.email(\"{}\", {});", name, text));
+                    }
+                    if let Some(pattern) = &rules.pattern {
+                        calls.push(format!(
+                            "validator.pattern(\"{}\", {}, {:?}, \"{}\");",
+                            name,
+                            text,
+                            pattern,
+                            messages::pattern(name)
+                        ));
+                    }
+                }
+                ValueKind::Number => {
+                    if let Some(min) = rules.min {
+                        calls.push(format!(
+                            "validator.min(\"{}\", {}, {:?});",
+                            name, number, min
+                        ));
+                    }
+                    if let Some(max) = rules.max {
+                        calls.push(format!(
+                            "validator.max(\"{}\", {}, {:?});",
+                            name, number, max
+                        ));
+                    }
+                }
+                ValueKind::List => {
+                    if let Some(min) = rules.min {
+                        calls.push(format!(
+                            "validator.min_items(\"{}\", {}.len(), {});",
+                            name, list, min as usize
+                        ));
+                    }
+                    if let Some(max) = rules.max {
+                        calls.push(format!(
+                            "validator.max_items(\"{}\", {}.len(), {});",
+                            name, list, max as usize
+                        ));
+                    }
+                }
+                ValueKind::Other => {}
+            }
+            if !rules.one_of.is_empty() {
+                let allowed: Vec<String> = rules
+                    .one_of
+                    .iter()
+                    .map(|v| match v {
+                        RuleValue::String(s) => format!("{:?}", s),
+                        RuleValue::Number(n) => format!("{:?}", n),
+                    })
+                    .collect();
+                let value = match (field.kind, field.optional) {
+                    (ValueKind::Text, true) => "&value.as_str()".to_string(),
+                    (ValueKind::Text, false) => format!("&{}.as_str()", access),
+                    (_, true) => "value".to_string(),
+                    (_, false) => format!("&{}", access),
+                };
+                calls.push(format!(
+                    "validator.one_of(\"{}\", {}, &[{}]);",
+                    name,
+                    value,
+                    allowed.join(", ")
+                ));
+            }
+
+            if field.optional {
+                if rules.required {
+                    self.writeln(&format!(
+                        "        validator.present(\"{}\", {}.is_some());",
+                        name, access
+                    ));
+                }
+                if !calls.is_empty() {
+                    self.writeln(&format!("        if let Some(value) = &{} {{", access));
+                    for call in calls {
+                        self.writeln(&format!("            {}", call));
+                    }
+                    self.writeln("        }");
+                }
+            } else {
+                if rules.required && field.kind == ValueKind::Text {
+                    self.writeln(&format!(
+                        "        validator.required(\"{}\", Some(&{}));",
+                        name, access
+                    ));
+                }
+                for call in calls {
+                    self.writeln(&format!("        {}", call));
+                }
+            }
+        }
+
+        self.writeln("    }");
+        self.writeln("}");
+    }
+
     fn generate_enum(&mut self, enum_def: &Enum) {
         self.writeln("#[derive(Debug, Clone, Serialize, Deserialize)]");
         if enum_def.visibility == Visibility::Public {
@@ -1223,7 +1689,14 @@ where
             Type::Boolean => self.write("bool"),
             Type::Void => self.write("()"),
             Type::Null => self.write("Option<String>"),
+            // Im Wasm-Modul muss `any` über JSON marshallbar sein
+            Type::Any if self.wasm => self.write("Any"),
             Type::Any => self.write("Box<dyn std::any::Any>"),
+            Type::Named(name)
+                if self.has_streams && name == crate::stdlib::stream::CANCEL_TOKEN_TYPE =>
+            {
+                self.write("crate::stdlib::stream::CancelToken")
+            }
             Type::Named(name) => self.write(&self.to_pascal_case(name)),
             Type::List(inner) => {
                 self.write("Vec<");
@@ -1259,6 +1732,11 @@ where
                 self.generate_type(err);
                 self.write(">");
             }
+            Type::Stream(item) => {
+                self.write("crate::stdlib::stream::Stream<");
+                self.generate_type(item);
+                self.write(">");
+            }
             Type::Function {
                 params,
                 return_type,
@@ -1307,6 +1785,16 @@ where
                         self.write("Option<()>");
                     }
                 }
+                "Connection" if self.has_realtime => {
+                    self.write("crate::stdlib::realtime::Connection<");
+                    for (i, param) in params.iter().enumerate() {
+                        if i > 0 {
+                            self.write(", ");
+                        }
+                        self.generate_type(param);
+                    }
+                    self.write(">");
+                }
                 _ => {
                     self.write(&self.to_pascal_case(name));
                     self.write("<");
@@ -1496,7 +1984,8 @@ where
                     if i > 0 {
                         self.write(", ");
                     }
-                    self.write(field_name);
+                    // Feldnamen wie in der Struct-Definition (snake_case)
+                    self.write(&self.to_snake_case(field_name));
                     self.write(": ");
                     if let Expression::Literal(Literal::String(s)) = field_expr {
                         let escaped = s.replace('\\', "\\\\").replace('"', "\\\"");
@@ -1597,8 +2086,42 @@ where
                     // Check for HTTP Client method calls
                     use crate::stdlib::http_client::is_http_client_method;
 
+                    // `db.find`/`db.delete` nicht als Collection- bzw. HTTP-Aufruf deuten
+                    if matches!(object.as_ref(), Expression::Identifier(name) if name == "db") {
+                        self.generate_traced_call(
+                            &format!("db.{}", member),
+                            &[("db.operation.name", member)],
+                            false,
+                            |this| this.generate_db_call(member, args),
+                        );
+                        return;
+                    }
+
+                    // `conn.join(...)` usw. nicht als Collection-Methode deuten
+                    if let Expression::Identifier(name) = object.as_ref() {
+                        if self.connections.contains(name) {
+                            self.generate_connection_call(name, member, args);
+                            return;
+                        }
+                        // Vor den List-Methoden: `stream.map`/`filter`/`reduce` sind lazy
+                        if name == "stream" {
+                            self.generate_stream_call(member, args);
+                            return;
+                        }
+                    }
+
                     if is_http_client_method(member) {
-                        self.generate_http_client_call(object, member, args);
+                        if matches!(member.as_str(), "get" | "post" | "put" | "delete" | "patch") {
+                            let method = member.to_uppercase();
+                            self.generate_traced_call(
+                                &method,
+                                &[("http.request.method", &method)],
+                                true,
+                                |this| this.generate_http_client_call(object, member, args),
+                            );
+                        } else {
+                            self.generate_http_client_call(object, member, args);
+                        }
                         return;
                     }
 
@@ -1623,10 +2146,7 @@ where
                     }
 
                     if let Expression::Identifier(obj_name) = object.as_ref() {
-                        if obj_name == "db" {
-                            self.generate_db_call(member, args);
-                            return;
-                        } else if obj_name == "backup" {
+                        if obj_name == "backup" {
                             self.generate_backup_call(member, args);
                             return;
                         } else if obj_name == "rollback" {
@@ -1665,8 +2185,19 @@ where
                         } else if obj_name == "flow" {
                             self.generate_flow_call(member, args);
                             return;
+                        } else if obj_name == "workflow" {
+                            self.generate_workflow_call(member, args);
+                            return;
+                        } else if obj_name == "scheduler" {
+                            self.generate_scheduler_call(member, args);
+                            return;
                         } else if obj_name == "llm" {
-                            self.generate_llm_call(member, args);
+                            self.generate_traced_call(
+                                &format!("llm.{}", member),
+                                &[("gen_ai.operation.name", member)],
+                                false,
+                                |this| this.generate_llm_call(member, args),
+                            );
                             return;
                         } else if obj_name == "embedding" {
                             self.generate_embedding_call(member, args);
@@ -1735,7 +2266,28 @@ where
                             self.generate_geolocation_call(member, args);
                             return;
                         } else if obj_name == "http" {
-                            self.generate_http_call(member, args);
+                            let method = member.to_uppercase();
+                            self.generate_traced_call(
+                                &method,
+                                &[("http.request.method", &method)],
+                                true,
+                                |this| this.generate_http_call(member, args),
+                            );
+                            return;
+                        } else if obj_name == "tracing" {
+                            self.generate_tracing_call(member, args);
+                            return;
+                        } else if obj_name == "privacy" {
+                            self.generate_privacy_call(member, args);
+                            return;
+                        } else if obj_name == "realtime" {
+                            self.generate_realtime_call(member, args);
+                            return;
+                        } else if obj_name == "template" {
+                            self.generate_template_call(member, args);
+                            return;
+                        } else if obj_name == "audit" {
+                            self.generate_audit_call(member, args);
                             return;
                         } else if obj_name == "i18n" {
                             self.generate_i18n_call(member, args);
@@ -1770,6 +2322,9 @@ where
                         } else if obj_name == "xml" {
                             self.generate_xml_call(member, args);
                             return;
+                        } else if obj_name == "inspect" {
+                            self.generate_inspect_call();
+                            return;
                         }
                     } else if let Expression::Member {
                         object: inner_obj,
@@ -1828,6 +2383,13 @@ where
                     self.generate_expression(object);
                     self.write(".");
                     self.write(&self.to_snake_case(member));
+                } else if matches!(object.as_ref(), Expression::Identifier(name) if self.connections.contains(name))
+                {
+                    // `conn.id`/`conn.user` kopieren, die Verbindung bleibt nutzbar
+                    self.generate_expression(object);
+                    self.write(".");
+                    self.write(&self.to_snake_case(member));
+                    self.write(".clone()");
                 } else {
                     self.generate_expression(object);
                     self.write(".");
@@ -2128,6 +2690,27 @@ where
         self.output.push_str(s);
     }
 
+    /// Mit aktivem Tracing läuft der generierte Aufruf in einem Client-Span
+    fn generate_traced_call(
+        &mut self,
+        name: &str,
+        attributes: &[(&str, &str)],
+        propagate: bool,
+        generate: impl FnOnce(&mut Self),
+    ) {
+        if !self.has_tracing {
+            generate(self);
+            return;
+        }
+        use crate::stdlib::tracing::TracingStdlib;
+        let old_output = std::mem::take(&mut self.output);
+        generate(self);
+        let call = std::mem::replace(&mut self.output, old_output);
+        self.write(&TracingStdlib::generate_client_span_code(
+            name, attributes, &call, propagate,
+        ));
+    }
+
     fn writeln(&mut self, s: &str) {
         self.write(s);
         self.write("\n");
@@ -2985,19 +3568,35 @@ where
         }
     }
 
-    fn generate_validation_code(&mut self, function: &Function, framework: &Framework) {
-        // Check if function has @Validate decorator or needs auto-validation
+    /// Route-Handler mit `@Validate` oder einem Body-Struct mit `@Validate`-Feldern
+    fn validates_request(&self, function: &Function) -> bool {
         let has_validate_decorator = function.decorators.iter().any(|d| {
             matches!(
                 d.name.as_str(),
                 "Validate" | "@Validate" | "Validation" | "@Validation"
             )
         });
+        has_validate_decorator
+            || function.params.iter().any(|param| {
+                matches!(&param.param_type, Type::Named(name) if self.validated_structs.contains(name))
+            })
+    }
 
-        if !has_validate_decorator && !self.has_validation {
-            return;
+    /// Rückgabetyp eines geprüften Handlers: Fehlerantwort oder JSON-Ergebnis
+    fn validated_return_type(framework: &Framework, return_type: &str) -> String {
+        match framework {
+            Framework::Actix => format!(
+                "actix_web::Either<actix_web::web::Json<{}>, actix_web::HttpResponse>",
+                return_type
+            ),
+            _ => format!(
+                "Result<axum::Json<{}>, axum::response::Response>",
+                return_type
+            ),
         }
+    }
 
+    fn generate_validation_code(&mut self, function: &Function, framework: &Framework) {
         // Generate validator initialization
         self.write("    ");
         self.writeln("let mut validator = Validator::new();");
@@ -3007,6 +3606,15 @@ where
         for param in &function.params {
             let param_name = &self.to_snake_case(&param.name);
 
+            // Request-Bodys mit `@Validate`-Feldern prüfen sich selbst
+            if let Type::Named(type_name) = &param.param_type {
+                if self.validated_structs.contains(type_name) {
+                    self.write("    ");
+                    self.writeln(&format!("{}.validate(&mut validator);", param_name));
+                    continue;
+                }
+            }
+
             match param.param_type {
                 Type::String => {
                     self.write("    ");
@@ -3063,21 +3671,17 @@ where
                 self.write("            ");
                 self.writeln(".collect();");
                 self.write("        ");
-                self.writeln("return Err(anyhow::anyhow!(\"Validation failed\")).map_err(|e| {");
-                self.write("            ");
                 self.writeln("let response = serde_json::json!({");
-                self.write("                ");
+                self.write("            ");
                 self.writeln("\"error\": \"Validation failed\",");
-                self.write("                ");
-                self.writeln("\"errors\": errors");
                 self.write("            ");
+                self.writeln("\"errors\": errors");
+                self.write("        ");
                 self.writeln("});");
-                self.write("            ");
+                self.write("        ");
                 self.writeln(
-                    "(axum::http::StatusCode::BAD_REQUEST, axum::Json(response)).into_response()",
+                    "return Err((axum::http::StatusCode::BAD_REQUEST, axum::Json(response)).into_response());",
                 );
-                self.write("        ");
-                self.writeln("});");
             }
             Framework::Actix => {
                 self.writeln("let errors: Vec<serde_json::Value> = validator.errors()");
@@ -3095,7 +3699,7 @@ where
                 self.writeln(".collect();");
                 self.write("        ");
                 self.writeln(
-                    "return Ok(actix_web::HttpResponse::BadRequest().json(serde_json::json!({",
+                    "return actix_web::Either::Right(actix_web::HttpResponse::BadRequest().json(serde_json::json!({",
                 );
                 self.write("            ");
                 self.writeln("\"error\": \"Validation failed\",");
@@ -4472,6 +5076,12 @@ where
         }
     }
 
+    /// `inspect.*` ohne `--inspect`: der InspectorInstrumentationPass hat die Aufrufe
+    /// nicht umgeleitet, `state` und `task` werden zu No-ops
+    fn generate_inspect_call(&mut self) {
+        self.write("()");
+    }
+
     fn generate_log_call(&mut self, method: &str, args: &[Expression]) {
         use crate::stdlib::log::LogStdlib;
 
@@ -4691,68 +5301,53 @@ where
         }
     }
 
-    #[allow(dead_code)]
-    fn generate_stream_call(&mut self, method: &str, args: &[Expression]) {
+    fn generate_stream_call(&mut self, method: &str, expressions: &[Expression]) {
         use crate::stdlib::stream::StreamStdlib;
 
-        match method {
-            "create" => {
-                self.write(&StreamStdlib::generate_create_code());
-            }
-            "map" => {
-                if args.len() >= 2 {
-                    let stream = self.capture_expression(&args[0]);
-                    let mapper = self.capture_expression(&args[1]);
-                    self.write(&StreamStdlib::generate_map_code(&stream, &mapper));
-                }
-            }
-            "filter" => {
-                if args.len() >= 2 {
-                    let stream = self.capture_expression(&args[0]);
-                    let predicate = self.capture_expression(&args[1]);
-                    self.write(&StreamStdlib::generate_filter_code(&stream, &predicate));
-                }
-            }
-            "reduce" => {
-                if args.len() >= 3 {
-                    let stream = self.capture_expression(&args[0]);
-                    let reducer = self.capture_expression(&args[1]);
-                    let initial = self.capture_expression(&args[2]);
-                    self.write(&StreamStdlib::generate_reduce_code(
-                        &stream, &reducer, &initial,
-                    ));
-                }
-            }
-            "batch" => {
-                if args.len() >= 2 {
-                    let stream = self.capture_expression(&args[0]);
-                    let size = self.capture_expression(&args[1]);
-                    self.write(&StreamStdlib::generate_batch_code(&stream, &size));
-                }
-            }
-            "buffer" => {
-                if args.len() >= 2 {
-                    let stream = self.capture_expression(&args[0]);
-                    let size = self.capture_expression(&args[1]);
-                    self.write(&StreamStdlib::generate_buffer_code(&stream, &size));
-                }
-            }
-            "merge" => {
-                if args.len() >= 2 {
-                    let stream1 = self.capture_expression(&args[0]);
-                    let stream2 = self.capture_expression(&args[1]);
-                    self.write(&StreamStdlib::generate_merge_code(&stream1, &stream2));
-                }
-            }
-            "zip" => {
-                if args.len() >= 2 {
-                    let stream1 = self.capture_expression(&args[0]);
-                    let stream2 = self.capture_expression(&args[1]);
-                    self.write(&StreamStdlib::generate_zip_code(&stream1, &stream2));
-                }
+        let asynchronous = method == "map_async";
+        let args: Vec<String> = expressions
+            .iter()
+            .map(|arg| match arg {
+                Expression::Lambda { params, body, .. } => {
+                    self.capture_stream_callback(params, body, asynchronous)
+                }
+                _ => self.capture_expression(arg),
+            })
+            .collect();
+        let event_type = crate::stdlib::stream::literal_topic(method, expressions)
+            .and_then(|topic| self.stream_topics.get(topic).cloned())
+            .map(|event| self.to_pascal_case(&event));
+        self.write(&StreamStdlib::generate_call_code(
+            method,
+            &args,
+            event_type.as_deref(),
+        ));
+    }
+
+    /// Lambda als `move`-Closure; bei `map_async` liefert sie ein Future
+    fn capture_stream_callback(
+        &mut self,
+        params: &[Parameter],
+        body: &Expression,
+        asynchronous: bool,
+    ) -> String {
+        let mut code = String::from("move |");
+        for (i, param) in params.iter().enumerate() {
+            if i > 0 {
+                code.push_str(", ");
             }
-            _ => self.write(&format!("// Unknown stream method: {}", method)),
+            code.push_str(&self.to_snake_case(&param.name));
+            code.push_str(": ");
+            code.push_str(&self.capture_type(&param.param_type));
+        }
+        code.push_str(if asynchronous { "| async move " } else { "| " });
+        let body = self.capture_expression(body);
+        if body.trim_start().starts_with('{') {
+            code.push_str(&body);
+        } else {
+            code.push_str(&format!("{{ {} }}", body));
         }
+        code
     }
 
     fn generate_redis_call(&mut self, method: &str, args: &[Expression]) {
@@ -4892,50 +5487,86 @@ where
         }
     }
 
-    #[allow(dead_code)]
     fn generate_tracing_call(&mut self, method: &str, args: &[Expression]) {
         use crate::stdlib::tracing::TracingStdlib;
 
-        match method {
-            "start_span" | "startSpan" => {
-                if let Some(arg) = args.first() {
-                    let name = self.capture_expression(arg);
-                    self.write(&TracingStdlib::generate_start_span_code(&name));
-                }
+        match (method, args) {
+            ("start_span" | "startSpan", [name]) => {
+                let name = self.capture_expression(name);
+                self.write(&TracingStdlib::generate_start_span_code(&name));
             }
-            "set_attribute" | "setAttribute" => {
-                if args.len() >= 3 {
-                    let span = self.capture_expression(&args[0]);
-                    let key = self.capture_expression(&args[1]);
-                    let value = self.capture_expression(&args[2]);
-                    self.write(&TracingStdlib::generate_set_attribute_code(
-                        &span, &key, &value,
-                    ));
-                }
+            ("set_attribute" | "setAttribute", [span, key, value]) => {
+                let span = self.capture_expression(span);
+                let key = self.capture_expression(key);
+                let value = self.capture_expression(value);
+                self.write(&TracingStdlib::generate_set_attribute_code(
+                    &span, &key, &value,
+                ));
             }
-            "child_span" | "childSpan" => {
-                if args.len() >= 2 {
-                    let parent = self.capture_expression(&args[0]);
-                    let name = self.capture_expression(&args[1]);
-                    self.write(&TracingStdlib::generate_child_span_code(&parent, &name));
-                }
+            ("child_span" | "childSpan", [parent, name]) => {
+                let parent = self.capture_expression(parent);
+                let name = self.capture_expression(name);
+                self.write(&TracingStdlib::generate_child_span_code(&parent, &name));
             }
-            "end_span" | "endSpan" => {
-                if let Some(arg) = args.first() {
-                    let span = self.capture_expression(arg);
-                    self.write(&TracingStdlib::generate_end_span_code(&span));
-                }
+            ("end_span" | "endSpan", [span]) => {
+                let span = self.capture_expression(span);
+                self.write(&TracingStdlib::generate_end_span_code(&span));
             }
-            "export" => {
-                if let Some(arg) = args.first() {
-                    let format = self.capture_expression(arg);
-                    self.write(&TracingStdlib::generate_export_code(&format));
-                }
+            ("export", [endpoint]) => {
+                let endpoint = self.capture_expression(endpoint);
+                self.write(&TracingStdlib::generate_export_code(&endpoint));
+            }
+            ("traceparent", []) => {
+                self.write(&TracingStdlib::generate_traceparent_code());
             }
             _ => self.write(&format!("// Unknown tracing method: {}", method)),
         }
     }
 
+    fn generate_privacy_call(&mut self, method: &str, args: &[Expression]) {
+        match (method, args) {
+            ("mask", [value]) => {
+                let value = self.capture_expression(value);
+                self.write(&format!("crate::stdlib::privacy::mask(&{})", value));
+            }
+            ("declassify", [value, reason]) => {
+                let value = self.capture_expression(value);
+                let reason = self.capture_expression(reason);
+                self.write(&format!(
+                    "crate::stdlib::privacy::declassify(&{}, {})",
+                    value, reason
+                ));
+            }
+            _ => self.write(&format!("// Unknown privacy method: {}", method)),
+        }
+    }
+
+    fn generate_realtime_call(&mut self, method: &str, args: &[Expression]) {
+        use crate::stdlib::realtime::RealtimeStdlib;
+        match (method, args) {
+            ("broadcast", [room, message]) => {
+                let room = self.capture_expression(room);
+                let message = self.capture_expression(message);
+                self.write(&RealtimeStdlib::generate_broadcast_code(&room, &message));
+            }
+            ("members", [room]) => {
+                let room = self.capture_expression(room);
+                self.write(&RealtimeStdlib::generate_members_code(&room));
+            }
+            _ => self.write(&format!("// Unknown realtime method: {}", method)),
+        }
+    }
+
+    fn generate_connection_call(&mut self, connection: &str, method: &str, args: &[Expression]) {
+        use crate::stdlib::realtime::RealtimeStdlib;
+        let args: Vec<String> = args.iter().map(|a| self.capture_expression(a)).collect();
+        let connection = self.to_snake_case(connection);
+        match RealtimeStdlib::generate_connection_call(&connection, method, &args) {
+            Some(code) => self.write(&code),
+            None => self.write(&format!("// Unknown Connection method: {}", method)),
+        }
+    }
+
     fn generate_encoding_call(&mut self, method: &str, args: &[Expression]) {
         use crate::stdlib::encoding::EncodingStdlib;
         match method {
@@ -5280,23 +5911,24 @@ where
         match method {
             "log" => {
                 if let Some(arg) = args.first() {
-                    let log_data = self.capture_expression(arg);
+                    let log_data = self.capture_json_expression(arg);
                     self.write(&AuditStdlib::generate_log_code(&log_data));
                 }
             }
             "query" => {
                 if let Some(arg) = args.first() {
-                    let filters = self.capture_expression(arg);
+                    let filters = self.capture_json_expression(arg);
                     self.write(&AuditStdlib::generate_query_code(&filters));
                 }
             }
             "export" => {
                 if args.len() >= 2 {
                     let format = self.capture_expression(&args[0]);
-                    let filters = self.capture_expression(&args[1]);
+                    let filters = self.capture_json_expression(&args[1]);
                     self.write(&AuditStdlib::generate_export_code(&format, &filters));
                 }
             }
+            "verify" => self.write(&AuditStdlib::generate_verify_code()),
             _ => self.write(&format!("// Unknown audit method: {}", method)),
         }
     }
@@ -5478,7 +6110,7 @@ where
         match method {
             "create" => {
                 if let Some(arg) = args.first() {
-                    let definition = self.capture_expression(arg);
+                    let definition = self.capture_json_expression(arg);
                     self.write(&WorkflowStdlib::generate_create_code(&definition));
                 }
             }
@@ -5488,6 +6120,13 @@ where
                     self.write(&WorkflowStdlib::generate_start_code(&workflow));
                 }
             }
+            "run" => {
+                if args.len() >= 2 {
+                    let workflow = self.capture_expression(&args[0]);
+                    let input = self.capture_expression(&args[1]);
+                    self.write(&WorkflowStdlib::generate_run_code(&workflow, &input));
+                }
+            }
             "execute_step" | "executeStep" => {
                 if args.len() >= 2 {
                     let workflow = self.capture_expression(&args[0]);
@@ -5497,6 +6136,15 @@ where
                     ));
                 }
             }
+            "resume" => {
+                if let Some(arg) = args.first() {
+                    let workflow = self.capture_expression(arg);
+                    self.write(&WorkflowStdlib::generate_resume_code(&workflow));
+                }
+            }
+            "resume_all" | "resumeAll" => {
+                self.write(&WorkflowStdlib::generate_resume_all_code());
+            }
             "get_status" | "getStatus" => {
                 if let Some(arg) = args.first() {
                     let workflow = self.capture_expression(arg);
@@ -5531,18 +6179,30 @@ where
         match method {
             "schedule" => {
                 if args.len() >= 2 {
-                    let task = self.capture_expression(&args[0]);
                     let cron = self.capture_expression(&args[1]);
-                    self.write(&SchedulerStdlib::generate_schedule_code(&task, &cron));
+                    if matches!(args[0], Expression::Lambda { .. }) {
+                        let task = self.capture_expression(&args[0]);
+                        self.write(&SchedulerStdlib::generate_schedule_fn_code(&task, &cron));
+                    } else {
+                        let task = self.capture_json_expression(&args[0]);
+                        self.write(&SchedulerStdlib::generate_schedule_code(&task, &cron));
+                    }
                 }
             }
             "schedule_interval" | "scheduleInterval" => {
                 if args.len() >= 2 {
-                    let task = self.capture_expression(&args[0]);
                     let interval = self.capture_expression(&args[1]);
-                    self.write(&SchedulerStdlib::generate_schedule_interval_code(
-                        &task, &interval,
-                    ));
+                    if matches!(args[0], Expression::Lambda { .. }) {
+                        let task = self.capture_expression(&args[0]);
+                        self.write(&SchedulerStdlib::generate_schedule_interval_fn_code(
+                            &task, &interval,
+                        ));
+                    } else {
+                        let task = self.capture_json_expression(&args[0]);
+                        self.write(&SchedulerStdlib::generate_schedule_interval_code(
+                            &task, &interval,
+                        ));
+                    }
                 }
             }
             "cancel" => {
@@ -5578,40 +6238,41 @@ where
 
     fn generate_event_bus_call(&mut self, method: &str, args: &[Expression]) {
         use crate::stdlib::event_bus::EventBusStdlib;
-        match method {
-            "create" => {
+        match (method, args) {
+            ("create", _) => {
                 self.write(&EventBusStdlib::generate_create_code());
             }
-            "publish" => {
-                if args.len() >= 3 {
-                    let bus = self.capture_expression(&args[0]);
-                    let topic = self.capture_expression(&args[1]);
-                    let event = self.capture_expression(&args[2]);
-                    self.write(&EventBusStdlib::generate_publish_code(&bus, &topic, &event));
-                }
-            }
-            "subscribe" => {
-                if args.len() >= 2 {
-                    let bus = self.capture_expression(&args[0]);
-                    let topic = self.capture_expression(&args[1]);
-                    self.write(&EventBusStdlib::generate_subscribe_code(&bus, &topic));
-                }
-            }
-            "unsubscribe" => {
-                if let Some(arg) = args.first() {
-                    let subscription = self.capture_expression(arg);
-                    self.write(&EventBusStdlib::generate_unsubscribe_code(&subscription));
-                }
-            }
-            "get_history" | "getHistory" => {
-                if args.len() >= 3 {
-                    let bus = self.capture_expression(&args[0]);
-                    let topic = self.capture_expression(&args[1]);
-                    let limit = self.capture_expression(&args[2]);
-                    self.write(&EventBusStdlib::generate_get_history_code(
-                        &bus, &topic, &limit,
-                    ));
-                }
+            ("connect", [url]) => {
+                let url = self.capture_expression(url);
+                self.write(&EventBusStdlib::generate_connect_code(&url));
+            }
+            ("publish", [bus, topic, event]) => {
+                let bus = self.capture_expression(bus);
+                let topic = self.capture_expression(topic);
+                let event = self.capture_json_expression(event);
+                self.write(&EventBusStdlib::generate_publish_code(&bus, &topic, &event));
+            }
+            ("subscribe", [bus, topic]) => {
+                let bus = self.capture_expression(bus);
+                let topic = self.capture_expression(topic);
+                self.write(&EventBusStdlib::generate_subscribe_code(&bus, &topic));
+            }
+            ("unsubscribe", [subscription]) => {
+                let subscription = self.capture_expression(subscription);
+                self.write(&EventBusStdlib::generate_unsubscribe_code(&subscription));
+            }
+            ("get_history" | "getHistory", [bus, topic, limit]) => {
+                let bus = self.capture_expression(bus);
+                let topic = self.capture_expression(topic);
+                let limit = self.capture_expression(limit);
+                self.write(&EventBusStdlib::generate_get_history_code(
+                    &bus, &topic, &limit,
+                ));
+            }
+            ("replay", [bus, event]) => {
+                let bus = self.capture_expression(bus);
+                let event = self.capture_json_expression(event);
+                self.write(&EventBusStdlib::generate_replay_code(&bus, &event));
             }
             _ => self.write(&format!("// Unknown event_bus method: {}", method)),
         }
@@ -5694,6 +6355,16 @@ where
 
     fn generate_template_call(&mut self, method: &str, args: &[Expression]) {
         use crate::stdlib::template::TemplateStdlib;
+        // Literal-Templates wurden vom Type Checker geprüft und werden direkt übersetzt
+        if let Some(Ok(nodes)) =
+            crate::stdlib::template::compile_call(method, args, &self.template_dir)
+        {
+            let context = self.capture_json_expression(&args[1]);
+            self.write(&crate::stdlib::template::generate_compiled_code(
+                method, &nodes, &context,
+            ));
+            return;
+        }
         match method {
             "render" => {
                 if args.len() >= 2 {
@@ -6204,6 +6875,12 @@ where
         }
     }
 
+    fn capture_type(&mut self, ty: &Type) -> String {
+        let old_output = std::mem::take(&mut self.output);
+        self.generate_type(ty);
+        std::mem::replace(&mut self.output, old_output)
+    }
+
     fn capture_expression(&mut self, expr: &Expression) -> String {
         let mut captured = String::new();
         let old_output = std::mem::replace(&mut self.output, captured);
@@ -6211,6 +6888,31 @@ where
         captured = std::mem::replace(&mut self.output, old_output);
         captured
     }
+
+    /// Ausdruck als `serde_json::json!(...)`; verschachtelte Map- und Listen-Literale
+    /// werden zu JSON-Objekten bzw. -Arrays statt zu `HashMap`/`Vec`
+    fn capture_json_expression(&mut self, expr: &Expression) -> String {
+        format!("serde_json::json!({})", self.json_tokens(expr))
+    }
+
+    fn json_tokens(&mut self, expr: &Expression) -> String {
+        match expr {
+            Expression::MapLiteral(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| format!("{:?}: {}", key, self.json_tokens(value)))
+                    .collect();
+                format!("{{ {} }}", fields.join(", "))
+            }
+            Expression::ListLiteral(items) => {
+                let items: Vec<String> = items.iter().map(|item| self.json_tokens(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Expression::Literal(Literal::String(s)) => format!("{:?}", s),
+            Expression::Literal(Literal::Null) => "null".to_string(),
+            other => format!("({})", self.capture_expression(other)),
+        }
+    }
     fn is_route_handler(&self, decorators: &[Decorator]) -> Option<(String, String)> {
         for dec in decorators {
             match dec.name.as_str() {
@@ -6384,3 +7086,107 @@ impl Default for RustCodeGenerator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_struct_literal_fields_match_the_struct_definition() {
+        let source = r#"struct Customer {
+    firstName: string,
+    loyaltyPoints: number,
+}
+
+fn newCustomer(name: string): Customer {
+    return Customer { firstName: name, loyaltyPoints: 0 };
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        let config = CodegenConfig {
+            target: TargetLanguage::Rust,
+            framework: None,
+            orm: None,
+            output_path: None,
+        };
+        let code =
+            CodeGenerator::generate(&mut RustCodeGenerator::new(), &program, &config).unwrap();
+
+        // Die Struct-Definition nutzt snake_case, das Literal muss dieselben Namen verwenden
+        assert!(code.contains("first_name: String"));
+        assert!(code.contains("Customer {first_name: name, loyalty_points: 0.0}"));
+        assert!(!code.contains("firstName"));
+    }
+
+    const VALIDATED_SERVICE: &str = r##"struct CreateProductRequest {
+    @Validate(required: true, min: 3, max: 100)
+    name: string,
+
+    @Validate(pattern: "^[A-Z]\"#[0-9]+$")
+    sku: string,
+}
+
+@POST("/api/products")
+fn createProduct(request: CreateProductRequest): CreateProductRequest {
+    return request;
+}
+
+@POST("/api/products/async")
+async fn createProductAsync(request: CreateProductRequest): CreateProductRequest {
+    return request;
+}
+
+@GET("/api/products/:sku")
+fn getProduct(sku: string): string {
+    return sku;
+}
+"##;
+
+    #[test]
+    fn test_validated_handler_answers_with_response() {
+        let program = Parser::parse(VALIDATED_SERVICE).unwrap();
+        let code = crate::codegen::generate_parsed_rust(&program, Some("axum"));
+
+        assert!(code.contains(
+            "fn create_product(Json(mut request): Json<CreateProductRequest>) -> Result<axum::Json<CreateProductRequest>, axum::response::Response> {"
+        ));
+        assert!(code.contains(
+            "return Err((axum::http::StatusCode::BAD_REQUEST, axum::Json(response)).into_response());"
+        ));
+        assert!(code.contains("let __validated: CreateProductRequest = (move || {"));
+        assert!(code.contains("let __validated: CreateProductRequest = async move {"));
+        assert!(code.contains("Ok(axum::Json(__validated))"));
+        assert!(!code.contains("anyhow::anyhow!(\"Validation failed\")"));
+
+        // Handler ohne geprüften Body behalten ihre Signatur
+        assert!(code.contains("fn get_product(Path(mut sku): Path<String>) -> String {"));
+        assert_eq!(
+            code.matches("let mut validator = Validator::new();")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_validated_handler_with_actix() {
+        let program = Parser::parse(VALIDATED_SERVICE).unwrap();
+        let code = crate::codegen::generate_parsed_rust(&program, Some("actix"));
+
+        assert!(code.contains(
+            "-> actix_web::Either<actix_web::web::Json<CreateProductRequest>, actix_web::HttpResponse> {"
+        ));
+        assert!(
+            code.contains("return actix_web::Either::Right(actix_web::HttpResponse::BadRequest()")
+        );
+        assert!(code.contains("actix_web::Either::Left(actix_web::web::Json(__validated))"));
+    }
+
+    #[test]
+    fn test_validation_pattern_is_escaped() {
+        let program = Parser::parse(VALIDATED_SERVICE).unwrap();
+        let code = crate::codegen::generate_parsed_rust(&program, Some("axum"));
+
+        assert!(code.contains(r##"validator.pattern("sku", &self.sku, "^[A-Z]\"#[0-9]+$", "##));
+    }
+}
@@ -69,6 +69,19 @@ pub enum DeploymentType {
     Serverless,
 }
 
+impl DeploymentType {
+    /// Plattform-Name aus der CLI (`local`, `compose`, `kubernetes`, `serverless`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "local" => Some(DeploymentType::Local),
+            "compose" | "docker" | "single" => Some(DeploymentType::CloudSingle),
+            "kubernetes" | "k8s" | "multi" => Some(DeploymentType::CloudMulti),
+            "serverless" | "lambda" => Some(DeploymentType::Serverless),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum APIType {
     Chatbot,
@@ -1624,6 +1637,9 @@ impl APICall {
             crate::parser::ast::Type::Optional(inner_type) => {
                 format!("Option<{}>", Self::type_to_string(inner_type))
             }
+            crate::parser::ast::Type::Stream(item_type) => {
+                format!("Stream<{}>", Self::type_to_string(item_type))
+            }
             crate::parser::ast::Type::Generic { name, params } => {
                 if params.is_empty() {
                     name.clone()
@@ -13,6 +13,8 @@ pub enum TargetLanguage {
     Go,
     Java,
     CSharp,
+    /// `wasm32`-Modul über den Rust-Backend, mit JS/TypeScript-Bindings
+    Wasm,
 }
 
 impl std::fmt::Display for TargetLanguage {
@@ -26,6 +28,7 @@ impl std::fmt::Display for TargetLanguage {
             TargetLanguage::Go => write!(f, "Go"),
             TargetLanguage::Java => write!(f, "Java"),
             TargetLanguage::CSharp => write!(f, "C#"),
+            TargetLanguage::Wasm => write!(f, "WebAssembly"),
         }
     }
 }
@@ -43,6 +46,7 @@ impl std::str::FromStr for TargetLanguage {
             "go" | "golang" => Ok(TargetLanguage::Go),
             "java" => Ok(TargetLanguage::Java),
             "csharp" | "c#" | "cs" => Ok(TargetLanguage::CSharp),
+            "wasm" | "webassembly" => Ok(TargetLanguage::Wasm),
             _ => Err(format!("Unknown target language: {}", s)),
         }
     }
@@ -1,13 +1,16 @@
 use super::{CodeGenerator, CodegenConfig, TargetLanguage};
 use crate::codegen::framework::{Framework, FrameworkSelector};
 use crate::parser::ast::*;
+use crate::validation::{self, Check, RuleValue, ValueKind};
 use anyhow::Result;
+use std::collections::HashSet;
 
 pub struct TypeScriptCodeGenerator {
     buffer: String,
     indent_level: usize,
     framework: Option<Framework>,
     routes: Vec<(String, String, String)>, // (method, path, handler_name)
+    validated_structs: HashSet<String>,
 }
 
 impl TypeScriptCodeGenerator {
@@ -17,6 +20,7 @@ impl TypeScriptCodeGenerator {
             indent_level: 0,
             framework: None,
             routes: Vec::new(),
+            validated_structs: HashSet::new(),
         }
     }
 
@@ -83,6 +87,73 @@ impl TypeScriptCodeGenerator {
         self.writeln("");
     }
 
+    /// `validate<Struct>()` mit einem Eintrag pro verletzter `@Validate`-Regel
+    fn generate_validator(&mut self, s: &Struct) {
+        self.writeln(&format!(
+            "export function validate{}(value: {}): ValidationIssue[] {{",
+            s.name, s.name
+        ));
+        self.indent();
+        self.writeln("const errors: ValidationIssue[] = [];");
+        for field in validation::struct_validations(s) {
+            let access = format!("value.{}", field.name);
+            for check in field.checks() {
+                let condition = script_failure(&check.check, &access, field.kind);
+                self.writeln(&format!("if ({}) {{", condition));
+                self.indent();
+                self.writeln(&format!(
+                    "errors.push({{ field: {:?}, message: {:?} }});",
+                    field.name, check.message
+                ));
+                self.dedent();
+                self.writeln("}");
+            }
+        }
+        self.writeln("return errors;");
+        self.dedent();
+        self.writeln("}");
+        self.writeln("");
+    }
+
+    /// Prüft Request-Bodys mit `@Validate`-Feldern und antwortet mit 400
+    fn generate_request_validation(&mut self, f: &Function) {
+        let params: Vec<(String, String)> =
+            validation::validated_params(f, &self.validated_structs)
+                .iter()
+                .map(|p| (p.name.clone(), self.map_type(&p.param_type)))
+                .collect();
+        for (param, struct_name) in params {
+            let body = format!(
+                "{{ error: '{}', errors: {}Errors }}",
+                validation::VALIDATION_FAILED,
+                param
+            );
+            let response = match self.framework {
+                Some(Framework::Express) => {
+                    vec![
+                        format!("res.status(400).json({});", body),
+                        "return;".to_string(),
+                    ]
+                }
+                Some(Framework::NestJS) => {
+                    vec![format!("throw new BadRequestException({});", body)]
+                }
+                _ => continue,
+            };
+            self.writeln(&format!(
+                "const {}Errors = validate{}({});",
+                param, struct_name, param
+            ));
+            self.writeln(&format!("if ({}Errors.length > 0) {{", param));
+            self.indent();
+            for line in response {
+                self.writeln(&line);
+            }
+            self.dedent();
+            self.writeln("}");
+        }
+    }
+
     fn generate_function(&mut self, f: &Function) {
         let is_handler = f.decorators.iter().any(|d| {
             matches!(
@@ -201,6 +272,10 @@ impl TypeScriptCodeGenerator {
             }
         }
 
+        if is_handler {
+            self.generate_request_validation(f);
+        }
+
         // Body
         if f.body.statements.is_empty() {
             // Empty body
@@ -387,6 +462,24 @@ impl CodeGenerator for TypeScriptCodeGenerator {
                 .push_str(&FrameworkSelector::generate_imports(fw));
         }
 
+        // Validatoren stehen vor dem NestJS-Controller auf Modulebene
+        self.validated_structs = validation::validated_structs(&program.items);
+        if !self.validated_structs.is_empty() {
+            self.writeln("");
+            self.writeln("export interface ValidationIssue {");
+            self.writeln("    field: string;");
+            self.writeln("    message: string;");
+            self.writeln("}");
+            self.writeln("");
+            for item in &program.items {
+                if let Item::Struct(s) = item {
+                    if self.validated_structs.contains(&s.name) {
+                        self.generate_validator(s);
+                    }
+                }
+            }
+        }
+
         // Generate Items
         let mut functions = Vec::new();
 
@@ -428,3 +521,28 @@ impl CodeGenerator for TypeScriptCodeGenerator {
         TargetLanguage::TypeScript
     }
 }
+
+/// Fehlerbedingung einer `@Validate`-Prüfung in TypeScript/JavaScript.
+/// Alle Regeln außer `required` gelten nur für vorhandene Werte.
+pub(crate) fn script_failure(check: &Check, access: &str, kind: ValueKind) -> String {
+    let failed = match check {
+        Check::Required if kind == ValueKind::Text => return format!("!{}", access),
+        Check::Required => return format!("{} == null", access),
+        Check::MinLength(n) | Check::MinItems(n) => format!("{}.length < {}", access, n),
+        Check::MaxLength(n) | Check::MaxItems(n) => format!("{}.length > {}", access, n),
+        Check::Pattern(pattern) => format!("!new RegExp({:?}).test({})", pattern, access),
+        Check::Min(n) => format!("{} < {}", access, n),
+        Check::Max(n) => format!("{} > {}", access, n),
+        Check::OneOf(values) => {
+            let values: Vec<String> = values
+                .iter()
+                .map(|v| match v {
+                    RuleValue::String(s) => format!("{:?}", s),
+                    RuleValue::Number(n) => n.to_string(),
+                })
+                .collect();
+            format!("![{}].includes({})", values.join(", "), access)
+        }
+    };
+    format!("{} != null && {}", access, failed)
+}
@@ -0,0 +1,787 @@
+// WebAssembly-Codegen
+// Erzeugt über den Rust-Backend ein `wasm32`-Modul und die JS/TypeScript-Bindings
+// für alle `@WASMExport`-Funktionen
+
+use crate::codegen::rust::RustCodeGenerator;
+use crate::codegen::traits::{CodeGenerator, CodegenConfig, TargetLanguage};
+use crate::parser::ast::*;
+use crate::parser::visitor::visit_block;
+use anyhow::{anyhow, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Stdlib-Module, die den Host (Dateisystem, Prozesse, Umgebung, Netzwerk) brauchen
+/// und in einem `wasm32-unknown-unknown`-Modul nicht verfügbar sind. `env` liefert dort
+/// immer einen Fehler, HTTP-Aufrufe laufen über reqwest ohne `fetch`-Anbindung, und die
+/// Laufzeiten von Event-Bus, Scheduler, Workflows, Audit und Realtime erzeugt nur der
+/// Server-Codegen.
+pub const HOST_MODULES: &[&str] = &[
+    "fs",
+    "file",
+    "process",
+    "system",
+    "env",
+    "db",
+    "redis",
+    "mongodb",
+    "smtp",
+    "email",
+    "sandbox",
+    "backup",
+    "rollback",
+    "http",
+    "http_client",
+    "llm",
+    "embedding",
+    "websocket",
+    "realtime",
+    "event_bus",
+    "scheduler",
+    "workflow",
+    "audit",
+];
+
+/// Rust-Target, für das das Modul gebaut wird
+pub const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// Symbolname der exportierten Wrapper-Funktion
+fn export_symbol(function: &str) -> String {
+    format!("__velin_export_{}", function)
+}
+
+fn has_decorator(function: &Function, name: &str) -> bool {
+    function.decorators.iter().any(|d| d.name == name)
+}
+
+/// Top-Level-Funktionen mit `@WASMExport`
+pub fn exports(program: &Program) -> Vec<&Function> {
+    program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(f) if has_decorator(f, "WASMExport") => Some(f),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Die `@WASMInit`-Funktion, die der Loader nach dem Instanziieren aufruft
+pub fn init_function(program: &Program) -> Option<&Function> {
+    program.items.iter().find_map(|item| match item {
+        Item::Function(f) if has_decorator(f, "WASMInit") => Some(f),
+        _ => None,
+    })
+}
+
+fn is_fallible(return_type: &Option<Type>) -> bool {
+    matches!(return_type, Some(Type::Result { .. }))
+        || matches!(return_type, Some(Type::Generic { name, .. }) if name == "Result")
+}
+
+/// Prüft, ob ein Programm als Wasm-Modul gebaut werden kann.
+///
+/// Liefert eine Fehlermeldung pro Problem: ungeeignete Export-Signaturen und
+/// Host-Module, die von einem Export oder der Init-Funktion aus erreichbar sind.
+pub fn check_program(program: &Program) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for item in &program.items {
+        if let Item::Module(module) = item {
+            for item in &module.items {
+                if let Item::Function(f) = item {
+                    if has_decorator(f, "WASMExport") || has_decorator(f, "WASMInit") {
+                        errors.push(format!(
+                            "`{}.{}`: @WASMExport/@WASMInit sind nur an Top-Level-Funktionen erlaubt",
+                            module.name, f.name
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    let exported = exports(program);
+    if exported.is_empty() {
+        errors.push("Keine Funktion mit @WASMExport gefunden".to_string());
+    }
+    for function in &exported {
+        if function.is_async {
+            errors.push(format!(
+                "@WASMExport `{}`: async-Funktionen können nicht exportiert werden",
+                function.name
+            ));
+        }
+        if !function.type_params.is_empty() {
+            errors.push(format!(
+                "@WASMExport `{}`: generische Funktionen können nicht exportiert werden",
+                function.name
+            ));
+        }
+        let signature = function
+            .params
+            .iter()
+            .map(|p| &p.param_type)
+            .chain(function.return_type.as_ref());
+        for ty in signature {
+            if let Some(reason) = unmarshallable(ty) {
+                errors.push(format!(
+                    "@WASMExport `{}`: Typ `{:?}` kann nicht an JavaScript übergeben werden ({})",
+                    function.name, ty, reason
+                ));
+            }
+        }
+    }
+
+    let inits: Vec<&Function> = program
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Function(f) if has_decorator(f, "WASMInit") => Some(f),
+            _ => None,
+        })
+        .collect();
+    if inits.len() > 1 {
+        errors.push("Nur eine Funktion darf @WASMInit tragen".to_string());
+    }
+    for init in &inits {
+        if !init.params.is_empty() || init.is_async {
+            errors.push(format!(
+                "@WASMInit `{}` darf weder Parameter haben noch async sein",
+                init.name
+            ));
+        }
+    }
+
+    let roots: Vec<&Function> = exported.iter().chain(inits.iter()).copied().collect();
+    errors.extend(host_usages(program, &roots));
+    errors
+}
+
+fn unmarshallable(ty: &Type) -> Option<&'static str> {
+    match ty {
+        Type::Function { .. } => Some("Funktionen sind nicht serialisierbar"),
+        Type::List(inner) | Type::Optional(inner) => unmarshallable(inner),
+        Type::Map { key, value } => unmarshallable(key).or_else(|| unmarshallable(value)),
+        Type::Tuple(types) => types.iter().find_map(unmarshallable),
+        Type::Result { ok, .. } => unmarshallable(ok),
+        Type::Generic { params, .. } => params.iter().find_map(unmarshallable),
+        _ => None,
+    }
+}
+
+/// Host-Modul-Aufrufe in allen Funktionen, die von `roots` aus erreichbar sind
+fn host_usages(program: &Program, roots: &[&Function]) -> Vec<String> {
+    let mut functions: HashMap<&str, &Function> = HashMap::new();
+    for item in &program.items {
+        match item {
+            Item::Function(f) => {
+                functions.insert(&f.name, f);
+            }
+            Item::Module(module) => {
+                for item in &module.items {
+                    if let Item::Function(f) = item {
+                        functions.entry(&f.name).or_insert(f);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut errors = Vec::new();
+    let mut visited = HashSet::new();
+    for root in roots {
+        let mut stack: Vec<&Function> = vec![root];
+        while let Some(function) = stack.pop() {
+            if !visited.insert(function.name.clone()) {
+                continue;
+            }
+            let mut calls = Vec::new();
+            let mut modules = Vec::new();
+            visit_block(&function.body, &mut |expr| {
+                if let Expression::Call { callee, .. } = expr {
+                    match callee.as_ref() {
+                        Expression::Identifier(name) => calls.push(name.clone()),
+                        Expression::Member { object, member } => match object.as_ref() {
+                            Expression::Identifier(module)
+                                if HOST_MODULES.contains(&module.as_str()) =>
+                            {
+                                modules.push(format!("{}.{}", module, member))
+                            }
+                            // `modul.funktion()` eines importierten Velin-Moduls
+                            Expression::Identifier(_) => calls.push(member.clone()),
+                            _ => {}
+                        },
+                        _ => {}
+                    }
+                }
+            });
+            for call in modules {
+                let via = if function.name == root.name {
+                    String::new()
+                } else {
+                    format!(" (erreichbar über `{}`)", root.name)
+                };
+                errors.push(format!(
+                    "`{}` in `{}`{} braucht den Host und ist im Wasm-Target nicht verfügbar",
+                    call, function.name, via
+                ));
+            }
+            stack.extend(calls.iter().filter_map(|name| functions.get(name.as_str())));
+        }
+    }
+    errors
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut result = String::new();
+    for ch in s.chars() {
+        if ch.is_uppercase() && !result.is_empty() {
+            result.push('_');
+        }
+        result.push(ch.to_lowercase().next().unwrap_or(ch));
+    }
+    result
+}
+
+/// Speicherverwaltung und JSON-Marshalling an der Modulgrenze.
+/// Argumente kommen als JSON-Array, Ergebnisse gehen als `{"ok": ...}` bzw.
+/// `{"error": "..."}` zurück; Zeiger und Länge sind in einem `u64` gepackt.
+const ABI_RUNTIME: &str = r#"
+// --- Velisch WebAssembly ABI ---
+mod __velin_wasm {
+    fn leak(bytes: Vec<u8>) -> (*mut u8, usize) {
+        let len = bytes.len();
+        let mut boxed = if len == 0 { vec![0u8].into_boxed_slice() } else { bytes.into_boxed_slice() };
+        let ptr = boxed.as_mut_ptr();
+        std::mem::forget(boxed);
+        (ptr, len)
+    }
+
+    pub fn alloc(len: usize) -> *mut u8 {
+        leak(vec![0u8; len]).0
+    }
+
+    /// # Safety
+    /// `ptr`/`len` müssen aus `alloc` bzw. einem Export-Ergebnis stammen
+    pub unsafe fn take(ptr: *mut u8, len: usize) -> Vec<u8> {
+        let slice = std::ptr::slice_from_raw_parts_mut(ptr, len.max(1));
+        let mut bytes = Box::from_raw(slice).into_vec();
+        bytes.truncate(len);
+        bytes
+    }
+
+    pub fn finish(result: Result<serde_json::Value, String>) -> u64 {
+        let envelope = match result {
+            Ok(value) => serde_json::json!({ "ok": value }),
+            Err(error) => serde_json::json!({ "error": error }),
+        };
+        let (ptr, len) = leak(envelope.to_string().into_bytes());
+        ((ptr as u64) << 32) | len as u64
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn __velin_alloc(len: usize) -> *mut u8 {
+    __velin_wasm::alloc(len)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __velin_free(ptr: *mut u8, len: usize) {
+    drop(__velin_wasm::take(ptr, len));
+}
+"#;
+
+/// Code-Generator für `--target wasm`: Rust-Quelle des Moduls (`src/lib.rs`)
+pub struct WasmCodeGenerator;
+
+impl WasmCodeGenerator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn generate_exports(&self, program: &Program) -> String {
+        let mut out = String::from(ABI_RUNTIME);
+        for function in exports(program) {
+            let rust_name = to_snake_case(&function.name);
+            let params: Vec<String> = function
+                .params
+                .iter()
+                .map(|p| to_snake_case(&p.name))
+                .collect();
+            let types: Vec<String> = function
+                .params
+                .iter()
+                .map(|p| rust_type(&p.param_type))
+                .collect();
+
+            out.push_str(&format!(
+                "\n#[no_mangle]\npub unsafe extern \"C\" fn {}(ptr: *mut u8, len: usize) -> u64 {{\n",
+                export_symbol(&rust_name)
+            ));
+            out.push_str("    let input = __velin_wasm::take(ptr, len);\n");
+            out.push_str("    __velin_wasm::finish((|| {\n");
+            if params.is_empty() {
+                out.push_str("        let _ = input;\n");
+            } else {
+                out.push_str(&format!(
+                    "        let ({},): ({},) = serde_json::from_slice(&input).map_err(|e| e.to_string())?;\n",
+                    params.join(", "),
+                    types.join(", ")
+                ));
+            }
+            let call = format!("{}({})", rust_name, params.join(", "));
+            if is_fallible(&function.return_type) {
+                out.push_str(&format!(
+                    "        let result = {}.map_err(|e| e.to_string())?;\n",
+                    call
+                ));
+            } else {
+                out.push_str(&format!("        let result = {};\n", call));
+            }
+            out.push_str("        serde_json::to_value(result).map_err(|e| e.to_string())\n");
+            out.push_str("    })())\n}\n");
+        }
+        if let Some(init) = init_function(program) {
+            out.push_str(&format!(
+                "\n#[no_mangle]\npub extern \"C\" fn __velin_init() {{\n    let _ = {}();\n}}\n",
+                to_snake_case(&init.name)
+            ));
+        }
+        out
+    }
+}
+
+impl Default for WasmCodeGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeGenerator for WasmCodeGenerator {
+    fn generate(&mut self, program: &Program, _config: &CodegenConfig) -> Result<String> {
+        let mut code = RustCodeGenerator::for_wasm().generate(program, None, None);
+        code.push_str(&self.generate_exports(program));
+        Ok(code)
+    }
+
+    fn get_target_language(&self) -> TargetLanguage {
+        TargetLanguage::Wasm
+    }
+}
+
+/// Rust-Typ eines Export-Parameters (wie `RustCodeGenerator` im Wasm-Modus)
+fn rust_type(ty: &Type) -> String {
+    match ty {
+        Type::String => "String".to_string(),
+        Type::Number => "f64".to_string(),
+        Type::Boolean => "bool".to_string(),
+        Type::Void => "()".to_string(),
+        Type::Null => "Option<String>".to_string(),
+        Type::Any => "Any".to_string(),
+        Type::Named(name) => pascal_case(name),
+        Type::List(inner) => format!("Vec<{}>", rust_type(inner)),
+        Type::Map { key, value } => format!(
+            "std::collections::HashMap<{}, {}>",
+            rust_type(key),
+            rust_type(value)
+        ),
+        Type::Tuple(types) => format!(
+            "({})",
+            types.iter().map(rust_type).collect::<Vec<_>>().join(", ")
+        ),
+        Type::Optional(inner) => format!("Option<{}>", rust_type(inner)),
+        Type::Result { ok, err } => format!("Result<{}, {}>", rust_type(ok), rust_type(err)),
+        // Streams und Funktionen überqueren die Modulgrenze nicht
+        Type::Function { .. } | Type::Stream(_) => "()".to_string(),
+        Type::Generic { name, params } => match (name.as_str(), params.as_slice()) {
+            ("List", [inner]) => format!("Vec<{}>", rust_type(inner)),
+            ("Map", [key, value]) => format!(
+                "std::collections::HashMap<{}, {}>",
+                rust_type(key),
+                rust_type(value)
+            ),
+            ("Optional", [inner]) => format!("Option<{}>", rust_type(inner)),
+            _ => format!(
+                "{}<{}>",
+                pascal_case(name),
+                params.iter().map(rust_type).collect::<Vec<_>>().join(", ")
+            ),
+        },
+    }
+}
+
+fn pascal_case(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// TypeScript-Typ eines Werts, wie er als JSON über die Modulgrenze kommt
+fn ts_type(ty: &Type) -> String {
+    match ty {
+        Type::String => "string".to_string(),
+        Type::Number => "number".to_string(),
+        Type::Boolean => "boolean".to_string(),
+        Type::Void => "void".to_string(),
+        Type::Null => "null".to_string(),
+        Type::Any => "unknown".to_string(),
+        Type::Named(name) => pascal_case(name),
+        Type::List(inner) => format!("{}[]", ts_element(inner)),
+        Type::Map { value, .. } => format!("Record<string, {}>", ts_type(value)),
+        Type::Tuple(types) => format!(
+            "[{}]",
+            types.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+        ),
+        Type::Optional(inner) => format!("{} | null", ts_type(inner)),
+        // Fehler werden als Exception geworfen
+        Type::Result { ok, .. } => ts_type(ok),
+        Type::Function { .. } | Type::Stream(_) => "never".to_string(),
+        Type::Generic { name, params } => match (name.as_str(), params.as_slice()) {
+            ("List", [inner]) => format!("{}[]", ts_element(inner)),
+            ("Map", [_, value]) => format!("Record<string, {}>", ts_type(value)),
+            ("Optional", [inner]) => format!("{} | null", ts_type(inner)),
+            ("Result", [ok, ..]) => ts_type(ok),
+            _ => pascal_case(name),
+        },
+    }
+}
+
+fn ts_element(ty: &Type) -> String {
+    let inner = ts_type(ty);
+    if inner.contains(' ') {
+        format!("({})", inner)
+    } else {
+        inner
+    }
+}
+
+/// Dateien eines Wasm-Pakets: Cargo-Manifest, JS-Loader und TypeScript-Deklarationen
+pub struct WasmPackage {
+    pub dir: PathBuf,
+    pub name: String,
+}
+
+impl WasmPackage {
+    /// Paket `<dir>` mit Modulnamen aus dem Verzeichnisnamen (`recommender-wasm` → `recommender_wasm`)
+    pub fn new(dir: PathBuf) -> Self {
+        let name = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("velin_wasm")
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Self { dir, name }
+    }
+
+    /// Pfad der Rust-Quelle, in die der Codegen schreibt
+    pub fn source_path(&self) -> PathBuf {
+        self.dir.join("src").join("lib.rs")
+    }
+
+    pub fn wasm_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.wasm", self.name))
+    }
+
+    pub fn write_support_files(&self, program: &Program) -> Result<()> {
+        let optimize = program
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Function(f) if has_decorator(f, "WASMOptimize")));
+        fs::create_dir_all(self.dir.join(".cargo"))?;
+        fs::write(self.dir.join("Cargo.toml"), self.cargo_toml(optimize))?;
+        if optimize {
+            // @WASMOptimize: SIMD-Instruktionen für das gesamte Modul
+            fs::write(
+                self.dir.join(".cargo").join("config.toml"),
+                format!(
+                    "[target.{}]\nrustflags = [\"-C\", \"target-feature=+simd128\"]\n",
+                    WASM_TARGET
+                ),
+            )?;
+        }
+        fs::write(
+            self.dir.join(format!("{}.js", self.name)),
+            self.generate_js(program),
+        )?;
+        fs::write(
+            self.dir.join(format!("{}.d.ts", self.name)),
+            self.generate_dts(program),
+        )?;
+        Ok(())
+    }
+
+    fn cargo_toml(&self, optimize: bool) -> String {
+        format!(
+            r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+crate-type = ["cdylib"]
+path = "src/lib.rs"
+
+[dependencies]
+serde = {{ version = "1.0", features = ["derive"] }}
+serde_json = "1.0"
+anyhow = "1.0"
+
+[profile.release]
+opt-level = {opt_level}
+lto = true
+"#,
+            name = self.name,
+            opt_level = if optimize { "3" } else { "\"s\"" }
+        )
+    }
+
+    /// ES-Modul, das das Wasm-Modul lädt und die Exporte als synchrone Funktionen anbietet
+    pub fn generate_js(&self, program: &Program) -> String {
+        let mut out = format!(
+            r#"// Generiert von velin-compiler (--target wasm) – nicht bearbeiten
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+let wasm;
+
+function call(symbol, args) {{
+  if (!wasm) throw new Error("{name}: init() wurde noch nicht aufgerufen");
+  const input = encoder.encode(JSON.stringify(args));
+  const ptr = wasm.__velin_alloc(input.length);
+  new Uint8Array(wasm.memory.buffer, ptr, input.length).set(input);
+  const packed = wasm[symbol](ptr, input.length);
+  const outPtr = Number(packed >> 32n);
+  const outLen = Number(packed & 0xffffffffn);
+  const text = decoder.decode(new Uint8Array(wasm.memory.buffer, outPtr, outLen));
+  wasm.__velin_free(outPtr, outLen);
+  const result = JSON.parse(text);
+  if ("error" in result) throw new Error(result.error);
+  return result.ok;
+}}
+
+/**
+ * Lädt das Modul. `source` ist ein Buffer/ArrayBuffer (z.B. aus `fs.readFile`),
+ * eine URL oder eine `Response`; Standard ist `{name}.wasm` neben dieser Datei.
+ */
+export async function init(source) {{
+  if (wasm) return;
+  source ??= new URL("{name}.wasm", import.meta.url);
+  if (typeof source === "string" || source instanceof URL) source = fetch(source);
+  source = await source;
+  const bytes = source instanceof Response ? await source.arrayBuffer() : source;
+  const {{ instance }} = await WebAssembly.instantiate(bytes, {{}});
+  wasm = instance.exports;
+  if (wasm.__velin_init) wasm.__velin_init();
+}}
+"#,
+            name = self.name
+        );
+        for function in exports(program) {
+            let params: Vec<&str> = function.params.iter().map(|p| p.name.as_str()).collect();
+            out.push_str(&format!(
+                "\nexport function {}({}) {{\n  return call(\"{}\", [{}]);\n}}\n",
+                function.name,
+                params.join(", "),
+                export_symbol(&to_snake_case(&function.name)),
+                params.join(", ")
+            ));
+        }
+        out
+    }
+
+    pub fn generate_dts(&self, program: &Program) -> String {
+        let mut out = String::from(
+            "// Generiert von velin-compiler (--target wasm) – nicht bearbeiten\n\n\
+             export function init(source?: BufferSource | URL | string | Response | Promise<Response>): Promise<void>;\n",
+        );
+        for item in &program.items {
+            match item {
+                Item::Struct(def) => {
+                    out.push_str(&format!(
+                        "\nexport interface {} {{\n",
+                        pascal_case(&def.name)
+                    ));
+                    for field in &def.fields {
+                        out.push_str(&format!(
+                            "  {}: {};\n",
+                            field.name,
+                            ts_type(&field.field_type)
+                        ));
+                    }
+                    out.push_str("}\n");
+                }
+                Item::Enum(def) => {
+                    // Serde-Darstellung: Varianten ohne Daten als String, sonst `{ Variante: daten }`
+                    let variants: Vec<String> = def
+                        .variants
+                        .iter()
+                        .map(|v| {
+                            let name = pascal_case(&v.name);
+                            match v.data.as_deref() {
+                                None | Some([]) => format!("\"{}\"", name),
+                                Some([single]) => format!("{{ {}: {} }}", name, ts_type(single)),
+                                Some(types) => format!(
+                                    "{{ {}: [{}] }}",
+                                    name,
+                                    types.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+                                ),
+                            }
+                        })
+                        .collect();
+                    out.push_str(&format!(
+                        "\nexport type {} = {};\n",
+                        pascal_case(&def.name),
+                        variants.join(" | ")
+                    ));
+                }
+                _ => {}
+            }
+        }
+        for function in exports(program) {
+            let params: Vec<String> = function
+                .params
+                .iter()
+                .map(|p| format!("{}: {}", p.name, ts_type(&p.param_type)))
+                .collect();
+            let ret = function
+                .return_type
+                .as_ref()
+                .map(ts_type)
+                .unwrap_or_else(|| "void".to_string());
+            out.push_str(&format!(
+                "\nexport function {}({}): {};\n",
+                function.name,
+                params.join(", "),
+                ret
+            ));
+        }
+        out
+    }
+
+    /// Baut das Modul mit `cargo` für `wasm32-unknown-unknown` und kopiert es ins Paket
+    pub fn build(&self) -> Result<PathBuf> {
+        let manifest = self.dir.join("Cargo.toml");
+        let status = Command::new("cargo")
+            .args([
+                "build",
+                "--release",
+                "--target",
+                WASM_TARGET,
+                "--manifest-path",
+            ])
+            .arg(&manifest)
+            .status()
+            .context("cargo konnte nicht gestartet werden")?;
+        if !status.success() {
+            return Err(anyhow!(
+                "Wasm-Build fehlgeschlagen (ist das Target installiert? `rustup target add {}`)",
+                WASM_TARGET
+            ));
+        }
+        let built = self
+            .dir
+            .join("target")
+            .join(WASM_TARGET)
+            .join("release")
+            .join(format!("{}.wasm", self.name));
+        let wasm = self.wasm_path();
+        fs::copy(&built, &wasm).with_context(|| format!("{} nicht gefunden", built.display()))?;
+        Ok(wasm)
+    }
+}
+
+/// Standard-Paketverzeichnis: `<datei>-wasm` neben der Eingabe
+pub fn default_package_dir(input: &Path) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("velin");
+    input.with_file_name(format!("{}-wasm", stem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_host_modules_reachable_from_exports_are_rejected() {
+        let source = r#"struct Point {
+    posX: number,
+    posY: number,
+}
+
+fn readConfig(): string {
+    return fs.readFile("config.json");
+}
+
+fn serverOnly() {
+    db.findAll(Point);
+}
+
+@WASMExport
+fn apiKey(): string {
+    return env.get("API_KEY");
+}
+
+@WASMExport
+fn download(url: string): string {
+    return http.get(url);
+}
+
+@WASMExport
+fn loadConfig(): string {
+    return readConfig();
+}
+
+@WASMExport
+fn shift(point: Point, dx: number): Point {
+    let moved = point.posX + dx;
+    return Point { posX: moved, posY: point.posY };
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        let errors = check_program(&program);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.contains("`env.get` in `apiKey`")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("`http.get` in `download`")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("`fs.readFile` in `readConfig` (erreichbar über `loadConfig`)")));
+
+        let package = WasmPackage::new(PathBuf::from("geo-wasm"));
+        let dts = package.generate_dts(&program);
+        assert!(dts.contains("export interface Point {\n  posX: number;\n  posY: number;\n}"));
+        assert!(dts.contains("export function shift(point: Point, dx: number): Point;"));
+        let js = package.generate_js(&program);
+        assert!(js.contains("return call(\"__velin_export_shift\", [point, dx]);"));
+
+        let code = WasmCodeGenerator::new()
+            .generate(
+                &program,
+                &CodegenConfig {
+                    target: TargetLanguage::Wasm,
+                    framework: None,
+                    orm: None,
+                    output_path: None,
+                },
+            )
+            .unwrap();
+        assert!(code.contains("#[serde(rename = \"posX\")]"));
+        assert!(code.contains("let (point, dx,): (Point, f64,) = serde_json::from_slice"));
+        assert!(!code.contains("axum"));
+    }
+}
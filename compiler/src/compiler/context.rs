@@ -1,6 +1,8 @@
 use crate::compiler::language::VELISCH_LANGUAGE_NAME;
+use crate::coverage::CoverageMap;
 use crate::error::CompilerError;
 use crate::parser::ast::Program;
+use crate::profiler::ProfileMap;
 use std::collections::HashMap;
 
 /// Metadaten für KI-basierte Code-Analyse
@@ -35,6 +37,10 @@ pub struct CompilationContext {
     pub root_file: String,
     /// KI-basierte semantische Metadaten
     pub semantic_metadata: SemanticMetadata,
+    /// Probe-Zuordnungen pro Datei, falls die Coverage-Instrumentierung gelaufen ist
+    pub coverage_maps: Vec<CoverageMap>,
+    /// Funktions-Zuordnung, falls die Profiling-Instrumentierung gelaufen ist
+    pub profile_map: ProfileMap,
 }
 
 impl CompilationContext {
@@ -52,6 +58,8 @@ impl CompilationContext {
             warnings: Vec::new(),
             root_file,
             semantic_metadata: SemanticMetadata::default(),
+            coverage_maps: Vec::new(),
+            profile_map: ProfileMap::default(),
         }
     }
 
@@ -3,6 +3,7 @@ pub mod context;
 pub mod error;
 pub mod language;
 pub mod orchestrator;
+pub mod packages;
 pub mod pass;
 
 use crate::compiler::config::CompilerConfig;
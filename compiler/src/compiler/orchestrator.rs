@@ -1,21 +1,19 @@
 use crate::compiler::context::CompilationContext;
 use crate::parser::ast::*;
+use crate::parser::parser::Parser;
 use anyhow::Result;
 use indexmap::IndexMap;
 use petgraph::algo::toposort;
 use petgraph::graph::DiGraph;
-use std::collections::HashMap;
-use std::path::Path;
+use petgraph::Direction;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-pub struct BuildOrchestrator {
-    file_dependencies: HashMap<String, Vec<String>>,
-}
+pub struct BuildOrchestrator;
 
 impl BuildOrchestrator {
     pub fn new() -> Self {
-        Self {
-            file_dependencies: HashMap::new(),
-        }
+        Self
     }
 
     /// Analyzes all files in the project and builds a dependency graph
@@ -31,19 +29,14 @@ impl BuildOrchestrator {
             }
         }
 
-        // Build edges based on use statements
-        let mut file_deps = HashMap::new();
-        if let Some(ref program) = context.program {
-            Self::extract_file_dependencies_static(program, &context.root_file, &mut file_deps);
-        }
-
-        // Add edges to graph
-        for (file, deps) in &file_deps {
-            if let Some(&file_idx) = node_map.get(file) {
-                for dep in deps {
-                    if let Some(&dep_idx) = node_map.get(dep) {
-                        graph.add_edge(dep_idx, file_idx, ());
-                    }
+        // Build edges based on the use statements of each file
+        for (file, source) in &context.source_map {
+            let Some(&file_idx) = node_map.get(file) else {
+                continue;
+            };
+            for dep in Self::file_dependencies(file, source, context) {
+                if let Some(&dep_idx) = node_map.get(&dep) {
+                    graph.add_edge(dep_idx, file_idx, ());
                 }
             }
         }
@@ -76,93 +69,68 @@ impl BuildOrchestrator {
         Ok(ordered_files)
     }
 
-    /// Extracts file dependencies from use statements in the program (static version)
-    fn extract_file_dependencies_static(
-        program: &Program,
-        current_file: &str,
-        file_deps: &mut HashMap<String, Vec<String>>,
-    ) {
-        for item in &program.items {
-            match item {
-                Item::Use(use_stmt) => {
-                    // Extract module path from use statement
-                    let module_path = use_stmt.path.join("::");
-
-                    // Try to resolve the module path to a file
-                    // For now, we'll use a simple heuristic: module paths map to files
-                    // In a real implementation, this would use the module resolution system
-                    if let Some(dep_file) =
-                        Self::resolve_module_to_file_static(&module_path, current_file)
-                    {
-                        file_deps
-                            .entry(current_file.to_string())
-                            .or_insert_with(Vec::new)
-                            .push(dep_file);
-                    }
-                }
-                Item::Module(module) => {
-                    // Recursively process nested modules
-                    // Note: Nested modules are in the same file, so we use the same current_file
-                    Self::extract_dependencies_from_items_static(
-                        &module.items,
-                        current_file,
-                        file_deps,
-                    );
-                }
-                _ => {}
+    /// Files imported by `file` via `use`, resolved like the `ParserPass`
+    /// (`use models;` → `models.velin` next to the importing file)
+    fn file_dependencies(file: &str, source: &str, context: &CompilationContext) -> Vec<String> {
+        // The root program is already parsed; module sources are parsed on demand
+        let parsed;
+        let items = match &context.program {
+            Some(program) if file == context.root_file => &program.items,
+            _ => {
+                parsed = match Parser::parse(source) {
+                    Ok(program) => program,
+                    Err(_) => return Vec::new(),
+                };
+                &parsed.items
             }
-        }
-    }
+        };
+        let base = Path::new(file).parent().unwrap_or_else(|| Path::new(""));
 
-    /// Extracts dependencies from a list of items (static version)
-    fn extract_dependencies_from_items_static(
-        items: &[Item],
-        current_file: &str,
-        file_deps: &mut HashMap<String, Vec<String>>,
-    ) {
+        let mut deps = Vec::new();
         for item in items {
-            match item {
-                Item::Use(use_stmt) => {
-                    let module_path = use_stmt.path.join("::");
-                    if let Some(dep_file) =
-                        Self::resolve_module_to_file_static(&module_path, current_file)
-                    {
-                        file_deps
-                            .entry(current_file.to_string())
-                            .or_insert_with(Vec::new)
-                            .push(dep_file);
+            if let Item::Use(use_stmt) = item {
+                if let Some(first_segment) = use_stmt.path.first() {
+                    let candidate = base
+                        .join(format!("{}.velin", first_segment))
+                        .to_string_lossy()
+                        .to_string();
+                    if context.source_map.contains_key(&candidate) && !deps.contains(&candidate) {
+                        deps.push(candidate);
                     }
                 }
-                Item::Module(module) => {
-                    Self::extract_dependencies_from_items_static(
-                        &module.items,
-                        current_file,
-                        file_deps,
-                    );
-                }
-                _ => {}
             }
         }
+        deps
     }
 
-    /// Resolves a module path to a file path
-    /// This is a simplified implementation - in a real system, this would use proper module resolution
-    fn resolve_module_to_file_static(module_path_str: &str, _current_file: &str) -> Option<String> {
-        // Simple heuristic: convert module path to file path
-        // e.g., "models::User" -> "models/user.velin" or "models/user.rs"
-        let file_path = module_path_str.replace("::", "/");
-
-        // Try common extensions
-        for ext in &["velin", "rs", "ts", "js", "py"] {
-            let candidate = format!("{}.{}", file_path, ext);
-            if Path::new(&candidate).exists() {
-                return Some(candidate);
+    /// Files that must be recompiled after `changed` were edited: the changed
+    /// files plus everything that imports them (transitively), in compilation order.
+    /// Paths are compared canonicalized, so absolute watcher paths match.
+    pub fn affected_files(
+        &self,
+        context: &CompilationContext,
+        changed: &[PathBuf],
+    ) -> Result<Vec<String>> {
+        let graph = self.analyze_project(context)?;
+        let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let changed: HashSet<PathBuf> = changed.iter().map(|p| canonical(p)).collect();
+
+        let mut affected = HashSet::new();
+        let mut stack: Vec<_> = graph
+            .node_indices()
+            .filter(|&idx| changed.contains(&canonical(Path::new(&graph[idx]))))
+            .collect();
+        while let Some(idx) = stack.pop() {
+            if affected.insert(graph[idx].clone()) {
+                stack.extend(graph.neighbors_directed(idx, Direction::Outgoing));
             }
         }
 
-        // If not found, return the module path as-is (will be handled by module resolution)
-        // For now, we'll return None to avoid false dependencies
-        None
+        Ok(self
+            .determine_compilation_order(context)?
+            .into_iter()
+            .filter(|file| affected.contains(file))
+            .collect())
     }
 
     /// Orchestrates the build process by ordering files based on dependencies
@@ -182,3 +150,42 @@ impl Default for BuildOrchestrator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affected_files_follow_importers() {
+        let mut context = CompilationContext::new(
+            "app/main.velin".to_string(),
+            "use models;\n\nfn main() {\n}\n".to_string(),
+        );
+        context.add_source(
+            "app/models.velin".to_string(),
+            "use util;\n\nstruct User {\n    name: string,\n}\n".to_string(),
+        );
+        context.add_source(
+            "app/util.velin".to_string(),
+            "fn trim(s: string): string {\n    return s;\n}\n".to_string(),
+        );
+        context.add_source(
+            "app/unused.velin".to_string(),
+            "fn unused() {\n}\n".to_string(),
+        );
+
+        let orchestrator = BuildOrchestrator::new();
+        let affected = orchestrator
+            .affected_files(&context, &[PathBuf::from("app/util.velin")])
+            .unwrap();
+        assert_eq!(
+            affected,
+            vec!["app/util.velin", "app/models.velin", "app/main.velin"]
+        );
+
+        let affected = orchestrator
+            .affected_files(&context, &[PathBuf::from("app/main.velin")])
+            .unwrap();
+        assert_eq!(affected, vec!["app/main.velin"]);
+    }
+}
@@ -0,0 +1,490 @@
+// Package-Auflösung - Findet Dependencies aus velin.toml für `use`-Imports
+//
+// Registry- und Git-Dependencies liegen nach `velin-pkg install` unter
+// `<projekt>/vendor/<name>`, Pfad-Dependencies werden direkt gelesen.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Einstiegsdateien eines Packages, in dieser Reihenfolge gesucht
+const ENTRY_FILES: [&str; 4] = ["lib.velin", "src/lib.velin", "main.velin", "src/main.velin"];
+
+/// Einstiegsdateien eines Workspace-Members für `velin compile -p` (Services vor Libraries)
+const MEMBER_ENTRY_FILES: [&str; 4] =
+    ["main.velin", "src/main.velin", "lib.velin", "src/lib.velin"];
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    package: Option<ManifestPackage>,
+    workspace: Option<WorkspaceManifest>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencySpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPackage {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceManifest {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencySpec>,
+}
+
+impl Manifest {
+    fn read(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join("velin.toml");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Invalid {}", path.display()))
+            .map(Some)
+    }
+}
+
+/// Gleiche Form wie in velin-pkg: `"^1.0"` oder `{ version, package, path, git, rev, workspace }`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum DependencySpec {
+    Version(#[allow(dead_code)] String),
+    Detailed {
+        package: Option<String>,
+        path: Option<String>,
+        #[serde(default)]
+        workspace: bool,
+    },
+}
+
+/// Package eines Workspaces (`[workspace] members` in der Wurzel-velin.toml)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+impl Member {
+    /// Einstiegsdatei zum Kompilieren; `main.velin` hat Vorrang vor `lib.velin`
+    pub fn entry(&self) -> Result<PathBuf> {
+        MEMBER_ENTRY_FILES
+            .iter()
+            .map(|file| self.dir.join(file))
+            .find(|path| path.is_file())
+            .with_context(|| {
+                format!(
+                    "Workspace member '{}' has no entry file (expected one of: {})",
+                    self.name,
+                    MEMBER_ENTRY_FILES.join(", ")
+                )
+            })
+    }
+}
+
+/// Workspace mit mehreren Packages, einer velin.lock und einem vendor/
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub members: Vec<Member>,
+    /// `[workspace.dependencies]`, von Members per `{ workspace = true }` übernommen
+    dependencies: BTreeMap<String, DependencySpec>,
+}
+
+impl Workspace {
+    /// Sucht ab `start` aufwärts eine velin.toml mit `[workspace]`
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        // Relative Pfade (Verzeichnis von `main.velin` ist ``) hätten keine Elternverzeichnisse
+        let start = std::env::current_dir()?.join(start);
+        for dir in start.ancestors() {
+            if let Some(Manifest {
+                workspace: Some(workspace),
+                ..
+            }) = Manifest::read(dir)?
+            {
+                return Self::load(dir, workspace).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    fn load(root: &Path, manifest: WorkspaceManifest) -> Result<Self> {
+        let mut dirs = Vec::new();
+        for pattern in &manifest.members {
+            // `services/*` nimmt jedes Unterverzeichnis mit velin.toml auf
+            if let Some(parent) = pattern.strip_suffix("/*") {
+                let parent = root.join(parent);
+                let entries = fs::read_dir(&parent).with_context(|| {
+                    format!("Workspace members '{}' not found", parent.display())
+                })?;
+                let mut found: Vec<_> = entries
+                    .filter_map(|entry| Some(entry.ok()?.path()))
+                    .filter(|path| path.join("velin.toml").is_file())
+                    .collect();
+                found.sort();
+                dirs.extend(found);
+            } else {
+                dirs.push(root.join(pattern));
+            }
+        }
+
+        let mut members = Vec::new();
+        for dir in dirs {
+            let manifest = Manifest::read(&dir)?
+                .with_context(|| format!("Workspace member {} has no velin.toml", dir.display()))?;
+            let name = match manifest.package {
+                Some(package) => package.name,
+                None => dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+            };
+            members.push(Member {
+                name,
+                dir: normalize(&dir),
+            });
+        }
+
+        Ok(Workspace {
+            root: root.to_path_buf(),
+            members,
+            dependencies: manifest.dependencies,
+        })
+    }
+
+    /// Members für `-p <name>`, ohne Namen alle
+    pub fn select(&self, package: Option<&str>) -> Result<Vec<&Member>> {
+        let Some(package) = package else {
+            return Ok(self.members.iter().collect());
+        };
+        match self.members.iter().find(|member| member.name == package) {
+            Some(member) => Ok(vec![member]),
+            None => {
+                let names: Vec<_> = self.members.iter().map(|m| m.name.as_str()).collect();
+                anyhow::bail!(
+                    "Package '{}' is not a member of the workspace at {} (members: {})",
+                    package,
+                    self.root.display(),
+                    names.join(", ")
+                )
+            }
+        }
+    }
+
+    /// `true`, wenn `dir` die Wurzel oder ein Member des Workspaces ist
+    fn contains(&self, dir: &Path) -> bool {
+        let dir = canonical(dir);
+        dir == canonical(&self.root)
+            || self
+                .members
+                .iter()
+                .any(|member| canonical(&member.dir) == dir)
+    }
+}
+
+/// Eine Dependency, die per `use` importiert werden kann
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    /// Package-Name (z.B. `github.com/user/http-utils`)
+    pub name: String,
+    /// Name im Quelltext (z.B. `http_utils`)
+    pub import_name: String,
+    pub root: PathBuf,
+    /// `true` für Registry-/Git-Packages aus `vendor/`
+    pub vendored: bool,
+}
+
+impl Package {
+    /// Einstiegsdatei des Packages
+    pub fn entry(&self) -> Result<PathBuf> {
+        if !self.root.is_dir() {
+            if self.vendored {
+                anyhow::bail!(
+                    "Package '{}' is not installed (expected {}). Run 'velin-pkg install'.",
+                    self.name,
+                    self.root.display()
+                );
+            }
+            anyhow::bail!(
+                "Path dependency '{}' not found: {}",
+                self.name,
+                self.root.display()
+            );
+        }
+        ENTRY_FILES
+            .iter()
+            .map(|file| self.root.join(file))
+            .find(|path| path.is_file())
+            .with_context(|| {
+                format!(
+                    "Package '{}' has no entry file (expected one of: {})",
+                    self.name,
+                    ENTRY_FILES.join(", ")
+                )
+            })
+    }
+}
+
+/// Ergebnis einer Suche nach Import-Namen
+#[derive(Debug)]
+pub enum Lookup<'a> {
+    None,
+    One(&'a Package),
+    Ambiguous(Vec<&'a Package>),
+}
+
+/// Dependencies eines Projekts oder Packages
+#[derive(Debug, Default)]
+pub struct PackageIndex {
+    pub vendor: PathBuf,
+    /// Workspace, zu dem das Projekt gehört
+    pub workspace: Option<Workspace>,
+    packages: Vec<Package>,
+}
+
+impl PackageIndex {
+    /// Sucht ab `start` aufwärts die nächste `velin.toml`
+    ///
+    /// Gehört das Projekt zu einem Workspace, liegt `vendor/` in dessen Wurzel.
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            if dir.join("velin.toml").is_file() {
+                let workspace = Workspace::discover(dir)?.filter(|w| w.contains(dir));
+                let vendor = match &workspace {
+                    Some(workspace) => workspace.root.join("vendor"),
+                    None => dir.join("vendor"),
+                };
+                return Self::load(dir, &vendor, workspace.as_ref()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Liest die Dependencies aus `<dir>/velin.toml` (fehlt sie, ist der Index leer)
+    ///
+    /// `vendor` ist immer das Verzeichnis des Projekts bzw. Workspaces, weil
+    /// `velin-pkg install` auch transitive Packages dorthin entpackt.
+    pub fn load(dir: &Path, vendor: &Path, workspace: Option<&Workspace>) -> Result<Self> {
+        let mut index = PackageIndex {
+            vendor: vendor.to_path_buf(),
+            workspace: workspace.cloned(),
+            packages: Vec::new(),
+        };
+        let Some(manifest) = Manifest::read(dir)? else {
+            return Ok(index);
+        };
+
+        for (key, spec) in manifest.dependencies {
+            // `{ workspace = true }` übernimmt den Eintrag aus `[workspace.dependencies]`,
+            // Pfade darin sind relativ zur Workspace-Wurzel
+            let (spec, base) = match spec {
+                DependencySpec::Detailed {
+                    workspace: true, ..
+                } => {
+                    let inherited = workspace
+                        .and_then(|w| Some((w.dependencies.get(&key)?.clone(), w.root.as_path())));
+                    inherited.with_context(|| {
+                        format!(
+                            "Dependency '{}' in {} uses 'workspace = true', but no workspace defines it",
+                            key,
+                            dir.join("velin.toml").display()
+                        )
+                    })?
+                }
+                spec => (spec, dir),
+            };
+            let (name, root, vendored) = match spec {
+                DependencySpec::Detailed {
+                    package,
+                    path: Some(path),
+                    ..
+                } => (
+                    package.unwrap_or_else(|| key.clone()),
+                    normalize(&base.join(path)),
+                    false,
+                ),
+                DependencySpec::Detailed { package, .. } => {
+                    let name = package.unwrap_or_else(|| key.clone());
+                    let root = vendor.join(name.replace('/', "_"));
+                    (name, root, true)
+                }
+                DependencySpec::Version(_) => {
+                    (key.clone(), vendor.join(key.replace('/', "_")), true)
+                }
+            };
+            index.packages.push(Package {
+                name,
+                import_name: import_name(&key),
+                root,
+                vendored,
+            });
+        }
+        Ok(index)
+    }
+
+    /// Index eines Packages, das aus diesem Projekt importiert wird
+    pub fn nested(&self, dir: &Path) -> Result<Self> {
+        Self::load(dir, &self.vendor, self.workspace.as_ref())
+    }
+
+    pub fn lookup(&self, import_name: &str) -> Lookup<'_> {
+        let mut matches: Vec<_> = self
+            .packages
+            .iter()
+            .filter(|package| package.import_name == import_name)
+            .collect();
+        match matches.len() {
+            0 => Lookup::None,
+            1 => Lookup::One(matches.remove(0)),
+            _ => Lookup::Ambiguous(matches),
+        }
+    }
+}
+
+/// Import-Name eines Dependency-Schlüssels: letztes Pfadsegment ohne `.git`,
+/// `-` wird zu `_` (`github.com/user/http-utils` → `http_utils`)
+pub fn import_name(key: &str) -> String {
+    let last = key.trim_end_matches('/').rsplit('/').next().unwrap_or(key);
+    last.trim_end_matches(".git").replace('-', "_")
+}
+
+/// Entfernt `.` und `a/..` ohne Dateisystemzugriff, damit derselbe Pfad aus
+/// verschiedenen Members gleich aussieht
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(result.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                result.pop();
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    let path = if path.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        path
+    };
+    fs::canonicalize(path).unwrap_or_else(|_| normalize(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_names() {
+        assert_eq!(import_name("github.com/user/http-utils"), "http_utils");
+        assert_eq!(import_name("github.com/user/models.git"), "models");
+        assert_eq!(import_name("mathlib"), "mathlib");
+    }
+
+    #[test]
+    fn test_index_locates_path_and_vendored_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("velin.toml"),
+            r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+"github.com/user/http-utils" = "^1.0"
+shared = { path = "../shared" }
+json = { package = "github.com/other/json", git = "https://example.com/json.git", rev = "v2" }
+"github.com/user/json" = "^2"
+"#,
+        )
+        .unwrap();
+
+        let index = PackageIndex::discover(&dir.path().join("src"))
+            .unwrap()
+            .unwrap();
+        let Lookup::One(http) = index.lookup("http_utils") else {
+            panic!("http_utils should resolve");
+        };
+        assert!(http.vendored);
+        assert_eq!(
+            http.root,
+            dir.path().join("vendor").join("github.com_user_http-utils")
+        );
+
+        let Lookup::One(shared) = index.lookup("shared") else {
+            panic!("shared should resolve");
+        };
+        assert!(!shared.vendored);
+        assert_eq!(shared.root, dir.path().parent().unwrap().join("shared"));
+
+        assert!(matches!(index.lookup("json"), Lookup::Ambiguous(ref p) if p.len() == 2));
+        assert!(matches!(index.lookup("missing"), Lookup::None));
+
+        let error = http.entry().unwrap_err().to_string();
+        assert!(error.contains("velin-pkg install"), "{}", error);
+    }
+
+    #[test]
+    fn test_workspace_members_share_vendor_and_dependencies() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("velin.toml"),
+            r#"[workspace]
+members = ["services/*", "libs/shared"]
+
+[workspace.dependencies]
+"github.com/user/json" = "^2.1"
+shared = { path = "libs/shared" }
+"#,
+        )
+        .unwrap();
+        for (member, manifest) in [
+            (
+                "services/api",
+                "[package]\nname = \"api\"\nversion = \"0.1.0\"\n\n[dependencies]\n\"github.com/user/json\" = { workspace = true }\nshared = { workspace = true }\n",
+            ),
+            (
+                "libs/shared",
+                "[package]\nname = \"shared\"\nversion = \"0.1.0\"\n",
+            ),
+        ] {
+            fs::create_dir_all(root.join(member)).unwrap();
+            fs::write(root.join(member).join("velin.toml"), manifest).unwrap();
+        }
+        fs::write(root.join("services/api/main.velin"), "").unwrap();
+
+        let workspace = Workspace::discover(&root.join("services/api"))
+            .unwrap()
+            .unwrap();
+        let names: Vec<_> = workspace.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["api", "shared"]);
+        let api = workspace.select(Some("api")).unwrap()[0];
+        assert_eq!(api.entry().unwrap(), root.join("services/api/main.velin"));
+        assert!(workspace.select(Some("web")).is_err());
+
+        let index = PackageIndex::discover(&root.join("services/api"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(index.vendor, root.join("vendor"));
+        let Lookup::One(shared) = index.lookup("shared") else {
+            panic!("shared should resolve");
+        };
+        assert_eq!(shared.root, root.join("libs/shared"));
+        let Lookup::One(json) = index.lookup("json") else {
+            panic!("json should resolve");
+        };
+        assert_eq!(json.root, root.join("vendor").join("github.com_user_json"));
+    }
+}
@@ -0,0 +1,364 @@
+// Coverage-Instrumentierung
+// Fügt vor jedem Statement und am Anfang jedes Zweigs einen Probe-Aufruf ein
+
+use crate::coverage::{CoverageMap, CoverageProbe, ProbeKind, PROBE_FUNCTION};
+use crate::parser::ast::*;
+use crate::parser::parser::SourceLines;
+
+/// Instrumentiert ein Programm mit Coverage-Probes.
+///
+/// Die Zeilen stammen aus `Parser::parse_with_lines` und werden in derselben
+/// Reihenfolge konsumiert, in der der Parser sie aufgezeichnet hat (Pre-Order,
+/// Quellreihenfolge). Der Walk muss deshalb vor jeder Desugaring-Transformation laufen.
+pub struct CoverageInstrumenter {
+    map: CoverageMap,
+    lines: SourceLines,
+    next_statement: usize,
+    next_function: usize,
+    next_branch: usize,
+    first_id: usize,
+}
+
+impl CoverageInstrumenter {
+    /// `first_id` erlaubt mehreren Dateien einen gemeinsamen Zähler-Adressraum
+    pub fn new(file: String, lines: SourceLines, first_id: usize) -> Self {
+        Self {
+            map: CoverageMap::new(file),
+            lines,
+            next_statement: 0,
+            next_function: 0,
+            next_branch: 0,
+            first_id,
+        }
+    }
+
+    /// Instrumentiert eine Item-Liste (z.B. den Inhalt eines importierten Moduls)
+    pub fn instrument_items(mut self, items: &mut [Item]) -> CoverageMap {
+        for item in items {
+            self.instrument_item(item);
+        }
+        self.map
+    }
+
+    /// Instrumentiert alle Items und liefert die Probe-Zuordnung
+    pub fn instrument(self, program: &mut Program) -> CoverageMap {
+        self.instrument_items(&mut program.items)
+    }
+
+    fn instrument_item(&mut self, item: &mut Item) {
+        match item {
+            Item::Function(function) => self.instrument_function(function),
+            Item::Impl(impl_def) => {
+                for method in &mut impl_def.methods {
+                    self.instrument_function(method);
+                }
+            }
+            Item::Module(module) => {
+                for item in &mut module.items {
+                    self.instrument_item(item);
+                }
+            }
+            Item::TopLevelCode(stmt) => self.instrument_expression(&mut stmt.expression),
+            Item::Struct(_)
+            | Item::Enum(_)
+            | Item::TypeAlias(_)
+            | Item::Use(_)
+            | Item::Trait(_) => {}
+        }
+    }
+
+    fn instrument_function(&mut self, function: &mut Function) {
+        let line = self.take_function_line();
+        for param in &mut function.params {
+            if let Some(default) = &mut param.default {
+                self.instrument_expression(default);
+            }
+        }
+        self.instrument_block(&mut function.body);
+
+        let id = self.add_probe(
+            line,
+            ProbeKind::Function {
+                name: function.name.clone(),
+            },
+        );
+        function
+            .body
+            .statements
+            .insert(0, Self::probe_statement(id));
+    }
+
+    fn instrument_block(&mut self, block: &mut Block) {
+        let statements = std::mem::take(&mut block.statements);
+        for mut statement in statements {
+            let line = self.take_statement_line();
+            let id = self.add_probe(line, ProbeKind::Statement);
+            self.instrument_statement(&mut statement, line);
+            block.statements.push(Self::probe_statement(id));
+            block.statements.push(statement);
+        }
+    }
+
+    fn instrument_statement(&mut self, statement: &mut Statement, line: usize) {
+        match statement {
+            Statement::Let(let_stmt) => self.instrument_expression(&mut let_stmt.value),
+            Statement::Return(ret) => {
+                if let Some(value) = &mut ret.value {
+                    self.instrument_expression(value);
+                }
+            }
+            Statement::Expression(expr_stmt) => {
+                self.instrument_expression(&mut expr_stmt.expression)
+            }
+            Statement::If(if_stmt) => {
+                self.instrument_expression(&mut if_stmt.condition);
+                let branch = self.take_branch();
+
+                self.instrument_block(&mut if_stmt.then_block);
+                let then_id = self.add_probe(line, ProbeKind::Branch { branch, arm: 0 });
+                if_stmt
+                    .then_block
+                    .statements
+                    .insert(0, Self::probe_statement(then_id));
+
+                // Ein fehlender else-Zweig wird als leerer Zweig gezählt,
+                // damit "Bedingung nie falsch" sichtbar wird
+                let else_block = if_stmt.else_block.get_or_insert_with(|| Block {
+                    statements: Vec::new(),
+                });
+                self.instrument_block(else_block);
+                let else_id = self.add_probe(line, ProbeKind::Branch { branch, arm: 1 });
+                else_block
+                    .statements
+                    .insert(0, Self::probe_statement(else_id));
+            }
+            Statement::For(for_stmt) => {
+                self.instrument_expression(&mut for_stmt.iterable);
+                self.instrument_block(&mut for_stmt.body);
+            }
+            Statement::While(while_stmt) => {
+                self.instrument_expression(&mut while_stmt.condition);
+                self.instrument_block(&mut while_stmt.body);
+            }
+            Statement::Match(match_stmt) => {
+                self.instrument_expression(&mut match_stmt.expression);
+                let branch = self.take_branch();
+                for (arm_index, arm) in match_stmt.arms.iter_mut().enumerate() {
+                    if let Some(guard) = &mut arm.guard {
+                        self.instrument_expression(guard);
+                    }
+                    self.instrument_block(&mut arm.body);
+                    let id = self.add_probe(
+                        line,
+                        ProbeKind::Branch {
+                            branch,
+                            arm: arm_index,
+                        },
+                    );
+                    arm.body.statements.insert(0, Self::probe_statement(id));
+                }
+            }
+            Statement::Throw(throw_stmt) => self.instrument_expression(&mut throw_stmt.expression),
+            Statement::Break(_) => {}
+            Statement::Try(try_stmt) => {
+                self.instrument_block(&mut try_stmt.try_block);
+                for catch in &mut try_stmt.catch_blocks {
+                    self.instrument_block(&mut catch.body);
+                }
+                if let Some(finally) = &mut try_stmt.finally_block {
+                    self.instrument_block(finally);
+                }
+            }
+        }
+    }
+
+    /// Besucht Ausdrücke in Quellreihenfolge, um verschachtelte Blöcke
+    /// (Lambdas, Block-Ausdrücke) zu instrumentieren
+    fn instrument_expression(&mut self, expr: &mut Expression) {
+        match expr {
+            Expression::Literal(_) | Expression::Identifier(_) => {}
+            Expression::BinaryOp { left, right, .. } => {
+                self.instrument_expression(left);
+                self.instrument_expression(right);
+            }
+            Expression::UnaryOp { expr, .. } => self.instrument_expression(expr),
+            Expression::Call { callee, args } => {
+                self.instrument_expression(callee);
+                for arg in args {
+                    self.instrument_expression(arg);
+                }
+            }
+            Expression::Member { object, .. } => self.instrument_expression(object),
+            Expression::Index { object, index } => {
+                self.instrument_expression(object);
+                self.instrument_expression(index);
+            }
+            Expression::If {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                self.instrument_expression(condition);
+                self.instrument_expression(then_expr);
+                self.instrument_expression(else_expr);
+            }
+            Expression::Block(block) => self.instrument_block(block),
+            Expression::Await { expr } => self.instrument_expression(expr),
+            Expression::StructLiteral { fields, .. } | Expression::MapLiteral(fields) => {
+                for (_, value) in fields {
+                    self.instrument_expression(value);
+                }
+            }
+            Expression::ListLiteral(items) => {
+                for item in items {
+                    self.instrument_expression(item);
+                }
+            }
+            Expression::GenericConstructor { args, .. } | Expression::LLMCall { args, .. } => {
+                for arg in args {
+                    self.instrument_expression(arg);
+                }
+            }
+            Expression::Lambda { params, body, .. } => {
+                for param in params {
+                    if let Some(default) = &mut param.default {
+                        self.instrument_expression(default);
+                    }
+                }
+                self.instrument_expression(body);
+            }
+            Expression::Assignment { target, value } => {
+                self.instrument_expression(target);
+                self.instrument_expression(value);
+            }
+            Expression::FormatString { parts } => {
+                for part in parts {
+                    if let FormatStringPart::Expression(expr) = part {
+                        self.instrument_expression(expr);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_probe(&mut self, line: usize, kind: ProbeKind) -> usize {
+        let id = self.first_id + self.map.probes.len();
+        self.map.probes.push(CoverageProbe { id, line, kind });
+        id
+    }
+
+    fn take_statement_line(&mut self) -> usize {
+        let line = self
+            .lines
+            .statements
+            .get(self.next_statement)
+            .copied()
+            .unwrap_or(0);
+        self.next_statement += 1;
+        line
+    }
+
+    fn take_function_line(&mut self) -> usize {
+        let line = self
+            .lines
+            .functions
+            .get(self.next_function)
+            .copied()
+            .unwrap_or(0);
+        self.next_function += 1;
+        line
+    }
+
+    fn take_branch(&mut self) -> usize {
+        let branch = self.next_branch;
+        self.next_branch += 1;
+        branch
+    }
+
+    /// `__velin_cov_hit(<id>);`
+    fn probe_statement(id: usize) -> Statement {
+        Statement::Expression(ExpressionStatement {
+            expression: Expression::Call {
+                callee: Box::new(Expression::Identifier(PROBE_FUNCTION.to_string())),
+                args: vec![Expression::Literal(Literal::Number(id as f64))],
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coverage::{CoverageCounts, CoverageReport};
+    use crate::parser::parser::Parser;
+
+    const SOURCE: &str = "fn check(x: number): string {
+    let y = x + 1;
+    if (y > 2) {
+        return \"big\";
+    }
+    return \"small\";
+}
+";
+
+    #[test]
+    fn test_probes_map_to_velin_lines() {
+        let (mut program, lines) = Parser::parse_with_lines(SOURCE).unwrap();
+        let map =
+            CoverageInstrumenter::new("check.velin".to_string(), lines, 0).instrument(&mut program);
+
+        let statement_lines: Vec<usize> = map
+            .probes
+            .iter()
+            .filter(|p| p.kind == ProbeKind::Statement)
+            .map(|p| p.line)
+            .collect();
+        assert_eq!(statement_lines, vec![2, 3, 4, 6]);
+
+        let branches = map
+            .probes
+            .iter()
+            .filter(|p| matches!(p.kind, ProbeKind::Branch { .. }))
+            .count();
+        assert_eq!(branches, 2);
+
+        let function = map
+            .probes
+            .iter()
+            .find(|p| matches!(p.kind, ProbeKind::Function { .. }))
+            .unwrap();
+        assert_eq!(function.line, 1);
+    }
+
+    #[test]
+    fn test_lcov_report() {
+        let (mut program, lines) = Parser::parse_with_lines(SOURCE).unwrap();
+        let map =
+            CoverageInstrumenter::new("check.velin".to_string(), lines, 0).instrument(&mut program);
+
+        // Nur der then-Zweig wurde ausgeführt
+        let mut counts = CoverageCounts {
+            counts: vec![0; map.next_id()],
+        };
+        for probe in &map.probes {
+            let hit = match &probe.kind {
+                ProbeKind::Statement => probe.line != 6,
+                ProbeKind::Branch { arm, .. } => *arm == 0,
+                ProbeKind::Function { .. } => true,
+            };
+            counts.counts[probe.id] = hit as u64;
+        }
+
+        let mut report = CoverageReport::new();
+        report.add(&map, &counts);
+        let lcov = report.to_lcov();
+
+        assert!(lcov.contains("SF:check.velin"));
+        assert!(lcov.contains("FN:1,check"));
+        assert!(lcov.contains("DA:6,0"));
+        assert!(lcov.contains("LF:4\nLH:3"));
+        assert!(lcov.contains("BRF:2\nBRH:1"));
+        assert_eq!(report.line_coverage(), 75.0);
+        assert_eq!(report.branch_coverage(), 50.0);
+    }
+}
@@ -0,0 +1,88 @@
+// Source-Level Coverage für Velin-Programme
+// Instrumentiert den AST mit Zählern, die auf Velin-Quellzeilen abgebildet werden
+
+pub mod instrument;
+pub mod report;
+pub mod runner;
+pub mod runtime;
+
+pub use instrument::CoverageInstrumenter;
+pub use report::{CoverageReport, CoverageThreshold, FileCoverage};
+pub use runner::{threshold_from_config, CoverageRun, CoverageRunner};
+pub use runtime::CoverageRuntime;
+
+use serde::{Deserialize, Serialize};
+
+/// Name der Laufzeitfunktion, die ein Probe-Treffer aufruft
+pub const PROBE_FUNCTION: &str = "__velin_cov_hit";
+
+/// Umgebungsvariable, in die die Laufzeit ihre Zählerstände schreibt
+pub const COVERAGE_FILE_ENV: &str = "VELIN_COVERAGE_FILE";
+
+/// Art eines Coverage-Probes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProbeKind {
+    /// Eintritt in eine Funktion
+    Function { name: String },
+    /// Ausführung eines Statements
+    Statement,
+    /// Ein Zweig (`arm`) einer Verzweigung (`branch`): if/else oder match-Arm
+    Branch { branch: usize, arm: usize },
+}
+
+/// Ein einzelner Zähler im instrumentierten Code
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoverageProbe {
+    pub id: usize,
+    pub line: usize,
+    #[serde(flatten)]
+    pub kind: ProbeKind,
+}
+
+/// Zuordnung Probe-ID → Velin-Quellposition für eine Datei
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageMap {
+    pub file: String,
+    pub probes: Vec<CoverageProbe>,
+}
+
+impl CoverageMap {
+    pub fn new(file: String) -> Self {
+        Self {
+            file,
+            probes: Vec::new(),
+        }
+    }
+
+    pub fn probe_count(&self) -> usize {
+        self.probes.len()
+    }
+
+    /// Erste freie Probe-ID nach dieser Datei
+    pub fn next_id(&self) -> usize {
+        self.probes.iter().map(|p| p.id + 1).max().unwrap_or(0)
+    }
+}
+
+/// Zählerstände, wie sie die Laufzeit nach `VELIN_COVERAGE_FILE` schreibt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverageCounts {
+    pub counts: Vec<u64>,
+}
+
+impl CoverageCounts {
+    pub fn get(&self, id: usize) -> u64 {
+        self.counts.get(id).copied().unwrap_or(0)
+    }
+
+    /// Addiert die Zählerstände eines weiteren Laufs
+    pub fn merge(&mut self, other: &CoverageCounts) {
+        if self.counts.len() < other.counts.len() {
+            self.counts.resize(other.counts.len(), 0);
+        }
+        for (i, count) in other.counts.iter().enumerate() {
+            self.counts[i] += count;
+        }
+    }
+}
@@ -0,0 +1,292 @@
+// Coverage-Report
+// Aggregiert Zählerstände pro .velin-Datei und exportiert LCOV und HTML
+
+use crate::coverage::{CoverageCounts, CoverageMap, ProbeKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Coverage einer einzelnen .velin-Datei
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileCoverage {
+    pub file: String,
+    /// Zeile → Anzahl Ausführungen
+    pub lines: BTreeMap<usize, u64>,
+    /// (Zeile, Verzweigung, Zweig) → Anzahl Ausführungen
+    pub branches: Vec<(usize, usize, usize, u64)>,
+    /// (Zeile, Funktionsname, Anzahl Aufrufe)
+    pub functions: Vec<(usize, String, u64)>,
+}
+
+impl FileCoverage {
+    pub fn from_counts(map: &CoverageMap, counts: &CoverageCounts) -> Self {
+        let mut coverage = FileCoverage {
+            file: map.file.clone(),
+            ..Default::default()
+        };
+
+        for probe in &map.probes {
+            let hits = counts.get(probe.id);
+            match &probe.kind {
+                ProbeKind::Statement => {
+                    // Mehrere Statements pro Zeile: die Zeile zählt ab dem ersten Treffer
+                    let entry = coverage.lines.entry(probe.line).or_insert(0);
+                    *entry = (*entry).max(hits);
+                }
+                ProbeKind::Branch { branch, arm } => {
+                    coverage.branches.push((probe.line, *branch, *arm, hits));
+                }
+                ProbeKind::Function { name } => {
+                    coverage.functions.push((probe.line, name.clone(), hits));
+                }
+            }
+        }
+
+        coverage
+    }
+
+    pub fn lines_found(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|hits| **hits > 0).count()
+    }
+
+    pub fn branches_found(&self) -> usize {
+        self.branches.len()
+    }
+
+    pub fn branches_hit(&self) -> usize {
+        self.branches.iter().filter(|b| b.3 > 0).count()
+    }
+
+    pub fn functions_found(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn functions_hit(&self) -> usize {
+        self.functions.iter().filter(|f| f.2 > 0).count()
+    }
+}
+
+/// Mindestwerte, unter denen `velin test --coverage` fehlschlägt (in Prozent)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CoverageThreshold {
+    pub line: Option<f64>,
+    pub branch: Option<f64>,
+}
+
+/// Gesamtreport über alle instrumentierten Dateien
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub files: Vec<FileCoverage>,
+}
+
+impl CoverageReport {
+    pub fn new() -> Self {
+        Self { files: Vec::new() }
+    }
+
+    /// Fügt eine Datei hinzu; Läufe derselben Datei werden zusammengeführt
+    pub fn add(&mut self, map: &CoverageMap, counts: &CoverageCounts) {
+        let coverage = FileCoverage::from_counts(map, counts);
+        match self.files.iter_mut().find(|f| f.file == coverage.file) {
+            Some(existing) => {
+                for (line, hits) in coverage.lines {
+                    *existing.lines.entry(line).or_insert(0) += hits;
+                }
+                for (existing_branch, branch) in existing.branches.iter_mut().zip(coverage.branches)
+                {
+                    existing_branch.3 += branch.3;
+                }
+                for (existing_fn, function) in existing.functions.iter_mut().zip(coverage.functions)
+                {
+                    existing_fn.2 += function.2;
+                }
+            }
+            None => self.files.push(coverage),
+        }
+    }
+
+    pub fn line_coverage(&self) -> f64 {
+        percent(
+            self.files.iter().map(|f| f.lines_hit()).sum(),
+            self.files.iter().map(|f| f.lines_found()).sum(),
+        )
+    }
+
+    pub fn branch_coverage(&self) -> f64 {
+        percent(
+            self.files.iter().map(|f| f.branches_hit()).sum(),
+            self.files.iter().map(|f| f.branches_found()).sum(),
+        )
+    }
+
+    pub fn function_coverage(&self) -> f64 {
+        percent(
+            self.files.iter().map(|f| f.functions_hit()).sum(),
+            self.files.iter().map(|f| f.functions_found()).sum(),
+        )
+    }
+
+    /// Prüft die Mindestwerte und liefert die Verstöße als Meldungen
+    pub fn check_threshold(&self, threshold: &CoverageThreshold) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Some(min) = threshold.line {
+            let actual = self.line_coverage();
+            if actual < min {
+                violations.push(format!(
+                    "Zeilen-Coverage {:.2}% liegt unter dem Minimum von {:.2}%",
+                    actual, min
+                ));
+            }
+        }
+        if let Some(min) = threshold.branch {
+            let actual = self.branch_coverage();
+            if actual < min {
+                violations.push(format!(
+                    "Branch-Coverage {:.2}% liegt unter dem Minimum von {:.2}%",
+                    actual, min
+                ));
+            }
+        }
+        violations
+    }
+
+    /// Exportiert den Report im LCOV-Tracefile-Format
+    pub fn to_lcov(&self) -> String {
+        let mut out = String::new();
+        for file in &self.files {
+            out.push_str("TN:\n");
+            out.push_str(&format!("SF:{}\n", file.file));
+            for (line, name, _) in &file.functions {
+                out.push_str(&format!("FN:{},{}\n", line, name));
+            }
+            for (_, name, hits) in &file.functions {
+                out.push_str(&format!("FNDA:{},{}\n", hits, name));
+            }
+            out.push_str(&format!("FNF:{}\n", file.functions_found()));
+            out.push_str(&format!("FNH:{}\n", file.functions_hit()));
+            for (line, branch, arm, hits) in &file.branches {
+                out.push_str(&format!("BRDA:{},{},{},{}\n", line, branch, arm, hits));
+            }
+            out.push_str(&format!("BRF:{}\n", file.branches_found()));
+            out.push_str(&format!("BRH:{}\n", file.branches_hit()));
+            for (line, hits) in &file.lines {
+                out.push_str(&format!("DA:{},{}\n", line, hits));
+            }
+            out.push_str(&format!("LF:{}\n", file.lines_found()));
+            out.push_str(&format!("LH:{}\n", file.lines_hit()));
+            out.push_str("end_of_record\n");
+        }
+        out
+    }
+
+    /// Exportiert den Report als HTML mit annotiertem Quelltext.
+    ///
+    /// `source_of` liefert den Quelltext einer Datei (None → nur Zusammenfassung).
+    pub fn to_html<F>(&self, source_of: F) -> String
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut html = String::from(
+            r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+    <meta charset="UTF-8">
+    <title>Velisch Coverage-Report</title>
+    <style>
+        body { font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; margin: 20px; background: #f5f5f5; }
+        .container { max-width: 1200px; margin: 0 auto; background: white; padding: 20px; border-radius: 8px; }
+        table { border-collapse: collapse; width: 100%; }
+        th, td { text-align: left; padding: 6px 10px; border-bottom: 1px solid #eee; }
+        pre { margin: 0; }
+        .src td { font-family: monospace; padding: 0 8px; border: none; white-space: pre; }
+        .hit { background: #e8f5e9; }
+        .miss { background: #ffebee; }
+        .count { color: #888; text-align: right; }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>Velisch Coverage-Report</h1>
+"#,
+        );
+
+        html.push_str(&format!(
+            "<p>Zeilen: {:.2}% &middot; Branches: {:.2}% &middot; Funktionen: {:.2}%</p>\n",
+            self.line_coverage(),
+            self.branch_coverage(),
+            self.function_coverage()
+        ));
+
+        html.push_str(
+            "<table>\n<tr><th>Datei</th><th>Zeilen</th><th>Branches</th><th>Funktionen</th></tr>\n",
+        );
+        for file in &self.files {
+            html.push_str(&format!(
+                "<tr><td><a href=\"#{}\">{}</a></td><td>{}/{}</td><td>{}/{}</td><td>{}/{}</td></tr>\n",
+                anchor(&file.file),
+                escape_html(&file.file),
+                file.lines_hit(),
+                file.lines_found(),
+                file.branches_hit(),
+                file.branches_found(),
+                file.functions_hit(),
+                file.functions_found()
+            ));
+        }
+        html.push_str("</table>\n");
+
+        for file in &self.files {
+            let Some(source) = source_of(&file.file) else {
+                continue;
+            };
+            html.push_str(&format!(
+                "<h2 id=\"{}\">{}</h2>\n<table class=\"src\">\n",
+                anchor(&file.file),
+                escape_html(&file.file)
+            ));
+            for (index, text) in source.lines().enumerate() {
+                let line = index + 1;
+                let (class, count) = match file.lines.get(&line) {
+                    Some(0) => ("miss", "0".to_string()),
+                    Some(hits) => ("hit", hits.to_string()),
+                    None => ("", String::new()),
+                };
+                html.push_str(&format!(
+                    "<tr class=\"{}\"><td class=\"count\">{}</td><td class=\"count\">{}</td><td>{}</td></tr>\n",
+                    class,
+                    line,
+                    count,
+                    escape_html(text)
+                ));
+            }
+            html.push_str("</table>\n");
+        }
+
+        html.push_str("    </div>\n</body>\n</html>\n");
+        html
+    }
+}
+
+fn percent(hit: usize, found: usize) -> f64 {
+    if found == 0 {
+        100.0
+    } else {
+        hit as f64 / found as f64 * 100.0
+    }
+}
+
+fn anchor(file: &str) -> String {
+    file.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,186 @@
+// Coverage-Runner
+// Kompiliert eine Test-Datei instrumentiert, führt sie aus und sammelt die Zählerstände
+
+use crate::codegen::TargetLanguage;
+use crate::compiler::{config::CompilerConfig, VelinCompiler};
+use crate::coverage::{
+    CoverageCounts, CoverageMap, CoverageRuntime, CoverageThreshold, COVERAGE_FILE_ENV,
+};
+use crate::parser::ast::Item;
+use crate::passes::{
+    code_order::CodeOrderingPass, codegen::CodegenPass, coverage::CoverageInstrumentationPass,
+    desugar::DesugaringPass, parser::ParserPass, type_check::TypeCheckPass,
+};
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Ergebnis eines instrumentierten Test-Laufs
+#[derive(Debug, Clone)]
+pub struct CoverageRun {
+    pub maps: Vec<CoverageMap>,
+    pub counts: CoverageCounts,
+    pub tests: Vec<(String, bool)>,
+    pub passed: bool,
+    pub output: String,
+}
+
+pub struct CoverageRunner {
+    work_dir: PathBuf,
+}
+
+impl CoverageRunner {
+    /// `work_dir` nimmt die generierten Harness-Crates auf (z.B. `target/velin-coverage`)
+    pub fn new(work_dir: PathBuf) -> Self {
+        Self { work_dir }
+    }
+
+    pub fn run_file(&self, file: &Path) -> Result<CoverageRun> {
+        let source = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+        let root_file = file.to_string_lossy().to_string();
+
+        // 1. Normale Prüfung ohne Probes, damit Typfehler auf den Originalcode zeigen
+        let mut checker = VelinCompiler::new(CompilerConfig::default());
+        checker.add_pass(Box::new(ParserPass::new()));
+        checker.add_pass(Box::new(DesugaringPass::new()));
+        checker.add_pass(Box::new(CodeOrderingPass::new()));
+        checker.add_pass(Box::new(TypeCheckPass::new(true)));
+        let checked = checker.compile(root_file.clone(), source.clone())?;
+        if checked.has_errors() {
+            let messages: Vec<String> = checked.errors.iter().map(|e| e.to_string()).collect();
+            return Err(anyhow!(
+                "Kompilierungsfehler in {}:\n{}",
+                file.display(),
+                messages.join("\n")
+            ));
+        }
+
+        // 2. Instrumentierter Build in ein eigenes Harness-Crate
+        let crate_name = harness_crate_name(file);
+        let crate_dir = self.work_dir.join(&crate_name);
+        let src_dir = crate_dir.join("src");
+        fs::create_dir_all(&src_dir)
+            .with_context(|| format!("Failed to create {}", src_dir.display()))?;
+        let program_path = src_dir.join("program.rs");
+
+        let mut compiler = VelinCompiler::new(CompilerConfig::default());
+        compiler.add_pass(Box::new(ParserPass::new()));
+        compiler.add_pass(Box::new(CoverageInstrumentationPass::new()));
+        compiler.add_pass(Box::new(DesugaringPass::new()));
+        compiler.add_pass(Box::new(CodeOrderingPass::new()));
+        // Direkter AST-Codegen: die Probe-Aufrufe bleiben als eigene Statements erhalten
+        compiler.add_pass(Box::new(
+            CodegenPass::new(
+                Some(program_path.clone()),
+                false,
+                TargetLanguage::Rust,
+                None,
+            )
+            .with_ir(false),
+        ));
+        let context = compiler.compile(root_file, source)?;
+        if context.has_errors() {
+            let messages: Vec<String> = context.errors.iter().map(|e| e.to_string()).collect();
+            return Err(anyhow!(
+                "Instrumentierung fehlgeschlagen für {}:\n{}",
+                file.display(),
+                messages.join("\n")
+            ));
+        }
+
+        let tests: Vec<(String, bool)> = context
+            .program
+            .as_ref()
+            .map(|program| {
+                program
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        Item::Function(f) if f.decorators.iter().any(|d| d.name == "test") => {
+                            Some((f.name.clone(), f.is_async))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let maps = context.coverage_maps;
+        let probe_count = maps.iter().map(|m| m.next_id()).max().unwrap_or(0);
+
+        let program_code = fs::read_to_string(&program_path)
+            .with_context(|| format!("Failed to read {}", program_path.display()))?;
+        let main_code = format!(
+            "{}\n{}\n{}",
+            program_code,
+            CoverageRuntime::generate_runtime_code(probe_count),
+            CoverageRuntime::generate_test_harness(&tests)
+        );
+        fs::write(src_dir.join("main.rs"), main_code)?;
+        fs::remove_file(&program_path).ok();
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            CoverageRuntime::generate_harness_manifest(&crate_name),
+        )?;
+
+        // 3. Ausführen; die Laufzeit schreibt die Zähler beim Beenden
+        let counts_path = crate_dir.join("coverage-counts.json");
+        fs::remove_file(&counts_path).ok();
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--manifest-path"])
+            .arg(crate_dir.join("Cargo.toml"))
+            .env(COVERAGE_FILE_ENV, &counts_path)
+            .output()
+            .context("Failed to run cargo for coverage harness")?;
+
+        let counts = match fs::read_to_string(&counts_path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("Invalid coverage data: {}", counts_path.display()))?,
+            Err(_) => CoverageCounts {
+                counts: vec![0; probe_count],
+            },
+        };
+
+        Ok(CoverageRun {
+            maps,
+            counts,
+            tests,
+            passed: output.status.success(),
+            output: format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        })
+    }
+}
+
+/// Liest `testing.coverage.line` / `testing.coverage.branch` aus velin.config.json
+pub fn threshold_from_config(config_file: &Path) -> CoverageThreshold {
+    let Ok(content) = fs::read_to_string(config_file) else {
+        return CoverageThreshold::default();
+    };
+    let Ok(config) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return CoverageThreshold::default();
+    };
+    let coverage = &config["testing"]["coverage"];
+    CoverageThreshold {
+        line: coverage["line"].as_f64(),
+        branch: coverage["branch"].as_f64(),
+    }
+}
+
+fn harness_crate_name(file: &Path) -> String {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("test")
+        .to_lowercase();
+    let sanitized: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("velin_cov_{}", sanitized)
+}
@@ -0,0 +1,135 @@
+// Coverage-Laufzeit
+// Rust-Code, der instrumentierten Programmen vorangestellt wird
+
+use crate::coverage::{COVERAGE_FILE_ENV, PROBE_FUNCTION};
+
+pub struct CoverageRuntime;
+
+impl CoverageRuntime {
+    /// Zähler-Array und Flush-Logik für `probe_count` Probes.
+    ///
+    /// Die Zählerstände werden beim Drop von `VelinCoverageGuard` (oder explizit
+    /// über `__velin_cov_flush`) als JSON nach `VELIN_COVERAGE_FILE` geschrieben.
+    pub fn generate_runtime_code(probe_count: usize) -> String {
+        format!(
+            r#"
+// --- Velisch Coverage Runtime ---
+mod __velin_coverage {{
+    use std::sync::atomic::{{AtomicU64, Ordering}};
+
+    pub const PROBE_COUNT: usize = {count};
+
+    pub static COUNTERS: [AtomicU64; PROBE_COUNT] = {{
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        [ZERO; PROBE_COUNT]
+    }};
+
+    pub fn hit(id: usize) {{
+        if let Some(counter) = COUNTERS.get(id) {{
+            counter.fetch_add(1, Ordering::Relaxed);
+        }}
+    }}
+
+    pub fn flush() {{
+        let Ok(path) = std::env::var("{env}") else {{
+            return;
+        }};
+        let counts: Vec<String> = COUNTERS
+            .iter()
+            .map(|c| c.load(Ordering::Relaxed).to_string())
+            .collect();
+        let json = format!("{{{{\"counts\":[{{}}]}}}}", counts.join(","));
+        if let Err(e) = std::fs::write(&path, json) {{
+            eprintln!("coverage: failed to write {{}}: {{}}", path, e);
+        }}
+    }}
+
+    pub struct VelinCoverageGuard;
+
+    impl Drop for VelinCoverageGuard {{
+        fn drop(&mut self) {{
+            flush();
+        }}
+    }}
+}}
+
+#[inline]
+pub fn {probe}(id: f64) {{
+    __velin_coverage::hit(id as usize);
+}}
+
+pub fn __velin_cov_flush() {{
+    __velin_coverage::flush();
+}}
+"#,
+            count = probe_count,
+            env = COVERAGE_FILE_ENV,
+            probe = PROBE_FUNCTION,
+        )
+    }
+
+    /// `main` für einen Test-Lauf: ruft alle `@test`-Funktionen (Name, async) auf
+    /// und schreibt die Zählerstände auch dann, wenn ein Test panikt.
+    pub fn generate_test_harness(test_functions: &[(String, bool)]) -> String {
+        let mut calls = String::new();
+        for (name, is_async) in test_functions {
+            let task = if *is_async {
+                format!("tokio::spawn(async {{ {}().await; }})", name)
+            } else {
+                format!("tokio::task::spawn_blocking(|| {{ {}(); }})", name)
+            };
+            calls.push_str(&format!(
+                r#"    match {task}.await {{
+        Ok(_) => println!("  ✓ {name}"),
+        Err(_) => {{
+            println!("  ✗ {name}");
+            failed += 1;
+        }}
+    }}
+"#,
+                task = task,
+                name = name
+            ));
+        }
+
+        format!(
+            r#"
+#[tokio::main]
+async fn main() {{
+    let _coverage = __velin_coverage::VelinCoverageGuard;
+    let mut failed = 0usize;
+{calls}    __velin_cov_flush();
+    if failed > 0 {{
+        std::process::exit(1);
+    }}
+}}
+"#,
+            calls = calls
+        )
+    }
+
+    /// Cargo-Manifest für das Harness-Crate eines Test-Laufs
+    /// (Abhängigkeiten des Prologs aus `RustCodeGenerator`)
+    pub fn generate_harness_manifest(name: &str) -> String {
+        format!(
+            r#"[package]
+name = "{}"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+axum = "0.7"
+serde = {{ version = "1.0", features = ["derive"] }}
+serde_json = "1.0"
+tokio = {{ version = "1.0", features = ["full"] }}
+anyhow = "1.0"
+regex = "1.10"
+itertools = "0.12"
+rayon = "1.8"
+tracing = "0.1"
+once_cell = "1.19"
+"#,
+            name
+        )
+    }
+}
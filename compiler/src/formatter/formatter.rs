@@ -54,6 +54,8 @@ impl Formatter {
     }
 
     fn format_function(&mut self, function: &Function) {
+        self.format_documentation(&function.documentation);
+
         // Format decorators
         for decorator in &function.decorators {
             self.format_decorator(decorator);
@@ -92,7 +94,22 @@ impl Formatter {
         self.format_block(&function.body);
     }
 
+    /// Doc-Kommentare (`///`) vor Funktionen, Structs und Enums
+    fn format_documentation(&mut self, documentation: &Option<String>) {
+        if let Some(doc) = documentation {
+            for line in doc.lines() {
+                self.indent();
+                if line.is_empty() {
+                    self.writeln("///");
+                } else {
+                    self.writeln(&format!("/// {}", line));
+                }
+            }
+        }
+    }
+
     fn format_decorator(&mut self, decorator: &Decorator) {
+        self.indent();
         self.write("@");
         self.write(&decorator.name);
 
@@ -112,9 +129,7 @@ impl Formatter {
     fn format_decorator_arg(&mut self, arg: &DecoratorArg) {
         match arg {
             DecoratorArg::String(s) => {
-                self.write("\"");
-                self.write(s);
-                self.write("\"");
+                self.write_string(s);
             }
             DecoratorArg::Number(n) => {
                 self.write(&n.to_string());
@@ -130,6 +145,16 @@ impl Formatter {
                 self.write(": ");
                 self.format_decorator_arg(value);
             }
+            DecoratorArg::List(items) => {
+                self.write("[");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.format_decorator_arg(item);
+                }
+                self.write("]");
+            }
         }
     }
 
@@ -145,6 +170,11 @@ impl Formatter {
     }
 
     fn format_struct(&mut self, struct_def: &Struct) {
+        self.format_documentation(&struct_def.documentation);
+        for decorator in &struct_def.decorators {
+            self.format_decorator(decorator);
+        }
+
         if struct_def.visibility == Visibility::Public {
             self.write("pub ");
         }
@@ -159,7 +189,6 @@ impl Formatter {
             if i > 0 {
                 self.writeln("");
             }
-            self.indent();
             self.format_struct_field(field);
         }
         self.indent_level -= 1;
@@ -170,6 +199,11 @@ impl Formatter {
     }
 
     fn format_struct_field(&mut self, field: &StructField) {
+        for decorator in &field.decorators {
+            self.format_decorator(decorator);
+        }
+
+        self.indent();
         if field.visibility == Visibility::Public {
             self.write("pub ");
         }
@@ -181,6 +215,8 @@ impl Formatter {
     }
 
     fn format_enum(&mut self, enum_def: &Enum) {
+        self.format_documentation(&enum_def.documentation);
+
         if enum_def.visibility == Visibility::Public {
             self.write("pub ");
         }
@@ -706,9 +742,7 @@ impl Formatter {
     fn format_literal(&mut self, lit: &Literal) {
         match lit {
             Literal::String(s) => {
-                self.write("\"");
-                self.write(s);
-                self.write("\"");
+                self.write_string(s);
             }
             Literal::Number(n) => {
                 self.write(&n.to_string());
@@ -759,6 +793,25 @@ impl Formatter {
         let _ = write!(self.output, "{}", s);
     }
 
+    /// String-Literal mit Escapes, `{` wird escaped damit kein Format-String entsteht
+    fn write_string(&mut self, s: &str) {
+        let mut escaped = String::with_capacity(s.len() + 2);
+        escaped.push('"');
+        for c in s.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '"' => escaped.push_str("\\\""),
+                '\n' => escaped.push_str("\\n"),
+                '\t' => escaped.push_str("\\t"),
+                '\r' => escaped.push_str("\\r"),
+                '{' => escaped.push_str("\\{"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped.push('"');
+        self.write(&escaped);
+    }
+
     fn writeln(&mut self, s: &str) {
         let _ = writeln!(self.output, "{}", s);
     }
@@ -0,0 +1,363 @@
+// Inspector-Instrumentierung
+// Startet den Inspect-Endpunkt in `main`, zählt Route-Aufrufe und leitet
+// `inspect`-, `metrics`- und `cache`-Aufrufe in die Inspector-Laufzeit um
+
+use crate::parser::ast::*;
+
+/// Variablenname des Server-Handles in `main`
+const SERVER_VARIABLE: &str = "__velin_inspect_server";
+
+/// Variablenname des Request-Guards; der Drop am Scope-Ende misst die Dauer
+const REQUEST_VARIABLE: &str = "__velin_inspect_request";
+
+/// Modulaufruf → Laufzeitfunktion. Labels von `metrics.*` werden verworfen,
+/// der Snapshot aggregiert nur nach Namen.
+fn runtime_function(module: &str, method: &str, arg_count: usize) -> Option<(&'static str, usize)> {
+    let target = match (module, method) {
+        ("inspect", "state") => ("__velin_inspect_state", 2),
+        ("inspect", "task") => ("__velin_inspect_task", 2),
+        ("metrics", "increment") => ("__velin_inspect_counter", 1),
+        ("metrics", "gauge") => ("__velin_inspect_gauge", 2),
+        ("metrics", "histogram") => ("__velin_inspect_observe", 2),
+        ("cache", "set") if arg_count >= 3 => ("__velin_inspect_cache_set_ttl", 3),
+        ("cache", "set") => ("__velin_inspect_cache_set", 2),
+        ("cache", "get") => ("__velin_inspect_cache_get", 1),
+        ("cache", "remove") => ("__velin_inspect_cache_remove", 1),
+        ("cache", "exists") => ("__velin_inspect_cache_exists", 1),
+        ("cache", "clear") => ("__velin_inspect_cache_clear", 0),
+        ("cache", "size") => ("__velin_inspect_cache_size", 0),
+        _ => return None,
+    };
+    Some(target)
+}
+
+/// Instrumentiert ein Programm für `velin-inspect`.
+///
+/// Anders als Coverage und Profiling braucht die Inspektion keine Zeilen,
+/// deshalb läuft sie auf dem fertig aufgelösten Programm nach dem Type Checking.
+pub struct InspectorInstrumenter {
+    service: String,
+}
+
+impl InspectorInstrumenter {
+    pub fn new(service: String) -> Self {
+        Self { service }
+    }
+
+    pub fn instrument(&self, program: &mut Program) {
+        for item in &mut program.items {
+            self.instrument_item(item, true);
+        }
+    }
+
+    fn instrument_item(&self, item: &mut Item, top_level: bool) {
+        match item {
+            Item::Function(function) => self.instrument_function(function, top_level),
+            Item::Impl(impl_def) => {
+                for method in &mut impl_def.methods {
+                    self.instrument_function(method, false);
+                }
+            }
+            Item::Module(module) => {
+                for item in &mut module.items {
+                    self.instrument_item(item, false);
+                }
+            }
+            Item::TopLevelCode(statement) => rewrite_expression(&mut statement.expression),
+            Item::Struct(_)
+            | Item::Enum(_)
+            | Item::TypeAlias(_)
+            | Item::Use(_)
+            | Item::Trait(_) => {}
+        }
+    }
+
+    fn instrument_function(&self, function: &mut Function, top_level: bool) {
+        rewrite_block(&mut function.body);
+
+        if let Some(route) = route_of(&function.decorators) {
+            function.body.statements.insert(
+                0,
+                guard_statement(
+                    REQUEST_VARIABLE,
+                    "__velin_inspect_request",
+                    vec![Expression::Literal(Literal::String(route))],
+                ),
+            );
+        }
+        if top_level && function.name == "main" {
+            function.body.statements.insert(
+                0,
+                guard_statement(
+                    SERVER_VARIABLE,
+                    "__velin_inspect_start",
+                    vec![Expression::Literal(Literal::String(self.service.clone()))],
+                ),
+            );
+        }
+    }
+}
+
+/// `"GET /users/:id"` für Handler mit HTTP-Decorator
+fn route_of(decorators: &[Decorator]) -> Option<String> {
+    decorators.iter().find_map(|decorator| {
+        match (decorator.name.as_str(), decorator.args.first()) {
+            ("GET" | "POST" | "PUT" | "DELETE" | "PATCH", Some(DecoratorArg::String(path))) => {
+                Some(format!("{} {}", decorator.name, path))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// `let <variable> = <function>(<args>);`
+fn guard_statement(variable: &str, function: &str, args: Vec<Expression>) -> Statement {
+    Statement::Let(LetStatement {
+        name: variable.to_string(),
+        var_type: None,
+        value: Expression::Call {
+            callee: Box::new(Expression::Identifier(function.to_string())),
+            args,
+        },
+        mutable: false,
+    })
+}
+
+fn rewrite_block(block: &mut Block) {
+    for statement in &mut block.statements {
+        rewrite_statement(statement);
+    }
+}
+
+fn rewrite_statement(statement: &mut Statement) {
+    match statement {
+        Statement::Let(let_stmt) => rewrite_expression(&mut let_stmt.value),
+        Statement::Return(ret) => {
+            if let Some(value) = &mut ret.value {
+                rewrite_expression(value);
+            }
+        }
+        Statement::Expression(expr_stmt) => rewrite_expression(&mut expr_stmt.expression),
+        Statement::If(if_stmt) => {
+            rewrite_expression(&mut if_stmt.condition);
+            rewrite_block(&mut if_stmt.then_block);
+            if let Some(else_block) = &mut if_stmt.else_block {
+                rewrite_block(else_block);
+            }
+        }
+        Statement::For(for_stmt) => {
+            rewrite_expression(&mut for_stmt.iterable);
+            rewrite_block(&mut for_stmt.body);
+        }
+        Statement::While(while_stmt) => {
+            rewrite_expression(&mut while_stmt.condition);
+            rewrite_block(&mut while_stmt.body);
+        }
+        Statement::Match(match_stmt) => {
+            rewrite_expression(&mut match_stmt.expression);
+            for arm in &mut match_stmt.arms {
+                if let Some(guard) = &mut arm.guard {
+                    rewrite_expression(guard);
+                }
+                rewrite_block(&mut arm.body);
+            }
+        }
+        Statement::Throw(throw) => rewrite_expression(&mut throw.expression),
+        Statement::Try(try_stmt) => {
+            rewrite_block(&mut try_stmt.try_block);
+            for catch in &mut try_stmt.catch_blocks {
+                rewrite_block(&mut catch.body);
+            }
+            if let Some(finally) = &mut try_stmt.finally_block {
+                rewrite_block(finally);
+            }
+        }
+        Statement::Break(_) => {}
+    }
+}
+
+fn rewrite_expression(expr: &mut Expression) {
+    // Zuerst die Kinder, damit verschachtelte Aufrufe ebenfalls umgeleitet werden
+    match expr {
+        Expression::BinaryOp { left, right, .. } => {
+            rewrite_expression(left);
+            rewrite_expression(right);
+        }
+        Expression::UnaryOp { expr, .. } | Expression::Await { expr } => rewrite_expression(expr),
+        Expression::Call { callee, args } => {
+            rewrite_expression(callee);
+            for arg in args.iter_mut() {
+                rewrite_expression(arg);
+            }
+        }
+        Expression::Member { object, .. } => rewrite_expression(object),
+        Expression::Index { object, index } => {
+            rewrite_expression(object);
+            rewrite_expression(index);
+        }
+        Expression::If {
+            condition,
+            then_expr,
+            else_expr,
+        } => {
+            rewrite_expression(condition);
+            rewrite_expression(then_expr);
+            rewrite_expression(else_expr);
+        }
+        Expression::Block(block) => rewrite_block(block),
+        Expression::StructLiteral { fields, .. } | Expression::MapLiteral(fields) => {
+            for (_, value) in fields {
+                rewrite_expression(value);
+            }
+        }
+        Expression::ListLiteral(items)
+        | Expression::GenericConstructor { args: items, .. }
+        | Expression::LLMCall { args: items, .. } => {
+            for item in items {
+                rewrite_expression(item);
+            }
+        }
+        Expression::Lambda { body, .. } => rewrite_expression(body),
+        Expression::Assignment { target, value } => {
+            rewrite_expression(target);
+            rewrite_expression(value);
+        }
+        Expression::FormatString { parts } => {
+            for part in parts {
+                if let FormatStringPart::Expression(inner) = part {
+                    rewrite_expression(inner);
+                }
+            }
+        }
+        Expression::Literal(_) | Expression::Identifier(_) => {}
+    }
+
+    let Expression::Call { callee, args } = expr else {
+        return;
+    };
+    let Expression::Member { object, member } = callee.as_ref() else {
+        return;
+    };
+    let Expression::Identifier(module) = object.as_ref() else {
+        return;
+    };
+    let Some((function, arity)) = runtime_function(module, member, args.len()) else {
+        return;
+    };
+
+    let args = args.drain(..).take(arity).map(owned_argument).collect();
+    *expr = Expression::Call {
+        callee: Box::new(Expression::Identifier(function.to_string())),
+        args,
+    };
+}
+
+/// Nicht-literale Argumente werden geklont, damit der Aufruf keine Variable verschiebt
+fn owned_argument(arg: Expression) -> Expression {
+    match arg {
+        Expression::Literal(_) => arg,
+        _ => Expression::Call {
+            callee: Box::new(Expression::Member {
+                object: Box::new(arg),
+                member: "clone".to_string(),
+            }),
+            args: Vec::new(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_routes_and_module_calls_are_instrumented() {
+        let source = r#"@GET("/orders")
+fn listOrders(): string {
+    metrics.increment("orders_listed");
+    cache.set("last", "orders", 30);
+    return "ok";
+}
+
+fn main() {
+    let total = 3;
+    inspect.state("total", total);
+}
+"#;
+        let mut program = Parser::parse(source).unwrap();
+        InspectorInstrumenter::new("shop".to_string()).instrument(&mut program);
+
+        let Item::Function(handler) = &program.items[0] else {
+            panic!("Handler erwartet");
+        };
+        let Statement::Let(guard) = &handler.body.statements[0] else {
+            panic!("Request-Guard erwartet");
+        };
+        assert_eq!(guard.name, REQUEST_VARIABLE);
+        assert_eq!(
+            guard.value,
+            Expression::Call {
+                callee: Box::new(Expression::Identifier(
+                    "__velin_inspect_request".to_string()
+                )),
+                args: vec![Expression::Literal(Literal::String(
+                    "GET /orders".to_string()
+                ))],
+            }
+        );
+        let Statement::Expression(counter) = &handler.body.statements[1] else {
+            panic!("Counter-Aufruf erwartet");
+        };
+        assert_eq!(
+            counter.expression,
+            Expression::Call {
+                callee: Box::new(Expression::Identifier(
+                    "__velin_inspect_counter".to_string()
+                )),
+                args: vec![Expression::Literal(Literal::String(
+                    "orders_listed".to_string()
+                ))],
+            }
+        );
+        let Statement::Expression(cache_set) = &handler.body.statements[2] else {
+            panic!("Cache-Aufruf erwartet");
+        };
+        let Expression::Call { callee, args } = &cache_set.expression else {
+            panic!("Aufruf erwartet");
+        };
+        assert_eq!(
+            **callee,
+            Expression::Identifier("__velin_inspect_cache_set_ttl".to_string())
+        );
+        assert_eq!(args.len(), 3);
+
+        let Item::Function(main) = &program.items[1] else {
+            panic!("main erwartet");
+        };
+        let Statement::Let(server) = &main.body.statements[0] else {
+            panic!("Server-Start erwartet");
+        };
+        assert_eq!(server.name, SERVER_VARIABLE);
+        let Statement::Expression(state) = &main.body.statements[2] else {
+            panic!("State-Aufruf erwartet");
+        };
+        let Expression::Call { callee, args } = &state.expression else {
+            panic!("Aufruf erwartet");
+        };
+        assert_eq!(
+            **callee,
+            Expression::Identifier("__velin_inspect_state".to_string())
+        );
+        assert_eq!(
+            args[1],
+            Expression::Call {
+                callee: Box::new(Expression::Member {
+                    object: Box::new(Expression::Identifier("total".to_string())),
+                    member: "clone".to_string(),
+                }),
+                args: Vec::new(),
+            }
+        );
+    }
+}
@@ -0,0 +1,81 @@
+// Live-Inspektion laufender Velin-Services
+// Instrumentierte Services liefern über HTTP einen Snapshot ihres Laufzeitzustands
+
+pub mod instrument;
+pub mod runtime;
+
+pub use instrument::InspectorInstrumenter;
+pub use runtime::InspectorRuntime;
+
+use serde::{Deserialize, Serialize};
+
+/// Umgebungsvariable, über die der Service die Adresse des Inspect-Endpunkts erhält
+pub const INSPECT_ADDR_ENV: &str = "VELIN_INSPECT_ADDR";
+
+/// Adresse, falls `VELIN_INSPECT_ADDR` nicht gesetzt ist (nur lokal erreichbar)
+pub const DEFAULT_INSPECT_ADDR: &str = "127.0.0.1:9930";
+
+/// HTTP-Pfad, unter dem der Snapshot als JSON ausgeliefert wird
+pub const INSPECT_PATH: &str = "/__velin/inspect";
+
+/// Per `inspect.state(...)` registrierter Wert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateEntry {
+    pub name: String,
+    pub value: serde_json::Value,
+    /// Millisekunden seit dem letzten Update
+    pub updated_ms_ago: u64,
+}
+
+/// Aufrufstatistik eines Route-Handlers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestStats {
+    pub route: String,
+    pub count: u64,
+    pub in_flight: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Wert aus `metrics.increment/gauge/histogram`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricEntry {
+    pub name: String,
+    /// `counter`, `gauge` oder `histogram`
+    pub kind: String,
+    /// Zählerstand, letzter Gauge-Wert bzw. Mittelwert des Histogramms
+    pub value: f64,
+    /// Anzahl der Beobachtungen (nur Histogramme)
+    pub count: u64,
+}
+
+/// Eintrag aus dem `cache`-Modul
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub key: String,
+    pub value: serde_json::Value,
+    /// Restlaufzeit, `None` ohne TTL
+    pub expires_in_ms: Option<u64>,
+}
+
+/// Per `inspect.task(...)` gemeldeter Task-Status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub status: String,
+    pub updated_ms_ago: u64,
+}
+
+/// Snapshot, den `GET /__velin/inspect` liefert
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InspectorSnapshot {
+    pub service: String,
+    pub uptime_ms: u64,
+    /// Resident Set Size des Prozesses (nur Linux)
+    pub memory_rss_bytes: Option<u64>,
+    pub state: Vec<StateEntry>,
+    pub requests: Vec<RequestStats>,
+    pub metrics: Vec<MetricEntry>,
+    pub cache: Vec<CacheEntry>,
+    pub tasks: Vec<TaskStatus>,
+}
@@ -0,0 +1,388 @@
+// Inspector-Laufzeit
+// Rust-Code, der mit `--inspect` kompilierten Services angehängt wird
+
+use crate::inspector::{DEFAULT_INSPECT_ADDR, INSPECT_ADDR_ENV, INSPECT_PATH};
+
+pub struct InspectorRuntime;
+
+/// Laufzeit-Vorlage; Platzhalter werden in `generate_runtime_code` ersetzt
+/// (kein `format!`, damit die Klammern des Rust-Codes lesbar bleiben)
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Inspector Runtime ---
+mod __velin_inspect {
+    use std::collections::BTreeMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::{Duration, Instant};
+
+    pub struct Metric {
+        pub kind: &'static str,
+        pub value: f64,
+        pub sum: f64,
+        pub count: u64,
+    }
+
+    #[derive(Default)]
+    pub struct Route {
+        pub count: u64,
+        pub in_flight: u64,
+        pub total_ms: f64,
+        pub max_ms: f64,
+    }
+
+    #[derive(Default)]
+    pub struct Registry {
+        pub service: String,
+        pub state: BTreeMap<String, (serde_json::Value, Instant)>,
+        pub tasks: BTreeMap<String, (String, Instant)>,
+        pub metrics: BTreeMap<String, Metric>,
+        pub routes: BTreeMap<String, Route>,
+        pub cache: BTreeMap<String, (serde_json::Value, Option<Instant>)>,
+    }
+
+    static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    pub fn with<R>(f: impl FnOnce(&mut Registry) -> R) -> R {
+        let mut guard = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+        f(guard.get_or_insert_with(Registry::default))
+    }
+
+    pub fn to_json(value: impl serde::Serialize) -> serde_json::Value {
+        serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+    }
+
+    pub fn millis(since: Instant) -> u64 {
+        since.elapsed().as_millis() as u64
+    }
+
+    pub struct Server;
+
+    pub fn start(service: &str) -> Server {
+        START.get_or_init(Instant::now);
+        with(|r| r.service = service.to_string());
+        let addr = std::env::var("__ADDR_ENV__").unwrap_or_else(|_| "__DEFAULT_ADDR__".to_string());
+        match TcpListener::bind(&addr) {
+            Ok(listener) => {
+                eprintln!("inspect: listening on http://{}__PATH__", addr);
+                std::thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let _ = serve(stream);
+                    }
+                });
+            }
+            Err(e) => eprintln!("inspect: failed to bind {}: {}", addr, e),
+        }
+        Server
+    }
+
+    fn serve(mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(stream.try_clone()?);
+        reader.read_line(&mut request_line)?;
+        // Header überspringen
+        let mut line = String::new();
+        while reader.read_line(&mut line)? > 2 {
+            line.clear();
+        }
+        let mut parts = request_line.split_whitespace();
+        let (status, body) = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("__PATH__")) => ("200 OK", snapshot().to_string()),
+            _ => ("404 Not Found", "{\"error\":\"not found\"}".to_string()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+
+    fn memory_rss_bytes() -> Option<u64> {
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        Some(pages * 4096)
+    }
+
+    fn snapshot() -> serde_json::Value {
+        let uptime_ms = START.get().map(|start| millis(*start)).unwrap_or(0);
+        with(|r| {
+            let now = Instant::now();
+            r.cache.retain(|_, (_, expires)| expires.map_or(true, |at| at > now));
+            serde_json::json!({
+                "service": r.service,
+                "uptime_ms": uptime_ms,
+                "memory_rss_bytes": memory_rss_bytes(),
+                "state": r.state.iter().map(|(name, (value, at))| serde_json::json!({
+                    "name": name, "value": value, "updated_ms_ago": millis(*at),
+                })).collect::<Vec<_>>(),
+                "requests": r.routes.iter().map(|(route, stats)| serde_json::json!({
+                    "route": route, "count": stats.count, "in_flight": stats.in_flight,
+                    "total_ms": stats.total_ms, "max_ms": stats.max_ms,
+                })).collect::<Vec<_>>(),
+                "metrics": r.metrics.iter().map(|(name, metric)| serde_json::json!({
+                    "name": name, "kind": metric.kind, "value": metric.value, "count": metric.count,
+                })).collect::<Vec<_>>(),
+                "cache": r.cache.iter().map(|(key, (value, expires))| serde_json::json!({
+                    "key": key, "value": value,
+                    "expires_in_ms": expires.map(|at| at.saturating_duration_since(now).as_millis() as u64),
+                })).collect::<Vec<_>>(),
+                "tasks": r.tasks.iter().map(|(name, (status, at))| serde_json::json!({
+                    "name": name, "status": status, "updated_ms_ago": millis(*at),
+                })).collect::<Vec<_>>(),
+            })
+        })
+    }
+
+    pub struct RequestGuard {
+        route: String,
+        start: Instant,
+    }
+
+    pub fn request(route: &str) -> RequestGuard {
+        with(|r| {
+            let stats = r.routes.entry(route.to_string()).or_default();
+            stats.count += 1;
+            stats.in_flight += 1;
+        });
+        RequestGuard {
+            route: route.to_string(),
+            start: Instant::now(),
+        }
+    }
+
+    impl Drop for RequestGuard {
+        fn drop(&mut self) {
+            let elapsed = self.start.elapsed().as_secs_f64() * 1000.0;
+            with(|r| {
+                if let Some(stats) = r.routes.get_mut(&self.route) {
+                    stats.in_flight = stats.in_flight.saturating_sub(1);
+                    stats.total_ms += elapsed;
+                    stats.max_ms = stats.max_ms.max(elapsed);
+                }
+            });
+        }
+    }
+
+    pub fn record(name: &str, kind: &'static str, value: f64) {
+        with(|r| {
+            let metric = r.metrics.entry(name.to_string()).or_insert(Metric {
+                kind,
+                value: 0.0,
+                sum: 0.0,
+                count: 0,
+            });
+            metric.count += 1;
+            match kind {
+                "counter" => metric.value += value,
+                "histogram" => {
+                    metric.sum += value;
+                    metric.value = metric.sum / metric.count as f64;
+                }
+                _ => metric.value = value,
+            }
+        });
+    }
+}
+
+pub fn __velin_inspect_start(service: impl AsRef<str>) -> __velin_inspect::Server {
+    __velin_inspect::start(service.as_ref())
+}
+
+pub fn __velin_inspect_request(route: impl AsRef<str>) -> __velin_inspect::RequestGuard {
+    __velin_inspect::request(route.as_ref())
+}
+
+pub fn __velin_inspect_state(name: impl AsRef<str>, value: impl serde::Serialize) {
+    let value = __velin_inspect::to_json(value);
+    __velin_inspect::with(|r| {
+        r.state.insert(name.as_ref().to_string(), (value, std::time::Instant::now()));
+    });
+}
+
+pub fn __velin_inspect_task(name: impl AsRef<str>, status: impl AsRef<str>) {
+    __velin_inspect::with(|r| {
+        r.tasks.insert(
+            name.as_ref().to_string(),
+            (status.as_ref().to_string(), std::time::Instant::now()),
+        );
+    });
+}
+
+pub fn __velin_inspect_counter(name: impl AsRef<str>) {
+    __velin_inspect::record(name.as_ref(), "counter", 1.0);
+}
+
+pub fn __velin_inspect_gauge(name: impl AsRef<str>, value: impl Into<f64>) {
+    __velin_inspect::record(name.as_ref(), "gauge", value.into());
+}
+
+pub fn __velin_inspect_observe(name: impl AsRef<str>, value: impl Into<f64>) {
+    __velin_inspect::record(name.as_ref(), "histogram", value.into());
+}
+
+pub fn __velin_inspect_cache_set(key: impl AsRef<str>, value: impl serde::Serialize) {
+    let value = __velin_inspect::to_json(value);
+    __velin_inspect::with(|r| {
+        r.cache.insert(key.as_ref().to_string(), (value, None));
+    });
+}
+
+pub fn __velin_inspect_cache_set_ttl(
+    key: impl AsRef<str>,
+    value: impl serde::Serialize,
+    ttl_seconds: impl Into<f64>,
+) {
+    let value = __velin_inspect::to_json(value);
+    let expires = std::time::Instant::now()
+        + std::time::Duration::from_secs_f64(ttl_seconds.into().max(0.0));
+    __velin_inspect::with(|r| {
+        r.cache.insert(key.as_ref().to_string(), (value, Some(expires)));
+    });
+}
+
+pub fn __velin_inspect_cache_get(key: impl AsRef<str>) -> Option<serde_json::Value> {
+    let now = std::time::Instant::now();
+    __velin_inspect::with(|r| match r.cache.get(key.as_ref()) {
+        Some((_, Some(expires))) if *expires <= now => {
+            r.cache.remove(key.as_ref());
+            None
+        }
+        Some((value, _)) => Some(value.clone()),
+        None => None,
+    })
+}
+
+pub fn __velin_inspect_cache_remove(key: impl AsRef<str>) {
+    __velin_inspect::with(|r| {
+        r.cache.remove(key.as_ref());
+    });
+}
+
+pub fn __velin_inspect_cache_exists(key: impl AsRef<str>) -> bool {
+    __velin_inspect_cache_get(key).is_some()
+}
+
+pub fn __velin_inspect_cache_clear() {
+    __velin_inspect::with(|r| r.cache.clear());
+}
+
+pub fn __velin_inspect_cache_size() -> f64 {
+    __velin_inspect::with(|r| r.cache.len() as f64)
+}
+"#;
+
+impl InspectorRuntime {
+    /// Registry und Inspect-Endpunkt für einen mit `--inspect` kompilierten Service.
+    ///
+    /// `__velin_inspect_start` bindet einen eigenen Thread an `VELIN_INSPECT_ADDR`
+    /// (Standard `127.0.0.1:9930`), der unter `/__velin/inspect` einen
+    /// `InspectorSnapshot` als JSON ausliefert. Der Cache ersetzt bei
+    /// Instrumentierung das `cache`-Modul, damit seine Einträge sichtbar sind.
+    pub fn generate_runtime_code() -> String {
+        RUNTIME_TEMPLATE
+            .replace("__ADDR_ENV__", INSPECT_ADDR_ENV)
+            .replace("__DEFAULT_ADDR__", DEFAULT_INSPECT_ADDR)
+            .replace("__PATH__", INSPECT_PATH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::{assert_parses_as_rust, generate_parsed_rust};
+    use crate::inspector::{
+        CacheEntry, InspectorInstrumenter, InspectorSnapshot, MetricEntry, RequestStats,
+        StateEntry, TaskStatus,
+    };
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_instrumented_service_with_runtime_parses_as_rust() {
+        let source = r#"@GET("/orders")
+fn listOrders(): string {
+    metrics.increment("orders_listed");
+    metrics.histogram("order_size", 3);
+    cache.set("last", "orders", 30);
+    return "ok";
+}
+
+fn main() {
+    let total = 3;
+    inspect.state("total", total);
+    inspect.task("import", "running");
+}
+"#;
+        let mut program = Parser::parse(source).unwrap();
+        InspectorInstrumenter::new("shop".to_string()).instrument(&mut program);
+        let code = generate_parsed_rust(&program, None);
+        assert!(code.contains("__velin_inspect_request(\"GET /orders\")"));
+        assert!(code.contains("__velin_inspect_start(\"shop\")"));
+
+        let runtime = InspectorRuntime::generate_runtime_code();
+        assert!(!runtime.contains("__ADDR_ENV__") && !runtime.contains("__PATH__"));
+        assert!(runtime.contains("std::env::var(\"VELIN_INSPECT_ADDR\")"));
+        assert!(runtime.contains("(Some(\"GET\"), Some(\"/__velin/inspect\"))"));
+        assert_parses_as_rust(&format!("{}\n{}", code, runtime));
+    }
+
+    #[test]
+    fn test_runtime_snapshot_matches_inspector_snapshot_fields() {
+        let snapshot = InspectorSnapshot {
+            service: "shop".to_string(),
+            uptime_ms: 1,
+            memory_rss_bytes: Some(1),
+            state: vec![StateEntry {
+                name: "total".to_string(),
+                value: serde_json::json!(3),
+                updated_ms_ago: 1,
+            }],
+            requests: vec![RequestStats::default()],
+            metrics: vec![MetricEntry {
+                name: "orders".to_string(),
+                kind: "counter".to_string(),
+                value: 1.0,
+                count: 1,
+            }],
+            cache: vec![CacheEntry {
+                key: "last".to_string(),
+                value: serde_json::json!("orders"),
+                expires_in_ms: None,
+            }],
+            tasks: vec![TaskStatus {
+                name: "import".to_string(),
+                status: "running".to_string(),
+                updated_ms_ago: 1,
+            }],
+        };
+
+        // Jedes Feld, das `velin-inspect` deserialisiert, muss die Laufzeit ausliefern
+        fn keys(value: &serde_json::Value, into: &mut Vec<String>) {
+            match value {
+                serde_json::Value::Object(map) => {
+                    for (key, child) in map {
+                        into.push(key.clone());
+                        keys(child, into);
+                    }
+                }
+                serde_json::Value::Array(items) => items.iter().for_each(|item| keys(item, into)),
+                _ => {}
+            }
+        }
+        let mut expected = Vec::new();
+        keys(&serde_json::to_value(&snapshot).unwrap(), &mut expected);
+
+        let runtime = InspectorRuntime::generate_runtime_code();
+        for key in expected {
+            assert!(
+                runtime.contains(&format!("\"{}\":", key)),
+                "runtime snapshot lacks field '{}'",
+                key
+            );
+        }
+    }
+}
@@ -0,0 +1,1617 @@
+// AST-Interpreter
+// Führt Velin-Programme direkt auf dem AST aus und meldet jede ausgeführte Zeile an einen Hook
+
+use crate::coverage::{CoverageMap, ProbeKind, PROBE_FUNCTION};
+use crate::interpreter::value::{Lambda, Value};
+use crate::parser::ast::*;
+use crate::parser::parser::Parser;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Maximale Aufruftiefe, bevor die Ausführung abbricht
+const MAX_DEPTH: usize = 512;
+
+/// Kontrollfluss, der über Statements hinweg propagiert wird
+#[derive(Debug)]
+pub enum Control {
+    Return(Value),
+    Break,
+    Throw(Value),
+    Error(String),
+}
+
+type Exec<T> = Result<T, Control>;
+
+fn error<T>(message: impl Into<String>) -> Exec<T> {
+    Err(Control::Error(message.into()))
+}
+
+/// Wird vor jedem Statement und für Programmausgaben aufgerufen
+pub trait DebugHook {
+    /// Ein `Err` bricht die Ausführung ab (z.B. bei Disconnect)
+    fn on_line(&mut self, interpreter: &mut Interpreter, file: &str, line: usize) -> Exec<()>;
+    fn on_output(&mut self, text: &str);
+}
+
+/// Aufruf-Frame mit Velin-Funktionsnamen und Block-Scopes
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    scopes: Vec<Vec<(String, Value)>>,
+}
+
+impl Frame {
+    fn new(name: String, file: String, line: usize) -> Self {
+        Self {
+            name,
+            file,
+            line,
+            scopes: vec![Vec::new()],
+        }
+    }
+
+    /// Sichtbare Variablen; innere Scopes überdecken äußere
+    pub fn variables(&self) -> Vec<(String, Value)> {
+        let mut result: Vec<(String, Value)> = Vec::new();
+        for scope in self.scopes.iter().rev() {
+            for (name, value) in scope {
+                if !result.iter().any(|(n, _)| n == name) {
+                    result.push((name.clone(), value.clone()));
+                }
+            }
+        }
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.iter().rev().find(|(n, _)| n == name).map(|(_, v)| v))
+    }
+
+    fn lookup_mut(&mut self, name: &str) -> Option<&mut Value> {
+        self.scopes.iter_mut().rev().find_map(|scope| {
+            scope
+                .iter_mut()
+                .rev()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v)
+        })
+    }
+
+    fn declare(&mut self, name: String, value: Value) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((name, value));
+        }
+    }
+}
+
+/// Position eines Statement-Probes
+#[derive(Debug, Clone)]
+struct ProbeSite {
+    file: String,
+    line: usize,
+    statement: bool,
+}
+
+pub struct Interpreter {
+    functions: HashMap<String, Arc<Function>>,
+    /// Top-Level-Modul (`""` = Root-Datei) → dort registrierte Funktionsnamen
+    modules: HashMap<String, Vec<String>>,
+    enums: HashMap<String, Vec<String>>,
+    globals: Vec<(String, Value)>,
+    frames: Vec<Frame>,
+    probes: HashMap<usize, ProbeSite>,
+    root_file: String,
+    hook: Option<Box<dyn DebugHook + Send>>,
+    /// Frame, in dem `evaluate` Variablen auflöst (Index in `frames`)
+    eval_frame: Option<usize>,
+    /// `self` nach dem letzten Methodenaufruf, wird in den Empfänger zurückgeschrieben
+    last_receiver: Option<Value>,
+}
+
+impl Interpreter {
+    /// `program` muss mit dem `CoverageInstrumentationPass` instrumentiert sein;
+    /// die Statement-Probes liefern die Zeileninformation
+    pub fn new(program: Program, maps: &[CoverageMap], root_file: String) -> Self {
+        let mut interpreter = Self {
+            functions: HashMap::new(),
+            modules: HashMap::new(),
+            enums: HashMap::new(),
+            globals: Vec::new(),
+            frames: Vec::new(),
+            probes: HashMap::new(),
+            root_file,
+            hook: None,
+            eval_frame: None,
+            last_receiver: None,
+        };
+        for map in maps {
+            for probe in &map.probes {
+                interpreter.probes.insert(
+                    probe.id,
+                    ProbeSite {
+                        file: map.file.clone(),
+                        line: probe.line,
+                        statement: matches!(probe.kind, ProbeKind::Statement),
+                    },
+                );
+            }
+        }
+        for item in program.items {
+            let owner = match &item {
+                Item::Module(module) => module.name.clone(),
+                _ => String::new(),
+            };
+            interpreter.register_item(item, None, &owner, true);
+        }
+        interpreter
+    }
+
+    pub fn set_hook(&mut self, hook: Box<dyn DebugHook + Send>) {
+        self.hook = Some(hook);
+    }
+
+    /// `top_level_code`: Top-Level-Statements nur beim ersten Laden übernehmen,
+    /// beim Austausch eines Moduls sind ihre Seiteneffekte bereits passiert
+    fn register_item(
+        &mut self,
+        item: Item,
+        prefix: Option<&str>,
+        owner: &str,
+        top_level_code: bool,
+    ) {
+        let qualify = |name: &str| match prefix {
+            Some(prefix) => format!("{}.{}", prefix, name),
+            None => name.to_string(),
+        };
+        match item {
+            Item::Function(function) => {
+                let name = qualify(&function.name);
+                self.define(owner, name, function);
+            }
+            Item::Impl(impl_def) => {
+                let type_name = impl_def.for_type.to_string();
+                for method in impl_def.methods {
+                    let name = format!("{}.{}", type_name, method.name);
+                    self.define(owner, name, method);
+                }
+            }
+            Item::Enum(enum_def) => {
+                let variants = enum_def.variants.iter().map(|v| v.name.clone()).collect();
+                self.enums.insert(enum_def.name.clone(), variants);
+            }
+            Item::Module(module) => {
+                let module_prefix = qualify(&module.name);
+                for item in module.items {
+                    self.register_item(item, Some(&module_prefix), owner, top_level_code);
+                }
+            }
+            Item::TopLevelCode(_) if !top_level_code => {}
+            Item::TopLevelCode(stmt) => {
+                // Als synthetische Init-Funktion in Quellreihenfolge ausführen
+                let index = self.functions.len();
+                self.functions.insert(
+                    format!("__toplevel_{}", index),
+                    Arc::new(Function {
+                        decorators: Vec::new(),
+                        visibility: Visibility::Private,
+                        name: format!("__toplevel_{}", index),
+                        type_params: Vec::new(),
+                        params: Vec::new(),
+                        return_type: None,
+                        body: Block {
+                            statements: vec![Statement::Expression(stmt)],
+                        },
+                        is_async: false,
+                        is_const: false,
+                        documentation: None,
+                    }),
+                );
+            }
+            Item::Struct(_) | Item::TypeAlias(_) | Item::Use(_) | Item::Trait(_) => {}
+        }
+    }
+
+    fn define(&mut self, owner: &str, name: String, function: Function) {
+        self.modules
+            .entry(owner.to_string())
+            .or_default()
+            .push(name.clone());
+        self.functions.insert(name, Arc::new(function));
+    }
+
+    /// Ersetzt alle Funktionen eines Top-Level-Moduls (`""` = Root-Datei) durch
+    /// `items`. Globale Variablen bleiben erhalten; neue globale `let`s
+    /// initialisiert `init_new_globals`.
+    pub fn replace_module(&mut self, module: &str, items: Vec<Item>) {
+        for name in self.modules.remove(module).unwrap_or_default() {
+            self.functions.remove(&name);
+        }
+        let prefix = (!module.is_empty()).then_some(module);
+        for item in items {
+            self.register_item(item, prefix, module, false);
+        }
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn globals(&self) -> &[(String, Value)] {
+        &self.globals
+    }
+
+    /// Initialisiert globale Variablen und ruft `main` auf
+    pub fn run_main(&mut self) -> Result<Value, String> {
+        self.init_globals()?;
+
+        let main = self
+            .functions
+            .get("main")
+            .cloned()
+            .ok_or_else(|| "Keine main()-Funktion gefunden".to_string())?;
+        self.call_function(&main, "main".to_string(), Vec::new(), None)
+            .map_err(describe)
+    }
+
+    /// Führt globale `let`s und Top-Level-Statements in Quellreihenfolge aus
+    pub fn init_globals(&mut self) -> Result<(), String> {
+        let mut init_names: Vec<&String> = self
+            .functions
+            .keys()
+            .filter(|name| global_of(name).is_some() || name.starts_with("__toplevel_"))
+            .collect();
+        init_names.sort();
+        let init_names: Vec<String> = init_names.into_iter().cloned().collect();
+        self.run_initializers(&init_names)
+    }
+
+    /// Initialisiert nur globale `let`s, deren Variable noch nicht existiert
+    /// (nach `replace_module`); liefert die Namen der neuen Variablen
+    pub fn init_new_globals(&mut self) -> Result<Vec<String>, String> {
+        let mut init_names: Vec<String> = self
+            .functions
+            .keys()
+            .filter(|name| {
+                global_of(name)
+                    .is_some_and(|variable| !self.globals.iter().any(|(n, _)| n == variable))
+            })
+            .cloned()
+            .collect();
+        init_names.sort();
+        self.run_initializers(&init_names)?;
+        Ok(init_names
+            .iter()
+            .filter_map(|name| global_of(name))
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn run_initializers(&mut self, names: &[String]) -> Result<(), String> {
+        let init_functions: Vec<Arc<Function>> = names
+            .iter()
+            .filter_map(|name| self.functions.get(name).cloned())
+            .collect();
+
+        // Globale `let`s laufen in einem gemeinsamen Frame, dessen Variablen global werden
+        self.frames.push(Frame::new(
+            "<global>".to_string(),
+            self.root_file.clone(),
+            0,
+        ));
+        for function in init_functions {
+            if let Err(control) = self.exec_block_in_place(&function.body) {
+                self.frames.pop();
+                return Err(describe(control));
+            }
+        }
+        if let Some(frame) = self.frames.pop() {
+            self.globals.extend(frame.scopes.into_iter().flatten());
+        }
+        Ok(())
+    }
+
+    /// Ruft eine registrierte Funktion (z.B. einen Route-Handler) auf
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let function = self
+            .functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("Unbekannte Funktion: {}", name))?;
+        self.call_function(&function, name.to_string(), args, None)
+            .map_err(describe)
+    }
+
+    /// Wertet einen Velin-Ausdruck im Frame `frame_index` (0 = innerster Frame) aus
+    pub fn evaluate(&mut self, source: &str, frame_index: usize) -> Result<Value, String> {
+        let expression = parse_expression(source)?;
+        let index = self
+            .frames
+            .len()
+            .checked_sub(1 + frame_index)
+            .ok_or_else(|| format!("Unbekannter Frame: {}", frame_index))?;
+
+        // Während der Auswertung keine Zeilen-Events (z.B. durch Funktionsaufrufe)
+        let hook = self.hook.take();
+        let previous = self.eval_frame.replace(index);
+        let result = self.eval(&expression);
+        self.eval_frame = previous;
+        self.hook = hook;
+        result.map_err(describe)
+    }
+
+    // --- Variablen ---
+
+    fn frame_index(&self) -> Option<usize> {
+        self.eval_frame.or_else(|| self.frames.len().checked_sub(1))
+    }
+
+    fn lookup(&self, name: &str) -> Option<Value> {
+        if let Some(index) = self.frame_index() {
+            if let Some(value) = self.frames[index].lookup(name) {
+                return Some(value.clone());
+            }
+        }
+        self.globals
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.clone())
+    }
+
+    fn variable_mut(&mut self, name: &str) -> Option<&mut Value> {
+        if let Some(index) = self.frame_index() {
+            if self.frames[index].lookup(name).is_some() {
+                return self.frames[index].lookup_mut(name);
+            }
+        }
+        self.globals
+            .iter_mut()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v)
+    }
+
+    fn declare(&mut self, name: String, value: Value) {
+        match self.frame_index() {
+            Some(index) => self.frames[index].declare(name, value),
+            None => self.globals.push((name, value)),
+        }
+    }
+
+    fn push_scope(&mut self) {
+        if let Some(index) = self.frame_index() {
+            self.frames[index].scopes.push(Vec::new());
+        }
+    }
+
+    fn pop_scope(&mut self) {
+        if let Some(index) = self.frame_index() {
+            self.frames[index].scopes.pop();
+        }
+    }
+
+    // --- Funktionsaufrufe ---
+
+    fn call_function(
+        &mut self,
+        function: &Function,
+        name: String,
+        args: Vec<Value>,
+        receiver: Option<Value>,
+    ) -> Exec<Value> {
+        if self.frames.len() >= MAX_DEPTH {
+            return error(format!("Maximale Aufruftiefe überschritten in {}", name));
+        }
+        let caller_file = self
+            .frames
+            .last()
+            .map(|f| f.file.clone())
+            .unwrap_or_else(|| self.root_file.clone());
+        let mut frame = Frame::new(name, caller_file, 0);
+        if let Some(receiver) = receiver {
+            frame.declare("self".to_string(), receiver);
+        }
+        let mut args = args.into_iter();
+        for param in &function.params {
+            let value = match args.next() {
+                Some(value) => value,
+                None => match &param.default {
+                    Some(default) => self.eval(default)?,
+                    None => Value::Null,
+                },
+            };
+            frame.declare(param.name.clone(), value);
+        }
+
+        let previous_eval = self.eval_frame.take();
+        self.frames.push(frame);
+        let result = self.exec_block_in_place(&function.body);
+        let frame = self.frames.pop();
+        self.eval_frame = previous_eval;
+
+        match result {
+            Ok(value) => Ok(value.unwrap_or(Value::Null)),
+            Err(Control::Return(value)) => Ok(value),
+            Err(Control::Break) => Ok(Value::Null),
+            Err(other) => Err(other),
+        }
+        .inspect(|_| {
+            // Methoden geben ein verändertes `self` über den Rückgabewert des Aufrufers zurück
+            if let Some(receiver) = frame.as_ref().and_then(|frame| frame.lookup("self")) {
+                self.last_receiver = Some(receiver.clone());
+            }
+        })
+    }
+
+    fn call_value(&mut self, callee: &Value, args: Vec<Value>) -> Exec<Value> {
+        match callee {
+            Value::Function(name) => {
+                let function = self
+                    .functions
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| Control::Error(format!("Unbekannte Funktion: {}", name)))?;
+                self.call_function(&function, name.clone(), args, None)
+            }
+            Value::Lambda(lambda) => self.call_lambda(lambda, args),
+            other => error(format!("{} ist nicht aufrufbar", other.type_name())),
+        }
+    }
+
+    fn call_lambda(&mut self, lambda: &Lambda, args: Vec<Value>) -> Exec<Value> {
+        if self.frames.len() >= MAX_DEPTH {
+            return error("Maximale Aufruftiefe überschritten in <lambda>");
+        }
+        let (file, line) = self
+            .frames
+            .last()
+            .map(|f| (f.file.clone(), f.line))
+            .unwrap_or_else(|| (self.root_file.clone(), 0));
+        let mut frame = Frame::new("<lambda>".to_string(), file, line);
+        for (name, value) in &lambda.captured {
+            frame.declare(name.clone(), value.clone());
+        }
+        frame.scopes.push(Vec::new());
+        let mut args = args.into_iter();
+        for param in &lambda.params {
+            frame.declare(param.name.clone(), args.next().unwrap_or(Value::Null));
+        }
+
+        let previous_eval = self.eval_frame.take();
+        self.frames.push(frame);
+        let result = match &lambda.body {
+            Expression::Block(block) => self
+                .exec_block_in_place(block)
+                .map(|value| value.unwrap_or(Value::Null)),
+            expr => self.eval(expr),
+        };
+        self.frames.pop();
+        self.eval_frame = previous_eval;
+
+        match result {
+            Err(Control::Return(value)) => Ok(value),
+            other => other,
+        }
+    }
+
+    // --- Statements ---
+
+    /// Führt einen Block im aktuellen Scope aus; liefert den Wert eines
+    /// abschließenden Ausdrucks-Statements
+    fn exec_block_in_place(&mut self, block: &Block) -> Exec<Option<Value>> {
+        let mut last = None;
+        for statement in &block.statements {
+            last = self.exec(statement)?;
+        }
+        Ok(last)
+    }
+
+    fn exec_block(&mut self, block: &Block) -> Exec<Option<Value>> {
+        self.push_scope();
+        let result = self.exec_block_in_place(block);
+        self.pop_scope();
+        result
+    }
+
+    fn exec(&mut self, statement: &Statement) -> Exec<Option<Value>> {
+        match statement {
+            Statement::Let(let_stmt) => {
+                let value = self.eval(&let_stmt.value)?;
+                self.declare(let_stmt.name.clone(), value);
+                Ok(None)
+            }
+            Statement::Return(ret) => {
+                let value = match &ret.value {
+                    Some(expr) => self.eval(expr)?,
+                    None => Value::Null,
+                };
+                Err(Control::Return(value))
+            }
+            Statement::Expression(expr_stmt) => {
+                if let Some(id) = probe_id(&expr_stmt.expression) {
+                    self.on_probe(id)?;
+                    return Ok(None);
+                }
+                self.eval(&expr_stmt.expression).map(Some)
+            }
+            Statement::If(if_stmt) => {
+                if self.eval(&if_stmt.condition)?.is_truthy() {
+                    self.exec_block(&if_stmt.then_block)
+                } else if let Some(else_block) = &if_stmt.else_block {
+                    self.exec_block(else_block)
+                } else {
+                    Ok(None)
+                }
+            }
+            Statement::For(for_stmt) => {
+                let items = match self.eval(&for_stmt.iterable)? {
+                    Value::List(items) => items,
+                    Value::Map(entries) => {
+                        entries.into_iter().map(|(k, _)| Value::String(k)).collect()
+                    }
+                    Value::String(s) => s.chars().map(|c| Value::String(c.to_string())).collect(),
+                    other => return error(format!("{} ist nicht iterierbar", other.type_name())),
+                };
+                for item in items {
+                    self.push_scope();
+                    self.declare(for_stmt.variable.clone(), item);
+                    let result = self.exec_block_in_place(&for_stmt.body);
+                    self.pop_scope();
+                    match result {
+                        Ok(_) => {}
+                        Err(Control::Break) => break,
+                        Err(other) => return Err(other),
+                    }
+                }
+                Ok(None)
+            }
+            Statement::While(while_stmt) => {
+                while self.eval(&while_stmt.condition)?.is_truthy() {
+                    match self.exec_block(&while_stmt.body) {
+                        Ok(_) => {}
+                        Err(Control::Break) => break,
+                        Err(other) => return Err(other),
+                    }
+                }
+                Ok(None)
+            }
+            Statement::Match(match_stmt) => {
+                let value = self.eval(&match_stmt.expression)?;
+                for arm in &match_stmt.arms {
+                    let mut bindings = Vec::new();
+                    if !self.match_pattern(&arm.pattern, &value, &mut bindings)? {
+                        continue;
+                    }
+                    self.push_scope();
+                    for (name, bound) in bindings {
+                        self.declare(name, bound);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        match self.eval(guard) {
+                            Ok(v) if v.is_truthy() => {}
+                            Ok(_) => {
+                                self.pop_scope();
+                                continue;
+                            }
+                            Err(e) => {
+                                self.pop_scope();
+                                return Err(e);
+                            }
+                        }
+                    }
+                    let result = self.exec_block_in_place(&arm.body);
+                    self.pop_scope();
+                    return result;
+                }
+                Ok(None)
+            }
+            Statement::Throw(throw_stmt) => {
+                let value = self.eval(&throw_stmt.expression)?;
+                Err(Control::Throw(value))
+            }
+            Statement::Break(_) => Err(Control::Break),
+            Statement::Try(try_stmt) => {
+                let mut result = self.exec_block(&try_stmt.try_block);
+                let thrown = match &result {
+                    Err(Control::Throw(value)) => Some(value.clone()),
+                    Err(Control::Error(message)) => Some(Value::String(message.clone())),
+                    _ => None,
+                };
+                if let (Some(thrown), Some(catch)) = (thrown, try_stmt.catch_blocks.first()) {
+                    self.push_scope();
+                    if let Some(var) = &catch.error_var {
+                        self.declare(var.clone(), thrown);
+                    }
+                    result = self.exec_block_in_place(&catch.body);
+                    self.pop_scope();
+                }
+                if let Some(finally) = &try_stmt.finally_block {
+                    self.exec_block(finally)?;
+                }
+                result
+            }
+        }
+    }
+
+    fn on_probe(&mut self, id: usize) -> Exec<()> {
+        let Some(site) = self.probes.get(&id).cloned() else {
+            return Ok(());
+        };
+        if let Some(frame) = self.frames.last_mut() {
+            frame.file = site.file.clone();
+            frame.line = site.line;
+        }
+        if !site.statement {
+            return Ok(());
+        }
+        match self.hook.take() {
+            Some(mut hook) => {
+                let result = hook.on_line(self, &site.file, site.line);
+                self.hook = Some(hook);
+                result
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn output(&mut self, text: &str) {
+        match &mut self.hook {
+            Some(hook) => hook.on_output(text),
+            None => println!("{}", text),
+        }
+    }
+
+    // --- Pattern Matching ---
+
+    fn match_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: &Value,
+        bindings: &mut Vec<(String, Value)>,
+    ) -> Exec<bool> {
+        Ok(match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Literal(literal) => literal_value(literal) == *value,
+            Pattern::Identifier(name) => {
+                // Varianten ohne Daten (`None`, `Active`) binden nicht
+                if let Value::Enum { name: variant, .. } = value {
+                    if variant == name || variant.ends_with(&format!("::{}", name)) {
+                        return Ok(true);
+                    }
+                }
+                if name == "None" {
+                    return Ok(matches!(value, Value::Null));
+                }
+                bindings.push((name.clone(), value.clone()));
+                true
+            }
+            Pattern::Tuple(patterns) => {
+                let items = match value {
+                    Value::List(items) => items.clone(),
+                    Value::Enum { data, .. } => data.clone(),
+                    other => vec![other.clone()],
+                };
+                if items.len() != patterns.len() {
+                    return Ok(false);
+                }
+                for (pattern, item) in patterns.iter().zip(items.iter()) {
+                    if !self.match_pattern(pattern, item, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Pattern::Struct { name, fields } => {
+                let Value::Struct {
+                    name: struct_name, ..
+                } = value
+                else {
+                    return Ok(false);
+                };
+                if struct_name != name {
+                    return Ok(false);
+                }
+                for (field, pattern) in fields {
+                    let Some(field_value) = value.field(field) else {
+                        return Ok(false);
+                    };
+                    if !self.match_pattern(pattern, field_value, bindings)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Pattern::EnumVariant { name, data } => {
+                let Value::Enum {
+                    name: variant,
+                    data: values,
+                } = value
+                else {
+                    return Ok(false);
+                };
+                let short = name.rsplit("::").next().unwrap_or(name);
+                if variant != name && variant.rsplit("::").next() != Some(short) {
+                    return Ok(false);
+                }
+                match data {
+                    Some(patterns) => {
+                        if patterns.len() != values.len() {
+                            return Ok(false);
+                        }
+                        for (pattern, item) in patterns.iter().zip(values.iter()) {
+                            if !self.match_pattern(pattern, item, bindings)? {
+                                return Ok(false);
+                            }
+                        }
+                        true
+                    }
+                    None => true,
+                }
+            }
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let (Value::Number(n), Value::Number(lo), Value::Number(hi)) =
+                    (value, self.eval(start)?, self.eval(end)?)
+                else {
+                    return Ok(false);
+                };
+                *n >= lo && if *inclusive { *n <= hi } else { *n < hi }
+            }
+            Pattern::Or(patterns) => {
+                for pattern in patterns {
+                    if self.match_pattern(pattern, value, bindings)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+        })
+    }
+
+    // --- Ausdrücke ---
+
+    fn eval(&mut self, expr: &Expression) -> Exec<Value> {
+        match expr {
+            Expression::Literal(literal) => Ok(literal_value(literal)),
+            Expression::Identifier(name) => self.eval_identifier(name),
+            Expression::BinaryOp { left, op, right } => self.eval_binary(left, op, right),
+            Expression::UnaryOp { op, expr } => {
+                let value = self.eval(expr)?;
+                match (op, value) {
+                    (UnaryOperator::Not, value) => Ok(Value::Bool(!value.is_truthy())),
+                    (UnaryOperator::Minus, Value::Number(n)) => Ok(Value::Number(-n)),
+                    (UnaryOperator::Minus, other) => {
+                        error(format!("Negation von {} nicht möglich", other.type_name()))
+                    }
+                }
+            }
+            Expression::Call { callee, args } => self.eval_call(callee, args),
+            Expression::Member { object, member } => self.eval_member(object, member),
+            Expression::Index { object, index } => {
+                let object = self.eval(object)?;
+                let index = self.eval(index)?;
+                index_value(&object, &index)
+            }
+            Expression::If {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                if self.eval(condition)?.is_truthy() {
+                    self.eval(then_expr)
+                } else {
+                    self.eval(else_expr)
+                }
+            }
+            Expression::Block(block) => self
+                .exec_block(block)
+                .map(|value| value.unwrap_or(Value::Null)),
+            Expression::Await { expr } => self.eval(expr),
+            Expression::StructLiteral { name, fields } => {
+                let mut values = Vec::new();
+                for (field, expr) in fields {
+                    values.push((field.clone(), self.eval(expr)?));
+                }
+                Ok(Value::Struct {
+                    name: name.clone(),
+                    fields: values,
+                })
+            }
+            Expression::MapLiteral(fields) => {
+                let mut values = Vec::new();
+                for (key, expr) in fields {
+                    values.push((key.clone(), self.eval(expr)?));
+                }
+                Ok(Value::Map(values))
+            }
+            Expression::ListLiteral(items) => {
+                let mut values = Vec::new();
+                for item in items {
+                    values.push(self.eval(item)?);
+                }
+                Ok(Value::List(values))
+            }
+            Expression::GenericConstructor { name, args, .. } => match name.as_str() {
+                "List" | "Vec" => Ok(Value::List(Vec::new())),
+                "Map" | "HashMap" => Ok(Value::Map(Vec::new())),
+                _ => {
+                    let mut values = Vec::new();
+                    for arg in args {
+                        values.push(self.eval(arg)?);
+                    }
+                    self.call_named(name, values, None)
+                }
+            },
+            Expression::Lambda { params, body, .. } => {
+                let captured = match self.frame_index() {
+                    Some(index) => self.frames[index].variables(),
+                    None => Vec::new(),
+                };
+                Ok(Value::Lambda(Arc::new(Lambda {
+                    params: params.clone(),
+                    body: (**body).clone(),
+                    captured,
+                })))
+            }
+            Expression::Assignment { target, value } => {
+                let value = self.eval(value)?;
+                self.assign(target, value.clone())?;
+                Ok(value)
+            }
+            Expression::FormatString { parts } => {
+                let mut text = String::new();
+                for part in parts {
+                    match part {
+                        FormatStringPart::Text(t) => text.push_str(t),
+                        FormatStringPart::Expression(expr) => {
+                            text.push_str(&self.eval(expr)?.to_string())
+                        }
+                    }
+                }
+                Ok(Value::String(text))
+            }
+            Expression::LLMCall { method, .. } => error(format!(
+                "@llm.{} wird im Interpreter nicht ausgeführt",
+                method
+            )),
+        }
+    }
+
+    fn eval_identifier(&mut self, name: &str) -> Exec<Value> {
+        if let Some(value) = self.lookup(name) {
+            return Ok(value);
+        }
+        if self.functions.contains_key(name) {
+            return Ok(Value::Function(name.to_string()));
+        }
+        match name {
+            "None" => Ok(Value::Enum {
+                name: "None".to_string(),
+                data: Vec::new(),
+            }),
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "null" => Ok(Value::Null),
+            _ => error(format!("Unbekannte Variable: {}", name)),
+        }
+    }
+
+    fn eval_binary(
+        &mut self,
+        left: &Expression,
+        op: &BinaryOperator,
+        right: &Expression,
+    ) -> Exec<Value> {
+        let left = self.eval(left)?;
+        match op {
+            BinaryOperator::And if !left.is_truthy() => return Ok(Value::Bool(false)),
+            BinaryOperator::Or if left.is_truthy() => return Ok(Value::Bool(true)),
+            _ => {}
+        }
+        let right = self.eval(right)?;
+        binary_op(op, left, right)
+    }
+
+    fn eval_member(&mut self, object: &Expression, member: &str) -> Exec<Value> {
+        if let Expression::Identifier(name) = object {
+            if self.lookup(name).is_none() {
+                // Enum-Variante oder qualifizierte Funktion (`Status.Active`, `models.load`)
+                if let Some(variants) = self.enums.get(name) {
+                    if variants.iter().any(|v| v == member) {
+                        return Ok(Value::Enum {
+                            name: format!("{}::{}", name, member),
+                            data: Vec::new(),
+                        });
+                    }
+                }
+                let qualified = format!("{}.{}", name, member);
+                if self.functions.contains_key(&qualified) {
+                    return Ok(Value::Function(qualified));
+                }
+            }
+        }
+        let value = self.eval(object)?;
+        member_value(&value, member)
+    }
+
+    fn eval_call(&mut self, callee: &Expression, args: &[Expression]) -> Exec<Value> {
+        let mut values = Vec::new();
+        for arg in args {
+            values.push(self.eval(arg)?);
+        }
+
+        match callee {
+            Expression::Identifier(name) => self.call_named(name, values, None),
+            Expression::Member { object, member } => {
+                // Logging und statische/Modul-Funktionen
+                if let Expression::Identifier(name) = object.as_ref() {
+                    if self.lookup(name).is_none() {
+                        if name == "log" || name == "console" {
+                            let text: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                            self.output(&text.join(" "));
+                            return Ok(Value::Null);
+                        }
+                        if let Some(variants) = self.enums.get(name) {
+                            if variants.iter().any(|v| v == member) {
+                                return Ok(Value::Enum {
+                                    name: format!("{}::{}", name, member),
+                                    data: values,
+                                });
+                            }
+                        }
+                        let qualified = format!("{}.{}", name, member);
+                        if self.functions.contains_key(&qualified) {
+                            return self.call_named(&qualified, values, None);
+                        }
+                    }
+                }
+
+                let receiver = self.eval(object)?;
+                // Benutzerdefinierte Methode auf einem Struct
+                if let Value::Struct { name, .. } = &receiver {
+                    let qualified = format!("{}.{}", name, member);
+                    if self.functions.contains_key(&qualified) {
+                        self.last_receiver = None;
+                        let result = self.call_named(&qualified, values, Some(receiver))?;
+                        if let Some(updated) = self.last_receiver.take() {
+                            if is_place(object) {
+                                self.assign(object, updated)?;
+                            }
+                        }
+                        return Ok(result);
+                    }
+                }
+                // Feld mit Funktionswert
+                if let Some(field @ (Value::Lambda(_) | Value::Function(_))) =
+                    receiver.field(member).cloned().as_ref()
+                {
+                    return self.call_value(field, values);
+                }
+
+                let mut receiver = receiver;
+                let (result, mutated) = self.builtin_method(&mut receiver, member, values)?;
+                if mutated && is_place(object) {
+                    self.assign(object, receiver)?;
+                }
+                Ok(result)
+            }
+            other => {
+                let callee = self.eval(other)?;
+                self.call_value(&callee, values)
+            }
+        }
+    }
+
+    fn call_named(&mut self, name: &str, args: Vec<Value>, receiver: Option<Value>) -> Exec<Value> {
+        if name == PROBE_FUNCTION {
+            if let Some(Value::Number(id)) = args.first() {
+                self.on_probe(*id as usize)?;
+            }
+            return Ok(Value::Null);
+        }
+        if let Some(value) = self.lookup(name) {
+            return self.call_value(&value, args);
+        }
+        if let Some(function) = self.functions.get(name).cloned() {
+            return self.call_function(&function, name.to_string(), args, receiver);
+        }
+        self.builtin_function(name, args)
+    }
+
+    fn builtin_function(&mut self, name: &str, args: Vec<Value>) -> Exec<Value> {
+        match name {
+            "print" | "println" => {
+                let text: Vec<String> = args.iter().map(|v| v.to_string()).collect();
+                self.output(&text.join(" "));
+                Ok(Value::Null)
+            }
+            "Some" | "Ok" | "Err" => Ok(Value::Enum {
+                name: name.to_string(),
+                data: args,
+            }),
+            "len" | "length" => match args.first() {
+                Some(value) => length(value),
+                None => error("len() erwartet ein Argument"),
+            },
+            "toString" | "string" => Ok(Value::String(
+                args.first().map(|v| v.to_string()).unwrap_or_default(),
+            )),
+            "parseNumber" | "number" => match args.first() {
+                Some(Value::Number(n)) => Ok(Value::Number(*n)),
+                Some(Value::String(s)) => s
+                    .trim()
+                    .parse::<f64>()
+                    .map(Value::Number)
+                    .or_else(|_| error(format!("Keine Zahl: {:?}", s))),
+                _ => error("number() erwartet einen String"),
+            },
+            "assert" => match args.first() {
+                Some(value) if value.is_truthy() => Ok(Value::Null),
+                _ => error("Assertion fehlgeschlagen"),
+            },
+            "assertEquals" | "assert_eq" => match (args.first(), args.get(1)) {
+                (Some(a), Some(b)) if a == b => Ok(Value::Null),
+                (Some(a), Some(b)) => error(format!(
+                    "Assertion fehlgeschlagen: {} != {}",
+                    a.repr(),
+                    b.repr()
+                )),
+                _ => error("assertEquals erwartet zwei Argumente"),
+            },
+            _ => error(format!("Unbekannte Funktion: {}", name)),
+        }
+    }
+
+    /// Eingebaute Methoden auf Strings, Listen und Maps.
+    /// Liefert zusätzlich, ob der Empfänger verändert wurde (`push`, `set`, …).
+    fn builtin_method(
+        &mut self,
+        receiver: &mut Value,
+        method: &str,
+        args: Vec<Value>,
+    ) -> Exec<(Value, bool)> {
+        let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Null);
+        let unchanged = |value: Value| Ok((value, false));
+
+        match receiver {
+            Value::String(s) => match method {
+                "length" | "len" => unchanged(Value::Number(s.chars().count() as f64)),
+                "toUpperCase" | "to_upper" => unchanged(Value::String(s.to_uppercase())),
+                "toLowerCase" | "to_lower" => unchanged(Value::String(s.to_lowercase())),
+                "trim" => unchanged(Value::String(s.trim().to_string())),
+                "isEmpty" => unchanged(Value::Bool(s.is_empty())),
+                "contains" => unchanged(Value::Bool(s.contains(&arg(0).to_string()))),
+                "startsWith" | "starts_with" => {
+                    unchanged(Value::Bool(s.starts_with(&arg(0).to_string())))
+                }
+                "endsWith" | "ends_with" => {
+                    unchanged(Value::Bool(s.ends_with(&arg(0).to_string())))
+                }
+                "replace" => unchanged(Value::String(
+                    s.replace(&arg(0).to_string(), &arg(1).to_string()),
+                )),
+                "split" => unchanged(Value::List(
+                    s.split(&arg(0).to_string())
+                        .map(|p| Value::String(p.to_string()))
+                        .collect(),
+                )),
+                "toString" => unchanged(Value::String(s.clone())),
+                _ => error(format!("Unbekannte String-Methode: {}", method)),
+            },
+            Value::List(items) => match method {
+                "length" | "len" | "size" => unchanged(Value::Number(items.len() as f64)),
+                "isEmpty" => unchanged(Value::Bool(items.is_empty())),
+                "push" | "add" | "append" => {
+                    items.push(arg(0));
+                    Ok((Value::Null, true))
+                }
+                "pop" => {
+                    let value = items.pop().unwrap_or(Value::Null);
+                    Ok((value, true))
+                }
+                "get" => unchanged(index_value(&Value::List(items.clone()), &arg(0))?),
+                "first" => unchanged(items.first().cloned().unwrap_or(Value::Null)),
+                "last" => unchanged(items.last().cloned().unwrap_or(Value::Null)),
+                "contains" => unchanged(Value::Bool(items.contains(&arg(0)))),
+                "join" => unchanged(Value::String(
+                    items
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(&arg(0).to_string()),
+                )),
+                "map" => {
+                    let f = arg(0);
+                    let mut result = Vec::new();
+                    for item in items.clone() {
+                        result.push(self.call_value(&f, vec![item])?);
+                    }
+                    unchanged(Value::List(result))
+                }
+                "filter" => {
+                    let f = arg(0);
+                    let mut result = Vec::new();
+                    for item in items.clone() {
+                        if self.call_value(&f, vec![item.clone()])?.is_truthy() {
+                            result.push(item);
+                        }
+                    }
+                    unchanged(Value::List(result))
+                }
+                "forEach" | "for_each" => {
+                    let f = arg(0);
+                    for item in items.clone() {
+                        self.call_value(&f, vec![item])?;
+                    }
+                    unchanged(Value::Null)
+                }
+                "reduce" => {
+                    let f = arg(0);
+                    let mut acc = arg(1);
+                    for item in items.clone() {
+                        acc = self.call_value(&f, vec![acc, item])?;
+                    }
+                    unchanged(acc)
+                }
+                "sum" => {
+                    let mut total = 0.0;
+                    for item in items.iter() {
+                        if let Value::Number(n) = item {
+                            total += n;
+                        }
+                    }
+                    unchanged(Value::Number(total))
+                }
+                _ => error(format!("Unbekannte List-Methode: {}", method)),
+            },
+            Value::Map(entries) => match method {
+                "length" | "len" | "size" => unchanged(Value::Number(entries.len() as f64)),
+                "isEmpty" => unchanged(Value::Bool(entries.is_empty())),
+                "get" => {
+                    let key = arg(0).to_string();
+                    unchanged(
+                        entries
+                            .iter()
+                            .find(|(k, _)| *k == key)
+                            .map(|(_, v)| v.clone())
+                            .unwrap_or(Value::Null),
+                    )
+                }
+                "set" | "insert" => {
+                    let key = arg(0).to_string();
+                    let value = arg(1);
+                    match entries.iter_mut().find(|(k, _)| *k == key) {
+                        Some(entry) => entry.1 = value,
+                        None => entries.push((key, value)),
+                    }
+                    Ok((Value::Null, true))
+                }
+                "remove" => {
+                    let key = arg(0).to_string();
+                    let removed = entries
+                        .iter()
+                        .position(|(k, _)| *k == key)
+                        .map(|i| entries.remove(i).1)
+                        .unwrap_or(Value::Null);
+                    Ok((removed, true))
+                }
+                "contains" | "containsKey" | "has" => {
+                    let key = arg(0).to_string();
+                    unchanged(Value::Bool(entries.iter().any(|(k, _)| *k == key)))
+                }
+                "keys" => unchanged(Value::List(
+                    entries
+                        .iter()
+                        .map(|(k, _)| Value::String(k.clone()))
+                        .collect(),
+                )),
+                "values" => unchanged(Value::List(
+                    entries.iter().map(|(_, v)| v.clone()).collect(),
+                )),
+                _ => error(format!("Unbekannte Map-Methode: {}", method)),
+            },
+            Value::Enum { name, data } => match method {
+                "isSome" | "isOk" => unchanged(Value::Bool(name == "Some" || name == "Ok")),
+                "isNone" | "isErr" => unchanged(Value::Bool(name == "None" || name == "Err")),
+                "unwrap" => match (name.as_str(), data.first()) {
+                    ("Some" | "Ok", Some(value)) => unchanged(value.clone()),
+                    _ => error(format!("unwrap() auf {}", name)),
+                },
+                "unwrapOr" | "unwrap_or" => match (name.as_str(), data.first()) {
+                    ("Some" | "Ok", Some(value)) => unchanged(value.clone()),
+                    _ => unchanged(arg(0)),
+                },
+                _ => error(format!("Unbekannte Methode {} auf {}", method, name)),
+            },
+            Value::Number(n) => match method {
+                "toString" => unchanged(Value::String(Value::Number(*n).to_string())),
+                "abs" => unchanged(Value::Number(n.abs())),
+                "floor" => unchanged(Value::Number(n.floor())),
+                "ceil" => unchanged(Value::Number(n.ceil())),
+                "round" => unchanged(Value::Number(n.round())),
+                _ => error(format!("Unbekannte Number-Methode: {}", method)),
+            },
+            other => error(format!(
+                "Unbekannte Methode {} auf {}",
+                method,
+                other.type_name()
+            )),
+        }
+    }
+
+    // --- Zuweisungen ---
+
+    fn assign(&mut self, target: &Expression, value: Value) -> Exec<()> {
+        let (root, path) = place_path(target)
+            .ok_or_else(|| Control::Error("Ungültiges Zuweisungsziel".to_string()))?;
+        let mut indices = Vec::new();
+        for step in &path {
+            indices.push(match step {
+                PlaceStep::Field(name) => PathIndex::Field(name.clone()),
+                PlaceStep::Index(expr) => PathIndex::Index(self.eval(expr)?),
+            });
+        }
+        let slot = match self.variable_mut(&root) {
+            Some(slot) => slot,
+            None => return error(format!("Unbekannte Variable: {}", root)),
+        };
+        let mut current = slot;
+        for index in indices {
+            current = match (index, current) {
+                (PathIndex::Field(name), target @ (Value::Struct { .. } | Value::Map(_))) => {
+                    if target.field(&name).is_none() {
+                        if let Value::Map(entries) = target {
+                            entries.push((name.clone(), Value::Null));
+                        } else {
+                            return error(format!("Unbekanntes Feld: {}", name));
+                        }
+                    }
+                    target.field_mut(&name).expect("Feld existiert")
+                }
+                (PathIndex::Index(Value::Number(i)), Value::List(items)) => {
+                    let len = items.len();
+                    items.get_mut(i as usize).ok_or_else(|| {
+                        Control::Error(format!("Index {} außerhalb der Liste (Länge {})", i, len))
+                    })?
+                }
+                (PathIndex::Index(key), target @ Value::Map(_)) => {
+                    let key = key.to_string();
+                    if target.field(&key).is_none() {
+                        if let Value::Map(entries) = target {
+                            entries.push((key.clone(), Value::Null));
+                        }
+                    }
+                    target.field_mut(&key).expect("Schlüssel existiert")
+                }
+                (_, other) => {
+                    return error(format!("Zuweisung in {} nicht möglich", other.type_name()))
+                }
+            };
+        }
+        *current = value;
+        Ok(())
+    }
+}
+
+enum PlaceStep<'a> {
+    Field(String),
+    Index(&'a Expression),
+}
+
+enum PathIndex {
+    Field(String),
+    Index(Value),
+}
+
+fn place_path(expr: &Expression) -> Option<(String, Vec<PlaceStep<'_>>)> {
+    match expr {
+        Expression::Identifier(name) => Some((name.clone(), Vec::new())),
+        Expression::Member { object, member } => {
+            let (root, mut path) = place_path(object)?;
+            path.push(PlaceStep::Field(member.clone()));
+            Some((root, path))
+        }
+        Expression::Index { object, index } => {
+            let (root, mut path) = place_path(object)?;
+            path.push(PlaceStep::Index(index));
+            Some((root, path))
+        }
+        _ => None,
+    }
+}
+
+fn is_place(expr: &Expression) -> bool {
+    place_path(expr).is_some()
+}
+
+fn probe_id(expr: &Expression) -> Option<usize> {
+    if let Expression::Call { callee, args } = expr {
+        if let (Expression::Identifier(name), [Expression::Literal(Literal::Number(id))]) =
+            (callee.as_ref(), args.as_slice())
+        {
+            if name == PROBE_FUNCTION {
+                return Some(*id as usize);
+            }
+        }
+    }
+    None
+}
+
+fn literal_value(literal: &Literal) -> Value {
+    match literal {
+        Literal::String(s) => Value::String(s.clone()),
+        Literal::Number(n) => Value::Number(*n),
+        Literal::Boolean(b) => Value::Bool(*b),
+        Literal::Null => Value::Null,
+    }
+}
+
+fn length(value: &Value) -> Exec<Value> {
+    match value {
+        Value::String(s) => Ok(Value::Number(s.chars().count() as f64)),
+        Value::List(items) => Ok(Value::Number(items.len() as f64)),
+        Value::Map(entries) => Ok(Value::Number(entries.len() as f64)),
+        other => error(format!("{} hat keine Länge", other.type_name())),
+    }
+}
+
+fn member_value(value: &Value, member: &str) -> Exec<Value> {
+    if let Some(field) = value.field(member) {
+        return Ok(field.clone());
+    }
+    match (value, member) {
+        (Value::String(_) | Value::List(_) | Value::Map(_), "length" | "len" | "size") => {
+            length(value)
+        }
+        (Value::Map(_), _) => Ok(Value::Null),
+        _ => error(format!("{} hat kein Feld '{}'", value.type_name(), member)),
+    }
+}
+
+fn index_value(object: &Value, index: &Value) -> Exec<Value> {
+    match (object, index) {
+        (Value::List(items), Value::Number(i)) => {
+            items.get(*i as usize).cloned().ok_or_else(|| {
+                Control::Error(format!(
+                    "Index {} außerhalb der Liste (Länge {})",
+                    i,
+                    items.len()
+                ))
+            })
+        }
+        (Value::String(s), Value::Number(i)) => s
+            .chars()
+            .nth(*i as usize)
+            .map(|c| Value::String(c.to_string()))
+            .ok_or_else(|| Control::Error(format!("Index {} außerhalb des Strings", i))),
+        (Value::Map(_) | Value::Struct { .. }, key) => Ok(object
+            .field(&key.to_string())
+            .cloned()
+            .unwrap_or(Value::Null)),
+        (other, _) => error(format!("{} ist nicht indizierbar", other.type_name())),
+    }
+}
+
+fn binary_op(op: &BinaryOperator, left: Value, right: Value) -> Exec<Value> {
+    use BinaryOperator::*;
+    match (op, &left, &right) {
+        (Add, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+        (Add, Value::String(_), _) | (Add, _, Value::String(_)) => {
+            Ok(Value::String(format!("{}{}", left, right)))
+        }
+        (Add, Value::List(a), Value::List(b)) => {
+            Ok(Value::List(a.iter().chain(b.iter()).cloned().collect()))
+        }
+        (Subtract, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
+        (Multiply, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a * b)),
+        (Divide, Value::Number(_), Value::Number(b)) if *b == 0.0 => error("Division durch 0"),
+        (Divide, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a / b)),
+        (Modulo, Value::Number(a), Value::Number(b)) => Ok(Value::Number(a % b)),
+        (Eq, _, _) => Ok(Value::Bool(left == right)),
+        (NotEq, _, _) => Ok(Value::Bool(left != right)),
+        (Lt | Gt | LtEq | GtEq, Value::Number(a), Value::Number(b)) => {
+            Ok(Value::Bool(compare(op, a.partial_cmp(b))))
+        }
+        (Lt | Gt | LtEq | GtEq, Value::String(a), Value::String(b)) => {
+            Ok(Value::Bool(compare(op, Some(a.cmp(b)))))
+        }
+        (And, _, _) => Ok(Value::Bool(left.is_truthy() && right.is_truthy())),
+        (Or, _, _) => Ok(Value::Bool(left.is_truthy() || right.is_truthy())),
+        (In, _, Value::List(items)) => Ok(Value::Bool(items.contains(&left))),
+        (In, _, Value::Map(_)) => Ok(Value::Bool(right.field(&left.to_string()).is_some())),
+        (In, Value::String(needle), Value::String(haystack)) => {
+            Ok(Value::Bool(haystack.contains(needle.as_str())))
+        }
+        _ => error(format!(
+            "Operator {:?} nicht anwendbar auf {} und {}",
+            op,
+            left.type_name(),
+            right.type_name()
+        )),
+    }
+}
+
+fn compare(op: &BinaryOperator, ordering: Option<std::cmp::Ordering>) -> bool {
+    use std::cmp::Ordering::*;
+    matches!(
+        (op, ordering),
+        (BinaryOperator::Lt, Some(Less))
+            | (BinaryOperator::Gt, Some(Greater))
+            | (BinaryOperator::LtEq, Some(Less | Equal))
+            | (BinaryOperator::GtEq, Some(Greater | Equal))
+    )
+}
+
+/// Variablenname einer Init-Funktion (`__init_x` bzw. `modul.__init_x`)
+fn global_of(function: &str) -> Option<&str> {
+    function.rsplit('.').next()?.strip_prefix("__init_")
+}
+
+/// Parst einen einzelnen Ausdruck (für `evaluate` und Breakpoint-Bedingungen)
+pub fn parse_expression(source: &str) -> Result<Expression, String> {
+    let wrapped = format!(
+        "fn __velin_dbg_eval() {{\n    return {};\n}}\n",
+        source.trim()
+    );
+    let program = Parser::parse(&wrapped).map_err(|e| e.to_string())?;
+    match program.items.first() {
+        Some(Item::Function(function)) => match function.body.statements.first() {
+            Some(Statement::Return(ReturnStatement { value: Some(expr) })) => Ok(expr.clone()),
+            _ => Err(format!("Kein gültiger Ausdruck: {}", source)),
+        },
+        _ => Err(format!("Kein gültiger Ausdruck: {}", source)),
+    }
+}
+
+/// Menschenlesbare Beschreibung eines nicht behandelten Kontrollflusses
+pub fn describe(control: Control) -> String {
+    match control {
+        Control::Return(_) | Control::Break => "Unerwarteter Kontrollfluss".to_string(),
+        Control::Throw(value) => format!("Unbehandelte Exception: {}", value),
+        Control::Error(message) => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::{config::CompilerConfig, VelinCompiler};
+    use crate::passes::{coverage::CoverageInstrumentationPass, parser::ParserPass};
+    use std::sync::Mutex;
+
+    /// Zeichnet pro Zeile den Call-Stack und die sichtbaren Variablen auf
+    #[derive(Clone, Default)]
+    struct Recorder {
+        lines: Arc<Mutex<Vec<(usize, Vec<String>, Vec<String>)>>>,
+        output: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl DebugHook for Recorder {
+        fn on_line(&mut self, interpreter: &mut Interpreter, _file: &str, line: usize) -> Exec<()> {
+            let frames = interpreter.frames();
+            let stack = frames.iter().map(|frame| frame.name.clone()).collect();
+            let variables = frames
+                .last()
+                .map(|frame| {
+                    frame
+                        .variables()
+                        .into_iter()
+                        .map(|(name, _)| name)
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.lines.lock().unwrap().push((line, stack, variables));
+            Ok(())
+        }
+
+        fn on_output(&mut self, text: &str) {
+            self.output.lock().unwrap().push(text.to_string());
+        }
+    }
+
+    fn interpreter(source: &str) -> Interpreter {
+        let mut compiler = VelinCompiler::new(CompilerConfig::default());
+        compiler.add_pass(Box::new(ParserPass::new()));
+        compiler.add_pass(Box::new(CoverageInstrumentationPass::new()));
+        let context = compiler
+            .compile("main.velin".to_string(), source.to_string())
+            .unwrap();
+        assert!(!context.has_errors(), "{:?}", context.errors);
+        Interpreter::new(
+            context.program.unwrap(),
+            &context.coverage_maps,
+            "main.velin".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_reports_lines_frames_and_block_scopes() {
+        let mut interpreter = interpreter(
+            "fn double(x: number): number {
+    let y = x * 2;
+    return y;
+}
+
+fn main(): void {
+    let a = double(2);
+    if (a == 4) {
+        let b = a + 1;
+        print(b);
+    }
+    print(a);
+}
+",
+        );
+        let recorder = Recorder::default();
+        interpreter.set_hook(Box::new(recorder.clone()));
+        interpreter.run_main().unwrap();
+
+        let lines = recorder.lines.lock().unwrap();
+        let summary: Vec<(usize, usize)> = lines
+            .iter()
+            .map(|(line, stack, _)| (*line, stack.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(7, 1), (2, 2), (3, 2), (8, 1), (9, 1), (10, 1), (12, 1)]
+        );
+        assert_eq!(lines[2].1, vec!["main", "double"]);
+        // Der Aufgerufene sieht nur seine Parameter und Locals
+        assert_eq!(lines[2].2, vec!["x", "y"]);
+        // `b` ist nur innerhalb des `if`-Blocks sichtbar
+        assert_eq!(lines[5].2, vec!["a", "b"]);
+        assert_eq!(lines[6].2, vec!["a"]);
+        assert_eq!(*recorder.output.lock().unwrap(), vec!["5", "4"]);
+    }
+
+    #[test]
+    fn test_evaluate_resolves_variables_per_frame() {
+        struct Evaluator(Arc<Mutex<Vec<String>>>);
+
+        impl DebugHook for Evaluator {
+            fn on_line(
+                &mut self,
+                interpreter: &mut Interpreter,
+                _file: &str,
+                line: usize,
+            ) -> Exec<()> {
+                if line == 3 {
+                    let inner = interpreter.evaluate("y + 1", 0).unwrap();
+                    let outer = interpreter.evaluate("a", 1).unwrap_err();
+                    self.0.lock().unwrap().push(inner.repr());
+                    self.0.lock().unwrap().push(outer);
+                }
+                Ok(())
+            }
+
+            fn on_output(&mut self, _text: &str) {}
+        }
+
+        let mut interpreter = interpreter(
+            "fn double(x: number): number {
+    let y = x * 2;
+    return y;
+}
+
+fn main(): void {
+    let a = double(2);
+}
+",
+        );
+        let results = Arc::new(Mutex::new(Vec::new()));
+        interpreter.set_hook(Box::new(Evaluator(Arc::clone(&results))));
+        interpreter.run_main().unwrap();
+
+        let results = results.lock().unwrap();
+        assert_eq!(results[0], "5");
+        // `a` wird erst nach der Rückkehr von `double` deklariert
+        assert!(results[1].contains('a'), "{}", results[1]);
+    }
+}
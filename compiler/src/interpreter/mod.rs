@@ -0,0 +1,8 @@
+// AST-Interpreter für Velin-Programme
+// Gemeinsame Ausführungsschicht für Debugger und Dev-Server (Hot Reload)
+
+pub mod eval;
+pub mod value;
+
+pub use eval::{describe, parse_expression, Control, DebugHook, Frame, Interpreter};
+pub use value::{Lambda, Value};
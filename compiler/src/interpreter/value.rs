@@ -0,0 +1,269 @@
+// Laufzeitwerte des AST-Interpreters
+
+use crate::parser::ast::{Expression, Parameter};
+use std::fmt;
+use std::sync::Arc;
+
+/// Closure: Parameter, Body und die beim Anlegen sichtbaren Variablen
+#[derive(Debug)]
+pub struct Lambda {
+    pub params: Vec<Parameter>,
+    pub body: Expression,
+    pub captured: Vec<(String, Value)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    List(Vec<Value>),
+    /// Map mit stabiler Einfüge-Reihenfolge
+    Map(Vec<(String, Value)>),
+    Struct {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+    /// Enum-Variante (`Status::Active`) bzw. `Some`/`None`/`Ok`/`Err`
+    Enum {
+        name: String,
+        data: Vec<Value>,
+    },
+    /// Referenz auf eine benannte Funktion
+    Function(String),
+    Lambda(Arc<Lambda>),
+}
+
+impl Value {
+    pub fn type_name(&self) -> String {
+        match self {
+            Value::Null => "null".to_string(),
+            Value::Bool(_) => "boolean".to_string(),
+            Value::Number(_) => "number".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::List(_) => "List".to_string(),
+            Value::Map(_) => "Map".to_string(),
+            Value::Struct { name, .. } => name.clone(),
+            Value::Enum { name, .. } => match name.split_once("::") {
+                Some((enum_name, _)) => enum_name.to_string(),
+                None => name.clone(),
+            },
+            Value::Function(_) | Value::Lambda(_) => "fn".to_string(),
+        }
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Bool(b) => *b,
+            Value::Number(n) => *n != 0.0,
+            Value::String(s) => !s.is_empty(),
+            Value::Enum { name, .. } => name != "None",
+            _ => true,
+        }
+    }
+
+    /// Kindelemente für die Variablen-Ansicht; leer bei skalaren Werten
+    pub fn children(&self) -> Vec<(String, Value)> {
+        match self {
+            Value::List(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (format!("[{}]", i), v.clone()))
+                .collect(),
+            Value::Map(entries)
+            | Value::Struct {
+                fields: entries, ..
+            } => entries.clone(),
+            Value::Enum { data, .. } => data
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i.to_string(), v.clone()))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Darstellung für Variablen und `evaluate` (Strings in Anführungszeichen)
+    pub fn repr(&self) -> String {
+        match self {
+            Value::String(s) => format!("{:?}", s),
+            Value::List(items) => format!("List[{}]", items.len()),
+            Value::Map(entries) => format!("Map{{{}}}", entries.len()),
+            Value::Struct { name, fields } => {
+                let preview: Vec<String> = fields
+                    .iter()
+                    .take(3)
+                    .map(|(k, v)| format!("{}: {}", k, v.short_repr()))
+                    .collect();
+                let more = if fields.len() > 3 { ", …" } else { "" };
+                format!("{} {{ {}{} }}", name, preview.join(", "), more)
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    fn short_repr(&self) -> String {
+        match self {
+            Value::List(_) | Value::Map(_) | Value::Struct { .. } => self.type_name(),
+            _ => self.repr(),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Value::Map(entries)
+            | Value::Struct {
+                fields: entries, ..
+            } => entries.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut Value> {
+        match self {
+            Value::Map(entries)
+            | Value::Struct {
+                fields: entries, ..
+            } => entries.iter_mut().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// JSON-Darstellung wie im generierten Code: `Some(x)`/`Ok(x)` → `x`,
+    /// `None` → `null`, Enum-Varianten ohne Daten als Variantenname
+    pub fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            Value::Null => Json::Null,
+            Value::Bool(b) => Json::Bool(*b),
+            // Ganzzahlige Werte als JSON-Integer (`3` statt `3.0`)
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 9.0e15 => Json::from(*n as i64),
+            Value::Number(n) => serde_json::Number::from_f64(*n)
+                .map(Json::Number)
+                .unwrap_or(Json::Null),
+            Value::String(s) => Json::String(s.clone()),
+            Value::List(items) => Json::Array(items.iter().map(Value::to_json).collect()),
+            Value::Map(entries)
+            | Value::Struct {
+                fields: entries, ..
+            } => Json::Object(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.to_json()))
+                    .collect(),
+            ),
+            Value::Enum { name, data } => match (name.as_str(), data.as_slice()) {
+                ("None", []) => Json::Null,
+                ("Some" | "Ok", [value]) => value.to_json(),
+                (_, []) => Json::String(name.rsplit("::").next().unwrap_or(name).to_string()),
+                (_, [value]) => value.to_json(),
+                (_, values) => Json::Array(values.iter().map(Value::to_json).collect()),
+            },
+            Value::Function(name) => Json::String(format!("fn {}", name)),
+            Value::Lambda(_) => Json::String("fn <lambda>".to_string()),
+        }
+    }
+
+    /// Objekte werden zu Maps; `into_struct` macht daraus einen Struct-Wert
+    pub fn from_json(json: &serde_json::Value) -> Value {
+        use serde_json::Value as Json;
+        match json {
+            Json::Null => Value::Null,
+            Json::Bool(b) => Value::Bool(*b),
+            Json::Number(n) => Value::Number(n.as_f64().unwrap_or(0.0)),
+            Json::String(s) => Value::String(s.clone()),
+            Json::Array(items) => Value::List(items.iter().map(Value::from_json).collect()),
+            Json::Object(entries) => Value::Map(
+                entries
+                    .iter()
+                    .map(|(k, v)| (k.clone(), Value::from_json(v)))
+                    .collect(),
+            ),
+        }
+    }
+
+    pub fn into_struct(self, name: &str) -> Value {
+        match self {
+            Value::Map(fields) => Value::Struct {
+                name: name.to_string(),
+                fields,
+            },
+            other => other,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::List(a), Value::List(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            (
+                Value::Struct {
+                    name: n1,
+                    fields: f1,
+                },
+                Value::Struct {
+                    name: n2,
+                    fields: f2,
+                },
+            ) => n1 == n2 && f1 == f2,
+            (Value::Enum { name: n1, data: d1 }, Value::Enum { name: n2, data: d2 }) => {
+                n1 == n2 && d1 == d2
+            }
+            (Value::Function(a), Value::Function(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) => {
+                if n.fract() == 0.0 && n.abs() < 1e15 {
+                    write!(f, "{}", *n as i64)
+                } else {
+                    write!(f, "{}", n)
+                }
+            }
+            Value::String(s) => write!(f, "{}", s),
+            Value::List(items) => {
+                let items: Vec<String> = items.iter().map(|v| v.repr()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+            Value::Map(entries) => {
+                let entries: Vec<String> = entries
+                    .iter()
+                    .map(|(k, v)| format!("{:?}: {}", k, v.repr()))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))
+            }
+            Value::Struct { name, fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.repr()))
+                    .collect();
+                write!(f, "{} {{ {} }}", name, fields.join(", "))
+            }
+            Value::Enum { name, data } => {
+                if data.is_empty() {
+                    write!(f, "{}", name)
+                } else {
+                    let data: Vec<String> = data.iter().map(|v| v.repr()).collect();
+                    write!(f, "{}({})", name, data.join(", "))
+                }
+            }
+            Value::Function(name) => write!(f, "fn {}", name),
+            Value::Lambda(_) => write!(f, "fn <lambda>"),
+        }
+    }
+}
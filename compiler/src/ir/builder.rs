@@ -972,6 +972,8 @@ impl IRBuilder {
                 ok: Box::new(self.ast_type_to_ir(ok)),
                 err: Box::new(self.ast_type_to_ir(err)),
             },
+            // Streams erzwingen den AST-Codegen, das IR kennt sie nicht
+            Type::Stream(_) => IRType::Any,
         }
     }
 
@@ -992,6 +994,10 @@ impl IRBuilder {
                             // Named arguments werden als Identifier behandelt
                             IRAttributeArg::Identifier(name.clone())
                         }
+                        DecoratorArg::List(_) => {
+                            // Listen haben keine IR-Entsprechung
+                            IRAttributeArg::Identifier("list".to_string())
+                        }
                     })
                     .collect();
 
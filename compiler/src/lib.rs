@@ -4,12 +4,19 @@ pub mod borrow;
 pub mod cli;
 pub mod codegen;
 pub mod compiler;
+pub mod coverage;
 pub mod error;
 pub mod formatter;
+pub mod inspector;
+pub mod interpreter;
 pub mod ir;
+pub mod migrations;
 pub mod optimizer;
 pub mod parser;
 pub mod passes;
+pub mod privacy;
+pub mod profiler;
 pub mod prompt;
 pub mod stdlib;
 pub mod type_checker;
+pub mod validation;
@@ -3,14 +3,27 @@ use clap::Parser as ClapParser;
 use std::fs;
 use std::path::PathBuf;
 use std::str::FromStr;
-use velin_compiler::cli::{Cli, Commands};
+use velin_compiler::cli::{AuditCommands, Cli, Commands, MigrateCommands};
 use velin_compiler::codegen::{
-    BoilerplateGenerator, ClientGenerator, OpenAPIGenerator, TargetLanguage,
+    asyncapi, client,
+    openapi::{self, OpenAPIFormat},
+    openapi_import,
+    wasm::default_package_dir,
+    BoilerplateGenerator, ClientGenerator, DeploymentAnalyzer, DeploymentType, OpenAPIGenerator,
+    OpenAPIImporter, TargetLanguage, WasmPackage,
+};
+use velin_compiler::coverage::{
+    threshold_from_config, CoverageReport, CoverageRunner, CoverageThreshold,
 };
 use velin_compiler::formatter::{FormatConfig, Formatter};
+use velin_compiler::inspector::InspectorRuntime;
+use velin_compiler::migrations::{self, Database, Migration, MigrationRunner, Schema};
 use velin_compiler::parser::parser::Parser;
 
+use velin_compiler::compiler::context::CompilationContext;
 use velin_compiler::compiler::language::get_velisch_identity;
+use velin_compiler::compiler::packages::Workspace;
+use velin_compiler::compiler::pass::Pass;
 use velin_compiler::compiler::{config::CompilerConfig, VelinCompiler};
 use velin_compiler::optimizer::parallelization::ParallelizationAnalyzer;
 use velin_compiler::passes::{
@@ -18,7 +31,8 @@ use velin_compiler::passes::{
     ai_codegen::AICodeGenerationPass, ai_optimization::AIOptimizationPass,
     ai_sandbox::AISandboxPass, ai_semantic::AISemanticPass, autofix::AutoFixPass,
     code_order::CodeOrderingPass, codegen::CodegenPass, desugar::DesugaringPass,
-    parser::ParserPass, type_check::TypeCheckPass,
+    inspector::InspectorInstrumentationPass, parser::ParserPass, type_check::TypeCheckPass,
+    wasm::WasmTargetPass,
 };
 
 fn main() -> AnyhowResult<()> {
@@ -27,6 +41,8 @@ fn main() -> AnyhowResult<()> {
     match cli.command {
         Commands::Compile {
             input,
+            package,
+            workspace,
             output,
             no_type_check,
             show_code,
@@ -39,6 +55,7 @@ fn main() -> AnyhowResult<()> {
             ai_api_key,
             target,
             framework,
+            inspect,
         } => {
             let mut config = CompilerConfig::default();
             config.enable_autofix = autofix;
@@ -56,111 +73,170 @@ fn main() -> AnyhowResult<()> {
 
             // Parse Target Language
             config.target = TargetLanguage::from_str(&target).map_err(|e| anyhow::anyhow!(e))?;
+            if inspect && config.target != TargetLanguage::Rust {
+                return Err(anyhow::anyhow!(
+                    "--inspect wird nur für das Rust-Target unterstützt"
+                ));
+            }
+
+            let inputs = workspace_inputs(input, package.as_deref())?;
+            if output.is_some() && inputs.len() > 1 {
+                return Err(anyhow::anyhow!(
+                    "--output kann nur mit einer einzelnen Eingabe-Datei verwendet werden"
+                ));
+            }
+
+            for input in inputs {
+                if workspace {
+                    println!("🔨 Kompiliere {}", input.display());
+                }
 
-            // Output path logic
-            let output_file = output.unwrap_or_else(|| {
-                // Extension based on target
-                let ext = match config.target {
-                    TargetLanguage::Rust => "rs",
-                    TargetLanguage::Php => "php",
-                    TargetLanguage::Python => "py",
-                    TargetLanguage::JavaScript => "js",
-                    TargetLanguage::TypeScript => "ts",
-                    TargetLanguage::Go => "go",
-                    TargetLanguage::Java => "java",
-                    TargetLanguage::CSharp => "cs",
+                // Output path logic
+                let output_file = output.clone().unwrap_or_else(|| {
+                    // Extension based on target
+                    let ext = match config.target {
+                        TargetLanguage::Rust => "rs",
+                        TargetLanguage::Php => "php",
+                        TargetLanguage::Python => "py",
+                        TargetLanguage::JavaScript => "js",
+                        TargetLanguage::TypeScript => "ts",
+                        TargetLanguage::Go => "go",
+                        TargetLanguage::Java => "java",
+                        TargetLanguage::CSharp => "cs",
+                        TargetLanguage::Wasm => return default_package_dir(&input),
+                    };
+                    input.with_extension(ext)
+                });
+                // Bei Wasm ist die Ausgabe ein Paketverzeichnis, der Codegen schreibt src/lib.rs
+                let wasm_package = (config.target == TargetLanguage::Wasm)
+                    .then(|| WasmPackage::new(output_file.clone()));
+                let output_file = match &wasm_package {
+                    Some(package) => {
+                        let source = package.source_path();
+                        if let Some(dir) = source.parent() {
+                            fs::create_dir_all(dir)
+                                .with_context(|| format!("Failed to create {}", dir.display()))?;
+                        }
+                        source
+                    }
+                    None => output_file,
                 };
-                input.with_extension(ext)
-            });
-            config.output_path = Some(output_file.clone());
-
-            let mut compiler = VelinCompiler::new(config.clone());
-
-            // Register Passes
-            compiler.add_pass(Box::new(AutoFixPass::new(autofix)));
-            compiler.add_pass(Box::new(ParserPass::new()));
-            compiler.add_pass(Box::new(DesugaringPass::new()));
-            // Code Ordering Pass: Automatically sorts functions, types, and blocks based on dependencies
-            compiler.add_pass(Box::new(CodeOrderingPass::new()));
-
-            // KI-Compiler-Passes (optional, via Feature Flags)
-            if config.enable_ai_semantic {
-                if let Ok(pass) = AISemanticPass::new(&config) {
-                    compiler.add_pass(Box::new(pass));
+                config.output_path = Some(output_file.clone());
+
+                let mut compiler = VelinCompiler::new(config.clone());
+
+                // Register Passes
+                compiler.add_pass(Box::new(AutoFixPass::new(autofix)));
+                compiler.add_pass(Box::new(ParserPass::new()));
+                compiler.add_pass(Box::new(DesugaringPass::new()));
+                // Code Ordering Pass: Automatically sorts functions, types, and blocks based on dependencies
+                compiler.add_pass(Box::new(CodeOrderingPass::new()));
+
+                // KI-Compiler-Passes (optional, via Feature Flags)
+                if config.enable_ai_semantic {
+                    if let Ok(pass) = AISemanticPass::new(&config) {
+                        compiler.add_pass(Box::new(pass));
+                    }
                 }
-            }
-            if config.enable_ai_bug_detection {
-                if let Ok(pass) = AIBugDetectionPass::new(&config) {
-                    compiler.add_pass(Box::new(pass));
+                if config.enable_ai_bug_detection {
+                    if let Ok(pass) = AIBugDetectionPass::new(&config) {
+                        compiler.add_pass(Box::new(pass));
+                    }
                 }
-            }
-            if !no_type_check {
-                compiler.add_pass(Box::new(TypeCheckPass::new(true)));
-            }
-            // Standard Optimizer Pass
-            compiler.add_pass(Box::new(ParallelizationAnalyzer::new()));
+                if !no_type_check {
+                    compiler.add_pass(Box::new(TypeCheckPass::new(true)));
+                }
+                // Standard Optimizer Pass
+                compiler.add_pass(Box::new(ParallelizationAnalyzer::new()));
 
-            if config.enable_ai_codegen {
-                if let Ok(pass) = AICodeGenerationPass::new(&config) {
-                    compiler.add_pass(Box::new(pass));
+                if config.enable_ai_codegen {
+                    if let Ok(pass) = AICodeGenerationPass::new(&config) {
+                        compiler.add_pass(Box::new(pass));
+                    }
                 }
-            }
-            // AI Code Review Pass (nach Code Generation)
-            if config.enable_ai_code_review {
-                if let Ok(pass) = AICodeReviewPass::new(&config) {
-                    compiler.add_pass(Box::new(pass));
+                // AI Code Review Pass (nach Code Generation)
+                if config.enable_ai_code_review {
+                    if let Ok(pass) = AICodeReviewPass::new(&config) {
+                        compiler.add_pass(Box::new(pass));
+                    }
                 }
-            }
-            // AI Sandbox Pass (nach Code Review)
-            if config.enable_ai_sandbox {
-                if let Ok(pass) = AISandboxPass::new(&config) {
-                    compiler.add_pass(Box::new(pass));
+                // AI Sandbox Pass (nach Code Review)
+                if config.enable_ai_sandbox {
+                    if let Ok(pass) = AISandboxPass::new(&config) {
+                        compiler.add_pass(Box::new(pass));
+                    }
                 }
-            }
-            if config.enable_ai_optimization {
-                if let Ok(pass) = AIOptimizationPass::new(&config) {
-                    compiler.add_pass(Box::new(pass));
+                if config.enable_ai_optimization {
+                    if let Ok(pass) = AIOptimizationPass::new(&config) {
+                        compiler.add_pass(Box::new(pass));
+                    }
                 }
-            }
-            // Add Codegen Pass
-            compiler.add_pass(Box::new(CodegenPass::new(
-                config.output_path,
-                show_code,
-                config.target,
-                framework,
-            )));
-
-            tracing::info!(file = ?input, language = %get_velisch_identity(), "Compiling Velisch file");
-
-            // SECURITY: Dateigrößen-Limit (max. 5MB)
-            let metadata = fs::metadata(&input)
-                .with_context(|| format!("Failed to read metadata: {}", input.display()))?;
-            const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024; // 5MB
-            if metadata.len() > MAX_FILE_SIZE {
-                return Err(anyhow::anyhow!(
-                    "File too large: {} bytes (max: {} bytes). File size limit exceeded.",
-                    metadata.len(),
-                    MAX_FILE_SIZE
+                if inspect {
+                    compiler.add_pass(Box::new(InspectorInstrumentationPass::new()));
+                }
+                if wasm_package.is_some() {
+                    compiler.add_pass(Box::new(WasmTargetPass::new()));
+                }
+                // Add Codegen Pass
+                // Mit --inspect direkter AST-Codegen, damit die Guards als Statements erhalten bleiben
+                compiler.add_pass(Box::new(
+                    CodegenPass::new(
+                        config.output_path,
+                        show_code,
+                        config.target,
+                        framework.clone(),
+                    )
+                    .with_ir(!inspect),
                 ));
-            }
 
-            let code = fs::read_to_string(&input)
-                .with_context(|| format!("Failed to read file: {}", input.display()))?;
+                tracing::info!(file = ?input, language = %get_velisch_identity(), "Compiling Velisch file");
+
+                // SECURITY: Dateigrößen-Limit (max. 5MB)
+                let metadata = fs::metadata(&input)
+                    .with_context(|| format!("Failed to read metadata: {}", input.display()))?;
+                const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024; // 5MB
+                if metadata.len() > MAX_FILE_SIZE {
+                    return Err(anyhow::anyhow!(
+                        "File too large: {} bytes (max: {} bytes). File size limit exceeded.",
+                        metadata.len(),
+                        MAX_FILE_SIZE
+                    ));
+                }
+
+                let code = fs::read_to_string(&input)
+                    .with_context(|| format!("Failed to read file: {}", input.display()))?;
 
-            let context = compiler.compile(input.to_string_lossy().to_string(), code)?;
+                let context = compiler.compile(input.to_string_lossy().to_string(), code)?;
 
-            if context.has_errors() {
-                eprintln!(
-                    "✗ Kompilierung fehlgeschlagen mit {} Fehlern:\n",
-                    context.errors.len()
-                );
-                for error in &context.errors {
-                    // Verwende verbesserte Fehlermeldungen mit Vorschlägen
-                    eprintln!("{}", error.with_suggestions());
-                    eprintln!("{}", "─".repeat(60));
+                if context.has_errors() {
+                    eprintln!(
+                        "✗ Kompilierung fehlgeschlagen mit {} Fehlern:\n",
+                        context.errors.len()
+                    );
+                    for error in &context.errors {
+                        // Verwende verbesserte Fehlermeldungen mit Vorschlägen
+                        eprintln!("{}", error.with_suggestions());
+                        eprintln!("{}", "─".repeat(60));
+                    }
+
+                    // Zeige Warnings, falls vorhanden
+                    if context.has_warnings() {
+                        eprintln!("\n⚠️  {} Warnung(en):\n", context.warning_count());
+                        for warning in &context.warnings {
+                            eprintln!("{}", warning.with_suggestions());
+                        }
+                    }
+
+                    eprintln!(
+                        "\n💡 Tipp: Nutze 'velin check --autofix' für automatische Korrekturen"
+                    );
+                    eprintln!(
+                        "📖 Hilfe: Siehe docs/guides/getting-started.md für weitere Informationen"
+                    );
+                    std::process::exit(1);
                 }
 
-                // Zeige Warnings, falls vorhanden
+                // Zeige Warnings auch bei erfolgreicher Kompilierung
                 if context.has_warnings() {
                     eprintln!("\n⚠️  {} Warnung(en):\n", context.warning_count());
                     for warning in &context.warnings {
@@ -168,88 +244,102 @@ fn main() -> AnyhowResult<()> {
                     }
                 }
 
-                eprintln!("\n💡 Tipp: Nutze 'velin check --autofix' für automatische Korrekturen");
-                eprintln!(
-                    "📖 Hilfe: Siehe docs/guides/getting-started.md für weitere Informationen"
-                );
-                std::process::exit(1);
-            }
+                if inspect {
+                    let code = fs::read_to_string(&output_file)
+                        .with_context(|| format!("Failed to read {}", output_file.display()))?;
+                    fs::write(
+                        &output_file,
+                        format!("{}\n{}", code, InspectorRuntime::generate_runtime_code()),
+                    )?;
+                }
 
-            // Zeige Warnings auch bei erfolgreicher Kompilierung
-            if context.has_warnings() {
-                eprintln!("\n⚠️  {} Warnung(en):\n", context.warning_count());
-                for warning in &context.warnings {
-                    eprintln!("{}", warning.with_suggestions());
+                if let (Some(package), Some(program)) = (&wasm_package, &context.program) {
+                    package
+                        .write_support_files(program)
+                        .with_context(|| format!("Failed to write {}", package.dir.display()))?;
+                    println!("🔨 Baue Wasm-Modul ({})...", package.dir.display());
+                    let wasm = package.build()?;
+                    println!("✓ Wasm-Modul: {}", wasm.display());
+                    println!("✓ Bindings: {}.js, {}.d.ts", package.name, package.name);
                 }
             }
 
             println!("✓ Kompilierung erfolgreich");
             Ok(())
         }
-        Commands::Check { input, autofix } => {
-            let mut config = CompilerConfig::default();
-            config.enable_autofix = autofix;
-            config.enable_type_check = true;
-
-            let mut compiler = VelinCompiler::new(config);
+        Commands::Check {
+            input,
+            package,
+            workspace: _,
+            autofix,
+        } => {
+            for input in workspace_inputs(input, package.as_deref())? {
+                let mut config = CompilerConfig::default();
+                config.enable_autofix = autofix;
+                config.enable_type_check = true;
+
+                let mut compiler = VelinCompiler::new(config);
+
+                compiler.add_pass(Box::new(AutoFixPass::new(autofix)));
+                compiler.add_pass(Box::new(ParserPass::new()));
+                compiler.add_pass(Box::new(DesugaringPass::new()));
+                // Code Ordering Pass: Automatically sorts functions, types, and blocks based on dependencies
+                compiler.add_pass(Box::new(CodeOrderingPass::new()));
+                compiler.add_pass(Box::new(TypeCheckPass::new(true)));
 
-            compiler.add_pass(Box::new(AutoFixPass::new(autofix)));
-            compiler.add_pass(Box::new(ParserPass::new()));
-            compiler.add_pass(Box::new(DesugaringPass::new()));
-            // Code Ordering Pass: Automatically sorts functions, types, and blocks based on dependencies
-            compiler.add_pass(Box::new(CodeOrderingPass::new()));
-            compiler.add_pass(Box::new(TypeCheckPass::new(true)));
+                println!("🔍 Prüfe: {}\n", input.display());
+
+                // SECURITY: Dateigrößen-Limit (max. 5MB)
+                let metadata = fs::metadata(&input)
+                    .with_context(|| format!("Failed to read metadata: {}", input.display()))?;
+                const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024; // 5MB
+                if metadata.len() > MAX_FILE_SIZE {
+                    return Err(anyhow::anyhow!(
+                        "File too large: {} bytes (max: {} bytes). File size limit exceeded.",
+                        metadata.len(),
+                        MAX_FILE_SIZE
+                    ));
+                }
 
-            println!("🔍 Prüfe: {}\n", input.display());
+                let code = fs::read_to_string(&input)
+                    .with_context(|| format!("Failed to read file: {}", input.display()))?;
 
-            // SECURITY: Dateigrößen-Limit (max. 5MB)
-            let metadata = fs::metadata(&input)
-                .with_context(|| format!("Failed to read metadata: {}", input.display()))?;
-            const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024; // 5MB
-            if metadata.len() > MAX_FILE_SIZE {
-                return Err(anyhow::anyhow!(
-                    "File too large: {} bytes (max: {} bytes). File size limit exceeded.",
-                    metadata.len(),
-                    MAX_FILE_SIZE
-                ));
-            }
+                let context = compiler.compile(input.to_string_lossy().to_string(), code)?;
 
-            let code = fs::read_to_string(&input)
-                .with_context(|| format!("Failed to read file: {}", input.display()))?;
+                if context.has_errors() {
+                    eprintln!(
+                        "✗ Checks fehlgeschlagen mit {} Fehlern:\n",
+                        context.errors.len()
+                    );
+                    for error in &context.errors {
+                        eprintln!("{}", error.with_suggestions());
+                        eprintln!("{}", "─".repeat(60));
+                    }
 
-            let context = compiler.compile(input.to_string_lossy().to_string(), code)?;
+                    // Zeige Warnings
+                    if context.has_warnings() {
+                        eprintln!("\n⚠️  {} Warnung(en):\n", context.warning_count());
+                        for warning in &context.warnings {
+                            eprintln!("{}", warning.with_suggestions());
+                        }
+                    }
 
-            if context.has_errors() {
-                eprintln!(
-                    "✗ Checks fehlgeschlagen mit {} Fehlern:\n",
-                    context.errors.len()
-                );
-                for error in &context.errors {
-                    eprintln!("{}", error.with_suggestions());
-                    eprintln!("{}", "─".repeat(60));
+                    eprintln!(
+                        "\n💡 Tipp: Nutze 'velin check --autofix' für automatische Korrekturen"
+                    );
+                    eprintln!(
+                        "📖 Hilfe: Siehe docs/guides/getting-started.md für weitere Informationen"
+                    );
+                    std::process::exit(1);
                 }
 
-                // Zeige Warnings
+                // Zeige Warnings auch bei erfolgreichen Checks
                 if context.has_warnings() {
                     eprintln!("\n⚠️  {} Warnung(en):\n", context.warning_count());
                     for warning in &context.warnings {
                         eprintln!("{}", warning.with_suggestions());
                     }
                 }
-
-                eprintln!("\n💡 Tipp: Nutze 'velin check --autofix' für automatische Korrekturen");
-                eprintln!(
-                    "📖 Hilfe: Siehe docs/guides/getting-started.md für weitere Informationen"
-                );
-                std::process::exit(1);
-            }
-
-            // Zeige Warnings auch bei erfolgreichen Checks
-            if context.has_warnings() {
-                eprintln!("\n⚠️  {} Warnung(en):\n", context.warning_count());
-                for warning in &context.warnings {
-                    eprintln!("{}", warning.with_suggestions());
-                }
             }
 
             println!("✓ Alle Checks bestanden!");
@@ -277,7 +367,11 @@ fn main() -> AnyhowResult<()> {
             // Alias für Serve
             serve_command(input.clone(), port, host.clone(), watch)
         }
-        Commands::OpenAPI { input, output } => openapi_command(input, output),
+        Commands::OpenAPI {
+            input,
+            output,
+            format,
+        } => openapi_command(input, output, format),
         Commands::Generate {
             gen_type,
             name,
@@ -286,13 +380,52 @@ fn main() -> AnyhowResult<()> {
             openapi,
             language,
             output,
-        } => generate_command(gen_type, name, fields, path, openapi, language, output),
+            check,
+            input,
+            platform,
+        } => generate_command(
+            gen_type, name, fields, path, openapi, language, output, check, input, platform,
+        ),
         Commands::Test {
             directory,
+            package,
+            workspace,
             unit,
             integration,
             verbose,
-        } => test_command(directory, unit, integration, verbose),
+            coverage,
+            coverage_threshold,
+            branch_threshold,
+            coverage_dir,
+        } => {
+            let coverage = if coverage {
+                let config_file = std::env::current_dir()
+                    .unwrap_or_else(|_| PathBuf::from("."))
+                    .join("velin.config.json");
+                let mut threshold = threshold_from_config(&config_file);
+                threshold.line = coverage_threshold.or(threshold.line);
+                threshold.branch = branch_threshold.or(threshold.branch);
+                Some((threshold, coverage_dir))
+            } else {
+                None
+            };
+            let directories = match directory {
+                Some(directory) => vec![directory],
+                None if package.is_some() || workspace => {
+                    let workspace = discover_workspace()?;
+                    workspace
+                        .select(package.as_deref())?
+                        .into_iter()
+                        .map(|member| member.dir.join("tests"))
+                        .filter(|dir| package.is_some() || dir.exists())
+                        .collect()
+                }
+                None => vec![std::env::current_dir()
+                    .unwrap_or_else(|_| PathBuf::from("."))
+                    .join("tests")],
+            };
+            test_command(directories, unit, integration, verbose, coverage)
+        }
         Commands::Config { subcommand } => match subcommand {
             velin_compiler::cli::ConfigCommands::Init { example } => config_init_command(example),
             velin_compiler::cli::ConfigCommands::Validate { file } => config_validate_command(file),
@@ -365,7 +498,59 @@ fn main() -> AnyhowResult<()> {
                 serialize_validate_yaml_command(file)
             }
         },
+        Commands::Db { subcommand } => match subcommand {
+            velin_compiler::cli::DbCommands::Migrate { subcommand } => match subcommand {
+                MigrateCommands::Generate { input, dir, name } => {
+                    migrate_generate_command(input, dir, name)
+                }
+                MigrateCommands::Up {
+                    dir,
+                    database_url,
+                    to,
+                    dry_run,
+                } => migrate_up_command(dir, database_url, to, dry_run),
+                MigrateCommands::Down {
+                    dir,
+                    database_url,
+                    steps,
+                    dry_run,
+                } => migrate_down_command(dir, database_url, steps, dry_run),
+                MigrateCommands::Status { dir, database_url } => {
+                    migrate_status_command(dir, database_url)
+                }
+            },
+        },
+        Commands::Audit { subcommand } => match subcommand {
+            AuditCommands::Verify { file, key } => audit_verify_command(file, key),
+        },
+    }
+}
+
+fn discover_workspace() -> AnyhowResult<Workspace> {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    Workspace::discover(&cwd)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Kein Workspace gefunden: keine velin.toml mit [workspace] in {} oder darüber",
+            cwd.display()
+        )
+    })
+}
+
+/// Eingabe-Dateien für compile/check: `--input` oder die Einstiegsdateien der
+/// per `-p <name>` bzw. `--workspace` gewählten Members
+fn workspace_inputs(input: Option<PathBuf>, package: Option<&str>) -> AnyhowResult<Vec<PathBuf>> {
+    if let Some(input) = input {
+        return Ok(vec![input]);
+    }
+    let workspace = discover_workspace()?;
+    let members = workspace.select(package)?;
+    if members.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Workspace {} hat keine Members",
+            workspace.root.display()
+        ));
     }
+    members.into_iter().map(|member| member.entry()).collect()
 }
 
 fn format_command(input: PathBuf, in_place: bool) -> AnyhowResult<()> {
@@ -458,30 +643,103 @@ fn info_command(input: PathBuf) -> AnyhowResult<()> {
     Ok(())
 }
 
-fn openapi_command(input: PathBuf, output: Option<PathBuf>) -> AnyhowResult<()> {
+fn openapi_command(
+    input: PathBuf,
+    output: Option<PathBuf>,
+    format: Option<String>,
+) -> AnyhowResult<()> {
     println!("📄 Generiere OpenAPI Specification: {}\n", input.display());
 
+    let format = match format {
+        Some(name) => OpenAPIFormat::parse(&name).ok_or_else(|| {
+            anyhow::anyhow!("Unbekanntes Format: {}. Unterstützt: json, yaml", name)
+        })?,
+        None => output
+            .as_deref()
+            .map(OpenAPIFormat::from_path)
+            .unwrap_or(OpenAPIFormat::Json),
+    };
+
+    // ParserPass löst `use`-Module auf, damit auch deren Routen im Dokument landen
     let code = fs::read_to_string(&input)
         .with_context(|| format!("Failed to read file: {}", input.display()))?;
-
-    let program =
-        Parser::parse(&code).map_err(|e| anyhow::anyhow!("Parse error: {}", e.message))?;
+    let mut context = CompilationContext::new(input.to_string_lossy().to_string(), code);
+    ParserPass::new().run(&mut context)?;
+    if context.has_errors() {
+        for error in &context.errors {
+            eprintln!("❌ {}", error);
+        }
+        return Err(anyhow::anyhow!("Parse error"));
+    }
+    let program = context
+        .program
+        .ok_or_else(|| anyhow::anyhow!("Kein Programm nach dem Parsen"))?;
 
     println!("✓ Parsing erfolgreich");
 
-    let mut openapi_gen = OpenAPIGenerator::new();
-    let openapi_spec = openapi_gen.generate(&program);
+    let spec = OpenAPIGenerator::new().build(&program);
+    let problems = openapi::validate(&spec);
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("❌ {}", problem);
+        }
+        return Err(anyhow::anyhow!(
+            "OpenAPI-Dokument ist ungültig ({} Fehler)",
+            problems.len()
+        ));
+    }
+    let document = openapi::render(&spec, format)?;
 
-    let output_file = output.unwrap_or_else(|| input.with_extension("openapi.json"));
+    let output_file = output.unwrap_or_else(|| input.with_extension(format.extension()));
 
-    fs::write(&output_file, openapi_spec)
+    fs::write(&output_file, document)
         .with_context(|| format!("Failed to write file: {}", output_file.display()))?;
 
     println!(
-        "✓ OpenAPI Specification generiert: {}",
-        output_file.display()
+        "✓ OpenAPI {} Specification generiert: {} ({} Pfade, {} Schemas)",
+        spec.openapi,
+        output_file.display(),
+        spec.paths.len(),
+        spec.components.schemas.len()
     );
 
+    // `@WebSocket`/`@SSE`-Endpunkte stehen als Kanäle im AsyncAPI-Dokument daneben
+    let async_spec = asyncapi::AsyncAPIGenerator::new().build(&program);
+    if !async_spec.channels.is_empty() {
+        let problems = asyncapi::validate(&async_spec);
+        if !problems.is_empty() {
+            for problem in &problems {
+                eprintln!("❌ {}", problem);
+            }
+            return Err(anyhow::anyhow!(
+                "AsyncAPI-Dokument ist ungültig ({} Fehler)",
+                problems.len()
+            ));
+        }
+        let file_name = output_file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let async_name = if file_name.contains("openapi") {
+            file_name.replacen("openapi", "asyncapi", 1)
+        } else {
+            match file_name.rsplit_once('.') {
+                Some((stem, extension)) => format!("{}.asyncapi.{}", stem, extension),
+                None => format!("{}.asyncapi", file_name),
+            }
+        };
+        let async_file = output_file.with_file_name(async_name);
+        fs::write(&async_file, asyncapi::render(&async_spec, format)?)
+            .with_context(|| format!("Failed to write file: {}", async_file.display()))?;
+
+        println!(
+            "✓ AsyncAPI {} Specification generiert: {} ({} Kanäle)",
+            async_spec.asyncapi,
+            async_file.display(),
+            async_spec.channels.len()
+        );
+    }
+
     Ok(())
 }
 
@@ -602,6 +860,7 @@ rand = "0.8"
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_command(
     gen_type: String,
     name: Option<String>,
@@ -610,6 +869,9 @@ fn generate_command(
     openapi: Option<PathBuf>,
     language: Option<String>,
     output: Option<PathBuf>,
+    check: bool,
+    input: Option<PathBuf>,
+    platform: Option<String>,
 ) -> AnyhowResult<()> {
     println!("🔧 Generiere Code: {}\n", gen_type);
 
@@ -643,7 +905,12 @@ fn generate_command(
                 match client_gen.generate_from_openapi(openapi_path, lang) {
                     Ok(code) => {
                         if let Some(ref output_path) = output {
-                            fs::write(output_path, &code).with_context(|| {
+                            // Verzeichnis als Ziel: Standard-Dateiname der Sprache
+                            let output_path = match client::default_file_name(lang) {
+                                Some(file) if output_path.is_dir() => output_path.join(file),
+                                _ => output_path.clone(),
+                            };
+                            fs::write(&output_path, &code).with_context(|| {
                                 format!("Failed to write file: {}", output_path.display())
                             })?;
                             println!("✓ Client generiert: {}", output_path.display());
@@ -663,9 +930,25 @@ fn generate_command(
                 ));
             }
         }
+        "from-openapi" => {
+            let Some(ref openapi_path) = openapi else {
+                return Err(anyhow::anyhow!(
+                    "--openapi is required for from-openapi generation"
+                ));
+            };
+            return import_openapi_command(openapi_path, output.as_deref(), check);
+        }
+        "deployment" => {
+            let Some(ref input_path) = input else {
+                return Err(anyhow::anyhow!(
+                    "--input is required for deployment generation"
+                ));
+            };
+            return deployment_command(input_path, name, platform, output);
+        }
         _ => {
             return Err(anyhow::anyhow!(
-                "Unknown generation type: {}. Available: api, crud, test, client, responses, errors, logging, cache, health, async, security",
+                "Unknown generation type: {}. Available: api, crud, test, client, from-openapi, deployment, responses, errors, logging, cache, health, async, security",
                 gen_type
             ));
         }
@@ -682,56 +965,204 @@ fn generate_command(
     Ok(())
 }
 
+/// `velin generate from-openapi`: Structs, Enums und Route-Stubs aus OpenAPI/JSON Schema
+fn import_openapi_command(
+    openapi_path: &std::path::Path,
+    output: Option<&std::path::Path>,
+    check: bool,
+) -> AnyhowResult<()> {
+    let content = fs::read_to_string(openapi_path)
+        .with_context(|| format!("Failed to read file: {}", openapi_path.display()))?;
+    let mut importer = OpenAPIImporter::new();
+    let spec = importer
+        .parse(
+            &content,
+            OpenAPIFormat::from_path(openapi_path) == OpenAPIFormat::Yaml,
+        )
+        .with_context(|| format!("Failed to parse {}", openapi_path.display()))?;
+    let program = importer.import(&spec);
+
+    let mut source = format!(
+        "// Generiert aus {} ({} {})\n// Die Route-Handler sind Stubs und müssen noch implementiert werden\n",
+        openapi_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        spec.info.title,
+        spec.info.version
+    );
+    let mut formatter = Formatter::new(FormatConfig::default());
+    for item in &program.items {
+        source.push('\n');
+        source.push_str(&formatter.format(&velin_compiler::parser::ast::Program {
+            items: vec![item.clone()],
+        }));
+        source.push('\n');
+    }
+
+    for warning in importer.warnings() {
+        eprintln!("⚠️  {}", warning);
+    }
+
+    match output {
+        Some(output_path) => {
+            fs::write(output_path, &source)
+                .with_context(|| format!("Failed to write file: {}", output_path.display()))?;
+            println!(
+                "✓ Velin-Code generiert: {} ({} Elemente, {} Warnungen)",
+                output_path.display(),
+                program.items.len(),
+                importer.warnings().len()
+            );
+        }
+        None => println!("{}", source),
+    }
+
+    if check {
+        let differences =
+            openapi_import::round_trip(&spec, &source).map_err(|e| anyhow::anyhow!(e))?;
+        if !differences.is_empty() {
+            for difference in &differences {
+                eprintln!("  ✗ {}", difference);
+            }
+            return Err(anyhow::anyhow!(
+                "Round-Trip-Prüfung fehlgeschlagen: {} Abweichungen",
+                differences.len()
+            ));
+        }
+        eprintln!("✓ Round-Trip-Prüfung bestanden: Spezifikation wird unverändert erzeugt");
+    }
+    Ok(())
+}
+
+/// `velin generate deployment`: Dockerfile, Compose- und Kubernetes-Manifeste aus dem Code
+fn deployment_command(
+    input: &std::path::Path,
+    name: Option<String>,
+    platform: Option<String>,
+    output: Option<PathBuf>,
+) -> AnyhowResult<()> {
+    let code = fs::read_to_string(input)
+        .with_context(|| format!("Failed to read file: {}", input.display()))?;
+    let mut context = CompilationContext::new(input.to_string_lossy().to_string(), code);
+    ParserPass::new().run(&mut context)?;
+    if context.has_errors() {
+        for error in &context.errors {
+            eprintln!("❌ {}", error);
+        }
+        return Err(anyhow::anyhow!("Parse error"));
+    }
+    let program = context
+        .program
+        .ok_or_else(|| anyhow::anyhow!("Kein Programm nach dem Parsen"))?;
+
+    let analyzer = DeploymentAnalyzer::new();
+    let mut plan = analyzer.analyze(&program)?;
+    if let Some(platform) = platform {
+        plan.deployment_type = DeploymentType::parse(&platform).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unbekannte Plattform: {}. Unterstützt: local, compose, kubernetes, serverless",
+                platform
+            )
+        })?;
+    }
+    // Ohne --name: Verzeichnis des Projekts
+    let service_name = name.or_else(|| {
+        fs::canonicalize(input)
+            .ok()?
+            .parent()?
+            .file_name()
+            .map(|dir| dir.to_string_lossy().to_string())
+    });
+    if let Some(service_name) = service_name {
+        plan.set_service_name(&service_name);
+    }
+
+    println!(
+        "📦 Service: {} ({:?})",
+        plan.service_name, plan.deployment_type
+    );
+    match plan.port {
+        Some(port) => println!("  Port: {}", port),
+        None => println!("  Port: - (keine Routen)"),
+    }
+    if let Some(path) = &plan.health.liveness {
+        println!("  Health: {}", path);
+    }
+    if !plan.services.is_empty() {
+        let services: Vec<_> = plan.services.iter().map(|service| service.name()).collect();
+        println!("  Backing Services: {}", services.join(", "));
+    }
+    if !plan.env_vars.is_empty() {
+        let vars: Vec<_> = plan.env_vars.iter().map(|var| var.name.as_str()).collect();
+        println!("  Umgebungsvariablen: {}", vars.join(", "));
+    }
+
+    let infrastructure = analyzer.generate_infrastructure(&plan)?;
+    if infrastructure.files.is_empty() {
+        println!("\nℹ️  Keine Dateien nötig: der Service läuft lokal ohne Backing Services");
+        return Ok(());
+    }
+    let output_dir = output.unwrap_or_else(|| PathBuf::from("deploy"));
+    fs::create_dir_all(&output_dir)
+        .with_context(|| format!("Failed to create directory: {}", output_dir.display()))?;
+    println!();
+    for file in &infrastructure.files {
+        let path = output_dir.join(&file.name);
+        fs::write(&path, &file.content)
+            .with_context(|| format!("Failed to write file: {}", path.display()))?;
+        println!("✓ {}", path.display());
+    }
+    Ok(())
+}
+
 fn test_command(
-    directory: Option<PathBuf>,
+    test_dirs: Vec<PathBuf>,
     unit: bool,
     integration: bool,
     verbose: bool,
+    coverage: Option<(CoverageThreshold, PathBuf)>,
 ) -> AnyhowResult<()> {
     println!("🧪 Führe Tests aus\n");
 
-    let test_dir = directory.unwrap_or_else(|| {
-        std::env::current_dir()
-            .unwrap_or_else(|_| PathBuf::from("."))
-            .join("tests")
-    });
-
-    if !test_dir.exists() {
-        return Err(anyhow::anyhow!(
-            "Test directory not found: {}. Create tests/ directory or use --directory",
-            test_dir.display()
-        ));
-    }
-
     let mut test_files = Vec::new();
 
-    if unit || (!unit && !integration) {
-        let unit_dir = test_dir.join("unit");
-        if unit_dir.exists() {
-            if verbose {
-                println!("📁 Scanne Unit Tests: {}", unit_dir.display());
+    for test_dir in &test_dirs {
+        if !test_dir.exists() {
+            return Err(anyhow::anyhow!(
+                "Test directory not found: {}. Create tests/ directory or use --directory",
+                test_dir.display()
+            ));
+        }
+
+        if unit || (!unit && !integration) {
+            let unit_dir = test_dir.join("unit");
+            if unit_dir.exists() {
+                if verbose {
+                    println!("📁 Scanne Unit Tests: {}", unit_dir.display());
+                }
+                scan_test_files(&unit_dir, &mut test_files, verbose);
+            } else if verbose {
+                println!(
+                    "⚠️  Unit Test-Verzeichnis nicht gefunden: {}",
+                    unit_dir.display()
+                );
             }
-            scan_test_files(&unit_dir, &mut test_files, verbose);
-        } else if verbose {
-            println!(
-                "⚠️  Unit Test-Verzeichnis nicht gefunden: {}",
-                unit_dir.display()
-            );
         }
-    }
 
-    if integration || (!unit && !integration) {
-        let integration_dir = test_dir.join("integration");
-        if integration_dir.exists() {
-            if verbose {
-                println!("📁 Scanne Integration Tests: {}", integration_dir.display());
+        if integration || (!unit && !integration) {
+            let integration_dir = test_dir.join("integration");
+            if integration_dir.exists() {
+                if verbose {
+                    println!("📁 Scanne Integration Tests: {}", integration_dir.display());
+                }
+                scan_test_files(&integration_dir, &mut test_files, verbose);
+            } else if verbose {
+                println!(
+                    "⚠️  Integration Test-Verzeichnis nicht gefunden: {}",
+                    integration_dir.display()
+                );
             }
-            scan_test_files(&integration_dir, &mut test_files, verbose);
-        } else if verbose {
-            println!(
-                "⚠️  Integration Test-Verzeichnis nicht gefunden: {}",
-                integration_dir.display()
-            );
         }
     }
 
@@ -742,6 +1173,10 @@ fn test_command(
 
     println!("✓ Gefundene Test-Dateien: {}\n", test_files.len());
 
+    if let Some((threshold, coverage_dir)) = coverage {
+        return coverage_test_command(&test_files, verbose, threshold, coverage_dir);
+    }
+
     let mut passed = 0;
     let mut failed = 0;
 
@@ -786,6 +1221,85 @@ fn test_command(
     Ok(())
 }
 
+fn coverage_test_command(
+    test_files: &[PathBuf],
+    verbose: bool,
+    threshold: CoverageThreshold,
+    coverage_dir: PathBuf,
+) -> AnyhowResult<()> {
+    let work_dir = PathBuf::from("target").join("velin-coverage");
+    let runner = CoverageRunner::new(work_dir);
+    let mut report = CoverageReport::new();
+    let mut failed = 0;
+
+    for test_file in test_files {
+        if verbose {
+            println!("🔍 Instrumentiere: {}", test_file.display());
+        }
+        match runner.run_file(test_file) {
+            Ok(run) => {
+                for map in &run.maps {
+                    report.add(map, &run.counts);
+                }
+                if verbose || !run.passed {
+                    print!("{}", run.output);
+                }
+                if !run.passed {
+                    eprintln!("  ✗ Tests fehlgeschlagen in {}", test_file.display());
+                    failed += 1;
+                }
+            }
+            Err(e) => {
+                eprintln!("  ✗ {}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    fs::create_dir_all(&coverage_dir)
+        .with_context(|| format!("Failed to create {}", coverage_dir.display()))?;
+    let lcov_path = coverage_dir.join("lcov.info");
+    fs::write(&lcov_path, report.to_lcov())
+        .with_context(|| format!("Failed to write {}", lcov_path.display()))?;
+    let html_path = coverage_dir.join("index.html");
+    fs::write(
+        &html_path,
+        report.to_html(|file| fs::read_to_string(file).ok()),
+    )
+    .with_context(|| format!("Failed to write {}", html_path.display()))?;
+
+    println!("\n📈 Coverage-Report:");
+    for file in &report.files {
+        println!(
+            "  {}: Zeilen {}/{}, Branches {}/{}",
+            file.file,
+            file.lines_hit(),
+            file.lines_found(),
+            file.branches_hit(),
+            file.branches_found()
+        );
+    }
+    println!("  Zeilen-Coverage: {:.2}%", report.line_coverage());
+    println!("  Branch-Coverage: {:.2}%", report.branch_coverage());
+    println!("  Funktionen-Coverage: {:.2}%", report.function_coverage());
+    println!("  LCOV: {}", lcov_path.display());
+    println!("  HTML: {}", html_path.display());
+
+    if failed > 0 {
+        return Err(anyhow::anyhow!("{} test files failed", failed));
+    }
+
+    let violations = report.check_threshold(&threshold);
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("✗ {}", violation);
+        }
+        return Err(anyhow::anyhow!("Coverage threshold not met"));
+    }
+
+    Ok(())
+}
+
 fn scan_test_files(dir: &PathBuf, files: &mut Vec<PathBuf>, verbose: bool) {
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
@@ -880,6 +1394,12 @@ fn config_init_command(example: bool) -> AnyhowResult<()> {
     "format": "json",
     "output": "console"
   },
+  "testing": {
+    "coverage": {
+      "line": 80,
+      "branch": 70
+    }
+  },
   "security": {
     "apiKeyRequired": false,
     "rateLimit": {
@@ -1204,3 +1724,172 @@ fn serialize_validate_yaml_command(file: PathBuf) -> AnyhowResult<()> {
     println!("  ✓ YAML ist gültig!");
     Ok(())
 }
+
+/// `velin db migrate generate`: Migration aus den `@Entity`-Änderungen
+fn migrate_generate_command(
+    input: PathBuf,
+    dir: PathBuf,
+    name: Option<String>,
+) -> AnyhowResult<()> {
+    let code = fs::read_to_string(&input)
+        .with_context(|| format!("Failed to read file: {}", input.display()))?;
+    let mut context = CompilationContext::new(input.to_string_lossy().to_string(), code);
+    ParserPass::new().run(&mut context)?;
+    if context.has_errors() {
+        for error in &context.errors {
+            eprintln!("❌ {}", error);
+        }
+        return Err(anyhow::anyhow!("Parse error"));
+    }
+    let program = context
+        .program
+        .ok_or_else(|| anyhow::anyhow!("Kein Programm nach dem Parsen"))?;
+
+    let schema = Schema::from_program(&program).map_err(|errors| {
+        for error in &errors {
+            eprintln!("❌ {}", error);
+        }
+        anyhow::anyhow!("Ungültige Entities ({} Fehler)", errors.len())
+    })?;
+
+    match migrations::generate(&dir, &schema, name.as_deref())? {
+        None => println!("✓ Keine Schema-Änderungen seit der letzten Migration"),
+        Some(generated) => {
+            println!("✓ Migration erstellt: {}", generated.path.display());
+            for operation in &generated.migration.up {
+                println!("  - {}", operation.slug());
+            }
+            for warning in &generated.warnings {
+                println!("⚠️  {}", warning);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn migration_runner(
+    dir: &std::path::Path,
+    database_url: Option<String>,
+) -> AnyhowResult<MigrationRunner> {
+    let url = database_url
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!("Keine Datenbank angegeben: --database-url oder DATABASE_URL setzen")
+        })?;
+    let database = Database::from_url(&url)?;
+    Ok(MigrationRunner::new(database, Migration::load_all(dir)?))
+}
+
+/// `velin db migrate up`
+fn migrate_up_command(
+    dir: PathBuf,
+    database_url: Option<String>,
+    to: Option<String>,
+    dry_run: bool,
+) -> AnyhowResult<()> {
+    let runner = migration_runner(&dir, database_url)?;
+    let pending = runner.pending(to.as_deref())?;
+    if pending.is_empty() {
+        println!("✓ Datenbank ist aktuell");
+        return Ok(());
+    }
+    for migration in pending {
+        if dry_run {
+            println!(
+                "-- {} (up)\n{}",
+                migration.file_name(),
+                runner.script(migration, true)
+            );
+            continue;
+        }
+        runner.apply(migration, true)?;
+        println!("✓ {}", migration.file_name());
+    }
+    Ok(())
+}
+
+/// `velin db migrate down`
+fn migrate_down_command(
+    dir: PathBuf,
+    database_url: Option<String>,
+    steps: usize,
+    dry_run: bool,
+) -> AnyhowResult<()> {
+    let runner = migration_runner(&dir, database_url)?;
+    let migrations = runner.to_revert(steps)?;
+    if migrations.is_empty() {
+        println!("✓ Keine angewendeten Migrationen");
+        return Ok(());
+    }
+    for migration in migrations {
+        if dry_run {
+            println!(
+                "-- {} (down)\n{}",
+                migration.file_name(),
+                runner.script(migration, false)
+            );
+            continue;
+        }
+        runner.apply(migration, false)?;
+        println!("↩ {}", migration.file_name());
+    }
+    Ok(())
+}
+
+/// `velin db migrate status`
+fn migrate_status_command(dir: PathBuf, database_url: Option<String>) -> AnyhowResult<()> {
+    let runner = migration_runner(&dir, database_url)?;
+    println!("🗄️  Migrationen ({})\n", runner.dialect().name());
+    let status = runner.status()?;
+    if status.is_empty() {
+        println!("  Keine Migrationen in {}", dir.display());
+    }
+    for entry in &status {
+        match (&entry.applied_at, entry.missing) {
+            (Some(at), false) => println!("  ✓ {}_{}  ({})", entry.version, entry.name, at),
+            (Some(at), true) => println!(
+                "  ⚠️  {}_{}  ({}, lokal nicht vorhanden)",
+                entry.version, entry.name, at
+            ),
+            (None, _) => println!("  ○ {}_{}  ausstehend", entry.version, entry.name),
+        }
+    }
+    Ok(())
+}
+
+fn audit_verify_command(file: Option<PathBuf>, key: Option<String>) -> AnyhowResult<()> {
+    use velin_compiler::stdlib::audit::store;
+
+    let file = file.unwrap_or_else(|| {
+        PathBuf::from(std::env::var("AUDIT_LOG_FILE").unwrap_or_else(|_| "audit.log".to_string()))
+    });
+    let key = key
+        .or_else(|| std::env::var("AUDIT_HMAC_KEY").ok())
+        .filter(|key| !key.is_empty());
+
+    println!("🔏 Audit-Log prüfen\n");
+    println!("  Datei: {}", file.display());
+    let report = store::verify(&file, key.as_deref().map(str::as_bytes));
+    println!(
+        "  Einträge: {} in {} Segment(en)",
+        report.entries, report.segments
+    );
+    if key.is_some() {
+        println!("  Signaturen geprüft: {}", report.signed);
+    } else if report.unchecked > 0 {
+        println!(
+            "  ⚠️  {} signierte Einträge, ohne --key bzw. AUDIT_HMAC_KEY nicht geprüft",
+            report.unchecked
+        );
+    }
+
+    if report.problems.is_empty() {
+        println!("\n✓ Audit-Log unverändert");
+        return Ok(());
+    }
+    eprintln!("\n✗ {} Problem(e) gefunden:", report.problems.len());
+    for problem in &report.problems {
+        eprintln!("  - {}", problem);
+    }
+    std::process::exit(1);
+}
@@ -0,0 +1,165 @@
+// Vergleich zweier Schemas zu geordneten, umkehrbaren Operationen
+
+use super::schema::{Schema, Table};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Schema-Änderung, unabhängig vom Datenbanksystem
+///
+/// Jede Operation trägt den vollständigen Tabellenzustand, damit sie umkehrbar ist
+/// und SQLite Änderungen, die `ALTER TABLE` nicht kann, per Tabellen-Neuaufbau
+/// umsetzen kann.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Operation {
+    CreateTable { table: Table },
+    DropTable { table: Table },
+    AlterTable { before: Table, after: Table },
+}
+
+impl Operation {
+    pub fn invert(&self) -> Operation {
+        match self {
+            Operation::CreateTable { table } => Operation::DropTable {
+                table: table.clone(),
+            },
+            Operation::DropTable { table } => Operation::CreateTable {
+                table: table.clone(),
+            },
+            Operation::AlterTable { before, after } => Operation::AlterTable {
+                before: after.clone(),
+                after: before.clone(),
+            },
+        }
+    }
+
+    /// Kurzbeschreibung, z.B. `create_users`
+    pub fn slug(&self) -> String {
+        match self {
+            Operation::CreateTable { table } => format!("create_{}", table.name),
+            Operation::DropTable { table } => format!("drop_{}", table.name),
+            Operation::AlterTable { after, .. } => format!("alter_{}", after.name),
+        }
+    }
+}
+
+/// Ergebnis eines Schema-Vergleichs
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDiff {
+    pub up: Vec<Operation>,
+    pub warnings: Vec<String>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.up.is_empty()
+    }
+
+    /// Umkehrung: inverse Operationen in umgekehrter Reihenfolge
+    pub fn down(&self) -> Vec<Operation> {
+        self.up.iter().rev().map(Operation::invert).collect()
+    }
+}
+
+/// Vergleicht den gespeicherten Snapshot mit dem aktuellen Schema
+///
+/// Reihenfolge: neue Tabellen (referenzierte zuerst), geänderte Tabellen,
+/// gelöschte Tabellen (referenzierende zuerst). Umbenennungen werden nicht
+/// erkannt und erscheinen als Löschen + Anlegen.
+pub fn diff(old: &Schema, new: &Schema) -> SchemaDiff {
+    let mut result = SchemaDiff::default();
+
+    let created: Vec<&Table> = new
+        .tables
+        .values()
+        .filter(|table| !old.tables.contains_key(&table.name))
+        .collect();
+    for table in dependency_order(&created) {
+        result.up.push(Operation::CreateTable {
+            table: table.clone(),
+        });
+    }
+
+    for (name, after) in &new.tables {
+        let Some(before) = old.tables.get(name) else {
+            continue;
+        };
+        if before.same_structure(after) {
+            continue;
+        }
+        for column in &before.columns {
+            if after.column(&column.name).is_none() {
+                result.warnings.push(format!(
+                    "Spalte '{}.{}' wird gelöscht, ihre Daten gehen verloren",
+                    name, column.name
+                ));
+            }
+        }
+        for column in &after.columns {
+            let added = before.column(&column.name).is_none();
+            if added && !column.nullable && column.default.is_none() {
+                result.warnings.push(format!(
+                    "Spalte '{}.{}' ist NOT NULL ohne Default; bestehende Zeilen erhalten den Leerwert des Typs",
+                    name, column.name
+                ));
+            }
+        }
+        result.up.push(Operation::AlterTable {
+            before: before.clone(),
+            after: after.clone(),
+        });
+    }
+
+    let dropped: Vec<&Table> = old
+        .tables
+        .values()
+        .filter(|table| !new.tables.contains_key(&table.name))
+        .collect();
+    for table in dependency_order(&dropped).into_iter().rev() {
+        result.warnings.push(format!(
+            "Tabelle '{}' wird gelöscht, ihre Daten gehen verloren",
+            table.name
+        ));
+        result.up.push(Operation::DropTable {
+            table: table.clone(),
+        });
+    }
+
+    result
+}
+
+/// Topologische Ordnung: referenzierte Tabellen vor referenzierenden
+///
+/// Zyklen werden in Namensreihenfolge aufgelöst.
+fn dependency_order<'a>(tables: &[&'a Table]) -> Vec<&'a Table> {
+    let by_name: BTreeMap<&str, &Table> = tables.iter().map(|t| (t.name.as_str(), *t)).collect();
+    let mut ordered = Vec::new();
+    let mut done = BTreeSet::new();
+    let mut visiting = BTreeSet::new();
+
+    fn visit<'a>(
+        name: &'a str,
+        by_name: &BTreeMap<&'a str, &'a Table>,
+        done: &mut BTreeSet<&'a str>,
+        visiting: &mut BTreeSet<&'a str>,
+        ordered: &mut Vec<&'a Table>,
+    ) {
+        if done.contains(name) || !visiting.insert(name) {
+            return;
+        }
+        let table = by_name[name];
+        for dependency in table.dependencies() {
+            if let Some((key, _)) = by_name.get_key_value(dependency) {
+                visit(key, by_name, done, visiting, ordered);
+            }
+        }
+        visiting.remove(name);
+        done.insert(name);
+        ordered.push(table);
+    }
+
+    for name in by_name.keys() {
+        visit(name, &by_name, &mut done, &mut visiting, &mut ordered);
+    }
+    ordered
+}
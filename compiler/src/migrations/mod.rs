@@ -0,0 +1,263 @@
+// Schema-Migrationen aus `@Entity`-Structs
+// Vergleicht die Structs mit einem gespeicherten Schema-Snapshot und erzeugt
+// geordnete, umkehrbare Migrationen für SQLite, PostgreSQL und MySQL
+
+pub mod diff;
+pub mod runner;
+pub mod schema;
+pub mod sql;
+
+pub use diff::{diff, Operation, SchemaDiff};
+pub use runner::{Database, MigrationRunner, MigrationStatus};
+pub use schema::{Column, ColumnType, Schema, Table};
+pub use sql::Dialect;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Snapshot des Schemas nach der letzten generierten Migration
+pub const SNAPSHOT_FILE: &str = "schema.json";
+
+/// Tabelle, in der die angewendeten Migrationen stehen
+pub const MIGRATIONS_TABLE: &str = "velin_migrations";
+
+/// Eine Migration; Dateiname `<version>_<name>.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Migration {
+    pub version: String,
+    pub name: String,
+    pub up: Vec<Operation>,
+    pub down: Vec<Operation>,
+}
+
+impl Migration {
+    pub fn file_name(&self) -> String {
+        format!("{}_{}.json", self.version, self.name)
+    }
+
+    /// SQL einer Richtung für ein Datenbanksystem
+    pub fn statements(&self, dialect: Dialect, up: bool) -> Vec<String> {
+        dialect.render(if up { &self.up } else { &self.down })
+    }
+
+    /// Alle Migrationen eines Verzeichnisses, nach Version sortiert
+    pub fn load_all(dir: &Path) -> Result<Vec<Migration>> {
+        let mut migrations = Vec::new();
+        if !dir.exists() {
+            return Ok(migrations);
+        }
+        for entry in fs::read_dir(dir)
+            .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if !file_name.ends_with(".json") || !file_name.starts_with(|c: char| c.is_ascii_digit())
+            {
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read file: {}", path.display()))?;
+            let migration: Migration = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid migration: {}", path.display()))?;
+            if migration.file_name() != file_name {
+                anyhow::bail!(
+                    "Migration {} declares version '{}' and name '{}', expected file name {}",
+                    path.display(),
+                    migration.version,
+                    migration.name,
+                    migration.file_name()
+                );
+            }
+            migrations.push(migration);
+        }
+        migrations.sort_by(|a, b| a.version.cmp(&b.version));
+        Ok(migrations)
+    }
+}
+
+/// Liest den Snapshot; ohne Snapshot ist das Schema leer
+pub fn load_snapshot(dir: &Path) -> Result<Schema> {
+    let path = dir.join(SNAPSHOT_FILE);
+    if !path.exists() {
+        return Ok(Schema::default());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid snapshot: {}", path.display()))
+}
+
+/// Ergebnis von `velin db migrate generate`
+#[derive(Debug)]
+pub struct GeneratedMigration {
+    pub migration: Migration,
+    pub path: PathBuf,
+    pub warnings: Vec<String>,
+}
+
+/// Erzeugt eine Migration vom Snapshot zum aktuellen Schema und aktualisiert den Snapshot
+///
+/// Gibt `None` zurück, wenn sich das Schema nicht geändert hat.
+pub fn generate(
+    dir: &Path,
+    schema: &Schema,
+    name: Option<&str>,
+) -> Result<Option<GeneratedMigration>> {
+    let snapshot = load_snapshot(dir)?;
+    let changes = diff(&snapshot, schema);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let existing = Migration::load_all(dir)?;
+    let name = match name {
+        Some(name) => sanitize_name(name),
+        None if changes.up.len() == 1 => changes.up[0].slug(),
+        None => "update_schema".to_string(),
+    };
+    let migration = Migration {
+        version: next_version(existing.last().map(|m| m.version.as_str())),
+        name,
+        down: changes.down(),
+        up: changes.up,
+    };
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let path = dir.join(migration.file_name());
+    fs::write(&path, serde_json::to_string_pretty(&migration)? + "\n")
+        .with_context(|| format!("Failed to write file: {}", path.display()))?;
+    fs::write(
+        dir.join(SNAPSHOT_FILE),
+        serde_json::to_string_pretty(schema)? + "\n",
+    )
+    .with_context(|| format!("Failed to write snapshot in {}", dir.display()))?;
+
+    Ok(Some(GeneratedMigration {
+        migration,
+        path,
+        warnings: changes.warnings,
+    }))
+}
+
+/// Zeitstempel-Version, immer größer als die letzte vorhandene
+fn next_version(last: Option<&str>) -> String {
+    let now = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+    match last {
+        Some(last) if last >= now.as_str() => last
+            .parse::<u64>()
+            .map(|version| (version + 1).to_string())
+            .unwrap_or(now),
+        _ => now,
+    }
+}
+
+fn sanitize_name(name: &str) -> String {
+    let name: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let name = name.trim_matches('_');
+    if name.is_empty() {
+        "migration".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    const MODELS: &str = r#"@Entity(table: "users")
+struct User {
+    @Id
+    @Generated
+    id: number,
+    @Column(unique: true)
+    email: string,
+    bio: Option<string>,
+}
+
+@Entity
+struct BlogPost {
+    id: string,
+    title: string,
+    @BelongsTo(User)
+    authorId: number,
+}
+"#;
+
+    fn schema(source: &str) -> Schema {
+        Schema::from_program(&Parser::parse(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_diff_orders_tables_and_reverses() {
+        let schema = schema(MODELS);
+        let posts = &schema.tables["blog_post"];
+        assert_eq!(posts.foreign_keys[0].references, "users");
+        assert!(schema.tables["users"].columns[2].nullable);
+
+        let changes = diff(&Schema::default(), &schema);
+        let slugs: Vec<String> = changes.up.iter().map(Operation::slug).collect();
+        assert_eq!(slugs, vec!["create_users", "create_blog_post"]);
+        let down: Vec<String> = changes.down().iter().map(Operation::slug).collect();
+        assert_eq!(down, vec!["drop_blog_post", "drop_users"]);
+
+        let sql = Dialect::Postgres.render(&changes.up).join(";\n");
+        assert!(sql.contains("GENERATED BY DEFAULT AS IDENTITY"));
+        assert!(sql.contains("CREATE UNIQUE INDEX \"uq_users_email\""));
+        assert!(sql.contains("REFERENCES \"users\" (\"id\")"));
+        assert!(diff(&schema, &schema).is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_up_and_down() {
+        if std::process::Command::new("sqlite3")
+            .arg("-version")
+            .output()
+            .is_err()
+        {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let migrations = dir.path().join("migrations");
+        generate(&migrations, &schema(MODELS), None)
+            .unwrap()
+            .unwrap();
+        let changed = MODELS.replace("    bio: Option<string>,\n", "    age: number,\n");
+        let generated = generate(&migrations, &schema(&changed), Some("Add age"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(generated.migration.name, "add_age");
+        assert_eq!(generated.warnings.len(), 2);
+
+        let database =
+            Database::from_url(&format!("sqlite:{}", dir.path().join("app.db").display())).unwrap();
+        let runner =
+            MigrationRunner::new(database.clone(), Migration::load_all(&migrations).unwrap());
+        for migration in runner.pending(None).unwrap() {
+            runner.apply(migration, true).unwrap();
+        }
+        assert!(runner.pending(None).unwrap().is_empty());
+        database
+            .run("INSERT INTO users (email, age) VALUES ('a@example.com', 42);")
+            .unwrap();
+
+        let reverted = runner.to_revert(1).unwrap();
+        assert_eq!(reverted[0].name, "add_age");
+        runner.apply(reverted[0], false).unwrap();
+        let rows = database.query("SELECT email, bio FROM users").unwrap();
+        assert_eq!(rows, vec![vec!["a@example.com".to_string(), String::new()]]);
+
+        let status = runner.status().unwrap();
+        assert!(status[0].applied_at.is_some());
+        assert!(status[1].applied_at.is_none());
+    }
+}
@@ -0,0 +1,318 @@
+// Wendet Migrationen über die Kommandozeilen-Clients an (sqlite3, psql, mysql)
+
+use super::{Dialect, Migration, MIGRATIONS_TABLE};
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Verbindung aus einer Datenbank-URL
+#[derive(Debug, Clone)]
+pub enum Database {
+    Sqlite {
+        path: String,
+    },
+    Postgres {
+        url: String,
+    },
+    MySql {
+        host: String,
+        port: u16,
+        user: String,
+        password: Option<String>,
+        database: String,
+    },
+}
+
+impl Database {
+    /// `sqlite:app.db`, `sqlite://./app.db`, `postgres://…`, `mysql://…` oder ein `.db`-Pfad
+    pub fn from_url(url: &str) -> Result<Self> {
+        if let Some(rest) = url.strip_prefix("sqlite:") {
+            let path = rest.strip_prefix("//").unwrap_or(rest);
+            let path = path.split('?').next().unwrap_or(path);
+            return Ok(Database::Sqlite {
+                path: path.to_string(),
+            });
+        }
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            return Ok(Database::Postgres {
+                url: url.to_string(),
+            });
+        }
+        if url.starts_with("mysql://") || url.starts_with("mariadb://") {
+            let parsed =
+                url::Url::parse(url).with_context(|| format!("Invalid database URL: {}", url))?;
+            let decode = |s: &str| {
+                urlencoding::decode(s)
+                    .map(|s| s.into_owned())
+                    .unwrap_or_else(|_| s.to_string())
+            };
+            return Ok(Database::MySql {
+                host: parsed.host_str().unwrap_or("localhost").to_string(),
+                port: parsed.port().unwrap_or(3306),
+                user: decode(parsed.username()),
+                password: parsed.password().map(decode),
+                database: parsed.path().trim_start_matches('/').to_string(),
+            });
+        }
+        if [".db", ".sqlite", ".sqlite3"]
+            .iter()
+            .any(|extension| url.ends_with(extension))
+        {
+            return Ok(Database::Sqlite {
+                path: url.to_string(),
+            });
+        }
+        anyhow::bail!(
+            "Unsupported database URL '{}' (expected sqlite:, postgres:// or mysql://)",
+            url
+        )
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        match self {
+            Database::Sqlite { .. } => Dialect::Sqlite,
+            Database::Postgres { .. } => Dialect::Postgres,
+            Database::MySql { .. } => Dialect::MySql,
+        }
+    }
+
+    fn command(&self) -> Command {
+        match self {
+            Database::Sqlite { path } => {
+                let mut command = Command::new("sqlite3");
+                command.args(["-bail", "-batch", "-noheader", "-separator", "\t"]);
+                command.arg(path);
+                command
+            }
+            Database::Postgres { url } => {
+                let mut command = Command::new("psql");
+                command.arg(url);
+                command.args(["-X", "-q", "-t", "-A", "-F", "\t", "-v", "ON_ERROR_STOP=1"]);
+                command
+            }
+            Database::MySql {
+                host,
+                port,
+                user,
+                password,
+                database,
+            } => {
+                let mut command = Command::new("mysql");
+                command.args(["-h", host, "-P", &port.to_string(), "-N", "-B"]);
+                if !user.is_empty() {
+                    command.args(["-u", user]);
+                }
+                // Passwort nicht in der Prozessliste
+                if let Some(password) = password {
+                    command.env("MYSQL_PWD", password);
+                }
+                command.arg(database);
+                command
+            }
+        }
+    }
+
+    /// Führt ein SQL-Skript aus und liefert die Ausgabe
+    pub(crate) fn run(&self, script: &str) -> Result<String> {
+        let mut command = self.command();
+        let program = command.get_program().to_string_lossy().to_string();
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "Failed to start '{}'; install the {} client to run migrations",
+                    program,
+                    self.dialect().name()
+                )
+            })?;
+        child
+            .stdin
+            .take()
+            .context("Failed to open stdin of the database client")?
+            .write_all(script.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Zeilen einer Abfrage, Spalten tab-getrennt
+    pub(crate) fn query(&self, sql: &str) -> Result<Vec<Vec<String>>> {
+        Ok(self
+            .run(&format!("{};\n", sql))?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .collect())
+    }
+}
+
+/// Stand einer Migration in der Datenbank
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub version: String,
+    pub name: String,
+    pub applied_at: Option<String>,
+    /// In der Datenbank angewendet, aber lokal nicht vorhanden
+    pub missing: bool,
+}
+
+pub struct MigrationRunner {
+    database: Database,
+    migrations: Vec<Migration>,
+}
+
+impl MigrationRunner {
+    pub fn new(database: Database, migrations: Vec<Migration>) -> Self {
+        Self {
+            database,
+            migrations,
+        }
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.database.dialect()
+    }
+
+    /// (Version, Name, Zeitpunkt) der angewendeten Migrationen
+    fn applied(&self) -> Result<Vec<(String, String, String)>> {
+        self.database.run(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (version VARCHAR(32) PRIMARY KEY, name VARCHAR(255) NOT NULL, applied_at VARCHAR(64) NOT NULL);\n",
+            MIGRATIONS_TABLE
+        ))?;
+        let rows = self.database.query(&format!(
+            "SELECT version, name, applied_at FROM {} ORDER BY version",
+            MIGRATIONS_TABLE
+        ))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|mut row| {
+                if row.len() < 3 {
+                    return None;
+                }
+                let applied_at = row.remove(2);
+                let name = row.remove(1);
+                Some((row.remove(0), name, applied_at))
+            })
+            .collect())
+    }
+
+    pub fn status(&self) -> Result<Vec<MigrationStatus>> {
+        let applied = self.applied()?;
+        let mut status: Vec<MigrationStatus> = self
+            .migrations
+            .iter()
+            .map(|migration| MigrationStatus {
+                version: migration.version.clone(),
+                name: migration.name.clone(),
+                applied_at: applied
+                    .iter()
+                    .find(|(version, _, _)| *version == migration.version)
+                    .map(|(_, _, at)| at.clone()),
+                missing: false,
+            })
+            .collect();
+        for (version, name, applied_at) in applied {
+            if !self.migrations.iter().any(|m| m.version == version) {
+                status.push(MigrationStatus {
+                    version,
+                    name,
+                    applied_at: Some(applied_at),
+                    missing: true,
+                });
+            }
+        }
+        status.sort_by(|a, b| a.version.cmp(&b.version));
+        Ok(status)
+    }
+
+    /// Noch nicht angewendete Migrationen bis einschließlich `to`
+    pub fn pending(&self, to: Option<&str>) -> Result<Vec<&Migration>> {
+        let applied = self.applied()?;
+        if let Some(to) = to {
+            if !self.migrations.iter().any(|m| m.version == to) {
+                anyhow::bail!("Unknown migration version '{}'", to);
+            }
+        }
+        Ok(self
+            .migrations
+            .iter()
+            .filter(|m| !applied.iter().any(|(version, _, _)| *version == m.version))
+            .filter(|m| to.is_none_or(|to| m.version.as_str() <= to))
+            .collect())
+    }
+
+    /// Die letzten `steps` angewendeten Migrationen, neueste zuerst
+    pub fn to_revert(&self, steps: usize) -> Result<Vec<&Migration>> {
+        let applied = self.applied()?;
+        applied
+            .iter()
+            .rev()
+            .take(steps)
+            .map(|(version, name, _)| {
+                self.migrations
+                    .iter()
+                    .find(|m| m.version == *version)
+                    .with_context(|| {
+                        format!(
+                            "Migration {}_{} is applied but missing locally; it cannot be reverted",
+                            version, name
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// SQL-Skript einer Migration inklusive Eintrag in der Migrations-Tabelle
+    pub fn script(&self, migration: &Migration, up: bool) -> String {
+        let dialect = self.dialect();
+        let mut statements = migration.statements(dialect, up);
+        statements.push(if up {
+            format!(
+                "INSERT INTO {} (version, name, applied_at) VALUES ('{}', '{}', '{}')",
+                MIGRATIONS_TABLE,
+                migration.version.replace('\'', "''"),
+                migration.name.replace('\'', "''"),
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            )
+        } else {
+            format!(
+                "DELETE FROM {} WHERE version = '{}'",
+                MIGRATIONS_TABLE,
+                migration.version.replace('\'', "''")
+            )
+        });
+        let body: String = statements.iter().map(|s| format!("{};\n", s)).collect();
+        match dialect {
+            // Fremdschlüssel aus, damit der Tabellen-Neuaufbau referenzierte Tabellen ersetzen kann
+            Dialect::Sqlite => format!(
+                "PRAGMA foreign_keys = OFF;\nBEGIN;\n{}COMMIT;\nPRAGMA foreign_keys = ON;\n",
+                body
+            ),
+            Dialect::Postgres => format!("BEGIN;\n{}COMMIT;\n", body),
+            // DDL in MySQL committet implizit; keine Transaktion möglich
+            Dialect::MySql => body,
+        }
+    }
+
+    pub fn apply(&self, migration: &Migration, up: bool) -> Result<()> {
+        self.database
+            .run(&self.script(migration, up))
+            .with_context(|| {
+                format!(
+                    "Migration {} ({}) failed",
+                    migration.file_name(),
+                    if up { "up" } else { "down" }
+                )
+            })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+// Datenbank-Schema aus `@Entity`-Structs
+
+use crate::parser::ast::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Gesamtes Schema, nach Tabellennamen sortiert
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    pub tables: BTreeMap<String, Table>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub primary_key: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indexes: Vec<Index>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub foreign_keys: Vec<ForeignKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: ColumnType,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub nullable: bool,
+    /// Vom Datenbanksystem vergeben (Auto-Increment)
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub generated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<DefaultValue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Real,
+    Boolean,
+    Timestamp,
+    Json,
+}
+
+impl ColumnType {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "text" | "string" => Some(ColumnType::Text),
+            "integer" | "int" | "bigint" => Some(ColumnType::Integer),
+            "real" | "float" | "double" | "number" => Some(ColumnType::Real),
+            "boolean" | "bool" => Some(ColumnType::Boolean),
+            "timestamp" | "datetime" => Some(ColumnType::Timestamp),
+            "json" => Some(ColumnType::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DefaultValue {
+    Boolean(bool),
+    Number(f64),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    pub name: String,
+    pub columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKey {
+    pub name: String,
+    pub column: String,
+    pub references: String,
+    pub referenced_column: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_delete: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl Table {
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    /// Gleiche Struktur unabhängig von der Reihenfolge der Spalten
+    pub fn same_structure(&self, other: &Table) -> bool {
+        let sorted = |table: &Table| {
+            let mut columns = table.columns.clone();
+            columns.sort_by(|a, b| a.name.cmp(&b.name));
+            columns
+        };
+        self.name == other.name
+            && self.primary_key == other.primary_key
+            && self.indexes == other.indexes
+            && self.foreign_keys == other.foreign_keys
+            && sorted(self) == sorted(other)
+    }
+
+    /// Tabellen, auf die Foreign Keys verweisen (ohne Selbstreferenz)
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.foreign_keys
+            .iter()
+            .map(|fk| fk.references.as_str())
+            .filter(move |table| *table != self.name)
+    }
+}
+
+/// Entity-Metadaten, die vor dem Aufbau der Spalten bekannt sein müssen
+struct EntityInfo {
+    table: String,
+    primary_key: Option<(String, ColumnType)>,
+}
+
+impl Schema {
+    /// Baut das Schema aus allen `@Entity`-Structs (auch in Modulen)
+    pub fn from_program(program: &Program) -> Result<Schema, Vec<String>> {
+        let mut structs = Vec::new();
+        let mut enums = HashSet::new();
+        collect(&program.items, &mut structs, &mut enums);
+
+        let entities: Vec<&Struct> = structs
+            .iter()
+            .copied()
+            .filter(|s| find_decorator(&s.decorators, "Entity").is_some())
+            .collect();
+        let infos: HashMap<&str, EntityInfo> = entities
+            .iter()
+            .map(|s| {
+                let primary_key = primary_key_field(s).map(|field| {
+                    let column_type = match unwrap_optional(&field.field_type) {
+                        Type::Number => ColumnType::Integer,
+                        other => scalar_type(other).unwrap_or(ColumnType::Text),
+                    };
+                    (column_name(field), column_type)
+                });
+                (
+                    s.name.as_str(),
+                    EntityInfo {
+                        table: table_name(s),
+                        primary_key,
+                    },
+                )
+            })
+            .collect();
+
+        let mut schema = Schema::default();
+        let mut errors = Vec::new();
+        for entity in entities {
+            match build_table(entity, &infos, &enums) {
+                Ok(table) => {
+                    if schema.tables.contains_key(&table.name) {
+                        errors.push(format!(
+                            "Entity '{}' uses table '{}', which is already used by another entity",
+                            entity.name, table.name
+                        ));
+                    } else {
+                        schema.tables.insert(table.name.clone(), table);
+                    }
+                }
+                Err(mut entity_errors) => errors.append(&mut entity_errors),
+            }
+        }
+        if errors.is_empty() {
+            Ok(schema)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+fn collect<'a>(items: &'a [Item], structs: &mut Vec<&'a Struct>, enums: &mut HashSet<&'a str>) {
+    for item in items {
+        match item {
+            Item::Struct(s) => structs.push(s),
+            Item::Enum(e) => {
+                enums.insert(e.name.as_str());
+            }
+            Item::Module(m) => collect(&m.items, structs, enums),
+            _ => {}
+        }
+    }
+}
+
+fn build_table(
+    entity: &Struct,
+    infos: &HashMap<&str, EntityInfo>,
+    enums: &HashSet<&str>,
+) -> Result<Table, Vec<String>> {
+    let info = &infos[entity.name.as_str()];
+    let Some((primary_key, _)) = &info.primary_key else {
+        return Err(vec![format!(
+            "Entity '{}' has no primary key; mark a field with @Id",
+            entity.name
+        )]);
+    };
+
+    let mut table = Table {
+        name: info.table.clone(),
+        columns: Vec::new(),
+        primary_key: vec![primary_key.clone()],
+        indexes: Vec::new(),
+        foreign_keys: Vec::new(),
+    };
+    let mut errors = Vec::new();
+    // Feldname -> Spaltenname, für Struct-Level-Indizes
+    let mut field_columns = HashMap::new();
+
+    for field in &entity.fields {
+        if find_decorator(&field.decorators, "Ignore").is_some()
+            || find_decorator(&field.decorators, "Transient").is_some()
+        {
+            continue;
+        }
+        let column_decorator = find_decorator(&field.decorators, "Column");
+        let nullable_type = matches!(field.field_type, Type::Optional(_))
+            || matches!(&field.field_type, Type::Generic { name, .. } if name == "Option" || name == "Optional");
+        let inner = unwrap_optional(&field.field_type);
+
+        // Beziehung über den Feldtyp (`author: User`) oder `@BelongsTo(User)`
+        let relation = find_decorator(&field.decorators, "BelongsTo")
+            .or_else(|| find_decorator(&field.decorators, "ForeignKey"));
+        let target = match (relation, inner) {
+            (Some(decorator), _) => match positional(decorator) {
+                Some(DecoratorArg::Identifier(name)) | Some(DecoratorArg::String(name)) => {
+                    Some(name.clone())
+                }
+                _ => {
+                    errors.push(format!(
+                        "@{} on '{}.{}' needs the referenced entity, e.g. @{}(User)",
+                        decorator.name, entity.name, field.name, decorator.name
+                    ));
+                    continue;
+                }
+            },
+            (None, Type::Named(name)) if infos.contains_key(name.as_str()) => Some(name.clone()),
+            _ => None,
+        };
+        // Has-many-Seite einer Beziehung hat keine eigene Spalte
+        if let Type::List(item) = inner {
+            if matches!(item.as_ref(), Type::Named(name) if infos.contains_key(name.as_str())) {
+                continue;
+            }
+        }
+
+        let mut name = column_name(field);
+        let column_type = if let Some(target) = &target {
+            let Some(target_info) = infos.get(target.as_str()) else {
+                errors.push(format!(
+                    "'{}.{}' references '{}', which is not an @Entity",
+                    entity.name, field.name, target
+                ));
+                continue;
+            };
+            let Some((referenced_column, referenced_type)) = &target_info.primary_key else {
+                continue;
+            };
+            if relation.is_none() && named_arg(column_decorator, "name").is_none() {
+                name = format!("{}_id", name);
+            }
+            let on_delete = relation
+                .and_then(|decorator| named_arg(Some(decorator), "onDelete"))
+                .and_then(string_arg)
+                .map(|action| action.to_uppercase().replace('_', " "));
+            table.foreign_keys.push(ForeignKey {
+                name: format!("fk_{}_{}", table.name, name),
+                column: name.clone(),
+                references: target_info.table.clone(),
+                referenced_column: referenced_column.clone(),
+                on_delete,
+            });
+            *referenced_type
+        } else if name == *primary_key {
+            info.primary_key.as_ref().map(|(_, t)| *t).unwrap()
+        } else {
+            column_type(inner, enums)
+        };
+        let column_type = match named_arg(column_decorator, "type").and_then(string_arg) {
+            Some(explicit) => match ColumnType::parse(explicit) {
+                Some(column_type) => column_type,
+                None => {
+                    errors.push(format!(
+                        "Unknown column type '{}' on '{}.{}' (text, integer, real, boolean, timestamp, json)",
+                        explicit, entity.name, field.name
+                    ));
+                    continue;
+                }
+            },
+            None => column_type,
+        };
+
+        let nullable = match named_arg(column_decorator, "nullable") {
+            Some(DecoratorArg::Boolean(value)) => *value,
+            _ => nullable_type,
+        };
+        let default = named_arg(column_decorator, "default").and_then(|arg| match arg {
+            DecoratorArg::String(s) => Some(DefaultValue::String(s.clone())),
+            DecoratorArg::Number(n) => Some(DefaultValue::Number(*n)),
+            DecoratorArg::Boolean(b) => Some(DefaultValue::Boolean(*b)),
+            _ => None,
+        });
+        let is_primary = name == *primary_key;
+        table.columns.push(Column {
+            name: name.clone(),
+            column_type,
+            nullable: nullable && !is_primary,
+            generated: is_primary
+                && column_type == ColumnType::Integer
+                && find_decorator(&field.decorators, "Generated").is_some(),
+            default,
+        });
+
+        let unique = matches!(
+            named_arg(column_decorator, "unique"),
+            Some(DecoratorArg::Boolean(true))
+        ) || find_decorator(&field.decorators, "Unique").is_some();
+        let index = find_decorator(&field.decorators, "Index");
+        if unique || index.is_some() {
+            let unique = unique
+                || matches!(
+                    named_arg(index, "unique"),
+                    Some(DecoratorArg::Boolean(true))
+                );
+            table
+                .indexes
+                .push(index_for(&table.name, vec![name.clone()], unique));
+        }
+        field_columns.insert(field.name.as_str(), name);
+    }
+
+    // `@Index("a", "b")` bzw. `@Index(["a", "b"], unique: true)` am Struct
+    for decorator in entity.decorators.iter().filter(|d| d.name == "Index") {
+        let mut fields = Vec::new();
+        for arg in &decorator.args {
+            match arg {
+                DecoratorArg::String(s) | DecoratorArg::Identifier(s) => fields.push(s.clone()),
+                DecoratorArg::List(items) => {
+                    fields.extend(items.iter().filter_map(|item| match item {
+                        DecoratorArg::String(s) | DecoratorArg::Identifier(s) => Some(s.clone()),
+                        _ => None,
+                    }))
+                }
+                _ => {}
+            }
+        }
+        let mut columns = Vec::new();
+        for field in fields {
+            match field_columns.get(field.as_str()) {
+                Some(column) => columns.push(column.clone()),
+                None => errors.push(format!(
+                    "@Index on '{}' references unknown field '{}'",
+                    entity.name, field
+                )),
+            }
+        }
+        if !columns.is_empty() {
+            let unique = matches!(
+                named_arg(Some(decorator), "unique"),
+                Some(DecoratorArg::Boolean(true))
+            );
+            table.indexes.push(index_for(&table.name, columns, unique));
+        }
+    }
+
+    table.indexes.sort_by(|a, b| a.name.cmp(&b.name));
+    table.indexes.dedup_by(|a, b| a.name == b.name);
+    table.foreign_keys.sort_by(|a, b| a.name.cmp(&b.name));
+    if errors.is_empty() {
+        Ok(table)
+    } else {
+        Err(errors)
+    }
+}
+
+fn index_for(table: &str, columns: Vec<String>, unique: bool) -> Index {
+    let prefix = if unique { "uq" } else { "idx" };
+    Index {
+        name: format!("{}_{}_{}", prefix, table, columns.join("_")),
+        columns,
+        unique,
+    }
+}
+
+/// Tabellenname aus `@Entity(table: "...")`, sonst der Struct-Name in snake_case
+pub fn table_name(entity: &Struct) -> String {
+    let decorator = find_decorator(&entity.decorators, "Entity");
+    named_arg(decorator, "table")
+        .or_else(|| decorator.and_then(positional))
+        .and_then(string_arg)
+        .map(str::to_string)
+        .unwrap_or_else(|| snake_case(&entity.name))
+}
+
+/// Spaltenname aus `@Column(name: "...")`, sonst der Feldname in snake_case
+pub fn column_name(field: &StructField) -> String {
+    named_arg(find_decorator(&field.decorators, "Column"), "name")
+        .and_then(string_arg)
+        .map(str::to_string)
+        .unwrap_or_else(|| snake_case(&field.name))
+}
+
+/// `@Id`-Feld, sonst ein Feld namens `id`
+pub fn primary_key_field(entity: &Struct) -> Option<&StructField> {
+    entity
+        .fields
+        .iter()
+        .find(|field| find_decorator(&field.decorators, "Id").is_some())
+        .or_else(|| entity.fields.iter().find(|field| field.name == "id"))
+}
+
+/// Inneren Typ von `Option<T>` bzw. `T?`
+pub fn unwrap_optional(ty: &Type) -> &Type {
+    match ty {
+        Type::Optional(inner) => inner,
+        Type::Generic { name, params }
+            if (name == "Option" || name == "Optional") && params.len() == 1 =>
+        {
+            &params[0]
+        }
+        other => other,
+    }
+}
+
+fn scalar_type(ty: &Type) -> Option<ColumnType> {
+    match ty {
+        Type::String => Some(ColumnType::Text),
+        Type::Number => Some(ColumnType::Real),
+        Type::Boolean => Some(ColumnType::Boolean),
+        Type::Named(name) if matches!(name.as_str(), "DateTime" | "Date" | "Timestamp") => {
+            Some(ColumnType::Timestamp)
+        }
+        _ => None,
+    }
+}
+
+fn column_type(ty: &Type, enums: &HashSet<&str>) -> ColumnType {
+    if let Some(scalar) = scalar_type(ty) {
+        return scalar;
+    }
+    match ty {
+        // Enums werden als Variantenname gespeichert
+        Type::Named(name) if enums.contains(name.as_str()) => ColumnType::Text,
+        _ => ColumnType::Json,
+    }
+}
+
+fn find_decorator<'a>(decorators: &'a [Decorator], name: &str) -> Option<&'a Decorator> {
+    decorators.iter().find(|decorator| decorator.name == name)
+}
+
+fn named_arg<'a>(decorator: Option<&'a Decorator>, name: &str) -> Option<&'a DecoratorArg> {
+    decorator?.args.iter().find_map(|arg| match arg {
+        DecoratorArg::Named {
+            name: arg_name,
+            value,
+        } if arg_name == name => Some(value.as_ref()),
+        _ => None,
+    })
+}
+
+fn positional(decorator: &Decorator) -> Option<&DecoratorArg> {
+    decorator
+        .args
+        .iter()
+        .find(|arg| !matches!(arg, DecoratorArg::Named { .. }))
+}
+
+fn string_arg(arg: &DecoratorArg) -> Option<&str> {
+    match arg {
+        DecoratorArg::String(s) | DecoratorArg::Identifier(s) => Some(s),
+        _ => None,
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            result.push('_');
+        }
+        result.extend(c.to_lowercase());
+    }
+    result
+}
@@ -0,0 +1,441 @@
+// SQL für SQLite, PostgreSQL und MySQL aus Schema-Operationen
+
+use super::diff::Operation;
+use super::schema::{Column, ColumnType, DefaultValue, ForeignKey, Index, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Sqlite,
+    Postgres,
+    MySql,
+}
+
+impl Dialect {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "sqlite" => Some(Dialect::Sqlite),
+            "postgres" | "postgresql" => Some(Dialect::Postgres),
+            "mysql" | "mariadb" => Some(Dialect::MySql),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dialect::Sqlite => "sqlite",
+            Dialect::Postgres => "postgres",
+            Dialect::MySql => "mysql",
+        }
+    }
+
+    fn quote(&self, ident: &str) -> String {
+        match self {
+            Dialect::MySql => format!("`{}`", ident.replace('`', "``")),
+            _ => format!("\"{}\"", ident.replace('"', "\"\"")),
+        }
+    }
+
+    fn quote_list(&self, idents: &[String]) -> String {
+        idents
+            .iter()
+            .map(|ident| self.quote(ident))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn column_type(&self, column_type: ColumnType) -> &'static str {
+        match (self, column_type) {
+            (Dialect::Sqlite, ColumnType::Text) => "TEXT",
+            (Dialect::Sqlite, ColumnType::Integer) => "INTEGER",
+            (Dialect::Sqlite, ColumnType::Real) => "REAL",
+            (Dialect::Sqlite, ColumnType::Boolean) => "BOOLEAN",
+            (Dialect::Sqlite, ColumnType::Timestamp) => "TEXT",
+            (Dialect::Sqlite, ColumnType::Json) => "TEXT",
+            (Dialect::Postgres, ColumnType::Text) => "TEXT",
+            (Dialect::Postgres, ColumnType::Integer) => "BIGINT",
+            (Dialect::Postgres, ColumnType::Real) => "DOUBLE PRECISION",
+            (Dialect::Postgres, ColumnType::Boolean) => "BOOLEAN",
+            (Dialect::Postgres, ColumnType::Timestamp) => "TIMESTAMPTZ",
+            (Dialect::Postgres, ColumnType::Json) => "JSONB",
+            // TEXT kann in MySQL weder Primary Key noch Index ohne Länge sein
+            (Dialect::MySql, ColumnType::Text) => "VARCHAR(255)",
+            (Dialect::MySql, ColumnType::Integer) => "BIGINT",
+            (Dialect::MySql, ColumnType::Real) => "DOUBLE",
+            (Dialect::MySql, ColumnType::Boolean) => "BOOLEAN",
+            (Dialect::MySql, ColumnType::Timestamp) => "DATETIME",
+            (Dialect::MySql, ColumnType::Json) => "JSON",
+        }
+    }
+
+    fn literal(&self, value: &DefaultValue) -> String {
+        match value {
+            DefaultValue::String(s) => format!("'{}'", s.replace('\'', "''")),
+            DefaultValue::Number(n) if n.fract() == 0.0 => format!("{}", *n as i64),
+            DefaultValue::Number(n) => n.to_string(),
+            DefaultValue::Boolean(true) => "TRUE".to_string(),
+            DefaultValue::Boolean(false) => "FALSE".to_string(),
+        }
+    }
+
+    /// Leerwert eines Typs für bestehende Zeilen bei neuen NOT-NULL-Spalten
+    fn zero(&self, column_type: ColumnType) -> &'static str {
+        match (self, column_type) {
+            (_, ColumnType::Text) => "''",
+            (_, ColumnType::Integer) | (_, ColumnType::Real) => "0",
+            (_, ColumnType::Boolean) => "FALSE",
+            (Dialect::Postgres, ColumnType::Timestamp) => "'1970-01-01 00:00:00+00'",
+            (_, ColumnType::Timestamp) => "'1970-01-01 00:00:00'",
+            (_, ColumnType::Json) => "'null'",
+        }
+    }
+
+    /// SQLite vergibt Auto-Increment nur für `INTEGER PRIMARY KEY` in der Spalte selbst
+    fn inline_primary_key(&self, table: &Table) -> bool {
+        *self == Dialect::Sqlite
+            && table.primary_key.len() == 1
+            && table
+                .column(&table.primary_key[0])
+                .is_some_and(|column| column.generated)
+    }
+
+    fn column_definition(&self, table: &Table, column: &Column) -> String {
+        let mut definition = format!(
+            "{} {}",
+            self.quote(&column.name),
+            self.column_type(column.column_type)
+        );
+        if self.inline_primary_key(table) && column.name == table.primary_key[0] {
+            definition.push_str(" PRIMARY KEY AUTOINCREMENT");
+            return definition;
+        }
+        if column.generated {
+            match self {
+                Dialect::Postgres => definition.push_str(" GENERATED BY DEFAULT AS IDENTITY"),
+                Dialect::MySql => definition.push_str(" NOT NULL AUTO_INCREMENT"),
+                Dialect::Sqlite => {}
+            }
+        }
+        if !(column.nullable || column.generated && *self == Dialect::MySql) {
+            definition.push_str(" NOT NULL");
+        }
+        if let Some(default) = &column.default {
+            definition.push_str(&format!(" DEFAULT {}", self.literal(default)));
+        }
+        definition
+    }
+
+    fn primary_key_constraint(&self, table: &Table) -> String {
+        let columns = self.quote_list(&table.primary_key);
+        match self {
+            // Fester Name, damit `DROP CONSTRAINT` ihn später findet
+            Dialect::Postgres => format!(
+                "CONSTRAINT {} PRIMARY KEY ({})",
+                self.quote(&format!("{}_pkey", table.name)),
+                columns
+            ),
+            _ => format!("PRIMARY KEY ({})", columns),
+        }
+    }
+
+    fn foreign_key_constraint(&self, fk: &ForeignKey) -> String {
+        let mut constraint = format!(
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+            self.quote(&fk.name),
+            self.quote(&fk.column),
+            self.quote(&fk.references),
+            self.quote(&fk.referenced_column)
+        );
+        if let Some(action) = &fk.on_delete {
+            constraint.push_str(&format!(" ON DELETE {}", action));
+        }
+        constraint
+    }
+
+    fn create_table(&self, table: &Table) -> Vec<String> {
+        self.create_table_named(table, &table.name)
+    }
+
+    fn create_table_named(&self, table: &Table, name: &str) -> Vec<String> {
+        let mut lines: Vec<String> = table
+            .columns
+            .iter()
+            .map(|column| self.column_definition(table, column))
+            .collect();
+        if !self.inline_primary_key(table) {
+            lines.push(self.primary_key_constraint(table));
+        }
+        lines.extend(
+            table
+                .foreign_keys
+                .iter()
+                .map(|fk| self.foreign_key_constraint(fk)),
+        );
+        let mut statements = vec![format!(
+            "CREATE TABLE {} (\n  {}\n)",
+            self.quote(name),
+            lines.join(",\n  ")
+        )];
+        if name == table.name {
+            statements.extend(
+                table
+                    .indexes
+                    .iter()
+                    .map(|index| self.create_index(table, index)),
+            );
+        }
+        statements
+    }
+
+    fn create_index(&self, table: &Table, index: &Index) -> String {
+        format!(
+            "CREATE {}INDEX {} ON {} ({})",
+            if index.unique { "UNIQUE " } else { "" },
+            self.quote(&index.name),
+            self.quote(&table.name),
+            self.quote_list(&index.columns)
+        )
+    }
+
+    fn drop_index(&self, table: &Table, index: &Index) -> String {
+        match self {
+            Dialect::MySql => format!(
+                "DROP INDEX {} ON {}",
+                self.quote(&index.name),
+                self.quote(&table.name)
+            ),
+            _ => format!("DROP INDEX {}", self.quote(&index.name)),
+        }
+    }
+
+    /// Rendert Operationen zu SQL-Statements (ohne abschließendes Semikolon)
+    pub fn render(&self, operations: &[Operation]) -> Vec<String> {
+        let mut statements = Vec::new();
+        for operation in operations {
+            match operation {
+                Operation::CreateTable { table } => statements.extend(self.create_table(table)),
+                Operation::DropTable { table } => {
+                    statements.push(format!("DROP TABLE {}", self.quote(&table.name)))
+                }
+                Operation::AlterTable { before, after } => {
+                    statements.extend(self.alter_table(before, after))
+                }
+            }
+        }
+        statements
+    }
+
+    fn alter_table(&self, before: &Table, after: &Table) -> Vec<String> {
+        let changes = Changes::between(before, after);
+        if *self == Dialect::Sqlite && changes.needs_rebuild() {
+            return self.rebuild_table(before, after);
+        }
+
+        let table = self.quote(&after.name);
+        let alter = |clause: String| format!("ALTER TABLE {} {}", table, clause);
+        let mut statements = Vec::new();
+
+        for fk in &changes.dropped_foreign_keys {
+            statements.push(alter(match self {
+                Dialect::MySql => format!("DROP FOREIGN KEY {}", self.quote(&fk.name)),
+                _ => format!("DROP CONSTRAINT {}", self.quote(&fk.name)),
+            }));
+        }
+        for index in &changes.dropped_indexes {
+            statements.push(self.drop_index(before, index));
+        }
+        if changes.primary_key_changed {
+            statements.push(alter(match self {
+                Dialect::MySql => "DROP PRIMARY KEY".to_string(),
+                _ => format!(
+                    "DROP CONSTRAINT {}",
+                    self.quote(&format!("{}_pkey", after.name))
+                ),
+            }));
+        }
+        for column in &changes.dropped_columns {
+            statements.push(alter(format!("DROP COLUMN {}", self.quote(&column.name))));
+        }
+        for column in &changes.added_columns {
+            let mut definition = self.column_definition(after, column);
+            let fill = !column.nullable && column.default.is_none() && !column.generated;
+            if fill && *self == Dialect::Postgres {
+                // Bestehende Zeilen füllen, danach wieder ohne Default
+                definition.push_str(&format!(" DEFAULT {}", self.zero(column.column_type)));
+            }
+            statements.push(alter(format!("ADD COLUMN {}", definition)));
+            if fill && *self == Dialect::Postgres {
+                statements.push(alter(format!(
+                    "ALTER COLUMN {} DROP DEFAULT",
+                    self.quote(&column.name)
+                )));
+            }
+        }
+        for (old, new) in &changes.altered_columns {
+            statements.extend(self.alter_column(after, old, new).into_iter().map(&alter));
+        }
+        if changes.primary_key_changed {
+            statements.push(alter(format!("ADD {}", self.primary_key_constraint(after))));
+        }
+        for index in &changes.added_indexes {
+            statements.push(self.create_index(after, index));
+        }
+        for fk in &changes.added_foreign_keys {
+            statements.push(alter(format!("ADD {}", self.foreign_key_constraint(fk))));
+        }
+        statements
+    }
+
+    fn alter_column(&self, table: &Table, old: &Column, new: &Column) -> Vec<String> {
+        let name = self.quote(&new.name);
+        match self {
+            Dialect::MySql => vec![format!(
+                "MODIFY COLUMN {}",
+                self.column_definition(table, new)
+            )],
+            _ => {
+                let mut clauses = Vec::new();
+                if old.column_type != new.column_type {
+                    let column_type = self.column_type(new.column_type);
+                    clauses.push(format!(
+                        "ALTER COLUMN {} TYPE {} USING {}::{}",
+                        name, column_type, name, column_type
+                    ));
+                }
+                if old.generated != new.generated {
+                    clauses.push(if new.generated {
+                        format!("ALTER COLUMN {} ADD GENERATED BY DEFAULT AS IDENTITY", name)
+                    } else {
+                        format!("ALTER COLUMN {} DROP IDENTITY IF EXISTS", name)
+                    });
+                }
+                if old.nullable != new.nullable {
+                    clauses.push(format!(
+                        "ALTER COLUMN {} {} NOT NULL",
+                        name,
+                        if new.nullable { "DROP" } else { "SET" }
+                    ));
+                }
+                if old.default != new.default {
+                    clauses.push(match &new.default {
+                        Some(default) => format!(
+                            "ALTER COLUMN {} SET DEFAULT {}",
+                            name,
+                            self.literal(default)
+                        ),
+                        None => format!("ALTER COLUMN {} DROP DEFAULT", name),
+                    });
+                }
+                clauses
+            }
+        }
+    }
+
+    /// SQLite: neue Tabelle anlegen, Daten kopieren, alte ersetzen
+    fn rebuild_table(&self, before: &Table, after: &Table) -> Vec<String> {
+        let temporary = format!("__velin_new_{}", after.name);
+        let mut statements = self.create_table_named(after, &temporary);
+
+        let mut targets = Vec::new();
+        let mut sources = Vec::new();
+        for column in &after.columns {
+            if before.column(&column.name).is_some() {
+                targets.push(self.quote(&column.name));
+                sources.push(self.quote(&column.name));
+            } else if !column.nullable && column.default.is_none() && !column.generated {
+                targets.push(self.quote(&column.name));
+                sources.push(self.zero(column.column_type).to_string());
+            }
+        }
+        statements.push(format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {}",
+            self.quote(&temporary),
+            targets.join(", "),
+            sources.join(", "),
+            self.quote(&before.name)
+        ));
+        statements.push(format!("DROP TABLE {}", self.quote(&before.name)));
+        statements.push(format!(
+            "ALTER TABLE {} RENAME TO {}",
+            self.quote(&temporary),
+            self.quote(&after.name)
+        ));
+        statements.extend(
+            after
+                .indexes
+                .iter()
+                .map(|index| self.create_index(after, index)),
+        );
+        statements
+    }
+}
+
+/// Unterschiede zwischen zwei Zuständen einer Tabelle
+struct Changes<'a> {
+    added_columns: Vec<&'a Column>,
+    dropped_columns: Vec<&'a Column>,
+    altered_columns: Vec<(&'a Column, &'a Column)>,
+    added_indexes: Vec<&'a Index>,
+    dropped_indexes: Vec<&'a Index>,
+    added_foreign_keys: Vec<&'a ForeignKey>,
+    dropped_foreign_keys: Vec<&'a ForeignKey>,
+    primary_key_changed: bool,
+}
+
+impl<'a> Changes<'a> {
+    fn between(before: &'a Table, after: &'a Table) -> Self {
+        Changes {
+            added_columns: after
+                .columns
+                .iter()
+                .filter(|column| before.column(&column.name).is_none())
+                .collect(),
+            dropped_columns: before
+                .columns
+                .iter()
+                .filter(|column| after.column(&column.name).is_none())
+                .collect(),
+            altered_columns: before
+                .columns
+                .iter()
+                .filter_map(|old| {
+                    let new = after.column(&old.name)?;
+                    (old != new).then_some((old, new))
+                })
+                .collect(),
+            // Geänderte Indizes/Foreign Keys werden gelöscht und neu angelegt
+            added_indexes: after
+                .indexes
+                .iter()
+                .filter(|index| !before.indexes.contains(index))
+                .collect(),
+            dropped_indexes: before
+                .indexes
+                .iter()
+                .filter(|index| !after.indexes.contains(index))
+                .collect(),
+            added_foreign_keys: after
+                .foreign_keys
+                .iter()
+                .filter(|fk| !before.foreign_keys.contains(fk))
+                .collect(),
+            dropped_foreign_keys: before
+                .foreign_keys
+                .iter()
+                .filter(|fk| !after.foreign_keys.contains(fk))
+                .collect(),
+            primary_key_changed: before.primary_key != after.primary_key,
+        }
+    }
+
+    /// Änderungen, die SQLite nicht per `ALTER TABLE` kann
+    fn needs_rebuild(&self) -> bool {
+        !self.altered_columns.is_empty()
+            || self.primary_key_changed
+            || !self.added_foreign_keys.is_empty()
+            || !self.dropped_foreign_keys.is_empty()
+            || self
+                .added_columns
+                .iter()
+                .any(|column| column.generated || (!column.nullable && column.default.is_none()))
+    }
+}
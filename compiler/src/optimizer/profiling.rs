@@ -16,6 +16,12 @@ pub struct ProfilingCollector {
     config: ProfilingConfig,
 }
 
+/// Laufzeitdaten des Programms; daraus liest der Optimizer die Hot Paths
+pub const PROFILING_PATH: &str = ".velin/profiling.json";
+
+/// Phasenzeiten des Compilers selbst, getrennt von den Programmdaten
+pub const COMPILER_TIMINGS_PATH: &str = ".velin/compiler-timings.json";
+
 #[derive(Debug, Clone)]
 pub struct ProfilingConfig {
     pub hot_path_threshold_calls: u64,
@@ -32,7 +38,7 @@ impl Default for ProfilingConfig {
             hot_path_threshold_time_ms: 100.0,
             bottleneck_threshold_ms: 500.0,
             bottleneck_max_time_ms: 1000.0,
-            persist_path: Some(".velin/profiling.json".to_string()),
+            persist_path: Some(PROFILING_PATH.to_string()),
         }
     }
 }
@@ -44,17 +50,21 @@ pub struct ProfilingData {
     pub memory_usage: u64,
     pub cpu_usage: f64,
     pub function_times: HashMap<String, f64>,
+    /// Vollständige Aufrufstatistik, damit `load_from_file` die Daten rekonstruieren kann
+    #[serde(default)]
+    pub function_calls: HashMap<String, FunctionCallStats>,
 }
 
 struct RuntimeProfiler {
     function_calls: HashMap<String, FunctionCallStats>,
 }
 
-struct FunctionCallStats {
-    count: u64,
-    total_time: f64,
-    min_time: f64,
-    max_time: f64,
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FunctionCallStats {
+    pub count: u64,
+    pub total_time: f64,
+    pub min_time: f64,
+    pub max_time: f64,
 }
 
 struct MetricsCollector {
@@ -92,15 +102,62 @@ impl ProfilingCollector {
 
     /// Lädt Profiling-Daten aus Datei
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let _content = fs::read_to_string(path)?;
-        let _data: ProfilingData = serde_json::from_str(&_content)?;
-
-        let collector = Self::new();
-        // Rekonstruiere Daten aus ProfilingData
-        // (vereinfacht - in Produktion würde man alle Daten laden)
+        let content = fs::read_to_string(path)?;
+        let data: ProfilingData = serde_json::from_str(&content)?;
+
+        let mut collector = Self::new();
+        collector.runtime_profiler.function_calls = data.function_calls;
+        // Ältere Dateien enthalten nur Durchschnittszeiten
+        for (func_name, avg_time) in data.function_times {
+            collector
+                .runtime_profiler
+                .function_calls
+                .entry(func_name)
+                .or_insert(FunctionCallStats {
+                    count: 1,
+                    total_time: avg_time,
+                    min_time: avg_time,
+                    max_time: avg_time,
+                });
+        }
+        if data.memory_usage > 0 {
+            collector.record_memory_sample(data.memory_usage);
+        }
+        if data.cpu_usage > 0.0 {
+            collector.record_cpu_sample(data.cpu_usage);
+        }
         Ok(collector)
     }
 
+    /// Lädt die persistierten Daten (`persist_path`), sonst einen leeren Collector
+    pub fn load_persisted() -> Self {
+        let config = ProfilingConfig::default();
+        let mut collector = config
+            .persist_path
+            .as_deref()
+            .filter(|path| Path::new(path).exists())
+            .and_then(|path| Self::load_from_file(path).ok())
+            .unwrap_or_else(Self::new);
+        collector.config = config;
+        collector
+    }
+
+    /// Collector für die Phasenzeiten eines Compiler-Laufs (`COMPILER_TIMINGS_PATH`);
+    /// jeder Lauf überschreibt die Datei
+    pub fn compiler_timings() -> Self {
+        Self::with_config(ProfilingConfig {
+            persist_path: Some(COMPILER_TIMINGS_PATH.to_string()),
+            ..ProfilingConfig::default()
+        })
+    }
+
+    /// Verwirft alle Messwerte, die Konfiguration bleibt
+    pub fn reset(&mut self) {
+        self.runtime_profiler.function_calls.clear();
+        self.metrics.memory_samples.clear();
+        self.metrics.cpu_samples.clear();
+    }
+
     /// Speichert Profiling-Daten in Datei
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let data = self.collect(&CompiledProgram {
@@ -148,6 +205,7 @@ impl ProfilingCollector {
             memory_usage,
             cpu_usage,
             function_times,
+            function_calls: self.runtime_profiler.function_calls.clone(),
         })
     }
 
@@ -273,6 +331,25 @@ impl ProfilingCollector {
         stats.max_time = stats.max_time.max(duration);
     }
 
+    /// Setzt die Statistik einer Funktion auf die aggregierten Werte eines
+    /// instrumentierten Laufs; ältere Werte der Funktion werden ersetzt
+    pub fn record_function_stats(&mut self, func_name: String, calls: u64, total_time: f64) {
+        if calls == 0 {
+            self.runtime_profiler.function_calls.remove(&func_name);
+            return;
+        }
+        let avg_time = total_time / calls as f64;
+        self.runtime_profiler.function_calls.insert(
+            func_name,
+            FunctionCallStats {
+                count: calls,
+                total_time,
+                min_time: avg_time,
+                max_time: avg_time,
+            },
+        );
+    }
+
     /// Registriert Memory Sample
     pub fn record_memory_sample(&mut self, memory_bytes: u64) {
         self.metrics.memory_samples.push(MemorySample {
@@ -46,6 +46,7 @@ pub enum DecoratorArg {
         name: String,
         value: Box<DecoratorArg>,
     },
+    List(Vec<DecoratorArg>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -322,6 +323,8 @@ pub struct Module {
     pub items: Vec<Item>,
     pub visibility: Visibility,
     pub documentation: Option<String>,
+    /// Package-Name, wenn das Modul aus einer Dependency stammt
+    pub package: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -408,6 +411,9 @@ pub enum Type {
         ok: Box<Type>,
         err: Box<Type>,
     },
+
+    // Lazy async stream: Stream<T>
+    Stream(Box<Type>),
 }
 
 impl Type {
@@ -455,6 +461,7 @@ impl Type {
             Type::Result { ok, err } => {
                 format!("Result<{}, {}>", ok.to_string(), err.to_string())
             }
+            Type::Stream(item) => format!("Stream<{}>", item.to_string()),
         }
     }
 }
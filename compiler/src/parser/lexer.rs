@@ -104,6 +104,10 @@ pub struct Lexer<'a> {
     pub byte_position: usize,
     pub line: usize,
     pub column: usize,
+    /// Quellzeile, in der das zuletzt gelesene Token beginnt
+    pub token_line: usize,
+    /// Quellzeilen aller Tokens (parallel zur Token-Liste aus `tokenize`)
+    pub token_lines: Vec<usize>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -128,6 +132,8 @@ impl<'a> Lexer<'a> {
             byte_position: 0,
             line: 1,
             column: 0,
+            token_line: 1,
+            token_lines: Vec::new(),
         };
         lexer.advance();
         // Skip BOM if present at the start
@@ -478,6 +484,13 @@ impl<'a> Lexer<'a> {
     pub fn next_token(&mut self) -> Result<Token, LexerError> {
         self.skip_whitespace();
 
+        // `line` wird bereits erhöht, sobald '\n' das aktuelle Zeichen ist
+        self.token_line = if self.current == Some('\n') {
+            self.line.saturating_sub(1).max(1)
+        } else {
+            self.line
+        };
+
         #[cfg(debug_assertions)]
         {
             if let Some(ch) = self.current {
@@ -706,9 +719,11 @@ impl<'a> Lexer<'a> {
 
     pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
         let mut tokens = Vec::new();
+        self.token_lines.clear();
 
         loop {
             let token = self.next_token()?;
+            self.token_lines.push(self.token_line);
             if token == Token::EOF {
                 tokens.push(token);
                 break;
@@ -57,6 +57,19 @@ pub struct Parser {
     source: String,
     line_starts: Vec<usize>,
     context: Vec<ParseContext>, // Stack von Kontexten für verschachtelte Strukturen
+    token_lines: Vec<usize>,    // Quellzeile pro Token (vom Lexer)
+    source_lines: SourceLines,
+}
+
+/// Zeileninformationen, die beim Parsen gesammelt werden.
+///
+/// Der AST selbst trägt keine Positionen; beide Listen sind in Quellreihenfolge
+/// (Statements in Pre-Order) und werden über einen AST-Walk in derselben
+/// Reihenfolge zugeordnet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceLines {
+    pub statements: Vec<usize>,
+    pub functions: Vec<usize>,
 }
 
 impl Parser {
@@ -73,9 +86,37 @@ impl Parser {
             source,
             line_starts,
             context: vec![ParseContext::TopLevel],
+            token_lines: Vec::new(),
+            source_lines: SourceLines::default(),
         }
     }
 
+    /// Übergibt die Quellzeilen der Tokens (siehe `Lexer::token_lines`)
+    pub fn with_token_lines(mut self, token_lines: Vec<usize>) -> Self {
+        self.token_lines = token_lines;
+        self
+    }
+
+    /// Quellzeile des aktuellen Tokens (0, wenn keine Zeileninformation vorliegt)
+    fn current_token_line(&self) -> usize {
+        self.token_lines.get(self.current).copied().unwrap_or(0)
+    }
+
+    /// Merkt sich die Startzeile eines Statements.
+    ///
+    /// Wird vor dem Parsen der Kinder aufgerufen, die Liste ist daher in Pre-Order
+    /// und kann über einen AST-Walk in Quellreihenfolge zugeordnet werden.
+    fn mark_statement(&mut self) {
+        let line = self.current_token_line();
+        self.source_lines.statements.push(line);
+    }
+
+    /// Merkt sich die Zeile einer Funktionsdefinition
+    fn mark_function(&mut self) {
+        let line = self.current_token_line();
+        self.source_lines.functions.push(line);
+    }
+
     /// Setzt den aktuellen Kontext (für verschachtelte Strukturen)
     fn push_context(&mut self, ctx: ParseContext) {
         self.context.push(ctx);
@@ -112,6 +153,26 @@ impl Parser {
         parser.parse_program()
     }
 
+    /// Parst wie `parse`, liefert zusätzlich die Zeilen aller Statements und
+    /// Funktionen (Grundlage für Coverage-Instrumentierung und Debugging).
+    pub fn parse_with_lines(input: &str) -> Result<(Program, SourceLines), ParseError> {
+        let mut lexer = Lexer::new(input);
+        let tokens = lexer.tokenize().map_err(|e| ParseError {
+            message: e.message,
+            expected: "valid token".to_string(),
+            found: "invalid token".to_string(),
+            position: 0,
+            line: e.line,
+            column: e.column,
+            source_context: None,
+        })?;
+        let token_lines = std::mem::take(&mut lexer.token_lines);
+
+        let mut parser = Parser::new(tokens, input.to_string()).with_token_lines(token_lines);
+        let program = parser.parse_program()?;
+        Ok((program, parser.source_lines))
+    }
+
     fn parse_program(&mut self) -> Result<Program, ParseError> {
         let mut items = Vec::new();
 
@@ -171,6 +232,10 @@ impl Parser {
     }
 
     fn parse_top_level_let(&mut self) -> Result<Item, ParseError> {
+        // Die synthetische Init-Funktion und ihr `let` zählen mit
+        self.mark_function();
+        self.mark_statement();
+
         // Consume 'let' token
         self.advance();
 
@@ -565,6 +630,39 @@ impl Parser {
                 self.advance();
                 Ok(DecoratorArg::Number(n_clone))
             }
+            Some(Token::Minus) => {
+                self.advance();
+                match self.peek() {
+                    Some(Token::Number(n)) => {
+                        let n_clone = *n;
+                        self.advance();
+                        Ok(DecoratorArg::Number(-n_clone))
+                    }
+                    _ => Err(self.error("Expected number after '-'")),
+                }
+            }
+            Some(Token::LBracket) => {
+                self.advance();
+                let mut items = Vec::new();
+                loop {
+                    while matches!(self.peek(), Some(Token::Newline)) {
+                        self.advance();
+                    }
+                    if self.check(&Token::RBracket) {
+                        break;
+                    }
+                    items.push(self.parse_decorator_arg()?);
+                    while matches!(self.peek(), Some(Token::Newline)) {
+                        self.advance();
+                    }
+                    if !self.check(&Token::Comma) {
+                        break;
+                    }
+                    self.advance();
+                }
+                self.consume(&Token::RBracket, "Expected ']'")?;
+                Ok(DecoratorArg::List(items))
+            }
             Some(Token::Boolean(b)) => {
                 let b_clone = *b;
                 self.advance();
@@ -591,6 +689,7 @@ impl Parser {
         while matches!(self.peek(), Some(Token::Newline)) {
             self.advance();
         }
+        self.mark_function();
 
         #[cfg(debug_assertions)]
         {
@@ -1061,6 +1160,7 @@ impl Parser {
         while matches!(self.peek(), Some(Token::Newline)) {
             self.advance();
         }
+        self.mark_statement();
 
         // FIX: Prüfe, ob das nächste Token ein Top-Level-Keyword ist
         // Diese sollten nicht innerhalb eines Blocks geparst werden
@@ -1627,6 +1727,7 @@ impl Parser {
             self.parse_block()?
         } else {
             // Single expression body - wrap in block
+            self.mark_statement();
             let expr = self.parse_expression()?;
             Block {
                 statements: vec![Statement::Expression(ExpressionStatement {
@@ -3284,6 +3385,9 @@ impl Parser {
 
                     self.consume(&Token::Gt, "Expected '>'")?;
 
+                    if name == "Stream" && params.len() == 1 {
+                        return Ok(Type::Stream(Box::new(params.remove(0))));
+                    }
                     Ok(Type::Generic {
                         name: name.clone(),
                         params,
@@ -3831,6 +3935,11 @@ impl Parser {
         self.consume(&Token::Eq, "Expected '='")?;
         let aliased_type = self.parse_type()?;
 
+        // Optionales Semikolon (`type UserId = string;`)
+        if self.check(&Token::Semicolon) {
+            self.advance();
+        }
+
         Ok(TypeAlias {
             name,
             aliased_type,
@@ -3880,6 +3989,7 @@ impl Parser {
             items,
             visibility,
             documentation: None,
+            package: None,
         })
     }
 
@@ -0,0 +1,183 @@
+// AST-Walker - Besucht alle Ausdrücke, z.B. um Aufrufe von Stdlib-Modulen zu finden
+
+use crate::parser::ast::*;
+
+/// Ruft `f` für jeden Ausdruck eines Blocks auf, auch in verschachtelten Blöcken
+pub fn visit_block(block: &Block, f: &mut dyn FnMut(&Expression)) {
+    for statement in &block.statements {
+        match statement {
+            Statement::Let(s) => visit_expression(&s.value, f),
+            Statement::Return(s) => {
+                if let Some(value) = &s.value {
+                    visit_expression(value, f);
+                }
+            }
+            Statement::Expression(s) => visit_expression(&s.expression, f),
+            Statement::If(s) => {
+                visit_expression(&s.condition, f);
+                visit_block(&s.then_block, f);
+                if let Some(else_block) = &s.else_block {
+                    visit_block(else_block, f);
+                }
+            }
+            Statement::For(s) => {
+                visit_expression(&s.iterable, f);
+                visit_block(&s.body, f);
+            }
+            Statement::While(s) => {
+                visit_expression(&s.condition, f);
+                visit_block(&s.body, f);
+            }
+            Statement::Match(s) => {
+                visit_expression(&s.expression, f);
+                for arm in &s.arms {
+                    if let Some(guard) = &arm.guard {
+                        visit_expression(guard, f);
+                    }
+                    visit_block(&arm.body, f);
+                }
+            }
+            Statement::Throw(s) => visit_expression(&s.expression, f),
+            Statement::Try(s) => {
+                visit_block(&s.try_block, f);
+                for catch in &s.catch_blocks {
+                    visit_block(&catch.body, f);
+                }
+                if let Some(finally) = &s.finally_block {
+                    visit_block(finally, f);
+                }
+            }
+            Statement::Break(_) => {}
+        }
+    }
+}
+
+/// Ruft `f` für `expr` und alle Teilausdrücke auf (Pre-Order)
+pub fn visit_expression(expr: &Expression, f: &mut dyn FnMut(&Expression)) {
+    f(expr);
+    match expr {
+        Expression::BinaryOp { left, right, .. } => {
+            visit_expression(left, f);
+            visit_expression(right, f);
+        }
+        Expression::UnaryOp { expr, .. } | Expression::Await { expr } => visit_expression(expr, f),
+        Expression::Call { callee, args } => {
+            visit_expression(callee, f);
+            for arg in args {
+                visit_expression(arg, f);
+            }
+        }
+        Expression::Member { object, .. } => visit_expression(object, f),
+        Expression::Index { object, index } => {
+            visit_expression(object, f);
+            visit_expression(index, f);
+        }
+        Expression::If {
+            condition,
+            then_expr,
+            else_expr,
+        } => {
+            visit_expression(condition, f);
+            visit_expression(then_expr, f);
+            visit_expression(else_expr, f);
+        }
+        Expression::Block(block) => visit_block(block, f),
+        Expression::StructLiteral { fields, .. } | Expression::MapLiteral(fields) => {
+            for (_, value) in fields {
+                visit_expression(value, f);
+            }
+        }
+        Expression::ListLiteral(items)
+        | Expression::GenericConstructor { args: items, .. }
+        | Expression::LLMCall { args: items, .. } => {
+            for item in items {
+                visit_expression(item, f);
+            }
+        }
+        Expression::Lambda { body, .. } => visit_expression(body, f),
+        Expression::Assignment { target, value } => {
+            visit_expression(target, f);
+            visit_expression(value, f);
+        }
+        Expression::FormatString { parts } => {
+            for part in parts {
+                if let FormatStringPart::Expression(inner) = part {
+                    visit_expression(inner, f);
+                }
+            }
+        }
+        Expression::Literal(_) | Expression::Identifier(_) => {}
+    }
+}
+
+/// Besucht alle Ausdrücke der Items, auch in `impl`-Methoden und Modulen
+pub fn visit_items(items: &[Item], f: &mut dyn FnMut(&Expression)) {
+    for item in items {
+        match item {
+            Item::Function(function) => visit_block(&function.body, f),
+            Item::Impl(implementation) => {
+                for method in &implementation.methods {
+                    visit_block(&method.body, f);
+                }
+            }
+            Item::Module(module) => visit_items(&module.items, f),
+            Item::TopLevelCode(code) => visit_expression(&code.expression, f),
+            _ => {}
+        }
+    }
+}
+
+/// `true`, wenn das Programm irgendwo `<module>.<funktion>(...)` aufruft
+pub fn calls_module(program: &Program, module: &str) -> bool {
+    let mut found = false;
+    visit_items(&program.items, &mut |expr| {
+        if let Expression::Call { callee, .. } = expr {
+            if let Expression::Member { object, .. } = callee.as_ref() {
+                if matches!(object.as_ref(), Expression::Identifier(name) if name == module) {
+                    found = true;
+                }
+            }
+        }
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_calls_module_looks_into_modules_and_impl_methods() {
+        let program = Parser::parse(
+            "mod reports {\n    fn export() {\n        let span = tracing.start_span(\"export\");\n    }\n}\n",
+        )
+        .unwrap();
+        assert!(calls_module(&program, "tracing"));
+        assert!(!calls_module(&program, "audit"));
+
+        // `impl X for Y` parst nicht, der Block wird daher direkt gebaut
+        let method = Parser::parse("fn save() {\n    audit.log({ action: \"order.saved\" });\n}\n")
+            .unwrap()
+            .items
+            .remove(0);
+        let Item::Function(method) = method else {
+            unreachable!()
+        };
+        let program = Program {
+            items: vec![Item::Impl(Impl {
+                trait_name: "Repository".to_string(),
+                for_type: Type::Named("Order".to_string()),
+                type_params: Vec::new(),
+                methods: vec![method],
+            })],
+        };
+        assert!(calls_module(&program, "audit"));
+        assert!(crate::stdlib::audit::uses_audit(&program));
+
+        // Ein lokaler Wert namens `audit` ohne Aufruf zählt nicht
+        let program =
+            Parser::parse("fn main() {\n    let audit = 1;\n    let x = audit;\n}\n").unwrap();
+        assert!(!calls_module(&program, "audit"));
+    }
+}
@@ -2,6 +2,7 @@ use crate::compiler::config::CompilerConfig;
 use crate::compiler::context::CompilationContext;
 use crate::compiler::pass::Pass;
 use crate::optimizer::pipeline::PipelineOptimizer;
+use crate::optimizer::profiling::{CompiledProgram, ProfilingCollector};
 use crate::parser::ast::*;
 use crate::prompt::sanitizer::PromptSanitizer;
 use crate::stdlib::ml::{LLMClient, LLMProvider};
@@ -74,7 +75,26 @@ impl AIOptimizationPass {
             pipeline_optimizer: PipelineOptimizer::new(),
             prompt_sanitizer: PromptSanitizer::new(),
             enabled: config.enable_ai_optimization,
-            profiling_data: None,
+            profiling_data: Self::load_profiling_data(),
+        })
+    }
+
+    /// Lädt persistierte Laufzeitdaten (z.B. aus `velin-profile cpu`)
+    fn load_profiling_data() -> Option<ProfilingData> {
+        let collector = ProfilingCollector::load_persisted();
+        let data = collector
+            .collect(&CompiledProgram {
+                name: "current".to_string(),
+            })
+            .ok()?;
+        if data.function_calls.is_empty() {
+            return None;
+        }
+        Some(ProfilingData {
+            hot_paths: data.hot_paths,
+            bottlenecks: data.bottlenecks,
+            memory_usage: data.memory_usage,
+            cpu_usage: data.cpu_usage,
         })
     }
 
@@ -40,17 +40,13 @@ impl CodeOrderingPass {
             }
         }
 
-        // Add any items that weren't in the graph (shouldn't happen, but safety check)
-        let graph_item_names: HashSet<String> = item_map_mut
-            .values()
-            .filter_map(|item| self.get_item_name(item))
-            .collect();
-
+        // Items ohne eigenen Knoten anhängen: `use`, Top-Level-Code und
+        // gleichnamige Items (der Graph enthält nur das erste Vorkommen)
+        let mut seen_names = HashSet::new();
         for item in &program.items {
-            if let Some(name) = self.get_item_name(item) {
-                if !graph_item_names.contains(&name) {
-                    ordered_items.push(item.clone());
-                }
+            match self.get_item_name(item) {
+                Some(name) if seen_names.insert(name.clone()) => {}
+                _ => ordered_items.push(item.clone()),
             }
         }
 
@@ -474,3 +470,72 @@ impl Pass for CodeOrderingPass {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    fn kinds(items: &[Item]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                Item::Use(use_stmt) => format!("use {}", use_stmt.path.join(".")),
+                Item::Impl(impl_def) => match &impl_def.for_type {
+                    Type::Named(name) => format!("impl {} for {}", impl_def.trait_name, name),
+                    other => format!("impl {} for {:?}", impl_def.trait_name, other),
+                },
+                Item::TopLevelCode(_) => "top-level".to_string(),
+                other => CodeOrderingPass::new()
+                    .get_item_name(other)
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_keeps_items_without_own_graph_node() {
+        let source = r#"use models;
+
+fn label(order: Order): string {
+    return order.id;
+}
+
+struct Order {
+    id: string,
+}
+
+struct Invoice {
+    id: string,
+}
+
+print("ready");
+"#;
+        let mut program = Parser::parse(source).unwrap();
+        for type_name in ["Invoice", "Order"] {
+            program.items.push(Item::Impl(Impl {
+                trait_name: "Describe".to_string(),
+                for_type: Type::Named(type_name.to_string()),
+                type_params: Vec::new(),
+                methods: Vec::new(),
+            }));
+        }
+        let before = program.items.len();
+        CodeOrderingPass::new().order_program(&mut program).unwrap();
+
+        // `use`, Top-Level-Code und die zweite `impl Describe` haben keinen eigenen
+        // Knoten im Graphen und dürfen trotzdem nicht verloren gehen
+        let kinds = kinds(&program.items);
+        assert_eq!(program.items.len(), before, "{:?}", kinds);
+        assert!(kinds.contains(&"use models".to_string()));
+        assert!(kinds.contains(&"top-level".to_string()));
+        assert!(kinds.contains(&"impl Describe for Invoice".to_string()));
+        assert!(kinds.contains(&"impl Describe for Order".to_string()));
+        // Items mit eigenem Knoten erscheinen genau einmal
+        assert_eq!(kinds.iter().filter(|kind| *kind == "Order").count(), 1);
+
+        // Abhängigkeiten stehen weiterhin vor ihren Verwendern
+        let position = |name: &str| kinds.iter().position(|k| k == name).unwrap();
+        assert!(position("Order") < position("label"));
+    }
+}
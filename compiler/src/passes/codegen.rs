@@ -1,13 +1,14 @@
 use crate::codegen::{
     CSharpCodeGenerator, CodeGenerator, CodegenConfig, GoCodeGenerator, IRCodeGenerator,
     JavaCodeGenerator, JavaScriptCodeGenerator, PhpCodeGenerator, PythonCodeGenerator,
-    RustCodeGenerator, TargetLanguage, TypeScriptCodeGenerator,
+    RustCodeGenerator, TargetLanguage, TypeScriptCodeGenerator, WasmCodeGenerator,
 };
 use crate::compiler::context::CompilationContext;
 use crate::compiler::language::VELISCH_LANGUAGE_NAME;
 use crate::compiler::pass::Pass;
 use crate::ir::{IRBuilder, IROptimizer, IRValidator};
 use crate::optimizer::profiling::ProfilingCollector;
+use crate::parser::ast::Program;
 use anyhow::Result;
 use std::fs;
 use std::time::Instant;
@@ -56,11 +57,17 @@ impl Pass for CodegenPass {
         }
 
         if let Some(program) = &context.program {
-            // Profiling: Track Codegen-Performance
-            let mut profiler = ProfilingCollector::new();
+            // Profiling: Phasenzeiten des Compilers, nicht die Laufzeitdaten des Programms
+            let mut profiler = ProfilingCollector::compiler_timings();
             let start = Instant::now();
 
-            let generated_code = if self.use_ir {
+            // Wasm hat keinen IR-Backend, es baut auf dem AST-Codegen für Rust auf.
+            // `@Validate`-Felder brauchen die Framework-Handler des AST-Codegens.
+            let use_ir = self.use_ir
+                && self.target != TargetLanguage::Wasm
+                && crate::validation::validated_structs(&program.items).is_empty()
+                && (self.target != TargetLanguage::Rust || !requires_ast_codegen(program));
+            let generated_code = if use_ir {
                 // IR-basierte Code-Generierung
                 // 1. AST → IR
                 let ir_start = Instant::now();
@@ -122,7 +129,9 @@ impl Pass for CodegenPass {
                 // Direkte AST → Code Generierung (Legacy)
                 let gen_start = Instant::now();
                 let mut generator: Box<dyn CodeGenerator> = match self.target {
-                    TargetLanguage::Rust => Box::new(RustCodeGenerator::new()),
+                    TargetLanguage::Rust => {
+                        Box::new(RustCodeGenerator::new().with_source_file(&context.root_file))
+                    }
                     TargetLanguage::Php => Box::new(PhpCodeGenerator::new()),
                     TargetLanguage::Python => Box::new(PythonCodeGenerator::new()),
                     TargetLanguage::Go => Box::new(GoCodeGenerator::new()),
@@ -130,6 +139,7 @@ impl Pass for CodegenPass {
                     TargetLanguage::JavaScript => Box::new(JavaScriptCodeGenerator::new()),
                     TargetLanguage::Java => Box::new(JavaCodeGenerator::new()),
                     TargetLanguage::CSharp => Box::new(CSharpCodeGenerator::new()),
+                    TargetLanguage::Wasm => Box::new(WasmCodeGenerator::new()),
                 };
 
                 let config = CodegenConfig {
@@ -200,3 +210,27 @@ impl Pass for CodegenPass {
         Ok(())
     }
 }
+
+/// Features, deren Rust-Code nur der AST-Codegen erzeugt
+///
+/// Sie bringen eigene Runtime-Module mit (Workflow-Engine, Scheduler, Event-Bus,
+/// Tracing, Privacy, Audit, Realtime, Templates, Streams), die der
+/// `RustCodeGenerator` aus den Aufrufen ableitet und an den Code anhängt. Der IR
+/// kennt diese Aufrufe nur als gewöhnliche Calls und würde Code ohne die Module
+/// erzeugen.
+const AST_ONLY_FEATURES: &[fn(&Program) -> bool] = &[
+    |program| crate::stdlib::workflow::WorkflowUsage::collect(program).is_some(),
+    |program| crate::stdlib::scheduler::SchedulerUsage::collect(program).is_some(),
+    |program| crate::stdlib::event_bus::EventBusUsage::collect(program).is_some(),
+    crate::stdlib::tracing::uses_tracing,
+    crate::privacy::uses_privacy_calls,
+    crate::stdlib::audit::uses_audit,
+    crate::stdlib::realtime::uses_realtime,
+    crate::stdlib::template::uses_templates,
+    crate::stdlib::stream::uses_streams,
+];
+
+/// `true`, wenn das Programm ein Feature aus [`AST_ONLY_FEATURES`] nutzt
+fn requires_ast_codegen(program: &Program) -> bool {
+    AST_ONLY_FEATURES.iter().any(|uses| uses(program))
+}
@@ -0,0 +1,47 @@
+use crate::compiler::context::CompilationContext;
+use crate::compiler::pass::Pass;
+use crate::coverage::CoverageInstrumenter;
+use crate::passes::instrument::instrument_sources;
+use anyhow::Result;
+
+/// Instrumentiert das Root-Programm und importierte Module mit Coverage-Probes.
+///
+/// Muss direkt nach dem `ParserPass` laufen: die Zeilenzuordnung setzt voraus,
+/// dass der AST noch der Quellreihenfolge entspricht (vor Desugaring und Code Ordering).
+pub struct CoverageInstrumentationPass;
+
+impl CoverageInstrumentationPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CoverageInstrumentationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for CoverageInstrumentationPass {
+    fn name(&self) -> &str {
+        "CoverageInstrumentation"
+    }
+
+    fn run(&self, context: &mut CompilationContext) -> Result<()> {
+        if context.has_errors() || context.program.is_none() {
+            return Ok(());
+        }
+
+        let mut next_id = 0;
+        let mut maps = Vec::new();
+        instrument_sources(context, |program, file, lines, _| {
+            let map =
+                CoverageInstrumenter::new(file.to_string(), lines, next_id).instrument(program);
+            next_id = next_id.max(map.next_id());
+            maps.push(map);
+        });
+
+        context.coverage_maps = maps;
+        Ok(())
+    }
+}
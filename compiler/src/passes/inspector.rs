@@ -0,0 +1,46 @@
+use crate::compiler::context::CompilationContext;
+use crate::compiler::pass::Pass;
+use crate::inspector::InspectorInstrumenter;
+use anyhow::Result;
+use std::path::Path;
+
+/// Instrumentiert einen Service für `velin-inspect`.
+///
+/// Läuft nach dem Type Checking, weil die umgeleiteten `__velin_inspect_*`-Aufrufe
+/// nur in der Laufzeit existieren, die nach dem Codegen angehängt wird.
+pub struct InspectorInstrumentationPass;
+
+impl InspectorInstrumentationPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for InspectorInstrumentationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for InspectorInstrumentationPass {
+    fn name(&self) -> &str {
+        "InspectorInstrumentation"
+    }
+
+    fn run(&self, context: &mut CompilationContext) -> Result<()> {
+        if context.has_errors() {
+            return Ok(());
+        }
+
+        let service = Path::new(&context.root_file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("velin-service")
+            .to_string();
+
+        if let Some(program) = &mut context.program {
+            InspectorInstrumenter::new(service).instrument(program);
+        }
+        Ok(())
+    }
+}
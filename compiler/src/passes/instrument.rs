@@ -0,0 +1,70 @@
+use crate::compiler::context::CompilationContext;
+use crate::parser::ast::{Item, Program};
+use crate::parser::parser::{Parser, SourceLines};
+use std::path::Path;
+
+/// Parst Root-Datei und aufgelöste Module erneut mit Zeileninformationen, lässt
+/// `instrument` die Items bearbeiten und setzt sie an ihrer Stelle im Programm ein.
+///
+/// `instrument` erhält das Programm einer Datei, ihren Pfad, die Zeilen und bei
+/// Modulen den Modulnamen. Die Root-Datei kommt zuerst, danach die Module in
+/// Programmreihenfolge. Module, deren Items nicht mehr zum AST passen, werden
+/// übersprungen.
+///
+/// Nur direkt nach dem `ParserPass` gültig: die Zuordnung setzt voraus, dass der
+/// AST noch der Quellreihenfolge entspricht (vor Desugaring und Code Ordering).
+pub(crate) fn instrument_sources<F>(context: &mut CompilationContext, mut instrument: F)
+where
+    F: FnMut(&mut Program, &str, SourceLines, Option<&str>),
+{
+    let root_source = match context.source_map.get(&context.root_file) {
+        Some(source) => source.clone(),
+        None => return,
+    };
+
+    // Der ParserPass hat die Root-Items vor den aufgelösten Modulen eingefügt
+    let (mut root_program, lines) = match Parser::parse_with_lines(&root_source) {
+        Ok(result) => result,
+        Err(e) => {
+            context.errors.push(e.into());
+            return;
+        }
+    };
+    instrument(&mut root_program, &context.root_file, lines, None);
+
+    let module_files: Vec<(String, String)> = context
+        .source_map
+        .iter()
+        .filter(|(file, _)| **file != context.root_file)
+        .map(|(file, source)| (file.clone(), source.clone()))
+        .collect();
+
+    let Some(program) = &mut context.program else {
+        return;
+    };
+    let root_count = root_program.items.len().min(program.items.len());
+    program.items.splice(
+        ..root_count,
+        root_program.items.into_iter().take(root_count),
+    );
+
+    // Aufgelöste Module (`use models;` → models.velin) folgen den Root-Items
+    for item in program.items.iter_mut().skip(root_count) {
+        let Item::Module(module) = item else {
+            continue;
+        };
+        let Some((file, source)) = module_files.iter().find(|(file, _)| {
+            Path::new(file).file_stem().and_then(|s| s.to_str()) == Some(module.name.as_str())
+        }) else {
+            continue;
+        };
+        let Ok((mut module_program, module_lines)) = Parser::parse_with_lines(source) else {
+            continue;
+        };
+        if module_program.items.len() != module.items.len() {
+            continue;
+        }
+        instrument(&mut module_program, file, module_lines, Some(&module.name));
+        module.items = module_program.items;
+    }
+}
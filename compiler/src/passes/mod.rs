@@ -7,12 +7,21 @@ pub mod ai_semantic;
 pub mod autofix;
 pub mod code_order;
 pub mod codegen;
+pub mod coverage;
 pub mod desugar;
+pub mod inspector;
+mod instrument;
 pub mod parser;
+pub mod profiling;
 pub mod type_check;
+pub mod wasm;
 
 // Re-export Passes
 pub use ai_code_review::{AICodeReviewPass, AICodeReviewer};
 pub use ai_sandbox::{AICodeSandbox, AISandboxPass};
 pub use code_order::CodeOrderingPass;
+pub use coverage::CoverageInstrumentationPass;
 pub use desugar::DesugaringPass;
+pub use inspector::InspectorInstrumentationPass;
+pub use profiling::ProfileInstrumentationPass;
+pub use wasm::WasmTargetPass;
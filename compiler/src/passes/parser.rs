@@ -1,30 +1,65 @@
 use crate::compiler::context::CompilationContext;
+use crate::compiler::packages::{Lookup, Package, PackageIndex};
 use crate::compiler::pass::Pass;
-use crate::parser::ast::{Item, Program};
+use crate::parser::ast::{Item, Module, Program, Visibility};
 use crate::parser::parser::Parser;
 use anyhow::Result;
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct ParserPass;
 
+/// Aus welchem Kontext eine Datei importiert: Projekt oder Package
+struct Scope<'a> {
+    /// Dependencies aus der zugehörigen velin.toml
+    packages: Option<&'a PackageIndex>,
+    /// Package-Name, `None` für Projektdateien
+    package: Option<&'a str>,
+}
+
+/// Zustand über alle Dateien eines Compile-Laufs
+#[derive(Default)]
+struct Imports {
+    visited_modules: HashSet<String>,
+    /// Geladene Packages (Wurzelverzeichnis, Modul); Reihenfolge = Ladereihenfolge
+    packages: Vec<(PathBuf, Module)>,
+}
+
 impl ParserPass {
     pub fn new() -> Self {
         Self
     }
 
+    fn error(context: &mut CompilationContext, message: String) {
+        context
+            .errors
+            .push(crate::error::CompilerError::parse_error(
+                message,
+                crate::error::ErrorLocation::new(0, 0),
+            ));
+    }
+
+    /// Lädt alle per `use` referenzierten Module
+    ///
+    /// Lokale Dateien (`use models;` → `models.velin`) landen in `local_modules`,
+    /// Packages aus velin.toml in `imports.packages`. Passt ein Name auf beides,
+    /// ist der Import mehrdeutig.
     fn resolve_imports(
         &self,
         program: &Program,
         base_path: &Path,
+        scope: &Scope,
         context: &mut CompilationContext,
-        visited_modules: &mut HashSet<String>,
-        global_modules: &mut Vec<Item>,
+        imports: &mut Imports,
+        local_modules: &mut Vec<Item>,
     ) -> Result<()> {
         let mut modules_to_load = Vec::new();
+        let mut packages_to_load = Vec::new();
+        // Fehler nur einmal pro Name melden (`use pkg;` + `use pkg.Item;`)
+        let mut reported = HashSet::new();
 
-        // 1. Collect all `use` statements that refer to local modules
+        // 1. Collect all `use` statements and decide between local modules and packages
         for item in &program.items {
             if let Item::Use(use_stmt) = item {
                 if let Some(first_segment) = use_stmt.path.first() {
@@ -33,10 +68,7 @@ impl ParserPass {
                         || first_segment.contains("\\")
                         || first_segment.starts_with("/")
                     {
-                        context.errors.push(crate::error::CompilerError::parse_error(
-                            format!("Invalid module path: '{}'. Path traversal (../) and absolute paths are not allowed.", first_segment),
-                            crate::error::ErrorLocation::new(0, 0),
-                        ));
+                        Self::error(context, format!("Invalid module path: '{}'. Path traversal (../) and absolute paths are not allowed.", first_segment));
                         continue;
                     }
 
@@ -45,102 +77,257 @@ impl ParserPass {
                         .chars()
                         .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
                     {
-                        context.errors.push(crate::error::CompilerError::parse_error(
-                            format!("Invalid module name: '{}'. Only alphanumeric characters, underscore, and hyphen are allowed.", first_segment),
-                            crate::error::ErrorLocation::new(0, 0),
-                        ));
+                        Self::error(context, format!("Invalid module name: '{}'. Only alphanumeric characters, underscore, and hyphen are allowed.", first_segment));
                         continue;
                     }
 
                     let module_path = base_path.join(format!("{}.velin", first_segment));
-                    if module_path.exists() {
-                        modules_to_load.push((first_segment.clone(), module_path));
-                    } else {
-                        // SECURITY: Fehler statt Warnung bei fehlenden Modulen
-                        context
-                            .errors
-                            .push(crate::error::CompilerError::parse_error(
+                    let lookup = scope
+                        .packages
+                        .map(|index| index.lookup(first_segment))
+                        .unwrap_or(Lookup::None);
+                    let first = reported.insert(first_segment.clone());
+
+                    match (module_path.exists(), lookup) {
+                        (true, Lookup::None) => {
+                            modules_to_load.push((first_segment.clone(), module_path));
+                        }
+                        (false, Lookup::One(package)) => {
+                            packages_to_load.push((package.clone(), use_stmt.path.get(1).cloned()));
+                        }
+                        (true, Lookup::One(package)) if first => Self::error(
+                            context,
+                            format!(
+                                "Import '{}' is ambiguous: it matches the local module {} and the package '{}'",
+                                first_segment,
+                                module_path.display(),
+                                package.name
+                            ),
+                        ),
+                        (_, Lookup::Ambiguous(packages)) if first => {
+                            let names: Vec<_> =
+                                packages.iter().map(|p| format!("'{}'", p.name)).collect();
+                            Self::error(
+                                context,
                                 format!(
-                                    "Module '{}' not found. Expected file: {}",
+                                    "Import '{}' is ambiguous: it matches the packages {}",
                                     first_segment,
-                                    module_path.display()
+                                    names.join(", ")
                                 ),
-                                crate::error::ErrorLocation::new(0, 0),
-                            ));
+                            );
+                        }
+                        // SECURITY: Fehler statt Warnung bei fehlenden Modulen
+                        (false, Lookup::None) if first => Self::error(
+                            context,
+                            format!(
+                                "Module '{}' not found. Expected file: {} or a dependency in velin.toml",
+                                first_segment,
+                                module_path.display()
+                            ),
+                        ),
+                        _ => {}
                     }
                 }
             }
         }
 
-        // 2. Load and parse these modules
+        // 2. Load and parse local modules
         for (mod_name, mod_path) in modules_to_load {
-            let mod_path_str = mod_path.to_string_lossy().to_string();
+            if let Some(module) =
+                self.load_module(&mod_name, &mod_path, scope, context, imports, local_modules)?
+            {
+                local_modules.push(Item::Module(module));
+            }
+        }
 
-            // Check if already visited to prevent infinite recursion and diamonds
-            if visited_modules.contains(&mod_path_str) {
-                continue;
+        // 3. Load packages (once per package, submodules on demand)
+        if let Some(index) = scope.packages {
+            for (package, submodule) in packages_to_load {
+                self.load_package(&package, submodule.as_deref(), index, context, imports)?;
             }
-            visited_modules.insert(mod_path_str.clone());
-
-            // Get source: from context or read file
-            let source = if let Some(src) = context.source_map.get(&mod_path_str) {
-                src.clone()
-            } else {
-                match fs::read_to_string(&mod_path) {
-                    Ok(s) => {
-                        context.add_source(mod_path_str.clone(), s.clone());
-                        s
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to read module {}: {}", mod_path.display(), e);
-                        continue;
-                    }
+        }
+
+        Ok(())
+    }
+
+    /// Parst eine Datei samt ihrer Imports und verpackt sie als Modul
+    ///
+    /// `None`, wenn die Datei bereits geladen wurde oder nicht geparst werden konnte.
+    fn load_module(
+        &self,
+        mod_name: &str,
+        mod_path: &Path,
+        scope: &Scope,
+        context: &mut CompilationContext,
+        imports: &mut Imports,
+        local_modules: &mut Vec<Item>,
+    ) -> Result<Option<Module>> {
+        let mod_path_str = mod_path.to_string_lossy().to_string();
+
+        // Check if already visited to prevent infinite recursion and diamonds
+        if !imports.visited_modules.insert(mod_path_str.clone()) {
+            return Ok(None);
+        }
+
+        // Get source: from context or read file
+        let source = if let Some(src) = context.source_map.get(&mod_path_str) {
+            src.clone()
+        } else {
+            match fs::read_to_string(mod_path) {
+                Ok(s) => {
+                    context.add_source(mod_path_str.clone(), s.clone());
+                    s
                 }
-            };
+                Err(e) => {
+                    eprintln!("Failed to read module {}: {}", mod_path.display(), e);
+                    return Ok(None);
+                }
+            }
+        };
+
+        match Parser::parse(&source) {
+            Ok(mod_program) => {
+                let mod_dir = mod_path.parent().unwrap();
+
+                // Recurse to find more modules
+                self.resolve_imports(
+                    &mod_program,
+                    mod_dir,
+                    scope,
+                    context,
+                    imports,
+                    local_modules,
+                )?;
 
-            match Parser::parse(&source) {
-                Ok(mod_program) => {
-                    let mod_dir = mod_path.parent().unwrap();
+                // Wrap imported items in a Module item
+                // This enables namespacing (e.g., models.Item)
+                Ok(Some(Module {
+                    name: mod_name.to_string(),
+                    items: mod_program.items,
+                    visibility: Visibility::Public,
+                    documentation: None,
+                    package: scope.package.map(str::to_string),
+                }))
+            }
+            Err(e) => {
+                // SECURITY: Fehler statt nur Logging
+                context
+                    .errors
+                    .push(crate::error::CompilerError::parse_error(
+                        format!(
+                            "Failed to parse module {}: {} (at line {}, column {})",
+                            mod_name, e.message, e.line, e.column
+                        ),
+                        crate::error::ErrorLocation::new(e.line, e.column),
+                    ));
+                eprintln!("Failed to parse module {}: {}", mod_name, e.message);
+                eprintln!("  at line {}, column {}", e.line, e.column);
+                eprintln!("  found: {}", e.found);
+                if let Some(ctx) = e.source_context {
+                    eprintln!("  Context:\n{}", ctx);
+                }
+                Ok(None)
+            }
+        }
+    }
 
-                    // Recurse to find more modules
-                    self.resolve_imports(
-                        &mod_program,
-                        mod_dir,
+    /// Lädt ein Package als Modul `<import_name>`
+    ///
+    /// Dateien innerhalb des Packages werden zu Untermodulen des Package-Moduls,
+    /// damit sie nicht mit Modulen des Projekts kollidieren. `use pkg.sub` lädt
+    /// `sub.velin` neben der Einstiegsdatei, auch wenn das Package es selbst nicht nutzt.
+    fn load_package(
+        &self,
+        package: &Package,
+        submodule: Option<&str>,
+        parent: &PackageIndex,
+        context: &mut CompilationContext,
+        imports: &mut Imports,
+    ) -> Result<()> {
+        let entry = match package.entry() {
+            Ok(entry) => entry,
+            Err(e) => {
+                Self::error(context, e.to_string());
+                return Ok(());
+            }
+        };
+        let entry_dir = entry.parent().unwrap().to_path_buf();
+        let index = match parent.nested(&package.root) {
+            Ok(index) => index,
+            Err(e) => {
+                Self::error(context, format!("{:#}", e));
+                return Ok(());
+            }
+        };
+        let scope = Scope {
+            packages: Some(&index),
+            package: Some(&package.name),
+        };
+
+        let position = match imports
+            .packages
+            .iter()
+            .position(|(root, _)| *root == package.root)
+        {
+            Some(position) => position,
+            None => {
+                if let Some((_, other)) = imports
+                    .packages
+                    .iter()
+                    .find(|(_, module)| module.name == package.import_name)
+                {
+                    Self::error(
                         context,
-                        visited_modules,
-                        global_modules,
-                    )?;
-
-                    // Wrap imported items in a Module item
-                    // This enables namespacing (e.g., models.Item)
-                    let mod_item = Item::Module(crate::parser::ast::Module {
-                        name: mod_name.clone(),
-                        items: mod_program.items,
-                        visibility: crate::parser::ast::Visibility::Public,
+                        format!(
+                            "Import '{}' is ambiguous: it matches the packages '{}', '{}'",
+                            package.import_name,
+                            other.package.as_deref().unwrap_or_default(),
+                            package.name
+                        ),
+                    );
+                    return Ok(());
+                }
+
+                // Platzhalter vor dem Laden, damit zyklische Packages terminieren
+                imports.packages.push((
+                    package.root.clone(),
+                    Module {
+                        name: package.import_name.clone(),
+                        items: Vec::new(),
+                        visibility: Visibility::Public,
                         documentation: None,
-                    });
+                        package: Some(package.name.clone()),
+                    },
+                ));
+                let position = imports.packages.len() - 1;
 
-                    // Add to global modules list (Flattening)
-                    global_modules.push(mod_item);
+                let mut submodules = Vec::new();
+                if let Some(module) = self.load_module(
+                    &package.import_name,
+                    &entry,
+                    &scope,
+                    context,
+                    imports,
+                    &mut submodules,
+                )? {
+                    let items = &mut imports.packages[position].1.items;
+                    items.extend(module.items);
+                    items.extend(submodules);
                 }
-                Err(e) => {
-                    // SECURITY: Fehler statt nur Logging
-                    context
-                        .errors
-                        .push(crate::error::CompilerError::parse_error(
-                            format!(
-                                "Failed to parse module {}: {} (at line {}, column {})",
-                                mod_name, e.message, e.line, e.column
-                            ),
-                            crate::error::ErrorLocation::new(e.line, e.column),
-                        ));
-                    eprintln!("Failed to parse module {}: {}", mod_name, e.message);
-                    eprintln!("  at line {}, column {}", e.line, e.column);
-                    eprintln!("  found: {}", e.found);
-                    if let Some(ctx) = e.source_context {
-                        eprintln!("  Context:\n{}", ctx);
-                    }
+                position
+            }
+        };
+
+        if let Some(submodule) = submodule {
+            let path = entry_dir.join(format!("{}.velin", submodule));
+            if path.is_file() {
+                let mut submodules = Vec::new();
+                if let Some(module) =
+                    self.load_module(submodule, &path, &scope, context, imports, &mut submodules)?
+                {
+                    submodules.push(Item::Module(module));
                 }
+                imports.packages[position].1.items.extend(submodules);
             }
         }
 
@@ -162,20 +349,52 @@ impl Pass for ParserPass {
 
         match Parser::parse(&root_source) {
             Ok(mut program) => {
+                let packages = match PackageIndex::discover(&root_path_buf) {
+                    Ok(packages) => packages,
+                    Err(e) => {
+                        Self::error(context, format!("{:#}", e));
+                        None
+                    }
+                };
+
                 // Resolve imports
-                let mut visited_modules = HashSet::new();
-                visited_modules.insert(context.root_file.clone()); // Mark root as visited
+                let mut imports = Imports::default();
+                imports.visited_modules.insert(context.root_file.clone()); // Mark root as visited
 
                 let mut global_modules = Vec::new();
 
                 self.resolve_imports(
                     &program,
                     &root_path_buf,
+                    &Scope {
+                        packages: packages.as_ref(),
+                        package: None,
+                    },
                     context,
-                    &mut visited_modules,
+                    &mut imports,
                     &mut global_modules,
                 )?;
 
+                // Package-Module neben den lokalen Modulen; Namenskonflikte über
+                // Verzeichnisgrenzen hinweg sind ebenfalls mehrdeutig
+                for (_, module) in imports.packages {
+                    let clash = global_modules
+                        .iter()
+                        .any(|item| matches!(item, Item::Module(m) if m.name == module.name));
+                    if clash {
+                        Self::error(
+                            context,
+                            format!(
+                                "Import '{}' is ambiguous: it matches a local module and the package '{}'",
+                                module.name,
+                                module.package.as_deref().unwrap_or_default()
+                            ),
+                        );
+                        continue;
+                    }
+                    global_modules.push(Item::Module(module));
+                }
+
                 // Add all resolved modules to the root program
                 program.items.extend(global_modules);
 
@@ -189,3 +408,150 @@ impl Pass for ParserPass {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::passes::type_check::TypeCheckPass;
+
+    const MANIFEST: &str = "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nmath-utils = { path = \"../math-utils\" }\n";
+
+    /// Legt die Dateien an und führt den Parser-Pass für `app/main.velin` aus
+    fn compile(files: &[(&str, &str)], type_check: bool) -> CompilationContext {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let main = dir.path().join("app/main.velin");
+        let source = fs::read_to_string(&main).unwrap();
+        let mut context = CompilationContext::new(main.to_string_lossy().to_string(), source);
+        ParserPass::new().run(&mut context).unwrap();
+        if type_check && context.errors.is_empty() {
+            TypeCheckPass::new(true).run(&mut context).unwrap();
+        }
+        context
+    }
+
+    fn errors(context: &CompilationContext) -> Vec<String> {
+        context.errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    fn module<'a>(items: &'a [Item], name: &str) -> &'a Module {
+        items
+            .iter()
+            .find_map(|item| match item {
+                Item::Module(module) if module.name == name => Some(module),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("Modul '{}' fehlt", name))
+    }
+
+    #[test]
+    fn test_path_dependency_is_loaded_with_its_submodules() {
+        let context = compile(
+            &[
+                ("app/velin.toml", MANIFEST),
+                (
+                    "app/main.velin",
+                    "use math_utils;\nuse math_utils.extra;\n\nfn main(): number {\n    return math_utils.square(2);\n}\n",
+                ),
+                (
+                    "math-utils/lib.velin",
+                    "use helpers;\n\npub fn square(x: number): number {\n    return helpers.times(x, x);\n}\n",
+                ),
+                (
+                    "math-utils/helpers.velin",
+                    "pub fn times(a: number, b: number): number {\n    return a * b;\n}\n",
+                ),
+                (
+                    "math-utils/extra.velin",
+                    "pub fn cube(x: number): number {\n    return x * x * x;\n}\n",
+                ),
+            ],
+            true,
+        );
+        assert!(context.errors.is_empty(), "{:?}", errors(&context));
+
+        let program = context.program.as_ref().unwrap();
+        let package = module(&program.items, "math_utils");
+        assert_eq!(package.package.as_deref(), Some("math-utils"));
+        // Dateien des Packages werden Untermodule, nicht Module des Projekts
+        module(&package.items, "helpers");
+        module(&package.items, "extra");
+        assert!(!program
+            .items
+            .iter()
+            .any(|item| matches!(item, Item::Module(m) if m.name == "helpers")));
+    }
+
+    #[test]
+    fn test_private_package_items_are_rejected() {
+        let context = compile(
+            &[
+                ("app/velin.toml", MANIFEST),
+                (
+                    "app/main.velin",
+                    "use math_utils;\n\nfn main(): number {\n    return math_utils.secret();\n}\n",
+                ),
+                (
+                    "math-utils/lib.velin",
+                    "fn secret(): number {\n    return 42;\n}\n",
+                ),
+            ],
+            true,
+        );
+        let errors = errors(&context);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("'secret' is private in package module 'math_utils'")),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_local_module_and_package_with_the_same_name_are_ambiguous() {
+        let context = compile(
+            &[
+                ("app/velin.toml", MANIFEST),
+                ("app/main.velin", "use math_utils;\n\nfn main() {\n}\n"),
+                ("app/math_utils.velin", "pub fn local() {\n}\n"),
+                ("math-utils/lib.velin", "pub fn remote() {\n}\n"),
+            ],
+            false,
+        );
+        let errors = errors(&context);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(
+            errors[0].contains("Import 'math_utils' is ambiguous: it matches the local module"),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_missing_vendored_package_asks_for_install() {
+        let context = compile(
+            &[
+                (
+                    "app/velin.toml",
+                    "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\njson = \"^1.0\"\n",
+                ),
+                ("app/main.velin", "use json;\n\nfn main() {\n}\n"),
+            ],
+            false,
+        );
+        let errors = errors(&context);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("Package 'json' is not installed")
+                    && e.contains("velin-pkg install")),
+            "{:?}",
+            errors
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::compiler::context::CompilationContext;
+use crate::compiler::pass::Pass;
+use crate::passes::instrument::instrument_sources;
+use crate::profiler::{ProfileInstrumenter, ProfileMap};
+use anyhow::Result;
+
+/// Instrumentiert alle Funktionen mit Entry/Exit-Hooks für `velin-profile`.
+///
+/// Läuft wie der `CoverageInstrumentationPass` direkt nach dem `ParserPass`,
+/// damit die Funktionszeilen noch der Quellreihenfolge entsprechen.
+pub struct ProfileInstrumentationPass;
+
+impl ProfileInstrumentationPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ProfileInstrumentationPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for ProfileInstrumentationPass {
+    fn name(&self) -> &str {
+        "ProfileInstrumentation"
+    }
+
+    fn run(&self, context: &mut CompilationContext) -> Result<()> {
+        if context.has_errors() || context.program.is_none() {
+            return Ok(());
+        }
+
+        let mut map = ProfileMap::default();
+        instrument_sources(context, |program, file, lines, module| {
+            let first = map.functions.len();
+            ProfileInstrumenter::new(&mut map, file.to_string(), lines).instrument(program);
+            if let Some(module) = module {
+                for function in &mut map.functions[first..] {
+                    function.name = format!("{}::{}", module, function.name);
+                }
+            }
+        });
+
+        context.profile_map = map;
+        Ok(())
+    }
+}
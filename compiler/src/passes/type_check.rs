@@ -26,7 +26,10 @@ impl Pass for TypeCheckPass {
         }
 
         if let Some(program) = &context.program {
-            let mut checker = TypeChecker::new();
+            let mut checker = TypeChecker::new().with_source_file(&context.root_file);
+            if let Some(source) = context.source_map.get(&context.root_file) {
+                checker = checker.with_source(&context.root_file, source);
+            }
             // Note: TypeChecker processes the entire merged AST from ParserPass,
             // so it sees all definitions across modules.
 
@@ -0,0 +1,47 @@
+use crate::codegen::wasm::check_program;
+use crate::compiler::context::CompilationContext;
+use crate::compiler::pass::Pass;
+use crate::error::{CompilerError, ErrorLocation};
+use anyhow::Result;
+
+/// Prüft vor dem Codegen, ob das Programm als Wasm-Modul gebaut werden kann:
+/// Export-Signaturen müssen über JSON marshallbar sein, und von Exporten aus
+/// erreichbarer Code darf keine Host-Module (`fs`, `process`, `db`, ...) nutzen.
+pub struct WasmTargetPass;
+
+impl WasmTargetPass {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for WasmTargetPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for WasmTargetPass {
+    fn name(&self) -> &str {
+        "WasmTarget"
+    }
+
+    fn run(&self, context: &mut CompilationContext) -> Result<()> {
+        if context.has_errors() {
+            return Ok(());
+        }
+
+        let errors = match &context.program {
+            Some(program) => check_program(program),
+            None => return Ok(()),
+        };
+        for message in errors {
+            context.add_error(CompilerError::codegen_error_with_location(
+                message,
+                ErrorLocation::with_file(0, 0, context.root_file.clone()),
+                Some("Target: WebAssembly".to_string()),
+            ));
+        }
+        Ok(())
+    }
+}
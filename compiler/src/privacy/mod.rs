@@ -0,0 +1,915 @@
+// PII-Flussprüfung zur Compile-Zeit
+// Felder mit `@Privacy` (bzw. `@PII`, `@Secret`) und `PrivacyWrapper`-Werte gelten
+// als personenbezogen. Der Type Checker meldet, wenn sie Logs, Audit-Einträge,
+// LLM-Prompts oder HTTP-Antworten ohne `@Expose` erreichen.
+
+use crate::parser::ast::*;
+use crate::parser::visitor::{calls_module, visit_block};
+use std::collections::{HashMap, HashSet};
+
+/// Hinweis, wie ein gemeldeter Fluss aufgelöst wird
+const REMEDY: &str =
+    "mask it with privacy.mask(...) or declassify it with privacy.declassify(..., \"reason\")";
+const RESPONSE_REMEDY: &str = "mark the field or route with @Expose, mask it with privacy.mask(...) or declassify it with privacy.declassify(..., \"reason\")";
+
+pub fn is_privacy_decorator(decorator: &Decorator) -> bool {
+    matches!(
+        decorator.name.trim_start_matches('@'),
+        "Privacy" | "PII" | "Secret"
+    )
+}
+
+fn is_expose(decorator: &Decorator) -> bool {
+    decorator.name.trim_start_matches('@') == "Expose"
+}
+
+fn is_route(function: &Function) -> bool {
+    crate::validation::is_route_handler(function)
+}
+
+/// Woher personenbezogene Daten in einem Wert stammen
+#[derive(Debug, Clone, PartialEq)]
+struct Taint {
+    source: String,
+    /// Nur `@Expose`-Felder: in HTTP-Antworten erlaubt
+    exposable: bool,
+}
+
+fn merge(left: Option<Taint>, right: Option<Taint>) -> Option<Taint> {
+    match (left, right) {
+        (Some(l), Some(r)) if l.exposable && !r.exposable => Some(r),
+        (Some(l), _) => Some(l),
+        (None, r) => r,
+    }
+}
+
+/// Taint und, soweit bekannt, Typ eines Ausdrucks
+#[derive(Debug, Clone, Default)]
+struct Flow {
+    taint: Option<Taint>,
+    ty: Option<Type>,
+}
+
+impl Flow {
+    fn clean() -> Flow {
+        Flow::default()
+    }
+}
+
+struct Catalog<'a> {
+    structs: HashMap<&'a str, &'a Struct>,
+    functions: HashMap<&'a str, &'a Function>,
+}
+
+impl<'a> Catalog<'a> {
+    fn collect(items: &'a [Item], catalog: &mut Catalog<'a>) {
+        for item in items {
+            match item {
+                Item::Struct(s) => {
+                    catalog.structs.insert(&s.name, s);
+                }
+                Item::Function(f) => {
+                    catalog.functions.insert(&f.name, f);
+                }
+                Item::Module(m) => Catalog::collect(&m.items, catalog),
+                _ => {}
+            }
+        }
+    }
+
+    /// Personenbezogene Anteile eines Typs; `None` bei unkritischen Typen
+    fn type_taint(&self, ty: &Type) -> Option<Taint> {
+        self.type_taint_in(ty, &mut HashSet::new())
+    }
+
+    fn type_taint_in(&self, ty: &Type, seen: &mut HashSet<String>) -> Option<Taint> {
+        match ty {
+            Type::Generic { name, .. } if name == "PrivacyWrapper" => Some(Taint {
+                source: "a PrivacyWrapper value".to_string(),
+                exposable: false,
+            }),
+            Type::Named(name) => {
+                let def = self.structs.get(name.as_str())?;
+                if !seen.insert(name.clone()) {
+                    return None;
+                }
+                let mut fields = Vec::new();
+                let mut exposable = true;
+                for field in &def.fields {
+                    if field.decorators.iter().any(is_privacy_decorator) {
+                        fields.push(field.name.as_str());
+                        exposable &= field.decorators.iter().any(is_expose);
+                    } else if let Some(inner) = self.type_taint_in(&field.field_type, seen) {
+                        fields.push(field.name.as_str());
+                        exposable &= inner.exposable;
+                    }
+                }
+                if fields.is_empty() {
+                    return None;
+                }
+                Some(Taint {
+                    source: format!("{} (PII in {})", name, fields.join(", ")),
+                    exposable,
+                })
+            }
+            Type::List(inner) | Type::Optional(inner) => self.type_taint_in(inner, seen),
+            Type::Map { value, .. } => self.type_taint_in(value, seen),
+            Type::Result { ok, .. } => self.type_taint_in(ok, seen),
+            Type::Generic { params, .. } | Type::Tuple(params) => params
+                .iter()
+                .fold(None, |taint, p| merge(taint, self.type_taint_in(p, seen))),
+            _ => None,
+        }
+    }
+
+    fn field(&self, ty: &Type, member: &str) -> Option<&'a StructField> {
+        match ty {
+            Type::Named(name) => self
+                .structs
+                .get(name.as_str())?
+                .fields
+                .iter()
+                .find(|f| f.name == member),
+            Type::Optional(inner) => self.field(inner, member),
+            Type::Result { ok, .. } => self.field(ok, member),
+            _ => None,
+        }
+    }
+}
+
+fn element_type(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::List(inner) => Some(*inner.clone()),
+        Type::Optional(inner) | Type::Result { ok: inner, .. } => element_type(inner),
+        Type::Generic { name, params } if name == "List" => params.first().cloned(),
+        _ => None,
+    }
+}
+
+/// Wohin ein Aufruf seine Argumente schreibt
+fn sink(object: &str, method: &str) -> Option<String> {
+    match (object, method) {
+        ("log" | "logging" | "logger", _) => Some(format!("{}.{}", object, method)),
+        ("audit", "log") => Some("audit.log".to_string()),
+        ("llm", _) => Some(format!("the prompt of llm.{}", method)),
+        _ => None,
+    }
+}
+
+/// Ein gemeldeter Fluss personenbezogener Daten in eine Senke
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leak {
+    /// Funktion, in der die Senke steht
+    pub function: String,
+    pub reason: String,
+    /// Senke im Quelltext: `log.info`, `@llm.summarize` oder `return`
+    pub sink: String,
+    /// Das wievielte Vorkommen von `sink` in der Funktion (ab 0)
+    pub occurrence: usize,
+}
+
+struct FunctionFlow<'c, 'a> {
+    catalog: &'c Catalog<'a>,
+    function: &'a Function,
+    vars: HashMap<String, Flow>,
+    /// Route mit `@Expose`: PII in der Antwort ist gewollt
+    exposed_response: bool,
+    /// Besuchte Senken je Quelltext, in Quellreihenfolge
+    sinks: HashMap<String, usize>,
+    problems: Vec<Leak>,
+}
+
+impl<'c, 'a> FunctionFlow<'c, 'a> {
+    /// Zählt eine Senke mit, bevor ihre Argumente geprüft werden
+    fn visit_sink(&mut self, sink: &str) -> (String, usize) {
+        let visited = self.sinks.entry(sink.to_string()).or_insert(0);
+        *visited += 1;
+        (sink.to_string(), *visited - 1)
+    }
+
+    fn report(&mut self, taint: &Taint, target: &str, remedy: &str, at: &(String, usize)) {
+        let reason = format!("{} reaches {}; {}", taint.source, target, remedy);
+        if self.problems.iter().any(|p| p.reason == reason) {
+            return;
+        }
+        self.problems.push(Leak {
+            function: self.function.name.clone(),
+            reason,
+            sink: at.0.clone(),
+            occurrence: at.1,
+        });
+    }
+
+    fn bind(&mut self, name: &str, flow: Flow) {
+        let previous = self.vars.remove(name).unwrap_or_default();
+        self.vars.insert(
+            name.to_string(),
+            Flow {
+                taint: merge(previous.taint, flow.taint),
+                ty: flow.ty.or(previous.ty),
+            },
+        );
+    }
+
+    fn typed(&self, ty: Type) -> Flow {
+        Flow {
+            taint: self.catalog.type_taint(&ty),
+            ty: Some(ty),
+        }
+    }
+
+    fn block(&mut self, block: &Block) -> Flow {
+        let mut last = Flow::clean();
+        for statement in &block.statements {
+            last = self.statement(statement);
+        }
+        last
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Flow {
+        match statement {
+            Statement::Let(let_stmt) => {
+                let mut flow = self.expression(&let_stmt.value);
+                if let Some(ty) = &let_stmt.var_type {
+                    let declared = self.typed(ty.clone());
+                    flow = Flow {
+                        taint: merge(flow.taint, declared.taint),
+                        ty: declared.ty,
+                    };
+                }
+                self.bind(&let_stmt.name, flow);
+                Flow::clean()
+            }
+            Statement::Return(ret) => {
+                let at = self.visit_sink("return");
+                if let Some(value) = &ret.value {
+                    let flow = self.expression(value);
+                    self.check_response(&flow, &at);
+                }
+                Flow::clean()
+            }
+            Statement::Expression(expr) => self.expression(&expr.expression),
+            Statement::If(if_stmt) => {
+                self.expression(&if_stmt.condition);
+                let then_flow = self.block(&if_stmt.then_block);
+                let else_flow = if_stmt
+                    .else_block
+                    .as_ref()
+                    .map(|b| self.block(b))
+                    .unwrap_or_default();
+                Flow {
+                    taint: merge(then_flow.taint, else_flow.taint),
+                    ty: then_flow.ty.or(else_flow.ty),
+                }
+            }
+            Statement::For(for_stmt) => {
+                let iterable = self.expression(&for_stmt.iterable);
+                let element = Flow {
+                    taint: iterable.taint,
+                    ty: iterable.ty.as_ref().and_then(element_type),
+                };
+                self.bind(&for_stmt.variable, element);
+                self.block(&for_stmt.body);
+                Flow::clean()
+            }
+            Statement::While(while_stmt) => {
+                self.expression(&while_stmt.condition);
+                self.block(&while_stmt.body);
+                Flow::clean()
+            }
+            Statement::Match(match_stmt) => {
+                let scrutinee = self.expression(&match_stmt.expression);
+                let mut result = Flow::clean();
+                for arm in &match_stmt.arms {
+                    self.bind_pattern(&arm.pattern, &scrutinee);
+                    if let Some(guard) = &arm.guard {
+                        self.expression(guard);
+                    }
+                    let flow = self.block(&arm.body);
+                    result.taint = merge(result.taint, flow.taint);
+                }
+                result
+            }
+            Statement::Throw(throw) => self.expression(&throw.expression),
+            Statement::Break(_) => Flow::clean(),
+            Statement::Try(try_stmt) => {
+                self.block(&try_stmt.try_block);
+                for catch in &try_stmt.catch_blocks {
+                    self.block(&catch.body);
+                }
+                if let Some(finally) = &try_stmt.finally_block {
+                    self.block(finally);
+                }
+                Flow::clean()
+            }
+        }
+    }
+
+    fn bind_pattern(&mut self, pattern: &Pattern, flow: &Flow) {
+        match pattern {
+            Pattern::Identifier(name) => self.bind(
+                name,
+                Flow {
+                    taint: flow.taint.clone(),
+                    ty: None,
+                },
+            ),
+            Pattern::Tuple(patterns) => {
+                for p in patterns {
+                    self.bind_pattern(p, flow);
+                }
+            }
+            Pattern::Struct { fields, .. } => {
+                for (_, p) in fields {
+                    self.bind_pattern(p, flow);
+                }
+            }
+            Pattern::EnumVariant {
+                data: Some(patterns),
+                ..
+            } => {
+                for p in patterns {
+                    self.bind_pattern(p, flow);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_response(&mut self, flow: &Flow, at: &(String, usize)) {
+        if !is_route(self.function) || self.exposed_response {
+            return;
+        }
+        if let Some(taint) = &flow.taint {
+            if !taint.exposable {
+                self.report(&taint.clone(), "the HTTP response", RESPONSE_REMEDY, at);
+            }
+        }
+    }
+
+    fn expressions(&mut self, exprs: &[Expression]) -> Flow {
+        let mut taint = None;
+        for expr in exprs {
+            taint = merge(taint, self.expression(expr).taint);
+        }
+        Flow { taint, ty: None }
+    }
+
+    fn expression(&mut self, expr: &Expression) -> Flow {
+        match expr {
+            Expression::Literal(_) => Flow::clean(),
+            Expression::Identifier(name) => self.vars.get(name).cloned().unwrap_or_default(),
+            Expression::BinaryOp { left, op, right } => {
+                let left = self.expression(left);
+                let right = self.expression(right);
+                match op {
+                    BinaryOperator::Add
+                    | BinaryOperator::Subtract
+                    | BinaryOperator::Multiply
+                    | BinaryOperator::Divide
+                    | BinaryOperator::Modulo => Flow {
+                        taint: merge(left.taint, right.taint),
+                        ty: None,
+                    },
+                    // Vergleiche liefern nur Wahrheitswerte
+                    _ => Flow::clean(),
+                }
+            }
+            Expression::UnaryOp { expr, .. } => Flow {
+                taint: self.expression(expr).taint,
+                ty: None,
+            },
+            Expression::Member { object, member } => {
+                let object = self.expression(object);
+                let Some(ty) = &object.ty else {
+                    return Flow {
+                        taint: object.taint,
+                        ty: None,
+                    };
+                };
+                match self.catalog.field(ty, member) {
+                    Some(field) if field.decorators.iter().any(is_privacy_decorator) => {
+                        let struct_name = match ty {
+                            Type::Named(name) => name.clone(),
+                            other => other.to_string(),
+                        };
+                        Flow {
+                            taint: Some(Taint {
+                                source: format!("{}.{} (@Privacy)", struct_name, field.name),
+                                exposable: field.decorators.iter().any(is_expose),
+                            }),
+                            ty: Some(field.field_type.clone()),
+                        }
+                    }
+                    Some(field) => self.typed(field.field_type.clone()),
+                    None => Flow {
+                        taint: object.taint,
+                        ty: None,
+                    },
+                }
+            }
+            Expression::Index { object, index } => {
+                self.expression(index);
+                let object = self.expression(object);
+                Flow {
+                    taint: object.taint,
+                    ty: object.ty.as_ref().and_then(element_type),
+                }
+            }
+            Expression::Call { callee, args } => self.call(callee, args),
+            Expression::LLMCall { method, args } => {
+                let at = self.visit_sink(&format!("@llm.{}", method));
+                let flow = self.expressions(args);
+                if let Some(taint) = flow.taint {
+                    self.report(
+                        &taint,
+                        &format!("the prompt of @llm.{}", method),
+                        REMEDY,
+                        &at,
+                    );
+                }
+                Flow::clean()
+            }
+            Expression::If {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                self.expression(condition);
+                let then_flow = self.expression(then_expr);
+                let else_flow = self.expression(else_expr);
+                Flow {
+                    taint: merge(then_flow.taint, else_flow.taint),
+                    ty: then_flow.ty.or(else_flow.ty),
+                }
+            }
+            Expression::Block(block) => self.block(block),
+            Expression::Await { expr } => self.expression(expr),
+            Expression::StructLiteral { name, fields } => {
+                let values: Vec<Expression> = fields.iter().map(|(_, v)| v.clone()).collect();
+                let values = self.expressions(&values);
+                let declared = self.typed(Type::Named(name.clone()));
+                Flow {
+                    taint: merge(declared.taint, values.taint),
+                    ty: declared.ty,
+                }
+            }
+            Expression::MapLiteral(entries) => {
+                let values: Vec<Expression> = entries.iter().map(|(_, v)| v.clone()).collect();
+                self.expressions(&values)
+            }
+            Expression::ListLiteral(items) => self.expressions(items),
+            Expression::GenericConstructor {
+                name,
+                type_params,
+                args,
+            } => {
+                let values = self.expressions(args);
+                let declared = self.typed(Type::Generic {
+                    name: name.clone(),
+                    params: type_params.clone(),
+                });
+                Flow {
+                    taint: merge(declared.taint, values.taint),
+                    ty: declared.ty,
+                }
+            }
+            Expression::Lambda { params, body, .. } => {
+                for param in params {
+                    let flow = self.typed(param.param_type.clone());
+                    self.bind(&param.name, flow);
+                }
+                self.expression(body);
+                Flow::clean()
+            }
+            Expression::Assignment { target, value } => {
+                let flow = self.expression(value);
+                let mut root = target.as_ref();
+                while let Expression::Member { object, .. } | Expression::Index { object, .. } =
+                    root
+                {
+                    root = object;
+                }
+                if let Expression::Identifier(name) = root {
+                    let name = name.clone();
+                    self.bind(
+                        &name,
+                        Flow {
+                            taint: flow.taint,
+                            ty: None,
+                        },
+                    );
+                }
+                Flow::clean()
+            }
+            Expression::FormatString { parts } => {
+                let values: Vec<Expression> = parts
+                    .iter()
+                    .filter_map(|part| match part {
+                        FormatStringPart::Expression(e) => Some(*e.clone()),
+                        FormatStringPart::Text(_) => None,
+                    })
+                    .collect();
+                self.expressions(&values)
+            }
+        }
+    }
+
+    fn call(&mut self, callee: &Expression, args: &[Expression]) -> Flow {
+        if let Expression::Member { object, member } = callee {
+            if let Expression::Identifier(module) = object.as_ref() {
+                if !self.vars.contains_key(module) {
+                    return self.module_call(module, member, args);
+                }
+            }
+            // Methodenaufruf: Ergebnis erbt vom Objekt und den Argumenten
+            let object = self.expression(object);
+            let args = self.expressions(args);
+            return Flow {
+                taint: merge(object.taint, args.taint),
+                ty: None,
+            };
+        }
+
+        let arg_flow = self.expressions(args);
+        let Expression::Identifier(name) = callee else {
+            return arg_flow;
+        };
+        let Some(function) = self.catalog.functions.get(name.as_str()) else {
+            return arg_flow;
+        };
+        match &function.return_type {
+            None | Some(Type::Void | Type::Boolean | Type::Number) => Flow::clean(),
+            Some(ty) => {
+                let declared = self.typed(ty.clone());
+                Flow {
+                    taint: merge(declared.taint, arg_flow.taint),
+                    ty: declared.ty,
+                }
+            }
+        }
+    }
+
+    fn module_call(&mut self, module: &str, method: &str, args: &[Expression]) -> Flow {
+        match (module, method) {
+            ("privacy", "mask") => {
+                self.expressions(args);
+                return Flow {
+                    taint: None,
+                    ty: Some(Type::String),
+                };
+            }
+            // Bewusst freigegeben: auch die Felder des Werts gelten nicht mehr als PII
+            ("privacy", "declassify") => {
+                self.expressions(args);
+                return Flow::clean();
+            }
+            _ => {}
+        }
+
+        if let Some(target) = sink(module, method) {
+            let at = self.visit_sink(&format!("{}.{}", module, method));
+            let flows: Vec<Flow> = args.iter().map(|arg| self.expression(arg)).collect();
+            for taint in flows.iter().filter_map(|f| f.taint.clone()) {
+                self.report(&taint, &target, REMEDY, &at);
+            }
+            return Flow::clean();
+        }
+        let flows: Vec<Flow> = args.iter().map(|arg| self.expression(arg)).collect();
+
+        // `db.find(User, id)` liefert die Entity
+        if module == "db" {
+            if let Some(Expression::Identifier(entity)) = args.first() {
+                if self.catalog.structs.contains_key(entity.as_str()) {
+                    let entity = Type::Named(entity.clone());
+                    let ty = match method {
+                        "findAll" | "find_all" | "query" => Type::List(Box::new(entity)),
+                        _ => entity,
+                    };
+                    return self.typed(ty);
+                }
+            }
+        }
+        flows.into_iter().fold(Flow::clean(), |acc, flow| Flow {
+            taint: merge(acc.taint, flow.taint),
+            ty: None,
+        })
+    }
+}
+
+fn check_function<'a>(catalog: &Catalog<'a>, function: &'a Function) -> Vec<Leak> {
+    let mut flow = FunctionFlow {
+        catalog,
+        function,
+        vars: HashMap::new(),
+        exposed_response: function.decorators.iter().any(is_expose),
+        sinks: HashMap::new(),
+        problems: Vec::new(),
+    };
+    for param in &function.params {
+        let param_flow = flow.typed(param.param_type.clone());
+        flow.bind(&param.name, param_flow);
+    }
+    flow.block(&function.body);
+    flow.problems
+}
+
+fn collect_functions<'a>(items: &'a [Item], functions: &mut Vec<&'a Function>) {
+    for item in items {
+        match item {
+            Item::Function(f) => functions.push(f),
+            Item::Impl(i) => functions.extend(i.methods.iter()),
+            Item::Module(m) => collect_functions(&m.items, functions),
+            _ => {}
+        }
+    }
+}
+
+/// Alle Flüsse personenbezogener Daten in Logs, Audit, LLM-Prompts und
+/// HTTP-Antworten
+pub fn check_flows(items: &[Item]) -> Vec<Leak> {
+    let mut catalog = Catalog {
+        structs: HashMap::new(),
+        functions: HashMap::new(),
+    };
+    Catalog::collect(items, &mut catalog);
+
+    let has_pii = catalog.structs.values().any(|s| {
+        s.fields
+            .iter()
+            .any(|f| f.decorators.iter().any(is_privacy_decorator))
+    }) || mentions_privacy_wrapper(items);
+    if !has_pii {
+        return Vec::new();
+    }
+
+    let mut functions = Vec::new();
+    collect_functions(items, &mut functions);
+    functions
+        .into_iter()
+        .flat_map(|function| check_function(&catalog, function))
+        .collect()
+}
+
+fn mentions_privacy_wrapper(items: &[Item]) -> bool {
+    fn wraps(ty: &Type) -> bool {
+        match ty {
+            Type::Generic { name, params } => name == "PrivacyWrapper" || params.iter().any(wraps),
+            Type::List(inner) | Type::Optional(inner) => wraps(inner),
+            Type::Map { key, value } => wraps(key) || wraps(value),
+            Type::Result { ok, err } => wraps(ok) || wraps(err),
+            Type::Tuple(types) => types.iter().any(wraps),
+            _ => false,
+        }
+    }
+    let mut functions = Vec::new();
+    collect_functions(items, &mut functions);
+    let in_signatures = functions.iter().any(|f| {
+        f.params.iter().any(|p| wraps(&p.param_type)) || f.return_type.as_ref().is_some_and(wraps)
+    });
+    let mut in_bodies = false;
+    for function in &functions {
+        visit_block(&function.body, &mut |expr| {
+            if let Expression::GenericConstructor { name, .. } = expr {
+                in_bodies |= name == "PrivacyWrapper";
+            }
+        });
+    }
+    let in_structs = items.iter().any(|item| match item {
+        Item::Struct(s) => s.fields.iter().any(|f| wraps(&f.field_type)),
+        _ => false,
+    });
+    in_signatures || in_bodies || in_structs
+}
+
+/// Prüft `privacy.*`-Aufrufe: Freigaben brauchen eine Begründung
+pub fn check_call(method: &str, args: &[Expression]) -> Result<(), String> {
+    match (method, args) {
+        ("declassify", [_, Expression::Literal(Literal::String(reason))])
+            if !reason.trim().is_empty() =>
+        {
+            Ok(())
+        }
+        ("declassify", _) => {
+            Err("declassification needs a non-empty reason string literal".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// `true`, wenn das Programm `privacy.*` aufruft
+pub fn uses_privacy_calls(program: &Program) -> bool {
+    calls_module(program, "privacy")
+}
+
+/// Runtime-Modul `stdlib::privacy` für `privacy.mask` und `privacy.declassify`
+pub const RUNTIME_CODE: &str = r#"
+/// `privacy.mask`: nur die ersten und letzten zwei Zeichen bleiben sichtbar
+pub fn mask<T: serde::Serialize>(value: &T) -> String {
+    let text = match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(text)) => text,
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    };
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= 4 {
+        return "***".to_string();
+    }
+    let head: String = chars[..2].iter().collect();
+    let tail: String = chars[chars.len() - 2..].iter().collect();
+    format!("{}***{}", head, tail)
+}
+
+/// `privacy.declassify`: gibt eine Kopie des Werts zurück, die Begründung steht im Quelltext
+pub fn declassify<T: Clone>(value: &T, _reason: &str) -> T {
+    value.clone()
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::context::CompilationContext;
+    use crate::compiler::pass::Pass;
+    use crate::parser::parser::Parser;
+    use crate::passes::type_check::TypeCheckPass;
+
+    const TYPES: &str = r#"struct User {
+    id: string,
+    @Privacy
+    email: string,
+    @Privacy
+    @Expose
+    name: string,
+}
+
+struct Profile {
+    user: User,
+    note: string,
+}
+"#;
+
+    fn leaks(functions: &str) -> Vec<Leak> {
+        let program = Parser::parse(&format!("{}\n{}", TYPES, functions)).unwrap();
+        check_flows(&program.items)
+    }
+
+    #[test]
+    fn test_pii_in_logs_is_reported_at_the_call() {
+        let leaks = leaks(
+            r#"fn notify(profile: Profile) {
+    for tag in [profile.note] {
+        log.info(tag);
+    }
+    let mail = profile.user.email;
+    log.info("mail: " + mail);
+    logging.warn(mail);
+}
+"#,
+        );
+        assert_eq!(leaks.len(), 2, "{:#?}", leaks);
+        assert_eq!(leaks[0].function, "notify");
+        assert!(leaks[0]
+            .reason
+            .starts_with("User.email (@Privacy) reaches log.info"));
+        assert_eq!(
+            (leaks[0].sink.as_str(), leaks[0].occurrence),
+            ("log.info", 1)
+        );
+        assert!(leaks[1]
+            .reason
+            .starts_with("User.email (@Privacy) reaches logging.warn"));
+        assert_eq!(
+            (leaks[1].sink.as_str(), leaks[1].occurrence),
+            ("logging.warn", 0)
+        );
+
+        // Der Type Checker meldet die Zeile der Senke, nicht nur die Funktion
+        let source = format!(
+            "{}\nfn lookup(user: User) {{\n    log.info(user.id);\n    log.info(user.email);\n}}\n",
+            TYPES
+        );
+        let mut context = CompilationContext::new("main.velin".to_string(), source.clone());
+        context.program = Some(Parser::parse(&source).unwrap());
+        TypeCheckPass::new(true).run(&mut context).unwrap();
+        let errors: Vec<String> = context.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert!(errors[0].contains("PII leak in lookup"), "{}", errors[0]);
+        assert!(errors[0].ends_with("at line 17, column 5"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_pii_in_audit_entries_is_reported() {
+        let leaks = leaks(
+            r#"fn record(user: User) {
+    audit.log({ "user": privacy.mask(user.email) });
+    audit.log({ "user": user.id, "email": user.email });
+}
+"#,
+        );
+        assert_eq!(leaks.len(), 1, "{:#?}", leaks);
+        assert!(leaks[0]
+            .reason
+            .starts_with("User.email (@Privacy) reaches audit.log"));
+        assert_eq!(
+            (leaks[0].sink.as_str(), leaks[0].occurrence),
+            ("audit.log", 1)
+        );
+    }
+
+    #[test]
+    fn test_pii_in_llm_prompts_is_reported() {
+        let leaks = leaks(
+            r#"fn summarize(user: User): string {
+    let summary = llm.summarize(user.name);
+    let analysis = @llm.analyze("contact " + user.email);
+    return summary;
+}
+"#,
+        );
+        assert_eq!(leaks.len(), 2, "{:#?}", leaks);
+        assert!(leaks[0]
+            .reason
+            .starts_with("User.name (@Privacy) reaches the prompt of llm.summarize"));
+        assert_eq!(leaks[0].sink, "llm.summarize");
+        assert!(leaks[1]
+            .reason
+            .starts_with("User.email (@Privacy) reaches the prompt of @llm.analyze"));
+        assert_eq!(leaks[1].sink, "@llm.analyze");
+    }
+
+    #[test]
+    fn test_pii_in_http_responses_needs_expose() {
+        let leaks = leaks(
+            r#"@GET("/users/:id")
+fn getUser(id: string): User {
+    let user = db.find(User, id);
+    if (user == null) {
+        return null;
+    }
+    return user;
+}
+
+@GET("/users/:id/name")
+fn getName(id: string): string {
+    let user = db.find(User, id);
+    return user.name;
+}
+
+@GET("/users/:id/profile")
+@Expose
+fn getProfile(id: string): Profile {
+    return Profile { user: db.find(User, id), note: "" };
+}
+
+fn load(id: string): User {
+    return db.find(User, id);
+}
+"#,
+        );
+        assert_eq!(leaks.len(), 1, "{:#?}", leaks);
+        assert_eq!(leaks[0].function, "getUser");
+        assert!(leaks[0].reason.starts_with(
+            "User (PII in email, name) reaches the HTTP response; mark the field or route with @Expose"
+        ));
+        assert_eq!((leaks[0].sink.as_str(), leaks[0].occurrence), ("return", 1));
+    }
+
+    #[test]
+    fn test_declassify_needs_a_reason() {
+        let leaks = leaks(
+            r#"fn share(user: User) {
+    log.info(user.email);
+}
+"#,
+        );
+        assert!(leaks[0]
+            .reason
+            .ends_with("or declassify it with privacy.declassify(..., \"reason\")"));
+
+        let reason = Expression::Literal(Literal::String(" ".to_string()));
+        let value = Expression::Identifier("user".to_string());
+        assert!(check_call("declassify", &[value.clone(), reason])
+            .unwrap_err()
+            .contains("non-empty reason"));
+        let reason = Expression::Literal(Literal::String("support ticket".to_string()));
+        assert!(check_call("declassify", &[value.clone(), reason]).is_ok());
+        assert!(check_call("mask", &[value]).is_ok());
+    }
+
+    #[test]
+    fn test_declassified_data_is_not_reported() {
+        let source = r#"@GET("/users/:id/contact")
+fn getContact(id: string): User {
+    let user = db.find(User, id);
+    let contact = privacy.declassify(user, "support ticket export");
+    log.info(contact.email);
+    audit.log({ "email": contact.email });
+    return contact;
+}
+"#;
+        assert_eq!(leaks(source), Vec::new());
+        let program = Parser::parse(&format!("{}\n{}", TYPES, source)).unwrap();
+        assert!(uses_privacy_calls(&program));
+    }
+}
@@ -0,0 +1,164 @@
+// Profiling-Instrumentierung
+// Öffnet am Anfang jeder Funktion einen Mess-Frame, der beim Verlassen des Scopes schließt
+
+use crate::parser::ast::*;
+use crate::parser::parser::SourceLines;
+use crate::profiler::{ProfileMap, ProfiledFunction, ENTER_FUNCTION, SESSION_FUNCTION};
+
+/// Variablenname des Frame-Guards; der Drop am Scope-Ende misst auch frühe `return`s
+const FRAME_VARIABLE: &str = "__velin_prof_frame";
+
+/// Variablenname des Session-Guards in `main`, der die Messdaten schreibt
+const SESSION_VARIABLE: &str = "__velin_prof_session_guard";
+
+/// Instrumentiert alle Funktionen eines Programms mit Entry/Exit-Hooks.
+///
+/// Die Funktionszeilen stammen aus `Parser::parse_with_lines` und werden in
+/// Item-Reihenfolge konsumiert, wie beim `CoverageInstrumenter`.
+pub struct ProfileInstrumenter<'a> {
+    map: &'a mut ProfileMap,
+    file: String,
+    lines: SourceLines,
+    next_function: usize,
+}
+
+impl<'a> ProfileInstrumenter<'a> {
+    /// Neue Funktionen werden ab `map.next_id()` an `map` angehängt
+    pub fn new(map: &'a mut ProfileMap, file: String, lines: SourceLines) -> Self {
+        Self {
+            map,
+            file,
+            lines,
+            next_function: 0,
+        }
+    }
+
+    pub fn instrument_items(mut self, items: &mut [Item]) {
+        for item in items {
+            self.instrument_item(item, None);
+        }
+    }
+
+    pub fn instrument(self, program: &mut Program) {
+        self.instrument_items(&mut program.items)
+    }
+
+    fn instrument_item(&mut self, item: &mut Item, owner: Option<&str>) {
+        match item {
+            Item::Function(function) => self.instrument_function(function, owner),
+            Item::Impl(impl_def) => {
+                let owner = impl_def.for_type.to_string();
+                for method in &mut impl_def.methods {
+                    self.instrument_function(method, Some(&owner));
+                }
+            }
+            Item::Module(module) => {
+                let owner = match owner {
+                    Some(outer) => format!("{}::{}", outer, module.name),
+                    None => module.name.clone(),
+                };
+                for item in &mut module.items {
+                    self.instrument_item(item, Some(&owner));
+                }
+            }
+            Item::TopLevelCode(_)
+            | Item::Struct(_)
+            | Item::Enum(_)
+            | Item::TypeAlias(_)
+            | Item::Use(_)
+            | Item::Trait(_) => {}
+        }
+    }
+
+    fn instrument_function(&mut self, function: &mut Function, owner: Option<&str>) {
+        let line = self
+            .lines
+            .functions
+            .get(self.next_function)
+            .copied()
+            .unwrap_or(0);
+        self.next_function += 1;
+
+        let id = self.map.next_id();
+        let name = match owner {
+            Some(owner) => format!("{}::{}", owner, function.name),
+            None => function.name.clone(),
+        };
+        self.map.functions.push(ProfiledFunction {
+            id,
+            name,
+            file: self.file.clone(),
+            line,
+        });
+
+        function.body.statements.insert(
+            0,
+            guard_statement(
+                FRAME_VARIABLE,
+                ENTER_FUNCTION,
+                vec![Expression::Literal(Literal::Number(id as f64))],
+            ),
+        );
+        // Der Session-Guard wird zuerst angelegt und damit zuletzt gedroppt,
+        // nachdem der Frame von `main` geschlossen ist
+        if function.name == "main" && owner.is_none() {
+            function.body.statements.insert(
+                0,
+                guard_statement(SESSION_VARIABLE, SESSION_FUNCTION, Vec::new()),
+            );
+        }
+    }
+}
+
+/// `let <variable> = <function>(<args>);`
+fn guard_statement(variable: &str, function: &str, args: Vec<Expression>) -> Statement {
+    Statement::Let(LetStatement {
+        name: variable.to_string(),
+        var_type: None,
+        value: Expression::Call {
+            callee: Box::new(Expression::Identifier(function.to_string())),
+            args,
+        },
+        mutable: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_functions_are_registered_with_lines() {
+        let source = "fn helper(x: number): number {
+    return x * 2;
+}
+
+fn main() {
+    let y = helper(2);
+}
+";
+        let (mut program, lines) = Parser::parse_with_lines(source).unwrap();
+        let mut map = ProfileMap::default();
+        ProfileInstrumenter::new(&mut map, "app.velin".to_string(), lines).instrument(&mut program);
+
+        let names: Vec<(&str, usize)> = map
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.line))
+            .collect();
+        assert_eq!(names, vec![("helper", 1), ("main", 5)]);
+
+        let Item::Function(main) = &program.items[1] else {
+            panic!("main erwartet");
+        };
+        let Statement::Let(session) = &main.body.statements[0] else {
+            panic!("Session-Guard erwartet");
+        };
+        assert_eq!(session.name, SESSION_VARIABLE);
+        let Statement::Let(frame) = &main.body.statements[1] else {
+            panic!("Frame-Guard erwartet");
+        };
+        assert_eq!(frame.name, FRAME_VARIABLE);
+    }
+}
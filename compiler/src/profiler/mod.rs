@@ -0,0 +1,77 @@
+// Instrumentiertes Profiling für Velin-Programme
+// Misst Funktionseintritt/-austritt und Allokationen im generierten Programm
+
+pub mod instrument;
+pub mod report;
+pub mod runner;
+pub mod runtime;
+
+pub use instrument::ProfileInstrumenter;
+pub use report::{FunctionProfile, ProfileReport};
+pub use runner::{ProfileRun, ProfileRunner};
+pub use runtime::ProfileRuntime;
+
+use serde::{Deserialize, Serialize};
+
+/// Laufzeitfunktion, die beim Funktionseintritt einen Frame öffnet
+pub const ENTER_FUNCTION: &str = "__velin_prof_enter";
+
+/// Laufzeitfunktion, die am Anfang von `main` die Messung startet
+pub const SESSION_FUNCTION: &str = "__velin_prof_session";
+
+/// Umgebungsvariable, in die die Laufzeit ihre Messdaten schreibt
+pub const PROFILE_FILE_ENV: &str = "VELIN_PROFILE_FILE";
+
+/// Eine instrumentierte Velin-Funktion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfiledFunction {
+    pub id: usize,
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// Zuordnung Funktions-ID → Velin-Funktion über alle Dateien eines Programms
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileMap {
+    pub functions: Vec<ProfiledFunction>,
+}
+
+impl ProfileMap {
+    pub fn function(&self, id: usize) -> Option<&ProfiledFunction> {
+        self.functions.iter().find(|f| f.id == id)
+    }
+
+    /// Erste freie Funktions-ID
+    pub fn next_id(&self) -> usize {
+        self.functions.iter().map(|f| f.id + 1).max().unwrap_or(0)
+    }
+}
+
+/// Messwerte einer Funktion, wie sie die Laufzeit schreibt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawFunctionStats {
+    pub calls: u64,
+    /// Inklusive Zeit (rekursive Aufrufe nur einmal gezählt)
+    pub total_ns: u64,
+    /// Exklusive Zeit ohne aufgerufene Velin-Funktionen
+    pub self_ns: u64,
+    pub alloc_bytes: u64,
+    pub allocations: u64,
+}
+
+/// Ein Call-Stack (Funktions-IDs von außen nach innen) mit seiner exklusiven Zeit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawStack {
+    pub frames: Vec<usize>,
+    pub self_ns: u64,
+}
+
+/// Messdaten eines Laufs, wie sie die Laufzeit nach `VELIN_PROFILE_FILE` schreibt
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawProfile {
+    pub total_ns: u64,
+    pub peak_bytes: u64,
+    pub functions: Vec<RawFunctionStats>,
+    pub stacks: Vec<RawStack>,
+}
@@ -0,0 +1,214 @@
+// Profiling-Report
+// Bildet die Rohdaten der Laufzeit auf Velin-Funktionen und gefaltete Stacks ab
+
+use crate::optimizer::profiling::ProfilingCollector;
+use crate::profiler::{ProfileMap, RawProfile};
+use serde::{Deserialize, Serialize};
+
+/// Messwerte einer Velin-Funktion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionProfile {
+    pub name: String,
+    pub file: String,
+    pub line: usize,
+    pub calls: u64,
+    /// Inklusive Zeit in ms
+    pub total_ms: f64,
+    /// Exklusive Zeit in ms
+    pub self_ms: f64,
+    /// Anteil der exklusiven Zeit an der Gesamtlaufzeit
+    pub percentage: f64,
+    pub alloc_bytes: u64,
+    pub allocations: u64,
+}
+
+impl FunctionProfile {
+    pub fn avg_ms(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.total_ms / self.calls as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileReport {
+    pub total_ms: f64,
+    pub peak_bytes: u64,
+    /// Nach exklusiver Zeit absteigend sortiert
+    pub functions: Vec<FunctionProfile>,
+    /// Gefaltete Stacks (`main;load;parse`) mit exklusiver Zeit in µs
+    pub stacks: Vec<(String, u64)>,
+}
+
+impl ProfileReport {
+    pub fn from_raw(map: &ProfileMap, raw: &RawProfile) -> Self {
+        let total_ms = raw.total_ns as f64 / 1_000_000.0;
+        let mut functions: Vec<FunctionProfile> = map
+            .functions
+            .iter()
+            .filter_map(|function| {
+                let stats = raw.functions.get(function.id)?;
+                if stats.calls == 0 {
+                    return None;
+                }
+                let self_ms = stats.self_ns as f64 / 1_000_000.0;
+                Some(FunctionProfile {
+                    name: function.name.clone(),
+                    file: function.file.clone(),
+                    line: function.line,
+                    calls: stats.calls,
+                    total_ms: stats.total_ns as f64 / 1_000_000.0,
+                    self_ms,
+                    percentage: if total_ms > 0.0 {
+                        self_ms / total_ms * 100.0
+                    } else {
+                        0.0
+                    },
+                    alloc_bytes: stats.alloc_bytes,
+                    allocations: stats.allocations,
+                })
+            })
+            .collect();
+        functions.sort_by(|a, b| {
+            b.self_ms
+                .partial_cmp(&a.self_ms)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let mut stacks: Vec<(String, u64)> = raw
+            .stacks
+            .iter()
+            .map(|stack| {
+                let frames: Vec<String> = stack
+                    .frames
+                    .iter()
+                    .map(|id| {
+                        map.function(*id)
+                            .map(|f| f.name.replace(';', "_"))
+                            .unwrap_or_else(|| format!("<{}>", id))
+                    })
+                    .collect();
+                (frames.join(";"), stack.self_ns / 1_000)
+            })
+            .filter(|(_, micros)| *micros > 0)
+            .collect();
+        stacks.sort();
+
+        Self {
+            total_ms,
+            peak_bytes: raw.peak_bytes,
+            functions,
+            stacks,
+        }
+    }
+
+    /// Gefaltete Stacks im Format von `flamegraph.pl` / inferno
+    pub fn to_folded(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|(stack, micros)| format!("{} {}\n", stack, micros))
+            .collect()
+    }
+
+    /// Übergibt die Messwerte an den `ProfilingCollector` für profilgesteuerte Optimierung.
+    /// Es zählt nur der letzte Lauf, frühere Messwerte werden verworfen.
+    pub fn feed(&self, collector: &mut ProfilingCollector) {
+        collector.reset();
+        for function in &self.functions {
+            collector.record_function_stats(
+                function.name.clone(),
+                function.calls,
+                function.total_ms / 1000.0,
+            );
+        }
+        collector.record_memory_sample(self.peak_bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::profiling::CompiledProgram;
+    use crate::profiler::{ProfiledFunction, RawFunctionStats, RawStack};
+
+    #[test]
+    fn test_report_maps_stacks_to_velin_functions() {
+        let map = ProfileMap {
+            functions: vec![
+                ProfiledFunction {
+                    id: 0,
+                    name: "main".to_string(),
+                    file: "app.velin".to_string(),
+                    line: 5,
+                },
+                ProfiledFunction {
+                    id: 1,
+                    name: "helper".to_string(),
+                    file: "app.velin".to_string(),
+                    line: 1,
+                },
+            ],
+        };
+        let raw = RawProfile {
+            total_ns: 10_000_000,
+            peak_bytes: 4096,
+            functions: vec![
+                RawFunctionStats {
+                    calls: 1,
+                    total_ns: 10_000_000,
+                    self_ns: 2_000_000,
+                    alloc_bytes: 64,
+                    allocations: 1,
+                },
+                RawFunctionStats {
+                    calls: 4,
+                    total_ns: 8_000_000,
+                    self_ns: 8_000_000,
+                    alloc_bytes: 1024,
+                    allocations: 8,
+                },
+            ],
+            stacks: vec![
+                RawStack {
+                    frames: vec![0],
+                    self_ns: 2_000_000,
+                },
+                RawStack {
+                    frames: vec![0, 1],
+                    self_ns: 8_000_000,
+                },
+            ],
+        };
+
+        let report = ProfileReport::from_raw(&map, &raw);
+        assert_eq!(report.functions[0].name, "helper");
+        assert_eq!(report.functions[0].percentage, 80.0);
+        assert_eq!(report.functions[0].avg_ms(), 2.0);
+        assert_eq!(report.to_folded(), "main 2000\nmain;helper 8000\n");
+
+        let mut collector = ProfilingCollector::new();
+        report.feed(&mut collector);
+        let data = collector
+            .collect(&CompiledProgram {
+                name: "app".to_string(),
+            })
+            .unwrap();
+        assert_eq!(data.function_calls["helper"].count, 4);
+        assert_eq!(data.memory_usage, 4096);
+
+        // Ein zweiter Lauf ersetzt den ersten statt ihn aufzusummieren
+        collector.record_function_call("codegen_total".to_string(), 0.5);
+        report.feed(&mut collector);
+        let data = collector
+            .collect(&CompiledProgram {
+                name: "app".to_string(),
+            })
+            .unwrap();
+        assert_eq!(data.function_calls["helper"].count, 4);
+        assert_eq!(data.function_calls["helper"].total_time, 0.008);
+        assert!(!data.function_calls.contains_key("codegen_total"));
+        assert_eq!(data.memory_usage, 4096);
+    }
+}
@@ -0,0 +1,178 @@
+// Profiling-Runner
+// Kompiliert ein Programm instrumentiert, führt es aus und liest die Messdaten
+
+use crate::codegen::TargetLanguage;
+use crate::compiler::{config::CompilerConfig, VelinCompiler};
+use crate::coverage::CoverageRuntime;
+use crate::parser::ast::Item;
+use crate::passes::{
+    code_order::CodeOrderingPass, codegen::CodegenPass, desugar::DesugaringPass,
+    parser::ParserPass, profiling::ProfileInstrumentationPass, type_check::TypeCheckPass,
+};
+use crate::profiler::{ProfileMap, ProfileReport, ProfileRuntime, RawProfile, PROFILE_FILE_ENV};
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Ergebnis eines instrumentierten Laufs
+#[derive(Debug, Clone)]
+pub struct ProfileRun {
+    pub map: ProfileMap,
+    pub raw: RawProfile,
+    pub report: ProfileReport,
+    pub success: bool,
+    pub output: String,
+}
+
+pub struct ProfileRunner {
+    work_dir: PathBuf,
+    args: Vec<String>,
+}
+
+impl ProfileRunner {
+    /// `work_dir` nimmt das generierte Crate auf (z.B. `target/velin-profile`)
+    pub fn new(work_dir: PathBuf) -> Self {
+        Self {
+            work_dir,
+            args: Vec::new(),
+        }
+    }
+
+    /// Argumente, die an das profilierte Programm weitergereicht werden
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    pub fn run_file(&self, file: &Path) -> Result<ProfileRun> {
+        let source = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read file: {}", file.display()))?;
+        let root_file = file.to_string_lossy().to_string();
+
+        // 1. Normale Prüfung, damit Typfehler auf den Originalcode zeigen
+        let mut checker = VelinCompiler::new(CompilerConfig::default());
+        checker.add_pass(Box::new(ParserPass::new()));
+        checker.add_pass(Box::new(DesugaringPass::new()));
+        checker.add_pass(Box::new(CodeOrderingPass::new()));
+        checker.add_pass(Box::new(TypeCheckPass::new(true)));
+        let checked = checker.compile(root_file.clone(), source.clone())?;
+        if checked.has_errors() {
+            let messages: Vec<String> = checked.errors.iter().map(|e| e.to_string()).collect();
+            return Err(anyhow!(
+                "Kompilierungsfehler in {}:\n{}",
+                file.display(),
+                messages.join("\n")
+            ));
+        }
+        let has_main = checked.program.as_ref().is_some_and(|program| {
+            program
+                .items
+                .iter()
+                .any(|item| matches!(item, Item::Function(f) if f.name == "main"))
+        });
+        if !has_main {
+            return Err(anyhow!(
+                "{} enthält keine main()-Funktion als Einstiegspunkt",
+                file.display()
+            ));
+        }
+
+        // 2. Instrumentierter Build in ein eigenes Crate
+        let crate_name = profile_crate_name(file);
+        let crate_dir = self.work_dir.join(&crate_name);
+        let src_dir = crate_dir.join("src");
+        fs::create_dir_all(&src_dir)
+            .with_context(|| format!("Failed to create {}", src_dir.display()))?;
+        let program_path = src_dir.join("program.rs");
+
+        let mut compiler = VelinCompiler::new(CompilerConfig::default());
+        compiler.add_pass(Box::new(ParserPass::new()));
+        compiler.add_pass(Box::new(ProfileInstrumentationPass::new()));
+        compiler.add_pass(Box::new(DesugaringPass::new()));
+        compiler.add_pass(Box::new(CodeOrderingPass::new()));
+        // Direkter AST-Codegen: die Guards bleiben als eigene Statements erhalten
+        compiler.add_pass(Box::new(
+            CodegenPass::new(
+                Some(program_path.clone()),
+                false,
+                TargetLanguage::Rust,
+                None,
+            )
+            .with_ir(false),
+        ));
+        let context = compiler.compile(root_file, source)?;
+        if context.has_errors() {
+            let messages: Vec<String> = context.errors.iter().map(|e| e.to_string()).collect();
+            return Err(anyhow!(
+                "Instrumentierung fehlgeschlagen für {}:\n{}",
+                file.display(),
+                messages.join("\n")
+            ));
+        }
+
+        let map = context.profile_map;
+        let program_code = fs::read_to_string(&program_path)
+            .with_context(|| format!("Failed to read {}", program_path.display()))?;
+        let main_code = format!(
+            "{}\n{}",
+            program_code,
+            ProfileRuntime::generate_runtime_code(map.next_id())
+        );
+        fs::write(src_dir.join("main.rs"), main_code)?;
+        fs::remove_file(&program_path).ok();
+        fs::write(
+            crate_dir.join("Cargo.toml"),
+            CoverageRuntime::generate_harness_manifest(&crate_name),
+        )?;
+
+        // 3. Im Release-Profil ausführen, damit die Zeiten realistisch sind
+        let profile_path = crate_dir.join("profile-data.json");
+        fs::remove_file(&profile_path).ok();
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--release", "--manifest-path"])
+            .arg(crate_dir.join("Cargo.toml"))
+            .arg("--")
+            .args(&self.args)
+            .env(PROFILE_FILE_ENV, &profile_path)
+            .output()
+            .context("Failed to run cargo for profiled program")?;
+        let output_text = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let raw: RawProfile = match fs::read_to_string(&profile_path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("Invalid profile data: {}", profile_path.display()))?,
+            Err(_) => {
+                return Err(anyhow!(
+                    "Programm hat keine Profiling-Daten geschrieben:\n{}",
+                    output_text
+                ))
+            }
+        };
+
+        Ok(ProfileRun {
+            report: ProfileReport::from_raw(&map, &raw),
+            map,
+            raw,
+            success: output.status.success(),
+            output: output_text,
+        })
+    }
+}
+
+fn profile_crate_name(file: &Path) -> String {
+    let stem = file
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("program")
+        .to_lowercase();
+    let sanitized: String = stem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("velin_prof_{}", sanitized)
+}
@@ -0,0 +1,216 @@
+// Profiling-Laufzeit
+// Rust-Code, der instrumentierten Programmen angehängt wird
+
+use crate::profiler::{ENTER_FUNCTION, PROFILE_FILE_ENV, SESSION_FUNCTION};
+
+pub struct ProfileRuntime;
+
+/// Laufzeit-Vorlage; Platzhalter werden in `generate_runtime_code` ersetzt
+/// (kein `format!`, damit die Klammern des Rust-Codes lesbar bleiben)
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Profiling Runtime ---
+mod __velin_profile {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::sync::{Mutex, OnceLock};
+    use std::time::Instant;
+
+    pub const FUNCTION_COUNT: usize = __FUNCTION_COUNT__;
+    const NONE: usize = usize::MAX;
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+
+    static CALLS: [AtomicU64; FUNCTION_COUNT] = [ZERO; FUNCTION_COUNT];
+    static TOTAL_NS: [AtomicU64; FUNCTION_COUNT] = [ZERO; FUNCTION_COUNT];
+    static SELF_NS: [AtomicU64; FUNCTION_COUNT] = [ZERO; FUNCTION_COUNT];
+    static ALLOC_BYTES: [AtomicU64; FUNCTION_COUNT] = [ZERO; FUNCTION_COUNT];
+    static ALLOCATIONS: [AtomicU64; FUNCTION_COUNT] = [ZERO; FUNCTION_COUNT];
+    static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static STACKS: Mutex<Option<HashMap<Vec<usize>, u64>>> = Mutex::new(None);
+    static START: OnceLock<Instant> = OnceLock::new();
+
+    struct Frame {
+        id: usize,
+        start: Instant,
+        child_ns: u64,
+    }
+
+    thread_local! {
+        // Funktion, der Allokationen dieses Threads zugerechnet werden
+        static CURRENT: Cell<usize> = const { Cell::new(NONE) };
+        static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub struct VelinProfileAlloc;
+
+    unsafe impl GlobalAlloc for VelinProfileAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                track_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: VelinProfileAlloc = VelinProfileAlloc;
+
+    fn track_alloc(size: usize) {
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+        let current = CURRENT.try_with(|c| c.get()).unwrap_or(NONE);
+        if current < FUNCTION_COUNT {
+            ALLOC_BYTES[current].fetch_add(size as u64, Ordering::Relaxed);
+            ALLOCATIONS[current].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub struct FrameGuard {
+        depth: usize,
+    }
+
+    pub fn enter(id: usize) -> FrameGuard {
+        START.get_or_init(Instant::now);
+        // Buchhaltung der Laufzeit wird keiner Velin-Funktion zugerechnet
+        CURRENT.with(|c| c.set(NONE));
+        let depth = STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.push(Frame {
+                id,
+                start: Instant::now(),
+                child_ns: 0,
+            });
+            stack.len()
+        });
+        CURRENT.with(|c| c.set(id));
+        FrameGuard { depth }
+    }
+
+    impl Drop for FrameGuard {
+        fn drop(&mut self) {
+            let now = Instant::now();
+            let _ = CURRENT.try_with(|c| c.set(NONE));
+            let _ = STACK.try_with(|stack| {
+                let mut stack = stack.borrow_mut();
+                // Async-Tasks können sich auf einem Thread verschränken:
+                // Frames oberhalb dieses Guards werden mit geschlossen
+                while stack.len() >= self.depth {
+                    let Some(frame) = stack.pop() else { break };
+                    let elapsed = now.duration_since(frame.start).as_nanos() as u64;
+                    let recursive = stack.iter().any(|f| f.id == frame.id);
+                    record(&stack, &frame, elapsed, recursive);
+                    if let Some(parent) = stack.last_mut() {
+                        parent.child_ns += elapsed;
+                    }
+                }
+                let _ = CURRENT.try_with(|c| c.set(stack.last().map(|f| f.id).unwrap_or(NONE)));
+            });
+        }
+    }
+
+    fn record(parents: &[Frame], frame: &Frame, elapsed: u64, recursive: bool) {
+        if frame.id >= FUNCTION_COUNT {
+            return;
+        }
+        let self_ns = elapsed.saturating_sub(frame.child_ns);
+        CALLS[frame.id].fetch_add(1, Ordering::Relaxed);
+        if !recursive {
+            TOTAL_NS[frame.id].fetch_add(elapsed, Ordering::Relaxed);
+        }
+        SELF_NS[frame.id].fetch_add(self_ns, Ordering::Relaxed);
+
+        let mut key: Vec<usize> = parents.iter().map(|f| f.id).collect();
+        key.push(frame.id);
+        if let Ok(mut stacks) = STACKS.lock() {
+            *stacks.get_or_insert_with(HashMap::new).entry(key).or_insert(0) += self_ns;
+        }
+    }
+
+    pub fn flush() {
+        let Ok(path) = std::env::var("__PROFILE_ENV__") else {
+            return;
+        };
+        let total_ns = START
+            .get()
+            .map(|start| start.elapsed().as_nanos() as u64)
+            .unwrap_or(0);
+        let functions: Vec<String> = (0..FUNCTION_COUNT)
+            .map(|i| {
+                format!(
+                    "{{\"calls\":{},\"total_ns\":{},\"self_ns\":{},\"alloc_bytes\":{},\"allocations\":{}}}",
+                    CALLS[i].load(Ordering::Relaxed),
+                    TOTAL_NS[i].load(Ordering::Relaxed),
+                    SELF_NS[i].load(Ordering::Relaxed),
+                    ALLOC_BYTES[i].load(Ordering::Relaxed),
+                    ALLOCATIONS[i].load(Ordering::Relaxed)
+                )
+            })
+            .collect();
+        let stacks: Vec<String> = STACKS
+            .lock()
+            .map(|stacks| {
+                stacks
+                    .iter()
+                    .flatten()
+                    .map(|(frames, self_ns)| {
+                        let frames: Vec<String> = frames.iter().map(|id| id.to_string()).collect();
+                        format!("{{\"frames\":[{}],\"self_ns\":{}}}", frames.join(","), self_ns)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let json = format!(
+            "{{\"total_ns\":{},\"peak_bytes\":{},\"functions\":[{}],\"stacks\":[{}]}}",
+            total_ns,
+            PEAK_BYTES.load(Ordering::Relaxed),
+            functions.join(","),
+            stacks.join(",")
+        );
+        if let Err(e) = std::fs::write(&path, json) {
+            eprintln!("profile: failed to write {}: {}", path, e);
+        }
+    }
+
+    pub struct SessionGuard;
+
+    impl Drop for SessionGuard {
+        fn drop(&mut self) {
+            flush();
+        }
+    }
+}
+
+#[inline]
+pub fn __ENTER_FUNCTION__(id: f64) -> __velin_profile::FrameGuard {
+    __velin_profile::enter(id as usize)
+}
+
+pub fn __SESSION_FUNCTION__() -> __velin_profile::SessionGuard {
+    __velin_profile::SessionGuard
+}
+"#;
+
+impl ProfileRuntime {
+    /// Zeit- und Allokationszähler für `function_count` instrumentierte Funktionen.
+    ///
+    /// Jeder Funktionseintritt legt einen Frame auf einen Thread-lokalen Call-Stack;
+    /// beim Drop des Guards werden inklusive/exklusive Zeit und der gefaltete Stack
+    /// verbucht. Ein `#[global_allocator]` ordnet Allokationen der aktiven Funktion zu.
+    /// Beim Drop des Session-Guards in `main` gehen die Daten als JSON nach
+    /// `VELIN_PROFILE_FILE`.
+    pub fn generate_runtime_code(function_count: usize) -> String {
+        RUNTIME_TEMPLATE
+            .replace("__FUNCTION_COUNT__", &function_count.to_string())
+            .replace("__PROFILE_ENV__", PROFILE_FILE_ENV)
+            .replace("__ENTER_FUNCTION__", ENTER_FUNCTION)
+            .replace("__SESSION_FUNCTION__", SESSION_FUNCTION)
+    }
+}
@@ -1,94 +1,688 @@
+// Audit Standard Library
+// Manipulationssicheres Audit-Log: Einträge bilden eine Hash-Kette (optional
+// HMAC-signiert), werden rotiert und über Indizes nach User, Aktion und
+// Zeitraum abgefragt; `@Audited` protokolliert jeden Aufruf eines Handlers
+
+pub mod store;
+
+use crate::parser::ast::*;
+use crate::parser::visitor::calls_module;
+
 pub struct AuditStdlib;
 
+/// Pfad des generierten Runtime-Moduls
+const RUNTIME: &str = "crate::stdlib::audit";
+
+/// Quelltext von `store.rs`, wird in die Runtime eingebettet
+const STORE_SOURCE: &str = include_str!("audit/store.rs");
+
+/// `true`, wenn das Programm `audit.*` aufruft oder `@Audited` verwendet
+pub fn uses_audit(program: &Program) -> bool {
+    let audited = program.items.iter().any(|item| match item {
+        Item::Function(f) => f.decorators.iter().any(|d| d.name == "Audited"),
+        _ => false,
+    });
+    audited || calls_module(program, "audit")
+}
+
+/// Optionen aus `@Audited("orders.delete", user: "userId")`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditedSpec {
+    /// Standard: `GET /orders/:id` bei Routen, sonst der Funktionsname
+    pub action: String,
+    /// Parameter, dessen Wert als `user_id` protokolliert wird
+    pub user_param: Option<String>,
+}
+
+/// `None` ohne `@Audited`; `Err` mit Begründung bei ungültigen Optionen
+pub fn audited_spec(function: &Function) -> Option<Result<AuditedSpec, String>> {
+    let decorator = function.decorators.iter().find(|d| d.name == "Audited")?;
+    let mut action = None;
+    let mut user_param = None;
+    for arg in &decorator.args {
+        match arg {
+            DecoratorArg::String(value) if action.is_none() => action = Some(value.clone()),
+            DecoratorArg::Named { name, value } if name == "action" => match value.as_ref() {
+                DecoratorArg::String(value) => action = Some(value.clone()),
+                _ => return Some(Err("'action' must be a string".to_string())),
+            },
+            DecoratorArg::Named { name, value } if name == "user" => match value.as_ref() {
+                DecoratorArg::String(param) | DecoratorArg::Identifier(param) => {
+                    user_param = Some(param.clone())
+                }
+                _ => return Some(Err("'user' must name a parameter".to_string())),
+            },
+            _ => {
+                return Some(Err(
+                    "expected an action followed by 'user: \"param\"'".to_string()
+                ))
+            }
+        }
+    }
+
+    let action = action.unwrap_or_else(|| default_action(function));
+    if action.trim().is_empty() {
+        return Some(Err("action must not be empty".to_string()));
+    }
+    if let Some(param) = &user_param {
+        if !function.params.iter().any(|p| &p.name == param) {
+            return Some(Err(format!(
+                "'user' refers to '{}', which is not a parameter of {}",
+                param, function.name
+            )));
+        }
+    }
+    Some(Ok(AuditedSpec { action, user_param }))
+}
+
+fn default_action(function: &Function) -> String {
+    function
+        .decorators
+        .iter()
+        .find_map(|d| match (d.name.as_str(), d.args.first()) {
+            ("GET" | "POST" | "PUT" | "DELETE" | "PATCH", Some(DecoratorArg::String(path))) => {
+                Some(format!("{} {}", d.name, path))
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| function.name.clone())
+}
+
 impl AuditStdlib {
+    /// Runtime-Modul `stdlib::audit` inklusive Speicherformat
+    pub fn generate_audit_runtime_code() -> String {
+        RUNTIME_TEMPLATE.replace("/*STORE*/\n", STORE_SOURCE)
+    }
+
     pub fn generate_log_code(log_data: &str) -> String {
-        format!(
-            "{{
-                use std::fs::OpenOptions;
-                use std::io::Write;
-                let data: serde_json::Value = {};
-                let log_entry = serde_json::json!({{
-                    \"timestamp\": chrono::Utc::now().to_rfc3339(),
-                    \"action\": data.get(\"action\").and_then(|v| v.as_str()).unwrap_or(\"unknown\"),
-                    \"user_id\": data.get(\"user_id\").and_then(|v| v.as_str()),
-                    \"ip_address\": data.get(\"ip_address\").and_then(|v| v.as_str()),
-                    \"metadata\": data.get(\"metadata\")
-                }});
-                let log_file = std::env::var(\"AUDIT_LOG_FILE\").unwrap_or_else(|_| \"audit.log\".to_string());
-                let mut file = OpenOptions::new()
-                    .create(true)
-                    .append(true)
-                    .open(&log_file)
-                    .map_err(|e| e.to_string())?;
-                writeln!(file, \"{{}}\", serde_json::to_string(&log_entry).unwrap_or_default()).map_err(|e| e.to_string())?;
-                Ok(())
-            }}",
-            log_data
-        )
+        format!("{}::log(&{})", RUNTIME, log_data)
     }
 
     pub fn generate_query_code(filters: &str) -> String {
+        format!("{}::query(&{})", RUNTIME, filters)
+    }
+
+    pub fn generate_export_code(format: &str, filters: &str) -> String {
+        format!("{}::export(&{}, &{})", RUNTIME, format, filters)
+    }
+
+    pub fn generate_verify_code() -> String {
+        format!("{}::verify()", RUNTIME)
+    }
+
+    /// Beginnt einen `@Audited`-Aufruf; `user` ist der Rust-Name des User-Parameters
+    pub fn generate_invocation_start_code(
+        spec: &AuditedSpec,
+        function: &str,
+        user: Option<&str>,
+        return_type: &str,
+        is_async: bool,
+    ) -> String {
+        let user = match user {
+            Some(user) => format!("{}::user(&{})", RUNTIME, user),
+            None => "None".to_string(),
+        };
+        let body_start = if is_async { "async move {" } else { "(|| {" };
         format!(
-            "
-            {{
-                use std::io::{{BufRead, BufReader}};
-                use std::fs::OpenOptions;
-                use std::sync::{{Arc, Mutex}};
-                let filters: serde_json::Value = {};
-                let log_file = std::env::var(\"AUDIT_LOG_FILE\").unwrap_or_else(|_| \"audit.log\".to_string());
-                let file = File::open(&log_file).map_err(|e| e.to_string())?;
-                let reader = BufReader::new(file);
-                let mut results = Vec::new();
-                for line in reader.lines() {{
-                    if let Ok(line_str) = line {{
-                        if let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line_str) {{
-                            let mut matches = true;
-                            if let Some(user_id) = filters.get(\"user_id\").and_then(|v| v.as_str()) {{
-                                if entry.get(\"user_id\").and_then(|v| v.as_str()) != Some(user_id) {{
-                                    matches = false;
-                                }}
-                            }}
-                            if let Some(action) = filters.get(\"action\").and_then(|v| v.as_str()) {{
-                                if entry.get(\"action\").and_then(|v| v.as_str()) != Some(action) {{
-                                    matches = false;
-                                }}
-                            }}
-                            if matches {{
-                                results.push(entry);
-                            }}
-                        }}
-                    }}
-                }}
-                Ok(results)
-            }}",
-            filters
+            "let __audit = {}::Invocation::start({:?}, {:?}, {});\nlet __audited: {} = {}",
+            RUNTIME, spec.action, function, user, return_type, body_start
         )
     }
 
-    pub fn generate_export_code(format: &str, filters: &str) -> String {
+    /// Schließt den Rumpf und schreibt den Eintrag; ein `Err` landet als Fehler im Log
+    pub fn generate_invocation_end_code(is_async: bool) -> String {
         format!(
-            "{{
-                let format_str = {};
-                let logs = audit.query({}).await?;
-                match format_str.as_str() {{
-                    \"csv\" => {{
-                        let mut csv = String::new();
-                        csv.push_str(\"timestamp,action,user_id,ip_address\\n\");
-                        for log in logs {{
-                            csv.push_str(&format!(\"{{}},{{}},{{}},{{}}\\n\",
-                                log.get(\"timestamp\").and_then(|v| v.as_str()).unwrap_or(\"\"),
-                                log.get(\"action\").and_then(|v| v.as_str()).unwrap_or(\"\"),
-                                log.get(\"user_id\").and_then(|v| v.as_str()).unwrap_or(\"\"),
-                                log.get(\"ip_address\").and_then(|v| v.as_str()).unwrap_or(\"\")
-                            ));
-                        }}
-                        Ok(csv)
-                    }},
-                    \"json\" => Ok(serde_json::to_string(&logs).unwrap_or_default()),
-                    _ => Err(\"Unsupported format\".to_string())
-                }}
-            }}",
-            format, filters
+            "{}\n{{\n    #[allow(unused_imports)]\n    use {}::{{ErrorStatus as _, NoStatus as _}};\n    __audit.finish((&__audited).error_message());\n}}\n__audited",
+            if is_async { "}.await;" } else { "})();" },
+            RUNTIME
         )
     }
 }
+
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Audit Runtime ---
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Instant;
+
+pub mod store {
+/*STORE*/
+}
+
+use store::{Entry, Head};
+
+/// Größe, ab der die aktive Datei rotiert wird, falls `AUDIT_MAX_BYTES` fehlt
+const MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+struct Location {
+    segment: usize,
+    offset: u64,
+    len: usize,
+}
+
+/// Positionen der Einträge, nach User, Aktion und Zeitpunkt
+#[derive(Default)]
+struct Index {
+    locations: Vec<Location>,
+    by_user: HashMap<String, Vec<usize>>,
+    by_action: HashMap<String, Vec<usize>>,
+    by_time: BTreeMap<i64, Vec<usize>>,
+}
+
+impl Index {
+    fn add(&mut self, entry: &Entry, location: Location) {
+        let position = self.locations.len();
+        self.locations.push(location);
+        if let Some(user) = &entry.user_id {
+            self.by_user.entry(user.clone()).or_default().push(position);
+        }
+        self.by_action.entry(entry.action.clone()).or_default().push(position);
+        if let Some(time) = millis(&entry.timestamp) {
+            self.by_time.entry(time).or_default().push(position);
+        }
+    }
+}
+
+fn millis(timestamp: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|time| time.timestamp_millis())
+}
+
+struct Store {
+    path: PathBuf,
+    key: Option<Vec<u8>>,
+    max_bytes: u64,
+    /// Rotierte Segmente, zuletzt die aktive Datei
+    segments: Vec<PathBuf>,
+    active_len: u64,
+    last: Option<(u64, String)>,
+    index: Index,
+}
+
+impl Store {
+    /// Liest vorhandene Segmente ein und baut den Index auf
+    fn open() -> Store {
+        let path = PathBuf::from(
+            std::env::var("AUDIT_LOG_FILE").unwrap_or_else(|_| "audit.log".to_string()),
+        );
+        let key = std::env::var("AUDIT_HMAC_KEY")
+            .ok()
+            .filter(|key| !key.is_empty())
+            .map(String::into_bytes);
+        let max_bytes = std::env::var("AUDIT_MAX_BYTES")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(MAX_BYTES);
+
+        let segments = store::segments(&path);
+        let mut index = Index::default();
+        let mut last = None;
+        for (segment, segment_path) in segments.iter().enumerate() {
+            let _ = store::read_segment(segment_path, &mut |offset, len, _, entry| {
+                if let Ok(entry) = entry {
+                    index.add(&entry, Location { segment, offset, len });
+                    last = Some((entry.seq, entry.hash));
+                }
+            });
+        }
+        let active_len = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Store { path, key, max_bytes, segments, active_len, last, index }
+    }
+
+    fn append(
+        &mut self,
+        action: String,
+        user_id: Option<String>,
+        ip_address: Option<String>,
+        metadata: serde_json::Value,
+    ) -> Result<Entry, String> {
+        if self.active_len > 0 && self.active_len >= self.max_bytes {
+            self.rotate()?;
+        }
+        let (seq, prev) = match &self.last {
+            Some((seq, hash)) => (seq + 1, hash.clone()),
+            None => (0, store::GENESIS.to_string()),
+        };
+        let entry = Entry {
+            seq,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            action,
+            user_id,
+            ip_address,
+            metadata,
+            prev,
+            hash: String::new(),
+            sig: None,
+        }
+        .seal(self.key.as_deref());
+
+        let mut line = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| {
+                file.write_all(line.as_bytes())?;
+                file.sync_data()
+            })
+            .map_err(|e| format!("cannot write audit log {}: {}", self.path.display(), e))?;
+
+        let location = Location {
+            segment: self.segments.len() - 1,
+            offset: self.active_len,
+            len: line.len(),
+        };
+        self.active_len += line.len() as u64;
+        self.index.add(&entry, location);
+        self.last = Some((entry.seq, entry.hash.clone()));
+        store::write_head(&self.path, &Head::new(entry.seq, &entry.hash, self.key.as_deref()))?;
+        Ok(entry)
+    }
+
+    /// Legt die aktive Datei als nächstes Segment ab; die Kette läuft weiter
+    fn rotate(&mut self) -> Result<(), String> {
+        let target = store::next_rotation(&self.path);
+        std::fs::rename(&self.path, &target)
+            .map_err(|e| format!("cannot rotate audit log {}: {}", self.path.display(), e))?;
+        let active = self.segments.len() - 1;
+        self.segments[active] = target;
+        self.segments.push(self.path.clone());
+        self.active_len = 0;
+        Ok(())
+    }
+
+    fn read(&self, files: &mut HashMap<usize, File>, position: usize) -> Result<Entry, String> {
+        let location = &self.index.locations[position];
+        let segment = &self.segments[location.segment];
+        let file = match files.entry(location.segment) {
+            std::collections::hash_map::Entry::Occupied(file) => file.into_mut(),
+            std::collections::hash_map::Entry::Vacant(slot) => slot.insert(
+                File::open(segment).map_err(|e| format!("{}: {}", segment.display(), e))?,
+            ),
+        };
+        let mut line = vec![0u8; location.len];
+        file.seek(SeekFrom::Start(location.offset))
+            .and_then(|_| file.read_exact(&mut line))
+            .map_err(|e| format!("{}: {}", segment.display(), e))?;
+        serde_json::from_slice(&line)
+            .map_err(|e| format!("{}: damaged entry at byte {}: {}", segment.display(), location.offset, e))
+    }
+}
+
+fn store() -> MutexGuard<'static, Store> {
+    static STORE: OnceLock<Mutex<Store>> = OnceLock::new();
+    STORE
+        .get_or_init(|| Mutex::new(Store::open()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn text(data: &serde_json::Value, key: &str) -> Option<String> {
+    match data.get(key)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(value) => Some(value.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// `audit.log`: `action`, `user_id`, `ip_address` und `metadata` aus `data`
+pub fn log<T: serde::Serialize>(data: &T) -> Result<(), String> {
+    let data = serde_json::to_value(data).map_err(|e| e.to_string())?;
+    store()
+        .append(
+            text(&data, "action").unwrap_or_else(|| "unknown".to_string()),
+            text(&data, "user_id"),
+            text(&data, "ip_address"),
+            data.get("metadata").cloned().unwrap_or(serde_json::Value::Null),
+        )
+        .map(|_| ())
+}
+
+fn time_filter(filters: &serde_json::Value, key: &str) -> Result<Option<i64>, String> {
+    match text(filters, key) {
+        None => Ok(None),
+        Some(value) => millis(&value)
+            .map(Some)
+            .ok_or_else(|| format!("invalid '{}' filter '{}', expected an RFC 3339 timestamp", key, value)),
+    }
+}
+
+/// Schnittmenge zweier aufsteigend sortierter Positionslisten
+fn intersect(left: &[usize], right: &[usize]) -> Vec<usize> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(&right[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                result.push(left[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+/// `audit.query`: Filter `user_id`, `action`, `from`, `to` (RFC 3339) und
+/// `limit`; Ergebnis in Log-Reihenfolge
+pub fn query<T: serde::Serialize>(filters: &T) -> Result<Vec<serde_json::Value>, String> {
+    let filters = serde_json::to_value(filters).map_err(|e| e.to_string())?;
+    let from = time_filter(&filters, "from")?;
+    let to = time_filter(&filters, "to")?;
+    let limit = filters
+        .get("limit")
+        .and_then(|v| v.as_f64())
+        .map(|limit| limit.max(0.0) as usize)
+        .unwrap_or(usize::MAX);
+
+    let store = store();
+    let index = &store.index;
+    let mut candidates: Option<Vec<usize>> = None;
+    let mut narrow = |positions: &[usize]| {
+        candidates = Some(match candidates.take() {
+            None => positions.to_vec(),
+            Some(current) => intersect(&current, positions),
+        });
+    };
+    if let Some(user) = text(&filters, "user_id") {
+        narrow(index.by_user.get(&user).map(Vec::as_slice).unwrap_or_default());
+    }
+    if let Some(action) = text(&filters, "action") {
+        narrow(index.by_action.get(&action).map(Vec::as_slice).unwrap_or_default());
+    }
+    if from.is_some() || to.is_some() {
+        let (from, to) = (from.unwrap_or(i64::MIN), to.unwrap_or(i64::MAX));
+        let mut positions: Vec<usize> = if from > to {
+            Vec::new()
+        } else {
+            index.by_time.range(from..=to).flat_map(|(_, p)| p.iter().copied()).collect()
+        };
+        positions.sort_unstable();
+        narrow(&positions);
+    }
+
+    let positions = candidates.unwrap_or_else(|| (0..index.locations.len()).collect());
+    let mut files = HashMap::new();
+    positions
+        .into_iter()
+        .take(limit)
+        .map(|position| {
+            let entry = store.read(&mut files, position)?;
+            serde_json::to_value(entry).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// `audit.export`: Abfrage als `csv` oder `json`
+pub fn export<F: AsRef<str>, T: serde::Serialize>(format: &F, filters: &T) -> Result<String, String> {
+    let entries = query(filters)?;
+    match format.as_ref() {
+        "csv" => {
+            let mut csv = String::from("seq,timestamp,action,user_id,ip_address,hash\n");
+            for entry in &entries {
+                let row: Vec<String> = ["seq", "timestamp", "action", "user_id", "ip_address", "hash"]
+                    .iter()
+                    .map(|key| csv_field(&text(entry, key).unwrap_or_default()))
+                    .collect();
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+            Ok(csv)
+        }
+        "json" => serde_json::to_string(&entries).map_err(|e| e.to_string()),
+        other => Err(format!("unsupported audit export format '{}', use csv or json", other)),
+    }
+}
+
+/// `audit.verify`: Anzahl der geprüften Einträge oder alle gefundenen Probleme
+pub fn verify() -> Result<u64, String> {
+    let store = store();
+    let report = store::verify(&store.path, store.key.as_deref());
+    if report.problems.is_empty() {
+        Ok(report.entries)
+    } else {
+        Err(report.problems.join("\n"))
+    }
+}
+
+/// Wert des `user`-Parameters von `@Audited` als Text
+pub fn user<T: serde::Serialize>(value: &T) -> Option<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(value)) => Some(value),
+        Ok(serde_json::Value::Null) | Err(_) => None,
+        Ok(other) => Some(other.to_string()),
+    }
+}
+
+/// Ein laufender Aufruf eines `@Audited`-Handlers
+pub struct Invocation {
+    action: &'static str,
+    function: &'static str,
+    user_id: Option<String>,
+    started: Instant,
+}
+
+impl Invocation {
+    pub fn start(action: &'static str, function: &'static str, user_id: Option<String>) -> Invocation {
+        Invocation { action, function, user_id, started: Instant::now() }
+    }
+
+    /// Schreibt den Eintrag; scheitert das, bleibt die Antwort des Handlers unberührt
+    pub fn finish(self, error: Option<String>) {
+        let mut metadata = serde_json::json!({
+            "function": self.function,
+            "outcome": if error.is_some() { "error" } else { "ok" },
+            "duration_ms": self.started.elapsed().as_millis() as u64,
+        });
+        if let Some(error) = error {
+            metadata["error"] = serde_json::json!(error);
+        }
+        if let Err(error) = store().append(self.action.to_string(), self.user_id, None, metadata) {
+            eprintln!("audit: {}", error);
+        }
+    }
+}
+
+/// Fehlertext eines `Result`; alle anderen Werte liefern über `NoStatus` `None`
+pub trait ErrorStatus {
+    fn error_message(&self) -> Option<String>;
+}
+
+impl<T, E: std::fmt::Display> ErrorStatus for Result<T, E> {
+    fn error_message(&self) -> Option<String> {
+        self.as_ref().err().map(|e| e.to_string())
+    }
+}
+
+pub trait NoStatus {
+    fn error_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T> NoStatus for &T {}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::store::{self, Entry, Head};
+    use super::*;
+    use crate::parser::parser::Parser;
+    use std::path::Path;
+
+    fn append(path: &Path, key: Option<&[u8]>, seq: u64, prev: &str, action: &str) -> Entry {
+        let entry = Entry {
+            seq,
+            timestamp: format!("2026-10-19T10:00:0{}+00:00", seq),
+            action: action.to_string(),
+            user_id: Some("u1".to_string()),
+            ip_address: None,
+            metadata: serde_json::json!({ "b": 1, "a": [true, null] }),
+            prev: prev.to_string(),
+            hash: String::new(),
+            sig: None,
+        }
+        .seal(key);
+        let mut text = std::fs::read_to_string(path).unwrap_or_default();
+        text.push_str(&serde_json::to_string(&entry).unwrap());
+        text.push('\n');
+        std::fs::write(path, text).unwrap();
+        store::write_head(path, &Head::new(entry.seq, &entry.hash, key)).unwrap();
+        entry
+    }
+
+    #[test]
+    fn test_audit_chain_detects_tampering_and_truncation() {
+        // RFC 4231, Testfall 2
+        let mac = store::hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let mac: String = mac.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            mac,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        let dir = std::env::temp_dir().join(format!("velin-audit-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.log");
+        let key = Some(b"secret".as_slice());
+
+        let first = append(&path, key, 0, store::GENESIS, "login");
+        std::fs::rename(&path, store::next_rotation(&path)).unwrap();
+        let second = append(&path, key, 1, &first.hash, "order.read");
+        append(&path, key, 2, &second.hash, "logout");
+
+        let report = store::verify(&path, key);
+        assert!(report.problems.is_empty(), "{:?}", report.problems);
+        assert_eq!((report.entries, report.segments, report.signed), (3, 2, 3));
+        assert!(store::next_rotation(&path).ends_with("audit.log.000002"));
+        assert_eq!(store::verify(&path, None).unchecked, 3);
+        assert!(store::verify(&path, Some(b"other".as_slice())).problems[0]
+            .ends_with("seq 0 has an invalid signature"));
+
+        let original = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, original.replace("order.read", "order.write")).unwrap();
+        let problems = store::verify(&path, key).problems;
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert!(problems[0].ends_with(":1: seq 1 was modified, its hash does not match"));
+
+        let first_line = original.lines().next().unwrap();
+        std::fs::write(&path, format!("{}\n", first_line)).unwrap();
+        let problems = store::verify(&path, key).problems;
+        assert_eq!(
+            problems,
+            vec!["the log ends at seq 1 but the head records seq 2, entries were truncated"]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_entries_hash_canonically_and_interrupted_writes_are_reported() {
+        let entry = |metadata: serde_json::Value| {
+            Entry {
+                seq: 0,
+                timestamp: "2026-10-19T10:00:00+00:00".to_string(),
+                action: "login".to_string(),
+                user_id: None,
+                ip_address: Some("10.0.0.1".to_string()),
+                metadata,
+                prev: store::GENESIS.to_string(),
+                hash: String::new(),
+                sig: None,
+            }
+            .seal(None)
+        };
+        // Die Schlüsselreihenfolge der Metadaten ändert den Hash nicht, der Inhalt schon
+        let sealed = entry(serde_json::json!({ "b": 1, "a": { "y": 2, "x": 3 } }));
+        assert_eq!(
+            sealed.hash,
+            entry(serde_json::json!({ "a": { "x": 3, "y": 2 }, "b": 1 })).hash
+        );
+        assert_ne!(
+            sealed.hash,
+            entry(serde_json::json!({ "a": 1, "b": 1 })).hash
+        );
+        assert_eq!(sealed.hash, sealed.digest());
+
+        let dir = std::env::temp_dir().join(format!("velin-audit-segment-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.log");
+        let line = serde_json::to_string(&sealed).unwrap();
+        std::fs::write(
+            &path,
+            format!("{}\n\n{{\"seq\": 1}}\n{}", line, &line[..20]),
+        )
+        .unwrap();
+
+        let mut read = Vec::new();
+        store::read_segment(&path, &mut |offset, len, number, entry| {
+            read.push((offset, len, number, entry.map(|e| e.seq)));
+        })
+        .unwrap();
+        // Zeile 2 ist leer, Zeile 3 kein Eintrag, Zeile 4 ohne Zeilenende
+        let (first, blank, foreign) = (line.len() + 1, 1, "{\"seq\": 1}\n".len());
+        let not_an_entry = "not a chained audit entry".to_string();
+        let incomplete = "incomplete entry, the write was interrupted".to_string();
+        assert_eq!(
+            read,
+            [
+                (0, first, 1, Ok(0)),
+                ((first + blank) as u64, foreign, 3, Err(not_an_entry)),
+                ((first + blank + foreign) as u64, 20, 4, Err(incomplete)),
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_audited_spec_names_action_and_user() {
+        let source = r#"@DELETE("/orders/:id")
+@Audited(user: "userId")
+fn deleteOrder(id: string, userId: string) {
+}
+
+@Audited("reports.build", user: "owner")
+fn buildReport(id: string) {
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        let functions: Vec<&Function> = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            audited_spec(functions[0]),
+            Some(Ok(AuditedSpec {
+                action: "DELETE /orders/:id".to_string(),
+                user_param: Some("userId".to_string()),
+            }))
+        );
+        assert_eq!(
+            audited_spec(functions[1]),
+            Some(Err(
+                "'user' refers to 'owner', which is not a parameter of buildReport".to_string()
+            ))
+        );
+        assert!(uses_audit(&program));
+    }
+}
@@ -0,0 +1,344 @@
+// Manipulationssicherer Audit-Speicher: JSON-Zeilen mit Hash-Kette, optional
+// HMAC-signiert, aufgeteilt in rotierte Segmente.
+// Die Datei wird unverändert in das generierte Modul `stdlib::audit`
+// eingebettet; `velin audit verify` prüft Logs mit demselben Code.
+
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// `prev` des ersten Eintrags
+pub const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Ein Eintrag, `hash` verkettet ihn über `prev` mit seinem Vorgänger
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Entry {
+    pub seq: u64,
+    pub timestamp: String,
+    pub action: String,
+    pub user_id: Option<String>,
+    pub ip_address: Option<String>,
+    #[serde(default)]
+    pub metadata: serde_json::Value,
+    pub prev: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+}
+
+impl Entry {
+    /// Berechnet `hash` und mit Schlüssel die Signatur `sig`
+    pub fn seal(mut self, key: Option<&[u8]>) -> Entry {
+        self.hash = self.digest();
+        self.sig = key.map(|key| sign(key, &self.hash));
+        self
+    }
+
+    /// SHA-256 über alle Felder außer `hash` und `sig`, Objektschlüssel sortiert
+    pub fn digest(&self) -> String {
+        let fields = serde_json::json!([
+            self.seq,
+            self.timestamp,
+            self.action,
+            self.user_id,
+            self.ip_address,
+            self.metadata,
+            self.prev
+        ]);
+        let mut canonical = String::new();
+        write_canonical(&fields, &mut canonical);
+        hex(&Sha256::digest(canonical.as_bytes()))
+    }
+}
+
+/// Letzter Eintrag, wie ihn der Schreiber zuletzt gesehen hat; deckt
+/// abgeschnittene Logs auf
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Head {
+    pub seq: u64,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig: Option<String>,
+}
+
+impl Head {
+    pub fn new(seq: u64, hash: &str, key: Option<&[u8]>) -> Head {
+        Head {
+            seq,
+            hash: hash.to_string(),
+            sig: key.map(|key| sign_head(key, seq, hash)),
+        }
+    }
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > 64 {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+pub fn sign(key: &[u8], hash: &str) -> String {
+    hex(&hmac_sha256(key, hash.as_bytes()))
+}
+
+/// Eigener Kontext, damit sich die Signatur eines Eintrags nicht als Head ausgeben lässt
+fn sign_head(key: &[u8], seq: u64, hash: &str) -> String {
+    hex(&hmac_sha256(
+        key,
+        format!("head:{}:{}", seq, hash).as_bytes(),
+    ))
+}
+
+pub fn head_path(path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.head", path.display()))
+}
+
+pub fn read_head(path: &Path) -> Result<Option<Head>, String> {
+    let head_path = head_path(path);
+    match std::fs::read_to_string(&head_path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| format!("{}: invalid head file: {}", head_path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {}", head_path.display(), e)),
+    }
+}
+
+/// Schreibt den Head atomar über eine temporäre Datei
+pub fn write_head(path: &Path, head: &Head) -> Result<(), String> {
+    let head_path = head_path(path);
+    let temp = PathBuf::from(format!("{}.tmp", head_path.display()));
+    let text = serde_json::to_string(head).map_err(|e| e.to_string())?;
+    std::fs::write(&temp, text)
+        .and_then(|_| std::fs::rename(&temp, &head_path))
+        .map_err(|e| format!("{}: {}", head_path.display(), e))
+}
+
+fn rotated(path: &Path) -> Vec<(u64, PathBuf)> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!(
+        "{}.",
+        path.file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+    );
+    let mut segments: Vec<(u64, PathBuf)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let number = name.strip_prefix(&prefix)?.parse::<u64>().ok()?;
+            Some((number, entry.path()))
+        })
+        .collect();
+    segments.sort();
+    segments
+}
+
+/// Rotierte Segmente (`audit.log.000001`, ...) in Reihenfolge, zuletzt die aktive Datei
+pub fn segments(path: &Path) -> Vec<PathBuf> {
+    let mut segments: Vec<PathBuf> = rotated(path).into_iter().map(|(_, p)| p).collect();
+    segments.push(path.to_path_buf());
+    segments
+}
+
+/// Name, unter dem die aktive Datei beim nächsten Rotieren abgelegt wird
+pub fn next_rotation(path: &Path) -> PathBuf {
+    let number = rotated(path).last().map(|(n, _)| n + 1).unwrap_or(1);
+    PathBuf::from(format!("{}.{:06}", path.display(), number))
+}
+
+/// Liest die Einträge eines Segments mit Byte-Offset, Länge und Zeilennummer
+pub fn read_segment(
+    segment: &Path,
+    visit: &mut dyn FnMut(u64, usize, usize, Result<Entry, String>),
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(File::open(segment)?);
+    let mut line = Vec::new();
+    let mut offset = 0u64;
+    let mut number = 0;
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        number += 1;
+        let start = offset;
+        offset += read as u64;
+        let text = String::from_utf8_lossy(&line);
+        if text.trim().is_empty() {
+            continue;
+        }
+        let entry = if line.last() != Some(&b'\n') {
+            Err("incomplete entry, the write was interrupted".to_string())
+        } else {
+            serde_json::from_str::<Entry>(text.trim_end())
+                .map_err(|_| "not a chained audit entry".to_string())
+        };
+        visit(start, read, number, entry);
+    }
+}
+
+/// Ergebnis von `verify`; ohne `problems` ist das Log unverändert
+#[derive(Debug, Default)]
+pub struct Report {
+    pub entries: u64,
+    pub segments: usize,
+    /// Mit dem Schlüssel geprüfte Signaturen
+    pub signed: u64,
+    /// Signaturen, die ohne Schlüssel nicht geprüft werden konnten
+    pub unchecked: u64,
+    pub problems: Vec<String>,
+}
+
+/// Prüft Kette, Signaturen und Head über alle Segmente von `path`
+pub fn verify(path: &Path, key: Option<&[u8]>) -> Report {
+    let mut report = Report::default();
+    let mut previous: Option<(u64, String)> = None;
+
+    for segment in segments(path) {
+        let mut visit = |_offset: u64, _len: usize, number: usize, entry: Result<Entry, String>| {
+            let at = format!("{}:{}", segment.display(), number);
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(reason) => {
+                    report.problems.push(format!("{}: {}", at, reason));
+                    return;
+                }
+            };
+            report.entries += 1;
+            match &previous {
+                None if entry.seq != 0 => report.problems.push(format!(
+                    "{}: log starts at seq {}, earlier entries are missing",
+                    at, entry.seq
+                )),
+                None if entry.prev != GENESIS => report
+                    .problems
+                    .push(format!("{}: seq 0 does not start the chain", at)),
+                Some((seq, _)) if entry.seq != seq + 1 => report.problems.push(format!(
+                    "{}: expected seq {}, found {}, entries were removed or reordered",
+                    at,
+                    seq + 1,
+                    entry.seq
+                )),
+                Some((_, hash)) if &entry.prev != hash => report.problems.push(format!(
+                    "{}: seq {} does not link to the previous entry",
+                    at, entry.seq
+                )),
+                _ => {}
+            }
+            if entry.digest() != entry.hash {
+                report.problems.push(format!(
+                    "{}: seq {} was modified, its hash does not match",
+                    at, entry.seq
+                ));
+            }
+            match (key, &entry.sig) {
+                (Some(key), Some(sig)) if *sig == sign(key, &entry.hash) => report.signed += 1,
+                (Some(_), Some(_)) => report.problems.push(format!(
+                    "{}: seq {} has an invalid signature",
+                    at, entry.seq
+                )),
+                (Some(_), None) => report
+                    .problems
+                    .push(format!("{}: seq {} is not signed", at, entry.seq)),
+                (None, Some(_)) => report.unchecked += 1,
+                (None, None) => {}
+            }
+            previous = Some((entry.seq, entry.hash));
+        };
+        match read_segment(&segment, &mut visit) {
+            Ok(()) => report.segments += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => report
+                .problems
+                .push(format!("{}: {}", segment.display(), e)),
+        }
+    }
+
+    match read_head(path) {
+        Ok(Some(head)) => {
+            if let Some(key) = key {
+                if head.sig.as_deref() != Some(sign_head(key, head.seq, &head.hash).as_str()) {
+                    report
+                        .problems
+                        .push("the head file has an invalid signature".to_string());
+                }
+            }
+            match &previous {
+                None => report.problems.push(format!(
+                    "the log is empty but the head records seq {}, entries were truncated",
+                    head.seq
+                )),
+                Some((seq, _)) if head.seq > *seq => report.problems.push(format!(
+                    "the log ends at seq {} but the head records seq {}, entries were truncated",
+                    seq, head.seq
+                )),
+                Some((seq, hash)) if head.seq != *seq || head.hash != *hash => {
+                    report.problems.push(format!(
+                        "the head does not match the last entry (seq {})",
+                        seq
+                    ))
+                }
+                _ => {}
+            }
+        }
+        Ok(None) if previous.is_some() => report.problems.push(format!(
+            "{} is missing, truncation cannot be ruled out",
+            head_path(path).display()
+        )),
+        Ok(None) => {}
+        Err(reason) => report.problems.push(reason),
+    }
+    report
+}
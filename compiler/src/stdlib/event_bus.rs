@@ -1,70 +1,1490 @@
+// Event Bus Standard Library
+// Getypte Events: Structs mit `@Event("orders.created")` legen Topic und Payload
+// fest, `@Subscribe`-Funktionen verarbeiten sie. Transporte: prozessintern,
+// NATS und Redis Streams
+
+pub mod transport;
+
+use crate::parser::ast::*;
+use crate::parser::visitor::{visit_block, visit_expression};
+use crate::stdlib::scheduler::schedule::parse_duration;
+use crate::stdlib::workflow::decorator_value;
+use std::collections::{BTreeMap, BTreeSet};
+
 pub struct EventBusStdlib;
 
+/// Pfad des generierten Runtime-Moduls
+const RUNTIME: &str = "crate::stdlib::event_bus";
+
+/// Quelltext von `transport.rs`, wird in die Runtime eingebettet
+const TRANSPORT_SOURCE: &str = include_str!("event_bus/transport.rs");
+
+/// Wiederholungen eines fehlgeschlagenen Handlers, bevor das Event im Dead-Letter-Topic landet
+const DEFAULT_RETRIES: u64 = 3;
+const DEFAULT_BACKOFF_MS: u64 = 100;
+
+/// `@Subscribe`-Handler für die generierte Registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscriber {
+    pub function: String,
+    pub topic: String,
+    /// Consumer-Group; Handler derselben Gruppe teilen sich die Events
+    pub group: String,
+    pub retries: u64,
+    pub backoff_ms: u64,
+    pub is_async: bool,
+    pub returns_result: bool,
+}
+
+/// Deklarierte Events und ihre Handler
+#[derive(Debug, Clone, Default)]
+pub struct EventCatalog {
+    /// Topic → Struct
+    pub topics: BTreeMap<String, String>,
+    pub subscribers: Vec<Subscriber>,
+}
+
+impl EventCatalog {
+    /// Katalog der Top-Level-Items; ungültige Deklarationen kommen als
+    /// `(Ziel, Begründung)` zurück und fehlen im Katalog
+    pub fn build(items: &[Item]) -> (EventCatalog, Vec<(String, String)>) {
+        let mut catalog = EventCatalog::default();
+        let mut problems = Vec::new();
+
+        for item in items {
+            let Item::Struct(struct_def) = item else {
+                continue;
+            };
+            let target = format!("@Event on {}", struct_def.name);
+            match event_topic(struct_def) {
+                None => {}
+                Some(Err(reason)) => problems.push((target, reason)),
+                Some(Ok(topic)) => match catalog.topics.get(&topic) {
+                    Some(other) => problems.push((
+                        target,
+                        format!("topic '{}' is also declared by {}", topic, other),
+                    )),
+                    None => {
+                        catalog.topics.insert(topic, struct_def.name.clone());
+                    }
+                },
+            }
+        }
+
+        for item in items {
+            let Item::Function(function) = item else {
+                continue;
+            };
+            let target = format!("@Subscribe on {}", function.name);
+            match subscription(function, &catalog.topics) {
+                None => {}
+                Some(Err(reason)) => problems.push((target, reason)),
+                Some(Ok(subscriber)) => {
+                    let taken = catalog
+                        .subscribers
+                        .iter()
+                        .find(|s| s.topic == subscriber.topic && s.group == subscriber.group);
+                    match taken {
+                        Some(other) => problems.push((
+                            target,
+                            format!(
+                                "group '{}' already handles '{}' in {}",
+                                subscriber.group, subscriber.topic, other.function
+                            ),
+                        )),
+                        None => catalog.subscribers.push(subscriber),
+                    }
+                }
+            }
+        }
+        (catalog, problems)
+    }
+}
+
+/// Was ein Programm vom Event-Bus braucht
+#[derive(Debug, Clone, Default)]
+pub struct EventBusUsage {
+    pub catalog: EventCatalog,
+    /// URL-Schemata aus `event_bus.connect("nats://...")` (ohne `memory`)
+    pub transports: BTreeSet<String>,
+}
+
+impl EventBusUsage {
+    /// `None`, wenn das Programm weder `event_bus.*` noch `@Event`/`@Subscribe` verwendet
+    pub fn collect(program: &Program) -> Option<EventBusUsage> {
+        let mut used = false;
+        let mut transports = BTreeSet::new();
+
+        let mut inspect = |expr: &Expression| {
+            let Some((method, args)) = event_bus_call(expr) else {
+                return;
+            };
+            used = true;
+            if method == "connect" {
+                if let Some(Expression::Literal(Literal::String(url))) = args.first() {
+                    if let Some((scheme, _)) = url.split_once("://") {
+                        if scheme != "memory" {
+                            transports.insert(scheme.to_string());
+                        }
+                    }
+                }
+            }
+        };
+        for item in &program.items {
+            match item {
+                Item::Function(f) => visit_block(&f.body, &mut inspect),
+                Item::TopLevelCode(code) => visit_expression(&code.expression, &mut inspect),
+                _ => {}
+            }
+        }
+
+        let (catalog, _) = EventCatalog::build(&program.items);
+        let declares = program.items.iter().any(|item| match item {
+            Item::Struct(s) => s.decorators.iter().any(|d| d.name == "Event"),
+            Item::Function(f) => f.decorators.iter().any(|d| d.name == "Subscribe"),
+            _ => false,
+        });
+        if used || declares {
+            Some(EventBusUsage {
+                catalog,
+                transports,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn event_bus_call(expr: &Expression) -> Option<(&str, &[Expression])> {
+    let Expression::Call { callee, args } = expr else {
+        return None;
+    };
+    let Expression::Member { object, member } = callee.as_ref() else {
+        return None;
+    };
+    match object.as_ref() {
+        Expression::Identifier(name) if name == "event_bus" => Some((member.as_str(), args)),
+        _ => None,
+    }
+}
+
+fn is_result(ty: &Type) -> bool {
+    match ty {
+        Type::Result { .. } => true,
+        Type::Generic { name, .. } => name == "Result",
+        _ => false,
+    }
+}
+
+/// Topics und Gruppen werden zu NATS-Subjects bzw. Redis-Stream-Namen
+fn check_name(kind: &str, name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.ends_with('.')
+        && !name.contains("..")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid {} '{}', use letters, digits, '.', '_' and '-'",
+            kind, name
+        ))
+    }
+}
+
+/// Topic aus `@Event("orders.created")`
+///
+/// `None` ohne `@Event`; `Err` mit Begründung, wenn die Deklaration ungültig ist.
+pub fn event_topic(struct_def: &Struct) -> Option<Result<String, String>> {
+    let decorator = struct_def.decorators.iter().find(|d| d.name == "Event")?;
+    let topic = match decorator.args.as_slice() {
+        [DecoratorArg::String(topic)] => topic,
+        _ => return Some(Err("expected exactly one topic string".into())),
+    };
+    if let Err(reason) = check_name("topic", topic) {
+        return Some(Err(reason));
+    }
+    if topic.ends_with(".dlq") {
+        return Some(Err(
+            "topics ending in '.dlq' are reserved for dead letters".into()
+        ));
+    }
+    if !struct_def.type_params.is_empty() {
+        return Some(Err("event structs cannot be generic".into()));
+    }
+    Some(Ok(topic.clone()))
+}
+
+/// Handler aus `@Subscribe("orders.created", group: "billing", retries: 5, backoff: "1s")`
+///
+/// Ohne Topic gilt das Topic des Parameter-Structs. `None` ohne `@Subscribe`.
+pub fn subscription(
+    function: &Function,
+    topics: &BTreeMap<String, String>,
+) -> Option<Result<Subscriber, String>> {
+    let decorator = function.decorators.iter().find(|d| d.name == "Subscribe")?;
+    Some(subscriber(function, decorator, topics))
+}
+
+fn subscriber(
+    function: &Function,
+    decorator: &Decorator,
+    topics: &BTreeMap<String, String>,
+) -> Result<Subscriber, String> {
+    let mut topic = None;
+    let mut group = function.name.clone();
+    let mut retries = DEFAULT_RETRIES;
+    let mut backoff_ms = DEFAULT_BACKOFF_MS;
+    for arg in &decorator.args {
+        match arg {
+            DecoratorArg::String(name) if topic.is_none() => topic = Some(name.clone()),
+            DecoratorArg::Named { name, value } => {
+                let value = decorator_value(value);
+                match name.as_str() {
+                    "group" => {
+                        group = value
+                            .as_str()
+                            .ok_or("'group' must be a string")?
+                            .to_string();
+                        check_name("group", &group)?;
+                    }
+                    "retries" => {
+                        retries = value
+                            .as_f64()
+                            .filter(|n| *n >= 0.0 && n.fract() == 0.0)
+                            .ok_or("'retries' must be a non-negative whole number")?
+                            as u64;
+                    }
+                    "backoff" => backoff_ms = parse_duration(&value)?,
+                    other => return Err(format!("unknown option '{}'", other)),
+                }
+            }
+            _ => return Err("expected a topic string followed by named options".into()),
+        }
+    }
+
+    let event_type = match function.params.as_slice() {
+        [param] => match &param.param_type {
+            Type::Named(name) => name.clone(),
+            other => {
+                return Err(format!(
+                    "parameter '{}' must be an @Event struct, found {}",
+                    param.name,
+                    other.to_string()
+                ))
+            }
+        },
+        params => {
+            return Err(format!(
+                "handlers take exactly one event parameter, found {}",
+                params.len()
+            ))
+        }
+    };
+    let topic = match topic {
+        Some(topic) => {
+            let declared = topics
+                .get(&topic)
+                .ok_or_else(|| format!("topic '{}' is not declared with @Event", topic))?;
+            if *declared != event_type {
+                return Err(format!(
+                    "topic '{}' carries {}, but the handler takes {}",
+                    topic, declared, event_type
+                ));
+            }
+            topic
+        }
+        None => topics
+            .iter()
+            .find(|(_, name)| **name == event_type)
+            .map(|(topic, _)| topic.clone())
+            .ok_or_else(|| format!("{} is not an @Event struct", event_type))?,
+    };
+
+    Ok(Subscriber {
+        function: function.name.clone(),
+        topic,
+        group,
+        retries,
+        backoff_ms,
+        is_async: function.is_async,
+        returns_result: function.return_type.as_ref().is_some_and(is_result),
+    })
+}
+
+/// Prüft `event_bus.*`-Aufrufe mit Literal-Argumenten gegen den Katalog
+///
+/// `event_type` liefert den Typnamen eines Event-Arguments, soweit er bekannt ist.
+pub fn check_call(
+    method: &str,
+    args: &[Expression],
+    catalog: &EventCatalog,
+    event_type: &dyn Fn(&Expression) -> Option<String>,
+) -> Result<(), String> {
+    match (method, args) {
+        ("publish", [_, Expression::Literal(Literal::String(topic)), event]) => {
+            check_name("topic", topic)?;
+            let Some(declared) = catalog.topics.get(topic) else {
+                return Ok(());
+            };
+            match event_type(event) {
+                Some(found) if found != *declared => Err(format!(
+                    "topic '{}' carries {}, found {}",
+                    topic, declared, found
+                )),
+                _ => Ok(()),
+            }
+        }
+        ("subscribe", [_, Expression::Literal(Literal::String(topic))]) => {
+            if catalog.subscribers.iter().any(|s| s.topic == *topic) {
+                Ok(())
+            } else {
+                Err(format!("no @Subscribe handler for topic '{}'", topic))
+            }
+        }
+        ("get_history" | "getHistory", [_, Expression::Literal(Literal::String(topic)), _]) => {
+            check_name("topic", topic)
+        }
+        ("connect", [Expression::Literal(Literal::String(url))]) => {
+            match url.split_once("://").map(|(scheme, _)| scheme) {
+                Some("memory" | "nats" | "redis" | "rediss") => Ok(()),
+                _ => Err(format!(
+                    "unsupported transport '{}', use memory://, nats:// or redis://",
+                    url
+                )),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
 impl EventBusStdlib {
-    pub fn generate_create_code() -> String {
+    /// Runtime-Modul `stdlib::event_bus` inklusive Handler-Registry
+    ///
+    /// NATS- und Redis-Transport werden nur generiert, wenn eine entsprechende
+    /// URL in `event_bus.connect` vorkommt (Redis braucht das `redis`-Crate).
+    pub fn generate_event_bus_runtime_code(
+        usage: &EventBusUsage,
+        rust_name: &dyn Fn(&str) -> String,
+    ) -> String {
+        let nats = usage.transports.contains("nats");
+        let redis = usage.transports.contains("redis") || usage.transports.contains("rediss");
+
+        let mut open_transports = String::new();
+        let mut transports = String::new();
+        if nats {
+            open_transports.push_str(
+                "    if url.starts_with(\"nats://\") {\n        return Ok(Arc::new(NatsTransport::open(url)?));\n    }\n",
+            );
+            transports.push_str(NATS_TRANSPORT);
+        }
+        if redis {
+            open_transports.push_str(
+                "    if url.starts_with(\"redis://\") || url.starts_with(\"rediss://\") {\n        return Ok(Arc::new(RedisTransport::open(url)?));\n    }\n",
+            );
+            transports.push_str(REDIS_TRANSPORT);
+        }
+
+        RUNTIME_TEMPLATE
+            .replace("/*TRANSPORT*/\n", TRANSPORT_SOURCE)
+            .replace("/*OPEN_TRANSPORTS*/\n", &open_transports)
+            .replace("/*TRANSPORTS*/\n", &transports)
+            .replace(
+                "/*REGISTRY*/\n",
+                &Self::generate_registry(&usage.catalog, rust_name),
+            )
+    }
+
+    fn generate_registry(catalog: &EventCatalog, rust_name: &dyn Fn(&str) -> String) -> String {
+        let mut specs = String::new();
+        let mut calls = String::new();
+        for subscriber in &catalog.subscribers {
+            specs.push_str(&format!(
+                "    HandlerSpec {{ function: {:?}, topic: {:?}, group: {:?}, retries: {}, backoff_ms: {} }},\n",
+                subscriber.function,
+                subscriber.topic,
+                subscriber.group,
+                subscriber.retries,
+                subscriber.backoff_ms
+            ));
+            let mut invocation =
+                format!("super::super::{}(event)", rust_name(&subscriber.function));
+            if subscriber.is_async {
+                invocation = format!("block_on({})", invocation);
+            }
+            calls.push_str(&format!(
+                "        {:?} => {{\n            let event = serde_json::from_value(data).map_err(|e| format!(\"invalid payload for '{}': {{}}\", e))?;\n            {}({})\n        }}\n",
+                subscriber.function,
+                subscriber.function,
+                if subscriber.returns_result {
+                    "handler_result"
+                } else {
+                    "handler_output"
+                },
+                invocation
+            ));
+        }
+
         format!(
-            "{{
-                use std::sync::Arc;
-                use tokio::sync::broadcast;
-                let (tx, _rx) = broadcast::channel::<serde_json::Value>(1000);
-                Arc::new(tx)
-            }}"
+            "const HANDLERS: &[HandlerSpec] = &[\n{}];\n\nfn call_handler(function: &str, {}: serde_json::Value) -> Result<(), String> {{\n    match function {{\n{}        _ => Err(format!(\"unknown event handler '{{}}'\", function)),\n    }}\n}}\n",
+            specs,
+            if calls.is_empty() { "_data" } else { "data" },
+            calls
         )
     }
 
+    pub fn generate_create_code() -> String {
+        format!("{}::create()", RUNTIME)
+    }
+
+    pub fn generate_connect_code(url: &str) -> String {
+        format!("{}::connect(&{})", RUNTIME, url)
+    }
+
     pub fn generate_publish_code(bus: &str, topic: &str, event: &str) -> String {
-        format!(
-            "{{
-                let event_data: serde_json::Value = {};
-                let mut event_obj = serde_json::json!({{
-                    \"topic\": {},
-                    \"data\": event_data,
-                    \"timestamp\": chrono::Utc::now().to_rfc3339()
-                }});
-                {}.send(event_obj).map_err(|e| e.to_string())
-            }}",
-            event, topic, bus
-        )
+        format!("{}::publish(&{}, &{}, &{})", RUNTIME, bus, topic, event)
     }
 
     pub fn generate_subscribe_code(bus: &str, topic: &str) -> String {
+        format!("{}::subscribe(&{}, &{})", RUNTIME, bus, topic)
+    }
+
+    pub fn generate_unsubscribe_code(subscription: &str) -> String {
+        format!("{}::unsubscribe(&{})", RUNTIME, subscription)
+    }
+
+    pub fn generate_get_history_code(bus: &str, topic: &str, limit: &str) -> String {
         format!(
-            "{{
-                let topic_str = {};
-                let mut rx = {}.subscribe();
-                tokio::spawn(async move {{
-                    while let Ok(event) = rx.recv().await {{
-                        if let Some(event_topic) = event.get(\"topic\").and_then(|v| v.as_str()) {{
-                            if event_topic == topic_str {{
-                                // Event handler would be called here
-                            }}
-                        }}
-                    }}
-                }});
-                Ok(())
-            }}",
-            topic, bus
+            "{}::get_history(&{}, &{}, ({}) as usize)",
+            RUNTIME, bus, topic, limit
         )
     }
 
-    pub fn generate_unsubscribe_code(_subscription: &str) -> String {
-        format!(
-            "{{
-                // In a real implementation, this would close the subscription
-                Ok(())
-            }}"
+    pub fn generate_replay_code(bus: &str, event: &str) -> String {
+        format!("{}::replay(&{}, &{})", RUNTIME, bus, event)
+    }
+
+    pub fn generate_start_subscribers_code() -> String {
+        format!("{}::start_subscribers();", RUNTIME)
+    }
+}
+
+/// Runtime-Vorlage; Platzhalter werden in `generate_event_bus_runtime_code` ersetzt
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Event Bus Runtime ---
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+pub mod transport {
+/*TRANSPORT*/
+}
+
+pub use transport::{Deliver, Transport, Unsubscribe};
+use transport::{check_topic, History, MemoryTransport};
+
+// --- Handler-Registry ---
+
+pub struct HandlerSpec {
+    pub function: &'static str,
+    pub topic: &'static str,
+    pub group: &'static str,
+    pub retries: u32,
+    pub backoff_ms: u64,
+}
+
+/*REGISTRY*/
+fn handler_output<T>(_value: T) -> Result<(), String> {
+    Ok(())
+}
+
+fn handler_result<T, E: std::fmt::Display>(result: Result<T, E>) -> Result<(), String> {
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Async-Handler laufen im Zustell-Thread auf einer eigenen Runtime
+#[allow(dead_code)]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start runtime for event handler")
+        .block_on(future)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// --- Envelopes ---
+
+fn new_envelope(topic: &str, data: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "topic": topic,
+        "data": data,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+// --- Transporte ---
+
+/// Bus mit nicht nutzbarer URL: jeder Aufruf liefert den Fehler
+struct Unavailable(String);
+
+impl Transport for Unavailable {
+    fn publish(&self, _topic: &str, _envelope: &serde_json::Value) -> Result<(), String> {
+        Err(self.0.clone())
+    }
+
+    fn subscribe(&self, _topic: &str, _group: &str, _deliver: Deliver) -> Result<Unsubscribe, String> {
+        Err(self.0.clone())
+    }
+
+    fn history(&self, _topic: &str, _limit: usize) -> Result<Vec<serde_json::Value>, String> {
+        Err(self.0.clone())
+    }
+}
+
+fn open_transport(url: &str) -> Result<Arc<dyn Transport>, String> {
+/*OPEN_TRANSPORTS*/
+    Err(format!(
+        "unsupported event bus '{}' (nats:// and redis:// are only available when used as URL in event_bus.connect)",
+        url
+    ))
+}
+/*TRANSPORTS*/
+// --- Zustellung ---
+
+fn panic_message(panic: Box<dyn std::any::Any + Send>) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .map(|message| format!("panicked: {}", message))
+        .unwrap_or_else(|| "panicked".to_string())
+}
+
+/// Ruft den Handler mit Wiederholungen auf; danach geht das Event an `<topic>.dlq`.
+/// Bestätigt wird erst nach Erfolg oder geschriebenem Dead Letter.
+fn deliverer(bus: EventBus, handler: &'static HandlerSpec) -> Deliver {
+    Arc::new(move |envelope: &serde_json::Value| {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let data = envelope.get("data").cloned().unwrap_or(serde_json::Value::Null);
+            let error = match std::panic::catch_unwind(|| call_handler(handler.function, data)) {
+                Ok(Ok(())) => return true,
+                Ok(Err(error)) => error,
+                Err(panic) => panic_message(panic),
+            };
+            eprintln!(
+                "[EventBus] Handler '{}' failed on '{}' (attempt {}/{}): {}",
+                handler.function,
+                handler.topic,
+                attempt,
+                handler.retries + 1,
+                error
+            );
+            if attempt > handler.retries {
+                return dead_letter(&bus, handler, envelope, &error, attempt);
+            }
+            let factor = 1u64 << (attempt - 1).min(16);
+            std::thread::sleep(Duration::from_millis(handler.backoff_ms.saturating_mul(factor).min(60_000)));
+        }
+    })
+}
+
+fn dead_letter(
+    bus: &EventBus,
+    handler: &HandlerSpec,
+    envelope: &serde_json::Value,
+    error: &str,
+    attempts: u32,
+) -> bool {
+    let topic = format!("{}.dlq", handler.topic);
+    let mut letter = new_envelope(&topic, envelope.get("data").cloned().unwrap_or(serde_json::Value::Null));
+    letter["source"] = serde_json::json!({
+        "id": envelope.get("id"),
+        "topic": handler.topic,
+        "group": handler.group,
+        "handler": handler.function,
+    });
+    letter["error"] = serde_json::json!(error);
+    letter["attempts"] = serde_json::json!(attempts);
+    match bus.transport.publish(&topic, &letter) {
+        Ok(()) => true,
+        Err(error) => {
+            eprintln!("[EventBus] Dead letter for '{}' could not be written: {}", topic, error);
+            false
+        }
+    }
+}
+
+// --- API für `event_bus.*` ---
+
+#[derive(Clone)]
+pub struct EventBus {
+    url: Arc<str>,
+    transport: Arc<dyn Transport>,
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EventBus({})", self.url)
+    }
+}
+
+/// `event_bus.create`: prozessinterner Bus, an dem auch die `@Subscribe`-Handler hängen
+pub fn create() -> EventBus {
+    static DEFAULT: OnceLock<EventBus> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| EventBus {
+            url: Arc::from("memory://"),
+            transport: Arc::new(MemoryTransport::default()),
+        })
+        .clone()
+}
+
+/// `event_bus.connect`: ein Bus je URL; verbunden wird beim ersten Gebrauch
+pub fn connect(url: &str) -> EventBus {
+    if url.starts_with("memory://") {
+        return create();
+    }
+    static BUSES: OnceLock<Mutex<HashMap<String, EventBus>>> = OnceLock::new();
+    lock(BUSES.get_or_init(Default::default))
+        .entry(url.to_string())
+        .or_insert_with(|| EventBus {
+            url: Arc::from(url),
+            transport: open_transport(url).unwrap_or_else(|error| {
+                eprintln!("[EventBus] {}", error);
+                Arc::new(Unavailable(error))
+            }),
+        })
+        .clone()
+}
+
+pub fn publish<E: serde::Serialize>(bus: &EventBus, topic: &str, event: &E) -> Result<(), String> {
+    check_topic(topic)?;
+    let data = serde_json::to_value(event).map_err(|e| e.to_string())?;
+    bus.transport.publish(topic, &new_envelope(topic, data))
+}
+
+fn subscriptions() -> MutexGuard<'static, HashMap<String, Vec<Unsubscribe>>> {
+    static SUBSCRIPTIONS: OnceLock<Mutex<HashMap<String, Vec<Unsubscribe>>>> = OnceLock::new();
+    lock(SUBSCRIPTIONS.get_or_init(Default::default))
+}
+
+/// Hängt die `@Subscribe`-Handler des Topics an den Bus
+pub fn subscribe(bus: &EventBus, topic: &str) -> Result<serde_json::Value, String> {
+    let handlers: Vec<&'static HandlerSpec> = HANDLERS.iter().filter(|h| h.topic == topic).collect();
+    if handlers.is_empty() {
+        return Err(format!("no @Subscribe handler for topic '{}'", topic));
+    }
+    let mut attached = Vec::new();
+    for handler in &handlers {
+        match bus.transport.subscribe(topic, handler.group, deliverer(bus.clone(), handler)) {
+            Ok(unsubscribe) => attached.push(unsubscribe),
+            Err(error) => {
+                attached.into_iter().for_each(|unsubscribe| unsubscribe());
+                return Err(error);
+            }
+        }
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    subscriptions().insert(id.clone(), attached);
+    Ok(serde_json::json!({
+        "id": id,
+        "topic": topic,
+        "bus": &*bus.url,
+        "handlers": handlers.iter().map(|h| h.function).collect::<Vec<_>>(),
+    }))
+}
+
+/// Nimmt das Ergebnis von `subscribe` oder dessen Id
+pub fn unsubscribe<S: serde::Serialize>(subscription: &S) -> Result<(), String> {
+    let subscription = serde_json::to_value(subscription).map_err(|e| e.to_string())?;
+    let id = subscription
+        .get("id")
+        .unwrap_or(&subscription)
+        .as_str()
+        .ok_or("expected a subscription or its id")?;
+    let attached = subscriptions()
+        .remove(id)
+        .ok_or_else(|| format!("unknown subscription '{}'", id))?;
+    attached.into_iter().for_each(|unsubscribe| unsubscribe());
+    Ok(())
+}
+
+/// Abonnement für `stream.from_topic` in eigener Gruppe: jeder Stream bekommt alle Events.
+/// Ein voller Puffer hält den Zustell-Thread an, bis der Stream weiterliest.
+#[allow(dead_code)]
+pub fn subscribe_stream(
+    bus: &EventBus,
+    topic: &str,
+    sender: tokio::sync::mpsc::Sender<serde_json::Value>,
+) -> Result<Unsubscribe, String> {
+    check_topic(topic)?;
+    let group = format!("stream-{}", uuid::Uuid::new_v4());
+    bus.transport.subscribe(
+        topic,
+        &group,
+        Arc::new(move |envelope: &serde_json::Value| {
+            let data = envelope.get("data").cloned().unwrap_or(serde_json::Value::Null);
+            // Verworfener Stream: Event trotzdem bestätigen
+            let _ = sender.blocking_send(data);
+            true
+        }),
+    )
+}
+
+/// Die letzten Events des Topics, älteste zuerst; Dead Letters unter `<topic>.dlq`
+pub fn get_history(bus: &EventBus, topic: &str, limit: usize) -> Vec<serde_json::Value> {
+    bus.transport.history(topic, limit).unwrap_or_else(|error| {
+        eprintln!("[EventBus] History of '{}' unavailable: {}", topic, error);
+        Vec::new()
+    })
+}
+
+/// Veröffentlicht ein Event aus `get_history` erneut, Dead Letters im ursprünglichen Topic
+pub fn replay<E: serde::Serialize>(bus: &EventBus, event: &E) -> Result<(), String> {
+    let event = serde_json::to_value(event).map_err(|e| e.to_string())?;
+    let source = event.get("source").unwrap_or(&event);
+    let topic = source
+        .get("topic")
+        .and_then(|t| t.as_str())
+        .ok_or("expected an event from get_history")?;
+    check_topic(topic)?;
+    let mut envelope = new_envelope(topic, event.get("data").cloned().unwrap_or(serde_json::Value::Null));
+    envelope["replay_of"] = source.get("id").cloned().unwrap_or(serde_json::Value::Null);
+    bus.transport.publish(topic, &envelope)
+}
+
+/// Hängt alle `@Subscribe`-Handler an den prozessinternen Bus
+pub fn start_subscribers() {
+    let mut topics: Vec<&str> = HANDLERS.iter().map(|h| h.topic).collect();
+    topics.sort();
+    topics.dedup();
+    for topic in topics {
+        if let Err(error) = subscribe(&create(), topic) {
+            eprintln!("[EventBus] Handlers for '{}' not started: {}", topic, error);
+        }
+    }
+}
+"#;
+
+/// NATS über das Core-Protokoll, ohne zusätzliches Crate
+const NATS_TRANSPORT: &str = r#"
+// --- NATS ---
+// Queue-Groups verteilen die Events einer Gruppe. Core-NATS speichert nichts:
+// zugestellt wird an verbundene Abonnenten, die Historie ist prozesslokal.
+
+use std::io::{BufRead, Read, Write};
+
+pub struct NatsTransport {
+    shared: Arc<NatsShared>,
+}
+
+type NatsSubscription = (String, String, std::sync::mpsc::Sender<serde_json::Value>);
+
+struct NatsShared {
+    address: String,
+    options: String,
+    connection: Mutex<Option<(u64, std::net::TcpStream)>>,
+    generation: std::sync::atomic::AtomicU64,
+    subscriptions: Mutex<HashMap<u64, NatsSubscription>>,
+    next_sid: std::sync::atomic::AtomicU64,
+    history: History,
+}
+
+impl NatsTransport {
+    /// `nats://[user:pass@|token@]host[:port]`
+    fn open(url: &str) -> Result<Self, String> {
+        let rest = url.strip_prefix("nats://").ok_or_else(|| format!("invalid NATS URL '{}'", url))?;
+        let (credentials, host) = match rest.rsplit_once('@') {
+            Some((credentials, host)) => (Some(credentials), host),
+            None => (None, rest),
+        };
+        let host = host.trim_end_matches('/');
+        if host.is_empty() {
+            return Err(format!("invalid NATS URL '{}'", url));
+        }
+        let mut options = serde_json::json!({
+            "verbose": false,
+            "pedantic": false,
+            "name": "velin",
+            "lang": "rust",
+            "version": "1.0.0",
+        });
+        if let Some(credentials) = credentials {
+            match credentials.split_once(':') {
+                Some((user, pass)) => {
+                    options["user"] = serde_json::json!(user);
+                    options["pass"] = serde_json::json!(pass);
+                }
+                None => options["auth_token"] = serde_json::json!(credentials),
+            }
+        }
+        Ok(NatsTransport {
+            shared: Arc::new(NatsShared {
+                address: if host.contains(':') { host.to_string() } else { format!("{}:4222", host) },
+                options: options.to_string(),
+                connection: Mutex::new(None),
+                generation: Default::default(),
+                subscriptions: Mutex::new(HashMap::new()),
+                next_sid: std::sync::atomic::AtomicU64::new(1),
+                history: History::default(),
+            }),
+        })
+    }
+}
+
+impl NatsShared {
+    /// Schreibt auf die bestehende oder eine neue Verbindung
+    fn with_connection<F>(self: &Arc<Self>, write: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut std::net::TcpStream) -> std::io::Result<()>,
+    {
+        let mut connection = lock(&self.connection);
+        if connection.is_none() {
+            *connection = Some(self.handshake()?);
+        }
+        let Some((_, stream)) = connection.as_mut() else {
+            return Err(format!("NATS {}: not connected", self.address));
+        };
+        if let Err(error) = write(stream) {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+            *connection = None;
+            return Err(format!("NATS {}: {}", self.address, error));
+        }
+        Ok(())
+    }
+
+    /// Verbindet, meldet sich an und abonniert bestehende Topics erneut
+    fn handshake(self: &Arc<Self>) -> Result<(u64, std::net::TcpStream), String> {
+        let error = |e: std::io::Error| format!("NATS {}: {}", self.address, e);
+        let mut stream = std::net::TcpStream::connect(&self.address).map_err(error)?;
+        stream.set_read_timeout(Some(Duration::from_secs(5))).map_err(error)?;
+        let mut reader = std::io::BufReader::new(stream.try_clone().map_err(error)?);
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(error)?;
+        if !line.starts_with("INFO") {
+            return Err(format!("NATS {}: unexpected greeting '{}'", self.address, line.trim()));
+        }
+        stream
+            .write_all(format!("CONNECT {}\r\nPING\r\n", self.options).as_bytes())
+            .map_err(error)?;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).map_err(error)? == 0 {
+                return Err(format!("NATS {}: connection closed", self.address));
+            }
+            match line.trim_end() {
+                "PONG" => break,
+                reply if reply.starts_with("-ERR") => {
+                    return Err(format!("NATS {}: {}", self.address, reply));
+                }
+                _ => {}
+            }
+        }
+        let mut subscribe = String::new();
+        for (sid, (topic, group, _)) in lock(&self.subscriptions).iter() {
+            subscribe.push_str(&format!("SUB {} {} {}\r\n", topic, group, sid));
+        }
+        stream.write_all(subscribe.as_bytes()).map_err(error)?;
+        stream.set_read_timeout(None).map_err(error)?;
+
+        let generation = self.generation.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        let shared = self.clone();
+        std::thread::spawn(move || shared.read_loop(generation, reader));
+        Ok((generation, stream))
+    }
+
+    fn read_loop(self: Arc<Self>, generation: u64, mut reader: std::io::BufReader<std::net::TcpStream>) {
+        let error = match self.read_messages(&mut reader) {
+            Ok(()) => "connection closed".to_string(),
+            Err(error) => error,
+        };
+        {
+            let mut connection = lock(&self.connection);
+            if !matches!(*connection, Some((current, _)) if current == generation) {
+                return;
+            }
+            *connection = None;
+        }
+        eprintln!("[EventBus] NATS {} disconnected: {}", self.address, error);
+        // Abonnements brauchen eine neue Verbindung; Publish verbindet selbst neu
+        while !lock(&self.subscriptions).is_empty() {
+            std::thread::sleep(Duration::from_secs(1));
+            let mut connection = lock(&self.connection);
+            if connection.is_some() {
+                return;
+            }
+            match self.handshake() {
+                Ok(established) => {
+                    *connection = Some(established);
+                    return;
+                }
+                Err(error) => eprintln!("[EventBus] {}", error),
+            }
+        }
+    }
+
+    fn read_messages(&self, reader: &mut std::io::BufReader<std::net::TcpStream>) -> Result<(), String> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+                return Ok(());
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                // MSG <subject> <sid> [reply-to] <#bytes>
+                ["MSG", subject, sid, .., size] => {
+                    let size: usize = size.parse().map_err(|_| format!("malformed '{}'", line.trim()))?;
+                    let mut payload = vec![0; size + 2];
+                    reader.read_exact(&mut payload).map_err(|e| e.to_string())?;
+                    let envelope: serde_json::Value = match serde_json::from_slice(&payload[..size]) {
+                        Ok(envelope) => envelope,
+                        Err(error) => {
+                            eprintln!("[EventBus] Ignoring malformed NATS message on '{}': {}", subject, error);
+                            continue;
+                        }
+                    };
+                    self.history.record(subject, &envelope);
+                    let sid: u64 = sid.parse().unwrap_or(0);
+                    if let Some((_, _, queue)) = lock(&self.subscriptions).get(&sid) {
+                        let _ = queue.send(envelope);
+                    }
+                }
+                ["PING"] => {
+                    if let Some((_, stream)) = lock(&self.connection).as_mut() {
+                        let _ = stream.write_all(b"PONG\r\n");
+                    }
+                }
+                ["-ERR", ..] => eprintln!("[EventBus] NATS {}: {}", self.address, line.trim()),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Transport for NatsTransport {
+    fn publish(&self, topic: &str, envelope: &serde_json::Value) -> Result<(), String> {
+        let payload = serde_json::to_vec(envelope).map_err(|e| e.to_string())?;
+        let mut frame = format!("PUB {} {}\r\n", topic, payload.len()).into_bytes();
+        frame.extend_from_slice(&payload);
+        frame.extend_from_slice(b"\r\n");
+        self.shared.with_connection(|stream| stream.write_all(&frame))?;
+        self.shared.history.record(topic, envelope);
+        Ok(())
+    }
+
+    fn subscribe(&self, topic: &str, group: &str, deliver: Deliver) -> Result<Unsubscribe, String> {
+        let shared = self.shared.clone();
+        let sid = shared.next_sid.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let (queue, receiver) = std::sync::mpsc::channel::<serde_json::Value>();
+        let subscribed = shared.with_connection(|stream| {
+            lock(&shared.subscriptions).insert(sid, (topic.to_string(), group.to_string(), queue));
+            stream.write_all(format!("SUB {} {} {}\r\n", topic, group, sid).as_bytes())
+        });
+        if let Err(error) = subscribed {
+            lock(&shared.subscriptions).remove(&sid);
+            return Err(error);
+        }
+        std::thread::spawn(move || {
+            for envelope in receiver {
+                deliver(&envelope);
+            }
+        });
+        Ok(Box::new(move || {
+            lock(&shared.subscriptions).remove(&sid);
+            let _ = shared.with_connection(|stream| stream.write_all(format!("UNSUB {}\r\n", sid).as_bytes()));
+        }))
+    }
+
+    fn history(&self, topic: &str, limit: usize) -> Result<Vec<serde_json::Value>, String> {
+        Ok(self.shared.history.last(topic, limit))
+    }
+}
+"#;
+
+/// Redis Streams über das `redis`-Crate
+const REDIS_TRANSPORT: &str = r#"
+// --- Redis Streams ---
+// Ein Stream je Topic, eine Consumer-Group je Handler-Gruppe. Bestätigt (XACK)
+// wird erst nach erfolgreicher Verarbeitung; Einträge abgestürzter Consumer
+// übernimmt nach REDIS_CLAIM_IDLE_MS ein anderer (XAUTOCLAIM).
+
+/// Unbestätigte Einträge gelten nach dieser Zeit als verwaist
+const REDIS_CLAIM_IDLE_MS: u64 = 60_000;
+/// Ungefähre Obergrenze je Stream (XADD MAXLEN ~)
+const REDIS_STREAM_LIMIT: usize = 10_000;
+
+pub struct RedisTransport {
+    client: redis::Client,
+    connection: Mutex<Option<redis::Connection>>,
+}
+
+impl RedisTransport {
+    fn open(url: &str) -> Result<Self, String> {
+        Ok(RedisTransport {
+            client: redis::Client::open(url).map_err(|e| e.to_string())?,
+            connection: Mutex::new(None),
+        })
+    }
+
+    /// Verwendet die Verbindung weiter, solange Befehle gelingen
+    fn query<T: redis::FromRedisValue>(&self, command: &redis::Cmd) -> Result<T, String> {
+        let mut cached = lock(&self.connection);
+        let mut connection = match cached.take() {
+            Some(connection) => connection,
+            None => self.client.get_connection().map_err(|e| e.to_string())?,
+        };
+        let result = command.query(&mut connection).map_err(|e| e.to_string());
+        if result.is_ok() {
+            *cached = Some(connection);
+        }
+        result
+    }
+}
+
+/// `[[id, [field, value, ...]], ...]`; Einträge ohne lesbares Envelope liefern `None`
+fn stream_entries(reply: &redis::Value) -> Vec<(String, Option<serde_json::Value>)> {
+    let redis::Value::Bulk(entries) = reply else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let redis::Value::Bulk(parts) = entry else {
+                return None;
+            };
+            let Some(redis::Value::Data(id)) = parts.first() else {
+                return None;
+            };
+            let envelope = match parts.get(1) {
+                Some(redis::Value::Bulk(fields)) => fields.chunks(2).find_map(|pair| match pair {
+                    [redis::Value::Data(key), redis::Value::Data(value)] if key == b"envelope" => {
+                        serde_json::from_slice(value).ok()
+                    }
+                    _ => None,
+                }),
+                _ => None,
+            };
+            Some((String::from_utf8_lossy(id).to_string(), envelope))
+        })
+        .collect()
+}
+
+fn redis_join(client: &redis::Client, topic: &str, group: &str) -> Result<redis::Connection, String> {
+    let mut connection = client.get_connection().map_err(|e| e.to_string())?;
+    let created = redis::cmd("XGROUP")
+        .arg("CREATE")
+        .arg(topic)
+        .arg(group)
+        .arg("$")
+        .arg("MKSTREAM")
+        .query::<()>(&mut connection);
+    match created {
+        Err(error) if error.code() != Some("BUSYGROUP") => Err(error.to_string()),
+        _ => Ok(connection),
+    }
+}
+
+fn redis_poll(
+    connection: &mut redis::Connection,
+    topic: &str,
+    group: &str,
+    consumer: &str,
+    deliver: &Deliver,
+    last_claim: &mut Option<std::time::Instant>,
+) -> Result<(), String> {
+    let mut entries = Vec::new();
+    let claim_due = last_claim.map_or(true, |at| at.elapsed() >= Duration::from_millis(REDIS_CLAIM_IDLE_MS / 4));
+    if claim_due {
+        *last_claim = Some(std::time::Instant::now());
+        let claimed = redis::cmd("XAUTOCLAIM")
+            .arg(topic)
+            .arg(group)
+            .arg(consumer)
+            .arg(REDIS_CLAIM_IDLE_MS)
+            .arg("0-0")
+            .arg("COUNT")
+            .arg(16)
+            .query::<redis::Value>(connection);
+        match claimed {
+            Ok(redis::Value::Bulk(parts)) => {
+                if let Some(claimed) = parts.get(1) {
+                    entries.extend(stream_entries(claimed));
+                }
+            }
+            Ok(_) => {}
+            Err(error) => eprintln!("[EventBus] XAUTOCLAIM on '{}' failed: {}", topic, error),
+        }
+    }
+    if entries.is_empty() {
+        let reply = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(group)
+            .arg(consumer)
+            .arg("COUNT")
+            .arg(16)
+            .arg("BLOCK")
+            .arg(2_000)
+            .arg("STREAMS")
+            .arg(topic)
+            .arg(">")
+            .query::<redis::Value>(connection)
+            .map_err(|e| e.to_string())?;
+        if let redis::Value::Bulk(streams) = reply {
+            for stream in streams {
+                if let redis::Value::Bulk(parts) = stream {
+                    if let Some(list) = parts.get(1) {
+                        entries.extend(stream_entries(list));
+                    }
+                }
+            }
+        }
+    }
+    for (id, envelope) in entries {
+        let done = match envelope {
+            Some(envelope) => deliver(&envelope),
+            None => {
+                eprintln!("[EventBus] Skipping malformed entry {} on '{}'", id, topic);
+                true
+            }
+        };
+        if done {
+            redis::cmd("XACK")
+                .arg(topic)
+                .arg(group)
+                .arg(&id)
+                .query::<()>(connection)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+fn redis_consume(
+    client: redis::Client,
+    topic: String,
+    group: String,
+    deliver: Deliver,
+    stopped: Arc<std::sync::atomic::AtomicBool>,
+) {
+    let consumer = format!("velin-{}", uuid::Uuid::new_v4().simple());
+    let mut connection: Option<redis::Connection> = None;
+    let mut last_claim = None;
+    while !stopped.load(std::sync::atomic::Ordering::SeqCst) {
+        let result = match connection.as_mut() {
+            Some(connection) => redis_poll(connection, &topic, &group, &consumer, &deliver, &mut last_claim),
+            None => redis_join(&client, &topic, &group).map(|joined| connection = Some(joined)),
+        };
+        if let Err(error) = result {
+            eprintln!("[EventBus] Redis consumer for '{}' ({}): {}", topic, group, error);
+            connection = None;
+            std::thread::sleep(Duration::from_secs(1));
+        }
+    }
+}
+
+impl Transport for RedisTransport {
+    fn publish(&self, topic: &str, envelope: &serde_json::Value) -> Result<(), String> {
+        self.query::<String>(
+            redis::cmd("XADD")
+                .arg(topic)
+                .arg("MAXLEN")
+                .arg("~")
+                .arg(REDIS_STREAM_LIMIT)
+                .arg("*")
+                .arg("envelope")
+                .arg(envelope.to_string()),
         )
+        .map(|_| ())
     }
 
-    pub fn generate_get_history_code(_bus: &str, topic: &str, limit: &str) -> String {
-        format!(
-            "{{
-                let topic_str = {};
-                let limit_num = {} as usize;
-                // In a real implementation, this would return the event history
-                Vec::<serde_json::Value>::new()
-            }}",
-            topic, limit
+    fn subscribe(&self, topic: &str, group: &str, deliver: Deliver) -> Result<Unsubscribe, String> {
+        let stopped = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let client = self.client.clone();
+        let (topic, group, running) = (topic.to_string(), group.to_string(), stopped.clone());
+        std::thread::spawn(move || redis_consume(client, topic, group, deliver, running));
+        Ok(Box::new(move || stopped.store(true, std::sync::atomic::Ordering::SeqCst)))
+    }
+
+    fn history(&self, topic: &str, limit: usize) -> Result<Vec<serde_json::Value>, String> {
+        let reply = self.query::<redis::Value>(
+            redis::cmd("XREVRANGE").arg(topic).arg("+").arg("-").arg("COUNT").arg(limit),
+        )?;
+        let mut events: Vec<serde_json::Value> =
+            stream_entries(&reply).into_iter().filter_map(|(_, envelope)| envelope).collect();
+        events.reverse();
+        Ok(events)
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::transport::{check_topic, Deliver, MemoryTransport, Transport};
+    use super::*;
+    use crate::parser::parser::Parser;
+    use std::sync::{mpsc, Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn test_events_and_subscribers_are_validated_and_collected() {
+        let source = r#"@Event("orders.created")
+struct OrderCreated {
+    id: string,
+}
+
+@Event("orders.created.dlq")
+struct Reserved {
+    id: string,
+}
+
+struct Plain {
+    id: string,
+}
+
+@Subscribe(retries: 5, backoff: "1s")
+fn onOrderCreated(event: OrderCreated): Result<boolean, string> {
+    return Ok(true);
+}
+
+@Subscribe("orders.created", group: "audit")
+fn auditOrder(order: OrderCreated) {
+    let x = order;
+}
+
+@Subscribe("orders.created", group: "audit")
+fn auditAgain(order: OrderCreated) {
+    let x = order;
+}
+
+@Subscribe
+fn untyped(event: Plain) {
+    let x = event;
+}
+
+fn main() {
+    let bus = event_bus.connect("nats://localhost:4222");
+    event_bus.publish(bus, "orders.created", OrderCreated { id: "o-1" });
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        let usage = EventBusUsage::collect(&program).unwrap();
+        let catalog = &usage.catalog;
+        assert_eq!(catalog.topics.len(), 1);
+        assert_eq!(catalog.topics["orders.created"], "OrderCreated");
+        let handlers: Vec<(&str, &str)> = catalog
+            .subscribers
+            .iter()
+            .map(|s| (s.function.as_str(), s.group.as_str()))
+            .collect();
+        assert_eq!(
+            handlers,
+            vec![
+                ("onOrderCreated", "onOrderCreated"),
+                ("auditOrder", "audit")
+            ]
+        );
+        assert_eq!(catalog.subscribers[0].retries, 5);
+        assert_eq!(catalog.subscribers[0].backoff_ms, 1_000);
+        assert!(catalog.subscribers[0].returns_result);
+
+        let (_, problems) = EventCatalog::build(&program.items);
+        let problems: Vec<String> = problems
+            .iter()
+            .map(|(target, reason)| format!("{}: {}", target, reason))
+            .collect();
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].contains("reserved for dead letters"));
+        assert!(problems[1].contains("group 'audit' already handles 'orders.created'"));
+        assert!(problems[2].contains("Plain is not an @Event struct"));
+
+        let bus = Expression::Identifier("bus".to_string());
+        let topic = Expression::Literal(Literal::String("orders.created".to_string()));
+        let event_type = |expr: &Expression| match expr {
+            Expression::MapLiteral(_) => Some("a map literal".to_string()),
+            _ => None,
+        };
+        let map = Expression::MapLiteral(Vec::new());
+        let error = check_call(
+            "publish",
+            &[bus.clone(), topic, map.clone()],
+            catalog,
+            &event_type,
         )
+        .unwrap_err();
+        assert!(
+            error.contains("carries OrderCreated, found a map literal"),
+            "{}",
+            error
+        );
+        let untyped = Expression::Literal(Literal::String("system_status".to_string()));
+        assert!(check_call(
+            "publish",
+            &[bus.clone(), untyped.clone(), map],
+            catalog,
+            &event_type
+        )
+        .is_ok());
+        assert!(
+            check_call("subscribe", &[bus, untyped], catalog, &event_type)
+                .unwrap_err()
+                .contains("no @Subscribe handler")
+        );
+
+        let runtime =
+            EventBusStdlib::generate_event_bus_runtime_code(&usage, &|name| name.to_lowercase());
+        assert!(runtime.contains("handler_result(super::super::onordercreated(event))"));
+        assert!(runtime.contains("group: \"audit\", retries: 3"));
+        assert!(runtime.contains("pub struct NatsTransport"));
+        assert!(!runtime.contains("RedisTransport"));
+        assert!(!runtime.contains("/*"));
+    }
+
+    fn envelope(id: &str) -> serde_json::Value {
+        serde_json::json!({ "id": id, "topic": "orders.created", "data": {} })
+    }
+
+    /// Zustellung, die Gruppe und Event-ID meldet und `accept` zurückgibt
+    fn deliver_to(
+        sender: &mpsc::Sender<(&'static str, String)>,
+        group: &'static str,
+        accept: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Deliver {
+        let sender = sender.clone();
+        Arc::new(move |envelope: &serde_json::Value| {
+            let id = envelope["id"].as_str().unwrap().to_string();
+            let accepted = accept(&id);
+            sender.send((group, id)).unwrap();
+            accepted
+        })
+    }
+
+    fn receive(
+        received: &mpsc::Receiver<(&'static str, String)>,
+        count: usize,
+    ) -> Vec<(&'static str, String)> {
+        let mut events: Vec<_> = (0..count)
+            .map(|_| {
+                received
+                    .recv_timeout(Duration::from_secs(5))
+                    .expect("event not delivered")
+            })
+            .collect();
+        events.sort();
+        events
+    }
+
+    #[test]
+    fn test_memory_transport_delivers_once_per_group() {
+        let transport = MemoryTransport::default();
+        let (sender, received) = mpsc::channel();
+        let billing = [
+            transport.subscribe(
+                "orders.created",
+                "billing",
+                deliver_to(&sender, "billing", |_| true),
+            ),
+            transport.subscribe(
+                "orders.created",
+                "billing",
+                deliver_to(&sender, "billing", |_| true),
+            ),
+        ];
+        let audit = transport
+            .subscribe(
+                "orders.created",
+                "audit",
+                deliver_to(&sender, "audit", |_| true),
+            )
+            .unwrap();
+
+        for id in ["1", "2", "3"] {
+            transport.publish("orders.created", &envelope(id)).unwrap();
+        }
+        transport.publish("orders.shipped", &envelope("4")).unwrap();
+
+        // Jede Gruppe bekommt jedes Event genau einmal, egal wie viele Mitglieder sie hat
+        let events = receive(&received, 6);
+        let expected: Vec<(&str, String)> = ["audit", "billing"]
+            .into_iter()
+            .flat_map(|group| ["1", "2", "3"].map(|id| (group, id.to_string())))
+            .collect();
+        assert_eq!(events, expected);
+        assert!(received.recv_timeout(Duration::from_millis(300)).is_err());
+
+        audit();
+        for unsubscribe in billing {
+            unsubscribe.unwrap()();
+        }
+        transport.publish("orders.created", &envelope("5")).unwrap();
+        assert!(received.recv_timeout(Duration::from_millis(300)).is_err());
+    }
+
+    #[test]
+    fn test_memory_transport_redelivers_unacknowledged_events() {
+        let transport = MemoryTransport::default();
+        let (sender, received) = mpsc::channel();
+        let rejected = Arc::new(Mutex::new(false));
+        let first_try = rejected.clone();
+        let _subscription = transport
+            .subscribe(
+                "orders.created",
+                "billing",
+                deliver_to(&sender, "billing", move |_| {
+                    std::mem::replace(&mut *first_try.lock().unwrap(), true)
+                }),
+            )
+            .unwrap();
+
+        transport.publish("orders.created", &envelope("1")).unwrap();
+        assert_eq!(
+            receive(&received, 2),
+            [("billing", "1".to_string()), ("billing", "1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_history_keeps_the_latest_events_once() {
+        let transport = MemoryTransport::default();
+        for id in ["1", "2", "2", "3"] {
+            transport.publish("orders.created", &envelope(id)).unwrap();
+        }
+        let ids = |limit| -> Vec<String> {
+            transport
+                .history("orders.created", limit)
+                .unwrap()
+                .iter()
+                .map(|event| event["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(ids(10), ["1", "2", "3"]);
+        assert_eq!(ids(2), ["2", "3"]);
+        assert!(transport.history("orders.shipped", 10).unwrap().is_empty());
+
+        assert!(check_topic("orders.created").is_ok());
+        assert!(check_topic("orders.created.dlq").is_ok());
+        for invalid in [
+            "",
+            ".orders",
+            "orders.",
+            "orders..created",
+            "orders created",
+            "orders/*",
+        ] {
+            assert_eq!(
+                check_topic(invalid),
+                Err(format!("invalid topic '{}'", invalid))
+            );
+        }
     }
 }
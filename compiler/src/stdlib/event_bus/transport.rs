@@ -0,0 +1,158 @@
+// Transporte für `event_bus`: Schnittstelle, Event-Historie und der
+// prozessinterne Transport.
+// Die Datei wird unverändert in das generierte Modul `stdlib::event_bus`
+// eingebettet; NATS und Redis kommen nur bei Bedarf hinzu.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Events je Topic, die `get_history` ohne persistenten Transport liefern kann
+const HISTORY_LIMIT: usize = 1000;
+
+/// Nimmt ein Event-Envelope entgegen; `true` bestätigt es beim Transport
+pub type Deliver = Arc<dyn Fn(&serde_json::Value) -> bool + Send + Sync>;
+
+/// Beendet ein Abonnement beim Transport
+pub type Unsubscribe = Box<dyn FnOnce() + Send>;
+
+pub trait Transport: Send + Sync {
+    fn publish(&self, topic: &str, envelope: &serde_json::Value) -> Result<(), String>;
+    /// Abonnenten derselben Gruppe teilen sich die Events eines Topics
+    fn subscribe(&self, topic: &str, group: &str, deliver: Deliver) -> Result<Unsubscribe, String>;
+    /// Die letzten `limit` Events, älteste zuerst
+    fn history(&self, topic: &str, limit: usize) -> Result<Vec<serde_json::Value>, String>;
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Topics: Punkt-getrennte Segmente aus Buchstaben, Ziffern, `_` und `-`
+pub fn check_topic(topic: &str) -> Result<(), String> {
+    let valid = !topic.is_empty()
+        && !topic.starts_with('.')
+        && !topic.ends_with('.')
+        && !topic.contains("..")
+        && topic
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid topic '{}'", topic))
+    }
+}
+
+/// Ringpuffer der letzten Events je Topic
+#[derive(Default)]
+pub struct History {
+    topics: Mutex<HashMap<String, VecDeque<serde_json::Value>>>,
+}
+
+impl History {
+    pub fn record(&self, topic: &str, envelope: &serde_json::Value) {
+        let mut topics = lock(&self.topics);
+        let events = topics.entry(topic.to_string()).or_default();
+        if events
+            .iter()
+            .rev()
+            .any(|event| event["id"] == envelope["id"])
+        {
+            return;
+        }
+        if events.len() == HISTORY_LIMIT {
+            events.pop_front();
+        }
+        events.push_back(envelope.clone());
+    }
+
+    pub fn last(&self, topic: &str, limit: usize) -> Vec<serde_json::Value> {
+        let topics = lock(&self.topics);
+        let Some(events) = topics.get(topic) else {
+            return Vec::new();
+        };
+        events
+            .iter()
+            .skip(events.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+}
+
+struct MemoryGroup {
+    queue: mpsc::Sender<serde_json::Value>,
+    receiver: Arc<Mutex<mpsc::Receiver<serde_json::Value>>>,
+    members: usize,
+}
+
+type MemoryGroups = Arc<Mutex<HashMap<(String, String), MemoryGroup>>>;
+
+/// Prozessintern: Events gehen beim Beenden des Prozesses verloren
+#[derive(Default)]
+pub struct MemoryTransport {
+    history: History,
+    groups: MemoryGroups,
+}
+
+impl Transport for MemoryTransport {
+    fn publish(&self, topic: &str, envelope: &serde_json::Value) -> Result<(), String> {
+        self.history.record(topic, envelope);
+        for ((group_topic, _), group) in lock(&self.groups).iter() {
+            if group_topic == topic {
+                let _ = group.queue.send(envelope.clone());
+            }
+        }
+        Ok(())
+    }
+
+    fn subscribe(&self, topic: &str, group: &str, deliver: Deliver) -> Result<Unsubscribe, String> {
+        let key = (topic.to_string(), group.to_string());
+        let (queue, receiver) = {
+            let mut groups = lock(&self.groups);
+            let group = groups.entry(key.clone()).or_insert_with(|| {
+                let (queue, receiver) = mpsc::channel();
+                MemoryGroup {
+                    queue,
+                    receiver: Arc::new(Mutex::new(receiver)),
+                    members: 0,
+                }
+            });
+            group.members += 1;
+            (group.queue.clone(), group.receiver.clone())
+        };
+        let stopped = Arc::new(AtomicBool::new(false));
+        let running = stopped.clone();
+        std::thread::spawn(move || {
+            while !running.load(Ordering::SeqCst) {
+                let next = lock(&receiver).recv_timeout(Duration::from_millis(200));
+                match next {
+                    // Nicht bestätigt: zurück in die Warteschlange der Gruppe
+                    Ok(envelope) => {
+                        if !deliver(&envelope) {
+                            let _ = queue.send(envelope);
+                        }
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+        let groups = self.groups.clone();
+        Ok(Box::new(move || {
+            stopped.store(true, Ordering::SeqCst);
+            let mut groups = lock(&groups);
+            if let Some(group) = groups.get_mut(&key) {
+                group.members -= 1;
+                if group.members == 0 {
+                    groups.remove(&key);
+                }
+            }
+        }))
+    }
+
+    fn history(&self, topic: &str, limit: usize) -> Result<Vec<serde_json::Value>, String> {
+        Ok(self.history.last(topic, limit))
+    }
+}
@@ -231,13 +231,16 @@ impl FlowStdlib {
             }
 
             pub fn step_failed(&self, name: &str, error: &str) {
-                let mut state = self.state.lock().unwrap();
-                state.steps.push(FlowStep {
-                    name: name.to_string(),
-                    status: StepStatus::Failed,
-                });
-                state.status = FlowStatus::Failed;
-                println!("Step failed: {} - {}", name, error);
+                {
+                    let mut state = self.state.lock().unwrap();
+                    state.steps.push(FlowStep {
+                        name: name.to_string(),
+                        status: StepStatus::Failed,
+                    });
+                    state.status = FlowStatus::Failed;
+                    println!("Step failed: {} - {}", name, error);
+                }
+                // Lock vor dem Rollback freigeben, sonst blockiert `rollback`
                 self.rollback(error);
             }
 
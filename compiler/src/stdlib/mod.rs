@@ -52,6 +52,7 @@ pub mod privacy;
 pub mod process;
 pub mod queue;
 pub mod rate_limit;
+pub mod realtime;
 pub mod redis;
 pub mod regex;
 pub mod result;
@@ -0,0 +1,1196 @@
+// Realtime Standard Library
+// Server-Endpunkte für WebSockets (`@WebSocket`) und Server-Sent Events (`@SSE`)
+// mit getypten Nachrichten, `@OnConnect`/`@OnDisconnect`-Hooks, Räumen und `@Auth`
+
+pub mod hub;
+
+use crate::codegen::framework::Framework;
+use crate::parser::ast::*;
+use crate::parser::visitor::calls_module;
+use std::collections::HashSet;
+
+pub struct RealtimeStdlib;
+
+/// Pfad des generierten Runtime-Moduls
+const RUNTIME: &str = "crate::stdlib::realtime";
+
+/// Quelltext von `hub.rs`, wird in die Runtime eingebettet
+const HUB_SOURCE: &str = include_str!("realtime/hub.rs");
+
+/// Parameter-Typ der Verbindung; `T` in `Connection<T>` ist die Nachricht an den Client
+pub const CONNECTION_TYPE: &str = "Connection";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointKind {
+    WebSocket,
+    Sse,
+}
+
+impl EndpointKind {
+    pub fn decorator(&self) -> &'static str {
+        match self {
+            EndpointKind::WebSocket => "WebSocket",
+            EndpointKind::Sse => "SSE",
+        }
+    }
+
+    fn from_decorator(name: &str) -> Option<EndpointKind> {
+        match name {
+            "WebSocket" => Some(EndpointKind::WebSocket),
+            "SSE" => Some(EndpointKind::Sse),
+            _ => None,
+        }
+    }
+}
+
+/// Argument eines Handler- oder Hook-Aufrufs, in Parameterreihenfolge
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    Connection,
+    Message,
+    Path(String),
+}
+
+/// `@OnConnect`- bzw. `@OnDisconnect`-Funktion eines Endpunkts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hook {
+    pub function: String,
+    pub args: Vec<Arg>,
+    pub is_async: bool,
+}
+
+/// Ein `@WebSocket`- oder `@SSE`-Endpunkt
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoint {
+    pub kind: EndpointKind,
+    pub path: String,
+    pub function: String,
+    pub args: Vec<Arg>,
+    pub path_params: Vec<(String, Type)>,
+    /// Struct der Nachrichten an den Client (`T` aus `Connection<T>`)
+    pub outgoing: String,
+    /// Struct der Nachrichten vom Client (nur WebSocket)
+    pub incoming: Option<String>,
+    /// Der Rückgabewert des Handlers geht als Antwort an den Absender
+    pub replies: bool,
+    pub is_async: bool,
+    pub auth: bool,
+    pub roles: Vec<String>,
+    pub on_connect: Option<Hook>,
+    pub on_disconnect: Option<Hook>,
+    pub documentation: Option<String>,
+}
+
+/// Alle Endpunkte eines Programms
+#[derive(Debug, Clone, Default)]
+pub struct RealtimeCatalog {
+    pub endpoints: Vec<Endpoint>,
+}
+
+impl RealtimeCatalog {
+    /// Katalog der Top-Level-Funktionen; ungültige Deklarationen kommen als
+    /// `(Ziel, Begründung)` zurück und fehlen im Katalog
+    pub fn build(items: &[Item]) -> (RealtimeCatalog, Vec<(String, String)>) {
+        let mut catalog = RealtimeCatalog::default();
+        let mut problems = Vec::new();
+        let structs: HashSet<&str> = items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Struct(s) => Some(s.name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let functions = items.iter().filter_map(|item| match item {
+            Item::Function(f) => Some(f),
+            _ => None,
+        });
+        let mut hooks = Vec::new();
+        for function in functions {
+            let mut endpoints = function
+                .decorators
+                .iter()
+                .filter_map(|d| EndpointKind::from_decorator(&d.name).map(|kind| (kind, d)));
+            if let Some((kind, decorator)) = endpoints.next() {
+                let target = format!("@{} on {}", kind.decorator(), function.name);
+                let endpoint = if endpoints.next().is_some() {
+                    Err("@WebSocket and @SSE cannot be combined".to_string())
+                } else if crate::validation::is_route_handler(function) {
+                    Err("realtime endpoints cannot also be HTTP routes".to_string())
+                } else {
+                    endpoint(function, kind, decorator, &structs)
+                };
+                match endpoint {
+                    Err(reason) => problems.push((target, reason)),
+                    Ok(endpoint) => {
+                        match catalog.endpoints.iter().find(|e| e.path == endpoint.path) {
+                            Some(other) => problems.push((
+                                target,
+                                format!(
+                                    "path '{}' is also served by {}",
+                                    endpoint.path, other.function
+                                ),
+                            )),
+                            None => catalog.endpoints.push(endpoint),
+                        }
+                    }
+                }
+            }
+            for decorator in &function.decorators {
+                if matches!(decorator.name.as_str(), "OnConnect" | "OnDisconnect") {
+                    hooks.push((function, decorator));
+                }
+            }
+        }
+
+        for (function, decorator) in hooks {
+            let target = format!("@{} on {}", decorator.name, function.name);
+            let result = hook(function, decorator, &catalog.endpoints).and_then(|(index, hook)| {
+                let endpoint = &mut catalog.endpoints[index];
+                let slot = if decorator.name == "OnConnect" {
+                    &mut endpoint.on_connect
+                } else {
+                    &mut endpoint.on_disconnect
+                };
+                match slot {
+                    Some(other) => Err(format!(
+                        "'{}' already has @{} in {}",
+                        endpoint.path, decorator.name, other.function
+                    )),
+                    None => {
+                        *slot = Some(hook);
+                        Ok(())
+                    }
+                }
+            });
+            if let Err(reason) = result {
+                problems.push((target, reason));
+            }
+        }
+        (catalog, problems)
+    }
+
+    /// Namen aller Endpunkt- und Hook-Funktionen
+    pub fn functions(&self) -> HashSet<String> {
+        let mut functions = HashSet::new();
+        for endpoint in &self.endpoints {
+            functions.insert(endpoint.function.clone());
+            for hook in endpoint.on_connect.iter().chain(&endpoint.on_disconnect) {
+                functions.insert(hook.function.clone());
+            }
+        }
+        functions
+    }
+}
+
+/// `true`, wenn das Programm Realtime-Decorators oder `realtime.*` verwendet
+pub fn uses_realtime(program: &Program) -> bool {
+    let declared = program.items.iter().any(|item| match item {
+        Item::Function(f) => f.decorators.iter().any(|d| {
+            EndpointKind::from_decorator(&d.name).is_some()
+                || matches!(d.name.as_str(), "OnConnect" | "OnDisconnect")
+        }),
+        _ => false,
+    });
+    declared || calls_module(program, "realtime")
+}
+
+/// Nachrichten-Struct aus `Connection<T>`; `None` für andere Typen
+pub fn connection_message(ty: &Type) -> Option<Result<String, String>> {
+    let missing = || {
+        Err(format!(
+            "{} needs the message struct, e.g. {}<ChatMessage>",
+            CONNECTION_TYPE, CONNECTION_TYPE
+        ))
+    };
+    match ty {
+        Type::Named(name) if name == CONNECTION_TYPE => Some(missing()),
+        Type::Generic { name, params } if name == CONNECTION_TYPE => match params.as_slice() {
+            [Type::Named(message)] => Some(Ok(message.clone())),
+            _ => Some(missing()),
+        },
+        _ => None,
+    }
+}
+
+/// Parameternamen aus `:name`- oder `{name}`-Segmenten
+pub fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| {
+            segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+/// Pfad in der Syntax des Frameworks: `/chat/:room` (Axum) bzw. `/chat/{room}` (Actix)
+pub fn route_path(path: &str, framework: Framework) -> String {
+    path.split('/')
+        .map(|segment| {
+            let name = segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')));
+            match (name, framework) {
+                (Some(name), Framework::Actix) => format!("{{{}}}", name),
+                (Some(name), _) => format!(":{}", name),
+                (None, _) => segment.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn endpoint_path(decorator: &Decorator) -> Result<String, String> {
+    let path = match decorator.args.as_slice() {
+        [DecoratorArg::String(path)] => path,
+        _ => return Err("expected exactly one path string".into()),
+    };
+    if !path.starts_with('/') {
+        return Err(format!("path '{}' must start with '/'", path));
+    }
+    for name in path_params(path) {
+        let valid = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("invalid path parameter '{}' in '{}'", name, path));
+        }
+    }
+    Ok(path.clone())
+}
+
+fn is_path_type(ty: &Type) -> bool {
+    matches!(ty, Type::String | Type::Number | Type::Boolean)
+}
+
+fn endpoint(
+    function: &Function,
+    kind: EndpointKind,
+    decorator: &Decorator,
+    structs: &HashSet<&str>,
+) -> Result<Endpoint, String> {
+    let path = endpoint_path(decorator)?;
+    let names = path_params(&path);
+    let mut args = Vec::new();
+    let mut path_values = Vec::new();
+    let mut outgoing = None;
+    let mut incoming = None;
+
+    for param in &function.params {
+        if let Some(message) = connection_message(&param.param_type) {
+            let message = message?;
+            if outgoing.is_some() {
+                return Err(format!("only one {} parameter is allowed", CONNECTION_TYPE));
+            }
+            if !structs.contains(message.as_str()) {
+                return Err(format!(
+                    "{} in {}<{}> is not a struct",
+                    message, CONNECTION_TYPE, message
+                ));
+            }
+            outgoing = Some(message);
+            args.push(Arg::Connection);
+        } else if names.contains(&param.name) {
+            if !is_path_type(&param.param_type) {
+                return Err(format!(
+                    "path parameter '{}' must be a string, number or boolean, found {}",
+                    param.name,
+                    param.param_type.to_string()
+                ));
+            }
+            path_values.push((param.name.clone(), param.param_type.clone()));
+            args.push(Arg::Path(param.name.clone()));
+        } else if kind == EndpointKind::WebSocket && incoming.is_none() {
+            match &param.param_type {
+                Type::Named(name) if structs.contains(name.as_str()) => {
+                    incoming = Some(name.clone());
+                    args.push(Arg::Message);
+                }
+                other => {
+                    return Err(format!(
+                        "message parameter '{}' must be a struct, found {}",
+                        param.name,
+                        other.to_string()
+                    ))
+                }
+            }
+        } else {
+            return Err(format!(
+                "parameter '{}' is not a path parameter of '{}'",
+                param.name, path
+            ));
+        }
+    }
+
+    let outgoing = outgoing.ok_or_else(|| {
+        format!(
+            "expected a {}<T> parameter with the struct sent to clients",
+            CONNECTION_TYPE
+        )
+    })?;
+    for name in &names {
+        if !path_values.iter().any(|(param, _)| param == name) {
+            return Err(format!(
+                "path parameter '{}' has no matching function parameter",
+                name
+            ));
+        }
+    }
+    if kind == EndpointKind::WebSocket && incoming.is_none() {
+        return Err("expected a message parameter with the struct sent by clients".into());
+    }
+
+    let replies = match &function.return_type {
+        None | Some(Type::Void) => false,
+        Some(Type::Named(name)) if kind == EndpointKind::WebSocket && *name == outgoing => true,
+        Some(other) if kind == EndpointKind::WebSocket => {
+            return Err(format!(
+                "replies must be {}, found {}",
+                outgoing,
+                other.to_string()
+            ))
+        }
+        Some(_) => {
+            return Err("SSE handlers cannot return a value, send with conn.send(...)".into())
+        }
+    };
+
+    let mut auth = false;
+    let mut roles = Vec::new();
+    for decorator in &function.decorators {
+        match decorator.name.as_str() {
+            "Auth" => auth = true,
+            "Role" | "Roles" => {
+                auth = true;
+                roles.extend(decorator.args.iter().filter_map(|arg| match arg {
+                    DecoratorArg::String(role) => Some(role.clone()),
+                    _ => None,
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(Endpoint {
+        kind,
+        path,
+        function: function.name.clone(),
+        args,
+        path_params: path_values,
+        outgoing,
+        incoming,
+        replies,
+        is_async: function.is_async,
+        auth,
+        roles,
+        on_connect: None,
+        on_disconnect: None,
+        documentation: function.documentation.clone(),
+    })
+}
+
+fn hook(
+    function: &Function,
+    decorator: &Decorator,
+    endpoints: &[Endpoint],
+) -> Result<(usize, Hook), String> {
+    let path = endpoint_path(decorator)?;
+    let index = endpoints
+        .iter()
+        .position(|e| e.path == path)
+        .ok_or_else(|| format!("no @WebSocket or @SSE endpoint serves '{}'", path))?;
+    let endpoint = &endpoints[index];
+
+    let mut args = Vec::new();
+    for param in &function.params {
+        if let Some(message) = connection_message(&param.param_type) {
+            let message = message?;
+            if message != endpoint.outgoing {
+                return Err(format!(
+                    "takes {}<{}>, but {} sends {}",
+                    CONNECTION_TYPE, message, endpoint.function, endpoint.outgoing
+                ));
+            }
+            args.push(Arg::Connection);
+            continue;
+        }
+        match endpoint
+            .path_params
+            .iter()
+            .find(|(name, _)| *name == param.name)
+        {
+            Some((_, ty)) if *ty == param.param_type => args.push(Arg::Path(param.name.clone())),
+            Some((_, ty)) => {
+                return Err(format!(
+                    "path parameter '{}' is {} in {}, found {}",
+                    param.name,
+                    ty.to_string(),
+                    endpoint.function,
+                    param.param_type.to_string()
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "parameter '{}' is not a path parameter of '{}'",
+                    param.name, path
+                ))
+            }
+        }
+    }
+    if !matches!(function.return_type, None | Some(Type::Void)) {
+        return Err("hooks cannot return a value".into());
+    }
+
+    Ok((
+        index,
+        Hook {
+            function: function.name.clone(),
+            args,
+            is_async: function.is_async,
+        },
+    ))
+}
+
+/// Prüft `realtime.*`-Aufrufe mit Literal-Argumenten
+pub fn check_call(method: &str, args: &[Expression]) -> Result<(), String> {
+    match (method, args) {
+        ("broadcast", [Expression::Literal(Literal::String(room)), _])
+        | ("members", [Expression::Literal(Literal::String(room))])
+            if room.trim().is_empty() =>
+        {
+            Err("room name must not be empty".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl RealtimeStdlib {
+    /// Runtime-Modul `stdlib::realtime` samt Endpunkten und Router für das Framework
+    pub fn generate_realtime_runtime_code(
+        catalog: &RealtimeCatalog,
+        framework: Framework,
+        rust_name: &dyn Fn(&str) -> String,
+        type_name: &dyn Fn(&str) -> String,
+    ) -> String {
+        let actix = framework == Framework::Actix;
+        let auth = catalog.endpoints.iter().any(|e| e.auth);
+
+        let mut endpoints = String::new();
+        let mut routes = String::new();
+        for endpoint in &catalog.endpoints {
+            let handler = format!("{}_endpoint", rust_name(&endpoint.function));
+            endpoints.push_str(&Self::generate_endpoint(
+                endpoint, actix, &handler, rust_name, type_name,
+            ));
+            let path = route_path(&endpoint.path, framework);
+            routes.push_str(&if actix {
+                format!(
+                    "    config.route({:?}, actix_web::web::get().to({}));\n",
+                    path, handler
+                )
+            } else {
+                format!(
+                    "        .route({:?}, axum::routing::get({}))\n",
+                    path, handler
+                )
+            });
+        }
+        let router = if actix {
+            format!(
+                "/// Registriert alle Endpunkte: `App::new().configure(stdlib::realtime::configure)`\npub fn configure({}: &mut actix_web::web::ServiceConfig) {{\n{}}}\n",
+                if routes.is_empty() { "_config" } else { "config" },
+                routes
+            )
+        } else {
+            format!(
+                "/// Alle Endpunkte: `Router::new().merge(stdlib::realtime::router())`\npub fn router() -> axum::Router {{\n    axum::Router::new()\n{}}}\n",
+                routes
+            )
+        };
+
+        RUNTIME_TEMPLATE
+            .replace("/*HUB*/\n", HUB_SOURCE)
+            .replace("/*AUTH*/\n", if auth { AUTH } else { "" })
+            .replace(
+                "/*SERVER*/\n",
+                if actix { ACTIX_SERVER } else { AXUM_SERVER },
+            )
+            .replace("/*ENDPOINTS*/\n", &(endpoints + &router))
+    }
+
+    fn generate_endpoint(
+        endpoint: &Endpoint,
+        actix: bool,
+        handler: &str,
+        rust_name: &dyn Fn(&str) -> String,
+        type_name: &dyn Fn(&str) -> String,
+    ) -> String {
+        let has_path = !endpoint.path_params.is_empty();
+        let mut code = format!(
+            "/// `@{}({:?})` → `{}`\n",
+            endpoint.kind.decorator(),
+            endpoint.path,
+            endpoint.function
+        );
+
+        // Signatur mit den Extractoren des Frameworks
+        if actix {
+            let uses_request =
+                endpoint.kind == EndpointKind::WebSocket || has_path || endpoint.auth;
+            code.push_str(&format!(
+                "pub async fn {}({}: actix_web::HttpRequest{}) -> actix_web::HttpResponse {{\n",
+                handler,
+                if uses_request { "request" } else { "_request" },
+                if endpoint.kind == EndpointKind::WebSocket {
+                    ", body: actix_web::web::Payload"
+                } else {
+                    ""
+                }
+            ));
+            if has_path {
+                code.push_str("    let path = path_values(&request);\n");
+            }
+            if endpoint.auth {
+                code.push_str("    let query = query_values(&request);\n");
+            }
+        } else {
+            let mut extractors = Vec::new();
+            if endpoint.kind == EndpointKind::WebSocket {
+                extractors.push("upgrade: axum::extract::ws::WebSocketUpgrade".to_string());
+            }
+            if has_path {
+                extractors.push(
+                    "axum::extract::Path(path): axum::extract::Path<HashMap<String, String>>"
+                        .to_string(),
+                );
+            }
+            if endpoint.auth {
+                extractors.push(
+                    "axum::extract::Query(query): axum::extract::Query<HashMap<String, String>>"
+                        .to_string(),
+                );
+                extractors.push("headers: axum::http::HeaderMap".to_string());
+            }
+            code.push_str(&format!("pub async fn {}(\n", handler));
+            for extractor in extractors {
+                code.push_str(&format!("    {},\n", extractor));
+            }
+            code.push_str(") -> axum::response::Response {\n");
+        }
+
+        // Authentifizierung vor dem Upgrade, damit abgelehnte Clients einen Statuscode bekommen
+        if endpoint.auth {
+            let roles: Vec<String> = endpoint.roles.iter().map(|r| format!("{:?}", r)).collect();
+            code.push_str(&format!(
+                "    let user = match authenticate(bearer(&{}), query.get(\"token\").map(String::as_str), &[{}]) {{\n        Ok(user) => user,\n        Err(rejection) => return reject(rejection),\n    }};\n",
+                if actix { "request" } else { "headers" },
+                roles.join(", ")
+            ));
+        } else {
+            code.push_str("    let user: Option<String> = None;\n");
+        }
+        for (name, ty) in &endpoint.path_params {
+            let rust_type = match ty {
+                Type::Number => "f64",
+                Type::Boolean => "bool",
+                _ => "String",
+            };
+            code.push_str(&format!(
+                "    let __{}: {} = match path_value(&path, {:?}) {{\n        Ok(value) => value,\n        Err(rejection) => return reject(rejection),\n    }};\n",
+                rust_name(name),
+                rust_type,
+                name
+            ));
+        }
+
+        let spawn = if actix {
+            "actix_web::rt::spawn"
+        } else {
+            "tokio::spawn"
+        };
+        let call = |function: &str, args: &[Arg], is_async: bool| {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| match arg {
+                    Arg::Connection => "__conn.clone()".to_string(),
+                    Arg::Message => "__message".to_string(),
+                    Arg::Path(name) => format!("__{}.clone()", rust_name(name)),
+                })
+                .collect();
+            format!(
+                "super::super::{}({}){}",
+                rust_name(function),
+                args.join(", "),
+                if is_async { ".await" } else { "" }
+            )
+        };
+        let clones: String = endpoint
+            .path_params
+            .iter()
+            .map(|(name, _)| {
+                let name = rust_name(name);
+                format!(" let __{} = __{}.clone();", name, name)
+            })
+            .collect();
+        let on_close = match &endpoint.on_disconnect {
+            None => "None".to_string(),
+            Some(hook) if hook.is_async => format!(
+                "Some(Box::new({{ let __conn = __conn.clone();{} move || {{ {}(async move {{ {}; }}); }} }}))",
+                clones,
+                spawn,
+                call(&hook.function, &hook.args, true)
+            ),
+            Some(hook) => format!(
+                "Some(Box::new({{ let __conn = __conn.clone();{} move || {{ {}; }} }}))",
+                clones,
+                call(&hook.function, &hook.args, false)
+            ),
+        };
+        let open = format!(
+            "let (__conn, inbox) = open::<{}>(user);",
+            type_name(&endpoint.outgoing)
+        );
+        let on_connect = endpoint
+            .on_connect
+            .as_ref()
+            .map(|hook| format!("{};", call(&hook.function, &hook.args, hook.is_async)));
+
+        match endpoint.kind {
+            EndpointKind::WebSocket => {
+                let invocation = call(&endpoint.function, &endpoint.args, endpoint.is_async);
+                let body = if endpoint.replies {
+                    format!(
+                        "let __reply = {};\n                __conn.send(&__reply);",
+                        invocation
+                    )
+                } else {
+                    format!("{};", invocation)
+                };
+                let on_text = format!(
+                    "move |text: String| {{\n            let __conn = __conn.clone();{}\n            async move {{\n                let __message: {} = decode(&text)?;\n                {}\n                Ok(())\n            }}\n        }}",
+                    clones,
+                    type_name(endpoint.incoming.as_deref().unwrap_or_default()),
+                    body
+                );
+                let mut session = format!(
+                    "        let _session = Session::new(&__conn, {});\n",
+                    on_close
+                );
+                if let Some(on_connect) = &on_connect {
+                    session.push_str(&format!("        {}\n", on_connect));
+                }
+                if actix {
+                    code.push_str("    let (response, socket, stream) = match actix_ws::handle(&request, body) {\n        Ok(parts) => parts,\n        Err(error) => return error.error_response(),\n    };\n");
+                    code.push_str(&format!("    {}\n", open));
+                    code.push_str(&format!("    {}(async move {{\n", spawn));
+                    code.push_str(&session);
+                    code.push_str(&format!(
+                        "        serve_socket(socket, stream, inbox, {}).await;\n    }});\n    response\n}}\n\n",
+                        on_text
+                    ));
+                } else {
+                    code.push_str("    upgrade.on_upgrade(move |socket| async move {\n");
+                    code.push_str(&format!("        {}\n", open));
+                    code.push_str(&session);
+                    code.push_str(&format!(
+                        "        serve_socket(socket, inbox, {}).await;\n    }})\n}}\n\n",
+                        on_text
+                    ));
+                }
+            }
+            EndpointKind::Sse => {
+                code.push_str(&format!("    {}\n", open));
+                code.push_str(&format!(
+                    "    let session = Session::new(&__conn, {});\n",
+                    on_close
+                ));
+                if let Some(on_connect) = &on_connect {
+                    code.push_str(&format!("    {}\n", on_connect));
+                }
+                code.push_str(&format!(
+                    "    {};\n    event_stream(inbox, session)\n}}\n\n",
+                    call(&endpoint.function, &endpoint.args, endpoint.is_async)
+                ));
+            }
+        }
+        code
+    }
+
+    pub fn generate_broadcast_code(room: &str, message: &str) -> String {
+        format!("{}::broadcast(&{}, &{})", RUNTIME, room, message)
+    }
+
+    pub fn generate_members_code(room: &str) -> String {
+        format!("{}::members(&{})", RUNTIME, room)
+    }
+
+    /// Methoden von `Connection<T>`; `None` für unbekannte Methoden
+    pub fn generate_connection_call(
+        connection: &str,
+        method: &str,
+        args: &[String],
+    ) -> Option<String> {
+        match (method, args) {
+            ("send", [message]) => Some(format!("{}.send(&{})", connection, message)),
+            ("broadcast", [room, message]) => {
+                Some(format!("{}.broadcast(&{}, &{})", connection, room, message))
+            }
+            ("join" | "leave", [room]) => Some(format!("{}.{}(&{})", connection, method, room)),
+            ("close", []) => Some(format!("{}.close()", connection)),
+            _ => None,
+        }
+    }
+}
+
+/// Runtime-Vorlage; Platzhalter werden in `generate_realtime_runtime_code` ersetzt
+const RUNTIME_TEMPLATE: &str = r#"
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+pub mod hub {
+/*HUB*/
+}
+
+pub use hub::{broadcast, members, Connection, Outgoing};
+use hub::{decode, open, Session};
+
+/// Close-Begründungen sind auf 123 Bytes begrenzt
+fn close_reason(mut reason: String) -> String {
+    while reason.len() > 123 {
+        reason.pop();
+    }
+    reason
+}
+
+fn path_value<V: std::str::FromStr>(values: &HashMap<String, String>, name: &str) -> Result<V, (u16, String)> {
+    values
+        .get(name)
+        .and_then(|value| value.parse().ok())
+        .ok_or_else(|| (400, format!("invalid path parameter '{}'", name)))
+}
+
+/*AUTH*/
+/*SERVER*/
+/*ENDPOINTS*/
+"#;
+
+/// JWT-Prüfung für `@Auth`/`@Role`
+const AUTH: &str = r#"
+#[derive(serde::Deserialize)]
+struct TokenClaims {
+    sub: String,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+/// Prüft das JWT aus `Authorization: Bearer ...` oder `?token=...`; Browser können
+/// beim WebSocket-Handshake und bei `EventSource` keine Header setzen
+fn authenticate(authorization: Option<&str>, token: Option<&str>, roles: &[&str]) -> Result<Option<String>, (u16, String)> {
+    let token = authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(token)
+        .ok_or_else(|| (401, "missing bearer token".to_string()))?;
+    let secret = std::env::var("JWT_SECRET").map_err(|_| (500, "JWT_SECRET is not set".to_string()))?;
+    let claims = jsonwebtoken::decode::<TokenClaims>(
+        token,
+        &jsonwebtoken::DecodingKey::from_secret(secret.as_bytes()),
+        &jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256),
+    )
+    .map_err(|e| (401, format!("invalid token: {}", e)))?
+    .claims;
+    if !roles.is_empty() && !claims.roles.iter().any(|role| roles.contains(&role.as_str())) {
+        return Err((403, format!("requires one of the roles: {}", roles.join(", "))));
+    }
+    Ok(Some(claims.sub))
+}
+"#;
+
+const AXUM_SERVER: &str = r#"
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use axum::response::IntoResponse;
+use futures_util::{SinkExt, StreamExt};
+
+fn reject((status, message): (u16, String)) -> axum::response::Response {
+    let status = axum::http::StatusCode::from_u16(status)
+        .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    (status, axum::Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+fn bearer(headers: &axum::http::HeaderMap) -> Option<&str> {
+    headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn close_frame(code: u16, reason: String) -> Message {
+    Message::Close(Some(CloseFrame {
+        code,
+        reason: close_reason(reason).into(),
+    }))
+}
+
+/// Leitet Nachrichten zwischen Socket und Outbox weiter, bis eine Seite schließt
+async fn serve_socket<F, Fut>(socket: WebSocket, mut inbox: UnboundedReceiver<Outgoing>, mut on_text: F)
+where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    let (mut sink, mut stream) = socket.split();
+    loop {
+        tokio::select! {
+            outgoing = inbox.recv() => match outgoing {
+                Some(Outgoing::Text(text)) => {
+                    if sink.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Outgoing::Close(code, reason)) => {
+                    let _ = sink.send(close_frame(code, reason)).await;
+                    break;
+                }
+                Some(Outgoing::Flush(done)) => {
+                    let _ = done.send(());
+                }
+                None => break,
+            },
+            incoming = stream.next() => match incoming {
+                Some(Ok(Message::Text(text))) => {
+                    if let Err(reason) = on_text(text).await {
+                        let _ = sink.send(close_frame(1007, reason)).await;
+                        break;
+                    }
+                }
+                Some(Ok(Message::Binary(_))) => {
+                    let _ = sink.send(close_frame(1003, "binary messages are not supported".to_string())).await;
+                    break;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+fn event_stream(inbox: UnboundedReceiver<Outgoing>, session: Session) -> axum::response::Response {
+    let events = futures_util::stream::unfold((inbox, session), |(mut inbox, session)| async move {
+        loop {
+            match inbox.recv().await {
+                Some(Outgoing::Text(text)) => {
+                    let event = axum::response::sse::Event::default().data(text);
+                    return Some((Ok::<_, std::convert::Infallible>(event), (inbox, session)));
+                }
+                Some(Outgoing::Flush(done)) => {
+                    let _ = done.send(());
+                }
+                _ => return None,
+            }
+        }
+    });
+    axum::response::sse::Sse::new(events)
+        .keep_alive(axum::response::sse::KeepAlive::default())
+        .into_response()
+}
+"#;
+
+const ACTIX_SERVER: &str = r#"
+use actix_web::ResponseError;
+
+/// Kommentarzeile gegen Proxy-Timeouts bei ruhigen SSE-Streams
+const KEEP_ALIVE: std::time::Duration = std::time::Duration::from_secs(15);
+
+fn reject((status, message): (u16, String)) -> actix_web::HttpResponse {
+    let status = actix_web::http::StatusCode::from_u16(status)
+        .unwrap_or(actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+    actix_web::HttpResponse::build(status).json(serde_json::json!({ "error": message }))
+}
+
+fn bearer(request: &actix_web::HttpRequest) -> Option<&str> {
+    request
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+}
+
+fn path_values(request: &actix_web::HttpRequest) -> HashMap<String, String> {
+    request
+        .match_info()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn query_values(request: &actix_web::HttpRequest) -> HashMap<String, String> {
+    actix_web::web::Query::<HashMap<String, String>>::from_query(request.query_string())
+        .map(|query| query.into_inner())
+        .unwrap_or_default()
+}
+
+/// Leitet Nachrichten zwischen Socket und Outbox weiter, bis eine Seite schließt
+async fn serve_socket<F, Fut>(
+    mut socket: actix_ws::Session,
+    mut stream: actix_ws::MessageStream,
+    mut inbox: UnboundedReceiver<Outgoing>,
+    mut on_text: F,
+) where
+    F: FnMut(String) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    let close = loop {
+        tokio::select! {
+            outgoing = inbox.recv() => match outgoing {
+                Some(Outgoing::Text(text)) => {
+                    if socket.text(text).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Outgoing::Close(code, reason)) => break Some((code, reason)),
+                Some(Outgoing::Flush(done)) => {
+                    let _ = done.send(());
+                }
+                None => break None,
+            },
+            incoming = stream.recv() => match incoming {
+                Some(Ok(actix_ws::Message::Text(text))) => {
+                    if let Err(reason) = on_text(text.to_string()).await {
+                        break Some((1007, reason));
+                    }
+                }
+                Some(Ok(actix_ws::Message::Binary(_))) => {
+                    break Some((1003, "binary messages are not supported".to_string()))
+                }
+                Some(Ok(actix_ws::Message::Ping(bytes))) => {
+                    if socket.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(actix_ws::Message::Close(_))) | Some(Err(_)) | None => break None,
+                Some(Ok(_)) => {}
+            },
+        }
+    };
+    let reason = close.map(|(code, reason)| actix_ws::CloseReason {
+        code: code.into(),
+        description: Some(close_reason(reason)),
+    });
+    let _ = socket.close(reason).await;
+}
+
+fn event_stream(inbox: UnboundedReceiver<Outgoing>, session: Session) -> actix_web::HttpResponse {
+    let events = futures_util::stream::unfold((inbox, session), |(mut inbox, session)| async move {
+        let chunk = loop {
+            tokio::select! {
+                outgoing = inbox.recv() => match outgoing {
+                    Some(Outgoing::Text(text)) => break format!("data: {}\n\n", text),
+                    Some(Outgoing::Flush(done)) => {
+                        let _ = done.send(());
+                    }
+                    _ => return None,
+                },
+                _ = tokio::time::sleep(KEEP_ALIVE) => break ":\n\n".to_string(),
+            }
+        };
+        Some((Ok::<_, actix_web::Error>(actix_web::web::Bytes::from(chunk)), (inbox, session)))
+    });
+    actix_web::HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(events)
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::hub::{broadcast, decode, members, open, Outgoing, Session};
+    use super::*;
+    use crate::parser::parser::Parser;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    #[test]
+    fn test_endpoints_and_hooks_are_validated_and_generated() {
+        let source = r#"struct ChatMessage {
+    text: string,
+}
+
+struct NewsItem {
+    title: string,
+}
+
+@Auth
+@Role("member")
+@WebSocket("/chat/:room")
+fn chat(conn: Connection<ChatMessage>, message: ChatMessage, room: string): ChatMessage {
+    return message;
+}
+
+@OnConnect("/chat/:room")
+fn joined(conn: Connection<ChatMessage>, room: string) {
+    conn.join(room);
+}
+
+@OnDisconnect("/chat/:room")
+async fn left(room: string) {
+    let x = room;
+}
+
+@SSE("/news")
+fn news(conn: Connection<NewsItem>) {
+    conn.join("news");
+}
+
+@SSE("/news")
+fn moreNews(conn: Connection<NewsItem>) {
+    conn.join("news");
+}
+
+@SSE("/feed/:id")
+fn feed(conn: Connection<NewsItem>, message: NewsItem) {
+    let x = message;
+}
+
+@WebSocket("/echo")
+fn echo(conn: Connection<ChatMessage>, message: ChatMessage): NewsItem {
+    return NewsItem { title: message.text };
+}
+
+@OnConnect("/chat/:room")
+fn joinedAgain(conn: Connection<NewsItem>) {
+    let x = conn;
+}
+
+@OnConnect("/missing")
+fn nowhere(conn: Connection<ChatMessage>) {
+    let x = conn;
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        assert!(uses_realtime(&program));
+        let (catalog, problems) = RealtimeCatalog::build(&program.items);
+
+        let problems: Vec<String> = problems
+            .iter()
+            .map(|(target, reason)| format!("{}: {}", target, reason))
+            .collect();
+        assert_eq!(problems.len(), 5, "{:?}", problems);
+        assert!(problems[0].contains("path '/news' is also served by news"));
+        assert!(problems[1].contains("parameter 'message' is not a path parameter of '/feed/:id'"));
+        assert!(problems[2].contains("replies must be ChatMessage, found NewsItem"));
+        assert!(problems[3].contains("takes Connection<NewsItem>, but chat sends ChatMessage"));
+        assert!(problems[4].contains("no @WebSocket or @SSE endpoint serves '/missing'"));
+
+        assert_eq!(catalog.endpoints.len(), 2);
+        let chat = &catalog.endpoints[0];
+        assert_eq!(chat.kind, EndpointKind::WebSocket);
+        assert_eq!(
+            chat.args,
+            vec![Arg::Connection, Arg::Message, Arg::Path("room".to_string())]
+        );
+        assert_eq!(chat.incoming.as_deref(), Some("ChatMessage"));
+        assert!(chat.replies && chat.auth);
+        assert_eq!(chat.roles, vec!["member".to_string()]);
+        assert_eq!(chat.on_connect.as_ref().unwrap().function, "joined");
+        assert!(chat.on_disconnect.as_ref().unwrap().is_async);
+
+        let name = |name: &str| name.to_string();
+        let axum =
+            RealtimeStdlib::generate_realtime_runtime_code(&catalog, Framework::Axum, &name, &name);
+        assert!(axum.contains(".route(\"/chat/:room\", axum::routing::get(chat_endpoint))"));
+        assert!(axum.contains("&[\"member\"]"));
+        assert!(axum.contains(
+            "let __reply = super::super::chat(__conn.clone(), __message, __room.clone());"
+        ));
+        assert!(
+            axum.contains("tokio::spawn(async move { super::super::left(__room.clone()).await; })")
+        );
+        assert!(axum.contains("event_stream(inbox, session)"));
+
+        let actix = RealtimeStdlib::generate_realtime_runtime_code(
+            &catalog,
+            Framework::Actix,
+            &name,
+            &name,
+        );
+        assert!(actix
+            .contains("config.route(\"/chat/{room}\", actix_web::web::get().to(chat_endpoint));"));
+        assert!(actix.contains("actix_ws::handle(&request, body)"));
+        assert!(!actix.contains("axum::"));
+
+        assert_eq!(
+            RealtimeStdlib::generate_connection_call(
+                "conn",
+                "broadcast",
+                &[name("room"), name("message")]
+            ),
+            Some("conn.broadcast(&room, &message)".to_string())
+        );
+        assert!(check_call(
+            "broadcast",
+            &[
+                Expression::Literal(Literal::String(" ".to_string())),
+                Expression::Identifier("message".to_string()),
+            ],
+        )
+        .is_err());
+    }
+
+    /// Bisher an eine Verbindung geschickte Texte
+    fn received(inbox: &mut UnboundedReceiver<Outgoing>) -> Vec<String> {
+        std::iter::from_fn(|| inbox.try_recv().ok())
+            .filter_map(|message| match message {
+                Outgoing::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rooms_broadcast_and_clean_up_on_disconnect() {
+        let room = "hub-test";
+        let (alice, mut alice_inbox) = open::<serde_json::Value>(Some("alice".to_string()));
+        let (bob, mut bob_inbox) = open::<serde_json::Value>(None);
+        let alice_session = Session::new(&alice, None);
+        let disconnected = Arc::new(AtomicBool::new(false));
+        let on_close = disconnected.clone();
+        let bob_session = Session::new(
+            &bob,
+            Some(Box::new(move || on_close.store(true, Ordering::SeqCst))),
+        );
+        alice.join(room);
+        bob.join(room);
+        assert_eq!(members(room), 2.0);
+
+        // `conn.broadcast` lässt den Absender aus, `realtime.broadcast` nicht
+        assert_eq!(alice.broadcast(room, &serde_json::json!("hi")), 1.0);
+        assert_eq!(received(&mut bob_inbox), ["\"hi\""]);
+        assert!(received(&mut alice_inbox).is_empty());
+        assert_eq!(broadcast(room, &serde_json::json!({ "n": 1 })), 2.0);
+        assert_eq!(received(&mut alice_inbox), [r#"{"n":1}"#]);
+        assert_eq!(received(&mut bob_inbox), [r#"{"n":1}"#]);
+
+        bob.leave(room);
+        assert_eq!(members(room), 1.0);
+        bob.join(room);
+        drop(bob_session);
+        assert!(disconnected.load(Ordering::SeqCst));
+        assert_eq!(members(room), 1.0);
+        assert_eq!(broadcast(room, &serde_json::json!("bye")), 1.0);
+
+        alice.close();
+        assert!(matches!(alice_inbox.try_recv(), Ok(Outgoing::Text(_))));
+        assert!(matches!(
+            alice_inbox.try_recv(),
+            Ok(Outgoing::Close(1000, _))
+        ));
+        drop(alice_session);
+        assert_eq!(members(room), 0.0);
+
+        assert!(decode::<serde_json::Value>("{")
+            .unwrap_err()
+            .starts_with("invalid message:"));
+    }
+}
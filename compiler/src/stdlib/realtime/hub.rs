@@ -0,0 +1,203 @@
+// Verbindungen und Räume für `realtime`: Beitreten, Verlassen, Broadcasts und
+// das Aufräumen beim Schließen einer Verbindung.
+// Die Datei wird unverändert in das generierte Modul `stdlib::realtime`
+// eingebettet; die Endpunkte für axum bzw. actix kommen dazu.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Nachricht an eine Verbindung
+#[derive(Debug)]
+pub enum Outgoing {
+    Text(String),
+    /// Close-Code und Begründung; SSE-Streams enden einfach
+    Close(u16, String),
+    /// Bestätigt, sobald alle vorherigen Nachrichten geschrieben sind
+    Flush(tokio::sync::oneshot::Sender<()>),
+}
+
+#[derive(Default)]
+struct Hub {
+    next: u64,
+    connections: HashMap<u64, UnboundedSender<Outgoing>>,
+    rooms: HashMap<String, BTreeSet<u64>>,
+}
+
+fn hub() -> MutexGuard<'static, Hub> {
+    static HUB: OnceLock<Mutex<Hub>> = OnceLock::new();
+    HUB.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Eine offene WebSocket- oder SSE-Verbindung; `T` ist die Nachricht an den Client
+pub struct Connection<T> {
+    pub id: String,
+    /// `sub` des JWT bei Endpunkten mit `@Auth`
+    pub user: Option<String>,
+    key: u64,
+    outbox: UnboundedSender<Outgoing>,
+    message: std::marker::PhantomData<fn(T)>,
+}
+
+impl<T> Clone for Connection<T> {
+    fn clone(&self) -> Self {
+        Connection {
+            id: self.id.clone(),
+            user: self.user.clone(),
+            key: self.key,
+            outbox: self.outbox.clone(),
+            message: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Connection<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Connection")
+            .field("id", &self.id)
+            .field("user", &self.user)
+            .finish()
+    }
+}
+
+impl<T> Connection<T> {
+    pub fn join<R: AsRef<str>>(&self, room: R) {
+        hub()
+            .rooms
+            .entry(room.as_ref().to_string())
+            .or_default()
+            .insert(self.key);
+    }
+
+    pub fn leave<R: AsRef<str>>(&self, room: R) {
+        let mut hub = hub();
+        if let Some(members) = hub.rooms.get_mut(room.as_ref()) {
+            members.remove(&self.key);
+            if members.is_empty() {
+                hub.rooms.remove(room.as_ref());
+            }
+        }
+    }
+
+    pub fn close(&self) {
+        let _ = self.outbox.send(Outgoing::Close(1000, String::new()));
+    }
+
+    /// Wartet, bis die Verbindung geschlossen ist
+    pub async fn closed(&self) {
+        self.outbox.closed().await
+    }
+
+    /// Wartet, bis alles bisher Gesendete geschrieben ist; `false`, wenn die Verbindung zu ist
+    pub async fn flushed(&self) -> bool {
+        let (done, written) = tokio::sync::oneshot::channel();
+        self.outbox.send(Outgoing::Flush(done)).is_ok() && written.await.is_ok()
+    }
+}
+
+impl<T: serde::Serialize> Connection<T> {
+    pub fn send(&self, message: &T) {
+        if let Some(text) = encode(message) {
+            let _ = self.outbox.send(Outgoing::Text(text));
+        }
+    }
+
+    /// An alle anderen Mitglieder des Raums; Ergebnis ist die Anzahl der Empfänger
+    pub fn broadcast<R: AsRef<str>>(&self, room: R, message: &T) -> f64 {
+        deliver(room.as_ref(), message, Some(self.key))
+    }
+}
+
+/// An alle Mitglieder des Raums; Ergebnis ist die Anzahl der Empfänger
+pub fn broadcast<R: AsRef<str>, M: serde::Serialize>(room: R, message: &M) -> f64 {
+    deliver(room.as_ref(), message, None)
+}
+
+pub fn members<R: AsRef<str>>(room: R) -> f64 {
+    hub()
+        .rooms
+        .get(room.as_ref())
+        .map_or(0, |members| members.len()) as f64
+}
+
+fn deliver<M: serde::Serialize>(room: &str, message: &M, except: Option<u64>) -> f64 {
+    let Some(text) = encode(message) else {
+        return 0.0;
+    };
+    let hub = hub();
+    let mut sent = 0;
+    for key in hub.rooms.get(room).into_iter().flatten() {
+        if Some(*key) == except {
+            continue;
+        }
+        if let Some(outbox) = hub.connections.get(key) {
+            if outbox.send(Outgoing::Text(text.clone())).is_ok() {
+                sent += 1;
+            }
+        }
+    }
+    sent as f64
+}
+
+fn encode<M: serde::Serialize>(message: &M) -> Option<String> {
+    serde_json::to_string(message)
+        .map_err(|e| eprintln!("realtime: message could not be serialized: {}", e))
+        .ok()
+}
+
+pub fn decode<M: serde::de::DeserializeOwned>(text: &str) -> Result<M, String> {
+    serde_json::from_str(text).map_err(|e| format!("invalid message: {}", e))
+}
+
+/// Registriert eine Verbindung; was an sie gesendet wird, kommt über den Receiver
+pub fn open<T>(user: Option<String>) -> (Connection<T>, UnboundedReceiver<Outgoing>) {
+    let (outbox, inbox) = unbounded_channel();
+    let mut hub = hub();
+    hub.next += 1;
+    let key = hub.next;
+    hub.connections.insert(key, outbox.clone());
+    let connection = Connection {
+        id: key.to_string(),
+        user,
+        key,
+        outbox,
+        message: std::marker::PhantomData,
+    };
+    (connection, inbox)
+}
+
+/// Lebensdauer einer Verbindung: beim Drop verlässt sie alle Räume, danach läuft `@OnDisconnect`
+pub struct Session {
+    key: u64,
+    on_close: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Session {
+    pub fn new<T>(
+        connection: &Connection<T>,
+        on_close: Option<Box<dyn FnOnce() + Send>>,
+    ) -> Session {
+        Session {
+            key: connection.key,
+            on_close,
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        {
+            let mut hub = hub();
+            hub.connections.remove(&self.key);
+            hub.rooms.retain(|_, members| {
+                members.remove(&self.key);
+                !members.is_empty()
+            });
+        }
+        if let Some(on_close) = self.on_close.take() {
+            on_close();
+        }
+    }
+}
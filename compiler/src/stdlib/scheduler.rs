@@ -1,105 +1,938 @@
+// Scheduler Standard Library
+// Cron- und Intervall-Jobs in generierten Services: Jobs rufen Velin-Funktionen
+// (Name oder `@Scheduled`) bzw. Lambdas auf einem Hintergrund-Thread auf
+
+pub mod schedule;
+
+use crate::parser::ast::*;
+use crate::parser::visitor::{visit_block, visit_expression};
+use crate::stdlib::workflow::decorator_value;
+use schedule::JobSpec;
+use std::collections::BTreeSet;
+
 pub struct SchedulerStdlib;
 
+/// Pfad des generierten Runtime-Moduls
+const RUNTIME: &str = "crate::stdlib::scheduler";
+
+/// Quelltext von `schedule.rs`, wird in die Runtime eingebettet
+const SCHEDULE_SOURCE: &str = include_str!("scheduler/schedule.rs");
+
+/// Funktion, die ein Job aufrufen kann
+#[derive(Debug, Clone)]
+pub struct TaskFunction {
+    pub name: String,
+    pub params: Vec<String>,
+    pub is_async: bool,
+    pub returns_result: bool,
+}
+
+/// Was ein Programm von `scheduler.*` braucht
+#[derive(Debug, Clone, Default)]
+pub struct SchedulerUsage {
+    pub tasks: Vec<TaskFunction>,
+    /// `@Scheduled`-Funktionen mit ihren Job-Optionen
+    pub scheduled: Vec<(String, serde_json::Value)>,
+}
+
+impl SchedulerUsage {
+    /// `None`, wenn das Programm weder `scheduler.*` noch `@Scheduled` verwendet
+    pub fn collect(program: &Program) -> Option<SchedulerUsage> {
+        let mut used = false;
+        let mut referenced = BTreeSet::new();
+        let mut usage = SchedulerUsage::default();
+
+        let mut inspect = |expr: &Expression| {
+            let Some((method, args)) = scheduler_call(expr) else {
+                return;
+            };
+            used = true;
+            if matches!(
+                method,
+                "schedule" | "schedule_interval" | "scheduleInterval"
+            ) {
+                match args.first() {
+                    Some(Expression::Literal(Literal::String(name))) => {
+                        referenced.insert(name.clone());
+                    }
+                    Some(Expression::MapLiteral(fields)) => {
+                        for (key, value) in fields {
+                            if let ("run" | "name", Expression::Literal(Literal::String(name))) =
+                                (key.as_str(), value)
+                            {
+                                referenced.insert(name.clone());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        };
+        for item in &program.items {
+            match item {
+                Item::Function(f) => visit_block(&f.body, &mut inspect),
+                Item::TopLevelCode(code) => visit_expression(&code.expression, &mut inspect),
+                _ => {}
+            }
+        }
+
+        for item in &program.items {
+            let Item::Function(function) = item else {
+                continue;
+            };
+            if let Some(Ok(spec)) = scheduled_spec(function) {
+                used = true;
+                referenced.insert(function.name.clone());
+                usage.scheduled.push((function.name.clone(), spec));
+            }
+            if referenced.contains(&function.name) {
+                usage.tasks.push(TaskFunction {
+                    name: function.name.clone(),
+                    params: function.params.iter().map(|p| p.name.clone()).collect(),
+                    is_async: function.is_async,
+                    returns_result: function.return_type.as_ref().is_some_and(is_result),
+                });
+            }
+        }
+        if used {
+            Some(usage)
+        } else {
+            None
+        }
+    }
+}
+
+fn scheduler_call(expr: &Expression) -> Option<(&str, &[Expression])> {
+    let Expression::Call { callee, args } = expr else {
+        return None;
+    };
+    let Expression::Member { object, member } = callee.as_ref() else {
+        return None;
+    };
+    match object.as_ref() {
+        Expression::Identifier(name) if name == "scheduler" => Some((member.as_str(), args)),
+        _ => None,
+    }
+}
+
+fn is_result(ty: &Type) -> bool {
+    match ty {
+        Type::Result { .. } => true,
+        Type::Generic { name, .. } => name == "Result",
+        _ => false,
+    }
+}
+
+/// Job-Optionen aus `@Scheduled("0 */5 * * * *", timezone: "Europe/Berlin", ...)`
+///
+/// `None` ohne `@Scheduled`; `Err` mit Begründung, wenn die Optionen ungültig sind.
+pub fn scheduled_spec(function: &Function) -> Option<Result<serde_json::Value, String>> {
+    let decorator = function.decorators.iter().find(|d| d.name == "Scheduled")?;
+    let mut spec = serde_json::Map::new();
+    for arg in &decorator.args {
+        match arg {
+            DecoratorArg::Named { name, .. } if name == "run" => {
+                return Some(Err(
+                    "'run' is not allowed, the decorated function is run".into()
+                ));
+            }
+            DecoratorArg::Named { name, value } => {
+                spec.insert(name.clone(), decorator_value(value));
+            }
+            DecoratorArg::String(cron) if !spec.contains_key("cron") => {
+                spec.insert("cron".to_string(), serde_json::json!(cron));
+            }
+            _ => {
+                return Some(Err(
+                    "expected a cron expression followed by named options".into()
+                ))
+            }
+        }
+    }
+    spec.entry("name")
+        .or_insert_with(|| serde_json::json!(function.name));
+    spec.insert("run".to_string(), serde_json::json!(function.name));
+    let spec = serde_json::Value::Object(spec);
+
+    if let Err(error) = JobSpec::parse(&spec) {
+        return Some(Err(error));
+    }
+    if !function.params.is_empty() && spec.get("input").is_none() {
+        return Some(Err(format!(
+            "'{}' takes parameters, pass them with 'input'",
+            function.name
+        )));
+    }
+    Some(Ok(spec))
+}
+
+/// Prüft `scheduler.schedule(task, cron)` bzw. `schedule_interval(task, every)`,
+/// soweit die Argumente Literale sind
+pub fn check_call(
+    method: &str,
+    args: &[Expression],
+    is_function: &dyn Fn(&str) -> bool,
+) -> Result<(), String> {
+    let key = match method {
+        "schedule" => "cron",
+        "schedule_interval" | "scheduleInterval" => "every",
+        _ => return Ok(()),
+    };
+    let [task, schedule] = args else {
+        return Ok(());
+    };
+    let Some(schedule) = literal_json(schedule) else {
+        return Ok(());
+    };
+    let mut spec = match task {
+        Expression::Literal(Literal::String(name)) => serde_json::json!({ "run": name }),
+        Expression::Lambda { params, .. } if params.is_empty() => serde_json::json!({}),
+        Expression::Lambda { .. } => return Err("task lambdas cannot take parameters".into()),
+        Expression::MapLiteral(fields) => {
+            let mut spec = serde_json::Map::new();
+            for (key, value) in fields {
+                let value = match (key.as_str(), literal_json(value)) {
+                    ("input", _) => serde_json::Value::Null,
+                    (_, Some(value)) => value,
+                    (_, None) => return Ok(()),
+                };
+                spec.insert(key.clone(), value);
+            }
+            serde_json::Value::Object(spec)
+        }
+        _ => return Ok(()),
+    };
+    spec[key] = schedule;
+    let job = JobSpec::parse(&spec)?;
+
+    if matches!(task, Expression::Lambda { .. }) {
+        return Ok(());
+    }
+    match job.run.or(job.name) {
+        Some(name) if is_function(&name) => Ok(()),
+        Some(name) => Err(format!("unknown task function '{}'", name)),
+        None => Err("the task needs 'run' with a function name".into()),
+    }
+}
+
+fn literal_json(expr: &Expression) -> Option<serde_json::Value> {
+    match expr {
+        Expression::Literal(Literal::String(s)) => Some(serde_json::json!(s)),
+        Expression::Literal(Literal::Number(n)) => Some(serde_json::json!(n)),
+        Expression::Literal(Literal::Boolean(b)) => Some(serde_json::json!(b)),
+        Expression::Literal(Literal::Null) => Some(serde_json::Value::Null),
+        _ => None,
+    }
+}
+
 impl SchedulerStdlib {
+    /// Runtime-Modul `stdlib::scheduler` inklusive Task-Registry
+    pub fn generate_scheduler_runtime_code(
+        usage: &SchedulerUsage,
+        rust_name: &dyn Fn(&str) -> String,
+    ) -> String {
+        RUNTIME_TEMPLATE
+            .replace("/*SCHEDULE*/\n", SCHEDULE_SOURCE)
+            .replace("/*REGISTRY*/\n", &Self::generate_registry(usage, rust_name))
+    }
+
+    fn generate_registry(usage: &SchedulerUsage, rust_name: &dyn Fn(&str) -> String) -> String {
+        let names: Vec<String> = usage
+            .tasks
+            .iter()
+            .map(|task| format!("{:?}", task.name))
+            .collect();
+
+        let mut calls = String::new();
+        for task in &usage.tasks {
+            let mut call = format!("        {:?} => {{\n", task.name);
+            let mut args = Vec::new();
+            for (index, param) in task.params.iter().enumerate() {
+                let value = if task.params.len() == 1 {
+                    "input.clone()".to_string()
+                } else {
+                    format!(
+                        "input.get({:?}).cloned().unwrap_or(serde_json::Value::Null)",
+                        param
+                    )
+                };
+                call.push_str(&format!(
+                    "            let arg{} = serde_json::from_value({}).map_err(|e| format!(\"invalid input for task '{}': {{}}\", e))?;\n",
+                    index, value, task.name
+                ));
+                args.push(format!("arg{}", index));
+            }
+            let mut invocation = format!(
+                "super::super::{}({})",
+                rust_name(&task.name),
+                args.join(", ")
+            );
+            if task.is_async {
+                invocation = format!("block_on({})", invocation);
+            }
+            call.push_str(&format!(
+                "            {}({})\n        }}\n",
+                if task.returns_result {
+                    "task_result"
+                } else {
+                    "task_output"
+                },
+                invocation
+            ));
+            calls.push_str(&call);
+        }
+
+        let scheduled: String = usage
+            .scheduled
+            .iter()
+            .map(|(name, spec)| format!("        ({:?}, serde_json::json!({})),\n", name, spec))
+            .collect();
+
+        format!(
+            "const TASKS: &[&str] = &[{}];\n\nfn call_task(name: &str, input: serde_json::Value) -> Result<(), String> {{\n    match name {{\n{}        _ => Err(format!(\"unknown scheduler task '{{}}'\", name)),\n    }}\n}}\n\n/// `@Scheduled`-Funktionen mit ihren Optionen\nfn scheduled_jobs() -> Vec<(&'static str, serde_json::Value)> {{\n    vec![\n{}    ]\n}}\n",
+            names.join(", "),
+            calls,
+            scheduled
+        )
+    }
+
     pub fn generate_schedule_code(task: &str, cron: &str) -> String {
+        format!("{}::schedule({}, &{})", RUNTIME, task, cron)
+    }
+
+    pub fn generate_schedule_fn_code(task: &str, cron: &str) -> String {
         format!(
-            "{{
-                let task_data: serde_json::Value = {};
-                let cron_expr = {};
-                let task_id = uuid::Uuid::new_v4().to_string();
-                serde_json::json!({{
-                    \"id\": task_id,
-                    \"task\": task_data,
-                    \"cron\": cron_expr,
-                    \"enabled\": true,
-                    \"next_run\": chrono::Utc::now().to_rfc3339(),
-                    \"created_at\": chrono::Utc::now().to_rfc3339()
-                }})
-            }}",
-            task, cron
+            "{}::schedule_fn(move || {{ let _ = ({})(); }}, &{})",
+            RUNTIME, task, cron
         )
     }
 
     pub fn generate_schedule_interval_code(task: &str, interval: &str) -> String {
         format!(
-            "{{
-                let task_data: serde_json::Value = {};
-                let interval_ms = {} as u64;
-                let task_id = uuid::Uuid::new_v4().to_string();
-                serde_json::json!({{
-                    \"id\": task_id,
-                    \"task\": task_data,
-                    \"interval_ms\": interval_ms,
-                    \"enabled\": true,
-                    \"next_run\": chrono::Utc::now().to_rfc3339(),
-                    \"created_at\": chrono::Utc::now().to_rfc3339()
-                }})
-            }}",
-            task, interval
+            "{}::schedule_interval({}, serde_json::json!({}))",
+            RUNTIME, task, interval
         )
     }
 
-    pub fn generate_cancel_code(task_id: &str) -> String {
+    pub fn generate_schedule_interval_fn_code(task: &str, interval: &str) -> String {
         format!(
-            "{{
-                let id = {};
-                // In a real implementation, this would remove the task from the scheduler
-                serde_json::json!({{
-                    \"id\": id,
-                    \"cancelled\": true,
-                    \"cancelled_at\": chrono::Utc::now().to_rfc3339()
-                }})
-            }}",
-            task_id
+            "{}::schedule_interval_fn(move || {{ let _ = ({})(); }}, serde_json::json!({}))",
+            RUNTIME, task, interval
         )
     }
 
+    pub fn generate_start_scheduled_code() -> String {
+        format!("{}::start_scheduled();", RUNTIME)
+    }
+
+    pub fn generate_cancel_code(task_id: &str) -> String {
+        format!("{}::cancel(&{})", RUNTIME, task_id)
+    }
+
     pub fn generate_list_code() -> String {
-        format!(
-            "{{
-                // In a real implementation, this would return all scheduled tasks
-                Vec::<serde_json::Value>::new()
-            }}"
-        )
+        format!("{}::list()", RUNTIME)
     }
 
     pub fn generate_get_code(task_id: &str) -> String {
-        format!(
-            "{{
-                let id = {};
-                // In a real implementation, this would return the task with the given ID
-                serde_json::json!({{
-                    \"id\": id,
-                    \"status\": \"unknown\"
-                }})
-            }}",
-            task_id
-        )
+        format!("{}::get(&{})", RUNTIME, task_id)
     }
 
     pub fn generate_enable_code(task_id: &str) -> String {
-        format!(
-            "{{
-                let id = {};
-                serde_json::json!({{
-                    \"id\": id,
-                    \"enabled\": true
-                }})
-            }}",
-            task_id
-        )
+        format!("{}::enable(&{})", RUNTIME, task_id)
     }
 
     pub fn generate_disable_code(task_id: &str) -> String {
-        format!(
-            "{{
-                let id = {};
-                serde_json::json!({{
-                    \"id\": id,
-                    \"enabled\": false
-                }})
-            }}",
-            task_id
-        )
+        format!("{}::disable(&{})", RUNTIME, task_id)
+    }
+}
+
+/// Runtime-Vorlage; Platzhalter werden in `generate_scheduler_runtime_code` ersetzt
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Scheduler Runtime ---
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+pub mod schedule {
+/*SCHEDULE*/
+}
+
+use schedule::{JobSpec, Overlap, Schedule};
+
+pub type Task = Arc<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+// --- Task-Registry ---
+
+/*REGISTRY*/
+fn task_output<T>(_value: T) -> Result<(), String> {
+    Ok(())
+}
+
+fn task_result<T, E: std::fmt::Display>(result: Result<T, E>) -> Result<(), String> {
+    result.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Async-Tasks laufen im Job-Thread auf einer eigenen Runtime
+#[allow(dead_code)]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start runtime for scheduled task")
+        .block_on(future)
+}
+
+// --- Jobs ---
+
+struct Job {
+    id: String,
+    name: String,
+    spec: JobSpec,
+    task: Task,
+    enabled: bool,
+    next_run: Option<i64>,
+    running: usize,
+    queued: bool,
+    last_run: Option<i64>,
+    last_error: Option<String>,
+    runs: u64,
+    failures: u64,
+    skipped: u64,
+}
+
+impl Job {
+    fn status(&self) -> &'static str {
+        if self.running > 0 {
+            "running"
+        } else if !self.enabled {
+            "disabled"
+        } else {
+            "scheduled"
+        }
+    }
+
+    fn plan_next(&mut self, now: i64) {
+        self.next_run = self.spec.schedule.next_after(now).map(|at| at + jitter(self.spec.jitter_ms));
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let (cron, timezone, interval_ms) = match &self.spec.schedule {
+            Schedule::Cron { expr, zone_name, .. } => (Some(expr.clone()), Some(zone_name.clone()), None),
+            Schedule::Interval { every_ms } => (None, None, Some(*every_ms)),
+        };
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "run": self.spec.run,
+            "cron": cron,
+            "timezone": timezone,
+            "interval_ms": interval_ms,
+            "overlap": self.spec.overlap.as_str(),
+            "jitter_ms": self.spec.jitter_ms,
+            "enabled": self.enabled,
+            "status": self.status(),
+            "next_run": self.next_run.and_then(timestamp),
+            "last_run": self.last_run.and_then(timestamp),
+            "last_error": self.last_error,
+            "runs": self.runs,
+            "failures": self.failures,
+            "skipped": self.skipped,
+        })
+    }
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn timestamp(ms: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp_millis(ms).map(|t| t.to_rfc3339())
+}
+
+fn jitter(max_ms: u64) -> i64 {
+    if max_ms == 0 {
+        return 0;
+    }
+    (uuid::Uuid::new_v4().as_u128() % (u128::from(max_ms) + 1)) as i64
+}
+
+struct Scheduler {
+    jobs: Mutex<HashMap<String, Job>>,
+    wake: Condvar,
+}
+
+/// Startet beim ersten Zugriff den Scheduler-Thread
+fn scheduler() -> &'static Scheduler {
+    static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+    let mut started = false;
+    let scheduler = SCHEDULER.get_or_init(|| {
+        started = true;
+        Scheduler {
+            jobs: Mutex::new(HashMap::new()),
+            wake: Condvar::new(),
+        }
+    });
+    if started {
+        std::thread::spawn(run_loop);
+    }
+    scheduler
+}
+
+fn jobs() -> MutexGuard<'static, HashMap<String, Job>> {
+    scheduler().jobs.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn run_loop() {
+    let scheduler = scheduler();
+    let mut jobs = scheduler.jobs.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        let now = now_ms();
+        for job in jobs.values_mut() {
+            if !job.enabled || !job.next_run.is_some_and(|at| at <= now) {
+                continue;
+            }
+            job.plan_next(now);
+            if job.running == 0 || job.spec.overlap == Overlap::Allow {
+                dispatch(job);
+            } else if job.spec.overlap == Overlap::Queue && !job.queued {
+                job.queued = true;
+            } else {
+                job.skipped += 1;
+            }
+        }
+        let wait = jobs
+            .values()
+            .filter(|job| job.enabled)
+            .filter_map(|job| job.next_run)
+            .min()
+            .map(|at| (at - now_ms()).clamp(0, 60_000) as u64)
+            .unwrap_or(60_000);
+        jobs = scheduler
+            .wake
+            .wait_timeout(jobs, Duration::from_millis(wait))
+            .unwrap_or_else(|e| e.into_inner())
+            .0;
+    }
+}
+
+/// Führt einen Durchgang in eigenem Thread aus; Panics zählen als Fehler
+fn dispatch(job: &mut Job) {
+    job.running += 1;
+    job.runs += 1;
+    job.last_run = Some(now_ms());
+    let task = job.task.clone();
+    let id = job.id.clone();
+    let name = job.name.clone();
+    std::thread::spawn(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task()))
+            .unwrap_or_else(|panic| {
+                Err(panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .map(|message| format!("panicked: {}", message))
+                    .unwrap_or_else(|| "panicked".to_string()))
+            });
+        if let Err(error) = &result {
+            eprintln!("[Scheduler] Job '{}' failed: {}", name, error);
+        }
+        finished(&id, result);
+    });
+}
+
+fn finished(id: &str, result: Result<(), String>) {
+    let mut jobs = jobs();
+    let Some(job) = jobs.get_mut(id) else {
+        return;
+    };
+    job.running -= 1;
+    match result {
+        Ok(()) => job.last_error = None,
+        Err(error) => {
+            job.failures += 1;
+            job.last_error = Some(error);
+        }
+    }
+    if job.queued && job.running == 0 && job.enabled {
+        job.queued = false;
+        dispatch(job);
+    }
+}
+
+fn add(id: String, spec: JobSpec, task: Task) -> serde_json::Value {
+    let name = spec
+        .name
+        .clone()
+        .or_else(|| spec.run.clone())
+        .unwrap_or_else(|| id.clone());
+    let mut job = Job {
+        id: id.clone(),
+        name,
+        enabled: spec.enabled,
+        spec,
+        task,
+        next_run: None,
+        running: 0,
+        queued: false,
+        last_run: None,
+        last_error: None,
+        runs: 0,
+        failures: 0,
+        skipped: 0,
+    };
+    if job.enabled {
+        job.plan_next(now_ms());
+    }
+    let record = job.to_json();
+    jobs().insert(id, job);
+    scheduler().wake.notify_all();
+    record
+}
+
+fn invalid(error: String) -> serde_json::Value {
+    eprintln!("[Scheduler] {}", error);
+    serde_json::json!({ "status": "invalid", "enabled": false, "error": error })
+}
+
+fn schedule_task(task: serde_json::Value, key: &str, value: serde_json::Value) -> serde_json::Value {
+    let mut spec = match task {
+        serde_json::Value::String(name) => serde_json::json!({ "run": name }),
+        serde_json::Value::Object(_) => task,
+        other => return invalid(format!("task must be a function name or an object, found {}", other)),
+    };
+    spec[key] = value;
+    let mut spec = match JobSpec::parse(&spec) {
+        Ok(spec) => spec,
+        Err(error) => return invalid(error),
+    };
+    let function = match spec.run.clone().or_else(|| spec.name.clone()) {
+        Some(function) if TASKS.contains(&function.as_str()) => function,
+        Some(function) => return invalid(format!("unknown scheduler task '{}'", function)),
+        None => return invalid("the task needs 'run' with a function name".to_string()),
+    };
+    spec.run = Some(function.clone());
+    let input = spec.input.clone();
+    add(
+        uuid::Uuid::new_v4().to_string(),
+        spec,
+        Arc::new(move || call_task(&function, input.clone())),
+    )
+}
+
+fn schedule_closure<F: Fn() + Send + Sync + 'static>(task: F, key: &str, value: serde_json::Value) -> serde_json::Value {
+    let mut spec = serde_json::json!({});
+    spec[key] = value;
+    match JobSpec::parse(&spec) {
+        Ok(spec) => add(
+            uuid::Uuid::new_v4().to_string(),
+            spec,
+            Arc::new(move || {
+                task();
+                Ok(())
+            }),
+        ),
+        Err(error) => invalid(error),
+    }
+}
+
+/// Job nach Id, sonst nach Name
+fn find<'a>(jobs: &'a mut HashMap<String, Job>, key: &str) -> Option<&'a mut Job> {
+    if jobs.contains_key(key) {
+        return jobs.get_mut(key);
+    }
+    jobs.values_mut().find(|job| job.name == key)
+}
+
+// --- API für `scheduler.*` ---
+
+/// `scheduler.schedule`: Task ist ein Funktionsname oder ein Objekt mit Job-Optionen
+pub fn schedule(task: serde_json::Value, cron: &str) -> serde_json::Value {
+    schedule_task(task, "cron", serde_json::json!(cron))
+}
+
+pub fn schedule_fn<F: Fn() + Send + Sync + 'static>(task: F, cron: &str) -> serde_json::Value {
+    schedule_closure(task, "cron", serde_json::json!(cron))
+}
+
+/// `scheduler.schedule_interval`: Intervall in Millisekunden oder als Dauer (`"5m"`)
+pub fn schedule_interval(task: serde_json::Value, every: serde_json::Value) -> serde_json::Value {
+    schedule_task(task, "every", every)
+}
+
+pub fn schedule_interval_fn<F: Fn() + Send + Sync + 'static>(task: F, every: serde_json::Value) -> serde_json::Value {
+    schedule_closure(task, "every", every)
+}
+
+/// Registriert alle `@Scheduled`-Funktionen; Id ist der Funktionsname
+pub fn start_scheduled() {
+    for (function, spec) in scheduled_jobs() {
+        match JobSpec::parse(&spec) {
+            Ok(spec) => {
+                let input = spec.input.clone();
+                add(
+                    function.to_string(),
+                    spec,
+                    Arc::new(move || call_task(function, input.clone())),
+                );
+            }
+            Err(error) => eprintln!("[Scheduler] @Scheduled '{}' is invalid: {}", function, error),
+        }
+    }
+}
+
+/// Entfernt den Job; ein laufender Durchgang wird nicht abgebrochen
+pub fn cancel(id: &str) -> serde_json::Value {
+    let mut jobs = jobs();
+    let Some(key) = find(&mut jobs, id).map(|job| job.id.clone()) else {
+        return serde_json::Value::Null;
+    };
+    let Some(job) = jobs.remove(&key) else {
+        return serde_json::Value::Null;
+    };
+    let mut record = job.to_json();
+    record["status"] = serde_json::json!("cancelled");
+    record["next_run"] = serde_json::Value::Null;
+    record
+}
+
+pub fn list() -> Vec<serde_json::Value> {
+    let jobs = jobs();
+    let mut list: Vec<&Job> = jobs.values().collect();
+    list.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    list.into_iter().map(Job::to_json).collect()
+}
+
+pub fn get(id: &str) -> serde_json::Value {
+    find(&mut jobs(), id)
+        .map(|job| job.to_json())
+        .unwrap_or(serde_json::Value::Null)
+}
+
+pub fn enable(id: &str) -> serde_json::Value {
+    let record = match find(&mut jobs(), id) {
+        Some(job) => {
+            if !job.enabled {
+                job.enabled = true;
+                job.plan_next(now_ms());
+            }
+            job.to_json()
+        }
+        None => return serde_json::Value::Null,
+    };
+    scheduler().wake.notify_all();
+    record
+}
+
+/// Keine neuen Durchgänge; ein laufender Durchgang läuft zu Ende
+pub fn disable(id: &str) -> serde_json::Value {
+    match find(&mut jobs(), id) {
+        Some(job) => {
+            job.enabled = false;
+            job.next_run = None;
+            job.queued = false;
+            job.to_json()
+        }
+        None => serde_json::Value::Null,
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::schedule::{CronExpr, JobSpec, Overlap, Zone};
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    fn at(text: &str) -> i64 {
+        chrono::DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .timestamp()
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let every_five = CronExpr::parse("0 */5 * * * *").unwrap();
+        assert_eq!(
+            every_five.next_after(at("2026-03-01T10:02:30Z"), &Zone::Utc),
+            Some(at("2026-03-01T10:05:00Z"))
+        );
+
+        // Fünf Felder: Sekunde 0; Werktage um 9:30
+        let weekdays = CronExpr::parse("30 9 * * MON-FRI").unwrap();
+        assert_eq!(
+            weekdays.next_after(at("2026-03-06T09:30:00Z"), &Zone::Utc),
+            Some(at("2026-03-09T09:30:00Z"))
+        );
+
+        // Tag und Wochentag eingeschränkt: einer von beiden genügt
+        let either = CronExpr::parse("0 0 0 13 * FRI").unwrap();
+        assert_eq!(
+            either.next_after(at("2026-03-01T00:00:00Z"), &Zone::Utc),
+            Some(at("2026-03-06T00:00:00Z"))
+        );
+
+        let leap = CronExpr::parse("@yearly").unwrap();
+        assert_eq!(
+            leap.next_after(at("2026-06-01T00:00:00Z"), &Zone::Fixed(3_600)),
+            Some(at("2026-12-31T23:00:00Z"))
+        );
+
+        for invalid in [
+            "* * * *",
+            "61 * * * * *",
+            "0 0 25 * * *",
+            "*/0 * * * *",
+            "@often",
+            "0 0 31 2 *",
+        ] {
+            assert!(CronExpr::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_cron_in_time_zone_across_dst() {
+        let Ok(berlin) = Zone::parse("Europe/Berlin") else {
+            return; // keine tz-Datenbank auf diesem System
+        };
+        let daily = CronExpr::parse("0 0 8 * * *").unwrap();
+        // Winterzeit UTC+1, ab 29.03.2026 Sommerzeit UTC+2
+        assert_eq!(
+            daily.next_after(at("2026-03-28T12:00:00Z"), &berlin),
+            Some(at("2026-03-29T06:00:00Z"))
+        );
+        assert_eq!(
+            daily.next_after(at("2026-03-27T12:00:00Z"), &berlin),
+            Some(at("2026-03-28T07:00:00Z"))
+        );
+        // 02:30 existiert am Umstellungstag nicht
+        let gap = CronExpr::parse("0 30 2 * * *").unwrap();
+        assert_eq!(
+            gap.next_after(at("2026-03-28T12:00:00Z"), &berlin),
+            Some(at("2026-03-30T00:30:00Z"))
+        );
+        // Jahre nach den expliziten Übergängen gilt die POSIX-Regel
+        assert_eq!(
+            daily.next_after(at("2060-07-01T12:00:00Z"), &berlin),
+            Some(at("2060-07-02T06:00:00Z"))
+        );
+        assert!(Zone::parse("Europe/Nowhere").is_err());
+        assert!(Zone::parse("../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_scheduled_functions_are_validated_and_collected() {
+        let source = r#"@Scheduled("0 */5 * * * *", timezone: "UTC", overlap: "queue", jitter: "10s")
+fn cleanupSessions() {
+    let x = 1;
+}
+
+@Scheduled(every: "1h")
+fn refreshCache(): Result<boolean, string> {
+    return Ok(true);
+}
+
+@Scheduled("0 61 * * * *")
+fn broken() {
+    let x = 1;
+}
+
+fn report(day: string) {
+    let x = day;
+}
+
+fn main() {
+    scheduler.schedule({ name: "daily report", run: "report", input: "today" }, "0 0 6 * * *");
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        let usage = SchedulerUsage::collect(&program).unwrap();
+        let tasks: Vec<&str> = usage.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(tasks, vec!["cleanupSessions", "refreshCache", "report"]);
+        assert_eq!(usage.scheduled.len(), 2);
+        assert_eq!(usage.scheduled[0].1["overlap"], "queue");
+
+        let functions: Vec<&Function> = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+        let error = scheduled_spec(functions[2]).unwrap().unwrap_err();
+        assert!(error.contains("minute 61 out of range"), "{}", error);
+        assert!(scheduled_spec(functions[3]).is_none());
+
+        let is_function = |name: &str| name == "report";
+        let task = Expression::Literal(Literal::String("missing".to_string()));
+        let cron = Expression::Literal(Literal::String("0 0 * * *".to_string()));
+        assert!(check_call("schedule", &[task, cron.clone()], &is_function)
+            .unwrap_err()
+            .contains("unknown task function 'missing'"));
+        let task = Expression::Literal(Literal::String("report".to_string()));
+        assert!(check_call("schedule", &[task.clone(), cron], &is_function).is_ok());
+        let every = Expression::Literal(Literal::String("soon".to_string()));
+        assert!(check_call("schedule_interval", &[task, every], &is_function).is_err());
+
+        let spec = JobSpec::parse(&serde_json::json!({ "every": 500, "overlap": "later" }));
+        assert!(spec.unwrap_err().contains("unknown overlap policy"));
+
+        let runtime =
+            SchedulerStdlib::generate_scheduler_runtime_code(&usage, &|name| name.to_lowercase());
+        assert!(runtime.contains("task_result(super::super::refreshcache())"));
+        assert!(runtime.contains("(\"cleanupSessions\", serde_json::json!("));
+        assert!(runtime.contains("pub struct CronExpr"));
+        assert!(!runtime.contains("/*"));
+    }
+
+    #[test]
+    fn test_job_spec_intervals_and_overlap() {
+        let job = JobSpec::parse(&serde_json::json!({
+            "name": "report",
+            "run": "report",
+            "every": "1h30m",
+            "overlap": "queue",
+            "jitter": "10s",
+        }))
+        .unwrap();
+        assert_eq!(job.overlap, Overlap::Queue);
+        assert_eq!(job.jitter_ms, 10_000);
+        assert!(job.enabled);
+        // Intervalle zählen ab dem letzten Lauf
+        let start = at("2026-03-01T10:00:00Z") * 1_000;
+        assert_eq!(
+            job.schedule.next_after(start),
+            Some(at("2026-03-01T11:30:00Z") * 1_000)
+        );
+
+        let cron = JobSpec::parse(&serde_json::json!({ "cron": "0 0 6 * * *", "enabled": false }))
+            .unwrap();
+        assert_eq!(cron.overlap, Overlap::Skip);
+        assert!(!cron.enabled);
+        assert_eq!(
+            cron.schedule.next_after(start),
+            Some(at("2026-03-02T06:00:00Z") * 1_000)
+        );
+
+        for (spec, error) in [
+            (
+                serde_json::json!({ "cron": "* * * * *", "every": "1m" }),
+                "'cron' and 'every' are mutually exclusive",
+            ),
+            (
+                serde_json::json!({ "every": "1m", "timezone": "UTC" }),
+                "'timezone' only applies to cron schedules",
+            ),
+            (
+                serde_json::json!({ "every": 0 }),
+                "interval must be greater than zero",
+            ),
+            (
+                serde_json::json!({ "every": "5 minutes" }),
+                "invalid duration '5 minutes'",
+            ),
+        ] {
+            assert_eq!(JobSpec::parse(&spec).unwrap_err(), error);
+        }
     }
 }
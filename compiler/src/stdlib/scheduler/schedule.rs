@@ -0,0 +1,744 @@
+// Zeitpläne für `scheduler`: Cron-Ausdrücke mit Sekunden, Zeitzonen, Dauern
+// und Job-Optionen.
+// Die Datei wird unverändert in das generierte Modul `stdlib::scheduler`
+// eingebettet; der Type Checker prüft `@Scheduled` mit demselben Code.
+
+use chrono::{Datelike, NaiveDate, Timelike};
+use std::sync::Arc;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// Cron-Ausdruck mit fünf (`min h dom mon dow`) oder sechs Feldern
+/// (`s min h dom mon dow`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Sind Tag und Wochentag eingeschränkt, reicht einer von beiden (wie Vixie-Cron)
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<CronExpr, String> {
+        let lower = expr.trim().to_ascii_lowercase();
+        let expanded = match lower.as_str() {
+            "@yearly" | "@annually" => "0 0 0 1 1 *",
+            "@monthly" => "0 0 0 1 * *",
+            "@weekly" => "0 0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 0 * * *",
+            "@hourly" => "0 0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(format!("unknown cron macro '{}'", expr.trim()))
+            }
+            _ => expr.trim(),
+        };
+        let mut fields: Vec<&str> = expanded.split_whitespace().collect();
+        match fields.len() {
+            5 => fields.insert(0, "0"),
+            6 => {}
+            n => {
+                return Err(format!(
+                    "cron expression '{}' has {} fields, expected 5 or 6",
+                    expr.trim(),
+                    n
+                ))
+            }
+        }
+
+        let mut weekdays = parse_field(fields[5], "weekday", 0, 7, &WEEKDAYS, 0)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        let cron = CronExpr {
+            seconds: parse_field(fields[0], "second", 0, 59, &[], 0)?,
+            minutes: parse_field(fields[1], "minute", 0, 59, &[], 0)?,
+            hours: parse_field(fields[2], "hour", 0, 23, &[], 0)?,
+            days: parse_field(fields[3], "day", 1, 31, &[], 0)?,
+            months: parse_field(fields[4], "month", 1, 12, &MONTHS, 1)?,
+            weekdays,
+            days_restricted: !is_wildcard(fields[3]),
+            weekdays_restricted: !is_wildcard(fields[5]),
+        };
+        // z. B. `0 0 31 2 *`
+        if cron.next_after(0, &Zone::Utc).is_none() {
+            return Err(format!("cron expression '{}' never matches", expr.trim()));
+        }
+        Ok(cron)
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// Nächster passender Zeitpunkt (Unix-Sekunden) echt nach `after`
+    ///
+    /// Gerechnet wird in der Ortszeit von `zone`: Zeiten in einer
+    /// Sommerzeit-Lücke entfallen, doppelte Stunden laufen zweimal.
+    pub fn next_after(&self, after: i64, zone: &Zone) -> Option<i64> {
+        let mut t = after + 1;
+        // 29. Februar an einem bestimmten Wochentag kann Jahre dauern
+        let limit = after + 30 * 366 * 86_400;
+        while t <= limit {
+            let local = chrono::DateTime::from_timestamp(t + zone.offset_at(t), 0)?.naive_utc();
+            let seconds_of_day = i64::from(local.num_seconds_from_midnight());
+            if self.months & (1 << local.month()) == 0 {
+                let (year, month) = if local.month() == 12 {
+                    (local.year() + 1, 1)
+                } else {
+                    (local.year(), local.month() + 1)
+                };
+                let next = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                t += (next - local).num_seconds();
+            } else if !self.matches_day(local.date()) {
+                t += 86_400 - seconds_of_day;
+            } else if self.hours & (1 << local.hour()) == 0 {
+                t += 3_600 - seconds_of_day % 3_600;
+            } else if self.minutes & (1 << local.minute()) == 0 {
+                t += 60 - seconds_of_day % 60;
+            } else if self.seconds & (1 << local.second()) == 0 {
+                t += 1;
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+fn is_wildcard(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+/// Ein Cron-Feld als Bitmaske: Listen, Bereiche, Schritte und Namen
+fn parse_field(
+    field: &str,
+    what: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+    name_base: u32,
+) -> Result<u64, String> {
+    let value = |text: &str| -> Result<u32, String> {
+        if let Some(index) = names.iter().position(|n| n.eq_ignore_ascii_case(text)) {
+            return Ok(index as u32 + name_base);
+        }
+        let number: u32 = text
+            .parse()
+            .map_err(|_| format!("invalid {} '{}' in cron field '{}'", what, text, field))?;
+        if number < min || number > max {
+            return Err(format!(
+                "{} {} out of range {}-{} in cron field '{}'",
+                what, number, min, max, field
+            ));
+        }
+        Ok(number)
+    };
+
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u32>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => return Err(format!("invalid step '{}' in cron field '{}'", step, field)),
+            },
+            None => (part, None),
+        };
+        let (start, end) = if is_wildcard(range) {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            (start, if step.is_some() { max } else { start })
+        };
+        if start > end {
+            return Err(format!(
+                "invalid range '{}' in cron field '{}'",
+                range, field
+            ));
+        }
+        for v in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+/// Zeitzone eines Jobs: `UTC`, `Local`, feste Offsets (`+02:00`, `UTC-5`)
+/// oder IANA-Namen (`Europe/Berlin`) aus der tz-Datenbank des Systems
+#[derive(Debug, Clone)]
+pub enum Zone {
+    Utc,
+    Local,
+    Fixed(i64),
+    Tz(Arc<TzData>),
+}
+
+impl Zone {
+    pub fn parse(name: &str) -> Result<Zone, String> {
+        let name = name.trim();
+        match name {
+            "" | "UTC" | "utc" | "Z" | "GMT" => return Ok(Zone::Utc),
+            "Local" | "local" => return Ok(Zone::Local),
+            _ => {}
+        }
+        let offset = name
+            .strip_prefix("UTC")
+            .or_else(|| name.strip_prefix("GMT"))
+            .unwrap_or(name);
+        if offset.starts_with('+') || offset.starts_with('-') {
+            return parse_offset(offset)
+                .filter(|seconds| seconds.abs() <= 18 * 3_600)
+                .map(Zone::Fixed)
+                .ok_or_else(|| format!("invalid UTC offset '{}'", name));
+        }
+
+        let valid_name = !name.starts_with('/')
+            && !name.split('/').any(|part| part.is_empty() || part == "..")
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'));
+        if !valid_name {
+            return Err(format!("invalid time zone '{}'", name));
+        }
+        let path = zoneinfo_dir().join(name);
+        let data = std::fs::read(&path).map_err(|_| format!("unknown time zone '{}'", name))?;
+        TzData::parse(&data)
+            .map(|data| Zone::Tz(Arc::new(data)))
+            .map_err(|e| format!("invalid time zone data for '{}': {}", name, e))
+    }
+
+    /// Abstand Ortszeit − UTC in Sekunden zum Zeitpunkt `t`
+    pub fn offset_at(&self, t: i64) -> i64 {
+        match self {
+            Zone::Utc => 0,
+            Zone::Fixed(offset) => *offset,
+            Zone::Local => {
+                use chrono::TimeZone;
+                chrono::Local
+                    .timestamp_opt(t, 0)
+                    .single()
+                    .map(|time| i64::from(time.offset().local_minus_utc()))
+                    .unwrap_or(0)
+            }
+            Zone::Tz(data) => data.offset_at(t),
+        }
+    }
+}
+
+/// Verzeichnis der tz-Datenbank (`TZDIR` oder `/usr/share/zoneinfo`)
+pub fn zoneinfo_dir() -> std::path::PathBuf {
+    std::env::var_os("TZDIR")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::PathBuf::from("/usr/share/zoneinfo"))
+}
+
+/// `[+-]hh[:mm[:ss]]` bzw. `[+-]hhmm` in Sekunden
+fn parse_offset(text: &str) -> Option<i64> {
+    let (sign, digits) = match text.as_bytes().first()? {
+        b'+' => (1, &text[1..]),
+        b'-' => (-1, &text[1..]),
+        _ => (1, text),
+    };
+    let parts: Vec<&str> = if digits.contains(':') {
+        digits.split(':').collect()
+    } else if digits.len() == 4 {
+        vec![&digits[..2], &digits[2..]]
+    } else {
+        vec![digits]
+    };
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let mut seconds = 0;
+    for (index, part) in parts.iter().enumerate() {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let value: i64 = part.parse().ok()?;
+        if index > 0 && value >= 60 {
+            return None;
+        }
+        seconds += value * [3_600, 60, 1][index];
+    }
+    Some(sign * seconds)
+}
+
+/// Übergänge aus einer TZif-Datei plus POSIX-Regel für spätere Jahre
+#[derive(Debug)]
+pub struct TzData {
+    /// (Zeitpunkt, Offset ab diesem Zeitpunkt)
+    transitions: Vec<(i64, i64)>,
+    initial: i64,
+    rule: Option<PosixRule>,
+}
+
+impl TzData {
+    fn parse(data: &[u8]) -> Result<TzData, String> {
+        let header = |pos: usize| -> Result<(u8, [usize; 6]), String> {
+            if data.get(pos..pos + 4) != Some(b"TZif".as_slice()) {
+                return Err("missing TZif header".to_string());
+            }
+            let mut counts = [0usize; 6];
+            for (i, count) in counts.iter_mut().enumerate() {
+                *count = read_int(data, pos + 20 + i * 4, 4)? as usize;
+            }
+            Ok((data[pos + 4], counts))
+        };
+        // isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt
+        let block_len = |c: &[usize; 6], time_size: usize| {
+            c[3] * time_size + c[3] + c[4] * 6 + c[5] + c[2] * (time_size + 4) + c[1] + c[0]
+        };
+
+        let (version, mut counts) = header(0)?;
+        let mut pos = 44;
+        let mut time_size = 4;
+        if version >= b'2' {
+            pos += block_len(&counts, 4);
+            counts = header(pos)?.1;
+            pos += 44;
+            time_size = 8;
+        }
+        let [_, _, _, timecnt, typecnt, _] = counts;
+        if typecnt == 0 {
+            return Err("no local time types".to_string());
+        }
+
+        let mut offsets = Vec::with_capacity(typecnt);
+        let types_at = pos + timecnt * (time_size + 1);
+        for i in 0..typecnt {
+            offsets.push(read_int(data, types_at + i * 6, 4)?);
+        }
+        let mut transitions = Vec::with_capacity(timecnt);
+        for i in 0..timecnt {
+            let at = read_int(data, pos + i * time_size, time_size)?;
+            let index = *data
+                .get(pos + timecnt * time_size + i)
+                .ok_or("truncated file")? as usize;
+            let offset = *offsets.get(index).ok_or("invalid local time type")?;
+            transitions.push((at, offset));
+        }
+
+        let footer_at = pos + block_len(&counts, time_size);
+        let rule = if version >= b'2' && data.get(footer_at) == Some(&b'\n') {
+            let footer = &data[footer_at + 1..];
+            let end = footer
+                .iter()
+                .position(|b| *b == b'\n')
+                .unwrap_or(footer.len());
+            std::str::from_utf8(&footer[..end])
+                .ok()
+                .and_then(PosixRule::parse)
+        } else {
+            None
+        };
+
+        Ok(TzData {
+            transitions,
+            initial: offsets[0],
+            rule,
+        })
+    }
+
+    fn offset_at(&self, t: i64) -> i64 {
+        let index = self.transitions.partition_point(|(at, _)| *at <= t);
+        if index == self.transitions.len() {
+            if let Some(rule) = &self.rule {
+                return rule.offset_at(t);
+            }
+        }
+        match index {
+            0 => self.initial,
+            _ => self.transitions[index - 1].1,
+        }
+    }
+}
+
+fn read_int(data: &[u8], pos: usize, size: usize) -> Result<i64, String> {
+    let bytes = data.get(pos..pos + size).ok_or("truncated file")?;
+    Ok(match size {
+        4 => i64::from(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])),
+        _ => i64::from_be_bytes(bytes.try_into().map_err(|_| "truncated file")?),
+    })
+}
+
+/// POSIX-TZ-Regel wie `CET-1CEST,M3.5.0,M10.5.0/3`
+#[derive(Debug)]
+struct PosixRule {
+    std: i64,
+    dst: Option<DstRule>,
+}
+
+#[derive(Debug)]
+struct DstRule {
+    offset: i64,
+    start: (RuleDay, i64),
+    end: (RuleDay, i64),
+}
+
+#[derive(Debug)]
+enum RuleDay {
+    /// `Jn`: 1–365, ohne 29. Februar
+    Julian(u32),
+    /// `n`: 0–365, mit 29. Februar
+    Ordinal(u32),
+    /// `Mm.w.d`: d-ter Wochentag der w-ten Woche (5 = letzte) im Monat m
+    Month { month: u32, week: u32, weekday: u32 },
+}
+
+impl PosixRule {
+    fn parse(text: &str) -> Option<PosixRule> {
+        let mut rest = text;
+        skip_zone_name(&mut rest)?;
+        let std = -take_time(&mut rest)?;
+        if rest.is_empty() {
+            return Some(PosixRule { std, dst: None });
+        }
+        skip_zone_name(&mut rest)?;
+        let offset = if rest.starts_with(',') {
+            std + 3_600
+        } else {
+            -take_time(&mut rest)?
+        };
+        rest = rest.strip_prefix(',')?;
+        let start = take_rule(&mut rest)?;
+        rest = rest.strip_prefix(',')?;
+        let end = take_rule(&mut rest)?;
+        if !rest.is_empty() {
+            return None;
+        }
+        Some(PosixRule {
+            std,
+            dst: Some(DstRule { offset, start, end }),
+        })
+    }
+
+    fn offset_at(&self, t: i64) -> i64 {
+        let Some(dst) = &self.dst else {
+            return self.std;
+        };
+        let Some(year) = chrono::DateTime::from_timestamp(t + self.std, 0).map(|d| d.year()) else {
+            return self.std;
+        };
+        let (Some(start_day), Some(end_day)) = (dst.start.0.day(year), dst.end.0.day(year)) else {
+            return self.std;
+        };
+        let start = start_day + dst.start.1 - self.std;
+        let end = end_day + dst.end.1 - dst.offset;
+        let in_dst = if start < end {
+            t >= start && t < end
+        } else {
+            t < end || t >= start
+        };
+        if in_dst {
+            dst.offset
+        } else {
+            self.std
+        }
+    }
+}
+
+impl RuleDay {
+    /// Mitternacht des Regeltags als Unix-Sekunden (ohne Offset)
+    fn day(&self, year: i32) -> Option<i64> {
+        let date = match *self {
+            RuleDay::Julian(n) => {
+                let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+                let n = if leap && n >= 60 { n } else { n - 1 };
+                NaiveDate::from_yo_opt(year, n + 1)?
+            }
+            RuleDay::Ordinal(n) => NaiveDate::from_yo_opt(year, n + 1)?,
+            RuleDay::Month {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let first_weekday = first.weekday().num_days_from_sunday();
+                let mut day = 1 + (weekday + 7 - first_weekday) % 7 + (week - 1) * 7;
+                while NaiveDate::from_ymd_opt(year, month, day).is_none() {
+                    day -= 7;
+                }
+                NaiveDate::from_ymd_opt(year, month, day)?
+            }
+        };
+        Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+    }
+}
+
+fn skip_zone_name(rest: &mut &str) -> Option<()> {
+    let len = if let Some(quoted) = rest.strip_prefix('<') {
+        quoted.find('>')? + 2
+    } else {
+        rest.find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len())
+    };
+    if len < 3 {
+        return None;
+    }
+    *rest = &rest[len..];
+    Some(())
+}
+
+/// `[+-]h[h][:mm[:ss]]`, Stunden bis 167 (POSIX-Erweiterung)
+fn take_time(rest: &mut &str) -> Option<i64> {
+    let len = rest
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, ':' | '+' | '-')))
+        .unwrap_or(rest.len());
+    let time = &rest[..len];
+    let (sign, digits) = match time.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, time.strip_prefix('+').unwrap_or(time)),
+    };
+    let mut seconds = 0;
+    for (index, part) in digits.split(':').enumerate() {
+        let value: i64 = part.parse().ok()?;
+        if index > 2 || (index == 0 && value > 167) || (index > 0 && value >= 60) {
+            return None;
+        }
+        seconds += value * [3_600, 60, 1][index];
+    }
+    *rest = &rest[len..];
+    Some(sign * seconds)
+}
+
+fn take_rule(rest: &mut &str) -> Option<(RuleDay, i64)> {
+    let len = rest.find([',', '/']).unwrap_or(rest.len());
+    let spec = &rest[..len];
+    let day = if let Some(month) = spec.strip_prefix('M') {
+        let parts: Vec<u32> = month
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+        match parts[..] {
+            [month @ 1..=12, week @ 1..=5, weekday @ 0..=6] => RuleDay::Month {
+                month,
+                week,
+                weekday,
+            },
+            _ => return None,
+        }
+    } else if let Some(day) = spec.strip_prefix('J') {
+        RuleDay::Julian(day.parse().ok().filter(|d| (1..=365).contains(d))?)
+    } else {
+        RuleDay::Ordinal(spec.parse().ok().filter(|d| *d <= 365)?)
+    };
+    *rest = &rest[len..];
+    let time = match rest.strip_prefix('/') {
+        Some(time) => {
+            *rest = time;
+            take_time(rest)?
+        }
+        None => 7_200,
+    };
+    Some((day, time))
+}
+
+/// Dauer als Millisekunden: Zahl (ms) oder `"500ms"`, `"30s"`, `"5m"`, `"1h30m"`, `"1d"`
+pub fn parse_duration(value: &serde_json::Value) -> Result<u64, String> {
+    if let Some(ms) = value.as_u64() {
+        return Ok(ms);
+    }
+    if let Some(ms) = value.as_f64().filter(|ms| *ms >= 0.0) {
+        return Ok(ms as u64);
+    }
+    let text = value
+        .as_str()
+        .ok_or_else(|| format!("invalid duration {}", value))?
+        .trim();
+    let invalid = || format!("invalid duration '{}'", text);
+    let mut total = 0u64;
+    let mut rest = text;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let factor = match &rest[..unit] {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit..];
+        total += number * factor;
+    }
+    if text.is_empty() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+/// Verhalten, wenn ein Job fällig wird, während der vorige Lauf noch arbeitet
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overlap {
+    /// Fälligkeit auslassen
+    Skip,
+    /// Parallel starten
+    Allow,
+    /// Einmal nach dem laufenden Durchgang nachholen
+    Queue,
+}
+
+impl Overlap {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Overlap::Skip => "skip",
+            Overlap::Allow => "allow",
+            Overlap::Queue => "queue",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Cron {
+        expr: String,
+        cron: CronExpr,
+        zone: Zone,
+        zone_name: String,
+    },
+    Interval {
+        every_ms: u64,
+    },
+}
+
+impl Schedule {
+    /// Nächste Fälligkeit (Unix-Millisekunden) nach `after_ms`
+    pub fn next_after(&self, after_ms: i64) -> Option<i64> {
+        match self {
+            Schedule::Cron { cron, zone, .. } => cron
+                .next_after(after_ms.div_euclid(1_000), zone)
+                .map(|t| t * 1_000),
+            Schedule::Interval { every_ms } => Some(after_ms + *every_ms as i64),
+        }
+    }
+}
+
+/// Geprüfte Job-Optionen aus `@Scheduled(...)` bzw. `scheduler.schedule(...)`
+#[derive(Debug, Clone)]
+pub struct JobSpec {
+    pub name: Option<String>,
+    /// Name der Velin-Funktion, die der Job aufruft
+    pub run: Option<String>,
+    pub input: serde_json::Value,
+    pub schedule: Schedule,
+    pub overlap: Overlap,
+    pub jitter_ms: u64,
+    pub enabled: bool,
+}
+
+impl JobSpec {
+    /// Schlüssel: `name`, `run`, `input`, `cron` oder `every`, `timezone`,
+    /// `overlap`, `jitter`, `enabled`
+    pub fn parse(spec: &serde_json::Value) -> Result<JobSpec, String> {
+        let fields = spec
+            .as_object()
+            .ok_or_else(|| format!("job options must be an object, found {}", spec))?;
+        let string = |key: &str| -> Result<Option<String>, String> {
+            match fields.get(key) {
+                None | Some(serde_json::Value::Null) => Ok(None),
+                Some(serde_json::Value::String(s)) => Ok(Some(s.clone())),
+                Some(other) => Err(format!("'{}' must be a string, found {}", key, other)),
+            }
+        };
+        for key in fields.keys() {
+            if !matches!(
+                key.as_str(),
+                "name"
+                    | "run"
+                    | "input"
+                    | "cron"
+                    | "every"
+                    | "timezone"
+                    | "zone"
+                    | "overlap"
+                    | "jitter"
+                    | "enabled"
+            ) {
+                return Err(format!("unknown job option '{}'", key));
+            }
+        }
+
+        let zone_name = match string("timezone")? {
+            Some(zone) => Some(zone),
+            None => string("zone")?,
+        };
+        let schedule = match (string("cron")?, fields.get("every")) {
+            (Some(_), Some(_)) => return Err("'cron' and 'every' are mutually exclusive".into()),
+            (Some(expr), None) => {
+                let zone_name = zone_name.unwrap_or_else(|| "UTC".to_string());
+                Schedule::Cron {
+                    cron: CronExpr::parse(&expr)?,
+                    zone: Zone::parse(&zone_name)?,
+                    expr,
+                    zone_name,
+                }
+            }
+            (None, Some(every)) => {
+                if zone_name.is_some() {
+                    return Err("'timezone' only applies to cron schedules".into());
+                }
+                let every_ms = parse_duration(every)?;
+                if every_ms == 0 {
+                    return Err("interval must be greater than zero".into());
+                }
+                Schedule::Interval { every_ms }
+            }
+            (None, None) => return Err("either 'cron' or 'every' is required".into()),
+        };
+        let overlap = match string("overlap")?.as_deref() {
+            None | Some("skip") => Overlap::Skip,
+            Some("allow") => Overlap::Allow,
+            Some("queue") => Overlap::Queue,
+            Some(other) => {
+                return Err(format!(
+                    "unknown overlap policy '{}' (expected skip, allow or queue)",
+                    other
+                ))
+            }
+        };
+        let jitter_ms = match fields.get("jitter") {
+            None | Some(serde_json::Value::Null) => 0,
+            Some(jitter) => parse_duration(jitter)?,
+        };
+        let enabled = match fields.get("enabled") {
+            None | Some(serde_json::Value::Null) => true,
+            Some(serde_json::Value::Bool(enabled)) => *enabled,
+            Some(other) => return Err(format!("'enabled' must be a boolean, found {}", other)),
+        };
+
+        Ok(JobSpec {
+            name: string("name")?,
+            run: string("run")?,
+            input: fields
+                .get("input")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
+            schedule,
+            overlap,
+            jitter_ms,
+            enabled,
+        })
+    }
+}
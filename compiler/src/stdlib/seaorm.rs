@@ -1,24 +1,44 @@
 // SeaORM Standard Library
 // Generiert SeaORM Entity-Code aus VelinScript Structs
 
+use crate::migrations::{schema, Dialect, Migration};
 use crate::parser::ast::*;
 
 pub struct SeaORMStdlib;
 
 impl SeaORMStdlib {
     /// Generiert SeaORM Entity aus VelinScript Struct
+    ///
+    /// Tabellen- und Spaltennamen folgen denselben Regeln wie `velin db migrate`.
     pub fn generate_entity(struct_def: &Struct) -> String {
         let mut code = format!(
-            "use sea_orm::entity::prelude::*;\n\n#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]\n#[sea_orm(table_name = \"{}\")]\npub struct Model {{\n",
-            struct_def.name.to_lowercase()
+            "use sea_orm::entity::prelude::*;\n\n#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]\n#[sea_orm(table_name = \"{}\")]\npub struct Model {{\n",
+            schema::table_name(struct_def)
         );
+        let primary_key = schema::primary_key_field(struct_def).map(|field| field.name.as_str());
 
         // Felder generieren
         for field in &struct_def.fields {
-            let rust_type = Self::velin_to_seaorm_type(&field.field_type);
-            let column_name = Self::field_name_to_column(&field.name);
-
-            code.push_str(&format!("    #[sea_orm(primary_key)]\n",));
+            let column_name = schema::column_name(field);
+            let mut attributes = Vec::new();
+            let rust_type = if Some(field.name.as_str()) == primary_key {
+                attributes.push("primary_key".to_string());
+                match field.field_type {
+                    Type::Number => "i64".to_string(),
+                    _ => {
+                        attributes.push("auto_increment = false".to_string());
+                        Self::velin_to_seaorm_type(&field.field_type)
+                    }
+                }
+            } else {
+                Self::velin_to_seaorm_type(&field.field_type)
+            };
+            if column_name != field.name {
+                attributes.push(format!("column_name = \"{}\"", column_name));
+            }
+            if !attributes.is_empty() {
+                code.push_str(&format!("    #[sea_orm({})]\n", attributes.join(", ")));
+            }
             code.push_str(&format!("    pub {}: {},\n", field.name, rust_type));
         }
 
@@ -78,9 +98,24 @@ impl SeaORMStdlib {
         )
     }
 
-    /// Generiert SeaORM Migration-Code
-    pub fn generate_migration_code() -> String {
-        "use sea_orm_migration::prelude::*;\n\npub struct Migration;\n\nimpl MigrationName for Migration {\n    fn name(&self) -> &str {\n        \"migration\"\n    }\n}\n\n#[async_trait::async_trait]\nimpl MigrationTrait for Migration {\n    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {\n        // Create tables\n        Ok(())\n    }\n\n    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {\n        // Drop tables\n        Ok(())\n    }\n}".to_string()
+    /// Generiert SeaORM Migration-Code aus einer `velin db migrate`-Migration
+    pub fn generate_migration_code(migration: &Migration, dialect: Dialect) -> String {
+        let statements = |up: bool| -> String {
+            migration
+                .statements(dialect, up)
+                .iter()
+                .map(|statement| {
+                    format!("        db.execute_unprepared({:?}).await?;\n", statement)
+                })
+                .collect()
+        };
+        format!(
+            "use sea_orm_migration::prelude::*;\n\npub struct Migration;\n\nimpl MigrationName for Migration {{\n    fn name(&self) -> &str {{\n        \"m{}_{}\"\n    }}\n}}\n\n#[async_trait::async_trait]\nimpl MigrationTrait for Migration {{\n    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {{\n        let db = manager.get_connection();\n{}        Ok(())\n    }}\n\n    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {{\n        let db = manager.get_connection();\n{}        Ok(())\n    }}\n}}\n",
+            migration.version,
+            migration.name,
+            statements(true),
+            statements(false)
+        )
     }
 
     /// Konvertiert VelinScript Type zu SeaORM Type
@@ -89,23 +124,20 @@ impl SeaORMStdlib {
             Type::String => "String".to_string(),
             Type::Number => "f64".to_string(),
             Type::Boolean => "bool".to_string(),
-            Type::List(ref inner) => format!("Vec<{}>", Self::velin_to_seaorm_type(inner)),
-            Type::Named(ref name) => name.clone(),
-            _ => "String".to_string(),
-        }
-    }
-
-    /// Konvertiert Feldname zu Column-Name (snake_case)
-    fn field_name_to_column(field_name: &str) -> String {
-        // Einfache Konvertierung: camelCase -> snake_case
-        let mut result = String::new();
-        for (i, c) in field_name.chars().enumerate() {
-            if c.is_uppercase() && i > 0 {
-                result.push('_');
+            Type::Optional(_) => format!(
+                "Option<{}>",
+                Self::velin_to_seaorm_type(schema::unwrap_optional(velin_type))
+            ),
+            Type::Generic { name, params } if name == "Option" && params.len() == 1 => {
+                format!("Option<{}>", Self::velin_to_seaorm_type(&params[0]))
+            }
+            Type::Named(name) if matches!(name.as_str(), "DateTime" | "Timestamp") => {
+                "DateTimeUtc".to_string()
             }
-            result.push(c.to_lowercase().next().unwrap());
+            Type::Named(name) if name == "Date" => "Date".to_string(),
+            // Listen, Maps und verschachtelte Structs als JSON-Spalte
+            _ => "Json".to_string(),
         }
-        result
     }
 
     /// Generiert Relationship-Code (One-to-Many)
@@ -1,41 +1,723 @@
+// Stream Standard Library
+// Lazy async Streams (`Stream<T>`): Quellen, Operatoren und Terminal-Operationen
+// laufen erst, wenn jemand zieht; `buffer` und `from_topic` puffern begrenzt, volle
+// Puffer bremsen den Erzeuger. Routen mit `Stream<T>` antworten als NDJSON.
+
+pub mod runtime;
+
+use crate::codegen::framework::Framework;
+use crate::parser::ast::*;
+use crate::parser::visitor::calls_module;
+
+const RUNTIME: &str = "crate::stdlib::stream";
+
+/// Quelltext von `runtime.rs`, wird in die Runtime eingebettet
+const RUNTIME_SOURCE: &str = include_str!("stream/runtime.rs");
+
+/// Typ des Abbruch-Tokens aus `stream.cancel_token()`
+pub const CANCEL_TOKEN_TYPE: &str = "CancelToken";
+
+/// Alle `stream.*`-Funktionen; ihre Typen berechnet `call_type` aus den Argumenten
+pub const METHODS: &[&str] = &[
+    "create",
+    "from",
+    "interval",
+    "from_topic",
+    "map",
+    "filter",
+    "map_async",
+    "take",
+    "batch",
+    "window",
+    "buffer",
+    "merge",
+    "zip",
+    "cancel_token",
+    "with_cancel",
+    "cancel",
+    "collect",
+    "reduce",
+    "for_each",
+    "send_to",
+    "publish",
+];
+
 pub struct StreamStdlib;
 
+/// Braucht das Programm das Runtime-Modul? (`stream.*`-Aufrufe oder `Stream<T>` in Signaturen)
+pub fn uses_streams(program: &Program) -> bool {
+    let declared = program.items.iter().any(|item| match item {
+        Item::Function(f) => {
+            f.params.iter().any(|p| mentions_stream(&p.param_type))
+                || f.return_type.as_ref().is_some_and(mentions_stream)
+        }
+        _ => false,
+    });
+    declared || calls_module(program, "stream")
+}
+
+fn mentions_stream(ty: &Type) -> bool {
+    match ty {
+        Type::Stream(_) => true,
+        Type::Named(name) => name == CANCEL_TOKEN_TYPE,
+        Type::List(inner) | Type::Optional(inner) => mentions_stream(inner),
+        Type::Generic { params, .. } | Type::Tuple(params) => params.iter().any(mentions_stream),
+        Type::Result { ok, err } => mentions_stream(ok) || mentions_stream(err),
+        _ => false,
+    }
+}
+
+fn is_any(ty: &Type) -> bool {
+    matches!(ty, Type::Any) || matches!(ty, Type::Named(name) if name == "any")
+}
+
+/// Elementtyp eines Streams
+fn item(ty: &Type) -> Result<Type, String> {
+    match ty {
+        Type::Stream(item) => Ok((**item).clone()),
+        ty if is_any(ty) => Ok(Type::Any),
+        other => Err(format!("expected a Stream, found {}", other.to_string())),
+    }
+}
+
+/// Parameter und Rückgabetyp eines Lambdas oder einer Funktion mit `arity` Parametern
+fn callable(ty: &Type, arity: usize) -> Result<(Vec<Type>, Type), String> {
+    match ty {
+        Type::Function {
+            params,
+            return_type,
+        } if params.len() == arity => Ok((params.clone(), (**return_type).clone())),
+        Type::Function { params, .. } => Err(format!(
+            "expected a function with {} parameter{}, found one with {}",
+            arity,
+            if arity == 1 { "" } else { "s" },
+            params.len()
+        )),
+        ty if is_any(ty) => Ok((vec![Type::Any; arity], Type::Any)),
+        other => Err(format!("expected a function, found {}", other.to_string())),
+    }
+}
+
+fn number(ty: &Type, what: &str) -> Result<(), String> {
+    if matches!(ty, Type::Number) || is_any(ty) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} must be a number, found {}",
+            what,
+            ty.to_string()
+        ))
+    }
+}
+
+fn stream_of(item: Type) -> Type {
+    Type::Stream(Box::new(item))
+}
+
+/// Ergebnistyp eines `stream.*`-Aufrufs. `topic_event` ist das `@Event`-Struct eines
+/// Literal-Topics bei `from_topic`, `compatible` die Typ-Kompatibilität des Checkers.
+pub fn call_type(
+    method: &str,
+    args: &[Type],
+    topic_event: Option<&str>,
+    compatible: &dyn Fn(&Type, &Type) -> bool,
+) -> Result<Type, String> {
+    if !METHODS.contains(&method) {
+        return Err(format!(
+            "unknown stream function '{}', available: {}",
+            method,
+            METHODS.join(", ")
+        ));
+    }
+    let arity = match method {
+        "create" | "cancel_token" => 0..=0,
+        "from" | "interval" | "cancel" | "collect" => 1..=1,
+        "from_topic" => 2..=3,
+        "map_async" | "window" | "reduce" | "publish" => 3..=3,
+        _ => 2..=2,
+    };
+    if !arity.contains(&args.len()) {
+        let expected = if arity.start() == arity.end() {
+            arity.start().to_string()
+        } else {
+            format!("{} or {}", arity.start(), arity.end())
+        };
+        return Err(format!(
+            "expects {} arguments, found {}",
+            expected,
+            args.len()
+        ));
+    }
+    // Parameter eines Callbacks muss das Stream-Element aufnehmen können
+    let takes = |param: &Type, item: &Type| -> Result<(), String> {
+        if compatible(item, param) {
+            Ok(())
+        } else {
+            Err(format!(
+                "callback takes {}, but the stream carries {}",
+                param.to_string(),
+                item.to_string()
+            ))
+        }
+    };
+    match method {
+        "create" => Ok(stream_of(Type::Any)),
+        "from" => match &args[0] {
+            Type::List(inner) => Ok(stream_of((**inner).clone())),
+            Type::Generic { name, params } if name == "List" && params.len() == 1 => {
+                Ok(stream_of(params[0].clone()))
+            }
+            ty if is_any(ty) => Ok(stream_of(Type::Any)),
+            other => Err(format!("expected a List, found {}", other.to_string())),
+        },
+        "interval" => {
+            number(&args[0], "interval")?;
+            Ok(stream_of(Type::Number))
+        }
+        "from_topic" => {
+            if !matches!(args[1], Type::String) && !is_any(&args[1]) {
+                return Err(format!(
+                    "topic must be a string, found {}",
+                    args[1].to_string()
+                ));
+            }
+            if let Some(capacity) = args.get(2) {
+                number(capacity, "capacity")?;
+            }
+            Ok(stream_of(
+                topic_event.map_or(Type::Any, |event| Type::Named(event.to_string())),
+            ))
+        }
+        "map" | "map_async" => {
+            let element = item(&args[0])?;
+            let (params, output) = callable(&args[1], 1)?;
+            takes(&params[0], &element)?;
+            if method == "map_async" {
+                number(&args[2], "concurrency")?;
+            }
+            Ok(stream_of(output))
+        }
+        "filter" => {
+            let element = item(&args[0])?;
+            let (params, output) = callable(&args[1], 1)?;
+            takes(&params[0], &element)?;
+            if !matches!(output, Type::Boolean) && !is_any(&output) {
+                return Err(format!(
+                    "predicate must return boolean, found {}",
+                    output.to_string()
+                ));
+            }
+            Ok(args[0].clone())
+        }
+        "take" | "buffer" => {
+            item(&args[0])?;
+            number(
+                &args[1],
+                if method == "take" {
+                    "count"
+                } else {
+                    "capacity"
+                },
+            )?;
+            Ok(args[0].clone())
+        }
+        "batch" | "window" => {
+            let element = item(&args[0])?;
+            number(&args[1], "size")?;
+            if method == "window" {
+                number(&args[2], "window duration")?;
+            }
+            Ok(stream_of(Type::List(Box::new(element))))
+        }
+        "merge" => {
+            let left = item(&args[0])?;
+            let right = item(&args[1])?;
+            if !compatible(&left, &right) {
+                return Err(format!(
+                    "cannot merge Stream<{}> with Stream<{}>",
+                    left.to_string(),
+                    right.to_string()
+                ));
+            }
+            Ok(args[0].clone())
+        }
+        "zip" => Ok(stream_of(Type::Tuple(vec![
+            item(&args[0])?,
+            item(&args[1])?,
+        ]))),
+        "cancel_token" => Ok(Type::Named(CANCEL_TOKEN_TYPE.to_string())),
+        "with_cancel" | "cancel" => {
+            let token = args.last().unwrap_or(&Type::Any);
+            if !matches!(token, Type::Named(name) if name == CANCEL_TOKEN_TYPE) && !is_any(token) {
+                return Err(format!(
+                    "expected a CancelToken, found {}",
+                    token.to_string()
+                ));
+            }
+            if method == "cancel" {
+                Ok(Type::Void)
+            } else {
+                item(&args[0])?;
+                Ok(args[0].clone())
+            }
+        }
+        "collect" => Ok(Type::List(Box::new(item(&args[0])?))),
+        "reduce" => {
+            let element = item(&args[0])?;
+            let (params, _) = callable(&args[1], 2)?;
+            takes(&params[1], &element)?;
+            if !compatible(&args[2], &params[0]) {
+                return Err(format!(
+                    "initial value is {}, but the reducer takes {}",
+                    args[2].to_string(),
+                    params[0].to_string()
+                ));
+            }
+            Ok(args[2].clone())
+        }
+        "for_each" => {
+            let element = item(&args[0])?;
+            let (params, _) = callable(&args[1], 1)?;
+            takes(&params[0], &element)?;
+            Ok(Type::Void)
+        }
+        "send_to" => {
+            let element = item(&args[0])?;
+            match crate::stdlib::realtime::connection_message(&args[1]) {
+                Some(Ok(message)) => {
+                    let message = Type::Named(message);
+                    if compatible(&element, &message) {
+                        Ok(Type::Void)
+                    } else {
+                        Err(format!(
+                            "connection sends {}, but the stream carries {}",
+                            message.to_string(),
+                            element.to_string()
+                        ))
+                    }
+                }
+                Some(Err(reason)) => Err(reason),
+                None if is_any(&args[1]) => Ok(Type::Void),
+                None => Err(format!(
+                    "expected a Connection, found {}",
+                    args[1].to_string()
+                )),
+            }
+        }
+        "publish" => {
+            item(&args[0])?;
+            if !matches!(args[2], Type::String) && !is_any(&args[2]) {
+                return Err(format!(
+                    "topic must be a string, found {}",
+                    args[2].to_string()
+                ));
+            }
+            Ok(Type::Number)
+        }
+        other => unreachable!("stream.{} is listed in METHODS but has no type rule", other),
+    }
+}
+
+/// Literal-Topic eines `from_topic`-/`publish`-Aufrufs
+pub fn literal_topic<'a>(method: &str, args: &'a [Expression]) -> Option<&'a str> {
+    let index = match method {
+        "from_topic" => 1,
+        "publish" => 2,
+        _ => return None,
+    };
+    match args.get(index) {
+        Some(Expression::Literal(Literal::String(topic))) => Some(topic),
+        _ => None,
+    }
+}
+
 impl StreamStdlib {
-    pub fn generate_create_code() -> String {
-        "futures::stream::unfold((), |_| async { Some(((), ())) }).boxed()".to_string()
+    /// Aufruf ins Runtime-Modul; `args` sind bereits generierte Ausdrücke.
+    /// `event_type` ist der Rust-Typ der Events bei `from_topic`.
+    pub fn generate_call_code(method: &str, args: &[String], event_type: Option<&str>) -> String {
+        let arg = |index: usize| args.get(index).map(String::as_str).unwrap_or("");
+        match method {
+            "create" => format!("{}::empty::<serde_json::Value>()", RUNTIME),
+            "from_topic" => format!(
+                "{}::from_topic::<{}, _>(&{}, {}, {})",
+                RUNTIME,
+                event_type.unwrap_or("serde_json::Value"),
+                arg(0),
+                arg(1),
+                args.get(2).map(String::as_str).unwrap_or("64.0")
+            ),
+            "with_cancel" => format!("{}::with_cancel({}, {}.clone())", RUNTIME, arg(0), arg(1)),
+            "cancel" => format!("{}::cancel(&{})", RUNTIME, arg(0)),
+            "send_to" => format!("{}::send_to({}, &{})", RUNTIME, arg(0), arg(1)),
+            "publish" => format!("{}::publish({}, &{}, {})", RUNTIME, arg(0), arg(1), arg(2)),
+            _ => format!("{}::{}({})", RUNTIME, method, args.join(", ")),
+        }
     }
 
-    pub fn generate_map_code(stream: &str, mapper: &str) -> String {
-        format!("{}.map(|item| {}).boxed()", stream, mapper)
+    /// Runtime-Modul `stdlib::stream`; Anbindungen an Realtime und Event-Bus nur bei Bedarf
+    pub fn generate_stream_runtime_code(
+        framework: Framework,
+        realtime: bool,
+        event_bus: bool,
+    ) -> String {
+        let response = match framework {
+            Framework::Actix => ACTIX_RESPONSE,
+            _ => AXUM_RESPONSE,
+        };
+        RUNTIME_TEMPLATE
+            .replace("/*RUNTIME*/\n", RUNTIME_SOURCE)
+            .replace("/*RESPONSE*/", response)
+            .replace("/*REALTIME*/", if realtime { REALTIME_BRIDGE } else { "" })
+            .replace(
+                "/*EVENT_BUS*/",
+                if event_bus { EVENT_BUS_BRIDGE } else { "" },
+            )
     }
+}
+
+const RUNTIME_TEMPLATE: &str = r#"
+/*RUNTIME*/
+/*RESPONSE*/
+/*REALTIME*/
+/*EVENT_BUS*/
+"#;
 
-    pub fn generate_filter_code(stream: &str, predicate: &str) -> String {
-        format!(
-            "{}.filter(|item| async move {{ {} }}).boxed()",
-            stream, predicate
+const AXUM_RESPONSE: &str = r#"
+impl<T: serde::Serialize + Send + 'static> axum::response::IntoResponse for Stream<T> {
+    fn into_response(self) -> axum::response::Response {
+        let lines = self.map(|item| Ok::<_, std::convert::Infallible>(ndjson_line(&item)));
+        (
+            [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+            axum::body::Body::from_stream(lines),
         )
+            .into_response()
     }
+}
+"#;
 
-    pub fn generate_reduce_code(stream: &str, reducer: &str, initial: &str) -> String {
-        format!(
-            "{}.fold({}, |acc, item| async move {{ {} }}).await",
-            stream, initial, reducer
-        )
+const ACTIX_RESPONSE: &str = r#"
+impl<T: serde::Serialize + Send + 'static> actix_web::Responder for Stream<T> {
+    type Body = actix_web::body::BoxBody;
+
+    fn respond_to(self, _request: &actix_web::HttpRequest) -> actix_web::HttpResponse {
+        let lines = self.map(|item| {
+            Ok::<_, actix_web::Error>(actix_web::web::Bytes::from(ndjson_line(&item)))
+        });
+        actix_web::HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .streaming(lines)
     }
+}
+"#;
 
-    pub fn generate_batch_code(stream: &str, size: &str) -> String {
-        format!("{}.chunks({} as usize).boxed()", stream, size)
+const REALTIME_BRIDGE: &str = r#"
+// --- Realtime ---
+
+/// Nachrichten, die höchstens unbestätigt zum Client unterwegs sind
+const SEND_WINDOW: usize = 32;
+
+/// Schickt die Elemente im Hintergrund an die Verbindung, bis Stream oder Verbindung enden
+pub fn send_to<T: serde::Serialize + Send + 'static>(
+    mut stream: Stream<T>,
+    connection: &super::realtime::Connection<T>,
+) {
+    let connection = connection.clone();
+    tokio::spawn(async move {
+        let mut sent = 0;
+        loop {
+            let item = tokio::select! {
+                item = stream.next() => item,
+                _ = connection.closed() => None,
+            };
+            let Some(item) = item else {
+                break;
+            };
+            connection.send(&item);
+            sent += 1;
+            if sent % SEND_WINDOW == 0 && !connection.flushed().await {
+                break;
+            }
+        }
+    });
+}
+"#;
+
+const EVENT_BUS_BRIDGE: &str = r#"
+// --- Event-Bus ---
+
+/// Meldet das Abonnement ab, sobald der Stream verworfen wird
+struct Unsubscribe(Option<super::event_bus::Unsubscribe>);
+
+impl Drop for Unsubscribe {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.0.take() {
+            unsubscribe();
+        }
+    }
+}
+
+/// Events eines Topics; abonniert wird sofort, jeder Stream bekommt alle Events.
+/// Ist der Puffer voll, wartet die Zustellung des Transports.
+pub fn from_topic<T, S>(bus: &super::event_bus::EventBus, topic: S, capacity: f64) -> Stream<T>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+    S: AsRef<str>,
+{
+    let topic = topic.as_ref().to_string();
+    let (sender, receiver) = tokio::sync::mpsc::channel(count(capacity));
+    let unsubscribe = match super::event_bus::subscribe_stream(bus, &topic, sender) {
+        Ok(unsubscribe) => Unsubscribe(Some(unsubscribe)),
+        Err(error) => {
+            eprintln!("[Stream] Topic '{}' not subscribed: {}", topic, error);
+            return empty();
+        }
+    };
+    let state = (receiver, unsubscribe, topic);
+    Stream::new(futures_util::stream::unfold(state, |(mut receiver, unsubscribe, topic)| async move {
+        loop {
+            let data = receiver.recv().await?;
+            match serde_json::from_value(data) {
+                Ok(event) => return Some((event, (receiver, unsubscribe, topic))),
+                Err(error) => eprintln!("[Stream] Skipped event on '{}': {}", topic, error),
+            }
+        }
+    }))
+}
+
+/// Veröffentlicht jedes Element; Ergebnis ist die Anzahl der Events
+pub async fn publish<T, S>(mut stream: Stream<T>, bus: &super::event_bus::EventBus, topic: S) -> f64
+where
+    T: serde::Serialize + Send + 'static,
+    S: AsRef<str>,
+{
+    let mut published = 0.0;
+    while let Some(item) = stream.next().await {
+        if let Err(error) = super::event_bus::publish(bus, topic.as_ref(), &item) {
+            eprintln!("[Stream] Publishing to '{}' stopped: {}", topic.as_ref(), error);
+            break;
+        }
+        published += 1.0;
+    }
+    published
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::context::CompilationContext;
+    use crate::compiler::pass::Pass;
+    use crate::parser::parser::Parser;
+    use crate::passes::type_check::TypeCheckPass;
+
+    fn type_errors(source: &str) -> Vec<String> {
+        let mut context = CompilationContext::new("main.velin".to_string(), source.to_string());
+        context.program = Some(Parser::parse(source).unwrap());
+        TypeCheckPass::new(true).run(&mut context).unwrap();
+        context.errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_stream_calls_are_typed_from_their_arguments() {
+        let compatible = |a: &Type, b: &Type| a == b || is_any(a) || is_any(b);
+        let numbers = stream_of(Type::Number);
+        let to_string = Type::Function {
+            params: vec![Type::Number],
+            return_type: Box::new(Type::String),
+        };
+
+        let mapped = call_type(
+            "map",
+            &[numbers.clone(), to_string.clone()],
+            None,
+            &compatible,
+        );
+        assert_eq!(mapped, Ok(stream_of(Type::String)));
+        let windows = call_type(
+            "window",
+            &[numbers.clone(), Type::Number, Type::Number],
+            None,
+            &compatible,
+        );
+        assert_eq!(windows, Ok(stream_of(Type::List(Box::new(Type::Number)))));
+        let events = call_type(
+            "from_topic",
+            &[Type::Named("EventBus".to_string()), Type::String],
+            Some("OrderPlaced"),
+            &compatible,
+        );
+        assert_eq!(
+            events,
+            Ok(stream_of(Type::Named("OrderPlaced".to_string())))
+        );
+
+        let mismatch = call_type(
+            "map",
+            &[stream_of(Type::String), to_string],
+            None,
+            &compatible,
+        );
+        assert_eq!(
+            mismatch,
+            Err("callback takes number, but the stream carries string".to_string())
+        );
+        assert!(call_type("batch", &[Type::Number, Type::Number], None, &compatible).is_err());
+        assert!(call_type("map_async", &[numbers], None, &compatible).is_err());
+
+        let code = StreamStdlib::generate_call_code(
+            "from_topic",
+            &["bus".to_string(), "\"orders\"".to_string()],
+            Some("OrderPlaced"),
+        );
+        assert_eq!(
+            code,
+            "crate::stdlib::stream::from_topic::<OrderPlaced, _>(&bus, \"orders\", 64.0)"
+        );
+        let runtime = StreamStdlib::generate_stream_runtime_code(Framework::Axum, false, true);
+        assert!(runtime.contains(
+            "impl<T: serde::Serialize + Send + 'static> axum::response::IntoResponse for Stream<T>"
+        ));
+        assert!(runtime.contains("pub fn from_topic<T, S>"));
+        assert!(!runtime.contains("pub fn send_to"));
+    }
+
+    #[test]
+    fn test_unknown_stream_function_is_reported_at_its_call() {
+        let source = r#"fn numbers(): List<number> {
+    return [1, 2, 3];
+}
+
+fn doubled(): Stream<number> {
+    let items = numbers();
+    return stream.map(stream.from_list(items), (n: number) => n * 2);
+}
+"#;
+        let errors = type_errors(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].starts_with(
+            "Type error: Invalid stream.from_list call: unknown stream function 'from_list', available: create, from,"
+        ));
+        assert!(errors[0].ends_with("at line 7, column 23"), "{}", errors[0]);
+
+        // Der zweite `stream.map` ist fehlerhaft, gemeldet wird dessen Position
+        let source = r#"fn labels(items: List<number>): Stream<string> {
+    let numbers = stream.from(items);
+    let doubled = stream.map(numbers, (n: number) => n * 2);
+    return stream.map(doubled, (s: string) => s);
+}
+"#;
+        let errors = type_errors(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(errors[0].contains(
+            "Invalid stream.map call: callback takes string, but the stream carries number"
+        ));
+        assert!(errors[0].ends_with("at line 4, column 12"), "{}", errors[0]);
     }
 
-    pub fn generate_buffer_code(stream: &str, size: &str) -> String {
-        format!("{}.buffered({} as usize).boxed()", stream, size)
+    fn run<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap()
+            .block_on(future)
     }
 
-    pub fn generate_merge_code(stream1: &str, stream2: &str) -> String {
-        format!("futures::stream::select({}, {}).boxed()", stream1, stream2)
+    #[test]
+    fn test_runtime_operators_transform_lazily_in_order() {
+        let doubled = run(runtime::collect(runtime::take(
+            runtime::filter(
+                runtime::map(runtime::from(vec![1.0, 2.0, 3.0, 4.0, 5.0]), |n: f64| {
+                    n * 2.0
+                }),
+                |n: f64| n > 2.0,
+            ),
+            3.0,
+        )));
+        assert_eq!(doubled, vec![4.0, 6.0, 8.0]);
+
+        let batches = run(runtime::collect(runtime::batch(
+            runtime::from(vec![1, 2, 3, 4, 5]),
+            2.0,
+        )));
+        assert_eq!(batches, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        let pairs = run(runtime::collect(runtime::zip(
+            runtime::from(vec!["a", "b", "c"]),
+            runtime::from(vec![1, 2]),
+        )));
+        assert_eq!(pairs, vec![("a", 1), ("b", 2)]);
+
+        let mut merged = run(runtime::collect(runtime::merge(
+            runtime::from(vec![1, 3]),
+            runtime::from(vec![2, 4]),
+        )));
+        merged.sort();
+        assert_eq!(merged, vec![1, 2, 3, 4]);
+
+        let total = run(runtime::reduce(
+            runtime::from(vec![1.0, 2.0, 3.0]),
+            |acc: f64, n: f64| acc + n,
+            0.0,
+        ));
+        assert_eq!(total, 6.0);
+        assert!(run(runtime::collect(runtime::empty::<i32>())).is_empty());
+    }
+
+    #[test]
+    fn test_runtime_window_closes_on_size_or_time() {
+        let by_size = run(runtime::collect(runtime::window(
+            runtime::from(vec![1, 2, 3, 4, 5]),
+            2.0,
+            1000.0,
+        )));
+        assert_eq!(by_size, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+        // Ein Takt alle 30 ms, das Fenster schließt nach 45 ms: höchstens zwei Ticks
+        let by_time = run(runtime::collect(runtime::take(
+            runtime::window(runtime::interval(30.0), 100.0, 45.0),
+            2.0,
+        )));
+        assert_eq!(by_time.len(), 2);
+        assert!(by_time.iter().all(|window| (1..=2).contains(&window.len())));
+        assert_eq!(by_time[0][0], 0.0);
+    }
+
+    #[test]
+    fn test_runtime_buffer_and_cancel_end_the_stream() {
+        let buffered = run(runtime::collect(runtime::buffer(
+            runtime::from((0..10).collect()),
+            2.0,
+        )));
+        assert_eq!(buffered, (0..10).collect::<Vec<_>>());
+
+        let token = runtime::cancel_token();
+        runtime::cancel(&token);
+        let cancelled = run(runtime::collect(runtime::with_cancel(
+            runtime::interval(1.0),
+            token,
+        )));
+        assert!(cancelled.is_empty());
+
+        let token = runtime::cancel_token();
+        let mut seen = Vec::new();
+        run(runtime::for_each(
+            runtime::with_cancel(runtime::interval(1.0), token.clone()),
+            |tick: f64| {
+                seen.push(tick);
+                if seen.len() == 3 {
+                    runtime::cancel(&token);
+                }
+            },
+        ));
+        assert_eq!(seen, vec![0.0, 1.0, 2.0]);
     }
 
-    pub fn generate_zip_code(stream1: &str, stream2: &str) -> String {
-        format!("{}.zip({}).boxed()", stream1, stream2)
+    #[test]
+    fn test_runtime_ndjson_lines_end_with_newline() {
+        assert_eq!(
+            runtime::ndjson_line(&serde_json::json!({ "id": "a" })),
+            "{\"id\":\"a\"}\n"
+        );
+        assert_eq!(runtime::ndjson_line(&vec![1, 2]), "[1,2]\n");
     }
 }
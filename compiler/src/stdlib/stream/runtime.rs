@@ -0,0 +1,273 @@
+// Streams für `stream`: Quellen, Operatoren, Abbruch und Terminal-Operationen.
+// Die Datei wird unverändert in das generierte Modul `stdlib::stream`
+// eingebettet; Antworten für axum/actix sowie Realtime- und Event-Bus-Anbindung
+// kommen dazu.
+
+use futures_util::stream::{BoxStream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// Lazy async Stream; läuft erst, wenn konsumiert wird, und nur einmal
+pub struct Stream<T> {
+    inner: BoxStream<'static, T>,
+}
+
+impl<T> std::fmt::Debug for Stream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Stream<{}>", std::any::type_name::<T>())
+    }
+}
+
+impl<T> futures_util::Stream for Stream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl<T: Send + 'static> Stream<T> {
+    pub fn new<S: futures_util::Stream<Item = T> + Send + 'static>(stream: S) -> Self {
+        Stream {
+            inner: stream.boxed(),
+        }
+    }
+}
+
+fn count(value: f64) -> usize {
+    if value.is_finite() && value >= 1.0 {
+        value as usize
+    } else {
+        1
+    }
+}
+
+fn millis(value: f64) -> Duration {
+    Duration::from_millis(if value.is_finite() && value > 0.0 {
+        value as u64
+    } else {
+        0
+    })
+}
+
+// --- Quellen ---
+
+pub fn empty<T: Send + 'static>() -> Stream<T> {
+    Stream::new(futures_util::stream::empty())
+}
+
+pub fn from<T: Send + 'static>(items: Vec<T>) -> Stream<T> {
+    Stream::new(futures_util::stream::iter(items))
+}
+
+/// Zählt 0, 1, 2, … im Abstand von `ms`; verpasste Takte werden nicht nachgeholt
+pub fn interval(ms: f64) -> Stream<f64> {
+    let period = millis(ms).max(Duration::from_millis(1));
+    Stream::new(futures_util::stream::unfold(
+        (None, 0.0),
+        move |(ticker, tick)| async move {
+            let mut ticker: tokio::time::Interval = ticker.unwrap_or_else(|| {
+                let mut ticker =
+                    tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                ticker
+            });
+            ticker.tick().await;
+            Some((tick, (Some(ticker), tick + 1.0)))
+        },
+    ))
+}
+
+// --- Operatoren ---
+
+pub fn map<T, U, F>(stream: Stream<T>, mapper: F) -> Stream<U>
+where
+    T: Send + 'static,
+    U: Send + 'static,
+    F: FnMut(T) -> U + Send + 'static,
+{
+    Stream::new(stream.map(mapper))
+}
+
+pub fn filter<T, F>(stream: Stream<T>, mut predicate: F) -> Stream<T>
+where
+    T: Clone + Send + 'static,
+    F: FnMut(T) -> bool + Send + 'static,
+{
+    Stream::new(stream.filter(move |item| futures_util::future::ready(predicate(item.clone()))))
+}
+
+/// Höchstens `concurrency` Aufrufe gleichzeitig, Reihenfolge bleibt erhalten
+pub fn map_async<T, U, F, Fut>(stream: Stream<T>, mapper: F, concurrency: f64) -> Stream<U>
+where
+    T: Send + 'static,
+    U: Send + 'static,
+    F: FnMut(T) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = U> + Send + 'static,
+{
+    Stream::new(stream.map(mapper).buffered(count(concurrency)))
+}
+
+pub fn take<T: Send + 'static>(stream: Stream<T>, n: f64) -> Stream<T> {
+    let n = if n.is_finite() && n > 0.0 {
+        n as usize
+    } else {
+        0
+    };
+    Stream::new(stream.take(n))
+}
+
+/// Fenster fester Größe; das letzte darf kleiner sein
+pub fn batch<T: Send + 'static>(stream: Stream<T>, size: f64) -> Stream<Vec<T>> {
+    Stream::new(stream.chunks(count(size)))
+}
+
+/// Fenster nach Anzahl oder Zeit: voll bei `size` Elementen oder `ms` nach dem ersten
+pub fn window<T: Send + 'static>(stream: Stream<T>, size: f64, ms: f64) -> Stream<Vec<T>> {
+    let size = count(size);
+    let span = millis(ms);
+    Stream::new(futures_util::stream::unfold(
+        Some(stream),
+        move |upstream| async move {
+            let mut upstream = upstream?;
+            let first = upstream.next().await?;
+            let mut window = vec![first];
+            let deadline = tokio::time::Instant::now() + span;
+            while window.len() < size {
+                match tokio::time::timeout_at(deadline, upstream.next()).await {
+                    Ok(Some(item)) => window.push(item),
+                    Ok(None) => return Some((window, None)),
+                    Err(_) => break,
+                }
+            }
+            Some((window, Some(upstream)))
+        },
+    ))
+}
+
+enum Buffered<T> {
+    Idle(Stream<T>, usize),
+    Running(tokio::sync::mpsc::Receiver<T>),
+}
+
+/// Entkoppelt Erzeuger und Verbraucher: der Erzeuger läuft höchstens `capacity`
+/// Elemente voraus und wartet dann. Gestartet wird beim ersten Abruf.
+pub fn buffer<T: Send + 'static>(stream: Stream<T>, capacity: f64) -> Stream<T> {
+    let state = Buffered::Idle(stream, count(capacity));
+    Stream::new(futures_util::stream::unfold(state, |state| async move {
+        let mut receiver = match state {
+            Buffered::Idle(upstream, capacity) => {
+                let (sender, receiver) = tokio::sync::mpsc::channel(capacity);
+                tokio::spawn(pump(upstream, sender));
+                receiver
+            }
+            Buffered::Running(receiver) => receiver,
+        };
+        let item = receiver.recv().await?;
+        Some((item, Buffered::Running(receiver)))
+    }))
+}
+
+/// Füllt den Puffer, bis der Stream endet oder niemand mehr liest
+async fn pump<T: Send + 'static>(mut upstream: Stream<T>, sender: tokio::sync::mpsc::Sender<T>) {
+    loop {
+        tokio::select! {
+            item = upstream.next() => match item {
+                Some(item) => {
+                    if sender.send(item).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            _ = sender.closed() => break,
+        }
+    }
+}
+
+pub fn merge<T: Send + 'static>(left: Stream<T>, right: Stream<T>) -> Stream<T> {
+    Stream::new(futures_util::stream::select(left, right))
+}
+
+pub fn zip<T: Send + 'static, U: Send + 'static>(
+    left: Stream<T>,
+    right: Stream<U>,
+) -> Stream<(T, U)> {
+    Stream::new(left.zip(right))
+}
+
+// --- Abbruch ---
+
+/// Beendet alle Streams, an die es mit `with_cancel` gehängt wurde
+#[derive(Clone, Debug)]
+pub struct CancelToken {
+    cancelled: Arc<tokio::sync::watch::Sender<bool>>,
+}
+
+impl CancelToken {
+    async fn cancelled(self) {
+        let mut receiver = self.cancelled.subscribe();
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+pub fn cancel_token() -> CancelToken {
+    CancelToken {
+        cancelled: Arc::new(tokio::sync::watch::channel(false).0),
+    }
+}
+
+pub fn cancel(token: &CancelToken) {
+    token.cancelled.send_replace(true);
+}
+
+/// Endet beim Abbruch; vorgelagerte Puffer und Abonnements werden dabei freigegeben
+pub fn with_cancel<T: Send + 'static>(stream: Stream<T>, token: CancelToken) -> Stream<T> {
+    Stream::new(stream.take_until(token.cancelled()))
+}
+
+// --- Terminal-Operationen ---
+
+pub async fn collect<T: Send + 'static>(stream: Stream<T>) -> Vec<T> {
+    stream.collect().await
+}
+
+pub async fn reduce<T, A, F>(stream: Stream<T>, mut reducer: F, initial: A) -> A
+where
+    T: Send + 'static,
+    F: FnMut(A, T) -> A,
+{
+    stream
+        .fold(initial, move |acc, item| {
+            futures_util::future::ready(reducer(acc, item))
+        })
+        .await
+}
+
+pub async fn for_each<T, R, F>(stream: Stream<T>, mut action: F)
+where
+    T: Send + 'static,
+    F: FnMut(T) -> R,
+{
+    stream
+        .for_each(move |item| {
+            action(item);
+            futures_util::future::ready(())
+        })
+        .await
+}
+
+// --- HTTP: Routen mit `Stream<T>` antworten als NDJSON ---
+
+/// Eine Zeile der NDJSON-Antwort
+pub fn ndjson_line<T: serde::Serialize>(item: &T) -> String {
+    match serde_json::to_string(item) {
+        Ok(mut line) => {
+            line.push('\n');
+            line
+        }
+        Err(error) => format!("{{\"error\":{:?}}}\n", error.to_string()),
+    }
+}
@@ -1,3 +1,16 @@
+// Template Standard Library
+// Templates mit Literal-Quelle (`template.render("...")`, `render_file`, `partial`)
+// werden beim Kompilieren geparst, gegen den Kontext-Typ geprüft und zu
+// Rust-Code übersetzt; dynamische Templates ersetzen weiterhin `{{key}}` zur Laufzeit
+
+pub mod runtime;
+
+use crate::parser::ast::*;
+use crate::parser::visitor::calls_module;
+use std::path::{Path, PathBuf};
+
+const RUNTIME: &str = "crate::stdlib::template";
+
 pub struct TemplateStdlib;
 
 impl TemplateStdlib {
@@ -92,3 +105,905 @@ impl TemplateStdlib {
         )
     }
 }
+
+/// Position eines Tags im Template (1-basiert), `file` fehlt bei Inline-Templates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "template {}:{}:{}", file, self.line, self.column),
+            None => write!(f, "inline template at {}:{}", self.line, self.column),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateError {
+    pub at: Position,
+    pub message: String,
+}
+
+impl TemplateError {
+    fn new(at: &Position, message: impl Into<String>) -> Self {
+        TemplateError {
+            at: at.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Punkt-Pfad wie `user.address.city`; leer steht für `this`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VarPath(pub Vec<String>);
+
+impl std::fmt::Display for VarPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            write!(f, "this")
+        } else {
+            write!(f, "{}", self.0.join("."))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Text(String),
+    /// `{{ path }}` (escaped) bzw. `{{{ path }}}` (raw)
+    Value {
+        path: VarPath,
+        escape: bool,
+        at: Position,
+    },
+    If {
+        path: VarPath,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+        at: Position,
+    },
+    Each {
+        path: VarPath,
+        binding: String,
+        body: Vec<Node>,
+        otherwise: Vec<Node>,
+        at: Position,
+    },
+    /// `{{> "file" path}}`; `body` wird beim Auflösen eingesetzt
+    Partial {
+        file: String,
+        context: Option<VarPath>,
+        body: Vec<Node>,
+        at: Position,
+    },
+}
+
+/// Werden `template.*`-Aufrufe verwendet? Dann braucht der Code die Laufzeit-Helfer
+pub fn uses_templates(program: &Program) -> bool {
+    calls_module(program, "template")
+}
+
+/// `"{{ name }}"` ist in Velin String-Interpolation (`{ name }`-Block), kein Template-Tag
+pub fn check_call(method: &str, args: &[Expression]) -> Result<(), String> {
+    if !matches!(method, "render" | "cache") {
+        return Ok(());
+    }
+    if let Some(Expression::FormatString { parts }) = args.first() {
+        let interpolates_block = parts.iter().any(|part| {
+            matches!(part, FormatStringPart::Expression(expr)
+                if matches!(expr.as_ref(), Expression::Block(_) | Expression::MapLiteral(_)))
+        });
+        if interpolates_block {
+            return Err(
+                "'{{' starts string interpolation, escape template tags as \\{\\{ name \\}\\} or use template.render_file"
+                    .to_string(),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Verzeichnis, relativ zu dem `render_file` und Partials aufgelöst werden
+pub fn source_dir(source_file: &str) -> PathBuf {
+    Path::new(source_file)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// Parst das Template eines `template.*`-Aufrufs, sofern die Quelle ein Literal ist.
+/// `None` heißt: dynamisches Template, wird zur Laufzeit gerendert.
+pub fn compile_call(
+    method: &str,
+    args: &[Expression],
+    base_dir: &Path,
+) -> Option<Result<Vec<Node>, Vec<TemplateError>>> {
+    if args.len() != 2 {
+        return None;
+    }
+    let source = literal_source(&args[0])?;
+    match method {
+        "render" => Some(compile(&source, None, base_dir)),
+        "render_file" | "renderFile" | "partial" => Some(compile_file(&source, base_dir)),
+        _ => None,
+    }
+}
+
+/// String-Literal oder Format-String ohne Interpolation (`"\{\{ name \}\}"`)
+fn literal_source(expr: &Expression) -> Option<String> {
+    match expr {
+        Expression::Literal(Literal::String(source)) => Some(source.clone()),
+        Expression::FormatString { parts } => parts
+            .iter()
+            .map(|part| match part {
+                FormatStringPart::Text(text) => Some(text.as_str()),
+                FormatStringPart::Expression(_) => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Inline-Template parsen; Partials werden relativ zu `base_dir` eingebunden
+pub fn compile(
+    source: &str,
+    file: Option<&str>,
+    base_dir: &Path,
+) -> Result<Vec<Node>, Vec<TemplateError>> {
+    let escape = file.map(escapes).unwrap_or(true);
+    let mut nodes = parse(source, file, escape).map_err(|error| vec![error])?;
+    let mut errors = Vec::new();
+    let mut stack: Vec<String> = file.map(|f| vec![f.to_string()]).unwrap_or_default();
+    expand(&mut nodes, file, base_dir, &mut stack, &mut errors);
+    if errors.is_empty() {
+        Ok(nodes)
+    } else {
+        Err(errors)
+    }
+}
+
+fn compile_file(file: &str, base_dir: &Path) -> Result<Vec<Node>, Vec<TemplateError>> {
+    let at = Position {
+        file: Some(file.to_string()),
+        line: 1,
+        column: 1,
+    };
+    let source = std::fs::read_to_string(base_dir.join(file)).map_err(|e| {
+        vec![TemplateError::new(
+            &at,
+            format!("cannot read template file: {}", e),
+        )]
+    })?;
+    compile(&source, Some(file), base_dir)
+}
+
+/// Auto-Escaping für HTML-artige Dateien
+fn escapes(file: &str) -> bool {
+    let extension = Path::new(file)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    matches!(extension.as_str(), "html" | "htm" | "xml" | "svg")
+}
+
+fn expand(
+    nodes: &mut [Node],
+    file: Option<&str>,
+    base_dir: &Path,
+    stack: &mut Vec<String>,
+    errors: &mut Vec<TemplateError>,
+) {
+    for node in nodes {
+        match node {
+            Node::If {
+                then, otherwise, ..
+            } => {
+                expand(then, file, base_dir, stack, errors);
+                expand(otherwise, file, base_dir, stack, errors);
+            }
+            Node::Each {
+                body, otherwise, ..
+            } => {
+                expand(body, file, base_dir, stack, errors);
+                expand(otherwise, file, base_dir, stack, errors);
+            }
+            Node::Partial {
+                file: partial,
+                body,
+                at,
+                ..
+            } => {
+                // Partials liegen relativ zur einbindenden Datei
+                let dir = file.and_then(|f| Path::new(f).parent());
+                let resolved = match dir {
+                    Some(dir) => dir.join(partial.as_str()),
+                    None => PathBuf::from(partial.as_str()),
+                }
+                .to_string_lossy()
+                .replace('\\', "/");
+                if stack.contains(&resolved) {
+                    let mut cycle = stack.clone();
+                    cycle.push(resolved);
+                    errors.push(TemplateError::new(
+                        at,
+                        format!("partial cycle: {}", cycle.join(" -> ")),
+                    ));
+                    continue;
+                }
+                let source = match std::fs::read_to_string(base_dir.join(&resolved)) {
+                    Ok(source) => source,
+                    Err(e) => {
+                        errors.push(TemplateError::new(
+                            at,
+                            format!("cannot read partial '{}': {}", resolved, e),
+                        ));
+                        continue;
+                    }
+                };
+                match parse(&source, Some(&resolved), escapes(&resolved)) {
+                    Ok(mut nodes) => {
+                        stack.push(resolved.clone());
+                        expand(&mut nodes, Some(&resolved), base_dir, stack, errors);
+                        stack.pop();
+                        *body = nodes;
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+            Node::Text(_) | Node::Value { .. } => {}
+        }
+    }
+}
+
+enum Block {
+    If(VarPath),
+    Each(VarPath, String),
+}
+
+impl Block {
+    fn name(&self) -> &'static str {
+        match self {
+            Block::If(_) => "if",
+            Block::Each(..) => "each",
+        }
+    }
+}
+
+struct Frame {
+    block: Block,
+    at: Position,
+    body: Vec<Node>,
+    otherwise: Option<Vec<Node>>,
+}
+
+fn position(source: &str, offset: usize, file: Option<&str>) -> Position {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    Position {
+        file: file.map(str::to_string),
+        line,
+        column,
+    }
+}
+
+fn parse_path(text: &str) -> Result<VarPath, String> {
+    if text == "this" || text == "." {
+        return Ok(VarPath(Vec::new()));
+    }
+    let segments: Vec<String> = text.split('.').map(str::to_string).collect();
+    let valid = segments.iter().all(|segment| {
+        let mut chars = segment.chars();
+        matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    });
+    if valid {
+        Ok(VarPath(segments))
+    } else {
+        Err(format!("invalid variable path '{}'", text))
+    }
+}
+
+fn parse(source: &str, file: Option<&str>, escape: bool) -> Result<Vec<Node>, TemplateError> {
+    let mut root: Vec<Node> = Vec::new();
+    let mut frames: Vec<Frame> = Vec::new();
+    let mut offset = 0;
+
+    fn target<'a>(root: &'a mut Vec<Node>, frames: &'a mut [Frame]) -> &'a mut Vec<Node> {
+        match frames.last_mut() {
+            Some(frame) => frame.otherwise.as_mut().unwrap_or(&mut frame.body),
+            None => root,
+        }
+    }
+
+    while let Some(found) = source[offset..].find("{{") {
+        let start = offset + found;
+        if found > 0 {
+            target(&mut root, &mut frames).push(Node::Text(source[offset..start].to_string()));
+        }
+        let at = position(source, start, file);
+        let rest = &source[start..];
+        let (open, close) = if rest.starts_with("{{!--") {
+            ("{{!--", "--}}")
+        } else if rest.starts_with("{{{") {
+            ("{{{", "}}}")
+        } else {
+            ("{{", "}}")
+        };
+        let inner_start = start + open.len();
+        let Some(length) = source[inner_start..].find(close) else {
+            return Err(TemplateError::new(&at, format!("unclosed '{}'", open)));
+        };
+        let inner = source[inner_start..inner_start + length].trim();
+        offset = inner_start + length + close.len();
+
+        if open == "{{!--" || inner.starts_with('!') {
+            continue;
+        }
+        if open == "{{{" {
+            let path = parse_path(inner).map_err(|e| TemplateError::new(&at, e))?;
+            target(&mut root, &mut frames).push(Node::Value {
+                path,
+                escape: false,
+                at,
+            });
+            continue;
+        }
+        if let Some(block) = inner.strip_prefix('#') {
+            let words: Vec<&str> = block.split_whitespace().collect();
+            let block = match words.as_slice() {
+                ["if", path] => {
+                    Block::If(parse_path(path).map_err(|e| TemplateError::new(&at, e))?)
+                }
+                ["each", path, "as", binding] => {
+                    let path = parse_path(path).map_err(|e| TemplateError::new(&at, e))?;
+                    match parse_path(binding) {
+                        Ok(VarPath(segments)) if segments.len() == 1 => {
+                            Block::Each(path, segments[0].clone())
+                        }
+                        _ => {
+                            return Err(TemplateError::new(
+                                &at,
+                                format!("invalid loop variable '{}'", binding),
+                            ))
+                        }
+                    }
+                }
+                ["each", ..] => {
+                    return Err(TemplateError::new(&at, "expected '{{#each list as item}}'"))
+                }
+                ["if", ..] => return Err(TemplateError::new(&at, "expected '{{#if path}}'")),
+                _ => {
+                    return Err(TemplateError::new(
+                        &at,
+                        format!("unknown block '{{{{{}}}}}', expected #if or #each", inner),
+                    ))
+                }
+            };
+            frames.push(Frame {
+                block,
+                at,
+                body: Vec::new(),
+                otherwise: None,
+            });
+            continue;
+        }
+        if inner == "else" {
+            match frames.last_mut() {
+                Some(frame) if frame.otherwise.is_none() => frame.otherwise = Some(Vec::new()),
+                Some(frame) => {
+                    return Err(TemplateError::new(
+                        &at,
+                        format!("second '{{{{else}}}}' in '{{{{#{}}}}}'", frame.block.name()),
+                    ))
+                }
+                None => {
+                    return Err(TemplateError::new(
+                        &at,
+                        "'{{else}}' outside of #if or #each",
+                    ))
+                }
+            }
+            continue;
+        }
+        if let Some(name) = inner.strip_prefix('/') {
+            let name = name.trim();
+            let Some(frame) = frames.pop() else {
+                return Err(TemplateError::new(
+                    &at,
+                    format!("'{{{{/{}}}}}' without opening block", name),
+                ));
+            };
+            if frame.block.name() != name {
+                return Err(TemplateError::new(
+                    &at,
+                    format!(
+                        "'{{{{/{}}}}}' closes '{{{{#{}}}}}' opened at {}:{}",
+                        name,
+                        frame.block.name(),
+                        frame.at.line,
+                        frame.at.column
+                    ),
+                ));
+            }
+            let otherwise = frame.otherwise.unwrap_or_default();
+            let node = match frame.block {
+                Block::If(path) => Node::If {
+                    path,
+                    then: frame.body,
+                    otherwise,
+                    at: frame.at,
+                },
+                Block::Each(path, binding) => Node::Each {
+                    path,
+                    binding,
+                    body: frame.body,
+                    otherwise,
+                    at: frame.at,
+                },
+            };
+            target(&mut root, &mut frames).push(node);
+            continue;
+        }
+        if let Some(partial) = inner.strip_prefix('>') {
+            let partial = partial.trim();
+            let (name, context) = if let Some(quoted) = partial.strip_prefix('"') {
+                match quoted.find('"') {
+                    Some(end) => (&quoted[..end], quoted[end + 1..].trim()),
+                    None => return Err(TemplateError::new(&at, "unterminated partial name")),
+                }
+            } else {
+                match partial.split_once(char::is_whitespace) {
+                    Some((name, context)) => (name, context.trim()),
+                    None => (partial, ""),
+                }
+            };
+            if name.is_empty() {
+                return Err(TemplateError::new(&at, "missing partial name"));
+            }
+            let context = if context.is_empty() {
+                None
+            } else {
+                Some(parse_path(context).map_err(|e| TemplateError::new(&at, e))?)
+            };
+            target(&mut root, &mut frames).push(Node::Partial {
+                file: name.to_string(),
+                context,
+                body: Vec::new(),
+                at,
+            });
+            continue;
+        }
+        let path = parse_path(inner).map_err(|e| TemplateError::new(&at, e))?;
+        target(&mut root, &mut frames).push(Node::Value { path, escape, at });
+    }
+    if offset < source.len() {
+        target(&mut root, &mut frames).push(Node::Text(source[offset..].to_string()));
+    }
+    if let Some(frame) = frames.pop() {
+        return Err(TemplateError::new(
+            &frame.at,
+            format!("'{{{{#{}}}}}' is never closed", frame.block.name()),
+        ));
+    }
+    Ok(root)
+}
+
+/// Deklarierte Typen, wie der Type Checker sie kennt
+pub enum Declared {
+    Struct(Vec<(String, Type)>),
+    Enum,
+}
+
+/// Kontext eines Templates: Typ des Ausdrucks oder Felder eines Map-Literals
+pub enum TemplateContext {
+    Type(Type),
+    Literal(Vec<(String, Type)>),
+}
+
+#[derive(Clone)]
+enum Shape {
+    Fields(String, Vec<(String, Type)>),
+    List(Type),
+    Map(Type),
+    Scalar,
+    Any,
+}
+
+struct Scope<'a> {
+    root: Shape,
+    bindings: Vec<(String, Type)>,
+    lookup: &'a dyn Fn(&str) -> Option<Declared>,
+}
+
+fn shape(ty: &Type, lookup: &dyn Fn(&str) -> Option<Declared>) -> Shape {
+    match ty {
+        Type::String | Type::Number | Type::Boolean | Type::Null => Shape::Scalar,
+        Type::Optional(inner) => shape(inner, lookup),
+        Type::List(inner) => Shape::List((**inner).clone()),
+        Type::Map { value, .. } => Shape::Map((**value).clone()),
+        Type::Generic { name, params } => match (name.as_str(), params.as_slice()) {
+            ("List" | "Vec", [item]) => Shape::List(item.clone()),
+            ("Map" | "HashMap", [_, value]) => Shape::Map(value.clone()),
+            ("Optional" | "Option", [inner]) => shape(inner, lookup),
+            _ => Shape::Any,
+        },
+        Type::Named(name) => match name.as_str() {
+            "string" | "number" | "boolean" => Shape::Scalar,
+            _ => match lookup(name) {
+                Some(Declared::Struct(fields)) => Shape::Fields(name.clone(), fields),
+                Some(Declared::Enum) => Shape::Scalar,
+                None => Shape::Any,
+            },
+        },
+        _ => Shape::Any,
+    }
+}
+
+impl Scope<'_> {
+    /// Löst einen Pfad auf: Schleifenvariablen vor Kontextfeldern
+    fn resolve(&self, path: &VarPath) -> Result<(Shape, String), String> {
+        let mut segments = path.0.iter();
+        let (mut current, mut described) = match path.0.first() {
+            None => return Ok((self.root.clone(), "this".to_string())),
+            Some(first) => match self.bindings.iter().rev().find(|(name, _)| name == first) {
+                Some((_, ty)) => {
+                    segments.next();
+                    (shape(ty, self.lookup), ty.to_string())
+                }
+                None => (self.root.clone(), "the template context".to_string()),
+            },
+        };
+        for segment in segments {
+            let next = match &current {
+                Shape::Fields(name, fields) => match fields.iter().find(|(f, _)| f == segment) {
+                    Some((_, ty)) => ty.clone(),
+                    None => return Err(format!("unknown field '{}' on {}", segment, name)),
+                },
+                Shape::Map(value) => value.clone(),
+                Shape::Any => return Ok((Shape::Any, "any".to_string())),
+                Shape::List(_) | Shape::Scalar => {
+                    return Err(format!(
+                        "cannot access '{}' on {} in '{}'",
+                        segment, described, path
+                    ))
+                }
+            };
+            current = shape(&next, self.lookup);
+            described = next.to_string();
+        }
+        Ok((current, described))
+    }
+}
+
+/// Prüft ein geparstes Template gegen den Kontext
+pub fn check(
+    nodes: &[Node],
+    context: TemplateContext,
+    lookup: &dyn Fn(&str) -> Option<Declared>,
+) -> Vec<TemplateError> {
+    let root = match context {
+        TemplateContext::Literal(fields) => Shape::Fields("the map literal".to_string(), fields),
+        TemplateContext::Type(ty) => shape(&ty, lookup),
+    };
+    let mut scope = Scope {
+        root,
+        bindings: Vec::new(),
+        lookup,
+    };
+    let mut errors = Vec::new();
+    check_nodes(nodes, &mut scope, &mut errors);
+    errors
+}
+
+fn check_nodes(nodes: &[Node], scope: &mut Scope, errors: &mut Vec<TemplateError>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Value { path, at, .. } => match scope.resolve(path) {
+                Ok((Shape::Scalar | Shape::Any, _)) => {}
+                Ok((_, ty)) => errors.push(TemplateError::new(
+                    at,
+                    format!(
+                        "'{}' is {}, only strings, numbers and booleans can be printed",
+                        path, ty
+                    ),
+                )),
+                Err(reason) => errors.push(TemplateError::new(at, reason)),
+            },
+            Node::If {
+                path,
+                then,
+                otherwise,
+                at,
+            } => {
+                if let Err(reason) = scope.resolve(path) {
+                    errors.push(TemplateError::new(at, reason));
+                }
+                check_nodes(then, scope, errors);
+                check_nodes(otherwise, scope, errors);
+            }
+            Node::Each {
+                path,
+                binding,
+                body,
+                otherwise,
+                at,
+            } => {
+                let item = match scope.resolve(path) {
+                    Ok((Shape::List(item), _)) => item,
+                    Ok((Shape::Any, _)) => Type::Any,
+                    Ok((_, ty)) => {
+                        errors.push(TemplateError::new(
+                            at,
+                            format!("'{}' is {}, #each needs a list", path, ty),
+                        ));
+                        Type::Any
+                    }
+                    Err(reason) => {
+                        errors.push(TemplateError::new(at, reason));
+                        Type::Any
+                    }
+                };
+                scope.bindings.push((binding.clone(), item));
+                check_nodes(body, scope, errors);
+                scope.bindings.pop();
+                check_nodes(otherwise, scope, errors);
+            }
+            Node::Partial {
+                file,
+                context,
+                body,
+                at,
+            } => {
+                let root = match context {
+                    None => scope.root.clone(),
+                    Some(path) => match scope.resolve(path) {
+                        Ok((shape @ (Shape::Fields(..) | Shape::Map(_) | Shape::Any), _)) => shape,
+                        Ok((_, ty)) => {
+                            errors.push(TemplateError::new(
+                                at,
+                                format!(
+                                    "context '{}' of partial '{}' is {}, expected a struct",
+                                    path, file, ty
+                                ),
+                            ));
+                            Shape::Any
+                        }
+                        Err(reason) => {
+                            errors.push(TemplateError::new(at, reason));
+                            Shape::Any
+                        }
+                    },
+                };
+                // Partials sehen nur ihren eigenen Kontext, keine Schleifenvariablen
+                let mut inner = Scope {
+                    root,
+                    bindings: Vec::new(),
+                    lookup: scope.lookup,
+                };
+                check_nodes(body, &mut inner, errors);
+            }
+        }
+    }
+}
+
+/// Erzeugt geradlinigen Rust-Code; `context` ist ein `serde_json::Value`-Ausdruck.
+/// `render_file`/`partial` behalten ihre `Result`-Signatur, obwohl nichts mehr fehlschlagen kann.
+pub fn generate_compiled_code(method: &str, nodes: &[Node], context: &str) -> String {
+    let mut emitter = Emitter {
+        code: String::new(),
+        next: 0,
+    };
+    emitter.code.push_str(&format!(
+        "{{\nlet __context: serde_json::Value = {};\nlet __root = &__context;\nlet mut __out = String::new();\n",
+        context
+    ));
+    emitter.nodes(nodes, "__root", &mut Vec::new());
+    emitter.code.push_str("__out\n}");
+    if method == "render" {
+        emitter.code
+    } else {
+        format!("Ok::<String, String>({})", emitter.code)
+    }
+}
+
+struct Emitter {
+    code: String,
+    next: usize,
+}
+
+impl Emitter {
+    fn value(&self, path: &VarPath, root: &str, bindings: &[(String, String)]) -> String {
+        let (var, segments) = match path.0.first() {
+            Some(first) => match bindings.iter().rev().find(|(name, _)| name == first) {
+                Some((_, var)) => (var.as_str(), &path.0[1..]),
+                None => (root, &path.0[..]),
+            },
+            None => (root, &path.0[..]),
+        };
+        if segments.is_empty() {
+            var.to_string()
+        } else {
+            let keys: Vec<String> = segments.iter().map(|s| format!("{:?}", s)).collect();
+            format!("{}::lookup({}, &[{}])", RUNTIME, var, keys.join(", "))
+        }
+    }
+
+    fn nodes(&mut self, nodes: &[Node], root: &str, bindings: &mut Vec<(String, String)>) {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self
+                    .code
+                    .push_str(&format!("__out.push_str({:?});\n", text)),
+                Node::Value { path, escape, .. } => {
+                    let value = self.value(path, root, bindings);
+                    self.code.push_str(&format!(
+                        "{}::write(&mut __out, {}, {});\n",
+                        RUNTIME, value, escape
+                    ));
+                }
+                Node::If {
+                    path,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    let value = self.value(path, root, bindings);
+                    self.code
+                        .push_str(&format!("if {}::truthy({}) {{\n", RUNTIME, value));
+                    self.nodes(then, root, bindings);
+                    if !otherwise.is_empty() {
+                        self.code.push_str("} else {\n");
+                        self.nodes(otherwise, root, bindings);
+                    }
+                    self.code.push_str("}\n");
+                }
+                Node::Each {
+                    path,
+                    binding,
+                    body,
+                    otherwise,
+                    ..
+                } => {
+                    self.next += 1;
+                    let items = format!("__items_{}", self.next);
+                    let item = format!("__item_{}", self.next);
+                    let value = self.value(path, root, bindings);
+                    self.code.push_str(&format!(
+                        "let {} = {}::items({});\nfor {} in {} {{\n",
+                        items, RUNTIME, value, item, items
+                    ));
+                    bindings.push((binding.clone(), item));
+                    self.nodes(body, root, bindings);
+                    bindings.pop();
+                    self.code.push_str("}\n");
+                    if !otherwise.is_empty() {
+                        self.code.push_str(&format!("if {}.is_empty() {{\n", items));
+                        self.nodes(otherwise, root, bindings);
+                        self.code.push_str("}\n");
+                    }
+                }
+                Node::Partial { context, body, .. } => {
+                    self.next += 1;
+                    let scope = format!("__partial_{}", self.next);
+                    let value = match context {
+                        Some(path) => self.value(path, root, bindings),
+                        None => root.to_string(),
+                    };
+                    self.code
+                        .push_str(&format!("{{\nlet {} = {};\n", scope, value));
+                    self.nodes(body, &scope, &mut Vec::new());
+                    self.code.push_str("}\n");
+                }
+            }
+        }
+    }
+}
+
+/// Laufzeit-Helfer für kompilierte Templates (`crate::stdlib::template`)
+pub const RUNTIME_CODE: &str = include_str!("template/runtime.rs");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_templates_are_checked_against_the_context_struct() {
+        let lookup = |name: &str| match name {
+            "User" => Some(Declared::Struct(vec![
+                ("name".to_string(), Type::String),
+                ("tags".to_string(), Type::List(Box::new(Type::String))),
+            ])),
+            "Role" => Some(Declared::Enum),
+            _ => None,
+        };
+        let page = Type::Named("User".to_string());
+
+        let nodes = compile(
+            "<b>{{ name }}</b>\n{{#each tags as tag}}{{ tag }}{{else}}-{{/each}}{{ nme }}",
+            None,
+            Path::new("."),
+        )
+        .unwrap();
+        let errors = check(&nodes, TemplateContext::Type(page.clone()), &lookup);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "unknown field 'nme' on User");
+        assert_eq!((errors[0].at.line, errors[0].at.column), (2, 49));
+
+        let errors = check(
+            &compile("{{ tags }}", None, Path::new(".")).unwrap(),
+            TemplateContext::Type(page.clone()),
+            &lookup,
+        );
+        assert!(errors[0]
+            .message
+            .contains("only strings, numbers and booleans"));
+        let errors = check(
+            &compile("{{#each name as n}}{{/each}}", None, Path::new(".")).unwrap(),
+            TemplateContext::Type(page),
+            &lookup,
+        );
+        assert_eq!(errors[0].message, "'name' is string, #each needs a list");
+
+        let unclosed = compile("a\n  {{#if name}}", None, Path::new(".")).unwrap_err();
+        assert_eq!(unclosed[0].at.to_string(), "inline template at 2:3");
+
+        let code = generate_compiled_code("render", &nodes, "serde_json::json!((user))");
+        assert!(code.contains("crate::stdlib::template::write(&mut __out, crate::stdlib::template::lookup(__root, &[\"name\"]), true);"));
+        assert!(code.contains("for __item_1 in __items_1 {"));
+        assert!(code.contains("if __items_1.is_empty() {"));
+    }
+
+    #[test]
+    fn test_runtime_helpers_resolve_test_and_escape_values() {
+        let context = serde_json::json!({
+            "user": { "name": "<Ann & 'Bo'>", "tags": ["a", "b"] },
+            "price": 2.5,
+            "count": 3,
+        });
+        let name = runtime::lookup(&context, &["user", "name"]);
+        assert_eq!(name, "<Ann & 'Bo'>");
+        assert!(runtime::lookup(&context, &["user", "email", "domain"]).is_null());
+        assert_eq!(
+            runtime::items(runtime::lookup(&context, &["user", "tags"])).len(),
+            2
+        );
+        assert!(runtime::items(name).is_empty());
+
+        let render = |value: &serde_json::Value, escape: bool| {
+            let mut out = String::new();
+            runtime::write(&mut out, value, escape);
+            out
+        };
+        assert_eq!(render(name, true), "&lt;Ann &amp; &#39;Bo&#39;&gt;");
+        assert_eq!(render(name, false), "<Ann & 'Bo'>");
+        assert_eq!(render(&context["price"], true), "2.5");
+        assert_eq!(render(&context["count"], true), "3");
+        assert_eq!(render(&serde_json::Value::Null, true), "");
+        assert_eq!(
+            render(&serde_json::json!(["\"x\""]), true),
+            "[&quot;\\&quot;x\\&quot;&quot;]"
+        );
+
+        for (value, truthy) in [
+            (serde_json::json!(null), false),
+            (serde_json::json!(false), false),
+            (serde_json::json!(0), false),
+            (serde_json::json!(""), false),
+            (serde_json::json!([]), false),
+            (serde_json::json!("0"), true),
+            (serde_json::json!(0.5), true),
+            (serde_json::json!([0]), true),
+            (serde_json::json!({}), true),
+        ] {
+            assert_eq!(runtime::truthy(&value), truthy, "{}", value);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Laufzeit-Helfer für kompilierte Templates: Pfade auflösen, Bedingungen und
+// Listen auswerten, Werte HTML-escaped ausgeben.
+// Die Datei wird unverändert in das generierte Modul `stdlib::template`
+// eingebettet.
+
+static NULL: serde_json::Value = serde_json::Value::Null;
+
+pub fn lookup<'a>(value: &'a serde_json::Value, path: &[&str]) -> &'a serde_json::Value {
+    path.iter()
+        .try_fold(value, |current, key| current.get(*key))
+        .unwrap_or(&NULL)
+}
+
+pub fn items(value: &serde_json::Value) -> &[serde_json::Value] {
+    value
+        .as_array()
+        .map(|items| items.as_slice())
+        .unwrap_or(&[])
+}
+
+pub fn truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null => false,
+        serde_json::Value::Bool(b) => *b,
+        serde_json::Value::Number(n) => n.as_f64() != Some(0.0),
+        serde_json::Value::String(s) => !s.is_empty(),
+        serde_json::Value::Array(items) => !items.is_empty(),
+        serde_json::Value::Object(_) => true,
+    }
+}
+
+pub fn write(out: &mut String, value: &serde_json::Value, escape: bool) {
+    let text = match value {
+        serde_json::Value::Null => return,
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => match n.as_f64() {
+            Some(f) => f.to_string(),
+            None => n.to_string(),
+        },
+        other => other.to_string(),
+    };
+    if !escape {
+        out.push_str(&text);
+        return;
+    }
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
@@ -1,38 +1,667 @@
+// Tracing Standard Library
+// OpenTelemetry-kompatible Spans: Routen, `db.*`, `http.*` und `llm.*` werden
+// automatisch instrumentiert, der Kontext reist als W3C `traceparent` mit
+// `http_client`-Aufrufen, exportiert wird per OTLP/HTTP JSON
+
+pub mod context;
+
+use crate::parser::ast::*;
+use crate::parser::visitor::calls_module;
+
 pub struct TracingStdlib;
 
+/// Pfad des generierten Runtime-Moduls
+const RUNTIME: &str = "crate::stdlib::tracing";
+
+/// Quelltext von `context.rs`, wird in die Runtime eingebettet
+const CONTEXT_SOURCE: &str = include_str!("tracing/context.rs");
+
+/// `true`, sobald das Programm das `tracing`-Modul verwendet
+///
+/// Erst dann werden Routen und Stdlib-Aufrufe mit Spans versehen.
+pub fn uses_tracing(program: &Program) -> bool {
+    calls_module(program, "tracing")
+}
+
+/// Collector-URL für OTLP/HTTP; wie bei `OTEL_EXPORTER_OTLP_ENDPOINT` wird
+/// `/v1/traces` angehängt
+pub fn traces_url(endpoint: &str) -> Result<String, String> {
+    let authority = endpoint
+        .strip_prefix("http://")
+        .map(|rest| rest.split('/').next().unwrap_or_default())
+        .unwrap_or_default();
+    if authority.is_empty() {
+        return Err(format!(
+            "unsupported OTLP endpoint '{}', use http://host:port",
+            endpoint
+        ));
+    }
+    Ok(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+}
+
+/// Prüft `tracing.*`-Aufrufe mit Literal-Argumenten
+pub fn check_call(method: &str, args: &[Expression]) -> Result<(), String> {
+    match (method, args) {
+        ("start_span" | "startSpan", [Expression::Literal(Literal::String(name))])
+        | ("child_span" | "childSpan", [_, Expression::Literal(Literal::String(name))])
+            if name.trim().is_empty() =>
+        {
+            Err("span name must not be empty".to_string())
+        }
+        ("set_attribute" | "setAttribute", [_, Expression::Literal(Literal::String(key)), _])
+            if key.trim().is_empty() =>
+        {
+            Err("attribute key must not be empty".to_string())
+        }
+        ("export", [Expression::Literal(Literal::String(endpoint))]) => {
+            traces_url(endpoint).map(|_| ())
+        }
+        _ => Ok(()),
+    }
+}
+
 impl TracingStdlib {
+    /// Runtime-Modul `stdlib::tracing`
+    pub fn generate_tracing_runtime_code() -> String {
+        RUNTIME_TEMPLATE.replace("/*CONTEXT*/\n", CONTEXT_SOURCE)
+    }
+
     pub fn generate_start_span_code(name: &str) -> String {
-        format!("tracing::span!(tracing::Level::INFO, \"{}\")", name)
+        format!("{}::Span::internal(&{}, None)", RUNTIME, name)
     }
 
     pub fn generate_set_attribute_code(span: &str, key: &str, value: &str) -> String {
-        format!("{}.record(\"{}\", {});", span, key, value)
+        format!("{}.set_attribute(&{}, &{})", span, key, value)
     }
 
     pub fn generate_child_span_code(parent: &str, name: &str) -> String {
+        format!("{}::Span::internal(&{}, Some(&{}))", RUNTIME, name, parent)
+    }
+
+    pub fn generate_end_span_code(span: &str) -> String {
+        format!("{}.end()", span)
+    }
+
+    pub fn generate_export_code(endpoint: &str) -> String {
+        format!("{}::export(&{})", RUNTIME, endpoint)
+    }
+
+    pub fn generate_traceparent_code() -> String {
+        format!("{}::traceparent()", RUNTIME)
+    }
+
+    /// Umschließt einen Stdlib-Aufruf mit einem Client-Span
+    ///
+    /// Ein `Err`-Ergebnis setzt den Span-Status auf Fehler. Mit `propagate`
+    /// bekommt der letzte `.send()` des Aufrufs den `traceparent`-Header.
+    pub fn generate_client_span_code(
+        name: &str,
+        attributes: &[(&str, &str)],
+        call: &str,
+        propagate: bool,
+    ) -> String {
+        let call = match call.rfind(".send()") {
+            Some(index) if propagate => format!(
+                "{}.header(\"traceparent\", __span.traceparent()){}",
+                &call[..index],
+                &call[index..]
+            ),
+            _ => call.to_string(),
+        };
         format!(
-            "tracing::span!(parent: {}, tracing::Level::INFO, \"{}\")",
-            parent, name
+            "{{\n    let __span = {}::Span::client({:?}, &{});\n    let __result = {};\n    {}\n    __result\n}}",
+            RUNTIME,
+            name,
+            Self::attributes(attributes),
+            call,
+            Self::generate_finish_code("__span", "__result")
         )
     }
 
-    pub fn generate_end_span_code(span: &str) -> String {
-        format!("drop({})", span)
+    /// Server-Span einer Route; `traceparent` ist ein `Option<&str>`-Ausdruck
+    pub fn generate_server_span_code(
+        method: &str,
+        route: &str,
+        function: &str,
+        traceparent: &str,
+    ) -> String {
+        format!(
+            "let __trace = {}::Span::server({:?}, &{}, {});",
+            RUNTIME,
+            format!("{} {}", method, route),
+            Self::attributes(&[
+                ("http.request.method", method),
+                ("http.route", route),
+                ("code.function", function),
+            ]),
+            traceparent
+        )
     }
 
-    pub fn generate_export_code(format: &str) -> String {
+    /// Führt den Rumpf einer Route im Kontext von `__trace` aus
+    pub fn generate_scope_start_code(return_type: &str, is_async: bool) -> String {
+        if is_async {
+            format!(
+                "let __result: {} = {}::CURRENT.scope(__trace.context(), async move {{",
+                return_type, RUNTIME
+            )
+        } else {
+            format!(
+                "let __result: {} = {}::CURRENT.sync_scope(__trace.context(), || {{",
+                return_type, RUNTIME
+            )
+        }
+    }
+
+    pub fn generate_scope_end_code(is_async: bool) -> String {
+        if is_async {
+            "}).await;".to_string()
+        } else {
+            "});".to_string()
+        }
+    }
+
+    /// Beendet `span`; Fehler aus einem `Result` landen im Span-Status
+    pub fn generate_finish_code(span: &str, result: &str) -> String {
         format!(
-            "{{
-                // Export tracing data in {:?} format
-                // This would typically be handled by a tracing subscriber
-                // For now, this is a placeholder
-                Ok(())
-            }}",
-            format
+            "{{\n        #[allow(unused_imports)]\n        use {}::{{ErrorStatus as _, NoStatus as _}};\n        {}.end_with((&{}).error_message());\n    }}",
+            RUNTIME, span, result
         )
     }
 
-    pub fn generate_span_enter_code(span: &str) -> String {
-        format!("let _guard = {}.enter();", span)
+    fn attributes(attributes: &[(&str, &str)]) -> String {
+        let pairs: Vec<String> = attributes
+            .iter()
+            .map(|(key, value)| format!("({:?}, {:?})", key, value))
+            .collect();
+        format!("[{}]", pairs.join(", "))
+    }
+}
+
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Tracing Runtime ---
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Gepufferte Spans, solange kein Collector konfiguriert ist; ältere werden verworfen
+const BUFFER_LIMIT: usize = 2048;
+/// Ab dieser Menge wird sofort exportiert
+const BATCH_SIZE: usize = 512;
+const EXPORT_INTERVAL: Duration = Duration::from_secs(2);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const KIND_INTERNAL: u8 = 1;
+const KIND_SERVER: u8 = 2;
+const KIND_CLIENT: u8 = 3;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+// --- Kontext ---
+
+pub mod context {
+/*CONTEXT*/
+}
+
+pub use context::SpanContext;
+use context::hex;
+
+tokio::task_local! {
+    /// Span der laufenden Route
+    pub static CURRENT: SpanContext;
+}
+
+fn current() -> Option<SpanContext> {
+    CURRENT.try_with(|context| *context).ok()
+}
+
+/// `tracing.traceparent`: Header für eigene Transporte, leer außerhalb eines Spans
+pub fn traceparent() -> String {
+    current()
+        .map(|context| context.traceparent())
+        .unwrap_or_default()
+}
+
+// --- Spans ---
+
+struct Record {
+    context: SpanContext,
+    parent: Option<[u8; 8]>,
+    name: String,
+    kind: u8,
+    start: u128,
+    end: u128,
+    attributes: Vec<(String, serde_json::Value)>,
+    error: Option<String>,
+}
+
+/// Endet spätestens beim Drop; ein Panic markiert den Span als fehlgeschlagen
+pub struct Span {
+    context: SpanContext,
+    record: Mutex<Option<Record>>,
+}
+
+impl std::fmt::Debug for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Span({})", self.context.traceparent())
+    }
+}
+
+impl Span {
+    fn start(name: &str, kind: u8, parent: Option<SpanContext>, attributes: &[(&str, &str)]) -> Span {
+        let context = parent.map(|p| p.child()).unwrap_or_else(SpanContext::root);
+        Span {
+            context,
+            record: Mutex::new(Some(Record {
+                context,
+                parent: parent.map(|p| p.span_id),
+                name: name.to_string(),
+                kind,
+                start: now_nanos(),
+                end: 0,
+                attributes: attributes
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), serde_json::Value::from(*value)))
+                    .collect(),
+                error: None,
+            })),
+        }
+    }
+
+    /// Route; ein gültiger `traceparent` des Aufrufers setzt dessen Trace fort
+    pub fn server(name: &str, attributes: &[(&str, &str)], traceparent: Option<&str>) -> Span {
+        let parent = traceparent.and_then(SpanContext::parse).or_else(current);
+        Span::start(name, KIND_SERVER, parent, attributes)
+    }
+
+    /// Ausgehender Aufruf (`db.*`, `http.*`, `llm.*`) unterhalb der laufenden Route
+    pub fn client(name: &str, attributes: &[(&str, &str)]) -> Span {
+        Span::start(name, KIND_CLIENT, current(), attributes)
+    }
+
+    /// `tracing.start_span` / `tracing.child_span`
+    pub fn internal(name: &str, parent: Option<&Span>) -> Span {
+        let parent = parent.map(|p| p.context).or_else(current);
+        Span::start(name, KIND_INTERNAL, parent, &[])
+    }
+
+    pub fn context(&self) -> SpanContext {
+        self.context
+    }
+
+    pub fn traceparent(&self) -> String {
+        self.context.traceparent()
+    }
+
+    pub fn set_attribute<V: serde::Serialize>(&self, key: &str, value: &V) {
+        if let Some(record) = lock(&self.record).as_mut() {
+            let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+            record.attributes.retain(|(existing, _)| existing != key);
+            record.attributes.push((key.to_string(), value));
+        }
+    }
+
+    pub fn end(&self) {
+        self.end_with(None);
+    }
+
+    /// Weitere Aufrufe nach dem ersten sind wirkungslos
+    pub fn end_with(&self, error: Option<String>) {
+        let Some(mut record) = lock(&self.record).take() else {
+            return;
+        };
+        record.end = now_nanos();
+        record.error = error;
+        if record.context.sampled {
+            enqueue(record);
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.end_with(Some("panicked".to_string()));
+        } else {
+            self.end();
+        }
+    }
+}
+
+/// Fehlertext eines `Result`; alle anderen Werte liefern über `NoStatus` `None`
+pub trait ErrorStatus {
+    fn error_message(&self) -> Option<String>;
+}
+
+impl<T, E: std::fmt::Display> ErrorStatus for Result<T, E> {
+    fn error_message(&self) -> Option<String> {
+        self.as_ref().err().map(|e| e.to_string())
+    }
+}
+
+pub trait NoStatus {
+    fn error_message(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T> NoStatus for &T {}
+
+// --- OTLP-Export ---
+
+#[derive(Default)]
+struct Exporter {
+    endpoint: Option<String>,
+    buffer: VecDeque<Record>,
+    running: bool,
+}
+
+fn exporter() -> &'static (Mutex<Exporter>, Condvar) {
+    static EXPORTER: OnceLock<(Mutex<Exporter>, Condvar)> = OnceLock::new();
+    EXPORTER.get_or_init(|| {
+        let traces = std::env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT").ok();
+        let base = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok();
+        let endpoint = traces
+            .filter(|url| !url.is_empty())
+            .or_else(|| base.and_then(|url| traces_url(&url).ok()));
+        let state = Exporter {
+            endpoint,
+            ..Default::default()
+        };
+        (Mutex::new(state), Condvar::new())
+    })
+}
+
+fn traces_url(endpoint: &str) -> Result<String, String> {
+    let authority = endpoint
+        .strip_prefix("http://")
+        .map(|rest| rest.split('/').next().unwrap_or_default())
+        .unwrap_or_default();
+    if authority.is_empty() {
+        return Err(format!("unsupported OTLP endpoint '{}', use http://host:port", endpoint));
+    }
+    Ok(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+}
+
+fn enqueue(record: Record) {
+    let (state, wakeup) = exporter();
+    let mut exporter = lock(state);
+    if exporter.buffer.len() >= BUFFER_LIMIT {
+        exporter.buffer.pop_front();
+    }
+    exporter.buffer.push_back(record);
+    if !exporter.running {
+        exporter.running = true;
+        std::thread::spawn(export_loop);
+    }
+    if exporter.buffer.len() >= BATCH_SIZE {
+        wakeup.notify_one();
+    }
+}
+
+fn export_loop() {
+    let (state, wakeup) = exporter();
+    loop {
+        let (endpoint, batch) = {
+            let exporter = lock(state);
+            let (mut exporter, _) = wakeup
+                .wait_timeout(exporter, EXPORT_INTERVAL)
+                .unwrap_or_else(|e| e.into_inner());
+            match exporter.endpoint.clone() {
+                Some(endpoint) if !exporter.buffer.is_empty() => {
+                    let batch: Vec<Record> = exporter.buffer.drain(..).collect();
+                    (endpoint, batch)
+                }
+                _ => continue,
+            }
+        };
+        if let Err(error) = send(&endpoint, &batch) {
+            eprintln!("[Tracing] OTLP export to '{}' failed: {}", endpoint, error);
+            requeue(batch);
+        }
+    }
+}
+
+/// Nicht zugestellte Spans kommen vor die neueren zurück in den Puffer
+fn requeue(batch: Vec<Record>) {
+    let mut exporter = lock(&exporter().0);
+    for record in batch.into_iter().rev() {
+        if exporter.buffer.len() >= BUFFER_LIMIT {
+            break;
+        }
+        exporter.buffer.push_front(record);
+    }
+}
+
+/// `tracing.export`: sendet gepufferte Spans und exportiert danach laufend dorthin
+pub fn export(endpoint: &str) -> Result<(), String> {
+    let url = traces_url(endpoint)?;
+    let batch: Vec<Record> = {
+        let mut exporter = lock(&exporter().0);
+        exporter.endpoint = Some(url.clone());
+        exporter.buffer.drain(..).collect()
+    };
+    if batch.is_empty() {
+        return Ok(());
+    }
+    send(&url, &batch).map_err(|error| {
+        requeue(batch);
+        error
+    })
+}
+
+fn service_name() -> String {
+    std::env::var("OTEL_SERVICE_NAME")
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| option_env!("CARGO_PKG_NAME").unwrap_or("velin-service").to_string())
+}
+
+fn any_value(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(text) => serde_json::json!({ "stringValue": text }),
+        serde_json::Value::Bool(flag) => serde_json::json!({ "boolValue": flag }),
+        serde_json::Value::Number(n) if n.is_i64() => {
+            serde_json::json!({ "intValue": n.to_string() })
+        }
+        serde_json::Value::Number(n) => serde_json::json!({ "doubleValue": n.as_f64() }),
+        other => serde_json::json!({ "stringValue": other.to_string() }),
+    }
+}
+
+fn key_value(key: &str, value: &serde_json::Value) -> serde_json::Value {
+    serde_json::json!({ "key": key, "value": any_value(value) })
+}
+
+/// OTLP/JSON: IDs als Hex, Zeitstempel als Nanosekunden-Strings
+fn encode(batch: &[Record]) -> serde_json::Value {
+    let spans: Vec<serde_json::Value> = batch
+        .iter()
+        .map(|record| {
+            let mut span = serde_json::json!({
+                "traceId": hex(&record.context.trace_id),
+                "spanId": hex(&record.context.span_id),
+                "name": record.name,
+                "kind": record.kind,
+                "startTimeUnixNano": record.start.to_string(),
+                "endTimeUnixNano": record.end.to_string(),
+                "attributes": record
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| key_value(key, value))
+                    .collect::<Vec<_>>(),
+                "status": match &record.error {
+                    Some(message) => serde_json::json!({ "code": 2, "message": message }),
+                    None => serde_json::json!({ "code": 0 }),
+                },
+            });
+            if let Some(parent) = record.parent {
+                span["parentSpanId"] = serde_json::Value::from(hex(&parent));
+            }
+            span
+        })
+        .collect();
+    serde_json::json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [key_value("service.name", &serde_json::Value::from(service_name()))]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "velin.tracing" },
+                "spans": spans
+            }]
+        }]
+    })
+}
+
+/// POST an den Collector über eine eigene TCP-Verbindung (nur `http://`)
+fn send(url: &str, batch: &[Record]) -> Result<(), String> {
+    use std::net::ToSocketAddrs;
+
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("unsupported OTLP endpoint '{}', use http://host:port", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    let socket = address
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("cannot resolve '{}'", authority))?;
+    let mut stream = std::net::TcpStream::connect_timeout(&socket, EXPORT_TIMEOUT)
+        .map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(EXPORT_TIMEOUT)).ok();
+    stream.set_write_timeout(Some(EXPORT_TIMEOUT)).ok();
+
+    let body = encode(batch).to_string();
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        authority,
+        body.len(),
+        body
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+
+    let mut response = Vec::new();
+    let mut chunk = [0u8; 512];
+    while !response.windows(2).any(|w| w == b"\r\n") {
+        let read = stream.read(&mut chunk).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        response.extend_from_slice(&chunk[..read]);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status = response.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(format!("collector answered '{}'", status)),
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::context::SpanContext;
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_tracing_calls_are_detected_and_validated() {
+        let source = r#"fn main() {
+    tracing.export("http://localhost:4318");
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        assert!(uses_tracing(&program));
+        let plain = Parser::parse("fn main() {\n    let x = 1;\n}\n").unwrap();
+        assert!(!uses_tracing(&plain));
+
+        let literal = |text: &str| Expression::Literal(Literal::String(text.to_string()));
+        assert_eq!(
+            traces_url("http://localhost:4318/").unwrap(),
+            "http://localhost:4318/v1/traces"
+        );
+        assert!(check_call("export", &[literal("https://collector:4318")])
+            .unwrap_err()
+            .contains("use http://host:port"));
+        assert!(check_call("start_span", &[literal(" ")])
+            .unwrap_err()
+            .contains("span name must not be empty"));
+        assert!(check_call("start_span", &[literal("checkout")]).is_ok());
+
+        let wrapped = TracingStdlib::generate_client_span_code(
+            "GET",
+            &[("http.request.method", "GET")],
+            "client.get(url).send().await",
+            true,
+        );
+        assert!(wrapped.contains(
+            "client.get(url).header(\"traceparent\", __span.traceparent()).send().await"
+        ));
+        assert!(wrapped.contains("__span.end_with((&__result).error_message())"));
+    }
+
+    #[test]
+    fn test_traceparent_is_parsed_and_propagated() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let parent = SpanContext::parse(header).unwrap();
+        assert!(parent.sampled);
+        assert_eq!(parent.traceparent(), header);
+
+        // Kind-Spans bleiben im Trace und erben die Sampling-Entscheidung
+        let child = parent.child();
+        assert_eq!(child.trace_id, parent.trace_id);
+        assert_ne!(child.span_id, parent.span_id);
+        assert!(child
+            .traceparent()
+            .starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+        assert!(child.traceparent().ends_with("-01"));
+
+        let unsampled =
+            SpanContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00").unwrap();
+        assert!(!unsampled.child().sampled);
+        // Spätere Versionen dürfen Felder anhängen
+        assert!(SpanContext::parse(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra"
+        )
+        .is_some());
+
+        let root = SpanContext::root();
+        assert!(root.sampled);
+        assert_ne!(root.trace_id, SpanContext::root().trace_id);
+        assert_eq!(SpanContext::parse(&root.traceparent()), Some(root));
+
+        for invalid in [
+            "",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902bz-01",
+        ] {
+            assert_eq!(SpanContext::parse(invalid), None, "{}", invalid);
+        }
     }
 }
@@ -0,0 +1,81 @@
+// Span-Kontext für `tracing`: IDs und W3C-`traceparent`-Header.
+// Die Datei wird unverändert in das generierte Modul `stdlib::tracing`
+// eingebettet.
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpanContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub sampled: bool,
+}
+
+fn new_span_id() -> [u8; 8] {
+    let mut id = [0u8; 8];
+    id.copy_from_slice(&uuid::Uuid::new_v4().as_bytes()[..8]);
+    id
+}
+
+impl SpanContext {
+    /// Neuer Trace für eine Anfrage ohne `traceparent`
+    pub fn root() -> SpanContext {
+        SpanContext {
+            trace_id: *uuid::Uuid::new_v4().as_bytes(),
+            span_id: new_span_id(),
+            sampled: true,
+        }
+    }
+
+    /// Kind-Span im selben Trace
+    pub fn child(&self) -> SpanContext {
+        SpanContext {
+            span_id: new_span_id(),
+            ..*self
+        }
+    }
+
+    /// W3C Trace Context: `00-<trace-id>-<parent-id>-<flags>`
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-{}",
+            hex(&self.trace_id),
+            hex(&self.span_id),
+            if self.sampled { "01" } else { "00" }
+        )
+    }
+
+    pub fn parse(header: &str) -> Option<SpanContext> {
+        let mut parts = header.trim().split('-');
+        let (version, trace, span, flags) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if version.len() != 2 || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        let decode = |text: &str, out: &mut [u8]| -> Option<()> {
+            if text.len() != out.len() * 2 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            for (i, byte) in out.iter_mut().enumerate() {
+                *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+            }
+            Some(())
+        };
+        let mut context = SpanContext {
+            trace_id: [0; 16],
+            span_id: [0; 8],
+            sampled: false,
+        };
+        decode(trace, &mut context.trace_id)?;
+        decode(span, &mut context.span_id)?;
+        let mut flags_byte = [0u8; 1];
+        decode(flags, &mut flags_byte)?;
+        context.sampled = flags_byte[0] & 1 == 1;
+        if context.trace_id == [0; 16] || context.span_id == [0; 8] {
+            return None;
+        }
+        Some(context)
+    }
+}
@@ -107,6 +107,52 @@ impl Validator {
         self
     }
 
+    pub fn one_of<T: PartialEq + std::fmt::Display>(
+        &mut self,
+        field: &str,
+        value: &T,
+        allowed: &[T],
+    ) -> &mut Self {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(|a| a.to_string()).collect();
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} muss einer der Werte {} sein", field, allowed.join(", ")),
+            });
+        }
+        self
+    }
+
+    pub fn min_items(&mut self, field: &str, count: usize, min: usize) -> &mut Self {
+        if count < min {
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} muss mindestens {} Einträge haben", field, min),
+            });
+        }
+        self
+    }
+
+    pub fn max_items(&mut self, field: &str, count: usize, max: usize) -> &mut Self {
+        if count > max {
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} darf maximal {} Einträge haben", field, max),
+            });
+        }
+        self
+    }
+
+    pub fn present(&mut self, field: &str, present: bool) -> &mut Self {
+        if !present {
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} ist erforderlich", field),
+            });
+        }
+        self
+    }
+
     pub fn custom<F>(&mut self, field: &str, value: &str, validator: F) -> &mut Self
     where
         F: Fn(&str) -> Option<String>,
@@ -298,6 +344,52 @@ impl Validator {
         self
     }
     
+    pub fn one_of<T: PartialEq + std::fmt::Display>(
+        &mut self,
+        field: &str,
+        value: &T,
+        allowed: &[T],
+    ) -> &mut Self {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(|a| a.to_string()).collect();
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} muss einer der Werte {} sein", field, allowed.join(", ")),
+            });
+        }
+        self
+    }
+
+    pub fn min_items(&mut self, field: &str, count: usize, min: usize) -> &mut Self {
+        if count < min {
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} muss mindestens {} Einträge haben", field, min),
+            });
+        }
+        self
+    }
+
+    pub fn max_items(&mut self, field: &str, count: usize, max: usize) -> &mut Self {
+        if count > max {
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} darf maximal {} Einträge haben", field, max),
+            });
+        }
+        self
+    }
+
+    pub fn present(&mut self, field: &str, present: bool) -> &mut Self {
+        if !present {
+            self.errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{} ist erforderlich", field),
+            });
+        }
+        self
+    }
+
     pub fn is_valid(&self) -> bool {
         self.errors.is_empty()
     }
@@ -1,106 +1,1301 @@
+// Workflow Standard Library
+// Dauerhafte Workflows: Schritte sind Velin-Funktionen, der Zustand wird nach
+// jedem Schritt in einen Store (Datei, SQLite, Redis) geschrieben und nach einem
+// Neustart fortgesetzt
+
+pub mod options;
+
+use crate::parser::ast::*;
+use crate::parser::visitor::{visit_block, visit_expression};
+use std::collections::BTreeSet;
+
 pub struct WorkflowStdlib;
 
+/// Pfad des generierten Runtime-Moduls
+const RUNTIME: &str = "crate::stdlib::workflow";
+
+/// Quelltext von `options.rs`, wird in die Runtime eingebettet
+const OPTIONS_SOURCE: &str = include_str!("workflow/options.rs");
+
+/// Schritt-Funktion für die generierte Registry
+#[derive(Debug, Clone)]
+pub struct StepFunction {
+    pub name: String,
+    pub params: Vec<String>,
+    pub is_async: bool,
+    pub returns_result: bool,
+    /// Optionen aus `@Step(...)` als JSON
+    pub options: serde_json::Value,
+}
+
+/// Was ein Programm von `workflow.*` braucht
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowUsage {
+    pub steps: Vec<StepFunction>,
+    /// Store-URLs aus `workflow.create({ store: "..." })`
+    pub stores: BTreeSet<String>,
+}
+
+impl WorkflowUsage {
+    /// `None`, wenn das Programm weder `workflow.*` noch `@Step` verwendet
+    pub fn collect(program: &Program) -> Option<WorkflowUsage> {
+        let mut used = false;
+        let mut referenced = BTreeSet::new();
+        let mut usage = WorkflowUsage::default();
+
+        let functions: Vec<&Function> = program
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Function(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+
+        let mut inspect = |expr: &Expression| {
+            let Expression::Call { callee, args } = expr else {
+                return;
+            };
+            let Expression::Member { object, member } = callee.as_ref() else {
+                return;
+            };
+            if !matches!(object.as_ref(), Expression::Identifier(name) if name == "workflow") {
+                return;
+            }
+            used = true;
+            if member == "create" || member == "run" {
+                if let Some(definition) = args.first() {
+                    collect_definition(definition, &mut referenced, &mut usage.stores);
+                }
+            }
+        };
+        for item in &program.items {
+            match item {
+                Item::Function(f) => visit_block(&f.body, &mut inspect),
+                Item::TopLevelCode(code) => visit_expression(&code.expression, &mut inspect),
+                _ => {}
+            }
+        }
+
+        for function in &functions {
+            if let Some(step) = step_decorator(function) {
+                used = true;
+                referenced.insert(function.name.clone());
+                if let Some(serde_json::Value::String(compensate)) =
+                    decorator_options(step).get("compensate")
+                {
+                    referenced.insert(compensate.clone());
+                }
+            }
+        }
+        if !used {
+            return None;
+        }
+
+        for function in functions {
+            if !referenced.contains(&function.name) {
+                continue;
+            }
+            usage.steps.push(StepFunction {
+                name: function.name.clone(),
+                params: function.params.iter().map(|p| p.name.clone()).collect(),
+                is_async: function.is_async,
+                returns_result: function.return_type.as_ref().is_some_and(is_result),
+                options: step_decorator(function)
+                    .map(decorator_options)
+                    .unwrap_or_else(|| serde_json::json!({})),
+            });
+        }
+        Some(usage)
+    }
+}
+
+fn step_decorator(function: &Function) -> Option<&Decorator> {
+    function.decorators.iter().find(|d| d.name == "Step")
+}
+
+fn is_result(ty: &Type) -> bool {
+    match ty {
+        Type::Result { .. } => true,
+        Type::Generic { name, .. } => name == "Result",
+        _ => false,
+    }
+}
+
+/// Benannte Argumente von `@Step(retries: 3, timeout: "10s", ...)`
+fn decorator_options(decorator: &Decorator) -> serde_json::Value {
+    let mut options = serde_json::Map::new();
+    for arg in &decorator.args {
+        if let DecoratorArg::Named { name, value } = arg {
+            options.insert(name.clone(), decorator_value(value));
+        }
+    }
+    serde_json::Value::Object(options)
+}
+
+pub(crate) fn decorator_value(arg: &DecoratorArg) -> serde_json::Value {
+    match arg {
+        DecoratorArg::String(s) | DecoratorArg::Identifier(s) => serde_json::json!(s),
+        DecoratorArg::Number(n) => serde_json::json!(n),
+        DecoratorArg::Boolean(b) => serde_json::json!(b),
+        DecoratorArg::List(items) => items.iter().map(decorator_value).collect(),
+        DecoratorArg::Named { value, .. } => decorator_value(value),
+    }
+}
+
+/// Schritt- und Kompensations-Namen sowie Stores aus einer Definition
+fn collect_definition(
+    expr: &Expression,
+    functions: &mut BTreeSet<String>,
+    stores: &mut BTreeSet<String>,
+) {
+    let Expression::MapLiteral(fields) = expr else {
+        return;
+    };
+    for (key, value) in fields {
+        match (key.as_str(), value) {
+            ("store", Expression::Literal(Literal::String(url))) => {
+                stores.insert(url.clone());
+            }
+            ("steps", Expression::ListLiteral(steps)) => {
+                for step in steps {
+                    match step {
+                        Expression::Literal(Literal::String(name)) => {
+                            functions.insert(name.clone());
+                        }
+                        Expression::MapLiteral(options) => {
+                            for (key, value) in options {
+                                if let (
+                                    "id" | "name" | "run" | "compensate",
+                                    Expression::Literal(Literal::String(name)),
+                                ) = (key.as_str(), value)
+                                {
+                                    functions.insert(name.clone());
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 impl WorkflowStdlib {
-    pub fn generate_create_code(definition: &str) -> String {
+    /// Runtime-Modul `stdlib::workflow` inklusive Schritt-Registry
+    ///
+    /// SQLite- und Redis-Stores werden nur generiert, wenn eine entsprechende
+    /// Store-URL im Code vorkommt (sonst fehlen `rusqlite`/`redis`).
+    pub fn generate_workflow_runtime_code(
+        usage: &WorkflowUsage,
+        rust_name: &dyn Fn(&str) -> String,
+    ) -> String {
+        let sqlite = usage.stores.iter().any(|url| url.starts_with("sqlite:"));
+        let redis = usage
+            .stores
+            .iter()
+            .any(|url| url.starts_with("redis://") || url.starts_with("rediss://"));
+
+        let mut open_stores = String::new();
+        let mut stores = String::new();
+        if sqlite {
+            open_stores.push_str(
+                "    if let Some(path) = url.strip_prefix(\"sqlite:\") {\n        return Ok(Arc::new(SqliteStore::open(path.trim_start_matches(\"//\"))?));\n    }\n",
+            );
+            stores.push_str(SQLITE_STORE);
+        }
+        if redis {
+            open_stores.push_str(
+                "    if url.starts_with(\"redis://\") || url.starts_with(\"rediss://\") {\n        return Ok(Arc::new(RedisStore::open(url)?));\n    }\n",
+            );
+            stores.push_str(REDIS_STORE);
+        }
+        let known_stores: Vec<String> = usage
+            .stores
+            .iter()
+            .map(|url| format!("{:?}", url))
+            .collect();
+
+        RUNTIME_TEMPLATE
+            .replace("/*OPTIONS*/\n", OPTIONS_SOURCE)
+            .replace("/*KNOWN_STORES*/", &known_stores.join(", "))
+            .replace("/*OPEN_STORES*/\n", &open_stores)
+            .replace("/*STORES*/\n", &stores)
+            .replace("/*REGISTRY*/\n", &Self::generate_registry(usage, rust_name))
+    }
+
+    fn generate_registry(usage: &WorkflowUsage, rust_name: &dyn Fn(&str) -> String) -> String {
+        let mut defaults = String::new();
+        let mut calls = String::new();
+        for step in &usage.steps {
+            defaults.push_str(&format!(
+                "        {:?} => Some(serde_json::json!({})),\n",
+                step.name, step.options
+            ));
+
+            let mut call = format!("        {:?} => {{\n", step.name);
+            let mut args = Vec::new();
+            for (index, param) in step.params.iter().enumerate() {
+                let value = if step.params.len() == 1 {
+                    "input.clone()".to_string()
+                } else {
+                    format!(
+                        "input.get({:?}).cloned().unwrap_or(serde_json::Value::Null)",
+                        param
+                    )
+                };
+                call.push_str(&format!(
+                    "            let arg{} = serde_json::from_value({}).map_err(|e| format!(\"invalid input for step '{}': {{}}\", e))?;\n",
+                    index, value, step.name
+                ));
+                args.push(format!("arg{}", index));
+            }
+            let mut invocation = format!(
+                "super::super::{}({})",
+                rust_name(&step.name),
+                args.join(", ")
+            );
+            if step.is_async {
+                invocation = format!("block_on({})", invocation);
+            }
+            call.push_str(&format!(
+                "            {}({})\n        }}\n",
+                if step.returns_result {
+                    "step_result"
+                } else {
+                    "step_output"
+                },
+                invocation
+            ));
+            calls.push_str(&call);
+        }
         format!(
-            "{{
-                let def: serde_json::Value = {};
-                let workflow_id = uuid::Uuid::new_v4().to_string();
-                let name = def.get(\"name\").and_then(|v| v.as_str()).unwrap_or(\"workflow\");
-                let steps = def.get(\"steps\").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-                serde_json::json!({{
-                    \"id\": workflow_id,
-                    \"name\": name,
-                    \"steps\": steps,
-                    \"status\": \"pending\",
-                    \"created_at\": chrono::Utc::now().to_rfc3339()
-                }})
-            }}",
-            definition
+            "/// Optionen aus `@Step`; `None` für Funktionen, die kein Schritt sind\nfn step_defaults(name: &str) -> Option<serde_json::Value> {{\n    match name {{\n{}        _ => None,\n    }}\n}}\n\nfn call_step(name: &str, input: serde_json::Value) -> StepResult {{\n    match name {{\n{}        _ => Err(format!(\"unknown workflow step '{{}}'\", name)),\n    }}\n}}\n",
+            defaults, calls
         )
     }
 
+    pub fn generate_create_code(definition: &str) -> String {
+        format!("{}::create({})", RUNTIME, definition)
+    }
+
     pub fn generate_start_code(workflow: &str) -> String {
+        format!("{}::start(&{})", RUNTIME, workflow)
+    }
+
+    pub fn generate_run_code(workflow: &str, input: &str) -> String {
         format!(
-            "{{
-                let mut wf: serde_json::Value = {};
-                wf[\"status\"] = serde_json::json!(\"running\");
-                wf[\"started_at\"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
-                wf
-            }}",
-            workflow
+            "{}::run(&{}, serde_json::json!({}))",
+            RUNTIME, workflow, input
         )
     }
 
     pub fn generate_execute_step_code(workflow: &str, step_id: &str) -> String {
-        format!(
-            "{{
-                let mut wf: serde_json::Value = {};
-                let step_id_str = {};
-                if let Some(steps) = wf.get_mut(\"steps\").and_then(|v| v.as_array_mut()) {{
-                    for step in steps {{
-                        if step.get(\"id\").and_then(|v| v.as_str()) == Some(step_id_str) {{
-                            step[\"status\"] = serde_json::json!(\"completed\");
-                            step[\"completed_at\"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
-                        }}
-                    }}
-                }}
-                wf
-            }}",
-            workflow, step_id
-        )
+        format!("{}::execute_step(&{}, &{})", RUNTIME, workflow, step_id)
+    }
+
+    pub fn generate_resume_code(workflow: &str) -> String {
+        format!("{}::resume(&{})", RUNTIME, workflow)
+    }
+
+    pub fn generate_resume_all_code() -> String {
+        format!("{}::resume_pending()", RUNTIME)
     }
 
     pub fn generate_get_status_code(workflow: &str) -> String {
-        format!(
-            "{}.get(\"status\").and_then(|v| v.as_str()).unwrap_or(\"unknown\")",
-            workflow
-        )
+        format!("{}::get_status(&{})", RUNTIME, workflow)
     }
 
     pub fn generate_get_history_code(workflow: &str) -> String {
-        format!(
-            "{{
-                let wf: serde_json::Value = {};
-                let mut history = Vec::new();
-                if let Some(steps) = wf.get(\"steps\").and_then(|v| v.as_array()) {{
-                    for step in steps {{
-                        history.push(serde_json::json!({{
-                            \"step_id\": step.get(\"id\"),
-                            \"name\": step.get(\"name\"),
-                            \"status\": step.get(\"status\"),
-                            \"completed_at\": step.get(\"completed_at\")
-                        }}));
-                    }}
-                }}
-                history
-            }}",
-            workflow
-        )
+        format!("{}::get_history(&{})", RUNTIME, workflow)
     }
 
     pub fn generate_complete_code(workflow: &str) -> String {
-        format!(
-            "{{
-                let mut wf: serde_json::Value = {};
-                wf[\"status\"] = serde_json::json!(\"completed\");
-                wf[\"completed_at\"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
-                wf
-            }}",
-            workflow
-        )
+        format!("{}::complete(&{})", RUNTIME, workflow)
     }
 
     pub fn generate_fail_code(workflow: &str, error: &str) -> String {
-        format!(
-            "{{
-                let mut wf: serde_json::Value = {};
-                wf[\"status\"] = serde_json::json!(\"failed\");
-                wf[\"error\"] = serde_json::json!({});
-                wf[\"failed_at\"] = serde_json::json!(chrono::Utc::now().to_rfc3339());
-                wf
-            }}",
-            workflow, error
-        )
+        format!("{}::fail(&{}, &{})", RUNTIME, workflow, error)
+    }
+}
+
+/// Runtime-Vorlage; Platzhalter werden in `generate_workflow_runtime_code` ersetzt
+const RUNTIME_TEMPLATE: &str = r#"
+// --- Velisch Workflow Runtime ---
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+pub type StepResult = Result<serde_json::Value, String>;
+
+const DEFAULT_STORE: &str = "file:.velin/workflows";
+const STORE_ENV: &str = "VELIN_WORKFLOW_STORE";
+const KNOWN_STORES: &[&str] = &[/*KNOWN_STORES*/];
+
+pub mod options {
+/*OPTIONS*/
+}
+
+use options::StepOptions;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StepState {
+    pub id: String,
+    pub function: String,
+    pub options: StepOptions,
+    /// pending, running, completed, failed, compensated, compensation_failed
+    pub status: String,
+    pub attempts: u32,
+    #[serde(default)]
+    pub compensation_attempts: u32,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HistoryEvent {
+    pub at: String,
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempt: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WorkflowRun {
+    pub id: String,
+    pub workflow: String,
+    pub store: String,
+    /// running, completed, compensating, compensated, failed
+    pub status: String,
+    pub input: serde_json::Value,
+    pub output: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub steps: Vec<StepState>,
+    pub history: Vec<HistoryEvent>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl WorkflowRun {
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status.as_str(), "completed" | "compensated" | "failed")
+    }
+
+    fn record(
+        &mut self,
+        event: &str,
+        step: Option<&str>,
+        attempt: Option<u32>,
+        detail: Option<serde_json::Value>,
+    ) {
+        self.updated_at = now();
+        self.history.push(HistoryEvent {
+            at: self.updated_at.clone(),
+            event: event.to_string(),
+            step: step.map(str::to_string),
+            attempt,
+            detail,
+        });
+    }
+
+    /// Eingabe eines Schritts: Ausgabe des vorherigen, für den ersten die Workflow-Eingabe
+    fn step_input(&self, index: usize) -> serde_json::Value {
+        match index.checked_sub(1).map(|previous| &self.steps[previous]) {
+            Some(previous) => previous.output.clone().unwrap_or(serde_json::Value::Null),
+            None => self.input.clone(),
+        }
+    }
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+// --- Stores ---
+
+/// Persistenz der Workflow-Läufe; eigene Stores über `register_store`
+pub trait WorkflowStore: Send + Sync {
+    fn save(&self, run: &WorkflowRun) -> Result<(), String>;
+    fn load(&self, id: &str) -> Result<Option<WorkflowRun>, String>;
+    /// Läufe mit Status `running` oder `compensating`
+    fn unfinished(&self) -> Result<Vec<WorkflowRun>, String>;
+}
+
+/// Eine JSON-Datei pro Lauf
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: &str) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("cannot create workflow store '{}': {}", dir, e))?;
+        Ok(FileStore { dir: PathBuf::from(dir) })
+    }
+}
+
+impl WorkflowStore for FileStore {
+    fn save(&self, run: &WorkflowRun) -> Result<(), String> {
+        let path = self.dir.join(format!("{}.json", run.id));
+        let temporary = self.dir.join(format!("{}.json.tmp", run.id));
+        let json = serde_json::to_vec_pretty(run).map_err(|e| e.to_string())?;
+        // Schreiben und Umbenennen, damit ein Absturz keine halbe Datei hinterlässt
+        std::fs::write(&temporary, json)
+            .and_then(|_| std::fs::rename(&temporary, &path))
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    }
+
+    fn load(&self, id: &str) -> Result<Option<WorkflowRun>, String> {
+        let path = self.dir.join(format!("{}.json", id));
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read(&path).map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        serde_json::from_slice(&json)
+            .map(Some)
+            .map_err(|e| format!("invalid workflow state {}: {}", path.display(), e))
+    }
+
+    fn unfinished(&self) -> Result<Vec<WorkflowRun>, String> {
+        let mut runs = Vec::new();
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| format!("cannot read {}: {}", self.dir.display(), e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if let Some(run) = self.load(id)? {
+                if !run.is_finished() {
+                    runs.push(run);
+                }
+            }
+        }
+        runs.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(runs)
+    }
+}
+/*STORES*/
+
+static STORES: OnceLock<Mutex<HashMap<String, Arc<dyn WorkflowStore>>>> = OnceLock::new();
+
+/// Läufe, die dieser Prozess gerade ausführt
+static ACTIVE: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Store aus `VELIN_WORKFLOW_STORE`, sonst `file:.velin/workflows`
+pub fn default_store_url() -> String {
+    std::env::var(STORE_ENV).unwrap_or_else(|_| DEFAULT_STORE.to_string())
+}
+
+/// Registriert einen eigenen Store unter einer URL
+pub fn register_store(url: &str, store: Arc<dyn WorkflowStore>) {
+    STORES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(url.to_string(), store);
+}
+
+fn store(url: &str) -> Result<Arc<dyn WorkflowStore>, String> {
+    let mut stores = STORES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(store) = stores.get(url) {
+        return Ok(store.clone());
+    }
+    let store = open_store(url)?;
+    stores.insert(url.to_string(), store.clone());
+    Ok(store)
+}
+
+fn open_store(url: &str) -> Result<Arc<dyn WorkflowStore>, String> {
+    if let Some(dir) = url.strip_prefix("file:") {
+        return Ok(Arc::new(FileStore::new(dir.trim_start_matches("//"))?));
+    }
+/*OPEN_STORES*/
+    Err(format!(
+        "unsupported workflow store '{}' (sqlite: and redis:// are only available when used as store in workflow.create)",
+        url
+    ))
+}
+
+/// Markiert einen Lauf als aktiv; `None`, wenn er bereits läuft
+fn claim(id: &str) -> Option<Claim> {
+    let mut active = ACTIVE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    active.insert(id.to_string()).then(|| Claim(id.to_string()))
+}
+
+struct Claim(String);
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if let Some(active) = ACTIVE.get() {
+            active.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.0);
+        }
+    }
+}
+
+// --- Schritt-Registry ---
+
+/*REGISTRY*/
+fn step_output<T: serde::Serialize>(value: T) -> StepResult {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
+
+/// Velin-`Result` wird zu `anyhow::Result`, der Fehler zählt nur als Text
+fn step_result<T: serde::Serialize, E: std::fmt::Display>(result: Result<T, E>) -> StepResult {
+    match result {
+        Ok(value) => step_output(value),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Async-Schritte laufen im Schritt-Thread auf einer eigenen Runtime
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start runtime for workflow step")
+        .block_on(future)
+}
+
+/// Ein Versuch in eigenem Thread: fängt Panics ab und setzt den Timeout durch
+///
+/// Ein Versuch, der den Timeout überschreitet, wird verworfen, läuft aber zu Ende.
+fn run_attempt(function: &str, input: serde_json::Value, timeout_ms: Option<u64>) -> StepResult {
+    let (sender, receiver) = mpsc::channel();
+    let function = function.to_string();
+    std::thread::spawn(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| call_step(&function, input)))
+            .unwrap_or_else(|panic| {
+                Err(panic
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .map(|message| format!("panicked: {}", message))
+                    .unwrap_or_else(|| "panicked".to_string()))
+            });
+        let _ = sender.send(result);
+    });
+    match timeout_ms {
+        Some(ms) => receiver
+            .recv_timeout(Duration::from_millis(ms))
+            .unwrap_or_else(|_| Err(format!("timed out after {}ms", ms))),
+        None => receiver
+            .recv()
+            .unwrap_or_else(|_| Err("step thread terminated".to_string())),
+    }
+}
+
+// --- Engine ---
+
+fn is_step(name: &str) -> bool {
+    step_defaults(name).is_some()
+}
+
+fn definition_steps(definition: &serde_json::Value) -> Result<Vec<StepState>, String> {
+    let steps = definition
+        .get("steps")
+        .and_then(|steps| steps.as_array())
+        .ok_or("workflow definition needs a 'steps' list")?;
+    if steps.is_empty() {
+        return Err("workflow definition has no steps".to_string());
+    }
+    let mut states: Vec<StepState> = Vec::new();
+    for step in steps {
+        let (id, function) = match step {
+            serde_json::Value::String(name) => (name.clone(), name.clone()),
+            serde_json::Value::Object(fields) => {
+                let id = fields
+                    .get("id")
+                    .or_else(|| fields.get("name"))
+                    .and_then(|v| v.as_str())
+                    .ok_or("each step needs an 'id' or 'name'")?;
+                let function = fields.get("run").and_then(|v| v.as_str()).unwrap_or(id);
+                (id.to_string(), function.to_string())
+            }
+            other => return Err(format!("invalid step {}", other)),
+        };
+        if states.iter().any(|s| s.id == id) {
+            return Err(format!("duplicate step '{}'", id));
+        }
+        let defaults = step_defaults(&function).ok_or_else(|| {
+            format!("step '{}' refers to '{}', which is not a @Step function", id, function)
+        })?;
+        let mut options = StepOptions::default();
+        options.merge(&defaults, &is_step)?;
+        options.merge(step, &is_step)?;
+        states.push(StepState {
+            id,
+            function,
+            options,
+            status: "pending".to_string(),
+            attempts: 0,
+            compensation_attempts: 0,
+            output: None,
+            error: None,
+            started_at: None,
+            finished_at: None,
+        });
+    }
+    Ok(states)
+}
+
+/// Führt eine Schritt- oder Kompensations-Funktion mit Wiederholungen aus
+///
+/// Vor jedem Versuch wird der Zustand gespeichert. Der äußere Fehler ist ein
+/// Store-Fehler, der innere das Ergebnis des Schritts.
+fn execute(
+    run: &mut WorkflowRun,
+    store: &dyn WorkflowStore,
+    index: usize,
+    compensation: bool,
+) -> Result<StepResult, String> {
+    let (function, options, input) = if compensation {
+        let step = &run.steps[index];
+        let function = step.options.compensate.clone().unwrap_or_default();
+        let mut options = StepOptions::default();
+        options.merge(&step_defaults(&function).unwrap_or_default(), &is_step)?;
+        (function, options, step.output.clone().unwrap_or(serde_json::Value::Null))
+    } else {
+        let step = &run.steps[index];
+        (step.function.clone(), step.options.clone(), run.step_input(index))
+    };
+    let (started, completed, failed, retry) = if compensation {
+        ("compensation_started", "step_compensated", "compensation_failed", "compensation_retry")
+    } else {
+        ("step_started", "step_completed", "step_failed", "step_retry")
+    };
+
+    loop {
+        let step = &mut run.steps[index];
+        let attempt = if compensation {
+            step.compensation_attempts += 1;
+            step.compensation_attempts
+        } else {
+            step.attempts += 1;
+            step.status = "running".to_string();
+            step.started_at.get_or_insert_with(now);
+            step.attempts
+        };
+        let id = step.id.clone();
+        run.record(started, Some(&id), Some(attempt), None);
+        store.save(run)?;
+
+        let clock = Instant::now();
+        let result = run_attempt(&function, input.clone(), options.timeout_ms);
+        let duration_ms = clock.elapsed().as_millis() as u64;
+        match result {
+            Ok(output) => {
+                let step = &mut run.steps[index];
+                if compensation {
+                    step.status = "compensated".to_string();
+                } else {
+                    step.status = "completed".to_string();
+                    step.output = Some(output.clone());
+                    step.error = None;
+                    step.finished_at = Some(now());
+                }
+                run.record(
+                    completed,
+                    Some(&id),
+                    Some(attempt),
+                    Some(serde_json::json!({ "duration_ms": duration_ms })),
+                );
+                store.save(run)?;
+                return Ok(Ok(output));
+            }
+            Err(error) if attempt > options.retries => {
+                let step = &mut run.steps[index];
+                step.status = if compensation { "compensation_failed" } else { "failed" }.to_string();
+                step.error = Some(error.clone());
+                step.finished_at = Some(now());
+                run.record(
+                    failed,
+                    Some(&id),
+                    Some(attempt),
+                    Some(serde_json::json!({ "error": error, "duration_ms": duration_ms })),
+                );
+                store.save(run)?;
+                return Ok(Err(error));
+            }
+            Err(error) => {
+                let delay = options.delay_for(attempt);
+                run.steps[index].error = Some(error.clone());
+                run.record(
+                    retry,
+                    Some(&id),
+                    Some(attempt),
+                    Some(serde_json::json!({ "error": error, "retry_in_ms": delay.as_millis() as u64 })),
+                );
+                store.save(run)?;
+                std::thread::sleep(delay);
+            }
+        }
+    }
+}
+
+/// Nimmt abgeschlossene Schritte in umgekehrter Reihenfolge zurück
+fn compensate(run: &mut WorkflowRun, store: &dyn WorkflowStore) -> Result<(), String> {
+    let mut failures = Vec::new();
+    for index in (0..run.steps.len()).rev() {
+        let step = &run.steps[index];
+        if step.status != "completed" || step.options.compensate.is_none() {
+            continue;
+        }
+        if let Err(error) = execute(run, store, index, true)? {
+            failures.push(format!("{}: {}", run.steps[index].id, error));
+        }
+    }
+    if failures.is_empty() {
+        run.status = "compensated".to_string();
+        run.record("compensated", None, None, None);
+    } else {
+        run.status = "failed".to_string();
+        run.record(
+            "failed",
+            None,
+            None,
+            Some(serde_json::json!({ "compensation_errors": failures })),
+        );
+    }
+    store.save(run)
+}
+
+/// Beginnt die Kompensation nach einem fehlgeschlagenen Schritt
+fn begin_compensation(
+    run: &mut WorkflowRun,
+    store: &dyn WorkflowStore,
+    flow: &super::flow::FlowManager,
+    step: &str,
+    error: &str,
+) -> Result<(), String> {
+    run.status = "compensating".to_string();
+    run.error = Some(format!("step '{}' failed: {}", step, error));
+    run.record(
+        "compensating",
+        Some(step),
+        None,
+        Some(serde_json::json!({ "error": error })),
+    );
+    store.save(run)?;
+    // Markiert den Flow als fehlgeschlagen und protokolliert den Rollback
+    flow.step_failed(step, error);
+    compensate(run, store)
+}
+
+/// Führt einen Lauf ab dem ersten nicht abgeschlossenen Schritt zu Ende
+fn drive(mut run: WorkflowRun) -> Result<WorkflowRun, String> {
+    let Some(_claim) = claim(&run.id) else {
+        return Ok(run);
+    };
+    let store = store(&run.store)?;
+    if run.status == "running" {
+        let flow = super::flow::FlowManager::new(&run.workflow);
+        flow.start();
+        for index in 0..run.steps.len() {
+            if run.steps[index].status == "completed" {
+                continue;
+            }
+            let clock = Instant::now();
+            match execute(&mut run, &*store, index, false)? {
+                Ok(_) => flow.step_success(&run.steps[index].id, clock.elapsed().as_millis() as u64),
+                Err(error) => {
+                    let step = run.steps[index].id.clone();
+                    begin_compensation(&mut run, &*store, &flow, &step, &error)?;
+                    return Ok(run);
+                }
+            }
+        }
+        run.status = "completed".to_string();
+        run.output = run.steps.last().and_then(|step| step.output.clone());
+        run.record("completed", None, None, None);
+        store.save(&run)?;
+        flow.commit();
+    } else if run.status == "compensating" {
+        compensate(&mut run, &*store)?;
+    }
+    Ok(run)
+}
+
+fn new_run(workflow: &serde_json::Value, input: serde_json::Value) -> Result<WorkflowRun, String> {
+    let name = workflow.get("name").and_then(|v| v.as_str()).unwrap_or("workflow");
+    let store_url = workflow
+        .get("store")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(default_store_url);
+    let created_at = now();
+    let mut run = WorkflowRun {
+        id: uuid::Uuid::new_v4().to_string(),
+        workflow: name.to_string(),
+        store: store_url,
+        status: "running".to_string(),
+        input,
+        output: None,
+        error: None,
+        steps: Vec::new(),
+        history: Vec::new(),
+        created_at: created_at.clone(),
+        updated_at: created_at,
+    };
+    match definition_steps(workflow) {
+        Ok(steps) => {
+            run.steps = steps;
+            run.record("started", None, None, None);
+        }
+        Err(error) => {
+            run.status = "failed".to_string();
+            run.error = Some(error.clone());
+            run.record("failed", None, None, Some(serde_json::json!({ "error": error })));
+        }
+    }
+    store(&run.store)?.save(&run)?;
+    Ok(run)
+}
+
+/// Lauf aus einem Lauf-Objekt oder einer Lauf-Id
+fn load(run: &serde_json::Value) -> Result<WorkflowRun, String> {
+    let id = run
+        .as_str()
+        .or_else(|| run.get("id").and_then(|v| v.as_str()))
+        .ok_or("expected a workflow run or run id")?;
+    let urls: Vec<String> = match run.get("store").and_then(|v| v.as_str()) {
+        Some(url) => vec![url.to_string()],
+        None => std::iter::once(default_store_url())
+            .chain(KNOWN_STORES.iter().map(|url| url.to_string()))
+            .collect(),
+    };
+    for url in urls {
+        if let Some(run) = store(&url)?.load(id)? {
+            return Ok(run);
+        }
+    }
+    Err(format!("unknown workflow run '{}'", id))
+}
+
+fn to_json(result: Result<WorkflowRun, String>) -> serde_json::Value {
+    match result {
+        Ok(run) => serde_json::to_value(run).unwrap_or(serde_json::Value::Null),
+        Err(error) => {
+            eprintln!("[Workflow] {}", error);
+            serde_json::json!({ "status": "failed", "error": error })
+        }
+    }
+}
+
+// --- API für `workflow.*` ---
+
+/// `workflow.create`: prüft die Definition und ergänzt Id, Status und Store
+pub fn create(definition: serde_json::Value) -> serde_json::Value {
+    let mut workflow = match definition {
+        serde_json::Value::Object(_) => definition,
+        _ => serde_json::json!({}),
+    };
+    if workflow.get("name").is_none() {
+        workflow["name"] = serde_json::json!("workflow");
+    }
+    if workflow.get("store").is_none() {
+        workflow["store"] = serde_json::json!(default_store_url());
+    }
+    workflow["id"] = serde_json::json!(uuid::Uuid::new_v4().to_string());
+    workflow["created_at"] = serde_json::json!(now());
+    match definition_steps(&workflow) {
+        Ok(_) => workflow["status"] = serde_json::json!("pending"),
+        Err(error) => {
+            workflow["status"] = serde_json::json!("invalid");
+            workflow["error"] = serde_json::json!(error);
+        }
+    }
+    workflow
+}
+
+/// `workflow.start`: neuer Lauf mit `input` aus der Definition
+pub fn start(workflow: &serde_json::Value) -> serde_json::Value {
+    run(workflow, workflow.get("input").cloned().unwrap_or(serde_json::Value::Null))
+}
+
+/// `workflow.run`: neuer Lauf, ausgeführt bis zum Abschluss oder zur Kompensation
+pub fn run(workflow: &serde_json::Value, input: serde_json::Value) -> serde_json::Value {
+    to_json(new_run(workflow, input).and_then(|run| {
+        if run.is_finished() {
+            Ok(run)
+        } else {
+            drive(run)
+        }
+    }))
+}
+
+/// `workflow.execute_step`: führt einen einzelnen Schritt eines Laufs aus
+pub fn execute_step(run: &serde_json::Value, step_id: &str) -> serde_json::Value {
+    to_json(load(run).and_then(|mut run| {
+        let _claim = claim(&run.id).ok_or_else(|| format!("workflow run '{}' is already running", run.id))?;
+        let index = run
+            .steps
+            .iter()
+            .position(|step| step.id == step_id)
+            .ok_or_else(|| format!("unknown step '{}'", step_id))?;
+        if run.status != "running" || run.steps[index].status == "completed" {
+            return Ok(run);
+        }
+        let store = store(&run.store)?;
+        if let Err(error) = execute(&mut run, &*store, index, false)? {
+            let flow = super::flow::FlowManager::new(&run.workflow);
+            begin_compensation(&mut run, &*store, &flow, step_id, &error)?;
+        } else if run.steps.iter().all(|step| step.status == "completed") {
+            run.status = "completed".to_string();
+            run.output = run.steps.last().and_then(|step| step.output.clone());
+            run.record("completed", None, None, None);
+            store.save(&run)?;
+        }
+        Ok(run)
+    }))
+}
+
+/// `workflow.resume`: setzt einen unterbrochenen Lauf fort
+///
+/// Ein durch Absturz unterbrochener Versuch wird wiederholt und nicht als
+/// Fehlversuch gezählt.
+pub fn resume(run: &serde_json::Value) -> serde_json::Value {
+    to_json(load(run).and_then(resume_run))
+}
+
+fn resume_run(mut run: WorkflowRun) -> Result<WorkflowRun, String> {
+    if run.is_finished() {
+        return Ok(run);
+    }
+    for index in 0..run.steps.len() {
+        if run.steps[index].status == "running" {
+            let step = &mut run.steps[index];
+            step.status = "pending".to_string();
+            step.attempts = step.attempts.saturating_sub(1);
+            let id = step.id.clone();
+            run.record("step_interrupted", Some(&id), None, None);
+        }
+    }
+    run.record("resumed", None, None, None);
+    drive(run)
+}
+
+/// Setzt alle unterbrochenen Läufe fort (beim Start des Services)
+pub fn resume_pending() -> Vec<serde_json::Value> {
+    let mut urls = vec![default_store_url()];
+    for url in KNOWN_STORES {
+        if !urls.iter().any(|known| known == url) {
+            urls.push(url.to_string());
+        }
+    }
+    let mut resumed = Vec::new();
+    for url in urls {
+        let runs = match store(&url).and_then(|store| store.unfinished()) {
+            Ok(runs) => runs,
+            Err(error) => {
+                eprintln!("[Workflow] {}", error);
+                continue;
+            }
+        };
+        for run in runs {
+            println!("[Workflow:{}] Resuming '{}' ({})", run.id, run.workflow, run.status);
+            resumed.push(to_json(resume_run(run)));
+        }
+    }
+    resumed
+}
+
+/// `workflow.get_status`
+pub fn get_status(run: &serde_json::Value) -> String {
+    match load(run) {
+        Ok(run) => run.status,
+        Err(_) => run
+            .get("status")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string(),
+    }
+}
+
+/// `workflow.get_history`: alle Ereignisse eines Laufs
+pub fn get_history(run: &serde_json::Value) -> Vec<serde_json::Value> {
+    match load(run) {
+        Ok(run) => run
+            .history
+            .iter()
+            .filter_map(|event| serde_json::to_value(event).ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// `workflow.complete`: schließt einen Lauf manuell ab
+pub fn complete(run: &serde_json::Value) -> serde_json::Value {
+    to_json(load(run).and_then(|mut run| {
+        if !run.is_finished() {
+            run.status = "completed".to_string();
+            run.record("completed", None, None, Some(serde_json::json!({ "manual": true })));
+            store(&run.store)?.save(&run)?;
+        }
+        Ok(run)
+    }))
+}
+
+/// `workflow.fail`: bricht einen Lauf ab und kompensiert abgeschlossene Schritte
+pub fn fail(run: &serde_json::Value, error: &str) -> serde_json::Value {
+    to_json(load(run).and_then(|mut run| {
+        if run.is_finished() {
+            return Ok(run);
+        }
+        let _claim = claim(&run.id).ok_or_else(|| format!("workflow run '{}' is already running", run.id))?;
+        let store = store(&run.store)?;
+        run.status = "compensating".to_string();
+        run.error = Some(error.to_string());
+        run.record("compensating", None, None, Some(serde_json::json!({ "error": error })));
+        store.save(&run)?;
+        compensate(&mut run, &*store)?;
+        Ok(run)
+    }))
+}
+"#;
+
+const SQLITE_STORE: &str = r#"
+/// Tabelle `velin_workflow_runs` in einer SQLite-Datenbank (`rusqlite`)
+pub struct SqliteStore {
+    connection: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteStore {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let connection = rusqlite::Connection::open(path).map_err(|e| e.to_string())?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS velin_workflow_runs (id TEXT PRIMARY KEY, workflow TEXT NOT NULL, status TEXT NOT NULL, state TEXT NOT NULL, updated_at TEXT NOT NULL)",
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(SqliteStore { connection: Mutex::new(connection) })
+    }
+}
+
+impl WorkflowStore for SqliteStore {
+    fn save(&self, run: &WorkflowRun) -> Result<(), String> {
+        let state = serde_json::to_string(run).map_err(|e| e.to_string())?;
+        self.connection
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .execute(
+                "INSERT INTO velin_workflow_runs (id, workflow, status, state, updated_at) VALUES (?1, ?2, ?3, ?4, ?5) \
+                 ON CONFLICT(id) DO UPDATE SET status = excluded.status, state = excluded.state, updated_at = excluded.updated_at",
+                rusqlite::params![run.id, run.workflow, run.status, state, run.updated_at],
+            )
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn load(&self, id: &str) -> Result<Option<WorkflowRun>, String> {
+        use rusqlite::OptionalExtension;
+        let state: Option<String> = self
+            .connection
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .query_row("SELECT state FROM velin_workflow_runs WHERE id = ?1", [id], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())?;
+        state
+            .map(|state| serde_json::from_str(&state).map_err(|e| e.to_string()))
+            .transpose()
+    }
+
+    fn unfinished(&self) -> Result<Vec<WorkflowRun>, String> {
+        let connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        let mut statement = connection
+            .prepare("SELECT state FROM velin_workflow_runs WHERE status IN ('running', 'compensating') ORDER BY rowid")
+            .map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?;
+        rows.map(|state| {
+            let state = state.map_err(|e| e.to_string())?;
+            serde_json::from_str(&state).map_err(|e| e.to_string())
+        })
+        .collect()
+    }
+}
+"#;
+
+const REDIS_STORE: &str = r#"
+/// Schlüssel `velin:workflow:<id>`, offene Läufe im Set `velin:workflows:unfinished`
+pub struct RedisStore {
+    client: redis::Client,
+}
+
+impl RedisStore {
+    pub fn open(url: &str) -> Result<Self, String> {
+        Ok(RedisStore {
+            client: redis::Client::open(url).map_err(|e| e.to_string())?,
+        })
+    }
+
+    fn connection(&self) -> Result<redis::Connection, String> {
+        self.client.get_connection().map_err(|e| e.to_string())
+    }
+}
+
+impl WorkflowStore for RedisStore {
+    fn save(&self, run: &WorkflowRun) -> Result<(), String> {
+        let mut connection = self.connection()?;
+        let state = serde_json::to_string(run).map_err(|e| e.to_string())?;
+        redis::cmd("SET")
+            .arg(format!("velin:workflow:{}", run.id))
+            .arg(state)
+            .query::<()>(&mut connection)
+            .map_err(|e| e.to_string())?;
+        redis::cmd(if run.is_finished() { "SREM" } else { "SADD" })
+            .arg("velin:workflows:unfinished")
+            .arg(&run.id)
+            .query::<()>(&mut connection)
+            .map_err(|e| e.to_string())
+    }
+
+    fn load(&self, id: &str) -> Result<Option<WorkflowRun>, String> {
+        let state: Option<String> = redis::cmd("GET")
+            .arg(format!("velin:workflow:{}", id))
+            .query(&mut self.connection()?)
+            .map_err(|e| e.to_string())?;
+        state
+            .map(|state| serde_json::from_str(&state).map_err(|e| e.to_string()))
+            .transpose()
+    }
+
+    fn unfinished(&self) -> Result<Vec<WorkflowRun>, String> {
+        let ids: Vec<String> = redis::cmd("SMEMBERS")
+            .arg("velin:workflows:unfinished")
+            .query(&mut self.connection()?)
+            .map_err(|e| e.to_string())?;
+        let mut runs = Vec::new();
+        for id in ids {
+            runs.extend(self.load(&id)?);
+        }
+        runs.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(runs)
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::options::StepOptions;
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_usage_collects_steps_compensations_and_stores() {
+        let source = r#"@Step(retries: 2, timeout: "5s", compensate: "releaseStock")
+fn reserveStock(orderId: string): string {
+    return orderId;
+}
+
+fn releaseStock(reservation: string): boolean {
+    return true;
+}
+
+fn shipOrder(orderId: string, carrier: string): string {
+    return carrier;
+}
+
+fn unrelated(): string {
+    return "x";
+}
+
+fn checkout(orderId: string): any {
+    let definition = workflow.create({
+        name: "checkout",
+        store: "sqlite:workflows.db",
+        steps: ["reserveStock", { name: "ship", run: "shipOrder" }],
+    });
+    return workflow.run(definition, orderId);
+}
+"#;
+        let program = Parser::parse(source).unwrap();
+        let usage = WorkflowUsage::collect(&program).unwrap();
+        let names: Vec<&str> = usage.steps.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["reserveStock", "releaseStock", "shipOrder"]);
+        assert_eq!(usage.steps[0].options["retries"], 2.0);
+        assert_eq!(usage.steps[0].options["compensate"], "releaseStock");
+
+        let runtime =
+            WorkflowStdlib::generate_workflow_runtime_code(&usage, &|name| name.to_lowercase());
+        assert!(runtime.contains("const KNOWN_STORES: &[&str] = &[\"sqlite:workflows.db\"];"));
+        assert!(runtime.contains("pub struct SqliteStore"));
+        assert!(!runtime.contains("pub struct RedisStore"));
+        assert!(runtime.contains("step_output(super::super::reservestock(arg0))"));
+        assert!(runtime.contains("input.get(\"carrier\")"));
+        assert!(!runtime.contains("/*"));
+
+        let plain = Parser::parse("fn main() {\n    let x = 1;\n}\n").unwrap();
+        assert!(WorkflowUsage::collect(&plain).is_none());
+    }
+
+    #[test]
+    fn test_step_options_merge_and_back_off() {
+        let is_step = |name: &str| name == "refund";
+        let mut options = StepOptions::default();
+        options
+            .merge(
+                &serde_json::json!({ "retries": 3, "delay": "1s", "maxDelay": "5s" }),
+                &is_step,
+            )
+            .unwrap();
+        options
+            .merge(
+                &serde_json::json!({ "timeout": "2m", "compensate": "refund" }),
+                &is_step,
+            )
+            .unwrap();
+        assert_eq!(options.retries, 3);
+        assert_eq!(options.timeout_ms, Some(120_000));
+        assert_eq!(options.compensate.as_deref(), Some("refund"));
+
+        // Exponentiell ab `delay`, gedeckelt durch `max_delay`
+        let delays: Vec<u64> = (1..=5)
+            .map(|attempt| options.delay_for(attempt).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, [1_000, 2_000, 4_000, 5_000, 5_000]);
+
+        options
+            .merge(
+                &serde_json::json!({ "backoff": "fixed", "delay": 250 }),
+                &is_step,
+            )
+            .unwrap();
+        assert_eq!(options.delay_for(4).as_millis(), 250);
+
+        let error =
+            |value: serde_json::Value| StepOptions::default().merge(&value, &is_step).unwrap_err();
+        assert_eq!(
+            error(serde_json::json!({ "compensate": "charge" })),
+            "unknown compensation function 'charge'"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "delay": "soon" })),
+            "invalid duration 'soon'"
+        );
+        assert_eq!(
+            error(serde_json::json!({ "retry": 1 })),
+            "unknown step option 'retry'"
+        );
     }
 }
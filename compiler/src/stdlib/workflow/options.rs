@@ -0,0 +1,115 @@
+// Schritt-Optionen für `workflow`: Wiederholungen, Backoff, Timeout und
+// Kompensation.
+// Die Datei wird unverändert in das generierte Modul `stdlib::workflow`
+// eingebettet.
+
+use std::time::Duration;
+
+/// Wiederholungen, Backoff, Timeout und Kompensation eines Schritts
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StepOptions {
+    pub retries: u32,
+    pub backoff: String,
+    pub delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub timeout_ms: Option<u64>,
+    pub compensate: Option<String>,
+}
+
+impl Default for StepOptions {
+    fn default() -> Self {
+        StepOptions {
+            retries: 0,
+            backoff: "exponential".to_string(),
+            delay_ms: 100,
+            max_delay_ms: 30_000,
+            timeout_ms: None,
+            compensate: None,
+        }
+    }
+}
+
+impl StepOptions {
+    /// Übernimmt Optionen aus `@Step` oder der Workflow-Definition; `is_step`
+    /// prüft, ob eine Kompensations-Funktion ein Schritt ist
+    pub fn merge(
+        &mut self,
+        options: &serde_json::Value,
+        is_step: &dyn Fn(&str) -> bool,
+    ) -> Result<(), String> {
+        let Some(options) = options.as_object() else {
+            return Ok(());
+        };
+        for (key, value) in options {
+            match key.as_str() {
+                "id" | "name" | "run" => {}
+                "retries" => {
+                    self.retries = value.as_f64().filter(|n| *n >= 0.0).ok_or_else(|| {
+                        format!("retries must be a non-negative number, got {}", value)
+                    })? as u32
+                }
+                "backoff" => match value.as_str() {
+                    Some(backoff @ ("fixed" | "exponential")) => self.backoff = backoff.to_string(),
+                    _ => {
+                        return Err(format!(
+                            "backoff must be \"fixed\" or \"exponential\", got {}",
+                            value
+                        ))
+                    }
+                },
+                "delay" => self.delay_ms = duration_ms(value)?,
+                "max_delay" | "maxDelay" => self.max_delay_ms = duration_ms(value)?,
+                "timeout" => self.timeout_ms = Some(duration_ms(value)?),
+                "compensate" => {
+                    let name = value.as_str().ok_or_else(|| {
+                        format!("compensate must be a function name, got {}", value)
+                    })?;
+                    if !is_step(name) {
+                        return Err(format!("unknown compensation function '{}'", name));
+                    }
+                    self.compensate = Some(name.to_string());
+                }
+                other => return Err(format!("unknown step option '{}'", other)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Wartezeit nach dem Fehlversuch `attempt` (ab 1)
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let ms = if self.backoff == "fixed" {
+            self.delay_ms
+        } else {
+            self.delay_ms
+                .saturating_mul(1u64 << attempt.saturating_sub(1).min(20))
+        };
+        Duration::from_millis(ms.min(self.max_delay_ms))
+    }
+}
+
+/// `"500ms"`, `"10s"`, `"5m"`, `"1h"` oder Millisekunden
+pub fn duration_ms(value: &serde_json::Value) -> Result<u64, String> {
+    if let Some(ms) = value.as_f64() {
+        return Ok(ms.max(0.0) as u64);
+    }
+    let text = value
+        .as_str()
+        .ok_or_else(|| format!("invalid duration {}", value))?
+        .trim();
+    let (number, factor) = if let Some(n) = text.strip_suffix("ms") {
+        (n, 1.0)
+    } else if let Some(n) = text.strip_suffix('s') {
+        (n, 1_000.0)
+    } else if let Some(n) = text.strip_suffix('m') {
+        (n, 60_000.0)
+    } else if let Some(n) = text.strip_suffix('h') {
+        (n, 3_600_000.0)
+    } else {
+        (text, 1.0)
+    };
+    number
+        .trim()
+        .parse::<f64>()
+        .map(|n| (n * factor).max(0.0) as u64)
+        .map_err(|_| format!("invalid duration '{}'", text))
+}
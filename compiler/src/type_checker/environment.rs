@@ -1,5 +1,5 @@
 use crate::parser::ast::{Enum, Struct, Type};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct Environment {
@@ -9,6 +9,8 @@ pub struct Environment {
     pub structs: HashMap<String, Struct>,
     pub enums: HashMap<String, Enum>,
     pub modules: HashMap<String, Box<Environment>>, // Make public for debug
+    /// Von außen sichtbare Namen; `None` = alles sichtbar (lokale Module)
+    exports: Option<HashSet<String>>,
     parent: Option<Box<Environment>>,
 }
 
@@ -49,6 +51,7 @@ impl Environment {
             structs: HashMap::new(),
             enums: HashMap::new(),
             modules: HashMap::new(),
+            exports: None,
             parent: None,
         }
     }
@@ -61,6 +64,7 @@ impl Environment {
             structs: HashMap::new(),
             enums: HashMap::new(),
             modules: HashMap::new(),
+            exports: None,
             parent: Some(Box::new(parent)),
         }
     }
@@ -122,6 +126,9 @@ impl Environment {
 
         if let Some(env) = self.modules.get(name) {
             Some(*env.clone())
+        } else if let Some((first, rest)) = name.split_once('.') {
+            // Verschachtelte Module (z.B. `http_utils.client`)
+            self.get_module(first)?.submodule(rest)
         } else if let Some(ref parent) = self.parent {
             parent.get_module(name)
         } else {
@@ -129,6 +136,40 @@ impl Environment {
         }
     }
 
+    /// Untermodul ohne Suche in Eltern-Scopes
+    fn submodule(&self, path: &str) -> Option<Environment> {
+        match path.split_once('.') {
+            Some((first, rest)) => self.modules.get(first)?.submodule(rest),
+            None => self.modules.get(path).map(|env| *env.clone()),
+        }
+    }
+
+    /// Schränkt die von außen sichtbaren Namen ein (Package-Module)
+    pub fn set_exports(&mut self, exports: HashSet<String>) {
+        self.exports = Some(exports);
+    }
+
+    pub fn is_exported(&self, name: &str) -> bool {
+        self.exports
+            .as_ref()
+            .is_none_or(|exports| exports.contains(name))
+    }
+
+    /// Prüft einen qualifizierten Namen (`pkg.mod.item`) auf private Bestandteile
+    ///
+    /// Liefert (Modul, Name) des ersten nicht exportierten Segments.
+    pub fn private_member(&self, name: &str) -> Option<(String, String)> {
+        let segments: Vec<&str> = name.split('.').collect();
+        for i in 1..segments.len() {
+            let module_name = segments[..i].join(".");
+            let module = self.get_module(&module_name)?;
+            if !module.is_exported(segments[i]) {
+                return Some((module_name, segments[i].to_string()));
+            }
+        }
+        None
+    }
+
     pub fn set_parent(&mut self, parent: Environment) {
         self.parent = Some(Box::new(parent));
     }
@@ -41,6 +41,23 @@ pub enum TypeErrorKind {
         found: String,
     },
     InvalidMemberAccess,
+    InvalidValidationRule {
+        struct_name: String,
+        field: String,
+    },
+    PrivateItem {
+        module: String,
+        name: String,
+    },
+    InvalidSchedule(String),
+    InvalidEvent(String),
+    InvalidTracing(String),
+    PiiLeak(String),
+    InvalidPrivacy(String),
+    InvalidAudit(String),
+    InvalidRealtime(String),
+    InvalidTemplate(String),
+    InvalidStream(String),
 }
 
 impl TypeError {
@@ -78,6 +95,20 @@ impl TypeError {
         TypeError::new(TypeErrorKind::UndefinedFunction(name.to_string()), message)
     }
 
+    pub fn private_item(module: &str, name: &str) -> Self {
+        let message = format!(
+            "'{}' is private in package module '{}' (mark it 'pub' to export it)",
+            name, module
+        );
+        TypeError::new(
+            TypeErrorKind::PrivateItem {
+                module: module.to_string(),
+                name: name.to_string(),
+            },
+            message,
+        )
+    }
+
     pub fn undefined_type(name: &str) -> Self {
         let message = format!("Undefined type: {}", name);
         TypeError::new(TypeErrorKind::UndefinedType(name.to_string()), message)
@@ -104,6 +135,62 @@ impl TypeError {
         TypeError::new(TypeErrorKind::MissingReturn, message)
     }
 
+    pub fn invalid_validation_rule(struct_name: &str, field: &str, reason: &str) -> Self {
+        let message = format!("Invalid @Validate on {}.{}: {}", struct_name, field, reason);
+        TypeError::new(
+            TypeErrorKind::InvalidValidationRule {
+                struct_name: struct_name.to_string(),
+                field: field.to_string(),
+            },
+            message,
+        )
+    }
+
+    pub fn invalid_schedule(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidSchedule(target.to_string()), message)
+    }
+
+    pub fn invalid_event(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidEvent(target.to_string()), message)
+    }
+
+    pub fn invalid_tracing(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidTracing(target.to_string()), message)
+    }
+
+    pub fn pii_leak(target: &str, reason: &str) -> Self {
+        let message = format!("PII leak in {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::PiiLeak(target.to_string()), message)
+    }
+
+    pub fn invalid_privacy(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidPrivacy(target.to_string()), message)
+    }
+
+    pub fn invalid_audit(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidAudit(target.to_string()), message)
+    }
+
+    pub fn invalid_realtime(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidRealtime(target.to_string()), message)
+    }
+
+    pub fn invalid_template(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidTemplate(target.to_string()), message)
+    }
+
+    pub fn invalid_stream(target: &str, reason: &str) -> Self {
+        let message = format!("Invalid {}: {}", target, reason);
+        TypeError::new(TypeErrorKind::InvalidStream(target.to_string()), message)
+    }
+
     pub fn wrong_argument_count(expected: usize, found: usize) -> Self {
         let message = format!(
             "Wrong argument count: expected {}, found {}",
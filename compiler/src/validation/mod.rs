@@ -0,0 +1,509 @@
+// Deklarative Feldvalidierung
+// `@Validate(...)` an Struct-Feldern: Regeln auslesen und gegen den Feldtyp prüfen.
+// Type Checker, alle Backends und der OpenAPI-Generator lesen dieselben Regeln.
+
+use crate::parser::ast::{
+    Decorator, DecoratorArg, Function, Item, Parameter, Struct, StructField, Type,
+};
+use std::collections::HashSet;
+
+/// `error`-Wert der 400-Antwort bei fehlgeschlagener Validierung
+pub const VALIDATION_FAILED: &str = "Validation failed";
+
+/// Unterstützte Regeln in `@Validate(...)`
+pub const RULES: &[&str] = &["min", "max", "email", "pattern", "required", "one_of"];
+
+/// Erlaubter Wert in `one_of`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleValue {
+    String(String),
+    Number(f64),
+}
+
+/// Worauf sich `min`/`max` beziehen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Zeichenanzahl
+    Text,
+    /// Zahlenwert
+    Number,
+    /// Anzahl der Elemente
+    List,
+    /// Nur `required` erlaubt
+    Other,
+}
+
+/// Regeln aller `@Validate`-Decorators eines Feldes
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FieldRules {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub email: bool,
+    pub pattern: Option<String>,
+    pub required: bool,
+    pub one_of: Vec<RuleValue>,
+}
+
+/// Gültig annotiertes Feld eines Structs
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValidation {
+    pub name: String,
+    pub kind: ValueKind,
+    /// `T?` bzw. `Option<T>`: Regeln gelten nur für vorhandene Werte
+    pub optional: bool,
+    pub rules: FieldRules,
+}
+
+fn is_validate(decorator: &Decorator) -> bool {
+    matches!(decorator.name.as_str(), "Validate" | "@Validate")
+}
+
+/// Innerer Typ ohne `Optional`, plus ob das Feld optional ist
+pub fn unwrap_optional(ty: &Type) -> (&Type, bool) {
+    match ty {
+        Type::Optional(inner) => (inner.as_ref(), true),
+        Type::Generic { name, params } if name == "Option" && params.len() == 1 => {
+            (&params[0], true)
+        }
+        _ => (ty, false),
+    }
+}
+
+pub fn value_kind(ty: &Type) -> ValueKind {
+    match ty {
+        Type::String => ValueKind::Text,
+        Type::Number => ValueKind::Number,
+        Type::List(_) => ValueKind::List,
+        Type::Generic { name, .. } if name == "List" || name == "Vec" => ValueKind::List,
+        _ => ValueKind::Other,
+    }
+}
+
+/// Liest die Regeln eines Feldes. `Ok(None)` ohne `@Validate`,
+/// `Err` mit allen Regelfehlern (unbekannte Regel, falscher Wert, unpassender Feldtyp).
+pub fn field_rules(field: &StructField) -> Result<Option<FieldRules>, Vec<String>> {
+    let decorators: Vec<&Decorator> = field.decorators.iter().filter(|d| is_validate(d)).collect();
+    if decorators.is_empty() {
+        return Ok(None);
+    }
+
+    let kind = value_kind(unwrap_optional(&field.field_type).0);
+    let mut rules = FieldRules::default();
+    let mut errors = Vec::new();
+    for arg in decorators.iter().flat_map(|d| &d.args) {
+        let DecoratorArg::Named { name, value } = arg else {
+            errors.push(format!(
+                "@Validate expects named rules ({}), found {:?}",
+                RULES.join(", "),
+                arg
+            ));
+            continue;
+        };
+        if let Err(message) = apply_rule(&mut rules, name, value, kind) {
+            errors.push(message);
+        }
+    }
+
+    if let (Some(min), Some(max)) = (rules.min, rules.max) {
+        if min > max {
+            errors.push(format!("min ({}) is greater than max ({})", min, max));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Some(rules))
+    } else {
+        Err(errors)
+    }
+}
+
+fn apply_rule(
+    rules: &mut FieldRules,
+    name: &str,
+    value: &DecoratorArg,
+    kind: ValueKind,
+) -> Result<(), String> {
+    match name {
+        "min" | "max" => {
+            let DecoratorArg::Number(bound) = value else {
+                return Err(format!("{} expects a number", name));
+            };
+            match kind {
+                ValueKind::Number => {}
+                ValueKind::Text | ValueKind::List => {
+                    if *bound < 0.0 || bound.fract() != 0.0 {
+                        return Err(format!(
+                            "{} on a {} counts {} and must be a non-negative integer",
+                            name,
+                            kind_name(kind),
+                            if kind == ValueKind::Text {
+                                "characters"
+                            } else {
+                                "items"
+                            }
+                        ));
+                    }
+                }
+                ValueKind::Other => return Err(unsupported(name, kind)),
+            }
+            if name == "min" {
+                rules.min = Some(*bound);
+            } else {
+                rules.max = Some(*bound);
+            }
+        }
+        "email" => {
+            let DecoratorArg::Boolean(enabled) = value else {
+                return Err("email expects true or false".to_string());
+            };
+            if kind != ValueKind::Text {
+                return Err(unsupported(name, kind));
+            }
+            rules.email = *enabled;
+        }
+        "pattern" => {
+            let DecoratorArg::String(pattern) = value else {
+                return Err("pattern expects a regular expression string".to_string());
+            };
+            if kind != ValueKind::Text {
+                return Err(unsupported(name, kind));
+            }
+            if let Err(e) = regex::Regex::new(pattern) {
+                return Err(format!("pattern is not a valid regular expression: {}", e));
+            }
+            rules.pattern = Some(pattern.clone());
+        }
+        "required" => {
+            let DecoratorArg::Boolean(required) = value else {
+                return Err("required expects true or false".to_string());
+            };
+            rules.required = *required;
+        }
+        "one_of" | "oneOf" => {
+            let values = match value {
+                DecoratorArg::List(values) => values.as_slice(),
+                single => std::slice::from_ref(single),
+            };
+            if values.is_empty() {
+                return Err("one_of needs at least one value".to_string());
+            }
+            for value in values {
+                let allowed = match (kind, value) {
+                    (ValueKind::Text, DecoratorArg::String(s)) => RuleValue::String(s.clone()),
+                    (ValueKind::Number, DecoratorArg::Number(n)) => RuleValue::Number(*n),
+                    (ValueKind::Text | ValueKind::Number, other) => {
+                        return Err(format!(
+                            "one_of value {:?} does not match the {} field",
+                            other,
+                            kind_name(kind)
+                        ))
+                    }
+                    _ => return Err(unsupported("one_of", kind)),
+                };
+                rules.one_of.push(allowed);
+            }
+        }
+        other => {
+            return Err(format!(
+                "unknown validation rule '{}' (expected one of: {})",
+                other,
+                RULES.join(", ")
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn kind_name(kind: ValueKind) -> &'static str {
+    match kind {
+        ValueKind::Text => "string",
+        ValueKind::Number => "number",
+        ValueKind::List => "list",
+        ValueKind::Other => "non-scalar",
+    }
+}
+
+fn unsupported(rule: &str, kind: ValueKind) -> String {
+    format!("{} cannot be applied to a {} field", rule, kind_name(kind))
+}
+
+/// Alle gültig annotierten Felder eines Structs. Fehlerhafte Regeln meldet der
+/// Type Checker; Codegen und OpenAPI überspringen sie.
+pub fn struct_validations(struct_def: &Struct) -> Vec<FieldValidation> {
+    struct_def
+        .fields
+        .iter()
+        .filter_map(|field| {
+            let rules = field_rules(field).ok()??;
+            let (inner, optional) = unwrap_optional(&field.field_type);
+            Some(FieldValidation {
+                name: field.name.clone(),
+                kind: value_kind(inner),
+                optional,
+                rules,
+            })
+        })
+        .collect()
+}
+
+pub fn has_validations(struct_def: &Struct) -> bool {
+    !struct_validations(struct_def).is_empty()
+}
+
+/// Namen aller Structs mit gültigen `@Validate`-Feldern (inklusive Module)
+pub fn validated_structs(items: &[Item]) -> HashSet<String> {
+    let mut structs = HashSet::new();
+    for item in items {
+        match item {
+            Item::Struct(s) if has_validations(s) => {
+                structs.insert(s.name.clone());
+            }
+            Item::Module(m) => structs.extend(validated_structs(&m.items)),
+            _ => {}
+        }
+    }
+    structs
+}
+
+/// HTTP-Handler, unabhängig von der Schreibweise (`@POST`, `@Post`)
+pub fn is_route_handler(function: &Function) -> bool {
+    function.decorators.iter().any(|d| {
+        matches!(
+            d.name.trim_start_matches('@').to_uppercase().as_str(),
+            "GET" | "POST" | "PUT" | "PATCH" | "DELETE"
+        )
+    })
+}
+
+/// Parameter eines Handlers, deren Struct-Typ `@Validate`-Felder hat
+pub fn validated_params<'a>(
+    function: &'a Function,
+    structs: &HashSet<String>,
+) -> Vec<&'a Parameter> {
+    if !is_route_handler(function) {
+        return Vec::new();
+    }
+    function
+        .params
+        .iter()
+        .filter(|p| matches!(&p.param_type, Type::Named(name) if structs.contains(name)))
+        .collect()
+}
+
+/// Einzelne Prüfung für die Backends; `email` wird zu `Pattern(EMAIL_PATTERN)`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    /// Text ist leer bzw. optionaler Wert fehlt
+    Required,
+    MinLength(usize),
+    MaxLength(usize),
+    Pattern(String),
+    Min(f64),
+    Max(f64),
+    MinItems(usize),
+    MaxItems(usize),
+    OneOf(Vec<RuleValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldCheck {
+    pub check: Check,
+    pub message: String,
+}
+
+impl FieldValidation {
+    /// Prüfungen in fester Reihenfolge. `Required` steht vorne und prüft das Fehlen
+    /// des Wertes; alle anderen gelten nur für vorhandene Werte.
+    pub fn checks(&self) -> Vec<FieldCheck> {
+        let name = self.name.as_str();
+        let rules = &self.rules;
+        let mut checks = Vec::new();
+        let mut push = |check, message| checks.push(FieldCheck { check, message });
+
+        if rules.required {
+            push(Check::Required, messages::required(name));
+        }
+        match self.kind {
+            ValueKind::Text => {
+                if let Some(min) = rules.min {
+                    push(
+                        Check::MinLength(min as usize),
+                        messages::min(name, self.kind, min),
+                    );
+                }
+                if let Some(max) = rules.max {
+                    push(
+                        Check::MaxLength(max as usize),
+                        messages::max(name, self.kind, max),
+                    );
+                }
+                if rules.email {
+                    push(
+                        Check::Pattern(EMAIL_PATTERN.to_string()),
+                        messages::email(name),
+                    );
+                }
+                if let Some(pattern) = &rules.pattern {
+                    push(Check::Pattern(pattern.clone()), messages::pattern(name));
+                }
+            }
+            ValueKind::Number => {
+                if let Some(min) = rules.min {
+                    push(Check::Min(min), messages::min(name, self.kind, min));
+                }
+                if let Some(max) = rules.max {
+                    push(Check::Max(max), messages::max(name, self.kind, max));
+                }
+            }
+            ValueKind::List => {
+                if let Some(min) = rules.min {
+                    push(
+                        Check::MinItems(min as usize),
+                        messages::min(name, self.kind, min),
+                    );
+                }
+                if let Some(max) = rules.max {
+                    push(
+                        Check::MaxItems(max as usize),
+                        messages::max(name, self.kind, max),
+                    );
+                }
+            }
+            ValueKind::Other => {}
+        }
+        if !rules.one_of.is_empty() {
+            let allowed: Vec<String> = rules.one_of.iter().map(RuleValue::display).collect();
+            push(
+                Check::OneOf(rules.one_of.clone()),
+                messages::one_of(name, &allowed),
+            );
+        }
+        checks
+    }
+}
+
+/// Fehlermeldungen der generierten Prüfungen (für alle Backends gleich)
+pub mod messages {
+    use super::ValueKind;
+
+    pub fn required(field: &str) -> String {
+        format!("{} ist erforderlich", field)
+    }
+
+    pub fn min(field: &str, kind: ValueKind, min: f64) -> String {
+        match kind {
+            ValueKind::Text => format!("{} muss mindestens {} Zeichen lang sein", field, min),
+            ValueKind::List => format!("{} muss mindestens {} Einträge haben", field, min),
+            _ => format!("{} muss mindestens {} sein", field, min),
+        }
+    }
+
+    pub fn max(field: &str, kind: ValueKind, max: f64) -> String {
+        match kind {
+            ValueKind::Text => format!("{} darf maximal {} Zeichen lang sein", field, max),
+            ValueKind::List => format!("{} darf maximal {} Einträge haben", field, max),
+            _ => format!("{} darf maximal {} sein", field, max),
+        }
+    }
+
+    pub fn email(field: &str) -> String {
+        format!("{} muss eine gültige E-Mail-Adresse sein", field)
+    }
+
+    pub fn pattern(field: &str) -> String {
+        format!("{} hat ein ungültiges Format", field)
+    }
+
+    pub fn one_of(field: &str, allowed: &[String]) -> String {
+        format!("{} muss einer der Werte {} sein", field, allowed.join(", "))
+    }
+}
+
+/// Regex für `email: true` (gleich wie `Validator::email` der Standardbibliothek)
+pub const EMAIL_PATTERN: &str = r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$";
+
+impl RuleValue {
+    /// Anzeigeform für Fehlermeldungen
+    pub fn display(&self) -> String {
+        match self {
+            RuleValue::String(s) => s.clone(),
+            RuleValue::Number(n) => n.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::Item;
+    use crate::parser::parser::Parser;
+
+    fn parse_struct(source: &str) -> Struct {
+        let program = Parser::parse(source).unwrap();
+        match program.items.into_iter().next() {
+            Some(Item::Struct(s)) => s,
+            other => panic!("Struct erwartet, gefunden {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rules_are_read_per_field_type() {
+        let user = parse_struct(
+            r#"struct User {
+    @Validate(min: 2, max: 50, required: true)
+    name: string,
+    @Validate(email: true)
+    email: string,
+    @Validate(min: 18, max: 130)
+    age: number,
+    @Validate(one_of: ["admin", "user"])
+    role: string,
+    nickname: string,
+}
+"#,
+        );
+        let fields = struct_validations(&user);
+        assert_eq!(fields.len(), 4);
+        assert_eq!(fields[0].kind, ValueKind::Text);
+        assert_eq!(fields[0].rules.min, Some(2.0));
+        assert!(fields[0].rules.required);
+        assert!(fields[1].rules.email);
+        assert_eq!(fields[2].kind, ValueKind::Number);
+        assert_eq!(fields[2].rules.max, Some(130.0));
+        assert_eq!(
+            fields[3].rules.one_of,
+            vec![
+                RuleValue::String("admin".to_string()),
+                RuleValue::String("user".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rules_that_do_not_fit_the_field_are_rejected() {
+        let invalid = parse_struct(
+            r#"struct Invalid {
+    @Validate(email: true, pattern: "[")
+    age: number,
+    @Validate(min: 10, max: 2, size: 3)
+    name: string,
+    @Validate(one_of: [1, 2])
+    role: string,
+}
+"#,
+        );
+        let errors: Vec<Vec<String>> = invalid
+            .fields
+            .iter()
+            .map(|field| field_rules(field).unwrap_err())
+            .collect();
+        assert_eq!(errors[0].len(), 2);
+        assert!(errors[0][0].contains("email cannot be applied to a number field"));
+        assert!(errors[1]
+            .iter()
+            .any(|e| e.contains("unknown validation rule 'size'")));
+        assert!(errors[1].iter().any(|e| e.contains("greater than max")));
+        assert!(errors[2][0].contains("does not match the string field"));
+        assert!(struct_validations(&invalid).is_empty());
+    }
+}
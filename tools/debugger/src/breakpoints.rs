@@ -1,8 +1,8 @@
 // Breakpoint Management
 
-use serde_json::{Value, Map};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct Breakpoint {
@@ -26,27 +26,34 @@ impl BreakpointManager {
         }
     }
 
+    /// Ersetzt alle Breakpoints der angegebenen Quelle (DAP `setBreakpoints`)
     pub fn set_breakpoints(&mut self, args: &Value) -> Value {
         let mut result = Map::new();
         let mut breakpoints = Vec::new();
 
-        if let Some(source) = args.get("source") {
-            if let Some(path) = source.get("path").and_then(|v| v.as_str()) {
-                // Use path variable - convert to PathBuf for breakpoint tracking
-                let path_buf = std::path::PathBuf::from(path);
-                
-                if let Some(lines) = args.get("breakpoints").and_then(|v| v.as_array()) {
-                    for line_obj in lines {
-                        if let Some(line) = line_obj.get("line").and_then(|v| v.as_u64()) {
-                            // Add breakpoint using the path
-                            let id = self.add_breakpoint(path_buf.clone(), line as usize, None);
-                            
-                            let mut bp = Map::new();
-                            bp.insert("id".to_string(), Value::Number(id.into()));
-                            bp.insert("verified".to_string(), Value::Bool(true));
-                            bp.insert("line".to_string(), Value::Number(line.into()));
-                            breakpoints.push(Value::Object(bp));
-                        }
+        if let Some(path) = args
+            .get("source")
+            .and_then(|source| source.get("path"))
+            .and_then(|v| v.as_str())
+        {
+            let path_buf = Self::normalize(Path::new(path));
+            self.breakpoints.retain(|_, bp| bp.file != path_buf);
+
+            if let Some(lines) = args.get("breakpoints").and_then(|v| v.as_array()) {
+                for line_obj in lines {
+                    if let Some(line) = line_obj.get("line").and_then(|v| v.as_u64()) {
+                        let condition = line_obj
+                            .get("condition")
+                            .and_then(|v| v.as_str())
+                            .map(|c| c.trim().to_string())
+                            .filter(|c| !c.is_empty());
+                        let id = self.add_breakpoint(path_buf.clone(), line as usize, condition);
+
+                        let mut bp = Map::new();
+                        bp.insert("id".to_string(), Value::Number(id.into()));
+                        bp.insert("verified".to_string(), Value::Bool(true));
+                        bp.insert("line".to_string(), Value::Number(line.into()));
+                        breakpoints.push(Value::Object(bp));
                     }
                 }
             }
@@ -56,18 +63,31 @@ impl BreakpointManager {
         Value::Object(result)
     }
 
-    pub fn add_breakpoint(&mut self, file: PathBuf, line: usize, condition: Option<String>) -> usize {
+    /// Einheitlicher Pfad für den Vergleich von IDE- und Interpreter-Pfaden
+    pub fn normalize(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    pub fn add_breakpoint(
+        &mut self,
+        file: PathBuf,
+        line: usize,
+        condition: Option<String>,
+    ) -> usize {
         let id = self.next_id;
         self.next_id += 1;
-        
-        self.breakpoints.insert(id, Breakpoint {
+
+        self.breakpoints.insert(
             id,
-            file,
-            line,
-            condition,
-            hit_count: 0,
-        });
-        
+            Breakpoint {
+                id,
+                file,
+                line,
+                condition,
+                hit_count: 0,
+            },
+        );
+
         id
     }
 
@@ -79,9 +99,16 @@ impl BreakpointManager {
         self.breakpoints.get(&id)
     }
 
-    pub fn get_breakpoints_at_line(&self, file: &PathBuf, line: usize) -> Vec<&Breakpoint> {
-        self.breakpoints.values()
-            .filter(|bp| &bp.file == file && bp.line == line)
+    pub fn record_hit(&mut self, id: usize) {
+        if let Some(bp) = self.breakpoints.get_mut(&id) {
+            bp.hit_count += 1;
+        }
+    }
+
+    pub fn get_breakpoints_at_line(&self, file: &Path, line: usize) -> Vec<&Breakpoint> {
+        self.breakpoints
+            .values()
+            .filter(|bp| bp.file == file && bp.line == line)
             .collect()
     }
 }
@@ -1,32 +1,30 @@
 // DAP (Debug Adapter Protocol) Server Implementation
 
-use anyhow::Result;
-use crate::debugger::Debugger;
 use crate::breakpoints::BreakpointManager;
-use crate::variables::VariableInspector;
+use crate::debugger::{event, Debugger, SessionState};
 use crate::stack::CallStack;
-use serde_json::{Value, Map};
+use crate::variables::VariableInspector;
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
 
 pub struct DAPServer {
     port: u16,
-    debugger: Arc<Mutex<Debugger>>,
-    breakpoints: Arc<Mutex<BreakpointManager>>,
-    variables: Arc<Mutex<VariableInspector>>,
-    call_stack: Arc<Mutex<CallStack>>,
+}
+
+/// Zustand einer DAP-Verbindung
+struct ClientSession {
+    debugger: Debugger,
+    state: SessionState,
 }
 
 impl DAPServer {
     pub fn new(port: u16) -> Self {
-        DAPServer {
-            port,
-            debugger: Arc::new(Mutex::new(Debugger::new())),
-            breakpoints: Arc::new(Mutex::new(BreakpointManager::new())),
-            variables: Arc::new(Mutex::new(VariableInspector::new())),
-            call_stack: Arc::new(Mutex::new(CallStack::new())),
-        }
+        DAPServer { port }
     }
 
     pub async fn run(&self) -> Result<()> {
@@ -37,13 +35,8 @@ impl DAPServer {
             match listener.accept().await {
                 Ok((stream, addr)) => {
                     println!("📡 New connection from {}", addr);
-                    let debugger = Arc::clone(&self.debugger);
-                    let breakpoints = Arc::clone(&self.breakpoints);
-                    let variables = Arc::clone(&self.variables);
-                    let call_stack = Arc::clone(&self.call_stack);
-                    
                     tokio::spawn(async move {
-                        if let Err(e) = Self::handle_client(stream, debugger, breakpoints, variables, call_stack).await {
+                        if let Err(e) = Self::handle_client(stream).await {
                             eprintln!("Error handling client: {}", e);
                         }
                     });
@@ -55,40 +48,63 @@ impl DAPServer {
         }
     }
 
-    async fn handle_client(
-        mut stream: TcpStream,
-        debugger: Arc<Mutex<Debugger>>,
-        breakpoints: Arc<Mutex<BreakpointManager>>,
-        variables: Arc<Mutex<VariableInspector>>,
-        call_stack: Arc<Mutex<CallStack>>,
-    ) -> Result<()> {
-        use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
-        let (reader, mut writer) = stream.split();
+    async fn handle_client(stream: TcpStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
         let mut buf_reader = tokio::io::BufReader::new(reader);
         let mut line_buf = String::new();
 
+        // Responses und Events laufen über einen Kanal, damit der Interpreter-Thread
+        // Events senden kann; `seq` wird beim Schreiben vergeben
+        let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Value>();
+        let writer_task = tokio::spawn(async move {
+            let mut seq: u64 = 1;
+            while let Some(mut message) = out_rx.recv().await {
+                if let Value::Object(map) = &mut message {
+                    map.insert("seq".to_string(), Value::Number(seq.into()));
+                }
+                seq += 1;
+                let message_json = serde_json::to_string(&message)?;
+                let header = format!("Content-Length: {}\r\n\r\n", message_json.len());
+                writer.write_all(header.as_bytes()).await?;
+                writer.write_all(message_json.as_bytes()).await?;
+                writer.flush().await?;
+            }
+            Ok::<(), anyhow::Error>(())
+        });
+
+        let mut session = ClientSession {
+            debugger: Debugger::new(),
+            state: SessionState {
+                breakpoints: Arc::new(Mutex::new(BreakpointManager::new())),
+                variables: Arc::new(Mutex::new(VariableInspector::new())),
+                call_stack: Arc::new(Mutex::new(CallStack::new())),
+                events: out_tx.clone(),
+            },
+        };
+
         loop {
             line_buf.clear();
-            
+
             // Read headers line by line
             let mut content_length = 0;
             loop {
                 let bytes_read = buf_reader.read_line(&mut line_buf).await?;
                 if bytes_read == 0 {
+                    session.debugger.disconnect();
                     return Ok(()); // Connection closed
                 }
-                
+
                 let line = line_buf.trim();
                 if line.is_empty() {
                     break; // End of headers
                 }
-                
+
                 if line.starts_with("Content-Length:") {
                     if let Some(len_str) = line.split(':').nth(1) {
                         content_length = len_str.trim().parse().unwrap_or(0);
                     }
                 }
-                
+
                 line_buf.clear();
             }
 
@@ -103,31 +119,60 @@ impl DAPServer {
 
             // Parse JSON message
             let message: Value = serde_json::from_str(&message_str)?;
+            let command = message
+                .get("command")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
 
             // Handle DAP request
-            let response = Self::handle_request(&message, &debugger, &breakpoints, &variables, &call_stack).await?;
-
-            // Send response
-            let response_json = serde_json::to_string(&response)?;
-            let response_header = format!("Content-Length: {}\r\n\r\n", response_json.len());
-            writer.write_all(response_header.as_bytes()).await?;
-            writer.write_all(response_json.as_bytes()).await?;
-            writer.flush().await?;
+            let response = Self::handle_request(&message, &mut session).await?;
+            let success = response
+                .get("success")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            out_tx.send(response)?;
+
+            // Aktionen, die laut DAP erst nach der Response erfolgen
+            match command.as_str() {
+                "initialize" => {
+                    out_tx.send(event("initialized", json!({})))?;
+                }
+                "configurationDone" if success => {
+                    if let Err(message) = session.debugger.start(session.state.clone()) {
+                        out_tx.send(event(
+                            "output",
+                            json!({ "category": "stderr", "output": format!("{}\n", message) }),
+                        ))?;
+                        out_tx.send(event("terminated", json!({})))?;
+                    }
+                }
+                "continue" if success => session.debugger.continue_execution(),
+                "next" if success => session.debugger.step_over(),
+                "stepIn" if success => session.debugger.step_into(),
+                "stepOut" if success => session.debugger.step_out(),
+                "disconnect" | "terminate" => {
+                    drop(out_tx);
+                    drop(session);
+                    let _ = writer_task.await;
+                    return Ok(());
+                }
+                _ => {}
+            }
         }
     }
 
-    async fn handle_request(
-        request: &Value,
-        _debugger: &Arc<Mutex<Debugger>>,
-        breakpoints: &Arc<Mutex<BreakpointManager>>,
-        variables: &Arc<Mutex<VariableInspector>>,
-        call_stack: &Arc<Mutex<CallStack>>,
-    ) -> Result<Value> {
-        let method = request.get("command")
+    async fn handle_request(request: &Value, session: &mut ClientSession) -> Result<Value> {
+        let method = request
+            .get("command")
             .and_then(|v| v.as_str())
             .unwrap_or("");
 
         let request_seq = request.get("seq").and_then(|v| v.as_u64()).unwrap_or(0);
+        let args = request
+            .get("arguments")
+            .cloned()
+            .unwrap_or(Value::Object(Map::new()));
 
         let mut response = Map::new();
         response.insert("type".to_string(), Value::String("response".to_string()));
@@ -135,85 +180,131 @@ impl DAPServer {
         response.insert("success".to_string(), Value::Bool(true));
         response.insert("command".to_string(), Value::String(method.to_string()));
 
-        match method {
+        // Fehler einer Anfrage werden als `success: false` mit Meldung beantwortet
+        let result: Result<Option<Value>, String> = match method {
             "initialize" => {
-                let body = Map::new();
-                response.insert("body".to_string(), Value::Object(body));
+                let mut body = Map::new();
+                body.insert(
+                    "supportsConfigurationDoneRequest".to_string(),
+                    Value::Bool(true),
+                );
+                body.insert(
+                    "supportsConditionalBreakpoints".to_string(),
+                    Value::Bool(true),
+                );
+                body.insert("supportsEvaluateForHovers".to_string(), Value::Bool(true));
+                body.insert("supportsTerminateRequest".to_string(), Value::Bool(true));
+                Ok(Some(Value::Object(body)))
             }
-            "setBreakpoints" => {
-                if let Some(args) = request.get("arguments") {
-                    let mut breakpoints_manager = breakpoints.lock().unwrap();
-                    let result = breakpoints_manager.set_breakpoints(args);
-                    response.insert("body".to_string(), result);
+            "launch" => match args.get("program").and_then(|v| v.as_str()) {
+                Some(program) => {
+                    let stop_on_entry = args
+                        .get("stopOnEntry")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                    session
+                        .debugger
+                        .launch(PathBuf::from(program), stop_on_entry)
+                        .map(|_| None)
                 }
+                None => Err("launch: 'program' fehlt".to_string()),
+            },
+            "configurationDone" => Ok(None),
+            "setBreakpoints" => {
+                let mut breakpoints_manager = session.state.breakpoints.lock().unwrap();
+                Ok(Some(breakpoints_manager.set_breakpoints(&args)))
             }
             "threads" => {
                 let mut body = Map::new();
-                body.insert("threads".to_string(), Value::Array(vec![
-                    Value::Object({
+                body.insert(
+                    "threads".to_string(),
+                    Value::Array(vec![Value::Object({
                         let mut thread = Map::new();
                         thread.insert("id".to_string(), Value::Number(1.into()));
                         thread.insert("name".to_string(), Value::String("main".to_string()));
                         thread
-                    })
-                ]));
-                response.insert("body".to_string(), Value::Object(body));
+                    })]),
+                );
+                Ok(Some(Value::Object(body)))
             }
             "stackTrace" => {
-                let call_stack_manager = call_stack.lock().unwrap();
+                let call_stack_manager = session.state.call_stack.lock().unwrap();
                 let stack_frames = call_stack_manager.get_frames();
                 let mut body = Map::new();
+                body.insert(
+                    "totalFrames".to_string(),
+                    Value::Number(stack_frames.len().into()),
+                );
                 body.insert("stackFrames".to_string(), Value::Array(stack_frames));
-                response.insert("body".to_string(), Value::Object(body));
+                Ok(Some(Value::Object(body)))
+            }
+            "scopes" => {
+                let variables_manager = session.state.variables.lock().unwrap();
+                let mut body = Map::new();
+                body.insert("scopes".to_string(), variables_manager.get_scopes(&args));
+                Ok(Some(Value::Object(body)))
             }
             "variables" => {
-                if let Some(args) = request.get("arguments") {
-                    let variables_manager = variables.lock().unwrap();
-                    let vars = variables_manager.get_variables(args);
-                    let mut body = Map::new();
-                    body.insert("variables".to_string(), vars);
-                    response.insert("body".to_string(), Value::Object(body));
-                }
+                let mut variables_manager = session.state.variables.lock().unwrap();
+                let vars = variables_manager.get_variables(&args);
+                let mut body = Map::new();
+                body.insert("variables".to_string(), vars);
+                Ok(Some(Value::Object(body)))
             }
-            "continue" => {
+            // Fortgesetzt wird erst nach der Response, damit `stopped` nicht überholt
+            "continue" => session.debugger.ensure_paused().map(|_| {
                 let mut body = Map::new();
                 body.insert("allThreadsContinued".to_string(), Value::Bool(true));
-                response.insert("body".to_string(), Value::Object(body));
-            }
-            "next" => {
-                let body = Map::new();
-                response.insert("body".to_string(), Value::Object(body));
-            }
-            "stepIn" => {
-                let body = Map::new();
-                response.insert("body".to_string(), Value::Object(body));
-            }
-            "stepOut" => {
-                let body = Map::new();
-                response.insert("body".to_string(), Value::Object(body));
-            }
+                Some(Value::Object(body))
+            }),
+            "next" | "stepIn" | "stepOut" => session.debugger.ensure_paused().map(|_| None),
             "pause" => {
-                let body = Map::new();
-                response.insert("body".to_string(), Value::Object(body));
+                session.debugger.pause();
+                Ok(None)
             }
             "evaluate" => {
-                if let Some(args) = request.get("arguments") {
-                    let expression = args.get("expression")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
-                    
-                    // Use args to get additional parameters if needed
-                    let _context = args.get("context").and_then(|v| v.as_str()).unwrap_or("watch");
-                    
-                    let mut body = Map::new();
-                    body.insert("result".to_string(), Value::String(format!("Evaluated: {}", expression)));
-                    body.insert("variablesReference".to_string(), Value::Number(0.into()));
-                    response.insert("body".to_string(), Value::Object(body));
+                let expression = args
+                    .get("expression")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let frame_id = args.get("frameId").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+
+                match session.debugger.evaluate(expression, frame_id) {
+                    Ok(reply) => match reply.await {
+                        Ok(Ok((result, type_name, reference))) => {
+                            let mut body = Map::new();
+                            body.insert("result".to_string(), Value::String(result));
+                            body.insert("type".to_string(), Value::String(type_name));
+                            body.insert(
+                                "variablesReference".to_string(),
+                                Value::Number(reference.into()),
+                            );
+                            Ok(Some(Value::Object(body)))
+                        }
+                        Ok(Err(message)) => Err(message),
+                        Err(_) => Err("Debug-Sitzung beendet".to_string()),
+                    },
+                    Err(message) => Err(message),
                 }
             }
-            _ => {
+            "disconnect" | "terminate" => {
+                session.debugger.disconnect();
+                Ok(None)
+            }
+            _ => Err(format!("Unknown command: {}", method)),
+        };
+
+        match result {
+            Ok(body) => {
+                response.insert(
+                    "body".to_string(),
+                    body.unwrap_or(Value::Object(Map::new())),
+                );
+            }
+            Err(message) => {
                 response.insert("success".to_string(), Value::Bool(false));
-                response.insert("message".to_string(), Value::String(format!("Unknown command: {}", method)));
+                response.insert("message".to_string(), Value::String(message));
             }
         }
 
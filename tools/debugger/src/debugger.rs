@@ -1,66 +1,200 @@
 // Debugger Core Logic
+// Steuert eine Debug-Sitzung: der Interpreter läuft in einem eigenen Thread und
+// wartet an Haltepunkten auf Befehle des DAP Servers
 
-use std::path::PathBuf;
+use crate::breakpoints::BreakpointManager;
+use crate::stack::CallStack;
+use crate::variables::VariableInspector;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+use velin_compiler::compiler::{config::CompilerConfig, VelinCompiler};
+use velin_compiler::interpreter::{describe, Control, DebugHook, Interpreter, Value};
+use velin_compiler::passes::{coverage::CoverageInstrumentationPass, parser::ParserPass};
+
+/// Ergebnis eines `evaluate`: Darstellung, Typ und Referenz für Kindelemente
+pub type EvaluateResult = Result<(String, String, usize), String>;
+
+/// Befehle vom DAP Server an den angehaltenen Interpreter
+pub enum Command {
+    Continue,
+    Next,
+    StepIn,
+    StepOut,
+    Evaluate {
+        expression: String,
+        frame_id: usize,
+        reply: oneshot::Sender<EvaluateResult>,
+    },
+    Disconnect,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StepMode {
+    Run,
+    StepIn,
+    /// Anhalten, sobald die Aufruftiefe höchstens diesen Wert hat
+    Over(usize),
+    /// Anhalten, sobald die Aufruftiefe kleiner als dieser Wert ist
+    Out(usize),
+}
+
+/// Von DAP Server und Interpreter-Thread gemeinsam genutzte Zustände
+#[derive(Clone)]
+pub struct SessionState {
+    pub breakpoints: Arc<Mutex<BreakpointManager>>,
+    pub variables: Arc<Mutex<VariableInspector>>,
+    pub call_stack: Arc<Mutex<CallStack>>,
+    pub events: UnboundedSender<serde_json::Value>,
+}
 
 pub struct Debugger {
-    program_path: Option<PathBuf>,
-    is_running: bool,
-    is_paused: bool,
-    current_line: Option<usize>,
+    interpreter: Option<Interpreter>,
+    stop_on_entry: bool,
+    commands: Option<mpsc::Sender<Command>>,
+    is_running: Arc<AtomicBool>,
+    is_paused: Arc<AtomicBool>,
+    pause_requested: Arc<AtomicBool>,
 }
 
 impl Debugger {
     pub fn new() -> Self {
         Debugger {
-            program_path: None,
-            is_running: false,
-            is_paused: false,
-            current_line: None,
+            interpreter: None,
+            stop_on_entry: false,
+            commands: None,
+            is_running: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
+            pause_requested: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    pub fn launch(&mut self, program: PathBuf) -> Result<(), String> {
-        self.program_path = Some(program);
-        self.is_running = true;
-        self.is_paused = false;
+    /// Parst und instrumentiert das Programm; gestartet wird erst mit `start`
+    pub fn launch(&mut self, program: PathBuf, stop_on_entry: bool) -> Result<(), String> {
+        if self.is_running() {
+            return Err("Es läuft bereits eine Debug-Sitzung".to_string());
+        }
+        self.interpreter = Some(load_program(&program)?);
+        self.stop_on_entry = stop_on_entry;
+        Ok(())
+    }
+
+    /// Startet den Interpreter-Thread (nach `configurationDone`)
+    pub fn start(&mut self, state: SessionState) -> Result<(), String> {
+        let mut interpreter = self
+            .interpreter
+            .take()
+            .ok_or_else(|| "Kein Programm geladen (launch fehlt)".to_string())?;
+        let (command_tx, command_rx) = mpsc::channel();
+        self.commands = Some(command_tx);
+        self.is_running.store(true, Ordering::SeqCst);
+        self.is_paused.store(false, Ordering::SeqCst);
+        self.pause_requested.store(false, Ordering::SeqCst);
+
+        let events = state.events.clone();
+        let is_running = Arc::clone(&self.is_running);
+        interpreter.set_hook(Box::new(SessionHook {
+            state,
+            commands: command_rx,
+            mode: if self.stop_on_entry {
+                StepMode::StepIn
+            } else {
+                StepMode::Run
+            },
+            entry: self.stop_on_entry,
+            is_paused: Arc::clone(&self.is_paused),
+            pause_requested: Arc::clone(&self.pause_requested),
+            canonical: HashMap::new(),
+        }));
+
+        std::thread::spawn(move || {
+            let exit_code = match interpreter.run_main() {
+                Ok(_) => 0,
+                Err(message) => {
+                    let _ = events.send(event(
+                        "output",
+                        json!({ "category": "stderr", "output": format!("{}\n", message) }),
+                    ));
+                    1
+                }
+            };
+            is_running.store(false, Ordering::SeqCst);
+            let _ = events.send(event("exited", json!({ "exitCode": exit_code })));
+            let _ = events.send(event("terminated", json!({})));
+        });
         Ok(())
     }
 
+    pub fn ensure_paused(&self) -> Result<(), String> {
+        if self.is_paused() {
+            Ok(())
+        } else {
+            Err("Programm ist nicht angehalten".to_string())
+        }
+    }
+
+    fn send(&self, command: Command) -> Result<(), String> {
+        self.ensure_paused()?;
+        self.commands
+            .as_ref()
+            .and_then(|tx| tx.send(command).ok())
+            .ok_or_else(|| "Keine aktive Debug-Sitzung".to_string())
+    }
+
     pub fn continue_execution(&mut self) {
-        self.is_paused = false;
+        let _ = self.send(Command::Continue);
     }
 
     pub fn pause(&mut self) {
-        self.is_paused = true;
+        self.pause_requested.store(true, Ordering::SeqCst);
     }
 
-    pub fn step_over(&mut self) -> Result<(), String> {
-        // In production, execute one line and pause
-        self.current_line = self.current_line.map(|l| l + 1);
-        Ok(())
+    pub fn step_over(&mut self) {
+        let _ = self.send(Command::Next);
     }
 
-    pub fn step_into(&mut self) -> Result<(), String> {
-        // In production, step into function call
-        self.current_line = self.current_line.map(|l| l + 1);
-        Ok(())
+    pub fn step_into(&mut self) {
+        let _ = self.send(Command::StepIn);
     }
 
-    pub fn step_out(&mut self) -> Result<(), String> {
-        // In production, step out of current function
-        Ok(())
+    pub fn step_out(&mut self) {
+        let _ = self.send(Command::StepOut);
     }
 
-    pub fn is_running(&self) -> bool {
-        self.is_running
+    /// Wertet `expression` im angehaltenen Frame `frame_id` aus
+    pub fn evaluate(
+        &mut self,
+        expression: String,
+        frame_id: usize,
+    ) -> Result<oneshot::Receiver<EvaluateResult>, String> {
+        let (reply, rx) = oneshot::channel();
+        self.send(Command::Evaluate {
+            expression,
+            frame_id,
+            reply,
+        })?;
+        Ok(rx)
     }
 
-    pub fn is_paused(&self) -> bool {
-        self.is_paused
+    /// Beendet die Sitzung; ein angehaltener Interpreter bricht sofort ab
+    pub fn disconnect(&mut self) {
+        if let Some(tx) = self.commands.take() {
+            // Ein laufendes Programm hält an der nächsten Zeile an und liest dann den Befehl
+            self.pause_requested.store(true, Ordering::SeqCst);
+            let _ = tx.send(Command::Disconnect);
+        }
+        self.interpreter = None;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.is_running.load(Ordering::SeqCst)
     }
 
-    pub fn current_line(&self) -> Option<usize> {
-        self.current_line
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
     }
 }
 
@@ -69,3 +203,407 @@ impl Default for Debugger {
         Self::new()
     }
 }
+
+/// Parst das Programm samt Modulen und fügt Statement-Probes für die Zeileninformation ein
+fn load_program(path: &Path) -> Result<Interpreter, String> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file {}: {}", path.display(), e))?;
+    let root_file = path.to_string_lossy().to_string();
+
+    let mut compiler = VelinCompiler::new(CompilerConfig::default());
+    compiler.add_pass(Box::new(ParserPass::new()));
+    compiler.add_pass(Box::new(CoverageInstrumentationPass::new()));
+    let context = compiler
+        .compile(root_file.clone(), source)
+        .map_err(|e| e.to_string())?;
+    if context.has_errors() {
+        let messages: Vec<String> = context.errors.iter().map(|e| e.to_string()).collect();
+        return Err(format!(
+            "Kompilierungsfehler in {}:\n{}",
+            path.display(),
+            messages.join("\n")
+        ));
+    }
+    let program = context
+        .program
+        .ok_or_else(|| format!("Kein Programm in {}", path.display()))?;
+    Ok(Interpreter::new(program, &context.coverage_maps, root_file))
+}
+
+pub fn event(name: &str, body: serde_json::Value) -> serde_json::Value {
+    json!({ "type": "event", "event": name, "body": body })
+}
+
+/// Hook im Interpreter-Thread: entscheidet über Haltepunkte und bedient Befehle
+struct SessionHook {
+    state: SessionState,
+    commands: mpsc::Receiver<Command>,
+    mode: StepMode,
+    /// Erster Halt bei `stopOnEntry` wird als "entry" gemeldet
+    entry: bool,
+    is_paused: Arc<AtomicBool>,
+    pause_requested: Arc<AtomicBool>,
+    canonical: HashMap<String, PathBuf>,
+}
+
+impl SessionHook {
+    fn canonical_path(&mut self, file: &str) -> PathBuf {
+        self.canonical
+            .entry(file.to_string())
+            .or_insert_with(|| BreakpointManager::normalize(Path::new(file)))
+            .clone()
+    }
+
+    /// Trifft ein Breakpoint dieser Zeile (unter Berücksichtigung der Bedingung)?
+    fn hits_breakpoint(&mut self, interpreter: &mut Interpreter, file: &str, line: usize) -> bool {
+        let path = self.canonical_path(file);
+        let candidates: Vec<(usize, Option<String>)> = {
+            let manager = self.state.breakpoints.lock().unwrap();
+            manager
+                .get_breakpoints_at_line(&path, line)
+                .into_iter()
+                .map(|bp| (bp.id, bp.condition.clone()))
+                .collect()
+        };
+
+        let mut hit = false;
+        for (id, condition) in candidates {
+            let matches = match condition {
+                Some(condition) => match interpreter.evaluate(&condition, 0) {
+                    Ok(value) => value.is_truthy(),
+                    Err(message) => {
+                        self.output(
+                            "stderr",
+                            &format!("Breakpoint-Bedingung '{}': {}\n", condition, message),
+                        );
+                        // Fehlerhafte Bedingungen halten an, damit sie auffallen
+                        true
+                    }
+                },
+                None => true,
+            };
+            if matches {
+                self.state.breakpoints.lock().unwrap().record_hit(id);
+                hit = true;
+            }
+        }
+        hit
+    }
+
+    fn stop_reason(
+        &mut self,
+        interpreter: &mut Interpreter,
+        file: &str,
+        line: usize,
+    ) -> Option<&'static str> {
+        let depth = interpreter.frames().len();
+        if self.pause_requested.swap(false, Ordering::SeqCst) {
+            return Some("pause");
+        }
+        let stepped = match self.mode {
+            StepMode::Run => false,
+            StepMode::StepIn => true,
+            StepMode::Over(target) => depth <= target,
+            StepMode::Out(target) => depth < target,
+        };
+        if stepped {
+            if std::mem::take(&mut self.entry) {
+                return Some("entry");
+            }
+            return Some("step");
+        }
+        if self.hits_breakpoint(interpreter, file, line) {
+            return Some("breakpoint");
+        }
+        None
+    }
+
+    /// Überträgt Call-Stack und Variablen in die Inspektoren des DAP Servers
+    fn publish_state(&self, interpreter: &Interpreter) {
+        let mut call_stack = self.state.call_stack.lock().unwrap();
+        let mut variables = self.state.variables.lock().unwrap();
+        call_stack.clear();
+        variables.clear();
+
+        let globals = variables.add_reference(interpreter.globals().to_vec());
+        // DAP-Frame 0 ist der innerste Frame
+        for (frame_id, frame) in interpreter.frames().iter().rev().enumerate() {
+            call_stack.push_frame(
+                frame.name.clone(),
+                PathBuf::from(&frame.file),
+                frame.line,
+                1,
+            );
+            let locals = variables.add_reference(frame.variables());
+            variables.set_scopes(
+                frame_id,
+                vec![
+                    ("Locals".to_string(), locals),
+                    ("Globals".to_string(), globals),
+                ],
+            );
+        }
+    }
+
+    fn output(&self, category: &str, text: &str) {
+        let _ = self.state.events.send(event(
+            "output",
+            json!({ "category": category, "output": text }),
+        ));
+    }
+
+    /// Blockiert den Interpreter, bis ein Fortsetzungsbefehl eintrifft
+    fn wait_for_command(&mut self, interpreter: &mut Interpreter) -> Result<(), Control> {
+        loop {
+            let command = match self.commands.recv() {
+                Ok(command) => command,
+                Err(_) => return Err(Control::Error("Debug-Sitzung beendet".to_string())),
+            };
+            let depth = interpreter.frames().len();
+            self.mode = match command {
+                Command::Continue => StepMode::Run,
+                Command::Next => StepMode::Over(depth),
+                Command::StepIn => StepMode::StepIn,
+                Command::StepOut => StepMode::Out(depth),
+                Command::Evaluate {
+                    expression,
+                    frame_id,
+                    reply,
+                } => {
+                    let result = interpreter.evaluate(&expression, frame_id).map(|value| {
+                        let reference = self.register_value(&value);
+                        (value.repr(), value.type_name(), reference)
+                    });
+                    let _ = reply.send(result);
+                    continue;
+                }
+                Command::Disconnect => {
+                    return Err(Control::Error("Debug-Sitzung beendet".to_string()))
+                }
+            };
+            return Ok(());
+        }
+    }
+
+    fn register_value(&self, value: &Value) -> usize {
+        let children = value.children();
+        if children.is_empty() {
+            0
+        } else {
+            self.state.variables.lock().unwrap().add_reference(children)
+        }
+    }
+}
+
+impl DebugHook for SessionHook {
+    fn on_line(
+        &mut self,
+        interpreter: &mut Interpreter,
+        file: &str,
+        line: usize,
+    ) -> Result<(), Control> {
+        let Some(reason) = self.stop_reason(interpreter, file, line) else {
+            return Ok(());
+        };
+        self.publish_state(interpreter);
+        self.is_paused.store(true, Ordering::SeqCst);
+        let _ = self.state.events.send(event(
+            "stopped",
+            json!({ "reason": reason, "threadId": 1, "allThreadsStopped": true }),
+        ));
+        let result = self.wait_for_command(interpreter);
+        self.is_paused.store(false, Ordering::SeqCst);
+        result.map_err(|control| Control::Error(describe(control)))
+    }
+
+    fn on_output(&mut self, text: &str) {
+        self.output("stdout", &format!("{}\n", text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    const PROGRAM: &str = "fn square(x: number): number {
+    let y = x * x;
+    return y;
+}
+
+fn main(): void {
+    let a = square(1);
+    let b = square(2);
+    if (a != b) {
+        let c = square(3);
+    }
+    let d = a + b;
+}
+";
+
+    struct Session {
+        debugger: Debugger,
+        state: SessionState,
+        events: UnboundedReceiver<serde_json::Value>,
+    }
+
+    impl Session {
+        fn start(name: &str, breakpoints: &[(usize, Option<&str>)], stop_on_entry: bool) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "velin-debugger-{}-{}.velin",
+                std::process::id(),
+                name
+            ));
+            std::fs::write(&path, PROGRAM).unwrap();
+
+            let (tx, events) = unbounded_channel();
+            let state = SessionState {
+                breakpoints: Arc::new(Mutex::new(BreakpointManager::new())),
+                variables: Arc::new(Mutex::new(VariableInspector::new())),
+                call_stack: Arc::new(Mutex::new(CallStack::new())),
+                events: tx,
+            };
+            {
+                let mut manager = state.breakpoints.lock().unwrap();
+                for (line, condition) in breakpoints {
+                    manager.add_breakpoint(
+                        BreakpointManager::normalize(&path),
+                        *line,
+                        condition.map(str::to_string),
+                    );
+                }
+            }
+
+            let mut debugger = Debugger::new();
+            debugger.launch(path, stop_on_entry).unwrap();
+            debugger.start(state.clone()).unwrap();
+            Session {
+                debugger,
+                state,
+                events,
+            }
+        }
+
+        /// Nächster Halt als (Grund, Zeile, Funktion); `None` nach Programmende
+        fn next_stop(&mut self) -> Option<(String, usize, String)> {
+            while let Some(message) = self.events.blocking_recv() {
+                match message["event"].as_str() {
+                    Some("stopped") => {
+                        let frames = self.state.call_stack.lock().unwrap().get_frames();
+                        return Some((
+                            message["body"]["reason"].as_str().unwrap().to_string(),
+                            frames[0]["line"].as_u64().unwrap() as usize,
+                            frames[0]["name"].as_str().unwrap().to_string(),
+                        ));
+                    }
+                    Some("terminated") => return None,
+                    _ => {}
+                }
+            }
+            None
+        }
+
+        /// Namen der lokalen Variablen im DAP-Frame `frame_id`
+        fn locals(&self, frame_id: usize) -> Vec<String> {
+            let mut variables = self.state.variables.lock().unwrap();
+            let scopes = variables.get_scopes(&json!({ "frameId": frame_id }));
+            let locals = scopes
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|scope| scope["name"] == "Locals")
+                .unwrap()["variablesReference"]
+                .clone();
+            variables
+                .get_variables(&json!({ "variablesReference": locals }))
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|variable| variable["name"].as_str().unwrap().to_string())
+                .collect()
+        }
+
+        fn evaluate(&mut self, expression: &str) -> String {
+            let reply = self.debugger.evaluate(expression.to_string(), 0).unwrap();
+            reply.blocking_recv().unwrap().unwrap().0
+        }
+    }
+
+    fn stop(reason: &str, line: usize, function: &str) -> Option<(String, usize, String)> {
+        Some((reason.to_string(), line, function.to_string()))
+    }
+
+    #[test]
+    fn test_step_in_over_and_out() {
+        let mut session = Session::start("stepping", &[], true);
+        assert_eq!(session.next_stop(), stop("entry", 7, "main"));
+
+        session.debugger.step_into();
+        assert_eq!(session.next_stop(), stop("step", 2, "square"));
+
+        session.debugger.step_out();
+        assert_eq!(session.next_stop(), stop("step", 8, "main"));
+
+        // `square(2)` wird übersprungen
+        session.debugger.step_over();
+        assert_eq!(session.next_stop(), stop("step", 9, "main"));
+
+        session.debugger.continue_execution();
+        assert_eq!(session.next_stop(), None);
+    }
+
+    #[test]
+    fn test_breakpoint_hits_and_hit_count() {
+        let mut session = Session::start("breakpoints", &[(2, None), (12, None)], false);
+        for _ in 0..3 {
+            assert_eq!(session.next_stop(), stop("breakpoint", 2, "square"));
+            session.debugger.continue_execution();
+        }
+        assert_eq!(session.next_stop(), stop("breakpoint", 12, "main"));
+        session.debugger.continue_execution();
+        assert_eq!(session.next_stop(), None);
+
+        let manager = session.state.breakpoints.lock().unwrap();
+        assert_eq!(manager.get_breakpoint(1).unwrap().hit_count, 3);
+        assert_eq!(manager.get_breakpoint(2).unwrap().hit_count, 1);
+    }
+
+    #[test]
+    fn test_conditional_breakpoint() {
+        let mut session = Session::start("condition", &[(3, Some("x == 2"))], false);
+        assert_eq!(session.next_stop(), stop("breakpoint", 3, "square"));
+        assert_eq!(session.evaluate("x"), "2");
+        assert_eq!(session.evaluate("y"), "4");
+        session.debugger.continue_execution();
+        assert_eq!(session.next_stop(), None);
+        assert_eq!(
+            session
+                .state
+                .breakpoints
+                .lock()
+                .unwrap()
+                .get_breakpoint(1)
+                .unwrap()
+                .hit_count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_variable_scopes() {
+        let mut session = Session::start("scopes", &[(3, Some("x == 3")), (12, None)], false);
+
+        // Innerster Frame sieht nur die Parameter und Locals von `square`
+        assert_eq!(session.next_stop(), stop("breakpoint", 3, "square"));
+        assert_eq!(session.locals(0), vec!["x", "y"]);
+        // Im Aufrufer ist die Block-Variable `c` noch nicht deklariert
+        assert_eq!(session.locals(1), vec!["a", "b"]);
+        session.debugger.continue_execution();
+
+        // Nach dem `if`-Block ist `c` nicht mehr sichtbar
+        assert_eq!(session.next_stop(), stop("breakpoint", 12, "main"));
+        assert_eq!(session.locals(0), vec!["a", "b"]);
+        session.debugger.continue_execution();
+        assert_eq!(session.next_stop(), None);
+    }
+}
@@ -1,13 +1,13 @@
 // VelinScript Debugger - DAP Server Implementation
 
+mod breakpoints;
 mod dap_server;
 mod debugger;
-mod breakpoints;
-mod variables;
 mod stack;
+mod variables;
 
-use clap::{Parser, Subcommand};
 use anyhow::Result;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 #[command(name = "velin-debugger")]
@@ -35,7 +35,7 @@ async fn main() -> Result<()> {
         Commands::Start { port } => {
             println!("🚀 VelinScript Debugger (DAP Server)");
             println!("📡 Listening on port {}", port);
-            
+
             let server = dap_server::DAPServer::new(port);
             server.run().await?;
         }
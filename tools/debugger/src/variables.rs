@@ -1,70 +1,104 @@
 // Variable Inspection
 
-use serde_json::{Value, Map};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use velin_compiler::interpreter::Value as VelinValue;
 
-#[derive(Debug, Clone)]
-pub struct Variable {
-    pub name: String,
-    pub value: String,
-    pub var_type: String,
-    pub children: Vec<Variable>,
-}
-
+/// Stand der Variablen beim letzten Halt. Referenzen sind nur bis zum
+/// nächsten Fortsetzen gültig (wie im DAP vorgesehen).
 pub struct VariableInspector {
-    variables: HashMap<usize, Variable>,
+    /// variablesReference -> benannte Werte (Scope oder Kindelemente)
+    references: HashMap<usize, Vec<(String, VelinValue)>>,
+    /// DAP-Frame-Id -> Scopes (Name, variablesReference)
+    scopes: HashMap<usize, Vec<(String, usize)>>,
     next_ref: usize,
 }
 
 impl VariableInspector {
     pub fn new() -> Self {
         VariableInspector {
-            variables: HashMap::new(),
+            references: HashMap::new(),
+            scopes: HashMap::new(),
             next_ref: 1,
         }
     }
 
-    pub fn get_variables(&self, args: &Value) -> Value {
-        let mut vars = Vec::new();
-        
-        // Use args to determine which variables to return
-        let _variables_ref = args.get("variablesReference").and_then(|v| v.as_u64()).unwrap_or(0);
-
-        // In production, get actual variables from current scope
-        // For now, return mock variables
-        let mut var1 = Map::new();
-        var1.insert("name".to_string(), Value::String("x".to_string()));
-        var1.insert("value".to_string(), Value::String("42".to_string()));
-        var1.insert("type".to_string(), Value::String("number".to_string()));
-        var1.insert("variablesReference".to_string(), Value::Number(0.into()));
-        vars.push(Value::Object(var1));
-
-        let mut var2 = Map::new();
-        var2.insert("name".to_string(), Value::String("name".to_string()));
-        var2.insert("value".to_string(), Value::String("\"John\"".to_string()));
-        var2.insert("type".to_string(), Value::String("string".to_string()));
-        var2.insert("variablesReference".to_string(), Value::Number(0.into()));
-        vars.push(Value::Object(var2));
-
-        Value::Array(vars)
+    pub fn clear(&mut self) {
+        self.references.clear();
+        self.scopes.clear();
+        self.next_ref = 1;
     }
 
-    pub fn add_variable(&mut self, name: String, value: String, var_type: String) -> usize {
+    pub fn add_reference(&mut self, entries: Vec<(String, VelinValue)>) -> usize {
         let ref_id = self.next_ref;
         self.next_ref += 1;
-        
-        self.variables.insert(ref_id, Variable {
-            name,
-            value,
-            var_type,
-            children: Vec::new(),
-        });
-        
+        self.references.insert(ref_id, entries);
         ref_id
     }
 
-    pub fn get_variable(&self, ref_id: usize) -> Option<&Variable> {
-        self.variables.get(&ref_id)
+    pub fn set_scopes(&mut self, frame_id: usize, scopes: Vec<(String, usize)>) {
+        self.scopes.insert(frame_id, scopes);
+    }
+
+    pub fn get_scopes(&self, args: &Value) -> Value {
+        let frame_id = args.get("frameId").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+        let scopes = self
+            .scopes
+            .get(&frame_id)
+            .map(|scopes| {
+                scopes
+                    .iter()
+                    .map(|(name, reference)| {
+                        let mut scope = Map::new();
+                        scope.insert("name".to_string(), Value::String(name.clone()));
+                        scope.insert(
+                            "variablesReference".to_string(),
+                            Value::Number((*reference).into()),
+                        );
+                        scope.insert("expensive".to_string(), Value::Bool(false));
+                        Value::Object(scope)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Value::Array(scopes)
+    }
+
+    /// Liefert die Einträge einer Referenz; Structs, Listen und Maps erhalten
+    /// beim Aufklappen eine eigene Referenz
+    pub fn get_variables(&mut self, args: &Value) -> Value {
+        let variables_ref = args
+            .get("variablesReference")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0) as usize;
+        let entries = self
+            .references
+            .get(&variables_ref)
+            .cloned()
+            .unwrap_or_default();
+
+        let vars = entries
+            .into_iter()
+            .map(|(name, value)| {
+                let children = value.children();
+                let reference = if children.is_empty() {
+                    0
+                } else {
+                    self.add_reference(children)
+                };
+                let mut var = Map::new();
+                var.insert("name".to_string(), Value::String(name));
+                var.insert("value".to_string(), Value::String(value.repr()));
+                var.insert("type".to_string(), Value::String(value.type_name()));
+                var.insert(
+                    "variablesReference".to_string(),
+                    Value::Number(reference.into()),
+                );
+                Value::Object(var)
+            })
+            .collect();
+
+        Value::Array(vars)
     }
 }
 
@@ -0,0 +1,463 @@
+// Development Server
+// Führt Route-Handler im AST-Interpreter aus, damit sie ohne Neustart ausgetauscht werden können
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use velin_compiler::interpreter::{Interpreter, Value};
+use velin_compiler::parser::ast::{DecoratorArg, Function, Item, Parameter, Program, Type};
+
+/// Maximale Größe eines Request-Bodys
+const MAX_BODY: usize = 10 * 1024 * 1024;
+
+/// Ein `@GET/@POST/...`-Handler
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub method: String,
+    pub path: String,
+    /// Qualifizierter Funktionsname im Interpreter (`handlers.listUsers`)
+    pub function: String,
+    params: Vec<Parameter>,
+}
+
+struct DevState {
+    interpreter: Interpreter,
+    routes: Vec<Route>,
+}
+
+/// Dev-Server, dessen Handler im laufenden Prozess ausgetauscht werden.
+/// Globale Variablen (Caches, Sessions, ...) überleben einen Austausch.
+pub struct DevServer {
+    entry: String,
+    state: Arc<Mutex<DevState>>,
+}
+
+impl DevServer {
+    pub fn new(program: Program, entry: &str) -> Result<Self> {
+        let state = DevState::load(program, entry)?;
+        Ok(Self {
+            entry: entry.to_string(),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    pub fn routes(&self) -> Vec<Route> {
+        self.lock().routes.clone()
+    }
+
+    /// Tauscht die Funktionen der Module aus; liefert neu angelegte globale Variablen
+    pub fn swap(&self, program: &Program, modules: Vec<(String, Vec<Item>)>) -> Result<Vec<String>> {
+        let mut state = self.lock();
+        for (module, items) in modules {
+            state.interpreter.replace_module(&module, items);
+        }
+        state.routes = collect_routes(program);
+        state.interpreter.init_new_globals().map_err(|e| anyhow!(e))
+    }
+
+    /// Neustart mit frischem Interpreter; der gesamte In-Memory-State geht verloren
+    pub fn restart(&self, program: Program) -> Result<()> {
+        let fresh = DevState::load(program, &self.entry)?;
+        *self.lock() = fresh;
+        Ok(())
+    }
+
+    /// Startet den HTTP-Listener in einem eigenen Thread
+    pub fn spawn(&self, port: u16) -> Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let state = Arc::clone(&self.state);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = Arc::clone(&state);
+                std::thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, &state) {
+                        eprintln!("⚠️  Request-Fehler: {}", e);
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, DevState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl DevState {
+    fn load(program: Program, entry: &str) -> Result<Self> {
+        let routes = collect_routes(&program);
+        let mut interpreter = Interpreter::new(program, &[], entry.to_string());
+        interpreter.init_globals().map_err(|e| anyhow!(e))?;
+        Ok(Self { interpreter, routes })
+    }
+}
+
+fn collect_routes(program: &Program) -> Vec<Route> {
+    let mut routes = Vec::new();
+    for item in &program.items {
+        match item {
+            Item::Function(function) => push_route(&mut routes, function, None),
+            Item::Module(module) => {
+                for item in &module.items {
+                    if let Item::Function(function) = item {
+                        push_route(&mut routes, function, Some(&module.name));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    routes
+}
+
+fn push_route(routes: &mut Vec<Route>, function: &Function, module: Option<&str>) {
+    for decorator in &function.decorators {
+        let method = decorator.name.as_str();
+        if !matches!(method, "GET" | "POST" | "PUT" | "DELETE" | "PATCH") {
+            continue;
+        }
+        if let Some(DecoratorArg::String(path)) = decorator.args.first() {
+            routes.push(Route {
+                method: method.to_string(),
+                path: path.clone(),
+                function: match module {
+                    Some(module) => format!("{}.{}", module, function.name),
+                    None => function.name.clone(),
+                },
+                params: function.params.clone(),
+            });
+        }
+    }
+}
+
+/// Pfad-Parameter (`/users/:id`) eines passenden Routen-Musters
+fn match_path(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if pattern.len() != path.len() {
+        return None;
+    }
+    let mut captures = HashMap::new();
+    for (expected, actual) in pattern.iter().zip(path) {
+        if let Some(name) = expected.strip_prefix(':') {
+            captures.insert(name.to_string(), actual.to_string());
+        } else if let Some(name) = expected.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            captures.insert(name.to_string(), actual.to_string());
+        } else if *expected != actual {
+            return None;
+        }
+    }
+    Some(captures)
+}
+
+fn text_argument(text: &str, param_type: &Type) -> Value {
+    match param_type {
+        Type::Number => text.parse().map(Value::Number).unwrap_or(Value::Null),
+        Type::Boolean => Value::Bool(text == "true"),
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// Argumente in Parameter-Reihenfolge: Pfad-Parameter, Query-Parameter, sonst der JSON-Body
+fn build_arguments(
+    params: &[Parameter],
+    captures: &HashMap<String, String>,
+    query: &HashMap<String, String>,
+    body: Option<serde_json::Value>,
+) -> Vec<Value> {
+    let mut body = body;
+    params
+        .iter()
+        .map(|param| {
+            if let Some(text) = captures.get(&param.name).or_else(|| query.get(&param.name)) {
+                return text_argument(text, &param.param_type);
+            }
+            match body.take() {
+                Some(json) => {
+                    let value = Value::from_json(&json);
+                    match &param.param_type {
+                        Type::Named(name) => value.into_struct(name),
+                        _ => value,
+                    }
+                }
+                None => Value::Null,
+            }
+        })
+        .collect()
+}
+
+fn handle_connection(mut stream: TcpStream, state: &Mutex<DevState>) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if content_length > MAX_BODY {
+        return respond(&mut stream, "413 Payload Too Large", &error_body("Body zu groß"));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("/").to_string();
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target.clone(), HashMap::new()),
+    };
+
+    let body = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(json) => Some(json),
+            Err(e) => {
+                return respond(
+                    &mut stream,
+                    "400 Bad Request",
+                    &error_body(&format!("Ungültiges JSON: {}", e)),
+                )
+            }
+        }
+    };
+
+    // Der Lock über den gesamten Aufruf serialisiert Requests und Reloads
+    let result = {
+        let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+        let route = state.routes.iter().find_map(|route| {
+            if route.method != method {
+                return None;
+            }
+            match_path(&route.path, &path).map(|captures| (route.clone(), captures))
+        });
+        match route {
+            Some((route, captures)) => {
+                let args = build_arguments(&route.params, &captures, &query, body);
+                Some(state.interpreter.call(&route.function, args))
+            }
+            None => None,
+        }
+    };
+
+    match result {
+        Some(Ok(value)) => respond(&mut stream, "200 OK", &value.to_json().to_string()),
+        Some(Err(message)) => respond(&mut stream, "500 Internal Server Error", &error_body(&message)),
+        None => respond(
+            &mut stream,
+            "404 Not Found",
+            &error_body(&format!("Keine Route für {} {}", method, path)),
+        ),
+    }
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.replace('+', " ")))
+        .collect()
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module_reload::{ModuleSession, ReloadOutcome};
+    use std::path::PathBuf;
+
+    const MAIN: &str = "use handlers;
+
+let hits: number = 0;
+
+@GET(\"/hit\")
+fn hit(): number {
+    hits = hits + 1;
+    return handlers.scale(hits);
+}
+";
+
+    const HANDLERS: &str = "struct Item {
+    name: string,
+}
+
+fn scale(value: number): number {
+    return value;
+}
+";
+
+    /// Projekt mit `main.velin` und `handlers.velin` in einem eigenen Temp-Verzeichnis
+    fn project(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("velin-hot-reload-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.velin"), MAIN).unwrap();
+        std::fs::write(dir.join("handlers.velin"), HANDLERS).unwrap();
+        dir
+    }
+
+    fn call(server: &DevServer, function: &str) -> String {
+        server
+            .lock()
+            .interpreter
+            .call(function, Vec::new())
+            .unwrap()
+            .repr()
+    }
+
+    #[test]
+    fn test_swap_keeps_global_state() {
+        let dir = project("swap");
+        let (mut session, program) = ModuleSession::compile(&dir.join("main.velin")).unwrap();
+        let server = DevServer::new(program, session.entry()).unwrap();
+        assert_eq!(server.routes()[0].function, "hit");
+        assert_eq!(call(&server, "hit"), "1");
+        assert_eq!(call(&server, "hit"), "2");
+
+        std::fs::write(
+            dir.join("handlers.velin"),
+            HANDLERS.replace("return value;", "return value * 10;"),
+        )
+        .unwrap();
+        let ReloadOutcome::Swapped { program, modules } =
+            session.reload(&[dir.join("handlers.velin")]).unwrap()
+        else {
+            panic!("Austausch erwartet");
+        };
+        // `main.velin` importiert `handlers` und wird mit ausgetauscht
+        let names: Vec<&str> = modules.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["handlers", ""]);
+        assert!(server.swap(&program, modules).unwrap().is_empty());
+        // Der Zähler läuft weiter, nur `scale` ist neu
+        assert_eq!(call(&server, "hit"), "30");
+    }
+
+    #[test]
+    fn test_new_global_is_initialized_on_swap() {
+        let dir = project("globals");
+        let (mut session, program) = ModuleSession::compile(&dir.join("main.velin")).unwrap();
+        let server = DevServer::new(program, session.entry()).unwrap();
+        assert_eq!(call(&server, "hit"), "1");
+
+        std::fs::write(
+            dir.join("main.velin"),
+            format!(
+                "{}\nlet greeting = \"hallo\";\n\n@GET(\"/greet\")\nfn greet(): string {{\n    return greeting;\n}}\n",
+                MAIN
+            ),
+        )
+        .unwrap();
+        let ReloadOutcome::Swapped { program, modules } =
+            session.reload(&[dir.join("main.velin")]).unwrap()
+        else {
+            panic!("Austausch erwartet");
+        };
+        assert_eq!(server.swap(&program, modules).unwrap(), vec!["greeting"]);
+        assert_eq!(server.routes().len(), 2);
+        assert_eq!(call(&server, "greet"), "\"hallo\"");
+        assert_eq!(call(&server, "hit"), "2");
+    }
+
+    #[test]
+    fn test_layout_change_requires_restart() {
+        let dir = project("layout");
+        let (mut session, _) = ModuleSession::compile(&dir.join("main.velin")).unwrap();
+
+        std::fs::write(
+            dir.join("handlers.velin"),
+            HANDLERS.replace("name: string,", "name: string,\n    price: number,"),
+        )
+        .unwrap();
+        match session.reload(&[dir.join("handlers.velin")]).unwrap() {
+            ReloadOutcome::Restart { reason, .. } => {
+                assert_eq!(reason, "Layout geändert: handlers.Item")
+            }
+            _ => panic!("Neustart erwartet"),
+        }
+    }
+
+    #[test]
+    fn test_errors_keep_previous_state() {
+        let dir = project("errors");
+        let (mut session, program) = ModuleSession::compile(&dir.join("main.velin")).unwrap();
+        let server = DevServer::new(program, session.entry()).unwrap();
+
+        std::fs::write(
+            dir.join("handlers.velin"),
+            "fn scale(value: number): number {\n",
+        )
+        .unwrap();
+        assert!(session.reload(&[dir.join("handlers.velin")]).is_err());
+        assert_eq!(call(&server, "hit"), "1");
+
+        // Nach der Korrektur wird wieder ausgetauscht
+        std::fs::write(dir.join("handlers.velin"), HANDLERS).unwrap();
+        assert!(matches!(
+            session.reload(&[dir.join("handlers.velin")]).unwrap(),
+            ReloadOutcome::Swapped { .. }
+        ));
+
+        // Dateien außerhalb des Programms lösen nichts aus
+        std::fs::write(dir.join("notes.velin"), "fn unused() {}\n").unwrap();
+        assert!(matches!(
+            session.reload(&[dir.join("notes.velin")]).unwrap(),
+            ReloadOutcome::Unchanged
+        ));
+    }
+
+    #[test]
+    fn test_match_path_and_arguments() {
+        let captures = match_path("/users/:id/orders/{order}", "/users/7/orders/a1").unwrap();
+        assert_eq!(captures["id"], "7");
+        assert_eq!(captures["order"], "a1");
+        assert!(match_path("/users/:id", "/users/7/orders").is_none());
+        assert!(match_path("/users/:id", "/teams/7").is_none());
+
+        let program = velin_compiler::parser::parser::Parser::parse(
+            "@POST(\"/users/:id\")\nfn update(id: number, verbose: boolean, payload: string): string {\n    return payload;\n}\n",
+        )
+        .unwrap();
+        let routes = collect_routes(&program);
+        let query = parse_query("verbose=true&name=a+b");
+        assert_eq!(query["name"], "a b");
+        let args = build_arguments(
+            &routes[0].params,
+            &captures,
+            &query,
+            Some(serde_json::json!("body")),
+        );
+        assert_eq!(
+            args.iter().map(Value::repr).collect::<Vec<_>>(),
+            vec!["7", "true", "\"body\""]
+        );
+    }
+}
@@ -3,12 +3,16 @@
 
 mod watcher;
 mod reloader;
+mod module_reload;
+mod dev_server;
 
 use clap::{Parser, ArgGroup};
 use anyhow::Result;
 use std::path::PathBuf;
 use watcher::FileWatcher;
 use reloader::Reloader;
+use module_reload::{ModuleSession, ReloadOutcome};
+use dev_server::DevServer;
 
 #[derive(Parser)]
 #[command(name = "velin-hot-reload")]
@@ -16,7 +20,7 @@ use reloader::Reloader;
 #[command(group(
     ArgGroup::new("mode")
         .required(true)
-        .args(&["watch", "server"])
+        .args(&["watch", "server", "dev"])
 ))]
 struct Cli {
     /// Überwacht Dateien und kompiliert bei Änderungen
@@ -27,6 +31,14 @@ struct Cli {
     #[arg(short, long)]
     server: bool,
     
+    /// Führt die Routen im Interpreter aus und tauscht geänderte Module ohne Neustart aus
+    #[arg(long, requires = "entry")]
+    dev: bool,
+    
+    /// Einstiegsdatei für den Dev-Modus
+    #[arg(long)]
+    entry: Option<PathBuf>,
+    
     /// Verzeichnis zu überwachen
     #[arg(short, long, default_value = ".")]
     directory: PathBuf,
@@ -71,6 +83,55 @@ async fn main() -> Result<()> {
                 }
             }
         }
+    } else if cli.dev {
+        // Dev Mode: Austausch geänderter Module im laufenden Server
+        let entry = cli.entry.expect("--entry wird von clap erzwungen");
+        let (mut session, program) = ModuleSession::compile(&entry)?;
+        let server = DevServer::new(program, session.entry())?;
+        server.spawn(cli.port)?;
+        let watcher = FileWatcher::new(&cli.directory)?;
+        
+        println!("🚀 Development Server auf http://127.0.0.1:{}", cli.port);
+        for route in server.routes() {
+            println!("  {} {} → {}", route.method, route.path, route.function);
+        }
+        println!("🔍 Überwache Verzeichnis: {}\n", cli.directory.display());
+        
+        loop {
+            if let Some(changed_files) = watcher.wait_for_changes().await? {
+                let changed_files: Vec<PathBuf> = changed_files
+                    .iter()
+                    .filter_map(|file| file.canonicalize().ok())
+                    .collect();
+                
+                match session.reload(&changed_files) {
+                    Ok(ReloadOutcome::Swapped { program, modules }) => {
+                        let names: Vec<String> = modules
+                            .iter()
+                            .map(|(name, _)| if name.is_empty() { "<main>".to_string() } else { name.clone() })
+                            .collect();
+                        match server.swap(&program, modules) {
+                            Ok(globals) => {
+                                println!("♻️  Ausgetauscht: {}", names.join(", "));
+                                if !globals.is_empty() {
+                                    println!("  Neue globale Variablen: {}", globals.join(", "));
+                                }
+                            }
+                            Err(e) => eprintln!("❌ Initialisierung fehlgeschlagen: {}", e),
+                        }
+                    }
+                    Ok(ReloadOutcome::Restart { program, reason }) => {
+                        println!("🔄 {} – Neustart, State wird verworfen", reason);
+                        match server.restart(program) {
+                            Ok(()) => println!("✓ Server neu gestartet\n"),
+                            Err(e) => eprintln!("❌ Neustart fehlgeschlagen: {}", e),
+                        }
+                    }
+                    Ok(ReloadOutcome::Unchanged) => {}
+                    Err(e) => eprintln!("❌ Kompilierungsfehler, alter Stand bleibt aktiv:\n{}", e),
+                }
+            }
+        }
     } else if cli.server {
         // Server Mode: Startet Server mit Hot Reload
         let watcher = FileWatcher::new(&cli.directory)?;
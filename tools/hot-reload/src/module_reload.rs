@@ -0,0 +1,259 @@
+// Module Reload
+// Kompiliert nur geänderte Module neu und entscheidet zwischen Hot-Swap und Neustart
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use velin_compiler::compiler::context::CompilationContext;
+use velin_compiler::compiler::orchestrator::BuildOrchestrator;
+use velin_compiler::compiler::pass::Pass;
+use velin_compiler::parser::ast::{Item, Module, Program, Visibility};
+use velin_compiler::parser::parser::Parser;
+use velin_compiler::passes::{parser::ParserPass, type_check::TypeCheckPass};
+
+/// Ergebnis eines Reloads
+pub enum ReloadOutcome {
+    /// Funktionen der Module wurden im laufenden Server ausgetauscht
+    Swapped {
+        program: Program,
+        /// Top-Level-Module (`""` = Einstiegsdatei) mit ihren neuen Items
+        modules: Vec<(String, Vec<Item>)>,
+    },
+    /// Struct- oder Enum-Layout geändert: gespeicherte Werte passen nicht mehr
+    Restart { program: Program, reason: String },
+    /// Die Änderung betrifft keine Datei des Programms
+    Unchanged,
+}
+
+/// Letzter erfolgreich kompilierter Stand eines Dev-Server-Programms
+pub struct ModuleSession {
+    entry: String,
+    context: CompilationContext,
+}
+
+impl ModuleSession {
+    /// Vollständige Erstkompilierung (Parser mit Modulauflösung + Type Checking)
+    pub fn compile(entry: &Path) -> Result<(Self, Program)> {
+        let entry = entry
+            .canonicalize()
+            .map_err(|e| anyhow!("{}: {}", entry.display(), e))?
+            .to_string_lossy()
+            .to_string();
+        let source = fs::read_to_string(&entry)?;
+        let mut context = CompilationContext::new(entry.clone(), source);
+        ParserPass::new().run(&mut context)?;
+        type_check(&mut context)?;
+
+        let program = context
+            .program
+            .clone()
+            .ok_or_else(|| anyhow!("Kein Programm nach dem Parsen"))?;
+        Ok((Self { entry, context }, program))
+    }
+
+    pub fn entry(&self) -> &str {
+        &self.entry
+    }
+
+    /// Kompiliert die geänderten Dateien und alle Dateien, die sie importieren
+    /// (laut `BuildOrchestrator`-Graph), und tauscht deren Items im Programm aus.
+    /// Bei Fehlern bleibt der alte Stand aktiv.
+    pub fn reload(&mut self, changed: &[PathBuf]) -> Result<ReloadOutcome> {
+        let affected = BuildOrchestrator::new().affected_files(&self.context, changed)?;
+        if affected.is_empty() {
+            return Ok(ReloadOutcome::Unchanged);
+        }
+
+        let old_program = self
+            .context
+            .program
+            .clone()
+            .ok_or_else(|| anyhow!("Kein Programm geladen"))?;
+
+        let mut sources = self.context.source_map.clone();
+        let mut parsed = Vec::new();
+        for file in &affected {
+            let source = fs::read_to_string(file)?;
+            let program = Parser::parse(&source).map_err(|e| {
+                anyhow!(
+                    "{}:{}:{}: {}",
+                    file,
+                    e.line,
+                    e.column,
+                    e.message
+                )
+            })?;
+            sources.insert(file.clone(), source);
+            parsed.push((file.clone(), program));
+        }
+
+        // Neue oder entfernte Imports brauchen die Modulauflösung des ParserPass
+        let imports_changed = parsed.iter().any(|(file, program)| {
+            let old_source = self.context.source_map.get(file).cloned().unwrap_or_default();
+            let old_imports = Parser::parse(&old_source)
+                .map(|old| imports(&old.items))
+                .unwrap_or_default();
+            imports(&program.items) != old_imports
+        });
+
+        let mut context = CompilationContext::new(self.entry.clone(), String::new());
+        context.source_map = sources;
+        let mut modules = Vec::new();
+        if imports_changed {
+            ParserPass::new().run(&mut context)?;
+            // Alle Module austauschen, da sich die Modulmenge geändert haben kann
+            if let Some(program) = &context.program {
+                modules = split_modules(program);
+            }
+        } else {
+            let mut program = old_program.clone();
+            for (file, module_program) in parsed {
+                let name = self.module_name(&file);
+                replace_items(&mut program, &name, module_program.items.clone());
+                modules.push((name, module_program.items));
+            }
+            context.program = Some(program);
+        }
+        type_check(&mut context)?;
+
+        let program = context
+            .program
+            .clone()
+            .ok_or_else(|| anyhow!("Kein Programm nach dem Parsen"))?;
+        let changed_layouts = layout_changes(&old_program, &program);
+        self.context = context;
+
+        if changed_layouts.is_empty() {
+            Ok(ReloadOutcome::Swapped { program, modules })
+        } else {
+            Ok(ReloadOutcome::Restart {
+                program,
+                reason: format!("Layout geändert: {}", changed_layouts.join(", ")),
+            })
+        }
+    }
+
+    /// `""` für die Einstiegsdatei, sonst der Modulname (Dateiname ohne `.velin`)
+    fn module_name(&self, file: &str) -> String {
+        if file == self.entry {
+            String::new()
+        } else {
+            Path::new(file)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .to_string()
+        }
+    }
+}
+
+fn type_check(context: &mut CompilationContext) -> Result<()> {
+    TypeCheckPass::new(true).run(context)?;
+    if context.has_errors() {
+        let messages: Vec<String> = context.errors.iter().map(|e| e.to_string()).collect();
+        return Err(anyhow!(messages.join("\n")));
+    }
+    Ok(())
+}
+
+fn imports(items: &[Item]) -> Vec<String> {
+    let mut imports: Vec<String> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Use(use_stmt) => use_stmt.path.first().cloned(),
+            _ => None,
+        })
+        .collect();
+    imports.sort();
+    imports
+}
+
+/// Zerlegt ein aufgelöstes Programm in Einstiegsdatei (`""`) und Module
+fn split_modules(program: &Program) -> Vec<(String, Vec<Item>)> {
+    let mut root = Vec::new();
+    let mut modules = Vec::new();
+    for item in &program.items {
+        match item {
+            Item::Module(module) => modules.push((module.name.clone(), module.items.clone())),
+            other => root.push(other.clone()),
+        }
+    }
+    modules.insert(0, (String::new(), root));
+    modules
+}
+
+/// Ersetzt die Items der Einstiegsdatei bzw. eines Moduls im aufgelösten Programm
+fn replace_items(program: &mut Program, module: &str, items: Vec<Item>) {
+    if module.is_empty() {
+        program.items.retain(|item| matches!(item, Item::Module(_)));
+        let modules = std::mem::take(&mut program.items);
+        program.items = items;
+        program.items.extend(modules);
+        return;
+    }
+    let existing = program
+        .items
+        .iter_mut()
+        .find(|item| matches!(item, Item::Module(m) if m.name == module));
+    // Package-Module behalten ihre Herkunft (und damit die `pub`-Prüfung)
+    let package = match &existing {
+        Some(Item::Module(m)) => m.package.clone(),
+        _ => None,
+    };
+    let replacement = Item::Module(Module {
+        name: module.to_string(),
+        items,
+        visibility: Visibility::Public,
+        documentation: None,
+        package,
+    });
+    match existing {
+        Some(item) => *item = replacement,
+        None => program.items.push(replacement),
+    }
+}
+
+/// Felder aller Structs (`Modul.Name` → Feldname/Typ) und Varianten aller Enums
+fn layouts(items: &[Item], prefix: &str, out: &mut HashMap<String, Vec<String>>) {
+    for item in items {
+        match item {
+            Item::Struct(def) => {
+                let fields = def
+                    .fields
+                    .iter()
+                    .map(|f| format!("{}: {:?}", f.name, f.field_type))
+                    .collect();
+                out.insert(format!("{}{}", prefix, def.name), fields);
+            }
+            Item::Enum(def) => {
+                let variants = def
+                    .variants
+                    .iter()
+                    .map(|v| format!("{:?}", v))
+                    .collect();
+                out.insert(format!("{}{}", prefix, def.name), variants);
+            }
+            Item::Module(module) => {
+                layouts(&module.items, &format!("{}{}.", prefix, module.name), out)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Geänderte oder entfernte Typen; neue Typen erzwingen keinen Neustart
+fn layout_changes(old: &Program, new: &Program) -> Vec<String> {
+    let mut old_layouts = HashMap::new();
+    let mut new_layouts = HashMap::new();
+    layouts(&old.items, "", &mut old_layouts);
+    layouts(&new.items, "", &mut new_layouts);
+
+    let mut changed: Vec<String> = old_layouts
+        .iter()
+        .filter(|(name, layout)| new_layouts.get(*name) != Some(*layout))
+        .map(|(name, _)| name.clone())
+        .collect();
+    changed.sort();
+    changed
+}
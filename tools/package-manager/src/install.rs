@@ -1,12 +1,14 @@
 // Package Installation - Installiert Packages in vendor/ Verzeichnis
 
 use anyhow::Result;
-use git2::Repository;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use crate::registry::Registry;
-use crate::resolver::VelinToml;
-use crate::lockfile::LockFile;
+use crate::resolver::{pinned_dependencies, resolve_dependencies, PinnedLocation, VelinToml};
+use crate::lockfile::{LockFile, LockedPackage};
+use crate::registry::IndexRegistry;
+use crate::source::{checksum, read_files, GitSource, PackageStore};
+use crate::workspace::Workspace;
 
 /// Initialisiert ein neues Projekt
 pub fn init_project(name: Option<&str>) -> Result<()> {
@@ -36,8 +38,19 @@ version = "0.1.0"
     Ok(())
 }
 
-/// Installiert alle Dependencies
-pub async fn install_dependencies() -> Result<()> {
+/// Verzeichnis eines installierten Packages (`github.com/a/b` → `vendor/github.com_a_b`)
+pub fn vendor_dir(package: &str) -> PathBuf {
+    PathBuf::from("vendor").join(package.replace('/', "_"))
+}
+
+/// velin.toml des aktuellen Verzeichnisses; in einer Workspace-Wurzel das
+/// virtuelle Wurzel-Package mit allen Members
+fn load_config() -> Result<(VelinToml, Option<Workspace>)> {
+    let cwd = std::env::current_dir()?;
+    if let Some(workspace) = Workspace::discover(&cwd)?.filter(|w| w.root == cwd) {
+        return Ok((workspace.root_config(), Some(workspace)));
+    }
+
     let toml_path = Path::new("velin.toml");
     
     if !toml_path.exists() {
@@ -45,51 +58,83 @@ pub async fn install_dependencies() -> Result<()> {
     }
 
     let content = fs::read_to_string(toml_path)?;
-    let config: VelinToml = toml::from_str(&content)?;
+    Ok((toml::from_str(&content)?, None))
+}
+
+/// Quelle für Registry-Dependencies: `--registry` vor `[registry]` in velin.toml,
+/// ohne beides die Git-Tags der Repositories
+fn open_store(config: &VelinToml, registry: Option<&str>) -> Result<Box<dyn PackageStore>> {
+    let url = registry
+        .map(str::to_string)
+        .or_else(|| config.registry.as_ref().map(|r| r.url.clone()));
+    Ok(match url {
+        Some(url) => Box::new(IndexRegistry::open(&url)?),
+        None => Box::new(GitSource::new(git_cache())),
+    })
+}
+
+fn git_cache() -> PathBuf {
+    Path::new(".velin").join("cache").join("git")
+}
+
+/// Installiert alle Dependencies
+///
+/// Löst die Versionen auf, entpackt jedes Package nach `vendor/` und schreibt
+/// `velin.lock`. Für bereits gelockte Versionen wird die Checksumme geprüft.
+/// Pfad-Dependencies werden nicht kopiert, der Compiler liest sie direkt.
+/// In einer Workspace-Wurzel werden alle Members gemeinsam aufgelöst.
+pub async fn install_dependencies(registry: Option<&str>) -> Result<()> {
+    let (config, workspace) = load_config()?;
+
+    let mut lock_file = LockFile::load()?;
+    let mut store = open_store(&config, registry)?;
+    let mut git = GitSource::new(git_cache());
+    let inherited = workspace.as_ref().map(|w| &w.config.dependencies);
+    let pinned = pinned_dependencies(&config, inherited, &mut git)?;
+    let resolved = resolve_dependencies(&config, &lock_file, store.as_mut(), &pinned)?;
 
     // Erstelle vendor/ Verzeichnis
     fs::create_dir_all("vendor")?;
 
-    let mut lock_file = LockFile::load()?;
-    let mut locked_packages = std::collections::HashMap::new();
-
-    // Installiere jede Dependency
-    for (package_name, version_req) in &config.dependencies {
-        println!("Installing {}...", package_name);
-        
-        // Parse package name (z.B. "github.com/user/repo")
-        let parts: Vec<&str> = package_name.split('/').collect();
-        if parts.len() < 3 {
-            anyhow::bail!("Ungültiges Package-Format: {}. Erwartet: github.com/user/repo", package_name);
-        }
+    let mut locked_packages = BTreeMap::new();
+    for (package_name, package) in &resolved {
+        println!("Installing {} v{}...", package_name, package.version);
 
-        let owner = parts[1];
-        let repo = parts[2];
-
-        // Hole Package-Metadaten
-        let metadata = Registry::fetch_package(owner, repo, Some(version_req)).await?;
-
-        // Klone Repository in vendor/
-        let vendor_path = PathBuf::from("vendor").join(package_name.replace('/', "_"));
-        let url = format!("https://github.com/{}/{}.git", owner, repo);
-        
-        if vendor_path.exists() {
-            // Update existing
-            let repo = Repository::open(&vendor_path)?;
-            // Use repo to check for updates
-            let _head = repo.head()?;
-            // In einer echten Implementierung würde man hier einen Pull machen
-        } else {
-            // Clone new
-            Repository::clone(&url, &vendor_path)?;
-        }
+        // Checksumme nur prüfen, wenn die gelockte Version weiterverwendet wird
+        let locked = lock_file.packages.get(package_name);
+        let vendor_path = vendor_dir(package_name);
+
+        let (source_url, checksum) = match pinned.get(package_name).map(|p| &p.location) {
+            Some(PinnedLocation::Path(path)) => (format!("path+{}", path.display()), None),
+            Some(PinnedLocation::Git { url, rev }) => {
+                let source_url = format!("git+{}#{}", url, rev);
+                let expected = locked
+                    .filter(|locked| locked.source == source_url)
+                    .and_then(|locked| locked.checksum.as_deref());
+                let checksum = git.checkout_rev(url, rev, &vendor_path, expected)?;
+                (source_url, Some(checksum))
+            }
+            None => {
+                let expected = locked
+                    .filter(|locked| locked.version == package.version.to_string())
+                    .and_then(|locked| locked.checksum.as_deref());
+                let checksum =
+                    store.checkout(package_name, &package.version, &vendor_path, expected)?;
+                (store.source_id(package_name, &package.version)?, Some(checksum))
+            }
+        };
 
         locked_packages.insert(
             package_name.clone(),
-            crate::lockfile::LockedPackage {
-                version: metadata.version,
-                source: url,
-                checksum: None,
+            LockedPackage {
+                version: package.version.to_string(),
+                source: source_url,
+                checksum,
+                dependencies: package
+                    .dependencies
+                    .iter()
+                    .map(|(name, version)| format!("{} {}", name, version))
+                    .collect(),
             },
         );
     }
@@ -100,3 +145,70 @@ pub async fn install_dependencies() -> Result<()> {
 
     Ok(())
 }
+
+/// Erstellt einen vollständigen, per Checksumme gesicherten `vendor/`-Baum
+///
+/// Installiert wie [`install_dependencies`], entfernt nicht mehr benötigte
+/// Verzeichnisse und prüft das Ergebnis gegen `velin.lock`. Danach lässt sich das
+/// Projekt ohne Netzwerk bauen; `verify_only` prüft einen vorhandenen Baum offline.
+pub async fn vendor_dependencies(registry: Option<&str>, verify_only: bool) -> Result<usize> {
+    if !verify_only {
+        install_dependencies(registry).await?;
+
+        let lock_file = LockFile::load()?;
+        let expected: Vec<PathBuf> = lock_file.packages.keys().map(|name| vendor_dir(name)).collect();
+        for entry in fs::read_dir("vendor")? {
+            let path = Path::new("vendor").join(entry?.file_name());
+            if path.is_dir() && !expected.contains(&path) {
+                println!("Entferne {}", path.display());
+                fs::remove_dir_all(&path)?;
+            }
+        }
+    }
+    verify_vendor()
+}
+
+/// Prüft `vendor/` gegen `velin.lock`, ohne auf Quellen zuzugreifen
+fn verify_vendor() -> Result<usize> {
+    let (config, _) = load_config()?;
+    if !Path::new("velin.lock").exists() {
+        anyhow::bail!("velin.lock fehlt. Führe 'velin-pkg vendor' auf einem Rechner mit Zugriff auf die Quellen aus.");
+    }
+    let lock_file = LockFile::load()?;
+
+    let mut problems = Vec::new();
+    for (key, spec) in &config.dependencies {
+        let name = spec.package(key);
+        if !lock_file.packages.contains_key(name) {
+            problems.push(format!("{}: fehlt in velin.lock", name));
+        }
+    }
+
+    let mut count = 0;
+    for (name, locked) in &lock_file.packages {
+        if locked.source.starts_with("path+") {
+            continue;
+        }
+        let dir = vendor_dir(name);
+        if !dir.is_dir() {
+            problems.push(format!("{}: fehlt in {}", name, dir.display()));
+            continue;
+        }
+        let actual = checksum(&read_files(&dir)?);
+        if locked.checksum.as_deref() != Some(actual.as_str()) {
+            problems.push(format!(
+                "{}: Inhalt von {} weicht von velin.lock ab ({})",
+                name,
+                dir.display(),
+                actual
+            ));
+            continue;
+        }
+        count += 1;
+    }
+
+    if !problems.is_empty() {
+        anyhow::bail!("vendor/ passt nicht zu velin.lock:\n  {}", problems.join("\n  "));
+    }
+    Ok(count)
+}
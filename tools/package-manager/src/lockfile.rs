@@ -2,15 +2,14 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-// All imports are used (Deserialize for structs, HashMap for packages map)
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LockFile {
     pub version: String,
-    pub packages: HashMap<String, LockedPackage>,
+    pub packages: BTreeMap<String, LockedPackage>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,6 +17,9 @@ pub struct LockedPackage {
     pub version: String,
     pub source: String,
     pub checksum: Option<String>,
+    /// Exakte Versionen der Dependencies (`name version`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
 }
 
 impl LockFile {
@@ -28,7 +30,7 @@ impl LockFile {
         if !lock_path.exists() {
             return Ok(LockFile {
                 version: "1".to_string(),
-                packages: HashMap::new(),
+                packages: BTreeMap::new(),
             });
         }
 
@@ -46,7 +48,7 @@ impl LockFile {
     }
 
     /// Aktualisiert Lock File mit neuen Dependencies
-    pub fn update(&mut self, packages: HashMap<String, LockedPackage>) {
+    pub fn update(&mut self, packages: BTreeMap<String, LockedPackage>) {
         self.packages = packages;
     }
 }
@@ -6,9 +6,13 @@ mod resolver;
 mod lockfile;
 mod install;
 mod updater;
+mod solver;
+mod source;
+mod workspace;
 
 use registry::Registry;
 use updater::DependencyUpdater;
+use workspace::Workspace;
 
 use clap::{Parser, Subcommand};
 use anyhow::Result;
@@ -18,11 +22,14 @@ use toml;
 #[command(name = "velin-pkg")]
 #[command(about = "VelinScript Package Manager", long_about = None)]
 struct Cli {
+    /// Workspace-Member, auf dem der Befehl arbeitet
+    #[arg(short = 'p', long = "package", value_name = "NAME", global = true)]
+    member: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Clone)]
 enum Commands {
     /// Initialisiert ein neues Projekt
     Init {
@@ -43,7 +50,20 @@ enum Commands {
         package: String,
     },
     /// Installiert alle Dependencies
-    Install,
+    Install {
+        /// Index-Registry (file:///… oder https://…) statt Git-Tags
+        #[arg(long)]
+        registry: Option<String>,
+    },
+    /// Erstellt einen per Checksumme gesicherten vendor/-Baum für Offline-Builds
+    Vendor {
+        /// Index-Registry (file:///… oder https://…) statt Git-Tags
+        #[arg(long)]
+        registry: Option<String>,
+        /// Prüft vendor/ nur gegen velin.lock (ohne Netzwerk)
+        #[arg(long)]
+        verify: bool,
+    },
     /// Aktualisiert Dependencies
     Update {
         /// Package Name (optional, wenn nicht angegeben werden Updates geprüft)
@@ -61,6 +81,9 @@ enum Commands {
     Publish {
         /// Version (z.B. 1.0.0)
         version: String,
+        /// Index-Registry, in die veröffentlicht wird (z.B. file:///srv/velin-registry)
+        #[arg(long)]
+        registry: Option<String>,
     },
     /// Prüft Dependencies auf Vulnerabilities
     Audit,
@@ -70,7 +93,68 @@ enum Commands {
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
+    let targets = targets(&cli.command, cli.member.as_deref())?;
+    let several = targets.len() > 1;
+    for (name, dir) in targets {
+        if several {
+            println!("\n[{}]", name);
+        }
+        std::env::set_current_dir(&dir)?;
+        run(cli.command.clone()).await?;
+    }
+    Ok(())
+}
+
+/// Verzeichnisse, in denen der Befehl ausgeführt wird (Member-Name, Verzeichnis)
+///
+/// In einem Workspace arbeiten `install` und `vendor` immer in der Wurzel, weil es
+/// nur eine velin.lock und ein vendor/ gibt. Alle anderen Befehle arbeiten auf dem
+/// Member aus `-p` bzw. dem Member, in dem man sich befindet; `list` und `audit`
+/// in der Wurzel auf allen Members.
+fn targets(command: &Commands, member: Option<&str>) -> Result<Vec<(String, std::path::PathBuf)>> {
+    let cwd = std::env::current_dir()?;
+    let Some(workspace) = Workspace::discover(&cwd)? else {
+        if let Some(member) = member {
+            anyhow::bail!("-p {} benötigt einen Workspace (velin.toml mit [workspace])", member);
+        }
+        return Ok(vec![(String::new(), cwd)]);
+    };
+    let root = workspace.root.clone();
+
+    if matches!(command, Commands::Install { .. } | Commands::Vendor { .. }) {
+        if let Some(member) = member {
+            workspace.member(member)?;
+        }
+        return Ok(vec![(String::new(), root)]);
+    }
+    if matches!(command, Commands::Init { .. }) {
+        return Ok(vec![(String::new(), cwd)]);
+    }
+
+    let selected = match member {
+        Some(member) => Some(workspace.member(member)?),
+        None => workspace.member_at(&cwd),
+    };
+    if let Some(member) = selected {
+        return Ok(vec![(member.name.clone(), root.join(&member.dir))]);
+    }
+    if matches!(command, Commands::List | Commands::Audit) {
+        return Ok(workspace
+            .members
+            .iter()
+            .map(|member| (member.name.clone(), root.join(&member.dir)))
+            .collect());
+    }
+    let names: Vec<_> = workspace.members.iter().map(|m| m.name.as_str()).collect();
+    anyhow::bail!(
+        "{} ist ein Workspace; wähle ein Member mit -p <name> ({})",
+        root.display(),
+        names.join(", ")
+    )
+}
+
+async fn run(command: Commands) -> Result<()> {
+    match command {
         Commands::Init { name } => {
             install::init_project(name.as_deref())?;
             println!("✓ Projekt initialisiert");
@@ -83,10 +167,14 @@ async fn main() -> Result<()> {
             resolver::remove_dependency(&package)?;
             println!("✓ Dependency entfernt: {}", package);
         }
-        Commands::Install => {
-            install::install_dependencies().await?;
+        Commands::Install { registry } => {
+            install::install_dependencies(registry.as_deref()).await?;
             println!("✓ Dependencies installiert");
         }
+        Commands::Vendor { registry, verify } => {
+            let count = install::vendor_dependencies(registry.as_deref(), verify).await?;
+            println!("✓ vendor/ stimmt mit velin.lock überein ({} Packages)", count);
+        }
         Commands::Update { package, all, allow_breaking } => {
             let toml_path = std::path::Path::new("velin.toml");
             if !toml_path.exists() {
@@ -140,8 +228,8 @@ async fn main() -> Result<()> {
                 }
             }
         }
-        Commands::Publish { version } => {
-            Registry::publish_package(&version).await?;
+        Commands::Publish { version, registry } => {
+            Registry::publish_package(&version, registry.as_deref()).await?;
             println!("✓ Package veröffentlicht: v{}", version);
         }
         Commands::Audit => {
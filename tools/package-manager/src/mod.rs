@@ -3,3 +3,6 @@ pub mod resolver;
 pub mod lockfile;
 pub mod install;
 pub mod updater;
+pub mod solver;
+pub mod source;
+pub mod workspace;
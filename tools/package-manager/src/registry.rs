@@ -1,7 +1,18 @@
 // Package Registry - Verwaltet Package-Veröffentlichung und -Abruf
 
-use anyhow::Result;
+use crate::resolver::VelinToml;
+use crate::source::{checksum, read_files, verify, write_files, Manifest, PackageSource, PackageStore};
+use crate::workspace::Workspace;
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use semver::Version;
 use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PackageMetadata {
@@ -58,12 +69,29 @@ impl Registry {
 
     /// Veröffentlicht ein Package zur Registry
     /// 
-    /// Validiert das Package und erstellt ein GitHub Release.
-    /// Für vollständige Veröffentlichung wird ein GitHub Token benötigt.
-    pub async fn publish_package(version: &str) -> Result<()> {
-        use std::fs;
-        use std::path::Path;
-        
+    /// Mit `registry` (oder `[registry]` in velin.toml) wird das Package als Tarball
+    /// samt Index-Eintrag in eine Index-Registry geschrieben. Ohne Registry wird es
+    /// validiert und auf ein GitHub Release (Git-Tag) verwiesen.
+    pub async fn publish_package(version: &str, registry: Option<&str>) -> Result<()> {
+        let config: Option<VelinToml> = fs::read_to_string("velin.toml")
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok());
+        let registry = registry
+            .map(str::to_string)
+            .or_else(|| config.and_then(|c| c.registry).map(|r| r.url))
+            .or_else(|| {
+                let workspace = Workspace::discover(&std::env::current_dir().ok()?).ok()??;
+                workspace.registry.map(|r| r.url)
+            });
+        if let Some(url) = registry {
+            let version = Version::parse(version)
+                .map_err(|e| anyhow::anyhow!("Ungültiges Version-Format: {} ({})", version, e))?;
+            let entry = IndexRegistry::open(&url)?.publish(Path::new("."), &version)?;
+            println!("Veröffentlicht: {} v{} nach {}", entry.name, entry.vers, url);
+            println!("  Checksumme: {}", entry.cksum);
+            return Ok(());
+        }
+
         // Validiere Version-Format (SemVer)
         if !version.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-') {
             anyhow::bail!("Ungültiges Version-Format: {}", version);
@@ -176,3 +204,469 @@ impl Registry {
         Ok(packages)
     }
 }
+
+/// Eintrag im Registry-Index - eine JSON-Zeile pro veröffentlichter Version
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub name: String,
+    pub vers: String,
+    #[serde(default)]
+    pub deps: BTreeMap<String, String>,
+    pub cksum: String,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+enum RegistryRoot {
+    Dir(PathBuf),
+    Http(String),
+}
+
+/// Registry aus einem Verzeichnis (`file://`) oder einem statischen Webserver (`https://`)
+///
+/// Layout im Stil eines Sparse-Index, damit jeder Dateiserver genügt:
+/// - `index/<name>`: eine [`IndexEntry`]-Zeile pro Version
+/// - `packages/<name>/<version>.tar.gz`: Inhalt der Version
+pub struct IndexRegistry {
+    url: String,
+    root: RegistryRoot,
+    entries: HashMap<String, Vec<IndexEntry>>,
+}
+
+impl IndexRegistry {
+    pub fn open(url: &str) -> Result<Self> {
+        let url = url.trim_end_matches('/').to_string();
+        let root = if let Some(path) = url.strip_prefix("file://") {
+            RegistryRoot::Dir(PathBuf::from(path))
+        } else if url.starts_with("https://") || url.starts_with("http://") {
+            RegistryRoot::Http(url.clone())
+        } else {
+            anyhow::bail!(
+                "Ungültige Registry-URL: {} (erwartet file://… oder https://…)",
+                url
+            );
+        };
+        Ok(IndexRegistry {
+            url,
+            root,
+            entries: HashMap::new(),
+        })
+    }
+
+    /// Package-Namen werden zu Pfaden im Index und dürfen ihn nicht verlassen
+    fn check_name(name: &str) -> Result<()> {
+        let valid = name.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+        if !valid {
+            anyhow::bail!("Ungültiger Package-Name für eine Registry: {}", name);
+        }
+        Ok(())
+    }
+
+    fn index_path(name: &str) -> String {
+        format!("index/{}", name)
+    }
+
+    fn tarball_path(name: &str, version: &str) -> String {
+        format!("packages/{}/{}.tar.gz", name, version)
+    }
+
+    /// Liest eine Datei der Registry; `None`, wenn sie nicht existiert
+    fn read(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        match &self.root {
+            RegistryRoot::Dir(dir) => {
+                let file = dir.join(path);
+                if !file.is_file() {
+                    return Ok(None);
+                }
+                Ok(Some(fs::read(&file)?))
+            }
+            RegistryRoot::Http(base) => {
+                let url = format!("{}/{}", base, path);
+                // Der Resolver arbeitet synchron innerhalb der Tokio-Runtime
+                tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(async {
+                        let response = reqwest::Client::new()
+                            .get(&url)
+                            .header("User-Agent", "velin-pkg")
+                            .send()
+                            .await
+                            .with_context(|| format!("{} nicht erreichbar", url))?;
+                        if response.status() == reqwest::StatusCode::NOT_FOUND {
+                            return Ok(None);
+                        }
+                        if !response.status().is_success() {
+                            anyhow::bail!("{} antwortet mit {}", url, response.status());
+                        }
+                        Ok(Some(response.bytes().await?.to_vec()))
+                    })
+                })
+            }
+        }
+    }
+
+    fn entries(&mut self, package: &str) -> Result<&[IndexEntry]> {
+        if !self.entries.contains_key(package) {
+            Self::check_name(package)?;
+            let content = self.read(&Self::index_path(package))?.unwrap_or_default();
+            let entries = String::from_utf8_lossy(&content)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    serde_json::from_str(line).with_context(|| {
+                        format!("Index-Eintrag von {} in {} ist ungültig", package, self.url)
+                    })
+                })
+                .collect::<Result<Vec<IndexEntry>>>()?;
+            self.entries.insert(package.to_string(), entries);
+        }
+        Ok(&self.entries[package])
+    }
+
+    fn entry(&mut self, package: &str, version: &Version) -> Result<IndexEntry> {
+        let url = self.url.clone();
+        self.entries(package)?
+            .iter()
+            .find(|entry| Version::parse(&entry.vers).ok().as_ref() == Some(version))
+            .cloned()
+            .with_context(|| format!("{} {} ist in {} nicht veröffentlicht", package, version, url))
+    }
+
+    /// Veröffentlicht das Package in `dir` als Tarball samt Index-Eintrag
+    ///
+    /// Nur für `file://`-Registries; statische HTTP-Registries entstehen durch
+    /// Kopieren bzw. Hochladen eines solchen Verzeichnisses.
+    pub fn publish(&mut self, dir: &Path, version: &Version) -> Result<IndexEntry> {
+        let RegistryRoot::Dir(root) = &self.root else {
+            anyhow::bail!(
+                "In {} kann nicht direkt veröffentlicht werden; nur file://-Registries sind beschreibbar",
+                self.url
+            );
+        };
+        let root = root.clone();
+
+        let content = fs::read_to_string(dir.join("velin.toml"))
+            .context("velin.toml nicht gefunden. Führe 'velin-pkg init' aus.")?;
+        // Workspace-Members werden mit aufgelösten `{ workspace = true }`-Einträgen veröffentlicht
+        let content = match Workspace::discover(&fs::canonicalize(dir)?)? {
+            Some(workspace) => workspace.inherit_manifest(&content)?,
+            None => content,
+        };
+        let config: VelinToml = toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Fehler beim Parsen von velin.toml: {}", e))?;
+        let name = config.package.name.clone();
+        Self::check_name(&name)?;
+        if config.package.version != version.to_string() {
+            anyhow::bail!(
+                "Version {} passt nicht zu velin.toml ({}); bitte [package].version anpassen",
+                version,
+                config.package.version
+            );
+        }
+        let origin = format!("{} {}", name, version);
+        let deps = Manifest::parse(&content, &origin)?.requirements(&origin)?;
+
+        if self
+            .entries(&name)?
+            .iter()
+            .any(|entry| entry.vers == version.to_string())
+        {
+            anyhow::bail!(
+                "{} {} ist bereits in {} veröffentlicht; veröffentlichte Versionen sind unveränderlich",
+                name,
+                version,
+                self.url
+            );
+        }
+
+        let mut files = read_files(dir)?;
+        if let Some((_, manifest)) = files.iter_mut().find(|(path, _)| path == "velin.toml") {
+            *manifest = content.into_bytes();
+        }
+        let entry = IndexEntry {
+            name: name.clone(),
+            vers: version.to_string(),
+            deps,
+            cksum: checksum(&files),
+            yanked: false,
+        };
+
+        let tarball = root.join(Self::tarball_path(&name, &entry.vers));
+        fs::create_dir_all(tarball.parent().unwrap())?;
+        fs::write(&tarball, pack(&files)?)?;
+
+        let index = root.join(Self::index_path(&name));
+        fs::create_dir_all(index.parent().unwrap())?;
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+
+        self.entries.remove(&name);
+        Ok(entry)
+    }
+}
+
+impl PackageSource for IndexRegistry {
+    fn versions(&mut self, package: &str) -> Result<Vec<Version>> {
+        Ok(self
+            .entries(package)?
+            .iter()
+            .filter(|entry| !entry.yanked)
+            .filter_map(|entry| Version::parse(&entry.vers).ok())
+            .collect())
+    }
+
+    fn dependencies(
+        &mut self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, String>> {
+        Ok(self.entry(package, version)?.deps)
+    }
+}
+
+impl PackageStore for IndexRegistry {
+    fn checkout(
+        &mut self,
+        package: &str,
+        version: &Version,
+        target: &Path,
+        expected: Option<&str>,
+    ) -> Result<String> {
+        let entry = self.entry(package, version)?;
+        let label = format!("{} {}", package, version);
+        let tarball = self
+            .read(&Self::tarball_path(package, &entry.vers))?
+            .with_context(|| format!("Tarball von {} fehlt in {}", label, self.url))?;
+        let files = unpack(&tarball).with_context(|| format!("Tarball von {} ist defekt", label))?;
+
+        if checksum(&files) != entry.cksum {
+            anyhow::bail!(
+                "Tarball von {} passt nicht zur Checksumme im Index von {}",
+                label,
+                self.url
+            );
+        }
+        let actual = verify(&label, &files, expected)?;
+        write_files(target, &files)?;
+        Ok(actual)
+    }
+
+    fn source_id(&self, _package: &str, _version: &Version) -> Result<String> {
+        Ok(format!("registry+{}", self.url))
+    }
+}
+
+/// Packt Dateien reproduzierbar (feste Zeitstempel und Rechte) als tar.gz
+fn pack(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(0);
+        builder.append_data(&mut header, path, content.as_slice())?;
+    }
+    Ok(builder.into_inner()?.finish()?)
+}
+
+fn unpack(tarball: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(tarball));
+    let mut files = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        // SECURITY: Keine Pfade außerhalb des Zielverzeichnisses
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            anyhow::bail!("Unzulässiger Pfad im Tarball: {}", path.display());
+        }
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        let path: Vec<_> = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        files.push((path.join("/"), content));
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Legt ein Package mit `velin.toml` und einer Quelldatei an
+    fn package(dir: &Path, version: &str, dependencies: &str) -> PathBuf {
+        let root = dir.join(format!("json-{}", version));
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("vendor/acme_text")).unwrap();
+        fs::write(
+            root.join("velin.toml"),
+            format!(
+                "[package]\nname = \"acme/json\"\nversion = \"{}\"\n\n[dependencies]\n{}",
+                version, dependencies
+            ),
+        )
+        .unwrap();
+        fs::write(root.join("lib.velin"), "pub fn parse() {\n}\n").unwrap();
+        fs::write(root.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(root.join("vendor/acme_text/lib.velin"), "").unwrap();
+        root
+    }
+
+    fn registry(dir: &Path) -> IndexRegistry {
+        IndexRegistry::open(&format!("file://{}", dir.join("registry").display())).unwrap()
+    }
+
+    #[test]
+    fn test_publish_and_fetch_from_file_registry() {
+        let dir = tempfile::tempdir().unwrap();
+        let version = Version::new(1, 0, 0);
+        let source = package(dir.path(), "1.0.0", "\"acme/text\" = \"^1.2\"\n");
+        let entry = registry(dir.path()).publish(&source, &version).unwrap();
+        assert_eq!(entry.name, "acme/json");
+        assert_eq!(entry.vers, "1.0.0");
+        assert!(!entry.yanked);
+
+        // Frische Instanz: alles kommt aus Index und Tarball
+        let mut registry = registry(dir.path());
+        assert_eq!(
+            registry.versions("acme/json").unwrap(),
+            vec![version.clone()]
+        );
+        assert_eq!(
+            registry.dependencies("acme/json", &version).unwrap(),
+            BTreeMap::from([("acme/text".to_string(), "^1.2".to_string())])
+        );
+        assert!(registry.versions("acme/unknown").unwrap().is_empty());
+
+        let target = dir.path().join("vendor/acme_json");
+        let cksum = registry
+            .checkout("acme/json", &version, &target, Some(&entry.cksum))
+            .unwrap();
+        assert_eq!(cksum, entry.cksum);
+        assert!(target.join("lib.velin").is_file());
+        assert!(target.join("velin.toml").is_file());
+        // Versteckte Dateien und vendor/ gehören nicht zum Package
+        assert!(!target.join(".git").exists());
+        assert!(!target.join("vendor").exists());
+        assert_eq!(cksum, checksum(&read_files(&target).unwrap()));
+        assert!(registry
+            .source_id("acme/json", &version)
+            .unwrap()
+            .starts_with("registry+file://"));
+
+        let error = registry.publish(&source, &version).unwrap_err().to_string();
+        assert!(error.contains("bereits in"), "{}", error);
+        let error = registry
+            .publish(&source, &Version::new(1, 0, 1))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("passt nicht zu velin.toml"), "{}", error);
+    }
+
+    #[test]
+    fn test_checksums_are_verified_on_checkout() {
+        let dir = tempfile::tempdir().unwrap();
+        let version = Version::new(1, 0, 0);
+        let source = package(dir.path(), "1.0.0", "");
+        let entry = registry(dir.path()).publish(&source, &version).unwrap();
+        let target = dir.path().join("vendor/acme_json");
+
+        // Abweichung zu velin.lock
+        let error = registry(dir.path())
+            .checkout("acme/json", &version, &target, Some("sha256:0000"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("stimmt nicht mit velin.lock überein"),
+            "{}",
+            error
+        );
+
+        // Nachträglich ausgetauschter Tarball
+        let tarball = dir
+            .path()
+            .join("registry")
+            .join(IndexRegistry::tarball_path("acme/json", "1.0.0"));
+        let files = vec![("lib.velin".to_string(), b"pub fn evil() {\n}\n".to_vec())];
+        fs::write(&tarball, pack(&files).unwrap()).unwrap();
+        let error = registry(dir.path())
+            .checkout("acme/json", &version, &target, Some(&entry.cksum))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("passt nicht zur Checksumme im Index"),
+            "{}",
+            error
+        );
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn test_yanked_versions_are_not_resolved_but_stay_installable() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut registry = registry(dir.path());
+        for version in ["1.0.0", "1.1.0"] {
+            let source = package(dir.path(), version, "");
+            registry
+                .publish(&source, &Version::parse(version).unwrap())
+                .unwrap();
+        }
+
+        let index = dir.path().join("registry/index/acme/json");
+        let content = fs::read_to_string(&index).unwrap();
+        let yanked: Vec<String> = content
+            .lines()
+            .map(|line| {
+                let mut entry: IndexEntry = serde_json::from_str(line).unwrap();
+                entry.yanked = entry.vers == "1.1.0";
+                serde_json::to_string(&entry).unwrap()
+            })
+            .collect();
+        fs::write(&index, yanked.join("\n") + "\n").unwrap();
+
+        let mut registry = self::registry(dir.path());
+        assert_eq!(
+            registry.versions("acme/json").unwrap(),
+            vec![Version::new(1, 0, 0)]
+        );
+        // Projekte, deren velin.lock die Version bereits enthält, können sie weiter installieren
+        let target = dir.path().join("vendor/acme_json");
+        registry
+            .checkout("acme/json", &Version::new(1, 1, 0), &target, None)
+            .unwrap();
+        assert!(target.join("lib.velin").is_file());
+    }
+
+    #[test]
+    fn test_only_registry_dependencies_can_be_published() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = package(dir.path(), "1.0.0", "shared = { path = \"../shared\" }\n");
+        let error = registry(dir.path())
+            .publish(&source, &Version::new(1, 0, 0))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("dürfen nur Registry-Dependencies haben"),
+            "{}",
+            error
+        );
+        assert!(!dir.path().join("registry/index/acme/json").exists());
+
+        let mut registry = registry(dir.path());
+        assert!(registry.versions("../escape").is_err());
+        assert!(IndexRegistry::open("ftp://example.com").is_err());
+    }
+}
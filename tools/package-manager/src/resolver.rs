@@ -1,12 +1,16 @@
 // Dependency Resolver - Löst Dependencies auf und verwaltet Versionen
 
-use anyhow::Result;
-use semver::{Version, VersionReq};
+use crate::lockfile::LockFile;
+use crate::solver::{Resolved, Solver};
+use crate::source::{GitSource, Manifest, PackageSource};
+use anyhow::{Context, Result};
+use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 // All imports are used
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use toml;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,7 +40,121 @@ impl Dependency {
 pub struct VelinToml {
     pub package: PackageInfo,
     #[serde(default)]
-    pub dependencies: HashMap<String, String>,
+    pub dependencies: HashMap<String, DependencySpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<RegistryConfig>,
+}
+
+/// `[registry]` - Index-Registry statt Git-Tags (`file://…` oder `https://…`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    pub url: String,
+}
+
+/// Eintrag unter `[dependencies]`
+///
+/// Entweder eine Versions-Anforderung (`"^1.2"`) oder eine Tabelle mit
+/// `version`, `path` oder `git` + `rev`. `package` erlaubt einen anderen
+/// Schlüssel als den Package-Namen. In Workspace-Members übernimmt
+/// `{ workspace = true }` den Eintrag aus `[workspace.dependencies]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DependencySpec {
+    Version(String),
+    Detailed(DetailedDependency),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetailedDependency {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub workspace: bool,
+}
+
+impl DependencySpec {
+    /// Versions-Anforderung für Registry-Dependencies, `None` für Pfad/Git/Workspace
+    pub fn requirement(&self) -> Option<&str> {
+        match self {
+            DependencySpec::Version(requirement) => Some(requirement),
+            DependencySpec::Detailed(detail)
+                if detail.path.is_none() && detail.git.is_none() && !detail.workspace =>
+            {
+                Some(detail.version.as_deref().unwrap_or("*"))
+            }
+            DependencySpec::Detailed(_) => None,
+        }
+    }
+
+    /// `true` für `{ workspace = true }`
+    pub fn is_inherited(&self) -> bool {
+        matches!(self, DependencySpec::Detailed(detail) if detail.workspace)
+    }
+
+    /// Package-Name der Dependency (`package = "..."` oder der Schlüssel)
+    pub fn package<'a>(&'a self, key: &'a str) -> &'a str {
+        match self {
+            DependencySpec::Detailed(DetailedDependency {
+                package: Some(package),
+                ..
+            }) => package,
+            _ => key,
+        }
+    }
+}
+
+impl fmt::Display for DependencySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(requirement) = self.requirement() {
+            return write!(f, "{}", requirement);
+        }
+        match self {
+            DependencySpec::Detailed(DetailedDependency {
+                workspace: true, ..
+            }) => write!(f, "workspace"),
+            DependencySpec::Detailed(DetailedDependency {
+                path: Some(path), ..
+            }) => write!(f, "path {}", path),
+            DependencySpec::Detailed(DetailedDependency { git, rev, .. }) => write!(
+                f,
+                "git {}@{}",
+                git.as_deref().unwrap_or_default(),
+                rev.as_deref().unwrap_or("?")
+            ),
+            DependencySpec::Version(_) => unreachable!(),
+        }
+    }
+}
+
+/// Package, das per `path` oder `git` + `rev` auf genau einen Stand festgelegt ist
+#[derive(Debug, Clone)]
+pub struct Pinned {
+    pub version: Version,
+    pub dependencies: BTreeMap<String, String>,
+    pub location: PinnedLocation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PinnedLocation {
+    Path(PathBuf),
+    Git { url: String, rev: String },
+}
+
+impl fmt::Display for PinnedLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PinnedLocation::Path(path) => write!(f, "{}", path.display()),
+            PinnedLocation::Git { url, rev } => write!(f, "{}@{}", url, rev),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,11 +183,15 @@ pub async fn add_dependency(package: &str, version: Option<&str>) -> Result<()>
                 version: "0.1.0".to_string(),
             },
             dependencies: HashMap::new(),
+            registry: None,
         }
     };
 
     let version_str = version.unwrap_or("*");
-    config.dependencies.insert(package.to_string(), version_str.to_string());
+    config.dependencies.insert(
+        package.to_string(),
+        DependencySpec::Version(version_str.to_string()),
+    );
 
     let toml_content = toml::to_string_pretty(&config)?;
     fs::write(toml_path, toml_content)?;
@@ -119,7 +241,7 @@ pub async fn update_dependency(package: Option<&str>) -> Result<()> {
     let mut updates_count = 0;
 
     if let Some(pkg_name) = package {
-        if let Some(version) = config.dependencies.get_mut(pkg_name) {
+        if let Some(DependencySpec::Version(version)) = config.dependencies.get_mut(pkg_name) {
             println!("Aktualisiere {} (aktuell: {})...", pkg_name, version);
             // Simuliere Update: Erhöhe Patch-Version
             if let Ok(mut sem_ver) = semver::Version::parse(version) {
@@ -135,7 +257,10 @@ pub async fn update_dependency(package: Option<&str>) -> Result<()> {
         }
     } else {
         println!("Prüfe alle Dependencies auf Updates...");
-        for (name, version) in config.dependencies.iter_mut() {
+        for (name, spec) in config.dependencies.iter_mut() {
+             let DependencySpec::Version(version) = spec else {
+                 continue;
+             };
              // Simuliere Update Check
              if let Ok(mut sem_ver) = semver::Version::parse(version) {
                 // Demo-Logik: Update jedes 3. Paket
@@ -172,7 +297,11 @@ pub fn list_dependencies() -> Result<Vec<(String, String)>> {
     let content = fs::read_to_string(toml_path)?;
     let config: VelinToml = toml::from_str(&content)?;
 
-    Ok(config.dependencies.into_iter().collect())
+    Ok(config
+        .dependencies
+        .into_iter()
+        .map(|(name, spec)| (name, spec.to_string()))
+        .collect())
 }
 
 /// Prüft Dependencies auf bekannte Security-Vulnerabilities
@@ -200,7 +329,8 @@ pub async fn audit_dependencies() -> Result<Vec<Vulnerability>> {
         ("serde", "0.1.0", "Deserialization issue (example)"),
     ];
     
-    for (name, version) in config.dependencies {
+    for (name, spec) in config.dependencies {
+        let version = spec.to_string();
         for (vuln_pkg, vuln_ver, desc) in &known_vulns {
             if name == *vuln_pkg && version == *vuln_ver {
                 vulnerabilities.push(Vulnerability {
@@ -215,24 +345,257 @@ pub async fn audit_dependencies() -> Result<Vec<Vulnerability>> {
     Ok(vulnerabilities)
 }
 
+/// Sammelt alle per `path` oder `git` + `rev` festgelegten Packages
+///
+/// Wird rekursiv über deren eigene `velin.toml` fortgesetzt. Pfade sind relativ
+/// zum deklarierenden Package; Git-Packages dürfen keine Pfad-Dependencies haben,
+/// weil diese außerhalb des Repositories liegen würden. `workspace` sind die
+/// `[workspace.dependencies]`, wenn das aktuelle Verzeichnis eine Workspace-Wurzel ist.
+pub fn pinned_dependencies(
+    config: &VelinToml,
+    workspace: Option<&BTreeMap<String, DependencySpec>>,
+    source: &mut GitSource,
+) -> Result<BTreeMap<String, Pinned>> {
+    let mut pinned = BTreeMap::new();
+    let dependencies: BTreeMap<_, _> = config.dependencies.clone().into_iter().collect();
+    collect_pinned(
+        &dependencies,
+        &config.package.name,
+        Some(Path::new("")),
+        workspace,
+        source,
+        &mut pinned,
+    )?;
+    Ok(pinned)
+}
+
+fn collect_pinned(
+    dependencies: &BTreeMap<String, DependencySpec>,
+    origin: &str,
+    base: Option<&Path>,
+    workspace: Option<&BTreeMap<String, DependencySpec>>,
+    source: &mut GitSource,
+    pinned: &mut BTreeMap<String, Pinned>,
+) -> Result<BTreeMap<String, String>> {
+    let mut requirements = BTreeMap::new();
+    for (key, spec) in dependencies {
+        // Geerbte Einträge sind relativ zur Workspace-Wurzel (= aktuelles Verzeichnis);
+        // Git-Packages gehören nie zum Workspace
+        let (spec, base) = if spec.is_inherited() {
+            let inherited = workspace
+                .filter(|_| base.is_some())
+                .and_then(|dependencies| dependencies.get(key))
+                .with_context(|| {
+                    format!(
+                        "Dependency '{}' von {} verwendet 'workspace = true', steht aber nicht in [workspace.dependencies]",
+                        key, origin
+                    )
+                })?;
+            (inherited, Some(Path::new("")))
+        } else {
+            (spec, base)
+        };
+        let name = spec.package(key).to_string();
+        if let Some(requirement) = spec.requirement() {
+            requirements.insert(name, requirement.to_string());
+            continue;
+        }
+        let DependencySpec::Detailed(detail) = spec else {
+            unreachable!()
+        };
+
+        let location = match (&detail.path, &detail.git) {
+            (Some(_), Some(_)) => anyhow::bail!(
+                "Dependency '{}' von {} hat sowohl 'path' als auch 'git'",
+                key,
+                origin
+            ),
+            (Some(path), None) => {
+                let Some(base) = base else {
+                    anyhow::bail!(
+                        "{} ist ein Git-Package und darf keine Pfad-Dependency '{}' haben",
+                        origin,
+                        key
+                    );
+                };
+                PinnedLocation::Path(normalize(&base.join(path)))
+            }
+            (None, Some(url)) => {
+                let rev = detail.rev.clone().with_context(|| {
+                    format!("Git-Dependency '{}' von {} benötigt 'rev'", key, origin)
+                })?;
+                PinnedLocation::Git {
+                    url: url.clone(),
+                    rev,
+                }
+            }
+            (None, None) => anyhow::bail!(
+                "Dependency '{}' von {}: 'workspace = true' ist in [workspace.dependencies] nicht erlaubt",
+                key,
+                origin
+            ),
+        };
+
+        if let Some(existing) = pinned.get(&name) {
+            if existing.location != location {
+                anyhow::bail!(
+                    "{} ist mehrfach festgelegt: {} und {}",
+                    name,
+                    existing.location,
+                    location
+                );
+            }
+            requirements.insert(name, format!("={}", existing.version));
+            continue;
+        }
+
+        let label = format!("{} ({})", name, location);
+        let (manifest, nested_base) = match &location {
+            PinnedLocation::Path(path) => {
+                let manifest_path = path.join("velin.toml");
+                if !path.is_dir() {
+                    anyhow::bail!("Pfad-Dependency {} existiert nicht", label);
+                }
+                let content = manifest_path
+                    .exists()
+                    .then(|| fs::read_to_string(&manifest_path))
+                    .transpose()?;
+                (content, Some(path.clone()))
+            }
+            PinnedLocation::Git { url, rev } => (source.manifest_at(url, rev)?, None),
+        };
+        let manifest = manifest
+            .map(|content| Manifest::parse(&content, &label))
+            .transpose()?;
+
+        let version = match manifest.as_ref().and_then(|m| m.package.as_ref()) {
+            Some(package) => Version::parse(&package.version).map_err(|e| {
+                anyhow::anyhow!("Ungültige Version '{}' von {}: {}", package.version, label, e)
+            })?,
+            None => Version::new(0, 0, 0),
+        };
+        requirements.insert(name.clone(), format!("={}", version));
+
+        // Vor der Rekursion eintragen, damit Zyklen terminieren
+        pinned.insert(
+            name.clone(),
+            Pinned {
+                version,
+                dependencies: BTreeMap::new(),
+                location,
+            },
+        );
+        let nested = match &manifest {
+            Some(manifest) => collect_pinned(
+                &manifest.dependencies,
+                &label,
+                nested_base.as_deref(),
+                workspace,
+                source,
+                pinned,
+            )?,
+            None => BTreeMap::new(),
+        };
+        pinned.get_mut(&name).unwrap().dependencies = nested;
+    }
+    Ok(requirements)
+}
+
+/// Entfernt `.` und `a/..` ohne Dateisystemzugriff, damit dasselbe Package aus
+/// verschiedenen Workspace-Members nicht als zwei Pfade erscheint
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(result.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                result.pop();
+            }
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Quelle, die festgelegte Packages selbst beantwortet und den Rest weiterreicht
+struct WithPinned<'a, S: ?Sized> {
+    inner: &'a mut S,
+    pinned: &'a BTreeMap<String, Pinned>,
+}
+
+impl<S: PackageSource + ?Sized> PackageSource for WithPinned<'_, S> {
+    fn versions(&mut self, package: &str) -> Result<Vec<Version>> {
+        match self.pinned.get(package) {
+            Some(pinned) => Ok(vec![pinned.version.clone()]),
+            None => self.inner.versions(package),
+        }
+    }
+
+    fn dependencies(
+        &mut self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, String>> {
+        match self.pinned.get(package) {
+            Some(pinned) => Ok(pinned.dependencies.clone()),
+            None => self.inner.dependencies(package, version),
+        }
+    }
+}
+
 /// Löst Dependencies auf (SemVer)
-/// 
-/// **Hinweis**: Diese Funktion ist für zukünftige Dependency-Auflösung vorgesehen.
-/// Aktuell wird sie nicht aufgerufen, da die vollständige Auflösung noch nicht implementiert ist.
-#[allow(dead_code)]
-pub fn resolve_dependencies(
-    dependencies: &HashMap<String, String>,
-) -> Result<HashMap<String, Version>> {
-    let mut resolved = HashMap::new();
-
-    for (name, version_req_str) in dependencies {
-        let version_req = VersionReq::parse(version_req_str)?; // Use version_req
-        // In einer echten Implementierung würde man die Registry abfragen
-        // und die neueste passende Version finden
-        // Use version_req to find compatible version
-        let _req_str = version_req.to_string();
-        resolved.insert(name.clone(), Version::parse("1.0.0")?);
-    }
-
-    Ok(resolved)
+///
+/// Löst die Anforderungen aus `velin.toml` samt aller transitiven Dependencies mit
+/// einem PubGrub-Solver auf. Versionen aus `velin.lock` werden bevorzugt, solange sie
+/// die Anforderungen noch erfüllen. Bei einem Konflikt erklärt der Fehler, welche
+/// Anforderungen sich widersprechen. Festgelegte Packages (`pinned`) haben genau
+/// eine Version.
+pub fn resolve_dependencies<S: PackageSource + ?Sized>(
+    config: &VelinToml,
+    lock: &LockFile,
+    source: &mut S,
+    pinned: &BTreeMap<String, Pinned>,
+) -> Result<BTreeMap<String, Resolved>> {
+    let root_version = Version::parse(&config.package.version).map_err(|e| {
+        anyhow::anyhow!(
+            "Ungültige Version '{}' in velin.toml: {}",
+            config.package.version,
+            e
+        )
+    })?;
+
+    let preferred = lock
+        .packages
+        .iter()
+        .filter_map(|(name, locked)| Some((name.clone(), Version::parse(&locked.version).ok()?)))
+        .collect();
+
+    let root_dependencies = config
+        .dependencies
+        .iter()
+        .map(|(key, spec)| {
+            let name = spec.package(key).to_string();
+            let requirement = match (spec.requirement(), pinned.get(&name)) {
+                (Some(requirement), _) => requirement.to_string(),
+                (None, Some(pinned)) => format!("={}", pinned.version),
+                (None, None) => anyhow::bail!("Dependency '{}' ist nicht aufgelöst", key),
+            };
+            Ok((name, requirement))
+        })
+        .collect::<Result<_>>()?;
+
+    let mut source = WithPinned {
+        inner: source,
+        pinned,
+    };
+    Solver::new(
+        &mut source,
+        &config.package.name,
+        root_version,
+        root_dependencies,
+        preferred,
+    )
+    .solve()
 }
@@ -0,0 +1,1024 @@
+// Versionsauflösung nach PubGrub
+//
+// Der Solver arbeitet mit Inkompatibilitäten (Mengen von Termen, die nicht gleichzeitig
+// gelten dürfen), leitet per Unit Propagation neue Terme ab und lernt bei Konflikten
+// neue Inkompatibilitäten, statt blind zurückzusetzen. Da die Versionen jedes Packages
+// bekannt sind, sind Versionsmengen einfach endliche Mengen. Aus dem Ableitungsbaum
+// der finalen Inkompatibilität entsteht eine lesbare Erklärung des Konflikts.
+
+use crate::source::PackageSource;
+use anyhow::Result;
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Aufgelöstes Package mit den exakten Versionen seiner Dependencies
+#[derive(Debug, Clone)]
+pub struct Resolved {
+    pub version: Version,
+    pub dependencies: BTreeMap<String, Version>,
+}
+
+/// Aussage über ein Package: gewählt mit einer Version aus `versions` (positiv)
+/// bzw. nicht gewählt oder mit einer Version außerhalb von `versions` (negativ)
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    package: String,
+    positive: bool,
+    versions: BTreeSet<Version>,
+}
+
+impl Term {
+    fn new(package: &str, positive: bool, versions: BTreeSet<Version>) -> Self {
+        Term {
+            package: package.to_string(),
+            positive,
+            versions,
+        }
+    }
+
+    /// Term ohne Einschränkung
+    fn any(package: &str) -> Self {
+        Term::new(package, false, BTreeSet::new())
+    }
+
+    fn negate(&self) -> Self {
+        Term::new(&self.package, !self.positive, self.versions.clone())
+    }
+
+    fn intersect(&self, other: &Term) -> Term {
+        let (a, b) = (&self.versions, &other.versions);
+        match (self.positive, other.positive) {
+            (true, true) => Term::new(&self.package, true, a.intersection(b).cloned().collect()),
+            (true, false) => Term::new(&self.package, true, a.difference(b).cloned().collect()),
+            (false, true) => Term::new(&self.package, true, b.difference(a).cloned().collect()),
+            (false, false) => Term::new(&self.package, false, a.union(b).cloned().collect()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.positive && self.versions.is_empty()
+    }
+
+    fn is_any(&self) -> bool {
+        !self.positive && self.versions.is_empty()
+    }
+
+    fn subset_of(&self, other: &Term) -> bool {
+        self.intersect(&other.negate()).is_empty()
+    }
+
+    fn disjoint(&self, other: &Term) -> bool {
+        self.intersect(other).is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Cause {
+    /// Das Projekt selbst muss gewählt werden
+    Root,
+    /// `depender version` benötigt `dependency requirement`
+    Dependency {
+        depender: String,
+        version: Version,
+        dependency: String,
+        requirement: String,
+    },
+    /// Version kann nicht geladen werden (Netzwerk, ungültiges Manifest, …)
+    Unavailable(String),
+    /// Gelernt aus zwei anderen Inkompatibilitäten
+    Derived(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+struct Incompatibility {
+    terms: Vec<Term>,
+    cause: Cause,
+}
+
+struct Assignment {
+    term: Term,
+    level: usize,
+    /// `None` für Entscheidungen, sonst die Inkompatibilität, aus der abgeleitet wurde
+    cause: Option<usize>,
+}
+
+enum Relation {
+    Satisfied,
+    AlmostSatisfied(Term),
+    Contradicted,
+    Inconclusive,
+}
+
+pub struct Solver<'a, S: PackageSource> {
+    source: &'a mut S,
+    root: String,
+    root_version: Version,
+    root_dependencies: BTreeMap<String, String>,
+    preferred: HashMap<String, Version>,
+    universes: HashMap<String, Vec<Version>>,
+    unavailable: HashMap<String, String>,
+    incompatibilities: Vec<Incompatibility>,
+    by_package: HashMap<String, Vec<usize>>,
+    assignments: Vec<Assignment>,
+    level: usize,
+    expanded: HashSet<(String, Version)>,
+    dependencies: HashMap<(String, Version), BTreeMap<String, String>>,
+}
+
+impl<'a, S: PackageSource> Solver<'a, S> {
+    /// `preferred` enthält bevorzugte Versionen (z.B. aus `velin.lock`), die gewählt
+    /// werden, solange sie mit den Anforderungen vereinbar sind
+    pub fn new(
+        source: &'a mut S,
+        root: &str,
+        root_version: Version,
+        root_dependencies: BTreeMap<String, String>,
+        preferred: HashMap<String, Version>,
+    ) -> Self {
+        Solver {
+            source,
+            root: root.to_string(),
+            root_version,
+            root_dependencies,
+            preferred,
+            universes: HashMap::new(),
+            unavailable: HashMap::new(),
+            incompatibilities: Vec::new(),
+            by_package: HashMap::new(),
+            assignments: Vec::new(),
+            level: 0,
+            expanded: HashSet::new(),
+            dependencies: HashMap::new(),
+        }
+    }
+
+    /// Löst alle Dependencies auf; bei einem Konflikt enthält der Fehler dessen Erklärung
+    pub fn solve(mut self) -> Result<BTreeMap<String, Resolved>> {
+        let root_set = BTreeSet::from([self.root_version.clone()]);
+        self.universes
+            .insert(self.root.clone(), vec![self.root_version.clone()]);
+        let root = self.add(Incompatibility {
+            terms: vec![Term::new(&self.root, false, root_set)],
+            cause: Cause::Root,
+        });
+        self.register(root);
+
+        let mut next = self.root.clone();
+        loop {
+            if let Err(conflict) = self.propagate(next) {
+                anyhow::bail!("{}", self.explain(conflict));
+            }
+            match self.decide() {
+                Some(package) => next = package,
+                None => break,
+            }
+        }
+
+        let decisions: HashMap<String, Version> = self
+            .assignments
+            .iter()
+            .filter(|a| a.cause.is_none() && a.term.package != self.root)
+            .map(|a| {
+                (
+                    a.term.package.clone(),
+                    a.term.versions.iter().next().unwrap().clone(),
+                )
+            })
+            .collect();
+
+        let mut resolved = BTreeMap::new();
+        for (package, version) in &decisions {
+            let dependencies = self.dependencies[&(package.clone(), version.clone())]
+                .keys()
+                .filter_map(|dep| Some((dep.clone(), decisions.get(dep)?.clone())))
+                .collect();
+            resolved.insert(
+                package.clone(),
+                Resolved {
+                    version: version.clone(),
+                    dependencies,
+                },
+            );
+        }
+        Ok(resolved)
+    }
+
+    /// Bekannte Versionen eines Packages (aufsteigend)
+    fn universe(&mut self, package: &str) -> &Vec<Version> {
+        if !self.universes.contains_key(package) {
+            let mut versions = match self.source.versions(package) {
+                Ok(versions) => versions,
+                Err(e) => {
+                    self.unavailable
+                        .insert(package.to_string(), format!("{:#}", e));
+                    Vec::new()
+                }
+            };
+            versions.sort();
+            versions.dedup();
+            self.universes.insert(package.to_string(), versions);
+        }
+        &self.universes[package]
+    }
+
+    fn add(&mut self, incompatibility: Incompatibility) -> usize {
+        self.incompatibilities.push(incompatibility);
+        self.incompatibilities.len() - 1
+    }
+
+    /// Macht eine Inkompatibilität für die Unit Propagation sichtbar
+    fn register(&mut self, id: usize) {
+        for term in &self.incompatibilities[id].terms {
+            self.by_package
+                .entry(term.package.clone())
+                .or_default()
+                .push(id);
+        }
+    }
+
+    fn accumulated(&self, package: &str) -> Term {
+        self.assignments
+            .iter()
+            .filter(|a| a.term.package == package)
+            .fold(Term::any(package), |acc, a| acc.intersect(&a.term))
+    }
+
+    fn relation(&self, id: usize) -> Relation {
+        let mut unsatisfied = None;
+        for term in &self.incompatibilities[id].terms {
+            let accumulated = self.accumulated(&term.package);
+            if accumulated.subset_of(term) {
+                continue;
+            }
+            if accumulated.disjoint(term) {
+                return Relation::Contradicted;
+            }
+            if unsatisfied.is_some() {
+                return Relation::Inconclusive;
+            }
+            unsatisfied = Some(term.clone());
+        }
+        match unsatisfied {
+            None => Relation::Satisfied,
+            Some(term) => Relation::AlmostSatisfied(term),
+        }
+    }
+
+    fn derive(&mut self, term: Term, cause: usize) {
+        self.assignments.push(Assignment {
+            term,
+            level: self.level,
+            cause: Some(cause),
+        });
+    }
+
+    /// Unit Propagation; `Err` enthält die Inkompatibilität, an der die Auflösung scheitert
+    fn propagate(&mut self, package: String) -> Result<(), usize> {
+        let mut changed = vec![package];
+        while let Some(package) = changed.pop() {
+            let ids = self.by_package.get(&package).cloned().unwrap_or_default();
+            for id in ids.into_iter().rev() {
+                match self.relation(id) {
+                    Relation::Satisfied => {
+                        let cause = self.resolve_conflict(id)?;
+                        let Relation::AlmostSatisfied(term) = self.relation(cause) else {
+                            unreachable!(
+                                "gelernte Inkompatibilität ist nach dem Zurücksetzen fast erfüllt"
+                            );
+                        };
+                        changed.clear();
+                        changed.push(term.package.clone());
+                        self.derive(term.negate(), cause);
+                        break;
+                    }
+                    Relation::AlmostSatisfied(term) => {
+                        changed.push(term.package.clone());
+                        self.derive(term.negate(), id);
+                    }
+                    Relation::Contradicted | Relation::Inconclusive => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Index der frühesten Zuweisung, ab der `term` erfüllt ist
+    fn satisfier(&self, term: &Term) -> usize {
+        let mut accumulated = Term::any(&term.package);
+        for (index, assignment) in self.assignments.iter().enumerate() {
+            if assignment.term.package != term.package {
+                continue;
+            }
+            accumulated = accumulated.intersect(&assignment.term);
+            if accumulated.subset_of(term) {
+                return index;
+            }
+        }
+        unreachable!("Term {} ist nicht erfüllt", term.package)
+    }
+
+    fn is_terminal(&self, id: usize) -> bool {
+        match self.incompatibilities[id].terms.as_slice() {
+            [] => true,
+            [term] => term.positive && term.package == self.root,
+            _ => false,
+        }
+    }
+
+    /// Konfliktanalyse: lernt aus einer erfüllten Inkompatibilität eine neue und setzt
+    /// so weit zurück, dass diese nur noch fast erfüllt ist
+    fn resolve_conflict(&mut self, mut id: usize) -> Result<usize, usize> {
+        let original = id;
+        loop {
+            if self.is_terminal(id) {
+                return Err(id);
+            }
+
+            let terms = self.incompatibilities[id].terms.clone();
+            let positions: Vec<usize> = terms.iter().map(|t| self.satisfier(t)).collect();
+            let (slot, &index) = positions
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, &index)| index)
+                .unwrap();
+            let term = &terms[slot];
+            let (satisfier_term, satisfier_level, satisfier_cause) = {
+                let satisfier = &self.assignments[index];
+                (satisfier.term.clone(), satisfier.level, satisfier.cause)
+            };
+
+            let mut previous_level = positions
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != slot)
+                .map(|(_, &i)| self.assignments[i].level)
+                .max()
+                .unwrap_or(1)
+                .max(1);
+
+            // Erfüllt der Satisfier den Term nicht allein, zählt die Zuweisung davor mit
+            let alone = satisfier_term.subset_of(term);
+            if !alone {
+                let mut accumulated = Term::any(&term.package);
+                for assignment in &self.assignments[..index] {
+                    if assignment.term.package != term.package {
+                        continue;
+                    }
+                    accumulated = accumulated.intersect(&assignment.term);
+                    if accumulated.intersect(&satisfier_term).subset_of(term) {
+                        previous_level = previous_level.max(assignment.level);
+                        break;
+                    }
+                }
+            }
+
+            if satisfier_cause.is_none() || previous_level != satisfier_level {
+                if id != original {
+                    self.register(id);
+                }
+                self.assignments.retain(|a| a.level <= previous_level);
+                self.level = previous_level;
+                return Ok(id);
+            }
+
+            // Neue Inkompatibilität aus beiden Ursachen ohne das Package des Satisfiers
+            let cause = satisfier_cause.unwrap();
+            let mut merged: Vec<Term> = Vec::new();
+            let cause_terms = self.incompatibilities[cause].terms.iter();
+            for t in terms.iter().chain(cause_terms) {
+                if t.package == term.package {
+                    continue;
+                }
+                match merged.iter_mut().find(|m| m.package == t.package) {
+                    Some(existing) => *existing = existing.intersect(t),
+                    None => merged.push(t.clone()),
+                }
+            }
+            if !alone {
+                merged.push(satisfier_term.intersect(&term.negate()).negate());
+            }
+            merged.retain(|t| !t.is_any());
+
+            id = self.add(Incompatibility {
+                terms: merged,
+                cause: Cause::Derived(id, cause),
+            });
+        }
+    }
+
+    /// Wählt das nächste Package (mit den wenigsten möglichen Versionen) und dessen
+    /// höchste erlaubte Version; `None`, wenn alles entschieden ist
+    fn decide(&mut self) -> Option<String> {
+        let decided: HashSet<&str> = self
+            .assignments
+            .iter()
+            .filter(|a| a.cause.is_none())
+            .map(|a| a.term.package.as_str())
+            .collect();
+
+        let mut candidate: Option<Term> = None;
+        let mut seen = HashSet::new();
+        for assignment in &self.assignments {
+            let package = assignment.term.package.as_str();
+            if decided.contains(package) || !seen.insert(package) {
+                continue;
+            }
+            let accumulated = self.accumulated(package);
+            if !accumulated.positive {
+                continue;
+            }
+            if candidate
+                .as_ref()
+                .is_none_or(|c| accumulated.versions.len() < c.versions.len())
+            {
+                candidate = Some(accumulated);
+            }
+        }
+
+        let candidate = candidate?;
+        let package = candidate.package.clone();
+        let version = self
+            .preferred
+            .get(&package)
+            .filter(|v| candidate.versions.contains(v))
+            .or_else(|| candidate.versions.iter().next_back())
+            .expect("positiver Term ist nie leer")
+            .clone();
+
+        let key = (package.clone(), version.clone());
+        if self.expanded.insert(key.clone()) {
+            let dependencies = if package == self.root {
+                Ok(self.root_dependencies.clone())
+            } else {
+                self.source.dependencies(&package, &version)
+            };
+            let this = Term::new(&package, true, BTreeSet::from([version.clone()]));
+            match dependencies {
+                Ok(dependencies) => {
+                    for (dependency, requirement) in &dependencies {
+                        if *dependency == package {
+                            continue;
+                        }
+                        let id = match VersionReq::parse(requirement) {
+                            Ok(req) => {
+                                let matching: BTreeSet<Version> = self
+                                    .universe(dependency)
+                                    .iter()
+                                    .filter(|v| req.matches(v))
+                                    .cloned()
+                                    .collect();
+                                let mut terms = vec![this.clone()];
+                                // Ohne passende Version schließt die Dependency die Version selbst aus
+                                if !matching.is_empty() {
+                                    terms.push(Term::new(dependency, false, matching));
+                                }
+                                self.add(Incompatibility {
+                                    terms,
+                                    cause: Cause::Dependency {
+                                        depender: package.clone(),
+                                        version: version.clone(),
+                                        dependency: dependency.clone(),
+                                        requirement: requirement.clone(),
+                                    },
+                                })
+                            }
+                            Err(e) => self.add(Incompatibility {
+                                terms: vec![this.clone()],
+                                cause: Cause::Unavailable(format!(
+                                    "ungültige Anforderung '{}' für {}: {}",
+                                    requirement, dependency, e
+                                )),
+                            }),
+                        };
+                        self.register(id);
+                    }
+                    self.dependencies.insert(key, dependencies);
+                }
+                Err(e) => {
+                    let id = self.add(Incompatibility {
+                        terms: vec![this],
+                        cause: Cause::Unavailable(format!("{:#}", e)),
+                    });
+                    self.register(id);
+                    // Keine Entscheidung: die Propagation schließt die Version aus
+                    return Some(package);
+                }
+            }
+        }
+
+        self.level += 1;
+        self.assignments.push(Assignment {
+            term: Term::new(&package, true, BTreeSet::from([version])),
+            level: self.level,
+            cause: None,
+        });
+        Some(package)
+    }
+
+    // --- Erklärung ---
+
+    fn explain(&self, id: usize) -> String {
+        let mut counts = HashMap::new();
+        self.count_references(id, &mut counts);
+
+        let mut report = Report {
+            lines: Vec::new(),
+            numbers: HashMap::new(),
+            counts,
+        };
+        match self.incompatibilities[id].cause {
+            Cause::Derived(..) => self.visit(id, &mut report),
+            _ => report.lines.push(format!("{}.", self.statement(id))),
+        }
+
+        let mut message = format!(
+            "Die Dependencies von {} lassen sich nicht auflösen:",
+            self.root
+        );
+        for line in report.lines {
+            message.push_str("\n  ");
+            message.push_str(&line);
+        }
+        message
+    }
+
+    fn count_references(&self, id: usize, counts: &mut HashMap<usize, usize>) {
+        if let Cause::Derived(a, b) = self.incompatibilities[id].cause {
+            for child in [a, b] {
+                let count = counts.entry(child).or_insert(0);
+                *count += 1;
+                if *count == 1 {
+                    self.count_references(child, counts);
+                }
+            }
+        }
+    }
+
+    fn is_derived(&self, id: usize) -> bool {
+        matches!(self.incompatibilities[id].cause, Cause::Derived(..))
+    }
+
+    fn visit(&self, id: usize, report: &mut Report) {
+        let Cause::Derived(a, b) = self.incompatibilities[id].cause else {
+            return;
+        };
+        let conclusion = self.conclusion(id);
+        let text = match (self.is_derived(a), self.is_derived(b)) {
+            (true, true) => {
+                for child in [a, b] {
+                    if !report.numbers.contains_key(&child) {
+                        self.visit(child, report);
+                        report.number_last(child);
+                    }
+                }
+                format!(
+                    "{}{} und {}{}, daher {}.",
+                    self.statement(a),
+                    report.reference(a),
+                    self.statement(b),
+                    report.reference(b),
+                    conclusion
+                )
+            }
+            (true, false) | (false, true) => {
+                let (derived, external) = if self.is_derived(a) { (a, b) } else { (b, a) };
+                if report.numbers.contains_key(&derived) {
+                    format!(
+                        "{} und {}{}, daher {}.",
+                        self.statement(external),
+                        self.statement(derived),
+                        report.reference(derived),
+                        conclusion
+                    )
+                } else {
+                    self.visit(derived, report);
+                    format!(
+                        "Außerdem gilt: {}, daher {}.",
+                        self.statement(external),
+                        conclusion
+                    )
+                }
+            }
+            (false, false) => format!(
+                "{} und {}, daher {}.",
+                self.statement(a),
+                self.statement(b),
+                conclusion
+            ),
+        };
+        report.push(id, text);
+    }
+
+    /// Aussage einer Inkompatibilität als Hauptsatz
+    fn statement(&self, id: usize) -> String {
+        let incompatibility = &self.incompatibilities[id];
+        match &incompatibility.cause {
+            Cause::Root => format!("{} ist das Projekt", self.root),
+            Cause::Dependency {
+                depender,
+                version,
+                dependency,
+                requirement,
+            } => {
+                let mut text = format!(
+                    "{} benötigt {} {}",
+                    self.package_version(depender, version),
+                    dependency,
+                    requirement
+                );
+                if incompatibility.terms.len() == 1 {
+                    match self.unavailable.get(dependency) {
+                        Some(error) => text.push_str(&format!(
+                            ", aber {} ist nicht verfügbar ({})",
+                            dependency, error
+                        )),
+                        None if self.universes[dependency].is_empty() => text.push_str(&format!(
+                            ", aber von {} gibt es keine Versionen",
+                            dependency
+                        )),
+                        None => text.push_str(&format!(
+                            ", aber keine Version von {} erfüllt das",
+                            dependency
+                        )),
+                    }
+                }
+                text
+            }
+            Cause::Unavailable(error) => {
+                format!(
+                    "{} lässt sich nicht laden ({})",
+                    self.term(&incompatibility.terms[0]),
+                    error
+                )
+            }
+            Cause::Derived(..) => match incompatibility.terms.as_slice() {
+                [] => "keine Auswahl ist möglich".to_string(),
+                [term] if term.positive && term.package == self.root => {
+                    format!(
+                        "die Dependencies von {} lassen sich nicht erfüllen",
+                        self.root
+                    )
+                }
+                [term] if term.positive => format!("{} ist nicht verwendbar", self.term(term)),
+                [term] => format!("{} wird benötigt", self.term(&term.negate())),
+                [a, b] if a.positive != b.positive => {
+                    let (positive, negative) = if a.positive { (a, b) } else { (b, a) };
+                    format!(
+                        "{} benötigt {}",
+                        self.term(positive),
+                        self.term(&negative.negate())
+                    )
+                }
+                [a, b] if a.positive && (a.package == self.root || b.package == self.root) => {
+                    let other = if a.package == self.root { b } else { a };
+                    format!("{} kann {} nicht verwenden", self.root, self.term(other))
+                }
+                [a, b] if a.positive => {
+                    format!("{} und {} sind unvereinbar", self.term(a), self.term(b))
+                }
+                terms => format!("{} sind unvereinbar", self.term_list(terms)),
+            },
+        }
+    }
+
+    /// Schlussfolgerung als Nebensatz nach „daher“
+    fn conclusion(&self, id: usize) -> String {
+        match self.incompatibilities[id].terms.as_slice() {
+            [] => "ist keine Auswahl möglich".to_string(),
+            [term] if term.positive && term.package == self.root => {
+                format!(
+                    "lassen sich die Dependencies von {} nicht erfüllen",
+                    self.root
+                )
+            }
+            [term] if term.positive => format!("ist {} nicht verwendbar", self.term(term)),
+            [term] => format!("wird {} benötigt", self.term(&term.negate())),
+            [a, b] if a.positive != b.positive => {
+                let (positive, negative) = if a.positive { (a, b) } else { (b, a) };
+                format!(
+                    "benötigt {} {}",
+                    self.term(positive),
+                    self.term(&negative.negate())
+                )
+            }
+            [a, b] if a.positive && (a.package == self.root || b.package == self.root) => {
+                let other = if a.package == self.root { b } else { a };
+                format!("kann {} {} nicht verwenden", self.root, self.term(other))
+            }
+            [a, b] if a.positive => {
+                format!("sind {} und {} unvereinbar", self.term(a), self.term(b))
+            }
+            terms => format!("sind {} unvereinbar", self.term_list(terms)),
+        }
+    }
+
+    fn term_list(&self, terms: &[Term]) -> String {
+        let rendered: Vec<String> = terms
+            .iter()
+            .map(|t| {
+                if t.positive {
+                    self.term(t)
+                } else {
+                    format!("nicht {}", self.term(&t.negate()))
+                }
+            })
+            .collect();
+        let (last, rest) = rendered.split_last().unwrap();
+        format!("{} und {}", rest.join(", "), last)
+    }
+
+    fn package_version(&self, package: &str, version: &Version) -> String {
+        if package == self.root {
+            package.to_string()
+        } else {
+            format!("{} {}", package, version)
+        }
+    }
+
+    /// Positiver Term als „package versionen“
+    fn term(&self, term: &Term) -> String {
+        if term.package == self.root {
+            return term.package.clone();
+        }
+        let universe = self
+            .universes
+            .get(&term.package)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        match render_versions(&term.versions, universe) {
+            Some(versions) => format!("{} {}", term.package, versions),
+            None => term.package.clone(),
+        }
+    }
+}
+
+struct Report {
+    lines: Vec<String>,
+    numbers: HashMap<usize, usize>,
+    counts: HashMap<usize, usize>,
+}
+
+impl Report {
+    fn push(&mut self, id: usize, mut line: String) {
+        if self.counts.get(&id).copied().unwrap_or(0) > 1 && !self.numbers.contains_key(&id) {
+            let number = self.numbers.len() + 1;
+            line.push_str(&format!(" ({})", number));
+            self.numbers.insert(id, number);
+        }
+        self.lines.push(line);
+    }
+
+    /// Nummeriert die zuletzt geschriebene Zeile, damit spätere Zeilen darauf verweisen können
+    fn number_last(&mut self, id: usize) {
+        if self.numbers.contains_key(&id) {
+            return;
+        }
+        let number = self.numbers.len() + 1;
+        if let Some(line) = self.lines.last_mut() {
+            line.push_str(&format!(" ({})", number));
+        }
+        self.numbers.insert(id, number);
+    }
+
+    fn reference(&self, id: usize) -> String {
+        self.numbers
+            .get(&id)
+            .map(|n| format!(" ({})", n))
+            .unwrap_or_default()
+    }
+}
+
+/// Versionsmenge als Bereiche über die bekannten Versionen (`>=1.0.0, <2.0.0`);
+/// `None`, wenn alle Versionen enthalten sind
+fn render_versions(versions: &BTreeSet<Version>, universe: &[Version]) -> Option<String> {
+    if universe.iter().all(|v| versions.contains(v)) {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+    let mut start: Option<usize> = None;
+    for index in 0..=universe.len() {
+        let included = universe.get(index).is_some_and(|v| versions.contains(v));
+        match (included, start) {
+            (true, None) => start = Some(index),
+            (false, Some(first)) => {
+                let last = index - 1;
+                ranges.push(if first == last {
+                    universe[first].to_string()
+                } else {
+                    let mut bounds = Vec::new();
+                    if first > 0 {
+                        bounds.push(format!(">={}", universe[first]));
+                    }
+                    if let Some(next) = universe.get(index) {
+                        bounds.push(format!("<{}", next));
+                    }
+                    bounds.join(", ")
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    Some(ranges.join(" oder "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Packages im Speicher: Name → Version → Dependencies
+    #[derive(Default)]
+    struct MemorySource {
+        packages: HashMap<String, BTreeMap<Version, BTreeMap<String, String>>>,
+        broken: HashSet<(String, Version)>,
+    }
+
+    impl MemorySource {
+        fn publish(&mut self, package: &str, version: &str, dependencies: &[(&str, &str)]) {
+            self.packages
+                .entry(package.to_string())
+                .or_default()
+                .insert(
+                    Version::parse(version).unwrap(),
+                    dependencies
+                        .iter()
+                        .map(|(name, req)| (name.to_string(), req.to_string()))
+                        .collect(),
+                );
+        }
+    }
+
+    impl PackageSource for MemorySource {
+        fn versions(&mut self, package: &str) -> Result<Vec<Version>> {
+            Ok(self
+                .packages
+                .get(package)
+                .map(|versions| versions.keys().cloned().collect())
+                .unwrap_or_default())
+        }
+
+        fn dependencies(
+            &mut self,
+            package: &str,
+            version: &Version,
+        ) -> Result<BTreeMap<String, String>> {
+            if self
+                .broken
+                .contains(&(package.to_string(), version.clone()))
+            {
+                anyhow::bail!("Manifest ist ungültig");
+            }
+            Ok(self.packages[package][version].clone())
+        }
+    }
+
+    fn solve(
+        source: &mut MemorySource,
+        dependencies: &[(&str, &str)],
+        preferred: &[(&str, &str)],
+    ) -> Result<BTreeMap<String, String>> {
+        let solver = Solver::new(
+            source,
+            "app",
+            Version::new(0, 1, 0),
+            dependencies
+                .iter()
+                .map(|(name, req)| (name.to_string(), req.to_string()))
+                .collect(),
+            preferred
+                .iter()
+                .map(|(name, version)| (name.to_string(), Version::parse(version).unwrap()))
+                .collect(),
+        );
+        Ok(solver
+            .solve()?
+            .into_iter()
+            .map(|(name, resolved)| (name, resolved.version.to_string()))
+            .collect())
+    }
+
+    fn versions(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_picks_highest_matching_versions() {
+        let mut source = MemorySource::default();
+        source.publish("http", "1.0.0", &[]);
+        source.publish("http", "1.4.0", &[("json", "^1.0")]);
+        source.publish("http", "2.0.0", &[]);
+        source.publish("json", "1.0.0", &[]);
+        source.publish("json", "1.2.0", &[]);
+
+        let solver = Solver::new(
+            &mut source,
+            "app",
+            Version::new(0, 1, 0),
+            BTreeMap::from([("http".to_string(), "^1.0".to_string())]),
+            HashMap::new(),
+        );
+        let resolved = solver.solve().unwrap();
+        assert_eq!(resolved["http"].version, Version::new(1, 4, 0));
+        assert_eq!(
+            resolved["http"].dependencies,
+            BTreeMap::from([("json".to_string(), Version::new(1, 2, 0))])
+        );
+        assert!(resolved["json"].dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_backtracks_from_a_conflicting_decision() {
+        // `a` hat weniger Versionen und wird zuerst entschieden; die höchste Version
+        // zieht `c 2` nach, mit dem keine Version von `b` vereinbar ist
+        let mut source = MemorySource::default();
+        source.publish("a", "1.0.0", &[("c", "^1.0")]);
+        source.publish("a", "1.1.0", &[("c", "^2.0")]);
+        source.publish("a", "1.2.0", &[("c", "^2.0")]);
+        for version in ["1.0.0", "1.1.0", "1.2.0", "1.3.0"] {
+            source.publish("b", version, &[("c", "^1.0")]);
+        }
+        source.publish("c", "1.0.0", &[]);
+        source.publish("c", "2.0.0", &[]);
+
+        let resolved = solve(&mut source, &[("a", "^1.0"), ("b", "^1.0")], &[]).unwrap();
+        assert_eq!(
+            resolved,
+            versions(&[("a", "1.0.0"), ("b", "1.3.0"), ("c", "1.0.0")])
+        );
+    }
+
+    #[test]
+    fn test_prefers_locked_versions_while_they_match() {
+        let mut source = MemorySource::default();
+        source.publish("log", "1.0.0", &[]);
+        source.publish("log", "1.1.0", &[]);
+        source.publish("log", "2.0.0", &[]);
+
+        let locked = solve(&mut source, &[("log", "^1.0")], &[("log", "1.0.0")]).unwrap();
+        assert_eq!(locked, versions(&[("log", "1.0.0")]));
+
+        // Passt der Lock-Eintrag nicht mehr zur Anforderung, gilt wieder die höchste Version
+        let outdated = solve(&mut source, &[("log", "^1.0")], &[("log", "2.0.0")]).unwrap();
+        assert_eq!(outdated, versions(&[("log", "1.1.0")]));
+    }
+
+    #[test]
+    fn test_conflict_is_explained() {
+        let mut source = MemorySource::default();
+        source.publish("a", "1.0.0", &[("c", "^1.0")]);
+        source.publish("b", "1.0.0", &[("c", "^2.0")]);
+        source.publish("c", "1.0.0", &[]);
+        source.publish("c", "2.0.0", &[]);
+
+        let error = solve(&mut source, &[("a", "^1.0"), ("b", "^1.0")], &[])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Die Dependencies von app lassen sich nicht auflösen:\n  \
+             b 1.0.0 benötigt c ^2.0 und a 1.0.0 benötigt c ^1.0, daher sind b und a unvereinbar.\n  \
+             Außerdem gilt: app benötigt a ^1.0, daher kann app b nicht verwenden.\n  \
+             Außerdem gilt: app benötigt b ^1.0, daher lassen sich die Dependencies von app nicht erfüllen."
+        );
+    }
+
+    #[test]
+    fn test_missing_and_unloadable_packages_are_reported() {
+        let mut source = MemorySource::default();
+        let error = solve(&mut source, &[("ghost", "^1.0")], &[])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Die Dependencies von app lassen sich nicht auflösen:\n  \
+             app benötigt ghost ^1.0, aber von ghost gibt es keine Versionen."
+        );
+
+        source.publish("a", "1.0.0", &[]);
+        source.publish("a", "1.1.0", &[]);
+        source
+            .broken
+            .insert(("a".to_string(), Version::new(1, 1, 0)));
+        // Die kaputte Version wird übersprungen
+        let resolved = solve(&mut source, &[("a", "^1.0")], &[]).unwrap();
+        assert_eq!(resolved, versions(&[("a", "1.0.0")]));
+
+        source
+            .broken
+            .insert(("a".to_string(), Version::new(1, 0, 0)));
+        let error = solve(&mut source, &[("a", "^1.0")], &[])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "Die Dependencies von app lassen sich nicht auflösen:\n  \
+             a 1.0.0 lässt sich nicht laden (Manifest ist ungültig) und \
+             a 1.1.0 lässt sich nicht laden (Manifest ist ungültig), daher ist a nicht verwendbar.\n  \
+             Außerdem gilt: app benötigt a ^1.0, daher lassen sich die Dependencies von app nicht erfüllen."
+        );
+    }
+}
@@ -0,0 +1,513 @@
+// Package-Quellen - Liefert verfügbare Versionen, Manifeste und Inhalte für den Resolver
+
+use crate::resolver::DependencySpec;
+use anyhow::{Context, Result};
+use git2::{ErrorCode, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use semver::Version;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Quelle für Package-Versionen und deren Dependencies
+pub trait PackageSource {
+    /// Alle veröffentlichten Versionen eines Packages
+    fn versions(&mut self, package: &str) -> Result<Vec<Version>>;
+
+    /// Dependencies (Name → Versions-Anforderung) einer bestimmten Version
+    fn dependencies(&mut self, package: &str, version: &Version)
+        -> Result<BTreeMap<String, String>>;
+}
+
+/// Quelle, aus der sich aufgelöste Versionen auch installieren lassen
+pub trait PackageStore: PackageSource {
+    /// Entpackt eine Version nach `target` und gibt ihre Checksumme zurück
+    ///
+    /// Ist `expected` gesetzt (aus `velin.lock`), muss der Inhalt dazu passen.
+    fn checkout(
+        &mut self,
+        package: &str,
+        version: &Version,
+        target: &Path,
+        expected: Option<&str>,
+    ) -> Result<String>;
+
+    /// Herkunft für `velin.lock` (z.B. `git+https://…#1.2.0`)
+    fn source_id(&self, package: &str, version: &Version) -> Result<String>;
+}
+
+/// Manifest eines Packages - nur die für die Auflösung relevanten Teile
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub package: Option<ManifestPackage>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, DependencySpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestPackage {
+    pub version: String,
+}
+
+impl Manifest {
+    pub fn parse(content: &str, origin: &str) -> Result<Self> {
+        toml::from_str(content).with_context(|| format!("velin.toml von {} ist ungültig", origin))
+    }
+
+    /// Registry-Anforderungen (Name → Versions-Anforderung)
+    ///
+    /// Veröffentlichte Versionen dürfen keine Pfad- oder Git-Dependencies haben,
+    /// weil diese auf anderen Rechnern nicht reproduzierbar sind.
+    pub fn requirements(&self, origin: &str) -> Result<BTreeMap<String, String>> {
+        let mut requirements = BTreeMap::new();
+        for (key, spec) in &self.dependencies {
+            let Some(requirement) = spec.requirement() else {
+                anyhow::bail!(
+                    "{} hat die Dependency '{}' ({}); veröffentlichte Versionen dürfen nur Registry-Dependencies haben",
+                    origin,
+                    key,
+                    spec
+                );
+            };
+            requirements.insert(spec.package(key).to_string(), requirement.to_string());
+        }
+        Ok(requirements)
+    }
+}
+
+/// Git-basierte Quelle
+///
+/// Versionen sind Tags (`v1.2.0` oder `1.2.0`), die Dependencies stehen in der
+/// `velin.toml` des jeweiligen Tags. Repositories werden als Bare-Clones unter
+/// `cache_dir` gehalten, damit nur Tags und nicht jeder Branch geladen werden.
+pub struct GitSource {
+    cache_dir: PathBuf,
+    repositories: HashMap<String, Repository>,
+    tags: HashMap<String, BTreeMap<Version, String>>,
+}
+
+impl GitSource {
+    pub fn new(cache_dir: impl Into<PathBuf>) -> Self {
+        GitSource {
+            cache_dir: cache_dir.into(),
+            repositories: HashMap::new(),
+            tags: HashMap::new(),
+        }
+    }
+
+    /// Repository-URL eines Packages (z.B. `github.com/user/repo`)
+    pub fn url(package: &str) -> Result<String> {
+        if package.split('/').filter(|part| !part.is_empty()).count() < 3 {
+            anyhow::bail!(
+                "Ungültiges Package-Format: {}. Erwartet: github.com/user/repo",
+                package
+            );
+        }
+        Ok(format!("https://{}.git", package.trim_end_matches(".git")))
+    }
+
+    /// Lädt (oder aktualisiert) einen Bare-Clone unter dem Schlüssel `key`
+    fn open(&mut self, key: &str, url: &str, refspecs: &[&str]) -> Result<()> {
+        if self.repositories.contains_key(key) {
+            return Ok(());
+        }
+
+        let directory: String = key
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        let path = self.cache_dir.join(directory);
+        let repo = if path.exists() {
+            Repository::open_bare(&path)?
+        } else {
+            fs::create_dir_all(&path)?;
+            Repository::init_bare(&path)?
+        };
+
+        let fetched = repo
+            .remote_anonymous(url)
+            .and_then(|mut remote| remote.fetch(refspecs, None, None));
+        if let Err(e) = fetched {
+            // Offline weiterarbeiten, solange der Cache bereits Referenzen enthält
+            if repo.references()?.next().is_none() {
+                return Err(e).with_context(|| format!("{} konnte nicht geladen werden", url));
+            }
+            eprintln!(
+                "⚠ {} nicht erreichbar, verwende Cache: {}",
+                url,
+                e.message()
+            );
+        }
+
+        self.repositories.insert(key.to_string(), repo);
+        Ok(())
+    }
+
+    /// Lädt ein Registry-Package und liest dessen Versions-Tags
+    fn fetch(&mut self, package: &str) -> Result<()> {
+        if self.tags.contains_key(package) {
+            return Ok(());
+        }
+        self.open(package, &Self::url(package)?, &["+refs/tags/*:refs/tags/*"])?;
+
+        let mut tags = BTreeMap::new();
+        for name in self.repositories[package].tag_names(None)?.iter().flatten() {
+            if let Ok(version) = Version::parse(name.strip_prefix('v').unwrap_or(name)) {
+                tags.insert(version, name.to_string());
+            }
+        }
+        self.tags.insert(package.to_string(), tags);
+        Ok(())
+    }
+
+    /// Lädt ein per URL angegebenes Repository mit allen Branches und Tags
+    fn fetch_url(&mut self, url: &str) -> Result<()> {
+        self.open(
+            url,
+            url,
+            &["+refs/tags/*:refs/tags/*", "+refs/heads/*:refs/heads/*"],
+        )
+    }
+
+    /// Revision (Tag) einer veröffentlichten Version
+    fn tag(&mut self, package: &str, version: &Version) -> Result<String> {
+        self.fetch(package)?;
+        let tag = self.tags[package]
+            .get(version)
+            .with_context(|| format!("{} {} ist nicht veröffentlicht", package, version))?;
+        Ok(format!("refs/tags/{}", tag))
+    }
+
+    fn tree(&self, key: &str, revision: &str) -> Result<Oid> {
+        let tree = self.repositories[key]
+            .revparse_single(revision)
+            .with_context(|| format!("Revision '{}' nicht gefunden", revision))?
+            .peel_to_tree()?;
+        Ok(tree.id())
+    }
+
+    /// Inhalt der `velin.toml` einer Revision, falls vorhanden
+    fn manifest(&self, key: &str, revision: &str) -> Result<Option<String>> {
+        let repo = &self.repositories[key];
+        let entry = match repo
+            .find_tree(self.tree(key, revision)?)?
+            .get_path(Path::new("velin.toml"))
+        {
+            Ok(entry) => entry,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let blob = entry.to_object(repo)?.peel_to_blob()?;
+        Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+    }
+
+    /// Alle Paket-Dateien einer Revision in Tree-Reihenfolge (Pfad, Inhalt)
+    fn files(&self, key: &str, revision: &str) -> Result<Vec<(String, Vec<u8>)>> {
+        let repo = &self.repositories[key];
+        let tree = repo.find_tree(self.tree(key, revision)?)?;
+
+        let mut files = Vec::new();
+        let mut error = None;
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() != Some(ObjectType::Blob) {
+                return TreeWalkResult::Ok;
+            }
+            let path = format!("{}{}", dir, entry.name().unwrap_or_default());
+            if !is_package_file(&path) {
+                return TreeWalkResult::Ok;
+            }
+            match entry.to_object(repo).and_then(|object| object.peel_to_blob()) {
+                Ok(blob) => {
+                    files.push((path, blob.content().to_vec()));
+                    TreeWalkResult::Ok
+                }
+                Err(e) => {
+                    error = Some(e);
+                    TreeWalkResult::Abort
+                }
+            }
+        })?;
+        if let Some(e) = error {
+            return Err(e.into());
+        }
+        Ok(files)
+    }
+
+    /// Entpackt eine Revision nach `target` und gibt ihre Checksumme zurück
+    fn extract(
+        &self,
+        key: &str,
+        revision: &str,
+        label: &str,
+        target: &Path,
+        expected: Option<&str>,
+    ) -> Result<String> {
+        let files = self.files(key, revision)?;
+        let actual = verify(label, &files, expected)?;
+        write_files(target, &files)?;
+        Ok(actual)
+    }
+
+    /// `velin.toml` einer Git-Revision (`git` + `rev` in velin.toml)
+    pub fn manifest_at(&mut self, url: &str, rev: &str) -> Result<Option<String>> {
+        self.fetch_url(url)?;
+        self.manifest(url, rev)
+    }
+
+    /// Entpackt eine Git-Revision nach `target`
+    pub fn checkout_rev(
+        &mut self,
+        url: &str,
+        rev: &str,
+        target: &Path,
+        expected: Option<&str>,
+    ) -> Result<String> {
+        self.fetch_url(url)?;
+        let label = format!("{}@{}", url, rev);
+        self.extract(url, rev, &label, target, expected)
+    }
+}
+
+impl PackageSource for GitSource {
+    fn versions(&mut self, package: &str) -> Result<Vec<Version>> {
+        self.fetch(package)?;
+        Ok(self.tags[package].keys().cloned().collect())
+    }
+
+    fn dependencies(
+        &mut self,
+        package: &str,
+        version: &Version,
+    ) -> Result<BTreeMap<String, String>> {
+        let tag = self.tag(package, version)?;
+        let origin = format!("{} {}", package, version);
+        match self.manifest(package, &tag)? {
+            Some(content) => Manifest::parse(&content, &origin)?.requirements(&origin),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+}
+
+impl PackageStore for GitSource {
+    fn checkout(
+        &mut self,
+        package: &str,
+        version: &Version,
+        target: &Path,
+        expected: Option<&str>,
+    ) -> Result<String> {
+        let tag = self.tag(package, version)?;
+        let label = format!("{} {}", package, version);
+        self.extract(package, &tag, &label, target, expected)
+    }
+
+    fn source_id(&self, package: &str, version: &Version) -> Result<String> {
+        Ok(format!("git+{}#{}", Self::url(package)?, version))
+    }
+}
+
+/// Prüft die Checksumme gegen `velin.lock` und gibt sie zurück
+pub fn verify(label: &str, files: &[(String, Vec<u8>)], expected: Option<&str>) -> Result<String> {
+    let actual = checksum(files);
+    if let Some(expected) = expected {
+        if expected != actual {
+            anyhow::bail!(
+                "Checksumme von {} stimmt nicht mit velin.lock überein (erwartet {}, erhalten {}). \
+                 Wurde der Inhalt nachträglich verändert?",
+                label,
+                expected,
+                actual
+            );
+        }
+    }
+    Ok(actual)
+}
+
+/// Ersetzt den Inhalt von `target` durch `files`
+pub fn write_files(target: &Path, files: &[(String, Vec<u8>)]) -> Result<()> {
+    if target.exists() {
+        fs::remove_dir_all(target)?;
+    }
+    fs::create_dir_all(target)?;
+    for (path, content) in files {
+        let file = target.join(path);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, content)?;
+    }
+    Ok(())
+}
+
+/// Gehört eine Datei (Pfad mit `/`) zum Inhalt eines Packages?
+///
+/// Ausgenommen sind versteckte Einträge sowie `vendor/`, `target/` und
+/// `velin.lock` auf oberster Ebene.
+pub fn is_package_file(path: &str) -> bool {
+    let top_level_dir = path.split_once('/').map(|(first, _)| first);
+    !path.split('/').any(|segment| segment.starts_with('.'))
+        && !matches!(top_level_dir, Some("vendor" | "target"))
+        && path != "velin.lock"
+}
+
+/// Alle Dateien eines Package-Verzeichnisses (siehe [`is_package_file`]), nach Pfad sortiert
+pub fn read_files(dir: &Path) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut files = Vec::new();
+    let walker = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'));
+    for entry in walker {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(dir)?;
+        let path: Vec<_> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let path = path.join("/");
+        if is_package_file(&path) {
+            files.push((path, fs::read(entry.path())?));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// SHA-256 über Pfade und Inhalte aller Dateien eines Packages
+///
+/// Die Dateien werden nach Pfad sortiert, damit Git-Trees, Tarballs und
+/// entpackte Verzeichnisse dieselbe Checksumme ergeben.
+pub fn checksum(files: &[(String, Vec<u8>)]) -> String {
+    let mut sorted: Vec<_> = files.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut hasher = Sha256::new();
+    for (path, content) in sorted {
+        hasher.update(path.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(content);
+    }
+    format!("sha256:{}", hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(entries: &[(&str, &str)]) -> Vec<(String, Vec<u8>)> {
+        entries
+            .iter()
+            .map(|(path, content)| (path.to_string(), content.as_bytes().to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_checksum_depends_on_paths_and_contents_only() {
+        let a = files(&[("lib.velin", "fn a() {}"), ("velin.toml", "")]);
+        let reordered = files(&[("velin.toml", ""), ("lib.velin", "fn a() {}")]);
+        assert_eq!(checksum(&a), checksum(&reordered));
+        assert!(checksum(&a).starts_with("sha256:"));
+
+        let renamed = files(&[("main.velin", "fn a() {}"), ("velin.toml", "")]);
+        let changed = files(&[("lib.velin", "fn b() {}"), ("velin.toml", "")]);
+        assert_ne!(checksum(&a), checksum(&renamed));
+        assert_ne!(checksum(&a), checksum(&changed));
+
+        assert_eq!(verify("json 1.0.0", &a, None).unwrap(), checksum(&a));
+        assert_eq!(
+            verify("json 1.0.0", &a, Some(&checksum(&a))).unwrap(),
+            checksum(&a)
+        );
+        let error = verify("json 1.0.0", &a, Some(&checksum(&changed)))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Checksumme von json 1.0.0"), "{}", error);
+    }
+
+    #[test]
+    fn test_package_files_exclude_hidden_vendor_and_lockfile() {
+        assert!(is_package_file("lib.velin"));
+        assert!(is_package_file("src/vendor/util.velin"));
+        assert!(!is_package_file(".gitignore"));
+        assert!(!is_package_file("src/.cache/x"));
+        assert!(!is_package_file("vendor/acme_json/lib.velin"));
+        assert!(!is_package_file("target/app.rs"));
+        assert!(!is_package_file("velin.lock"));
+    }
+
+    #[test]
+    fn test_manifest_requirements_use_package_names() {
+        let manifest = Manifest::parse(
+            "[dependencies]\n\"acme/text\" = \"^1.0\"\njson = { package = \"acme/json\", version = \"2\" }\n",
+            "app",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.requirements("app").unwrap(),
+            BTreeMap::from([
+                ("acme/json".to_string(), "2".to_string()),
+                ("acme/text".to_string(), "^1.0".to_string()),
+            ])
+        );
+
+        let manifest = Manifest::parse(
+            "[dependencies]\nutil = { git = \"https://example.com/util.git\", rev = \"main\" }\n",
+            "app",
+        )
+        .unwrap();
+        assert!(manifest.requirements("app").is_err());
+    }
+
+    #[test]
+    fn test_git_revision_is_checked_out_with_its_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let origin = dir.path().join("origin");
+        let repo = Repository::init(&origin).unwrap();
+        fs::write(
+            origin.join("velin.toml"),
+            "[package]\nname = \"util\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        fs::write(origin.join("lib.velin"), "pub fn trim() {\n}\n").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("velin", "velin@example.com").unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "Release", &tree, &[])
+            .unwrap();
+        repo.tag_lightweight("v1.0.0", &repo.find_object(commit, None).unwrap(), false)
+            .unwrap();
+
+        let url = format!("file://{}", origin.display());
+        let mut source = GitSource::new(dir.path().join("cache"));
+        let manifest = source.manifest_at(&url, "v1.0.0").unwrap().unwrap();
+        assert!(manifest.contains("name = \"util\""));
+
+        let target = dir.path().join("vendor/util");
+        let cksum = source.checkout_rev(&url, "v1.0.0", &target, None).unwrap();
+        assert_eq!(cksum, checksum(&read_files(&target).unwrap()));
+        assert!(target.join("lib.velin").is_file());
+        assert!(!target.join(".git").exists());
+
+        let error = source
+            .checkout_rev(&url, "v1.0.0", &target, Some("sha256:0000"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("stimmt nicht mit velin.lock überein"),
+            "{}",
+            error
+        );
+        assert!(source.checkout_rev(&url, "v9.9.9", &target, None).is_err());
+    }
+}
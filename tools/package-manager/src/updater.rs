@@ -3,7 +3,7 @@
 use anyhow::Result;
 use semver::{Version, VersionReq};
 use serde::Serialize;
-use crate::resolver::VelinToml;
+use crate::resolver::{DependencySpec, VelinToml};
 use crate::registry::Registry;
 
 #[derive(Debug, Clone, Serialize)]
@@ -23,7 +23,10 @@ impl DependencyUpdater {
     pub async fn check_updates(config: &VelinToml) -> Result<Vec<UpdateInfo>> {
         let mut updates = Vec::new();
 
-        for (package_name, version_req_str) in &config.dependencies {
+        for (package_name, spec) in &config.dependencies {
+            let Some(version_req_str) = spec.requirement() else {
+                continue;
+            };
             if VersionReq::parse(version_req_str).is_ok() {
                 // Parse package name (z.B. "github.com/user/repo")
                 let parts: Vec<&str> = package_name.split('/').collect();
@@ -43,7 +46,7 @@ impl DependencyUpdater {
                                 // Immer Update vorschlagen (vereinfachte Logik)
                                 updates.push(UpdateInfo {
                                     package: package_name.clone(),
-                                    current: version_req_str.clone(),
+                                    current: version_req_str.to_string(),
                                     latest: metadata.version,
                                     breaking,
                                     changelog: None,
@@ -70,7 +73,10 @@ impl DependencyUpdater {
     ) -> Result<()> {
         if let Some(version_str) = version {
             // Spezifische Version setzen
-            config.dependencies.insert(package.to_string(), version_str.to_string());
+            config.dependencies.insert(
+                package.to_string(),
+                DependencySpec::Version(version_str.to_string()),
+            );
         } else {
             // Neueste kompatible Version finden
             let parts: Vec<&str> = package.split('/').collect();
@@ -82,7 +88,9 @@ impl DependencyUpdater {
                 match Registry::fetch_package(owner, repo, None).await {
                     Ok(metadata) => {
                         // Behalte SemVer-Constraint, aber aktualisiere auf neueste kompatible Version
-                        if let Some(current_req) = config.dependencies.get(package) {
+                        if let Some(current_req) =
+                            config.dependencies.get(package).and_then(|spec| spec.requirement())
+                        {
                             if let Ok(req) = VersionReq::parse(current_req) {
                                 // Prüfe ob neue Version kompatibel ist
                                 if let Ok(latest) = Version::parse(&metadata.version) {
@@ -90,7 +98,7 @@ impl DependencyUpdater {
                                         // Update zu neuester kompatibler Version
                                         config.dependencies.insert(
                                             package.to_string(),
-                                            format!("^{}", latest),
+                                            DependencySpec::Version(format!("^{}", latest)),
                                         );
                                     } else {
                                         // Breaking Change - behalte alte Version
@@ -0,0 +1,390 @@
+// Workspaces - Mehrere Packages mit gemeinsamen Dependencies und einer velin.lock
+
+use crate::resolver::{DependencySpec, DetailedDependency, PackageInfo, RegistryConfig, VelinToml};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name der virtuellen Wurzel, unter dem alle Members gemeinsam aufgelöst werden
+const ROOT_NAME: &str = "workspace";
+
+/// `[workspace]` in der velin.toml der Wurzel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    /// Member-Verzeichnisse relativ zur Wurzel; `services/*` nimmt jedes
+    /// Unterverzeichnis mit velin.toml auf
+    #[serde(default)]
+    pub members: Vec<String>,
+    /// Gemeinsame Versionen, in Members per `{ workspace = true }` übernommen
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, DependencySpec>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RootManifest {
+    workspace: Option<WorkspaceConfig>,
+    package: Option<toml::Value>,
+    registry: Option<RegistryConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MemberManifest {
+    package: Option<PackageInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Member {
+    pub name: String,
+    /// Verzeichnis relativ zur Workspace-Wurzel
+    pub dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub config: WorkspaceConfig,
+    pub registry: Option<RegistryConfig>,
+    pub members: Vec<Member>,
+}
+
+impl Workspace {
+    /// Sucht ab `start` aufwärts eine velin.toml mit `[workspace]`
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let path = dir.join("velin.toml");
+            if !path.is_file() {
+                continue;
+            }
+            let content = fs::read_to_string(&path)?;
+            let manifest: RootManifest = toml::from_str(&content)
+                .with_context(|| format!("{} ist ungültig", path.display()))?;
+            let Some(config) = manifest.workspace else {
+                continue;
+            };
+            if manifest.package.is_some() {
+                anyhow::bail!(
+                    "{} enthält [workspace] und [package]; lege das Package als Member in einem Unterverzeichnis an",
+                    path.display()
+                );
+            }
+            return Self::load(dir, config, manifest.registry).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn load(root: &Path, config: WorkspaceConfig, registry: Option<RegistryConfig>) -> Result<Self> {
+        let mut dirs = Vec::new();
+        for pattern in &config.members {
+            if let Some(parent) = pattern.strip_suffix("/*") {
+                let entries = fs::read_dir(root.join(parent))
+                    .with_context(|| format!("Workspace-Members '{}' nicht gefunden", pattern))?;
+                let mut found: Vec<_> = entries
+                    .filter_map(|entry| Some(entry.ok()?.file_name()))
+                    .map(|name| Path::new(parent).join(name))
+                    .filter(|dir| root.join(dir).join("velin.toml").is_file())
+                    .collect();
+                found.sort();
+                dirs.extend(found);
+            } else {
+                dirs.push(PathBuf::from(pattern));
+            }
+        }
+
+        let mut members: Vec<Member> = Vec::new();
+        for dir in dirs {
+            let path = root.join(&dir).join("velin.toml");
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Workspace-Member {} hat keine velin.toml", dir.display()))?;
+            let manifest: MemberManifest = toml::from_str(&content)
+                .with_context(|| format!("{} ist ungültig", path.display()))?;
+            let name = manifest
+                .package
+                .with_context(|| format!("{} hat kein [package]", path.display()))?
+                .name;
+            if let Some(other) = members.iter().find(|member| member.name == name) {
+                anyhow::bail!(
+                    "Workspace-Member {} und {} heißen beide '{}'",
+                    other.dir.display(),
+                    dir.display(),
+                    name
+                );
+            }
+            members.push(Member { name, dir });
+        }
+
+        Ok(Workspace {
+            root: root.to_path_buf(),
+            config,
+            registry,
+            members,
+        })
+    }
+
+    /// Member für `-p <name>`
+    pub fn member(&self, name: &str) -> Result<&Member> {
+        self.members
+            .iter()
+            .find(|member| member.name == name)
+            .with_context(|| {
+                let names: Vec<_> = self.members.iter().map(|m| m.name.as_str()).collect();
+                format!(
+                    "'{}' ist kein Member des Workspaces {} (Members: {})",
+                    name,
+                    self.root.display(),
+                    names.join(", ")
+                )
+            })
+    }
+
+    /// Member, in dessen Verzeichnis `dir` liegt
+    pub fn member_at(&self, dir: &Path) -> Option<&Member> {
+        let dir = fs::canonicalize(dir).ok()?;
+        self.members.iter().find(|member| {
+            fs::canonicalize(self.root.join(&member.dir))
+                .map(|member_dir| dir.starts_with(member_dir))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Virtuelles Wurzel-Package mit allen Members als Pfad-Dependencies
+    ///
+    /// Damit werden alle Members in einem Durchlauf aufgelöst: jedes Package hat im
+    /// ganzen Workspace genau eine Version, und es gibt nur eine velin.lock.
+    pub fn root_config(&self) -> VelinToml {
+        let dependencies: HashMap<_, _> = self
+            .members
+            .iter()
+            .map(|member| {
+                let spec = DependencySpec::Detailed(DetailedDependency {
+                    path: Some(member.dir.to_string_lossy().to_string()),
+                    ..Default::default()
+                });
+                (member.name.clone(), spec)
+            })
+            .collect();
+        VelinToml {
+            package: PackageInfo {
+                name: ROOT_NAME.to_string(),
+                version: "0.0.0".to_string(),
+            },
+            dependencies,
+            registry: self.registry.clone(),
+        }
+    }
+
+    /// Ersetzt `{ workspace = true }` in einer Member-velin.toml durch die
+    /// Einträge aus `[workspace.dependencies]` (für veröffentlichte Versionen)
+    pub fn inherit_manifest(&self, content: &str) -> Result<String> {
+        let mut manifest: toml::Table = toml::from_str(content)?;
+        let Some(toml::Value::Table(dependencies)) = manifest.get_mut("dependencies") else {
+            return Ok(content.to_string());
+        };
+        let mut changed = false;
+        for (key, value) in dependencies.iter_mut() {
+            let spec: DependencySpec = value.clone().try_into()?;
+            if !spec.is_inherited() {
+                continue;
+            }
+            let inherited = self.config.dependencies.get(key).with_context(|| {
+                format!(
+                    "Dependency '{}' verwendet 'workspace = true', steht aber nicht in [workspace.dependencies]",
+                    key
+                )
+            })?;
+            *value = toml::Value::try_from(inherited)?;
+            changed = true;
+        }
+        if !changed {
+            return Ok(content.to_string());
+        }
+        Ok(toml::to_string_pretty(&manifest)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn member(root: &Path, dir: &str, name: &str, dependencies: &str) {
+        write(
+            &root.join(dir).join("velin.toml"),
+            &format!(
+                "[package]\nname = \"{}\"\nversion = \"0.1.0\"\n\n[dependencies]\n{}",
+                name, dependencies
+            ),
+        );
+    }
+
+    /// Workspace mit `services/*` und einer einzeln genannten Library
+    fn workspace(root: &Path) {
+        write(
+            &root.join("velin.toml"),
+            r#"[workspace]
+members = ["services/*", "libs/shared"]
+
+[workspace.dependencies]
+"github.com/acme/json" = "^1.2"
+shared = { path = "libs/shared" }
+
+[registry]
+url = "file:///srv/velin-registry"
+"#,
+        );
+        member(
+            root,
+            "services/web",
+            "web",
+            "shared = { workspace = true }\n",
+        );
+        member(
+            root,
+            "services/api",
+            "api",
+            "\"github.com/acme/json\" = { workspace = true }\nshared = { workspace = true }\n",
+        );
+        member(root, "libs/shared", "shared", "");
+        // Ohne velin.toml kein Member
+        fs::create_dir_all(root.join("services/assets")).unwrap();
+    }
+
+    #[test]
+    fn test_discover_expands_member_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        workspace(dir.path());
+
+        let workspace = Workspace::discover(&dir.path().join("services/api"))
+            .unwrap()
+            .expect("Workspace nicht gefunden");
+        assert_eq!(workspace.root, dir.path());
+        assert_eq!(
+            workspace.registry.as_ref().map(|r| r.url.as_str()),
+            Some("file:///srv/velin-registry")
+        );
+        let members: Vec<_> = workspace
+            .members
+            .iter()
+            .map(|m| (m.name.as_str(), m.dir.to_string_lossy().to_string()))
+            .collect();
+        assert_eq!(
+            members,
+            [
+                ("api", "services/api".to_string()),
+                ("web", "services/web".to_string()),
+                ("shared", "libs/shared".to_string()),
+            ]
+        );
+
+        let outside = tempfile::tempdir().unwrap();
+        assert!(Workspace::discover(outside.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_member_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        workspace(dir.path());
+        let workspace = Workspace::discover(dir.path()).unwrap().unwrap();
+
+        assert_eq!(
+            workspace.member("web").unwrap().dir,
+            Path::new("services/web")
+        );
+        let error = workspace.member("admin").unwrap_err().to_string();
+        assert!(error.contains("'admin' ist kein Member"), "{}", error);
+        assert!(error.contains("Members: api, web, shared"), "{}", error);
+
+        let nested = dir.path().join("services/api/src");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(workspace.member_at(&nested).unwrap().name, "api");
+        assert!(workspace.member_at(dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_root_config_resolves_all_members_together() {
+        let dir = tempfile::tempdir().unwrap();
+        workspace(dir.path());
+        let workspace = Workspace::discover(dir.path()).unwrap().unwrap();
+
+        let config = workspace.root_config();
+        assert_eq!(config.package.name, ROOT_NAME);
+        assert_eq!(config.registry.unwrap().url, "file:///srv/velin-registry");
+        let mut paths: Vec<_> = config
+            .dependencies
+            .iter()
+            .map(|(name, spec)| match spec {
+                DependencySpec::Detailed(detail) => (name.as_str(), detail.path.clone().unwrap()),
+                DependencySpec::Version(_) => panic!("{} ist keine Pfad-Dependency", name),
+            })
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            [
+                ("api", "services/api".to_string()),
+                ("shared", "libs/shared".to_string()),
+                ("web", "services/web".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inherit_manifest_replaces_workspace_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        workspace(dir.path());
+        let workspace = Workspace::discover(dir.path()).unwrap().unwrap();
+
+        let content = fs::read_to_string(dir.path().join("services/api/velin.toml")).unwrap();
+        let inherited: toml::Table =
+            toml::from_str(&workspace.inherit_manifest(&content).unwrap()).unwrap();
+        let dependencies = inherited["dependencies"].as_table().unwrap();
+        assert_eq!(dependencies["github.com/acme/json"].as_str(), Some("^1.2"));
+        assert_eq!(dependencies["shared"]["path"].as_str(), Some("libs/shared"));
+        assert!(dependencies["shared"].get("workspace").is_none());
+
+        // Ohne geerbte Einträge bleibt die Datei unverändert
+        let plain =
+            "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n\n[dependencies]\nlog = \"^0.4\"\n";
+        assert_eq!(workspace.inherit_manifest(plain).unwrap(), plain);
+
+        let error = workspace
+            .inherit_manifest("[dependencies]\nhttp = { workspace = true }\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'http'"), "{}", error);
+    }
+
+    #[test]
+    fn test_invalid_workspaces_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("velin.toml"),
+            "[workspace]\nmembers = [\"app\"]\n\n[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        );
+        let error = Workspace::discover(dir.path()).unwrap_err().to_string();
+        assert!(error.contains("[workspace] und [package]"), "{}", error);
+
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("velin.toml"),
+            "[workspace]\nmembers = [\"a\", \"b\"]\n",
+        );
+        member(dir.path(), "a", "app", "");
+        member(dir.path(), "b", "app", "");
+        let error = Workspace::discover(dir.path()).unwrap_err().to_string();
+        assert!(error.contains("heißen beide 'app'"), "{}", error);
+
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("velin.toml"),
+            "[workspace]\nmembers = [\"missing\"]\n",
+        );
+        let error = Workspace::discover(dir.path()).unwrap_err().to_string();
+        assert!(error.contains("missing hat keine velin.toml"), "{}", error);
+    }
+}
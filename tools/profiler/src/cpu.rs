@@ -1,87 +1,85 @@
 // CPU Profiler
-// Führt CPU-Profiling durch
+// Führt ein instrumentiertes Velin-Programm aus und wertet die Funktions-Frames aus
 
 use anyhow::Result;
-use std::path::Path;
-use std::fs;
-use serde::{Serialize, Deserialize};
-use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use velin_compiler::profiler::{FunctionProfile, ProfileReport, ProfileRunner};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CpuProfileData {
+    /// Gesamtlaufzeit in ms
     pub total_time: f64,
+    /// Pro Velin-Funktion, nach exklusiver Zeit sortiert
     pub functions: Vec<FunctionProfile>,
+    /// Gefaltete Stacks für den Flame Graph
+    pub folded: String,
+    #[serde(skip)]
+    pub report: ProfileReport,
+    #[serde(skip)]
+    pub success: bool,
+    #[serde(skip)]
+    pub program_output: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FunctionProfile {
-    pub name: String,
-    pub time: f64,
-    pub calls: usize,
-    pub percentage: f64,
+pub struct CpuProfiler {
+    runner: ProfileRunner,
 }
 
-pub struct CpuProfiler;
-
 impl CpuProfiler {
-    pub fn new() -> Self {
-        Self
+    pub fn new(args: Vec<String>) -> Self {
+        Self {
+            runner: ProfileRunner::new(PathBuf::from("target/velin-profile")).with_args(args),
+        }
     }
-    
+
     pub fn profile(&self, file: &Path) -> Result<CpuProfileData> {
-        let content = fs::read_to_string(file)?;
-        
-        // Vereinfachte Profiling-Implementierung
-        // In Produktion würde man echte Profiling-Tools nutzen (perf, dtrace, etc.)
-        let start = Instant::now();
-        
-        // Simuliere Code-Ausführung
-        std::thread::sleep(std::time::Duration::from_millis(10));
-        
-        let elapsed = start.elapsed();
-        let total_time = elapsed.as_secs_f64() * 1000.0; // in ms
-        
-        // Analysiere Funktionen im Code
-        let functions = self.analyze_functions(&content, total_time);
-        
-        Ok(CpuProfileData {
-            total_time,
-            functions,
-        })
+        let run = self.runner.run_file(file)?;
+        Ok(CpuProfileData::from_report(
+            run.report,
+            run.success,
+            run.output,
+        ))
     }
-    
-    fn analyze_functions(&self, content: &str, total_time: f64) -> Vec<FunctionProfile> {
-        let mut functions = Vec::new();
-        
-        // Einfache String-basierte Analyse
-        // In Produktion sollte man den AST nutzen
-        for line in content.lines() {
-            if line.trim().starts_with("fn ") {
-                let name = self.extract_function_name(line);
-                if !name.is_empty() {
-                    // Simuliere Profiling-Daten
-                    let time = total_time * 0.1; // 10% pro Funktion (vereinfacht)
-                    functions.push(FunctionProfile {
-                        name,
-                        time,
-                        calls: 1,
-                        percentage: 10.0,
-                    });
-                }
-            }
+}
+
+impl CpuProfileData {
+    pub fn from_report(report: ProfileReport, success: bool, program_output: String) -> Self {
+        CpuProfileData {
+            total_time: report.total_ms,
+            functions: report.functions.clone(),
+            folded: report.to_folded(),
+            report,
+            success,
+            program_output,
         }
-        
-        functions
     }
-    
-    fn extract_function_name(&self, line: &str) -> String {
-        // Extrahiere Funktionsname aus "fn name(...)"
-        if let Some(start) = line.find("fn ") {
-            let rest = &line[start + 3..];
-            if let Some(end) = rest.find('(') {
-                return rest[..end].trim().to_string();
-            }
-        }
-        String::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::tests::report;
+
+    #[test]
+    fn test_cpu_report_lists_functions_and_folded_stacks() {
+        let data = CpuProfileData::from_report(report(), true, String::new());
+
+        assert_eq!(data.total_time, 10.0);
+        let names: Vec<_> = data.functions.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["parse", "load", "main"]);
+        assert_eq!(data.folded.lines().count(), 3);
+        assert!(
+            data.folded.contains("main;load;parse 6000"),
+            "{}",
+            data.folded
+        );
+
+        // profile.json: Messwerte und Stacks, aber nicht Rohdaten und Programmausgabe
+        let json: serde_json::Value = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["functions"][0]["self_ms"], 6.0);
+        assert_eq!(json["folded"], data.folded.as_str());
+        assert!(json.get("report").is_none());
+        assert!(json.get("program_output").is_none());
     }
 }
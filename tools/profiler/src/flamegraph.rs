@@ -1,8 +1,11 @@
 // Flame Graph Generator
-// Generiert Flame Graphs aus Profiling-Daten
+// Rendert die gefalteten Stacks eines Profiling-Laufs mit inferno
 
 use crate::cpu::CpuProfileData;
 use anyhow::Result;
+use inferno::flamegraph::{self, Options};
+use std::fs::File;
+use std::io::BufWriter;
 use std::path::PathBuf;
 
 pub struct FlameGraphGenerator;
@@ -11,40 +14,52 @@ impl FlameGraphGenerator {
     pub fn new() -> Self {
         Self
     }
-    
+
     pub fn generate(&self, data: &CpuProfileData, output: &PathBuf) -> Result<()> {
-        // Generiere vereinfachtes Flame Graph
-        // In Produktion würde man inferno oder flamegraph crate nutzen
-        
-        let mut svg = String::from(r#"<svg xmlns="http://www.w3.org/2000/svg" width="1200" height="800">"#);
-        svg.push_str(r#"<text x="10" y="20" font-family="Arial" font-size="14">Flame Graph</text>"#);
-        
-        // Generiere einfache Visualisierung
-        let mut y = 40;
-        for (i, func) in data.functions.iter().enumerate() {
-            let width = (func.percentage * 10.0) as i32;
-            svg.push_str(&format!(
-                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"20\" fill=\"#{:02x}{:02x}{:02x}\"/>",
-                10 + i * 120,
-                y,
-                width,
-                (i * 50) % 255,
-                (i * 100) % 255,
-                (i * 150) % 255
-            ));
-            svg.push_str(&format!(
-                r#"<text x="{}" y="{}" font-family="Arial" font-size="10">{}</text>"#,
-                10 + i * 120,
-                y + 15,
-                func.name
-            ));
-            y += 30;
+        if data.folded.trim().is_empty() {
+            anyhow::bail!("Keine Stack-Samples vorhanden - wurde eine Velin-Funktion aufgerufen?");
         }
-        
-        svg.push_str("</svg>");
-        
-        std::fs::write(output, svg)?;
-        
+
+        let mut options = Options::default();
+        options.title = "Velin CPU Profile".to_string();
+        options.count_name = "µs".to_string();
+
+        let writer = BufWriter::new(File::create(output)?);
+        flamegraph::from_lines(&mut options, data.folded.lines(), writer)?;
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::tests::report;
+    use velin_compiler::profiler::ProfileReport;
+
+    #[test]
+    fn test_flamegraph_renders_every_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("flamegraph.svg");
+        let data = CpuProfileData::from_report(report(), true, String::new());
+
+        FlameGraphGenerator::new().generate(&data, &output).unwrap();
+        let svg = std::fs::read_to_string(&output).unwrap();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("Velin CPU Profile"));
+        for frame in ["main", "load", "parse"] {
+            assert!(svg.contains(&format!(">{}<", frame)), "{} fehlt", frame);
+        }
+    }
+
+    #[test]
+    fn test_flamegraph_needs_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = CpuProfileData::from_report(ProfileReport::default(), true, String::new());
+
+        let error = FlameGraphGenerator::new()
+            .generate(&data, &dir.path().join("flamegraph.svg"))
+            .unwrap_err();
+        assert!(error.to_string().contains("Keine Stack-Samples"));
+    }
+}
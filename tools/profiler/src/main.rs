@@ -2,12 +2,14 @@
 // CPU- und Memory-Profiling mit Flame Graphs und Allocation Tracking
 
 mod cpu;
-mod memory;
 mod flamegraph;
+mod memory;
 
-use clap::{Parser, Subcommand};
 use anyhow::Result;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use velin_compiler::optimizer::profiling::ProfilingCollector;
+use velin_compiler::profiler::ProfileReport;
 
 #[derive(Parser)]
 #[command(name = "velin-profile")]
@@ -23,23 +25,31 @@ enum Commands {
     Cpu {
         /// Zu profilende Datei
         file: PathBuf,
-        
+
         /// Generiert Flame Graph
         #[arg(long)]
         flamegraph: bool,
-        
-        /// Output-Verzeichnis
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+
+        /// Output-Verzeichnis (profile.folded, profile.json, flamegraph.svg)
+        #[arg(short, long, default_value = "profile")]
+        output: PathBuf,
+
+        /// Argumente für das profilierte Programm (nach `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
     },
     /// Memory-Profiling
     Memory {
         /// Zu profilende Datei
         file: PathBuf,
-        
-        /// Output-Verzeichnis
+
+        /// Report-Datei (JSON)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Argumente für das profilierte Programm (nach `--`)
+        #[arg(last = true)]
+        args: Vec<String>,
     },
 }
 
@@ -47,52 +57,101 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Cpu { file, flamegraph, output } => {
-            cpu_profiling(file, flamegraph, output)
-        }
-        Commands::Memory { file, output } => {
-            memory_profiling(file, output)
-        }
+        Commands::Cpu {
+            file,
+            flamegraph,
+            output,
+            args,
+        } => cpu_profiling(file, flamegraph, output, args),
+        Commands::Memory { file, output, args } => memory_profiling(file, output, args),
     }
 }
 
-fn cpu_profiling(file: PathBuf, flamegraph: bool, output: Option<PathBuf>) -> Result<()> {
+fn cpu_profiling(
+    file: PathBuf,
+    flamegraph: bool,
+    output: PathBuf,
+    args: Vec<String>,
+) -> Result<()> {
     println!("⚡ CPU-Profiling für: {}\n", file.display());
-    
-    let profiler = cpu::CpuProfiler::new();
+
+    let profiler = cpu::CpuProfiler::new(args);
     let profile_data = profiler.profile(&file)?;
-    
+    if !profile_data.success {
+        println!(
+            "⚠️  Programm wurde mit Fehler beendet:\n{}",
+            profile_data.program_output
+        );
+    }
+
     println!("📊 CPU-Profiling-Ergebnisse:");
     println!("  Gesamt-Zeit: {:.2}ms", profile_data.total_time);
-    println!("  Funktionen: {}", profile_data.functions.len());
-    
+    println!("  Funktionen: {}\n", profile_data.functions.len());
+    println!(
+        "  {:<32} {:>8} {:>12} {:>12} {:>7}",
+        "Funktion", "Aufrufe", "Gesamt (ms)", "Selbst (ms)", "%"
+    );
+    for function in &profile_data.functions {
+        println!(
+            "  {:<32} {:>8} {:>12.3} {:>12.3} {:>6.1}%",
+            function.name, function.calls, function.total_ms, function.self_ms, function.percentage
+        );
+    }
+
+    std::fs::create_dir_all(&output)?;
+    let folded_path = output.join("profile.folded");
+    std::fs::write(&folded_path, &profile_data.folded)?;
+    let report_path = output.join("profile.json");
+    std::fs::write(&report_path, serde_json::to_string_pretty(&profile_data)?)?;
+    println!("\n✓ Gefaltete Stacks: {}", folded_path.display());
+    println!("✓ Report: {}", report_path.display());
+
     if flamegraph {
         println!("\n🔥 Generiere Flame Graph...");
         let flame_gen = flamegraph::FlameGraphGenerator::new();
-        let output_path = output.unwrap_or_else(|| PathBuf::from("flamegraph.svg"));
+        let output_path = output.join("flamegraph.svg");
         flame_gen.generate(&profile_data, &output_path)?;
         println!("✓ Flame Graph gespeichert: {}", output_path.display());
     }
-    
-    Ok(())
+
+    persist_for_optimizer(&profile_data.report)
 }
 
-fn memory_profiling(file: PathBuf, output: Option<PathBuf>) -> Result<()> {
+fn memory_profiling(file: PathBuf, output: Option<PathBuf>, args: Vec<String>) -> Result<()> {
     println!("💾 Memory-Profiling für: {}\n", file.display());
-    
-    let profiler = memory::MemoryProfiler::new();
+
+    let profiler = memory::MemoryProfiler::new(args);
     let profile_data = profiler.profile(&file)?;
-    
+
     println!("📊 Memory-Profiling-Ergebnisse:");
-    println!("  Gesamt-Allokationen: {} bytes", profile_data.total_allocations);
+    println!(
+        "  Gesamt-Allokationen: {} bytes",
+        profile_data.total_allocations
+    );
     println!("  Peak-Memory: {} bytes", profile_data.peak_memory);
     println!("  Allokationen: {}", profile_data.allocation_count);
-    
+    for allocation in &profile_data.allocations {
+        println!(
+            "  {:<32} {:>12} bytes {:>8}x  ({})",
+            allocation.function, allocation.size, allocation.count, allocation.location
+        );
+    }
+
     if let Some(output_path) = output {
         let json = serde_json::to_string_pretty(&profile_data)?;
         std::fs::write(&output_path, json)?;
         println!("\n✓ Report gespeichert: {}", output_path.display());
     }
-    
+
+    persist_for_optimizer(&profile_data.report)
+}
+
+/// Übergibt die Messwerte an den ProfilingCollector (.velin/profiling.json),
+/// den der Compiler für profilgesteuerte Optimierung liest
+fn persist_for_optimizer(report: &ProfileReport) -> Result<()> {
+    let mut collector = ProfilingCollector::load_persisted();
+    report.feed(&mut collector);
+    collector.persist()?;
+    println!("✓ Profiling-Daten für den Optimizer gespeichert");
     Ok(())
 }
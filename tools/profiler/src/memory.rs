@@ -1,10 +1,10 @@
 // Memory Profiler
-// Führt Memory-Profiling durch
+// Ordnet die Allokationen eines instrumentierten Laufs Velin-Funktionen zu
 
 use anyhow::Result;
-use std::path::Path;
-use std::fs;
-use serde::{Serialize, Deserialize};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use velin_compiler::profiler::{ProfileReport, ProfileRunner};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryProfileData {
@@ -12,57 +12,136 @@ pub struct MemoryProfileData {
     pub peak_memory: usize,
     pub allocation_count: usize,
     pub allocations: Vec<Allocation>,
+    #[serde(skip)]
+    pub report: ProfileReport,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Allocation {
+    pub function: String,
     pub location: String,
     pub size: usize,
     pub count: usize,
 }
 
-pub struct MemoryProfiler;
+pub struct MemoryProfiler {
+    runner: ProfileRunner,
+}
 
 impl MemoryProfiler {
-    pub fn new() -> Self {
-        Self
+    pub fn new(args: Vec<String>) -> Self {
+        Self {
+            runner: ProfileRunner::new(PathBuf::from("target/velin-profile")).with_args(args),
+        }
     }
-    
+
     pub fn profile(&self, file: &Path) -> Result<MemoryProfileData> {
-        let content = fs::read_to_string(file)?;
-        
-        // Vereinfachte Memory-Profiling-Implementierung
-        // In Produktion würde man dhat oder ähnliche Tools nutzen
-        
-        // Analysiere Code auf Allokationen
-        let allocations = self.analyze_allocations(&content);
-        let total_allocations: usize = allocations.iter().map(|a| a.size * a.count).sum();
-        let peak_memory = total_allocations;
-        let allocation_count = allocations.iter().map(|a| a.count).sum();
-        
-        Ok(MemoryProfileData {
-            total_allocations,
-            peak_memory,
-            allocation_count,
+        let run = self.runner.run_file(file)?;
+        Ok(MemoryProfileData::from_report(run.report))
+    }
+}
+
+impl MemoryProfileData {
+    /// Funktionen mit Allokationen, nach allokierten Bytes absteigend sortiert
+    pub fn from_report(report: ProfileReport) -> Self {
+        let mut allocations: Vec<Allocation> = report
+            .functions
+            .iter()
+            .filter(|f| f.allocations > 0)
+            .map(|f| Allocation {
+                function: f.name.clone(),
+                location: format!("{}:{}", f.file, f.line),
+                size: f.alloc_bytes as usize,
+                count: f.allocations as usize,
+            })
+            .collect();
+        allocations.sort_by_key(|a| std::cmp::Reverse(a.size));
+
+        MemoryProfileData {
+            total_allocations: allocations.iter().map(|a| a.size).sum(),
+            peak_memory: report.peak_bytes as usize,
+            allocation_count: allocations.iter().map(|a| a.count).sum(),
+            allocations,
+            report,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use velin_compiler::profiler::FunctionProfile;
+
+    fn function(
+        name: &str,
+        line: usize,
+        self_ms: f64,
+        alloc_bytes: u64,
+        allocations: u64,
+    ) -> FunctionProfile {
+        FunctionProfile {
+            name: name.to_string(),
+            file: "main.velin".to_string(),
+            line,
+            calls: 1,
+            total_ms: self_ms,
+            self_ms,
+            percentage: self_ms / 10.0 * 100.0,
+            alloc_bytes,
             allocations,
-        })
+        }
     }
-    
-    fn analyze_allocations(&self, content: &str) -> Vec<Allocation> {
-        let mut allocations = Vec::new();
-        
-        // Einfache Analyse auf Array/List-Erstellungen
-        // In Produktion sollte man den AST nutzen
-        for (line_num, line) in content.lines().enumerate() {
-            if line.contains("List") || line.contains("Array") || line.contains("Vec") {
-                allocations.push(Allocation {
-                    location: format!("{}:{}", "file", line_num + 1),
-                    size: 1024, // Geschätzte Größe
-                    count: 1,
-                });
-            }
+
+    /// Lauf mit `main` → `load` → `parse`, gemessen in 10ms
+    pub(crate) fn report() -> ProfileReport {
+        ProfileReport {
+            total_ms: 10.0,
+            peak_bytes: 4096,
+            functions: vec![
+                function("parse", 7, 6.0, 512, 4),
+                function("load", 3, 3.0, 2048, 2),
+                function("main", 1, 1.0, 0, 0),
+            ],
+            stacks: vec![
+                ("main".to_string(), 1000),
+                ("main;load".to_string(), 3000),
+                ("main;load;parse".to_string(), 6000),
+            ],
         }
-        
-        allocations
+    }
+
+    #[test]
+    fn test_allocations_are_attributed_and_sorted_by_size() {
+        let data = MemoryProfileData::from_report(report());
+
+        let functions: Vec<_> = data
+            .allocations
+            .iter()
+            .map(|a| (a.function.as_str(), a.location.as_str(), a.size, a.count))
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("load", "main.velin:3", 2048, 2),
+                ("parse", "main.velin:7", 512, 4)
+            ]
+        );
+        assert_eq!(data.total_allocations, 2560);
+        assert_eq!(data.allocation_count, 6);
+        assert_eq!(data.peak_memory, 4096);
+    }
+
+    #[test]
+    fn test_json_report_leaves_out_the_raw_profile() {
+        let data = MemoryProfileData::from_report(report());
+        let json: serde_json::Value = serde_json::to_value(&data).unwrap();
+
+        assert_eq!(json["total_allocations"], 2560);
+        assert_eq!(json["allocations"][0]["function"], "load");
+        assert!(json.get("report").is_none());
+
+        let empty = MemoryProfileData::from_report(ProfileReport::default());
+        assert!(empty.allocations.is_empty());
+        assert_eq!(empty.total_allocations, 0);
     }
 }
@@ -1,88 +1,269 @@
 // Runtime Inspector
-// Haupt-Logik für Runtime-Inspection
+// Holt Snapshots vom Inspect-Endpunkt eines laufenden Services und stellt sie dar
 
 use crate::variables::VariableInspector;
 use crate::memory::MemoryInspector;
-use velin_compiler::compiler::{VelinCompiler, config::CompilerConfig};
-use velin_compiler::passes::{parser::ParserPass, type_check::TypeCheckPass};
-use anyhow::Result;
-use std::path::Path;
-use std::fs;
-use tokio::time::{sleep, Duration};
+use velin_compiler::inspector::{InspectorSnapshot, INSPECT_PATH};
+use anyhow::{anyhow, Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout, Duration};
+
+/// Auswahl der angezeigten Abschnitte
+#[derive(Debug, Clone, Copy)]
+pub struct Sections {
+    pub variables: bool,
+    pub memory: bool,
+    pub requests: bool,
+    pub metrics: bool,
+    pub cache: bool,
+    pub tasks: bool,
+}
+
+impl Sections {
+    /// Ohne explizite Auswahl werden alle Abschnitte angezeigt
+    pub fn or_all(self) -> Self {
+        if self.variables || self.memory || self.requests || self.metrics || self.cache || self.tasks {
+            self
+        } else {
+            Sections {
+                variables: true,
+                memory: true,
+                requests: true,
+                metrics: true,
+                cache: true,
+                tasks: true,
+            }
+        }
+    }
+}
 
 pub struct RuntimeInspector {
+    addr: String,
     variable_inspector: VariableInspector,
     memory_inspector: MemoryInspector,
 }
 
 impl RuntimeInspector {
-    pub fn new() -> Self {
+    pub fn new(addr: String) -> Self {
         Self {
+            addr,
             variable_inspector: VariableInspector::new(),
             memory_inspector: MemoryInspector::new(),
         }
     }
-    
-    pub async fn inspect(
-        &self,
-        file: &Path,
-        show_variables: bool,
-        show_memory: bool,
-    ) -> Result<()> {
-        let content = fs::read_to_string(file)?;
-        
-        // Kompiliere Code
-        let mut config = CompilerConfig::default();
-        config.enable_type_check = true;
-        
-        let mut compiler = VelinCompiler::new(config);
-        compiler.add_pass(Box::new(ParserPass::new()));
-        compiler.add_pass(Box::new(TypeCheckPass::new(true)));
-        
-        let context = compiler.compile(
-            file.to_string_lossy().to_string(),
-            content.clone(),
-        )?;
-        
-        if context.has_errors() {
-            eprintln!("⚠️  Kompilierungsfehler: {:?}", context.errors);
-            return Ok(());
+
+    /// `GET /__velin/inspect` per HTTP/1.1 mit `Connection: close`
+    pub async fn fetch(&self) -> Result<InspectorSnapshot> {
+        let request = async {
+            let mut stream = TcpStream::connect(&self.addr).await?;
+            let request = format!(
+                "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                INSPECT_PATH, self.addr
+            );
+            stream.write_all(request.as_bytes()).await?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await?;
+            Ok::<String, std::io::Error>(response)
+        };
+        let response = timeout(Duration::from_secs(5), request)
+            .await
+            .map_err(|_| anyhow!("Zeitüberschreitung bei {}", self.addr))?
+            .with_context(|| {
+                format!(
+                    "Keine Verbindung zu {} (Service mit `velin compile --inspect` gebaut und gestartet?)",
+                    self.addr
+                )
+            })?;
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| anyhow!("Ungültige HTTP-Antwort von {}", self.addr))?;
+        let status = head.lines().next().unwrap_or("");
+        if !status.contains(" 200 ") {
+            return Err(anyhow!("Inspect-Endpunkt antwortet mit '{}'", status));
         }
-        
-        println!("✓ Code kompiliert erfolgreich\n");
-        
-        if show_variables {
+        serde_json::from_str(body).context("Ungültiger Inspect-Snapshot")
+    }
+
+    pub async fn inspect(&self, sections: &Sections) -> Result<()> {
+        let snapshot = self.fetch().await?;
+        self.render(&snapshot, sections);
+        Ok(())
+    }
+
+    pub async fn watch(&self, sections: &Sections, interval: Duration) -> Result<()> {
+        loop {
+            // Bildschirm leeren und Cursor nach oben
+            print!("\x1b[2J\x1b[H");
+            println!("👀 Watch-Mode: {} (CTRL-C zum Beenden)\n", self.addr);
+            match self.fetch().await {
+                Ok(snapshot) => self.render(&snapshot, sections),
+                Err(e) => eprintln!("Fehler: {:#}", e),
+            }
+
+            sleep(interval).await;
+        }
+    }
+
+    fn render(&self, snapshot: &InspectorSnapshot, sections: &Sections) {
+        println!(
+            "🔍 Service '{}' – läuft seit {}\n",
+            snapshot.service,
+            format_duration(snapshot.uptime_ms)
+        );
+
+        if sections.variables {
             println!("📊 Variablen:");
-            let variables = self.variable_inspector.extract_variables(&content)?;
-            for var in &variables {
-                println!("  {}: {}", var.name, var.value);
+            self.variable_inspector.print_state(&snapshot.state);
+            println!();
+        }
+
+        if sections.requests {
+            println!("🌐 Requests:");
+            if snapshot.requests.is_empty() {
+                println!("  (keine)");
+            }
+            for route in &snapshot.requests {
+                let average = if route.count > 0 {
+                    route.total_ms / route.count as f64
+                } else {
+                    0.0
+                };
+                println!(
+                    "  {:<30} {:>8} Aufrufe  {:>3} aktiv  Ø {:.2} ms  max {:.2} ms",
+                    route.route, route.count, route.in_flight, average, route.max_ms
+                );
             }
             println!();
         }
-        
-        if show_memory {
+
+        if sections.metrics {
+            println!("📈 Metriken:");
+            if snapshot.metrics.is_empty() {
+                println!("  (keine)");
+            }
+            for metric in &snapshot.metrics {
+                match metric.kind.as_str() {
+                    "histogram" => println!(
+                        "  {:<30} {:<10} Ø {} ({} Werte)",
+                        metric.name, metric.kind, metric.value, metric.count
+                    ),
+                    _ => println!("  {:<30} {:<10} {}", metric.name, metric.kind, metric.value),
+                }
+            }
+            println!();
+        }
+
+        if sections.cache {
+            println!("🗄️  Cache:");
+            self.memory_inspector.print_cache(&snapshot.cache);
+            println!();
+        }
+
+        if sections.tasks {
+            println!("⚙️  Tasks:");
+            self.variable_inspector.print_tasks(&snapshot.tasks);
+            println!();
+        }
+
+        if sections.memory {
             println!("💾 Memory-Usage:");
-            let memory = self.memory_inspector.analyze(&content)?;
-            println!("  Geschätzte Allokationen: {} bytes", memory.estimated_allocations);
-            println!("  Variablen: {}", memory.variable_count);
+            self.memory_inspector.print_memory(snapshot);
             println!();
         }
-        
-        Ok(())
     }
-    
-    pub async fn watch(
-        &self,
-        file: &Path,
-        show_variables: bool,
-        show_memory: bool,
-    ) -> Result<()> {
-        loop {
-            if let Err(e) = self.inspect(file, show_variables, show_memory).await {
-                eprintln!("Fehler: {}", e);
+}
+
+pub fn format_duration(ms: u64) -> String {
+    match ms {
+        0..=999 => format!("{} ms", ms),
+        1_000..=59_999 => format!("{:.1} s", ms as f64 / 1000.0),
+        60_000..=3_599_999 => format!("{} min {} s", ms / 60_000, (ms % 60_000) / 1000),
+        _ => format!("{} h {} min", ms / 3_600_000, (ms % 3_600_000) / 60_000),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::net::TcpListener;
+
+    /// Beantwortet genau eine Anfrage mit `status` und `body`; liefert die Request-Zeile
+    async fn serve_once(
+        status: &'static str,
+        body: String,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = tokio::io::BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).await.unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).await.unwrap() > 2 {
+                line.clear();
             }
-            
-            sleep(Duration::from_secs(2)).await;
-        }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            reader
+                .get_mut()
+                .write_all(response.as_bytes())
+                .await
+                .unwrap();
+            request_line
+        });
+        (addr, handle)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_parses_snapshot() {
+        let body = serde_json::json!({
+            "service": "shop",
+            "uptime_ms": 61_000,
+            "memory_rss_bytes": 2048,
+            "state": [{ "name": "total", "value": 3, "updated_ms_ago": 5 }],
+            "requests": [{ "route": "GET /orders", "count": 2, "in_flight": 0, "total_ms": 3.0, "max_ms": 2.0 }],
+            "metrics": [],
+            "cache": [{ "key": "last", "value": "orders", "expires_in_ms": null }],
+            "tasks": [],
+        });
+        let (addr, server) = serve_once("200 OK", body.to_string()).await;
+
+        let snapshot = RuntimeInspector::new(addr).fetch().await.unwrap();
+        assert_eq!(
+            server.await.unwrap().trim(),
+            "GET /__velin/inspect HTTP/1.1"
+        );
+        assert_eq!(snapshot.service, "shop");
+        assert_eq!(snapshot.memory_rss_bytes, Some(2048));
+        assert_eq!(snapshot.state[0].value, serde_json::json!(3));
+        assert_eq!(snapshot.requests[0].count, 2);
+        assert_eq!(snapshot.cache[0].expires_in_ms, None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reports_http_status() {
+        let (addr, server) = serve_once("404 Not Found", "{}".to_string()).await;
+        let error = RuntimeInspector::new(addr).fetch().await.unwrap_err();
+        server.await.unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Inspect-Endpunkt antwortet mit 'HTTP/1.1 404 Not Found'"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(250), "250 ms");
+        assert_eq!(format_duration(1_500), "1.5 s");
+        assert_eq!(format_duration(61_000), "1 min 1 s");
+        assert_eq!(format_duration(3_720_000), "1 h 2 min");
     }
 }
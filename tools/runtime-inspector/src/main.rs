@@ -1,5 +1,5 @@
 // VelinScript Runtime Inspector
-// Live-Inspection von Variablen, State und Memory während der Ausführung
+// Live-Inspection laufender Velin-Services über deren Inspect-Endpunkt
 
 mod inspector;
 mod variables;
@@ -7,8 +7,9 @@ mod memory;
 
 use clap::{Parser, Subcommand};
 use anyhow::Result;
-use std::path::PathBuf;
-use inspector::RuntimeInspector;
+use inspector::{RuntimeInspector, Sections};
+use std::time::Duration;
+use velin_compiler::inspector::{DEFAULT_INSPECT_ADDR, INSPECT_ADDR_ENV};
 
 #[derive(Parser)]
 #[command(name = "velin-inspect")]
@@ -20,22 +21,43 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Startet Inspector
+    /// Verbindet sich mit einem mit `velin compile --inspect` gebauten Service
     Inspect {
-        /// Zu inspizierende Datei
-        file: PathBuf,
-        
+        /// Adresse des Inspect-Endpunkts (Standard: $VELIN_INSPECT_ADDR oder 127.0.0.1:9930)
+        #[arg(short, long)]
+        addr: Option<String>,
+
         /// Live-Monitoring
         #[arg(short, long)]
         watch: bool,
-        
-        /// Zeigt alle Variablen
+
+        /// Aktualisierungsintervall im Watch-Mode (Sekunden)
+        #[arg(long, default_value = "2")]
+        interval: u64,
+
+        /// Zeigt registrierten State (`inspect.state`)
         #[arg(short, long)]
         variables: bool,
-        
-        /// Zeigt Memory-Usage
+
+        /// Zeigt Memory-Usage (RSS)
         #[arg(short, long)]
         memory: bool,
+
+        /// Zeigt Request-Zähler pro Route
+        #[arg(short, long)]
+        requests: bool,
+
+        /// Zeigt Werte aus `metrics.*`
+        #[arg(long)]
+        metrics: bool,
+
+        /// Zeigt Cache-Einträge
+        #[arg(short, long)]
+        cache: bool,
+
+        /// Zeigt Task-Status (`inspect.task`)
+        #[arg(short, long)]
+        tasks: bool,
     },
 }
 
@@ -44,28 +66,45 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Inspect { file, watch, variables, memory } => {
-            inspect_command(file, watch, variables, memory).await
+        Commands::Inspect {
+            addr,
+            watch,
+            interval,
+            variables,
+            memory,
+            requests,
+            metrics,
+            cache,
+            tasks,
+        } => {
+            let addr = addr
+                .or_else(|| std::env::var(INSPECT_ADDR_ENV).ok())
+                .unwrap_or_else(|| DEFAULT_INSPECT_ADDR.to_string());
+            let sections = Sections {
+                variables,
+                memory,
+                requests,
+                metrics,
+                cache,
+                tasks,
+            }
+            .or_all();
+            inspect_command(addr, watch, Duration::from_secs(interval.max(1)), sections).await
         }
     }
 }
 
 async fn inspect_command(
-    file: PathBuf,
+    addr: String,
     watch: bool,
-    show_variables: bool,
-    show_memory: bool,
+    interval: Duration,
+    sections: Sections,
 ) -> Result<()> {
-    println!("🔍 Runtime Inspector für: {}\n", file.display());
-    
-    let inspector = RuntimeInspector::new();
-    
+    let inspector = RuntimeInspector::new(addr);
+
     if watch {
-        println!("👀 Watch-Mode aktiviert (CTRL-C zum Beenden)\n");
-        inspector.watch(&file, show_variables, show_memory).await?;
+        inspector.watch(&sections, interval).await
     } else {
-        inspector.inspect(&file, show_variables, show_memory).await?;
+        inspector.inspect(&sections).await
     }
-    
-    Ok(())
 }
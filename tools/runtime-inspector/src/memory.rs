@@ -1,15 +1,8 @@
 // Memory Inspector
-// Analysiert Memory-Usage
+// Stellt Speicherverbrauch und Cache-Inhalte eines Snapshots dar
 
-use anyhow::Result;
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MemoryInfo {
-    pub estimated_allocations: usize,
-    pub variable_count: usize,
-    pub function_count: usize,
-}
+use crate::variables::render_value;
+use velin_compiler::inspector::{CacheEntry, InspectorSnapshot};
 
 pub struct MemoryInspector;
 
@@ -17,21 +10,40 @@ impl MemoryInspector {
     pub fn new() -> Self {
         Self
     }
-    
-    pub fn analyze(&self, content: &str) -> Result<MemoryInfo> {
-        // Einfache Memory-Analyse
-        // In Produktion würde man echte Runtime-Memory-Tracking nutzen
-        
-        let variable_count = content.matches("let ").count();
-        let function_count = content.matches("fn ").count();
-        
-        // Geschätzte Allokationen basierend auf Code-Struktur
-        let estimated_allocations = (variable_count * 64) + (function_count * 128);
-        
-        Ok(MemoryInfo {
-            estimated_allocations,
-            variable_count,
-            function_count,
-        })
+
+    pub fn print_memory(&self, snapshot: &InspectorSnapshot) {
+        match snapshot.memory_rss_bytes {
+            Some(bytes) => println!("  Resident Set Size: {}", format_bytes(bytes)),
+            None => println!("  Resident Set Size: nicht verfügbar (nur Linux)"),
+        }
+        println!("  Cache-Einträge: {}", snapshot.cache.len());
+        println!("  Registrierter State: {}", snapshot.state.len());
+    }
+
+    pub fn print_cache(&self, cache: &[CacheEntry]) {
+        if cache.is_empty() {
+            println!("  (leer)");
+        }
+        for entry in cache {
+            let ttl = match entry.expires_in_ms {
+                Some(ms) => format!("läuft ab in {:.1} s", ms as f64 / 1000.0),
+                None => "ohne TTL".to_string(),
+            };
+            println!("  {} = {}  ({})", entry.key, render_value(&entry.value), ttl);
+        }
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    let value = bytes as f64;
+    if value >= KB * KB * KB {
+        format!("{:.2} GB", value / (KB * KB * KB))
+    } else if value >= KB * KB {
+        format!("{:.2} MB", value / (KB * KB))
+    } else if value >= KB {
+        format!("{:.2} KB", value / KB)
+    } else {
+        format!("{} B", bytes)
     }
 }
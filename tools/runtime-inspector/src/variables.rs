@@ -1,16 +1,8 @@
 // Variable Inspector
-// Extrahiert und inspiziert Variablen
+// Stellt registrierten State und Task-Status eines Snapshots dar
 
-use anyhow::Result;
-use serde::{Serialize, Deserialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Variable {
-    pub name: String,
-    pub value: String,
-    pub type_name: String,
-    pub line: usize,
-}
+use crate::inspector::format_duration;
+use velin_compiler::inspector::{StateEntry, TaskStatus};
 
 pub struct VariableInspector;
 
@@ -18,40 +10,57 @@ impl VariableInspector {
     pub fn new() -> Self {
         Self
     }
-    
-    pub fn extract_variables(&self, content: &str) -> Result<Vec<Variable>> {
-        let mut variables = Vec::new();
-        
-        // Einfache String-basierte Extraktion
-        // In Produktion sollte man den AST nutzen
-        for (line_num, line) in content.lines().enumerate() {
-            if line.trim().starts_with("let ") {
-                if let Some(var) = self.parse_variable(line, line_num + 1) {
-                    variables.push(var);
-                }
-            }
+
+    pub fn print_state(&self, state: &[StateEntry]) {
+        if state.is_empty() {
+            println!("  (kein State registriert – `inspect.state(name, wert)` im Service aufrufen)");
+        }
+        for entry in state {
+            println!(
+                "  {}: {}  (vor {})",
+                entry.name,
+                render_value(&entry.value),
+                format_duration(entry.updated_ms_ago)
+            );
         }
-        
-        Ok(variables)
     }
-    
-    fn parse_variable(&self, line: &str, line_num: usize) -> Option<Variable> {
-        // Vereinfachte Parsing-Logik
-        // Extrahiere "let name = value"
-        if let Some(start) = line.find("let ") {
-            let rest = &line[start + 4..];
-            if let Some(equals) = rest.find('=') {
-                let name = rest[..equals].trim().to_string();
-                let value = rest[equals + 1..].trim().to_string();
-                
-                return Some(Variable {
-                    name,
-                    value,
-                    type_name: "unknown".to_string(),
-                    line: line_num,
-                });
-            }
+
+    pub fn print_tasks(&self, tasks: &[TaskStatus]) {
+        if tasks.is_empty() {
+            println!("  (keine)");
+        }
+        for task in tasks {
+            println!(
+                "  {:<30} {:<12} (vor {})",
+                task.name,
+                task.status,
+                format_duration(task.updated_ms_ago)
+            );
         }
-        None
+    }
+}
+
+/// Kompakte JSON-Darstellung; lange Werte werden gekürzt
+pub fn render_value(value: &serde_json::Value) -> String {
+    const MAX_LEN: usize = 120;
+    let text = value.to_string();
+    if text.chars().count() > MAX_LEN {
+        let short: String = text.chars().take(MAX_LEN).collect();
+        format!("{}…", short)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_value_truncates_long_values() {
+        assert_eq!(render_value(&serde_json::json!({ "a": 1 })), "{\"a\":1}");
+        let long = render_value(&serde_json::json!("x".repeat(200)));
+        assert_eq!(long.chars().count(), 121);
+        assert!(long.ends_with('…'));
     }
 }
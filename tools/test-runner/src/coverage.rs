@@ -1,47 +1,44 @@
 // Coverage Collector
-// Sammelt Coverage-Daten
+// Sammelt Source-Level Coverage über die instrumentierten Builds des Compilers
 
 use crate::runner::CoverageData;
 use anyhow::Result;
-use std::process::Command;
+use std::path::PathBuf;
+use velin_compiler::coverage::{CoverageReport, CoverageRunner};
 
-pub struct CoverageCollector;
+pub struct CoverageCollector {
+    runner: CoverageRunner,
+}
 
 impl CoverageCollector {
     pub fn new() -> Self {
-        Self
+        Self {
+            runner: CoverageRunner::new(PathBuf::from("target").join("velin-coverage")),
+        }
     }
     
-    pub fn generate_report(&self) -> Result<CoverageData> {
-        // Versuche cargo-tarpaulin oder cargo-llvm-cov zu nutzen
-        // Für jetzt geben wir Mock-Daten zurück
-        // In Produktion würde man die tatsächlichen Coverage-Tools aufrufen
+    /// Führt die Test-Dateien instrumentiert aus und aggregiert die Zählerstände
+    /// pro .velin-Datei
+    pub fn generate_report(&self, files: &[PathBuf]) -> Result<(CoverageData, CoverageReport)> {
+        let mut report = CoverageReport::new();
         
-        Ok(CoverageData {
-            line_coverage: 0.0,
-            function_coverage: 0.0,
-            covered_lines: 0,
-            total_lines: 0,
-            covered_functions: 0,
-            total_functions: 0,
-        })
-    }
-    
-    fn try_tarpaulin(&self) -> Result<CoverageData> {
-        // Versuche cargo-tarpaulin auszuführen
-        let output = Command::new("cargo")
-            .args(&["tarpaulin", "--out", "stdout"])
-            .output();
+        for file in files {
+            let run = self.runner.run_file(file)?;
+            for map in &run.maps {
+                report.add(map, &run.counts);
+            }
+        }
+        
+        let data = CoverageData {
+            line_coverage: report.line_coverage(),
+            function_coverage: report.function_coverage(),
+            branch_coverage: report.branch_coverage(),
+            covered_lines: report.files.iter().map(|f| f.lines_hit()).sum(),
+            total_lines: report.files.iter().map(|f| f.lines_found()).sum(),
+            covered_functions: report.files.iter().map(|f| f.functions_hit()).sum(),
+            total_functions: report.files.iter().map(|f| f.functions_found()).sum(),
+        };
         
-        // Parse Output und generiere CoverageData
-        // Für jetzt Mock
-        Ok(CoverageData {
-            line_coverage: 0.0,
-            function_coverage: 0.0,
-            covered_lines: 0,
-            total_lines: 0,
-            covered_functions: 0,
-            total_functions: 0,
-        })
+        Ok((data, report))
     }
 }
@@ -92,6 +92,7 @@ async fn run_tests(
         if let Some(ref coverage_data) = results.coverage {
             println!("  Zeilen-Coverage: {:.2}%", coverage_data.line_coverage);
             println!("  Funktionen-Coverage: {:.2}%", coverage_data.function_coverage);
+            println!("  Branch-Coverage: {:.2}%", coverage_data.branch_coverage);
         }
     }
     
@@ -35,6 +35,7 @@ pub struct TestFailure {
 pub struct CoverageData {
     pub line_coverage: f64,
     pub function_coverage: f64,
+    pub branch_coverage: f64,
     pub covered_lines: usize,
     pub total_lines: usize,
     pub covered_functions: usize,
@@ -131,11 +132,21 @@ impl TestRunner {
             }
         }
         
-        // Coverage-Report
+        // Coverage-Report (LCOV + HTML unter coverage/)
         let coverage = if self.coverage_enabled {
-            self.coverage_collector.as_ref()
-                .map(|c| c.generate_report())
-                .transpose()?
+            match self.coverage_collector.as_ref() {
+                Some(collector) => {
+                    let (data, report) = collector.generate_report(&files)?;
+                    fs::create_dir_all("coverage")?;
+                    fs::write("coverage/lcov.info", report.to_lcov())?;
+                    fs::write(
+                        "coverage/index.html",
+                        report.to_html(|file| fs::read_to_string(file).ok()),
+                    )?;
+                    Some(data)
+                }
+                None => None,
+            }
         } else {
             None
         };